@@ -1,4 +1,6 @@
 import {
+    AddressLookupTableAccount,
+    AddressLookupTableProgram,
     Connection,
     Keypair,
     PublicKey,
@@ -6,15 +8,17 @@ import {
     SYSVAR_RENT_PUBKEY,
     Transaction,
     TransactionInstruction,
+    TransactionMessage,
+    VersionedTransaction,
     sendAndConfirmTransaction,
 } from '@solana/web3.js';
 import {
     TOKEN_PROGRAM_ID,
     ASSOCIATED_TOKEN_PROGRAM_ID,
     getAssociatedTokenAddress,
-    createAssociatedTokenAccountInstruction
 } from '@solana/spl-token';
 import { Buffer } from 'buffer';
+import { createHash } from 'crypto';
 import BN from 'bn.js';
 
 // Types for instructions
@@ -27,6 +31,18 @@ export enum RewardPoolInstruction {
     ResumePool = 5,
 }
 
+const SECONDS_PER_DAY = 86_400;
+
+export const MEMO_PROGRAM_ID = new PublicKey('MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr');
+
+/**
+ * Memo the program tags a reward transfer with: `clones:task:` followed by
+ * the hex SHA-256 hash of the task id
+ */
+export function taskMemo(taskId: string): string {
+    return `clones:task:${createHash('sha256').update(taskId, 'utf8').digest('hex')}`;
+}
+
 // Reward pool structure
 export interface RewardPool {
     platformAuthority: PublicKey;
@@ -37,6 +53,14 @@ export interface RewardPool {
     totalPlatformFeesCollected: BN;
     isPaused: boolean;
     bumpSeed: number;
+    // Seeds of the pool's address: 0 mint, 1 platform namespace, 2 factory
+    addressKind: number;
+    addressCreator: PublicKey;
+    addressSeed: BN;
+    // Tokens of pending rewards the treasury vault holds for farmers
+    outstandingRewards: BN;
+    lastAuthorityActionAt: BN;
+    sequence: BN;
 }
 
 // Structure for pending rewards
@@ -54,6 +78,7 @@ export interface WithdrawalRecord {
     amount: BN;
     nonce: BN;
     withdrawnAt: BN;
+    payer: PublicKey;
 }
 
 // Client errors
@@ -75,29 +100,31 @@ export class RewardPoolClient {
     }
 
     /**
-     * Creates an instruction to initialize a reward pool
+     * Creates an instruction to initialize the reward pool of a mint, at the
+     * address derived from it, and its treasury vault
      */
     createInitializePoolInstruction(
         platformAuthority: PublicKey,
-        poolAccount: PublicKey,
         rewardMint: PublicKey,
-        platformTreasury: PublicKey,
         platformFeePercentage: number,
     ): TransactionInstruction {
         const data = Buffer.alloc(1 + 1); // instruction + platform_fee_percentage
         data.writeUInt8(RewardPoolInstruction.InitializePool, 0);
         data.writeUInt8(platformFeePercentage, 1);
 
+        const poolAccount = this.findPoolAddress(rewardMint);
         return new TransactionInstruction({
             keys: [
-                { pubkey: platformAuthority, isSigner: true, isWritable: false },
+                // The platform authority pays the pool's rent
+                { pubkey: platformAuthority, isSigner: true, isWritable: true },
                 { pubkey: poolAccount, isSigner: false, isWritable: true },
                 { pubkey: rewardMint, isSigner: false, isWritable: false },
-                { pubkey: platformTreasury, isSigner: false, isWritable: true },
+                { pubkey: this.findVaultAddress(poolAccount), isSigner: false, isWritable: true },
                 { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
                 { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
                 { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
                 { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+                { pubkey: this.findInstructionCountersAddress(poolAccount), isSigner: false, isWritable: true },
             ],
             programId: this.programId,
             data,
@@ -105,17 +132,682 @@ export class RewardPoolClient {
     }
 
     /**
-     * Creates an instruction to record a reward
+     * Unix day of the local clock, used to pick the daily stats account
+     */
+    static currentDay(): number {
+        return Math.floor(Date.now() / 1000 / SECONDS_PER_DAY);
+    }
+
+    /**
+     * Derives the stats account of a pool for a unix day
+     */
+    findDailyStatsAddress(poolAccount: PublicKey, day: number = RewardPoolClient.currentDay()): PublicKey {
+        const dayBuffer = Buffer.alloc(8);
+        dayBuffer.writeBigInt64LE(BigInt(day));
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('daily_stats'), poolAccount.toBuffer(), dayBuffer],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the pool of a reward mint
+     */
+    findPoolAddress(rewardMint: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('reward_pool'), rewardMint.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the instruction counters account of a pool
+     */
+    findInstructionCountersAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('instruction_counters'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the withdrawal destination whitelist of a farmer
+     */
+    findWithdrawalWhitelistAddress(poolAccount: PublicKey, farmer: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('withdrawal_whitelist'), poolAccount.toBuffer(), farmer.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the payout address registration of a farmer
+     */
+    findPayoutAddressAddress(poolAccount: PublicKey, farmer: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('payout_address'), poolAccount.toBuffer(), farmer.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the state account of a farmer within a pool
+     */
+    findFarmerStateAddress(poolAccount: PublicKey, farmer: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('farmer_state'), poolAccount.toBuffer(), farmer.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the recent withdrawals account of a farmer within a pool
+     */
+    findWithdrawalHistoryAddress(poolAccount: PublicKey, farmer: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('withdrawal_history'), poolAccount.toBuffer(), farmer.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the reward verifier configuration account of a pool
+     */
+    findPoolVerifierAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('pool_verifier'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the reward attestor configuration account of a pool
+     */
+    findPoolAttestorAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('pool_attestor'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the budget account locked for a task of a pool
+     */
+    findTaskBudgetAddress(poolAccount: PublicKey, taskId: string): PublicKey {
+        const taskHash = createHash('sha256').update(taskId, 'utf8').digest();
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('task_budget'), poolAccount.toBuffer(), taskHash],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the token account holding the locked task budgets of a pool
+     */
+    findBudgetEscrowAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('budget_escrow'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the maker-checker configuration account of a pool
+     */
+    findRewardApprovalAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('reward_approval'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the platform fees per task type of a pool
+     */
+    findFeeOverridesAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('fee_overrides'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the staking-tier fee discounts of a pool
+     */
+    findFeeDiscountsAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('fee_discounts'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the share of a pool's withdrawals burned
+     */
+    findWithdrawBurnAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('withdraw_burn'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the reward governor of a pool
+     */
+    findRewardGovernorAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('reward_governor'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the secondary mint configuration of a pool
+     */
+    findSecondaryRewardAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('secondary_reward'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the token account backing a pool's secondary rewards
+     */
+    findSecondaryVaultAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('secondary_vault'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the secondary rewards of a farmer in a pool
+     */
+    findSecondaryBalanceAddress(poolAccount: PublicKey, farmer: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('secondary_balance'), poolAccount.toBuffer(), farmer.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the milestone bonuses of a pool
+     */
+    findMilestoneBonusesAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('milestone_bonuses'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the lifetime earnings and milestones of a farmer in a pool
+     */
+    findFarmerStatsAddress(poolAccount: PublicKey, farmer: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('farmer_stats'), poolAccount.toBuffer(), farmer.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the fee rebate configuration of a pool
+     */
+    findFeeRebateAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('fee_rebate'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the token account funding a pool's fee rebates
+     */
+    findRebateVaultAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('rebate_vault'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the namespace account of a platform
+     */
+    findPlatformAddress(platformId: BN): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('platform'), platformId.toArrayLike(Buffer, 'le', 8)],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the address of a platform's pool for a reward mint
+     */
+    findPlatformPoolAddress(platformId: BN, rewardMint: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('reward_pool'), platformId.toArrayLike(Buffer, 'le', 8), rewardMint.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the protocol-wide configuration of the pool factory
+     */
+    findProtocolConfigAddress(): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('protocol_config')],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the address of a pool a creator made through the factory
+     */
+    findFactoryPoolAddress(creator: PublicKey, rewardMint: PublicKey, seed: BN): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [
+                Buffer.from('factory_pool'),
+                creator.toBuffer(),
+                rewardMint.toBuffer(),
+                seed.toArrayLike(Buffer, 'le', 8),
+            ],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the factory record of a pool
+     */
+    findFactoryRecordAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('factory_record'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives a reward voucher of a pool
+     */
+    findVoucherAddress(poolAccount: PublicKey, voucherId: BN): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('voucher'), poolAccount.toBuffer(), voucherId.toArrayLike(Buffer, 'le', 8)],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the NFT mint of a reward voucher
+     */
+    findVoucherMintAddress(voucher: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('voucher_mint'), voucher.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the payout queue of a pool
+     */
+    findPayoutQueueAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('payout_queue'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives a farmer's payout queue ticket
+     */
+    findPayoutTicketAddress(poolAccount: PublicKey, farmer: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('payout_ticket'), poolAccount.toBuffer(), farmer.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the per-epoch claim cap of a pool resumed with a ramp
+     */
+    findWithdrawalRampAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('withdrawal_ramp'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the dead-man switch of a pool's platform authority
+     */
+    findAuthorityRecoveryAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('authority_recovery'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the history of a pool's platform authority keys
+     */
+    findKeyHistoryAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('key_history'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the recovery guardians account of a farmer within a pool
+     */
+    findFarmerRecoveryAddress(poolAccount: PublicKey, farmer: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('farmer_recovery'), poolAccount.toBuffer(), farmer.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the lamport withdrawal fee configuration of a pool
+     */
+    findWithdrawalFeeAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('withdrawal_fee'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the account collecting a pool's lamport withdrawal fees
+     */
+    findFeeVaultAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('fee_vault'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the guild of a pool led by an authority
+     */
+    findGuildAddress(poolAccount: PublicKey, authority: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('guild'), poolAccount.toBuffer(), authority.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the token account holding a guild's rewards
+     */
+    findGuildVaultAddress(guild: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('guild_vault'), guild.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the fee ledger of a pool
+     */
+    findFeeLedgerAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('fee_ledger'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives a fee invoice of a pool
+     */
+    findFeeInvoiceAddress(poolAccount: PublicKey, invoiceNumber: BN): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('fee_invoice'), poolAccount.toBuffer(), invoiceNumber.toArrayLike(Buffer, 'le', 8)],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the treasury governance of a pool
+     */
+    findTreasuryGovernanceAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('treasury_governance'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the reward rate accumulators of a pool
+     */
+    findRewardRateAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('reward_rate'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the claim shard counting a farmer's claims, picked by the first
+     * byte of the hash of their key among the pool's 16 shards
+     */
+    findClaimShardAddress(poolAccount: PublicKey, farmer: PublicKey): PublicKey {
+        const shard = createHash('sha256').update(farmer.toBuffer()).digest()[0] % 16;
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('claim_shard'), poolAccount.toBuffer(), Buffer.from([shard])],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the consolidated claim totals of a pool
+     */
+    findClaimLedgerAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('claim_ledger'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the receipt of a reward recorded under an idempotency key
+     */
+    findRewardReceiptAddress(poolAccount: PublicKey, idempotencyKey: Buffer): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('reward_receipt'), poolAccount.toBuffer(), idempotencyKey],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the record of a farmer's withdrawal under a nonce
+     */
+    findWithdrawalRecordAddress(poolAccount: PublicKey, farmer: PublicKey, nonce: BN): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [
+                Buffer.from('withdrawal_record'),
+                poolAccount.toBuffer(),
+                farmer.toBuffer(),
+                nonce.toArrayLike(Buffer, 'le', 8),
+            ],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the token account collecting a pool's platform fees
+     */
+    findPlatformFeeVaultAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('platform_fee_vault'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the payout queue account of a pool
+     */
+    findPayoutQueueAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('payout_queue'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives a farmer's payout ticket for a pool's recovery rounds
+     */
+    findPayoutTicketAddress(poolAccount: PublicKey, farmer: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('payout_ticket'), poolAccount.toBuffer(), farmer.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the withdrawal ramp account of a pool
+     */
+    findWithdrawalRampAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('withdrawal_ramp'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the interest policy account of a pool
+     */
+    findInterestPolicyAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('interest_policy'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the bonus vault paying a pool's interest
+     */
+    findBonusVaultAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('bonus_vault'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the pending reward account of a farmer for a task
+     */
+    findPendingRewardAddress(poolAccount: PublicKey, farmer: PublicKey, taskId: string): PublicKey {
+        const taskHash = createHash('sha256').update(taskId, 'utf8').digest();
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('pending_reward'), poolAccount.toBuffer(), farmer.toBuffer(), taskHash],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the treasury vault token account of a pool, its platform
+     * treasury
+     */
+    findVaultAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('vault'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Derives the tunable limits account of a pool
+     */
+    findParametersAddress(poolAccount: PublicKey): PublicKey {
+        const [address] = PublicKey.findProgramAddressSync(
+            [Buffer.from('parameters'), poolAccount.toBuffer()],
+            this.programId,
+        );
+        return address;
+    }
+
+    /**
+     * Creates an instruction to record a reward. Pools with an attestor
+     * need the task's attestation account, pools with a verifier the
+     * verifier's signature, and pools of a platform namespace the
+     * platform's account. Farmers past milestones of the pool need the
+     * number of milestones they reached so far. Pools created through the
+     * factory need the protocol treasury's token account of the reward
+     * mint. With `taskMemo`, the instruction is tagged with the task's memo.
      */
     createRecordRewardInstruction(
         platformAuthority: PublicKey,
         poolAccount: PublicKey,
-        platformTreasury: PublicKey,
         farmerRewardAccount: PublicKey,
         rewardMint: PublicKey,
         amount: BN,
         farmerPubkey: PublicKey,
         taskId: string,
+        dailyStatsAccount: PublicKey = this.findDailyStatsAddress(poolAccount),
+        verifier?: PublicKey,
+        attestation?: PublicKey,
+        platform?: PublicKey,
+        stakeAccount?: PublicKey,
+        milestonesReached = 0,
+        protocolFeeAccount?: PublicKey,
+        taskMemo = false,
+        approvers: PublicKey[] = [],
     ): TransactionInstruction {
         const taskIdBuffer = Buffer.from(taskId, 'utf8');
         const data = Buffer.alloc(1 + 8 + 32 + 4 + taskIdBuffer.length);
@@ -135,32 +827,115 @@ export class RewardPoolClient {
 
         taskIdBuffer.copy(data, offset);
 
+        const keys = [
+            { pubkey: platformAuthority, isSigner: true, isWritable: true },
+            { pubkey: poolAccount, isSigner: false, isWritable: true },
+            { pubkey: this.findVaultAddress(poolAccount), isSigner: false, isWritable: true },
+            // The reward is booked as a pending reward, the farmer's reward account is unused
+            { pubkey: farmerRewardAccount, isSigner: false, isWritable: false },
+            { pubkey: rewardMint, isSigner: false, isWritable: false },
+            { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+            { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+            { pubkey: dailyStatsAccount, isSigner: false, isWritable: true },
+            { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+            { pubkey: this.findInstructionCountersAddress(poolAccount), isSigner: false, isWritable: true },
+            { pubkey: this.findFarmerStateAddress(poolAccount, farmerPubkey), isSigner: false, isWritable: true },
+            { pubkey: this.findPoolVerifierAddress(poolAccount), isSigner: false, isWritable: false },
+            { pubkey: this.findPoolAttestorAddress(poolAccount), isSigner: false, isWritable: false },
+            // The attestation slot is ignored unless the pool has an attestor
+            {
+                pubkey: attestation ?? this.findPoolAttestorAddress(poolAccount),
+                isSigner: false,
+                isWritable: false,
+            },
+            { pubkey: this.findTaskBudgetAddress(poolAccount, taskId), isSigner: false, isWritable: true },
+            { pubkey: this.findBudgetEscrowAddress(poolAccount), isSigner: false, isWritable: true },
+            { pubkey: this.findRewardApprovalAddress(poolAccount), isSigner: false, isWritable: false },
+            {
+                pubkey: this.findPendingRewardAddress(poolAccount, farmerPubkey, taskId),
+                isSigner: false,
+                isWritable: true,
+            },
+            // Platform fees move to the fee vault, which funds buybacks and other fee spending
+            { pubkey: this.findPlatformFeeVaultAddress(poolAccount), isSigner: false, isWritable: true },
+            { pubkey: this.findFeeOverridesAddress(poolAccount), isSigner: false, isWritable: false },
+            // The platform slot is ignored outside a platform namespace
+            { pubkey: platform ?? poolAccount, isSigner: false, isWritable: true },
+            // V1 rewards carry no campaign id, so the campaign slot is ignored
+            { pubkey: poolAccount, isSigner: false, isWritable: true },
+            { pubkey: this.findFeeDiscountsAddress(poolAccount), isSigner: false, isWritable: false },
+            // The stake slot is ignored unless the pool has fee discounts
+            { pubkey: stakeAccount ?? poolAccount, isSigner: false, isWritable: false },
+            { pubkey: this.findRewardGovernorAddress(poolAccount), isSigner: false, isWritable: true },
+            { pubkey: this.findMilestoneBonusesAddress(poolAccount), isSigner: false, isWritable: true },
+            { pubkey: this.findFeeRebateAddress(poolAccount), isSigner: false, isWritable: true },
+            { pubkey: this.findRewardRateAddress(poolAccount), isSigner: false, isWritable: true },
+            { pubkey: this.findTreasuryGovernanceAddress(poolAccount), isSigner: false, isWritable: false },
+            // Every feature account follows, so the reward suits any pool.
+            // V1 rewards carry no idempotency key, so the receipt slot is ignored
+            { pubkey: poolAccount, isSigner: false, isWritable: true },
+            // The memo slot tags the instruction with the task's memo when it holds the memo program
+            { pubkey: taskMemo ? MEMO_PROGRAM_ID : poolAccount, isSigner: false, isWritable: false },
+            {
+                pubkey: this.findFarmerStatsAddress(poolAccount, farmerPubkey),
+                isSigner: false,
+                isWritable: true,
+            },
+            // Booked with the bonus when the reward reaches the farmer's next milestone
+            {
+                pubkey: this.findPendingRewardAddress(
+                    poolAccount,
+                    farmerPubkey,
+                    `milestone:${milestonesReached}`,
+                ),
+                isSigner: false,
+                isWritable: true,
+            },
+            { pubkey: this.findSecondaryRewardAddress(poolAccount), isSigner: false, isWritable: true },
+            // Funded with the farmer's fee rebate, if the pool has one
+            { pubkey: this.findSecondaryVaultAddress(poolAccount), isSigner: false, isWritable: true },
+            {
+                pubkey: this.findSecondaryBalanceAddress(poolAccount, farmerPubkey),
+                isSigner: false,
+                isWritable: true,
+            },
+            { pubkey: this.findRebateVaultAddress(poolAccount), isSigner: false, isWritable: true },
+            { pubkey: this.findFactoryRecordAddress(poolAccount), isSigner: false, isWritable: true },
+            // Both ignored unless the pool was created through the factory
+            { pubkey: this.findProtocolConfigAddress(), isSigner: false, isWritable: false },
+            { pubkey: protocolFeeAccount ?? poolAccount, isSigner: false, isWritable: true },
+        ];
+        // Under treasury governance, rewards above the approval threshold
+        // need the approvers' signatures next to the verifier's
+        if (verifier) {
+            keys.push({ pubkey: verifier, isSigner: true, isWritable: false });
+        }
+        for (const approver of approvers) {
+            keys.push({ pubkey: approver, isSigner: true, isWritable: false });
+        }
+
         return new TransactionInstruction({
-            keys: [
-                { pubkey: platformAuthority, isSigner: true, isWritable: false },
-                { pubkey: poolAccount, isSigner: false, isWritable: true },
-                { pubkey: platformTreasury, isSigner: false, isWritable: true },
-                { pubkey: farmerRewardAccount, isSigner: false, isWritable: true },
-                { pubkey: rewardMint, isSigner: false, isWritable: false },
-                { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
-                { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
-            ],
+            keys,
             programId: this.programId,
             data,
         });
     }
 
     /**
-     * Creates an instruction to withdraw rewards
+     * Creates an instruction to withdraw the farmer's pending reward for
+     * `taskId` from the vault; `amount` must be the whole pending reward
      */
     createWithdrawRewardInstruction(
         farmer: PublicKey,
         poolAccount: PublicKey,
-        farmerRewardAccount: PublicKey,
+        taskId: string,
         farmerDestinationAccount: PublicKey,
         rewardMint: PublicKey,
         amount: BN,
         nonce: BN,
+        dailyStatsAccount: PublicKey = this.findDailyStatsAddress(poolAccount),
+        sponsor?: PublicKey,
+        cosigner?: PublicKey,
     ): TransactionInstruction {
         const data = Buffer.alloc(1 + 8 + 8); // instruction + amount + nonce
         let offset = 0;
@@ -173,16 +948,63 @@ export class RewardPoolClient {
 
         nonce.toArrayLike(Buffer, 'le', 8).copy(data, offset);
 
+        // A sponsor pays rent and any withdrawal fee (and usually transaction
+        // fees) so the farmer needs no SOL
+        const keys = [
+            { pubkey: farmer, isSigner: true, isWritable: !sponsor },
+            { pubkey: poolAccount, isSigner: false, isWritable: true },
+            { pubkey: this.findVaultAddress(poolAccount), isSigner: false, isWritable: true },
+            { pubkey: farmerDestinationAccount, isSigner: false, isWritable: true },
+            // Writable so pools burning a share of withdrawals can burn it
+            { pubkey: rewardMint, isSigner: false, isWritable: true },
+            { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+            { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+            { pubkey: dailyStatsAccount, isSigner: false, isWritable: true },
+            { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+            { pubkey: this.findInstructionCountersAddress(poolAccount), isSigner: false, isWritable: true },
+            { pubkey: this.findWithdrawalWhitelistAddress(poolAccount, farmer), isSigner: false, isWritable: false },
+            { pubkey: this.findParametersAddress(poolAccount), isSigner: false, isWritable: false },
+            { pubkey: this.findPayoutAddressAddress(poolAccount, farmer), isSigner: false, isWritable: false },
+            { pubkey: this.findFarmerStateAddress(poolAccount, farmer), isSigner: false, isWritable: true },
+            { pubkey: this.findWithdrawalHistoryAddress(poolAccount, farmer), isSigner: false, isWritable: true },
+            { pubkey: this.findWithdrawBurnAddress(poolAccount), isSigner: false, isWritable: true },
+            { pubkey: this.findWithdrawalFeeAddress(poolAccount), isSigner: false, isWritable: true },
+            { pubkey: this.findFeeVaultAddress(poolAccount), isSigner: false, isWritable: true },
+            // Farmers with a withdrawal cosigner need its signature here
+            cosigner
+                ? { pubkey: cosigner, isSigner: true, isWritable: false }
+                : { pubkey: poolAccount, isSigner: false, isWritable: false },
+        ];
+        if (sponsor) {
+            keys.push({ pubkey: sponsor, isSigner: true, isWritable: true });
+        }
+        // The payout queue accounts, the withdrawal ramp, the interest
+        // accounts, the pending reward withdrawn and the withdrawal's record,
+        // which makes its nonce unusable, come last
+        keys.push(
+            { pubkey: this.findPayoutQueueAddress(poolAccount), isSigner: false, isWritable: false },
+            {
+                pubkey: this.findPayoutTicketAddress(poolAccount, farmer),
+                isSigner: false,
+                isWritable: false,
+            },
+            { pubkey: this.findWithdrawalRampAddress(poolAccount), isSigner: false, isWritable: true },
+            { pubkey: this.findInterestPolicyAddress(poolAccount), isSigner: false, isWritable: true },
+            { pubkey: this.findBonusVaultAddress(poolAccount), isSigner: false, isWritable: true },
+            {
+                pubkey: this.findPendingRewardAddress(poolAccount, farmer, taskId),
+                isSigner: false,
+                isWritable: true,
+            },
+            {
+                pubkey: this.findWithdrawalRecordAddress(poolAccount, farmer, nonce),
+                isSigner: false,
+                isWritable: true,
+            },
+        );
+
         return new TransactionInstruction({
-            keys: [
-                { pubkey: farmer, isSigner: true, isWritable: false },
-                { pubkey: poolAccount, isSigner: false, isWritable: true },
-                { pubkey: farmerRewardAccount, isSigner: false, isWritable: true },
-                { pubkey: farmerDestinationAccount, isSigner: false, isWritable: true },
-                { pubkey: rewardMint, isSigner: false, isWritable: false },
-                { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
-                { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
-            ],
+            keys,
             programId: this.programId,
             data,
         });
@@ -202,8 +1024,11 @@ export class RewardPoolClient {
 
         return new TransactionInstruction({
             keys: [
-                { pubkey: platformAuthority, isSigner: true, isWritable: false },
+                { pubkey: platformAuthority, isSigner: true, isWritable: true },
                 { pubkey: poolAccount, isSigner: false, isWritable: true },
+                { pubkey: this.findInstructionCountersAddress(poolAccount), isSigner: false, isWritable: true },
+                { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+                { pubkey: this.findParametersAddress(poolAccount), isSigner: false, isWritable: false },
             ],
             programId: this.programId,
             data,
@@ -222,8 +1047,10 @@ export class RewardPoolClient {
 
         return new TransactionInstruction({
             keys: [
-                { pubkey: platformAuthority, isSigner: true, isWritable: false },
+                { pubkey: platformAuthority, isSigner: true, isWritable: true },
                 { pubkey: poolAccount, isSigner: false, isWritable: true },
+                { pubkey: this.findInstructionCountersAddress(poolAccount), isSigner: false, isWritable: true },
+                { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
             ],
             programId: this.programId,
             data,
@@ -242,8 +1069,10 @@ export class RewardPoolClient {
 
         return new TransactionInstruction({
             keys: [
-                { pubkey: platformAuthority, isSigner: true, isWritable: false },
+                { pubkey: platformAuthority, isSigner: true, isWritable: true },
                 { pubkey: poolAccount, isSigner: false, isWritable: true },
+                { pubkey: this.findInstructionCountersAddress(poolAccount), isSigner: false, isWritable: true },
+                { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
             ],
             programId: this.programId,
             data,
@@ -251,14 +1080,13 @@ export class RewardPoolClient {
     }
 
     /**
-     * Initializes a reward pool
+     * Initializes the reward pool of a mint, found afterwards with
+     * `findPoolAddress`
      */
     async initializePool(
         payer: Keypair,
         platformAuthority: Keypair,
-        poolAccount: Keypair,
         rewardMint: PublicKey,
-        platformTreasury: PublicKey,
         platformFeePercentage: number,
     ): Promise<string> {
         const transaction = new Transaction();
@@ -266,9 +1094,7 @@ export class RewardPoolClient {
         // Pool initialization instruction
         const initInstruction = this.createInitializePoolInstruction(
             platformAuthority.publicKey,
-            poolAccount.publicKey,
             rewardMint,
-            platformTreasury,
             platformFeePercentage,
         );
 
@@ -277,48 +1103,126 @@ export class RewardPoolClient {
         return await sendAndConfirmTransaction(
             this.connection,
             transaction,
-            [payer, platformAuthority, poolAccount],
+            [payer, platformAuthority],
         );
     }
 
     /**
-     * Records a reward for a farmer
+     * Accounts of `createRecordRewardInstruction` shared by every reward of
+     * the pool, which `createRecordRewardLookupTable` moves into a lookup
+     * table. Farmer, task and day accounts stay inline.
+     */
+    recordRewardLookupAddresses(poolAccount: PublicKey, rewardMint: PublicKey): PublicKey[] {
+        return [
+            poolAccount,
+            this.findVaultAddress(poolAccount),
+            rewardMint,
+            TOKEN_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            SystemProgram.programId,
+            this.findInstructionCountersAddress(poolAccount),
+            this.findPoolVerifierAddress(poolAccount),
+            this.findPoolAttestorAddress(poolAccount),
+            this.findBudgetEscrowAddress(poolAccount),
+            this.findRewardApprovalAddress(poolAccount),
+            this.findPlatformFeeVaultAddress(poolAccount),
+            this.findFeeOverridesAddress(poolAccount),
+            this.findFeeDiscountsAddress(poolAccount),
+            this.findRewardGovernorAddress(poolAccount),
+            this.findSecondaryRewardAddress(poolAccount),
+            this.findSecondaryVaultAddress(poolAccount),
+            this.findMilestoneBonusesAddress(poolAccount),
+            this.findFeeRebateAddress(poolAccount),
+            this.findRebateVaultAddress(poolAccount),
+            this.findFactoryRecordAddress(poolAccount),
+            this.findProtocolConfigAddress(),
+            this.findRewardRateAddress(poolAccount),
+            this.findTreasuryGovernanceAddress(poolAccount),
+        ];
+    }
+
+    /**
+     * Creates a lookup table owned by the platform authority holding
+     * `recordRewardLookupAddresses`, for `recordReward`. A `RecordReward`
+     * with every account inline is larger than a transaction may be.
+     * Returns once the table can be used, the slot after it is extended.
+     */
+    async createRecordRewardLookupTable(
+        platformAuthority: Keypair,
+        poolAccount: PublicKey,
+        rewardMint: PublicKey,
+    ): Promise<PublicKey> {
+        const recentSlot = await this.connection.getSlot('finalized');
+        const [createInstruction, lookupTable] = AddressLookupTableProgram.createLookupTable({
+            authority: platformAuthority.publicKey,
+            payer: platformAuthority.publicKey,
+            recentSlot,
+        });
+        const extendInstruction = AddressLookupTableProgram.extendLookupTable({
+            lookupTable,
+            authority: platformAuthority.publicKey,
+            payer: platformAuthority.publicKey,
+            addresses: this.recordRewardLookupAddresses(poolAccount, rewardMint),
+        });
+
+        const transaction = new Transaction().add(createInstruction, extendInstruction);
+        await sendAndConfirmTransaction(this.connection, transaction, [platformAuthority]);
+
+        // Addresses added in a slot become usable in the next one
+        const extendedAt = await this.connection.getSlot();
+        while ((await this.connection.getSlot()) <= extendedAt) {
+            await new Promise((resolve) => setTimeout(resolve, 400));
+        }
+        return lookupTable;
+    }
+
+    /**
+     * Compiles `recordInstruction` into a v0 transaction paid by
+     * `platformAuthority`, looking its pool accounts up in `lookupTable`
+     */
+    createRecordRewardTransaction(
+        platformAuthority: PublicKey,
+        recordInstruction: TransactionInstruction,
+        lookupTable: AddressLookupTableAccount,
+        recentBlockhash: string,
+    ): VersionedTransaction {
+        const message = new TransactionMessage({
+            payerKey: platformAuthority,
+            recentBlockhash,
+            instructions: [recordInstruction],
+        }).compileToV0Message([lookupTable]);
+        return new VersionedTransaction(message);
+    }
+
+    /**
+     * Records a reward for a farmer, in a v0 transaction against
+     * `lookupTable`, created with `createRecordRewardLookupTable`
      */
     async recordReward(
         platformAuthority: Keypair,
         poolAccount: PublicKey,
-        platformTreasury: PublicKey,
         rewardMint: PublicKey,
         amount: BN,
         farmerPubkey: PublicKey,
         taskId: string,
+        lookupTable: PublicKey,
     ): Promise<string> {
-        const transaction = new Transaction();
+        const table = (await this.connection.getAddressLookupTable(lookupTable)).value;
+        if (!table) {
+            throw new RewardPoolClientError(`Lookup table ${lookupTable.toBase58()} not found`);
+        }
 
-        // Get farmer's reward account address
+        // Get farmer's reward account address, the farmer may be a PDA
         const farmerRewardAccount = await getAssociatedTokenAddress(
             rewardMint,
             farmerPubkey,
+            true,
         );
 
-        // Check if reward account exists
-        const accountInfo = await this.connection.getAccountInfo(farmerRewardAccount);
-        if (!accountInfo) {
-            // Create ATA account if it doesn't exist
-            const createAtaInstruction = createAssociatedTokenAccountInstruction(
-                platformAuthority.publicKey,
-                farmerRewardAccount,
-                farmerPubkey,
-                rewardMint,
-            );
-            transaction.add(createAtaInstruction);
-        }
-
         // Reward recording instruction
         const recordInstruction = this.createRecordRewardInstruction(
             platformAuthority.publicKey,
             poolAccount,
-            platformTreasury,
             farmerRewardAccount,
             rewardMint,
             amount,
@@ -326,33 +1230,33 @@ export class RewardPoolClient {
             taskId,
         );
 
-        transaction.add(recordInstruction);
-
-        return await sendAndConfirmTransaction(
-            this.connection,
-            transaction,
-            [platformAuthority],
+        const { blockhash, lastValidBlockHeight } = await this.connection.getLatestBlockhash();
+        const transaction = this.createRecordRewardTransaction(
+            platformAuthority.publicKey,
+            recordInstruction,
+            table,
+            blockhash,
         );
+        transaction.sign([platformAuthority]);
+
+        const signature = await this.connection.sendTransaction(transaction);
+        await this.connection.confirmTransaction({ signature, blockhash, lastValidBlockHeight });
+        return signature;
     }
 
     /**
-     * Allows a farmer to withdraw their rewards
+     * Allows a farmer to withdraw their pending reward for a task
      */
     async withdrawReward(
         farmer: Keypair,
         poolAccount: PublicKey,
         rewardMint: PublicKey,
+        taskId: string,
         amount: BN,
         nonce: BN,
     ): Promise<string> {
         const transaction = new Transaction();
 
-        // Get farmer's reward account address
-        const farmerRewardAccount = await getAssociatedTokenAddress(
-            rewardMint,
-            farmer.publicKey,
-        );
-
         // Get farmer's destination account address
         const farmerDestinationAccount = await getAssociatedTokenAddress(
             rewardMint,
@@ -363,7 +1267,7 @@ export class RewardPoolClient {
         const withdrawInstruction = this.createWithdrawRewardInstruction(
             farmer.publicKey,
             poolAccount,
-            farmerRewardAccount,
+            taskId,
             farmerDestinationAccount,
             rewardMint,
             amount,
@@ -475,6 +1379,12 @@ export class RewardPoolClient {
                 totalPlatformFeesCollected: new BN(data.slice(105, 113), 'le'),
                 isPaused: data[113] === 1,
                 bumpSeed: data[114] ?? 0,
+                addressKind: data[115] ?? 0,
+                addressCreator: new PublicKey(data.slice(116, 148)),
+                addressSeed: new BN(data.slice(148, 156), 'le'),
+                outstandingRewards: new BN(data.slice(156, 164), 'le'),
+                lastAuthorityActionAt: new BN(data.slice(164, 172), 'le'),
+                sequence: new BN(data.slice(172, 180), 'le'),
             };
 
             return pool;
@@ -494,6 +1404,7 @@ export class RewardPoolClient {
             const farmerRewardAccount = await getAssociatedTokenAddress(
                 rewardMint,
                 farmerPubkey,
+                true,
             );
 
             const accountInfo = await this.connection.getAccountInfo(farmerRewardAccount);
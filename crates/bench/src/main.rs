@@ -0,0 +1,462 @@
+//! Compute unit regression benchmark for the reward pool program.
+//!
+//! ```text
+//! cu-bench [--budgets <FILE>] [--threshold <PERCENT>] [--record]
+//! cu-bench --native
+//! ```
+//!
+//! Runs a fixed set of scenarios against the SBF build of the program under
+//! `solana-program-test` and compares the compute units every instruction
+//! consumed with the budgets recorded in `cu-budgets.json`. An instruction
+//! more than `--threshold` percent (default 5) over its budget, or without a
+//! budget, fails the run; `--record` writes the measured units as the new
+//! budgets instead.
+//!
+//! The program is loaded from `reward_pool.so` in `SBF_OUT_DIR`, e.g. after
+//! `cargo build-sbf --manifest-path programs/reward-pool/Cargo.toml`.
+//! `--native` runs the scenarios against the natively built program to check
+//! they still succeed. Native runs do not meter the program's own compute,
+//! so their units are printed but never compared or recorded.
+
+use std::{collections::BTreeMap, env, error::Error, fs, path::PathBuf, process};
+
+use reward_pool::{DailyStats, MAX_CLAIM_BATCH, PLATFORM_FEE_PERCENTAGE};
+use reward_pool_client::{instructions, RewardAttribution};
+use reward_pool_test_utils::{
+    airdrop, fixture_keypair, program_test, token, PoolFixture, ProgramTestContext, TestPool,
+};
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_keypair::Keypair;
+use solana_program::{
+    clock::Clock, hash::Hash, instruction::Instruction, native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+};
+use solana_program_test::{find_file, ProgramTest};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+const USAGE: &str =
+    "usage: cu-bench [--budgets <FILE>] [--threshold <PERCENT>] [--record]\n       \
+                     cu-bench --native";
+
+const DEFAULT_BUDGETS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/cu-budgets.json");
+const DEFAULT_THRESHOLD_PERCENT: f64 = 5.0;
+
+/// Limit every measured transaction requests, so that no scenario fails on
+/// the default 200k per instruction before its regression can be reported.
+/// The compute budget instruction's own units are the same in every
+/// measurement.
+const COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Largest serialized transaction the network accepts
+const MAX_TRANSACTION_SIZE: usize = 1232;
+
+const TREASURY_FUNDS: u64 = 1_000_000_000;
+const VAULT_FUNDS: u64 = 1_000_000;
+const REWARD_AMOUNT: u64 = 1_000_000;
+
+// Keypair roles of the accounts the benchmark adds to the fixture's
+const APPROVER: u8 = 100;
+const DESTINATION: u8 = 101;
+
+struct Options {
+    budgets: PathBuf,
+    threshold_percent: f64,
+    record: bool,
+    native: bool,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        budgets: PathBuf::from(DEFAULT_BUDGETS),
+        threshold_percent: DEFAULT_THRESHOLD_PERCENT,
+        record: false,
+        native: false,
+    };
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {flag}"));
+        match flag.as_str() {
+            "--budgets" => options.budgets = PathBuf::from(value()?),
+            "--threshold" => {
+                let input = value()?;
+                options.threshold_percent = input
+                    .parse()
+                    .ok()
+                    .filter(|percent: &f64| *percent >= 0.0)
+                    .ok_or(format!("invalid threshold {input}"))?
+            }
+            "--record" => options.record = true,
+            "--native" => options.native = true,
+            other => return Err(format!("unknown argument {other}")),
+        }
+    }
+
+    if options.native && options.record {
+        return Err("native runs cannot record budgets".to_string());
+    }
+    Ok(options)
+}
+
+/// A bootstrapped pool and the compute units measured against it so far
+struct Bench {
+    context: ProgramTestContext,
+    pool: TestPool,
+    measurements: Vec<(String, u64)>,
+    tasks: u64,
+    nonce: u64,
+}
+
+impl Bench {
+    async fn start(native: bool) -> Result<Self, Box<dyn Error>> {
+        let program_test = if native {
+            program_test()
+        } else {
+            if find_file("reward_pool.so").is_none() {
+                return Err("reward_pool.so not found; build the program with \
+                            `cargo build-sbf` and point SBF_OUT_DIR at its output"
+                    .into());
+            }
+            let mut program_test = ProgramTest::new("reward_pool", reward_pool::id(), None);
+            program_test.prefer_bpf(true);
+            program_test
+        };
+        let mut context = program_test.start_with_context().await;
+        let pool = PoolFixture::new()
+            .with_funded_treasury(TREASURY_FUNDS)
+            .with_funded_vault(VAULT_FUNDS)
+            .bootstrap(&mut context)
+            .await?;
+
+        Ok(Self {
+            context,
+            pool,
+            measurements: Vec::new(),
+            tasks: 0,
+            nonce: 0,
+        })
+    }
+
+    /// The transaction every measurement sends: the compute unit limit
+    /// followed by `instruction`, paid by the context's payer
+    fn transaction(
+        &self,
+        instruction: Instruction,
+        signer: &Keypair,
+        blockhash: Hash,
+    ) -> Transaction {
+        Transaction::new_signed_with_payer(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+                instruction,
+            ],
+            Some(&self.context.payer.pubkey()),
+            &[&self.context.payer, signer],
+            blockhash,
+        )
+    }
+
+    fn transaction_size(transaction: &Transaction) -> usize {
+        1 + transaction.signatures.len() * 64 + transaction.message.serialize().len()
+    }
+
+    /// Processes `instruction` signed by `signer`, recording its units
+    /// under `name` unless it is `None`
+    async fn process(
+        &mut self,
+        name: Option<&str>,
+        instruction: Instruction,
+        signer: &Keypair,
+    ) -> Result<(), Box<dyn Error>> {
+        let label = name.unwrap_or("setup");
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let transaction = self.transaction(instruction, signer, blockhash);
+        let size = Self::transaction_size(&transaction);
+        if size > MAX_TRANSACTION_SIZE {
+            return Err(format!(
+                "{label}: transaction of {size} bytes exceeds {MAX_TRANSACTION_SIZE}"
+            )
+            .into());
+        }
+        let outcome = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await?;
+        let metadata = outcome
+            .metadata
+            .ok_or(format!("{label}: no transaction metadata"))?;
+        if let Err(error) = outcome.result {
+            return Err(format!(
+                "{label} failed: {error}\n{}",
+                metadata.log_messages.join("\n")
+            )
+            .into());
+        }
+        if let Some(name) = name {
+            self.measurements
+                .push((name.to_string(), metadata.compute_units_consumed));
+        }
+        Ok(())
+    }
+
+    async fn stats_day(&mut self) -> Result<i64, Box<dyn Error>> {
+        let clock: Clock = self.context.banks_client.get_sysvar().await?;
+        Ok(DailyStats::day_of(clock.unix_timestamp))
+    }
+
+    /// A fresh task id of 32 bytes, which pending rewards store in full
+    fn next_task_id(&mut self) -> String {
+        self.tasks += 1;
+        format!("bench-task-{:021}", self.tasks)
+    }
+
+    async fn record_reward(
+        &mut self,
+        farmer: &Pubkey,
+        task_id: String,
+    ) -> Result<Instruction, Box<dyn Error>> {
+        let stats_day = self.stats_day().await?;
+        Ok(instructions::record_reward(
+            &self.pool.accounts,
+            &self.pool.platform_authority.pubkey(),
+            farmer,
+            REWARD_AMOUNT,
+            task_id,
+            RewardAttribution::default(),
+            stats_day,
+        ))
+    }
+
+    /// Withdraws the farmer's pending reward for `task_id`
+    async fn withdraw_reward(
+        &mut self,
+        farmer: &Pubkey,
+        task_id: &str,
+        destination: &Pubkey,
+    ) -> Result<Instruction, Box<dyn Error>> {
+        self.nonce += 1;
+        let stats_day = self.stats_day().await?;
+        Ok(instructions::withdraw_reward(
+            &self.pool.accounts,
+            farmer,
+            task_id,
+            destination,
+            REWARD_AMOUNT - REWARD_AMOUNT * PLATFORM_FEE_PERCENTAGE as u64 / 100,
+            self.nonce,
+            stats_day,
+        ))
+    }
+
+    /// Funds `farmer` and creates their reward account, which RecordReward
+    /// expects to exist
+    async fn add_farmer(&mut self, index: u8) -> Result<Keypair, Box<dyn Error>> {
+        let farmer = self.pool.farmer(index);
+        airdrop(&mut self.context, &farmer.pubkey(), LAMPORTS_PER_SOL).await?;
+        self.pool
+            .create_reward_account(&mut self.context, &farmer.pubkey())
+            .await?;
+        Ok(farmer)
+    }
+
+    /// Runs every scenario, in an order where each leaves the pool in the
+    /// state the next one measures
+    async fn run(&mut self) -> Result<(), Box<dyn Error>> {
+        let authority = self.pool.platform_authority.insecure_clone();
+        let accounts = self.pool.accounts;
+
+        // Rewards and withdrawals of a farmer with history
+        let farmer = self.add_farmer(0).await?;
+        let destination = fixture_keypair(0, DESTINATION, 0);
+        token::create_token_account(
+            &mut self.context,
+            &destination,
+            &accounts.reward_mint,
+            &farmer.pubkey(),
+        )
+        .await?;
+
+        let first_task_id = self.next_task_id();
+        let instruction = self
+            .record_reward(&farmer.pubkey(), first_task_id.clone())
+            .await?;
+        self.process(Some("RecordReward (first reward)"), instruction, &authority)
+            .await?;
+        let task_id = self.next_task_id();
+        let instruction = self
+            .record_reward(&farmer.pubkey(), task_id.clone())
+            .await?;
+        self.process(Some("RecordReward"), instruction, &authority)
+            .await?;
+        let instruction = self
+            .withdraw_reward(&farmer.pubkey(), &first_task_id, &destination.pubkey())
+            .await?;
+        self.process(Some("WithdrawReward"), instruction, &farmer)
+            .await?;
+        let instruction = instructions::set_withdraw_burn(&accounts, &authority.pubkey(), 100);
+        self.process(Some("SetWithdrawBurn"), instruction, &authority)
+            .await?;
+        let instruction = self
+            .withdraw_reward(&farmer.pubkey(), &task_id, &destination.pubkey())
+            .await?;
+        self.process(Some("WithdrawReward (burn)"), instruction, &farmer)
+            .await?;
+
+        // Administration
+        let instruction = instructions::update_platform_fee(&accounts, &authority.pubkey(), 3);
+        self.process(Some("UpdatePlatformFee"), instruction, &authority)
+            .await?;
+        let instruction = instructions::pause_pool(&accounts, &authority.pubkey());
+        self.process(Some("PausePool"), instruction, &authority)
+            .await?;
+        let instruction = instructions::resume_pool(&accounts, &authority.pubkey());
+        self.process(Some("ResumePool"), instruction, &authority)
+            .await?;
+
+        // Maker-checker rewards, up to a full claim batch
+        let approver = fixture_keypair(0, APPROVER, 0);
+        let instruction = instructions::set_reward_approver(
+            &accounts,
+            &authority.pubkey(),
+            Some(&approver.pubkey()),
+        );
+        self.process(Some("SetRewardApprover"), instruction, &authority)
+            .await?;
+
+        let claimant = self.add_farmer(1).await?;
+        let mut pending_rewards = Vec::new();
+        for index in 0..=MAX_CLAIM_BATCH {
+            let task_id = self.next_task_id();
+            let instruction = self
+                .record_reward(&claimant.pubkey(), task_id.clone())
+                .await?;
+            let name = (index == 0).then_some("RecordReward (awaiting approval)");
+            self.process(name, instruction, &authority).await?;
+
+            let instruction = instructions::approve_reward(
+                &accounts,
+                &approver.pubkey(),
+                &claimant.pubkey(),
+                &task_id,
+            );
+            let name = (index == 0).then_some("ApproveReward");
+            self.process(name, instruction, &approver).await?;
+            pending_rewards.push(accounts.pending_reward(&claimant.pubkey(), &task_id));
+        }
+
+        let instruction =
+            instructions::claim_many(&accounts, &claimant.pubkey(), &pending_rewards[..1]);
+        self.process(Some("ClaimMany (1 reward)"), instruction, &claimant)
+            .await?;
+        // The largest batch a legacy transaction carries, the fixed accounts
+        // leaving less room than `MAX_CLAIM_BATCH` pending rewards need
+        let mut batch = pending_rewards.len() - 1;
+        let instruction = loop {
+            let instruction = instructions::claim_many(
+                &accounts,
+                &claimant.pubkey(),
+                &pending_rewards[1..=batch],
+            );
+            let transaction = self.transaction(instruction.clone(), &claimant, Hash::default());
+            if batch == 1 || Self::transaction_size(&transaction) <= MAX_TRANSACTION_SIZE {
+                break instruction;
+            }
+            batch -= 1;
+        };
+        let name = format!("ClaimMany ({batch} rewards)");
+        self.process(Some(&name), instruction, &claimant).await?;
+        Ok(())
+    }
+}
+
+fn read_budgets(path: &PathBuf) -> Result<BTreeMap<String, u64>, Box<dyn Error>> {
+    let contents =
+        fs::read_to_string(path).map_err(|error| format!("{}: {error}", path.display()))?;
+    Ok(serde_json::from_str(&contents).map_err(|error| format!("{}: {error}", path.display()))?)
+}
+
+/// Prints every measurement next to its budget and returns whether each has
+/// a budget and none exceeds it by more than `threshold_percent`. Budgets no
+/// instruction was measured against are reported as stale but do not fail
+/// the run.
+fn compare(
+    measurements: &[(String, u64)],
+    budgets: &BTreeMap<String, u64>,
+    threshold_percent: f64,
+) -> bool {
+    let mut within_budgets = true;
+    println!(
+        "{:<36} {:>9} {:>9} {:>9}",
+        "instruction", "units", "budget", "change"
+    );
+    for (name, units) in measurements {
+        let Some(&budget) = budgets.get(name) else {
+            within_budgets = false;
+            println!("{name:<36} {units:>9} {:>9} {:>9}  no budget", "-", "new");
+            continue;
+        };
+        let change_percent = (*units as f64 - budget as f64) * 100.0 / budget.max(1) as f64;
+        let regressed = change_percent > threshold_percent;
+        within_budgets &= !regressed;
+        println!(
+            "{name:<36} {units:>9} {budget:>9} {change_percent:>+8.1}%{}",
+            if regressed { "  over budget" } else { "" }
+        );
+    }
+    for name in budgets.keys() {
+        if !measurements.iter().any(|(measured, _)| measured == name) {
+            println!("{name:<36} {:>9} {:>9} {:>9}", "-", budgets[name], "stale");
+        }
+    }
+    within_budgets
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = parse_options(&args).unwrap_or_else(|error| {
+        eprintln!("{error}\n{USAGE}");
+        process::exit(2);
+    });
+
+    let mut bench = Bench::start(options.native).await?;
+    bench.run().await?;
+
+    if options.native {
+        for (name, units) in &bench.measurements {
+            println!("{name:<36} {units:>9}");
+        }
+        eprintln!("Native run: units are not metered like the SBF build's, budgets not checked");
+        return Ok(());
+    }
+
+    if options.record {
+        let budgets: BTreeMap<&str, u64> = bench
+            .measurements
+            .iter()
+            .map(|(name, units)| (name.as_str(), *units))
+            .collect();
+        fs::write(
+            &options.budgets,
+            serde_json::to_string_pretty(&budgets)? + "\n",
+        )?;
+        eprintln!(
+            "Recorded {} budgets to {}",
+            budgets.len(),
+            options.budgets.display()
+        );
+        return Ok(());
+    }
+
+    let budgets = read_budgets(&options.budgets)?;
+    if !compare(&bench.measurements, &budgets, options.threshold_percent) {
+        eprintln!(
+            "Compute units lack a budget or exceed theirs by more than {}%; \
+             run with --record to record new budgets",
+            options.threshold_percent
+        );
+        process::exit(1);
+    }
+    Ok(())
+}
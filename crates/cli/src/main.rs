@@ -0,0 +1,259 @@
+//! Operator commands for the reward pool program.
+//!
+//! ```text
+//! reward-pool snapshot --rpc <URL> [--program-id <ADDRESS>] [--out <FILE>]
+//! reward-pool diff <OLD> <NEW> [--json]
+//! ```
+//!
+//! `snapshot` decodes every account owned by the program at the current
+//! slot and writes it as JSON. `diff` compares two snapshot files field by
+//! field, e.g. one taken before and one after an incident.
+
+use std::{env, error::Error, fs::File, io, process};
+
+use reward_pool_client::{
+    diff_snapshots, fetch_snapshot,
+    snapshot::{AccountDiff, SnapshotDiff},
+    AccountSnapshot,
+};
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+
+const USAGE: &str =
+    "usage: reward-pool snapshot --rpc <URL> [--program-id <ADDRESS>] [--out <FILE>]\n       \
+                     reward-pool diff <OLD> <NEW> [--json]";
+
+enum Command {
+    Snapshot {
+        rpc_url: String,
+        program_id: Pubkey,
+        out: Option<String>,
+    },
+    Diff {
+        old: String,
+        new: String,
+        json: bool,
+    },
+}
+
+fn parse_command(args: &[String]) -> Result<Command, String> {
+    let (command, args) = args.split_first().ok_or("missing command")?;
+    match command.as_str() {
+        "snapshot" => parse_snapshot(args),
+        "diff" => parse_diff(args),
+        other => Err(format!("unknown command {other}")),
+    }
+}
+
+fn parse_snapshot(args: &[String]) -> Result<Command, String> {
+    let mut rpc_url = None;
+    let mut program_id = reward_pool::id();
+    let mut out = None;
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {flag}"));
+        match flag.as_str() {
+            "--rpc" => rpc_url = Some(value()?.clone()),
+            "--program-id" => {
+                let input = value()?;
+                program_id = input
+                    .parse()
+                    .map_err(|_| format!("invalid address {input}"))?
+            }
+            "--out" => out = Some(value()?.clone()),
+            other => return Err(format!("unknown argument {other}")),
+        }
+    }
+
+    Ok(Command::Snapshot {
+        rpc_url: rpc_url.ok_or("--rpc is required")?,
+        program_id,
+        out,
+    })
+}
+
+fn parse_diff(args: &[String]) -> Result<Command, String> {
+    let mut files = Vec::new();
+    let mut json = false;
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown argument {flag}")),
+            file => files.push(file.to_string()),
+        }
+    }
+
+    match <[String; 2]>::try_from(files) {
+        Ok([old, new]) => Ok(Command::Diff { old, new, json }),
+        Err(_) => Err("diff takes exactly two snapshot files".to_string()),
+    }
+}
+
+fn read_snapshot(path: &str) -> Result<AccountSnapshot, Box<dyn Error>> {
+    let file = File::open(path).map_err(|error| format!("{path}: {error}"))?;
+    Ok(serde_json::from_reader(io::BufReader::new(file))
+        .map_err(|error| format!("{path}: {error}"))?)
+}
+
+fn print_diff(diff: &SnapshotDiff) {
+    println!(
+        "slot {} -> {}: {} accounts changed",
+        diff.from_slot,
+        diff.to_slot,
+        diff.accounts.len()
+    );
+    for account in &diff.accounts {
+        match account {
+            AccountDiff::Added { address, kind } => println!("+ {kind} {address}"),
+            AccountDiff::Removed { address, kind } => println!("- {kind} {address}"),
+            AccountDiff::Changed {
+                address,
+                kind,
+                fields,
+            } => {
+                println!("~ {kind} {address}");
+                for field in fields {
+                    println!("    {}: {} -> {}", field.path, field.old, field.new);
+                }
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = parse_command(&args).unwrap_or_else(|error| {
+        eprintln!("{error}\n{USAGE}");
+        process::exit(2);
+    });
+
+    match command {
+        Command::Snapshot {
+            rpc_url,
+            program_id,
+            out,
+        } => {
+            let rpc = RpcClient::new(rpc_url);
+            let snapshot = fetch_snapshot(&rpc, &program_id).await?;
+            let writer: Box<dyn io::Write> = match &out {
+                Some(path) => Box::new(File::create(path)?),
+                None => Box::new(io::stdout()),
+            };
+            serde_json::to_writer_pretty(writer, &snapshot)?;
+            eprintln!(
+                "Captured {} accounts at slot {}",
+                snapshot.accounts.len(),
+                snapshot.slot
+            );
+        }
+        Command::Diff { old, new, json } => {
+            let diff = diff_snapshots(&read_snapshot(&old)?, &read_snapshot(&new)?);
+            if json {
+                serde_json::to_writer_pretty(io::stdout(), &diff)?;
+                println!();
+            } else {
+                print_diff(&diff);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        parse_command(&args)
+    }
+
+    #[test]
+    fn diff_takes_two_files_and_an_optional_json_flag() {
+        assert!(matches!(
+            parse(&["diff", "old.json", "new.json"]),
+            Ok(Command::Diff { old, new, json: false }) if old == "old.json" && new == "new.json"
+        ));
+        assert!(matches!(
+            parse(&["diff", "--json", "old.json", "new.json"]),
+            Ok(Command::Diff { json: true, .. })
+        ));
+    }
+
+    #[test]
+    fn diff_rejects_a_wrong_number_of_files_and_unknown_flags() {
+        for args in [
+            &["diff"][..],
+            &["diff", "old.json"],
+            &["diff", "a.json", "b.json", "c.json"],
+        ] {
+            assert_eq!(
+                parse(args).err().as_deref(),
+                Some("diff takes exactly two snapshot files")
+            );
+        }
+        assert_eq!(
+            parse(&["diff", "old.json", "new.json", "--yaml"])
+                .err()
+                .as_deref(),
+            Some("unknown argument --yaml")
+        );
+    }
+
+    #[test]
+    fn snapshot_requires_an_rpc_url_and_defaults_to_the_program_id() {
+        assert!(matches!(
+            parse(&["snapshot", "--rpc", "http://localhost:8899"]),
+            Ok(Command::Snapshot { rpc_url, program_id, out: None })
+                if rpc_url == "http://localhost:8899" && program_id == reward_pool::id()
+        ));
+        assert_eq!(
+            parse(&["snapshot"]).err().as_deref(),
+            Some("--rpc is required")
+        );
+        assert_eq!(
+            parse(&["snapshot", "--rpc"]).err().as_deref(),
+            Some("missing value for --rpc")
+        );
+    }
+
+    #[test]
+    fn snapshot_rejects_an_invalid_program_id() {
+        assert_eq!(
+            parse(&["snapshot", "--rpc", "url", "--program-id", "nope"])
+                .err()
+                .as_deref(),
+            Some("invalid address nope")
+        );
+        let program_id = Pubkey::new_unique();
+        assert!(matches!(
+            parse(&["snapshot", "--rpc", "url", "--program-id", &program_id.to_string(), "--out", "s.json"]),
+            Ok(Command::Snapshot { program_id: parsed, out: Some(out), .. })
+                if parsed == program_id && out == "s.json"
+        ));
+    }
+
+    #[test]
+    fn an_unknown_or_missing_command_is_rejected() {
+        assert_eq!(parse(&[]).err().as_deref(), Some("missing command"));
+        assert_eq!(
+            parse(&["restore"]).err().as_deref(),
+            Some("unknown command restore")
+        );
+    }
+
+    #[test]
+    fn an_unreadable_snapshot_names_its_file() {
+        let error = read_snapshot("/nonexistent/old.json").unwrap_err();
+        assert!(error.to_string().starts_with("/nonexistent/old.json: "));
+
+        let path = env::temp_dir().join(format!("reward-pool-cli-{}.json", process::id()));
+        std::fs::write(&path, "{ not json").unwrap();
+        let path = path.to_str().unwrap();
+        let error = read_snapshot(path).unwrap_err();
+        std::fs::remove_file(path).unwrap();
+        assert!(error.to_string().starts_with(&format!("{path}: ")));
+    }
+}
@@ -0,0 +1,585 @@
+use std::{collections::HashSet, ops::Range, time::Duration};
+
+use solana_address_lookup_table_interface::{
+    instruction::{create_lookup_table, extend_lookup_table},
+    state::LOOKUP_TABLE_MAX_ADDRESSES,
+};
+use solana_program::{
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, AddressLookupTableAccount, VersionedMessage},
+    pubkey::Pubkey,
+};
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_transaction::{versioned::VersionedTransaction, Transaction};
+
+use crate::{
+    client::RewardPoolClient,
+    error::RewardPoolClientError,
+    instructions::{self, PoolAccounts, RewardAttribution},
+    tx_builder::TxBuilder,
+};
+
+/// Largest serialized transaction the network accepts
+const MAX_TRANSACTION_SIZE: usize = 1232;
+/// Most accounts a transaction may lock, inline or looked up
+const MAX_ACCOUNT_LOCKS: usize = 64;
+/// Highest compute unit limit a transaction may request
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+/// Addresses added per lookup table extension transaction
+const EXTEND_CHUNK_SIZE: usize = 20;
+
+// One reward to record in a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewardEntry {
+    pub farmer: Pubkey,
+    pub amount: u64,
+    pub task_id: String,
+    pub attribution: RewardAttribution,
+}
+
+// Which lookup table batches compile against
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LookupTableStrategy {
+    /// Create a table only when the entries do not fit in one transaction,
+    /// which a single `RecordReward` with every account inline does not
+    #[default]
+    Auto,
+    /// Extend and use a table the authority already owns
+    Existing(Pubkey),
+    /// Inline every account
+    Disabled,
+}
+
+// Batching policy for `record_rewards_batched`
+#[derive(Debug, Clone)]
+pub struct BatchConfig {
+    pub lookup_table: LookupTableStrategy,
+    /// Compute units requested per entry, covering reward account creation
+    pub compute_units_per_entry: u32,
+    /// Priority fee attached to every batch, in micro-lamports per unit
+    pub compute_unit_price: Option<u64>,
+    /// Delay between two slot polls while a lookup table warms up
+    pub poll_interval: Duration,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            lookup_table: LookupTableStrategy::Auto,
+            compute_units_per_entry: 60_000,
+            compute_unit_price: None,
+            poll_interval: Duration::from_millis(400),
+        }
+    }
+}
+
+// Result of one batch transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchResult {
+    /// Indices of the entries the batch holds
+    pub entries: Range<usize>,
+    pub outcome: Result<Signature, String>,
+}
+
+// Result of a batched run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchReport {
+    /// Lookup table the batches were compiled against, if any
+    pub lookup_table: Option<Pubkey>,
+    pub batches: Vec<BatchResult>,
+}
+
+impl BatchReport {
+    /// Entries whose batch failed, to be retried
+    pub fn failed_entries(&self) -> impl Iterator<Item = usize> + '_ {
+        self.batches
+            .iter()
+            .filter(|batch| batch.outcome.is_err())
+            .flat_map(|batch| batch.entries.clone())
+    }
+}
+
+/// Splits `entries` into consecutive batches, each as large as fits in one
+/// v0 transaction against `lookup_tables`: within the size, account lock
+/// and compute unit limits. Filling batches greedily in order yields the
+/// fewest transactions.
+pub fn pack_reward_batches(
+    accounts: &PoolAccounts,
+    authority: &Pubkey,
+    entries: &[RewardEntry],
+    lookup_tables: &[AddressLookupTableAccount],
+    config: &BatchConfig,
+    stats_day: i64,
+) -> Result<Vec<Range<usize>>, RewardPoolClientError> {
+    let mut batches = Vec::new();
+    let mut start = 0;
+
+    while start < entries.len() {
+        let mut end = start;
+        while end < entries.len()
+            && batch_fits(
+                &batch_builder(
+                    accounts,
+                    authority,
+                    &entries[start..=end],
+                    config,
+                    stats_day,
+                ),
+                lookup_tables,
+                end + 1 - start,
+                config,
+            )
+        {
+            end += 1;
+        }
+
+        if end == start {
+            return Err(RewardPoolClientError::EntryTooLarge(start));
+        }
+        batches.push(start..end);
+        start = end;
+    }
+
+    Ok(batches)
+}
+
+impl RewardPoolClient {
+    /// Records rewards in as few transactions as possible, signed and paid
+    /// by `authority`. Unless disabled, pool accounts and farmer reward and
+    /// state accounts are moved into a lookup table first so more entries
+    /// fit per transaction. Batches are sent in order and a failed batch does not
+    /// stop the following ones; the report tells which entries to retry.
+    pub async fn record_rewards_batched(
+        &self,
+        authority: &dyn Signer,
+        entries: &[RewardEntry],
+        config: &BatchConfig,
+    ) -> Result<BatchReport, RewardPoolClientError> {
+        let stats_day = instructions::current_day();
+        let authority_key = authority.pubkey();
+
+        let lookup_table = match config.lookup_table {
+            LookupTableStrategy::Disabled => None,
+            LookupTableStrategy::Existing(address) => Some(address),
+            LookupTableStrategy::Auto => {
+                match pack_reward_batches(
+                    self.accounts(),
+                    &authority_key,
+                    entries,
+                    &[],
+                    config,
+                    stats_day,
+                ) {
+                    Ok(batches) if batches.len() <= 1 => None,
+                    Ok(_) | Err(RewardPoolClientError::EntryTooLarge(_)) => {
+                        Some(self.create_lookup_table(authority).await?)
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+        };
+
+        let lookup_tables = match lookup_table {
+            Some(address) => {
+                let addresses =
+                    batch_addresses(self.accounts(), &authority_key, entries, config, stats_day);
+                self.extend_lookup_table(authority, address, &addresses, config)
+                    .await?;
+                self.fetch_lookup_tables(&[address]).await?
+            }
+            None => Vec::new(),
+        };
+
+        let batches = pack_reward_batches(
+            self.accounts(),
+            &authority_key,
+            entries,
+            &lookup_tables,
+            config,
+            stats_day,
+        )?;
+
+        let mut results = Vec::with_capacity(batches.len());
+        for range in batches {
+            let builder = batch_builder(
+                self.accounts(),
+                &authority_key,
+                &entries[range.clone()],
+                config,
+                stats_day,
+            );
+            let outcome = self
+                .send_batch(&builder, &lookup_tables, authority)
+                .await
+                .map_err(|error| error.to_string());
+            results.push(BatchResult {
+                entries: range,
+                outcome,
+            });
+        }
+
+        Ok(BatchReport {
+            lookup_table,
+            batches: results,
+        })
+    }
+
+    async fn send_batch(
+        &self,
+        builder: &TxBuilder,
+        lookup_tables: &[AddressLookupTableAccount],
+        authority: &dyn Signer,
+    ) -> Result<Signature, RewardPoolClientError> {
+        let blockhash = self.rpc().get_latest_blockhash().await?;
+        let message = builder.build_v0(blockhash, lookup_tables)?;
+        let transaction = VersionedTransaction::try_new(message, &[authority])?;
+        Ok(self
+            .rpc()
+            .send_and_confirm_transaction(&transaction)
+            .await?)
+    }
+
+    async fn create_lookup_table(
+        &self,
+        authority: &dyn Signer,
+    ) -> Result<Pubkey, RewardPoolClientError> {
+        let recent_slot = self.rpc().get_slot().await?;
+        let (instruction, address) =
+            create_lookup_table(authority.pubkey(), authority.pubkey(), recent_slot);
+        self.send_legacy(&[instruction], authority).await?;
+        Ok(address)
+    }
+
+    // Adds the addresses the table does not hold yet, as far as it has
+    // room, then waits until they can be looked up
+    async fn extend_lookup_table(
+        &self,
+        authority: &dyn Signer,
+        address: Pubkey,
+        addresses: &[Pubkey],
+        config: &BatchConfig,
+    ) -> Result<(), RewardPoolClientError> {
+        let existing = self
+            .fetch_lookup_tables(&[address])
+            .await?
+            .pop()
+            .map(|table| table.addresses)
+            .unwrap_or_default();
+        let known: HashSet<Pubkey> = existing.iter().copied().collect();
+        let room = LOOKUP_TABLE_MAX_ADDRESSES.saturating_sub(existing.len());
+        let missing: Vec<Pubkey> = addresses
+            .iter()
+            .filter(|address| !known.contains(address))
+            .copied()
+            .take(room)
+            .collect();
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        for chunk in missing.chunks(EXTEND_CHUNK_SIZE) {
+            let instruction = extend_lookup_table(
+                address,
+                authority.pubkey(),
+                Some(authority.pubkey()),
+                chunk.to_vec(),
+            );
+            self.send_legacy(&[instruction], authority).await?;
+        }
+
+        // Addresses added in a slot become usable in the next one
+        let extended_at = self.rpc().get_slot().await?;
+        while self.rpc().get_slot().await? <= extended_at {
+            tokio::time::sleep(config.poll_interval).await;
+        }
+        Ok(())
+    }
+
+    async fn send_legacy(
+        &self,
+        instructions: &[Instruction],
+        authority: &dyn Signer,
+    ) -> Result<Signature, RewardPoolClientError> {
+        let blockhash = self.rpc().get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&authority.pubkey()),
+            &[authority],
+            blockhash,
+        );
+        Ok(self
+            .rpc()
+            .send_and_confirm_transaction(&transaction)
+            .await?)
+    }
+}
+
+fn batch_builder(
+    accounts: &PoolAccounts,
+    authority: &Pubkey,
+    entries: &[RewardEntry],
+    config: &BatchConfig,
+    stats_day: i64,
+) -> TxBuilder {
+    let units = config
+        .compute_units_per_entry
+        .saturating_mul(entries.len() as u32);
+    let mut builder = TxBuilder::new(*accounts, *authority)
+        .stats_day(stats_day)
+        .with_compute_unit_limit(units);
+    if let Some(micro_lamports) = config.compute_unit_price {
+        builder = builder.with_compute_unit_price(micro_lamports);
+    }
+
+    entries.iter().fold(builder, |builder, entry| {
+        builder.record_attributed_reward(
+            entry.farmer,
+            entry.amount,
+            entry.task_id.clone(),
+            entry.attribution,
+        )
+    })
+}
+
+fn batch_fits(
+    builder: &TxBuilder,
+    lookup_tables: &[AddressLookupTableAccount],
+    entry_count: usize,
+    config: &BatchConfig,
+) -> bool {
+    if config
+        .compute_units_per_entry
+        .saturating_mul(entry_count as u32)
+        > MAX_COMPUTE_UNIT_LIMIT
+    {
+        return false;
+    }
+
+    // The blockhash does not change the size
+    let Ok(VersionedMessage::V0(message)) = builder.build_v0(Hash::default(), lookup_tables) else {
+        return false;
+    };
+
+    let signatures = message.header.num_required_signatures as usize;
+    let size = 1 + signatures * 64 + VersionedMessage::V0(message.clone()).serialize().len();
+    size <= MAX_TRANSACTION_SIZE && account_locks(&message) <= MAX_ACCOUNT_LOCKS
+}
+
+fn account_locks(message: &v0::Message) -> usize {
+    message.account_keys.len()
+        + message
+            .address_table_lookups
+            .iter()
+            .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+            .sum::<usize>()
+}
+
+// Accounts of every batch that may be looked up: everything but signers
+// and invoked programs, which the runtime requires inline
+fn batch_addresses(
+    accounts: &PoolAccounts,
+    authority: &Pubkey,
+    entries: &[RewardEntry],
+    config: &BatchConfig,
+    stats_day: i64,
+) -> Vec<Pubkey> {
+    let builder = batch_builder(accounts, authority, entries, config, stats_day);
+    let instructions = builder.instructions();
+    let programs: HashSet<Pubkey> = instructions
+        .iter()
+        .map(|instruction| instruction.program_id)
+        .collect();
+
+    let mut seen = HashSet::new();
+    instructions
+        .iter()
+        .flat_map(|instruction| &instruction.accounts)
+        .filter(|meta| !meta.is_signer && !programs.contains(&meta.pubkey))
+        .map(|meta| meta.pubkey)
+        .filter(|address| seen.insert(*address))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATS_DAY: i64 = 20_000;
+
+    fn accounts() -> PoolAccounts {
+        PoolAccounts::for_mint(Pubkey::new_from_array([1; 32]))
+    }
+
+    fn authority() -> Pubkey {
+        Pubkey::new_from_array([2; 32])
+    }
+
+    fn entries(count: u8) -> Vec<RewardEntry> {
+        (0..count)
+            .map(|index| RewardEntry {
+                farmer: Pubkey::new_from_array([10 + index; 32]),
+                amount: 1_000_000,
+                task_id: format!("task-{index}"),
+                attribution: RewardAttribution::default(),
+            })
+            .collect()
+    }
+
+    fn lookup_table(entries: &[RewardEntry], config: &BatchConfig) -> AddressLookupTableAccount {
+        AddressLookupTableAccount {
+            key: Pubkey::new_from_array([3; 32]),
+            addresses: batch_addresses(&accounts(), &authority(), entries, config, STATS_DAY),
+        }
+    }
+
+    fn fits(
+        entries: &[RewardEntry],
+        lookup_tables: &[AddressLookupTableAccount],
+        config: &BatchConfig,
+    ) -> bool {
+        let builder = batch_builder(&accounts(), &authority(), entries, config, STATS_DAY);
+        batch_fits(&builder, lookup_tables, entries.len(), config)
+    }
+
+    #[test]
+    fn batches_cover_every_entry_in_order_and_are_full() {
+        let config = BatchConfig::default();
+        let entries = entries(40);
+        let tables = [lookup_table(&entries, &config)];
+        let batches = pack_reward_batches(
+            &accounts(),
+            &authority(),
+            &entries,
+            &tables,
+            &config,
+            STATS_DAY,
+        )
+        .expect("entries packed");
+
+        assert!(batches.len() > 1, "40 rewards overflow one transaction");
+        assert_eq!(batches.first().map(|batch| batch.start), Some(0));
+        assert_eq!(batches.last().map(|batch| batch.end), Some(entries.len()));
+        for pair in batches.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+            // Greedy packing: the next entry would not have fit
+            assert!(!fits(
+                &entries[pair[0].start..=pair[0].end],
+                &tables,
+                &config
+            ));
+        }
+    }
+
+    #[test]
+    fn a_lookup_table_fits_more_entries_per_batch() {
+        let config = BatchConfig::default();
+        let entries = entries(20);
+        let inline =
+            pack_reward_batches(&accounts(), &authority(), &entries, &[], &config, STATS_DAY)
+                .expect("entries packed inline");
+        let tables = [lookup_table(&entries, &config)];
+        let looked_up = pack_reward_batches(
+            &accounts(),
+            &authority(),
+            &entries,
+            &tables,
+            &config,
+            STATS_DAY,
+        )
+        .expect("entries packed against the table");
+        assert!(looked_up.len() < inline.len());
+    }
+
+    #[test]
+    fn batches_stop_at_the_compute_unit_limit() {
+        let config = BatchConfig {
+            lookup_table: LookupTableStrategy::Disabled,
+            compute_units_per_entry: MAX_COMPUTE_UNIT_LIMIT / 2,
+            ..BatchConfig::default()
+        };
+        let entries = entries(5);
+        let tables = [lookup_table(&entries, &config)];
+        let batches = pack_reward_batches(
+            &accounts(),
+            &authority(),
+            &entries,
+            &tables,
+            &config,
+            STATS_DAY,
+        )
+        .expect("entries packed");
+        assert_eq!(batches, vec![0..2, 2..4, 4..5]);
+    }
+
+    #[test]
+    fn an_entry_no_transaction_holds_is_reported() {
+        let config = BatchConfig {
+            compute_units_per_entry: MAX_COMPUTE_UNIT_LIMIT + 1,
+            ..BatchConfig::default()
+        };
+        let result = pack_reward_batches(
+            &accounts(),
+            &authority(),
+            &entries(3),
+            &[],
+            &config,
+            STATS_DAY,
+        );
+        assert!(matches!(
+            result,
+            Err(RewardPoolClientError::EntryTooLarge(0))
+        ));
+    }
+
+    #[test]
+    fn no_entries_make_no_batches() {
+        let batches = pack_reward_batches(
+            &accounts(),
+            &authority(),
+            &[],
+            &[],
+            &BatchConfig::default(),
+            STATS_DAY,
+        )
+        .expect("nothing to pack");
+        assert!(batches.is_empty());
+    }
+
+    #[test]
+    fn looked_up_addresses_leave_out_signers_and_programs() {
+        let config = BatchConfig::default();
+        let entries = entries(2);
+        let addresses = batch_addresses(&accounts(), &authority(), &entries, &config, STATS_DAY);
+
+        assert!(!addresses.contains(&authority()));
+        assert!(!addresses.contains(&accounts().program_id));
+        assert!(addresses.contains(&accounts().pool));
+        let unique: HashSet<&Pubkey> = addresses.iter().collect();
+        assert_eq!(unique.len(), addresses.len());
+    }
+
+    #[test]
+    fn failed_entries_are_those_of_failed_batches() {
+        let report = BatchReport {
+            lookup_table: None,
+            batches: vec![
+                BatchResult {
+                    entries: 0..3,
+                    outcome: Ok(Signature::default()),
+                },
+                BatchResult {
+                    entries: 3..5,
+                    outcome: Err("blockhash expired".to_string()),
+                },
+                BatchResult {
+                    entries: 5..6,
+                    outcome: Err("insufficient funds".to_string()),
+                },
+            ],
+        };
+        assert_eq!(report.failed_entries().collect::<Vec<_>>(), vec![3, 4, 5]);
+    }
+}
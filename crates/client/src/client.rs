@@ -0,0 +1,1122 @@
+use std::sync::Arc;
+
+use borsh::BorshDeserialize;
+use reward_pool::{
+    AuthorityRecovery, BuybackConfig, Campaign, ClaimLedger, ClaimShard, FactoryPool,
+    FarmerRecovery, FarmerState, FarmerStats, FeeDiscounts, FeeInvoice, FeeLedger, FeeOverrides,
+    FeeRebate, Guild, InterestPolicy, KeyHistory, MilestoneBonuses, ParameterHistory,
+    ParameterValues, PayoutAddress, PayoutQueue, PayoutTicket, PendingAction, PendingReward,
+    PendingTransferPolicy, Platform, PoolArchive, PoolAttestor, PoolCharity, PoolGuardians,
+    PoolMetadata, PoolParameters, PoolSnapshot, PoolVerifier, ProtocolConfig, ProtocolLiquidity,
+    RewardApproval, RewardChallenge, RewardGovernor, RewardPool, RewardRate, RewardReceipt,
+    SecondaryBalance, SecondaryReward, StateExport, TaskBudget, TreasuryGovernance,
+    TreasuryProposal, Voucher, WithdrawBurn, WithdrawalFee, WithdrawalHistory, WithdrawalRamp,
+    WithdrawalRecord, WithdrawalWhitelist,
+};
+use solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use spl_token::state::Account as TokenAccount;
+
+use crate::{error::RewardPoolClientError, instructions::PoolAccounts, tx_builder::TxBuilder};
+
+// RPC-backed client bound to a single pool
+#[derive(Clone)]
+pub struct RewardPoolClient {
+    rpc: Arc<RpcClient>,
+    accounts: PoolAccounts,
+}
+
+impl RewardPoolClient {
+    pub fn new(rpc: Arc<RpcClient>, accounts: PoolAccounts) -> Self {
+        Self { rpc, accounts }
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    pub fn accounts(&self) -> &PoolAccounts {
+        &self.accounts
+    }
+
+    /// Starts a transaction against this client's pool
+    pub fn tx_builder(&self, payer: Pubkey) -> TxBuilder {
+        TxBuilder::new(self.accounts, payer)
+    }
+
+    /// Fetches and decodes the pool account
+    pub async fn get_pool(&self) -> Result<RewardPool, RewardPoolClientError> {
+        let data = self.get_account_data(&self.accounts.pool).await?;
+        RewardPool::deserialize(&mut data.as_slice())
+            .map_err(|_| RewardPoolClientError::InvalidAccountData(self.accounts.pool))
+    }
+
+    /// Fetches the pool's display name and metadata URI, returning `None` if
+    /// they were never set
+    pub async fn get_pool_metadata(&self) -> Result<Option<PoolMetadata>, RewardPoolClientError> {
+        let address = self.accounts.pool_metadata();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                PoolMetadata::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the pool's archival record, returning `None` if the pool is
+    /// not archived
+    pub async fn get_pool_archive(&self) -> Result<Option<PoolArchive>, RewardPoolClientError> {
+        let address = self.accounts.pool_archive();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                PoolArchive::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the pool's charity configuration, returning `None` if no
+    /// charity account was set
+    pub async fn get_pool_charity(&self) -> Result<Option<PoolCharity>, RewardPoolClientError> {
+        let address = self.accounts.pool_charity();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                PoolCharity::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Whether farmers of the pool may reassign their pending rewards
+    pub async fn pending_transfers_allowed(&self) -> Result<bool, RewardPoolClientError> {
+        let address = self.accounts.pending_transfer_policy();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        match account {
+            Some(account) => PendingTransferPolicy::deserialize(&mut account.data.as_slice())
+                .map(|policy| !policy.transfers_disabled)
+                .map_err(|_| RewardPoolClientError::InvalidAccountData(address)),
+            None => Ok(true),
+        }
+    }
+
+    /// Fetches the pool's state commitment, returning `None` if the pool
+    /// was not exported
+    pub async fn get_state_export(&self) -> Result<Option<StateExport>, RewardPoolClientError> {
+        let address = self.accounts.state_export();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                StateExport::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches and decodes the pool's treasury governance, which holds the
+    /// id of the next proposal
+    pub async fn get_treasury_governance(
+        &self,
+    ) -> Result<TreasuryGovernance, RewardPoolClientError> {
+        let address = self.accounts.treasury_governance();
+        let data = self.get_account_data(&address).await?;
+        TreasuryGovernance::deserialize(&mut data.as_slice())
+            .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+    }
+
+    /// Fetches and decodes a treasury withdrawal proposal
+    pub async fn get_treasury_proposal(
+        &self,
+        proposal_id: u64,
+    ) -> Result<TreasuryProposal, RewardPoolClientError> {
+        let address = self.accounts.treasury_proposal(proposal_id);
+        let data = self.get_account_data(&address).await?;
+        TreasuryProposal::deserialize(&mut data.as_slice())
+            .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+    }
+
+    /// Fetches and decodes a queued admin action
+    pub async fn get_pending_action(
+        &self,
+        action_id: u64,
+    ) -> Result<PendingAction, RewardPoolClientError> {
+        let address = self.accounts.pending_action(action_id);
+        let data = self.get_account_data(&address).await?;
+        PendingAction::deserialize(&mut data.as_slice())
+            .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+    }
+
+    /// Tunable limits the program currently applies to the pool, the
+    /// defaults until the governance first sets them
+    pub async fn get_parameters(&self) -> Result<ParameterValues, RewardPoolClientError> {
+        let address = self.accounts.parameters();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        match account {
+            Some(account) => PoolParameters::deserialize(&mut account.data.as_slice())
+                .map(|parameters| parameters.values)
+                .map_err(|_| RewardPoolClientError::InvalidAccountData(address)),
+            None => Ok(ParameterValues::default()),
+        }
+    }
+
+    /// Fetches the pool's parameter history, returning `None` if the
+    /// parameters never changed
+    pub async fn get_parameter_history(
+        &self,
+    ) -> Result<Option<ParameterHistory>, RewardPoolClientError> {
+        let address = self.accounts.parameter_history();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                ParameterHistory::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Keys allowed to pause the pool besides the platform authority
+    pub async fn get_guardians(&self) -> Result<Vec<Pubkey>, RewardPoolClientError> {
+        let address = self.accounts.guardians();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        match account {
+            Some(account) => PoolGuardians::deserialize(&mut account.data.as_slice())
+                .map(|guardians| guardians.guardians().to_vec())
+                .map_err(|_| RewardPoolClientError::InvalidAccountData(address)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Current upgrade authority of the program, `None` if it is immutable.
+    /// Equals `PoolAccounts::upgrade_authority` while this pool governs
+    /// upgrades.
+    pub async fn get_upgrade_authority(&self) -> Result<Option<Pubkey>, RewardPoolClientError> {
+        let address = get_program_data_address(&self.accounts.program_id);
+        let data = self.get_account_data(&address).await?;
+        match bincode::deserialize(&data) {
+            Ok(UpgradeableLoaderState::ProgramData {
+                upgrade_authority_address,
+                ..
+            }) => Ok(upgrade_authority_address),
+            _ => Err(RewardPoolClientError::InvalidAccountData(address)),
+        }
+    }
+
+    /// Fetches a token account, returning `None` if it does not exist
+    pub async fn get_token_account(
+        &self,
+        address: &Pubkey,
+    ) -> Result<Option<TokenAccount>, RewardPoolClientError> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                TokenAccount::unpack(&account.data)
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(*address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the farmer's pending reward for a task, `None` if none was
+    /// booked
+    pub async fn get_pending_reward(
+        &self,
+        farmer: &Pubkey,
+        task_id: &str,
+    ) -> Result<Option<PendingReward>, RewardPoolClientError> {
+        let address = self.accounts.pending_reward(farmer, task_id);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                PendingReward::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the open challenge of a farmer's pending reward for a task,
+    /// `None` if it is not challenged
+    pub async fn get_reward_challenge(
+        &self,
+        farmer: &Pubkey,
+        task_id: &str,
+    ) -> Result<Option<RewardChallenge>, RewardPoolClientError> {
+        let address = self.accounts.reward_challenge(farmer, task_id);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                RewardChallenge::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the budget locked for a task, `None` if there is none or it
+    /// was released
+    pub async fn get_task_budget(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<TaskBudget>, RewardPoolClientError> {
+        let address = self.accounts.task_budget(task_id);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                TaskBudget::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the pool's attestor configuration, returning `None` if the
+    /// pool never had an attestor
+    pub async fn get_attestor(&self) -> Result<Option<PoolAttestor>, RewardPoolClientError> {
+        let address = self.accounts.pool_attestor();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                PoolAttestor::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the approver rewards wait for in maker-checker mode, `None`
+    /// if the pool records rewards directly
+    pub async fn get_reward_approver(&self) -> Result<Option<Pubkey>, RewardPoolClientError> {
+        let address = self.accounts.reward_approval();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        Ok(account
+            .map(|account| {
+                RewardApproval::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()?
+            .and_then(|reward_approval| reward_approval.approver()))
+    }
+
+    /// Fetches the pool's platform fees per task type, `None` if no task
+    /// type has a fee of its own
+    pub async fn get_fee_overrides(&self) -> Result<Option<FeeOverrides>, RewardPoolClientError> {
+        let address = self.accounts.fee_overrides();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                FeeOverrides::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the namespace account of the pool's platform, `None` if the
+    /// pool is not in a platform namespace or the platform is unregistered
+    pub async fn get_platform(&self) -> Result<Option<Platform>, RewardPoolClientError> {
+        let Some(address) = self.accounts.platform() else {
+            return Ok(None);
+        };
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                Platform::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches a campaign of the pool, `None` if it does not exist
+    pub async fn get_campaign(
+        &self,
+        campaign_id: u64,
+    ) -> Result<Option<Campaign>, RewardPoolClientError> {
+        let address = self.accounts.campaign(campaign_id);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                Campaign::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the pool's buyback-and-burn configuration, `None` if it was
+    /// never configured
+    pub async fn get_buyback(&self) -> Result<Option<BuybackConfig>, RewardPoolClientError> {
+        let address = self.accounts.buyback();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                BuybackConfig::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the pool's fee discounts by staking tier, `None` if they were
+    /// never set
+    pub async fn get_fee_discounts(&self) -> Result<Option<FeeDiscounts>, RewardPoolClientError> {
+        let address = self.accounts.fee_discounts();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                FeeDiscounts::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the interest policy of the pool's unclaimed rewards, `None`
+    /// if it was never set
+    pub async fn get_interest_policy(
+        &self,
+    ) -> Result<Option<InterestPolicy>, RewardPoolClientError> {
+        let address = self.accounts.interest_policy();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                InterestPolicy::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the share of the pool's withdrawals burned and the burned
+    /// total, `None` if it was never set
+    pub async fn get_withdraw_burn(&self) -> Result<Option<WithdrawBurn>, RewardPoolClientError> {
+        let address = self.accounts.withdraw_burn();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                WithdrawBurn::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the pool's lamport withdrawal fee and the fees collected,
+    /// `None` if it was never set
+    pub async fn get_withdrawal_fee(&self) -> Result<Option<WithdrawalFee>, RewardPoolClientError> {
+        let address = self.accounts.withdrawal_fee();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                WithdrawalFee::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the snapshot exported into `buffer`, `None` if the buffer
+    /// does not exist or nothing was exported into it yet. Check it with
+    /// `PoolSnapshot::is_consistent`.
+    pub async fn get_pool_snapshot(
+        &self,
+        buffer: &Pubkey,
+    ) -> Result<Option<PoolSnapshot>, RewardPoolClientError> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(buffer, self.rpc.commitment())
+            .await?
+            .value;
+
+        // Buffers may be longer than their snapshot, and are zeroed before
+        let snapshot = account
+            .map(|account| {
+                PoolSnapshot::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(*buffer))
+            })
+            .transpose()?;
+        Ok(snapshot.filter(|snapshot| snapshot.pool != Pubkey::default()))
+    }
+
+    /// Fetches the reward governor of the pool and its rolling distribution
+    /// window, `None` if it was never set
+    pub async fn get_reward_governor(
+        &self,
+    ) -> Result<Option<RewardGovernor>, RewardPoolClientError> {
+        let address = self.accounts.reward_governor();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                RewardGovernor::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the secondary mint of the pool and its totals, `None` if the
+    /// pool has none
+    pub async fn get_secondary_reward(
+        &self,
+    ) -> Result<Option<SecondaryReward>, RewardPoolClientError> {
+        let address = self.accounts.secondary_reward();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                SecondaryReward::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the secondary rewards of a farmer, `None` if they were never
+    /// booked any
+    pub async fn get_secondary_balance(
+        &self,
+        farmer: &Pubkey,
+    ) -> Result<Option<SecondaryBalance>, RewardPoolClientError> {
+        let address = self.accounts.secondary_balance(farmer);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                SecondaryBalance::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the milestone bonuses of the pool, `None` if it never had any
+    pub async fn get_milestone_bonuses(
+        &self,
+    ) -> Result<Option<MilestoneBonuses>, RewardPoolClientError> {
+        let address = self.accounts.milestone_bonuses();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                MilestoneBonuses::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the lifetime earnings and milestones of a farmer, `None` until
+    /// a reward is recorded to them while the pool has milestone bonuses
+    pub async fn get_farmer_stats(
+        &self,
+        farmer: &Pubkey,
+    ) -> Result<Option<FarmerStats>, RewardPoolClientError> {
+        let address = self.accounts.farmer_stats(farmer);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                FarmerStats::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the pool's protocol-owned liquidity configuration and totals,
+    /// `None` if it was never configured
+    pub async fn get_protocol_liquidity(
+        &self,
+    ) -> Result<Option<ProtocolLiquidity>, RewardPoolClientError> {
+        let address = self.accounts.protocol_liquidity();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                ProtocolLiquidity::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the pool's fee rebate configuration and totals, `None` if it
+    /// was never set
+    pub async fn get_fee_rebate(&self) -> Result<Option<FeeRebate>, RewardPoolClientError> {
+        let address = self.accounts.fee_rebate();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                FeeRebate::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the protocol-wide configuration of the pool factory, `None`
+    /// if it was never set
+    pub async fn get_protocol_config(
+        &self,
+    ) -> Result<Option<ProtocolConfig>, RewardPoolClientError> {
+        let address = self.accounts.protocol_config();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                ProtocolConfig::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the factory record of the pool, `None` unless it was created
+    /// through the factory
+    pub async fn get_factory_pool(&self) -> Result<Option<FactoryPool>, RewardPoolClientError> {
+        let address = self.accounts.factory_record();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                FactoryPool::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches a reward voucher of the pool, `None` if it was never issued
+    pub async fn get_voucher(
+        &self,
+        voucher_id: u64,
+    ) -> Result<Option<Voucher>, RewardPoolClientError> {
+        let address = self.accounts.voucher(voucher_id);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                Voucher::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the pool's payout queue, `None` if it was never set
+    pub async fn get_payout_queue(&self) -> Result<Option<PayoutQueue>, RewardPoolClientError> {
+        let address = self.accounts.payout_queue();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                PayoutQueue::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches every change of the pool's platform authority, `None` if it
+    /// never changed hands
+    pub async fn get_key_history(&self) -> Result<Option<KeyHistory>, RewardPoolClientError> {
+        let address = self.accounts.key_history();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                KeyHistory::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the farmer's recovery guardians, `None` if they were never
+    /// set
+    pub async fn get_farmer_recovery(
+        &self,
+        farmer: &Pubkey,
+    ) -> Result<Option<FarmerRecovery>, RewardPoolClientError> {
+        let address = self.accounts.farmer_recovery(farmer);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                FarmerRecovery::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the guild led by `authority`, `None` if it was never created
+    pub async fn get_guild(
+        &self,
+        authority: &Pubkey,
+    ) -> Result<Option<Guild>, RewardPoolClientError> {
+        let address = self.accounts.guild(authority);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                Guild::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the pool's fee ledger, `None` before its first fee invoice
+    pub async fn get_fee_ledger(&self) -> Result<Option<FeeLedger>, RewardPoolClientError> {
+        let address = self.accounts.fee_ledger();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                FeeLedger::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches fee invoice `number` of the pool, `None` if it was not closed
+    /// yet
+    pub async fn get_fee_invoice(
+        &self,
+        number: u64,
+    ) -> Result<Option<FeeInvoice>, RewardPoolClientError> {
+        let address = self.accounts.fee_invoice(number);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                FeeInvoice::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the pool's reward rate accumulators, `None` before its first
+    /// reward
+    pub async fn get_reward_rate(&self) -> Result<Option<RewardRate>, RewardPoolClientError> {
+        let address = self.accounts.reward_rate();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                RewardRate::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the farmer's claim shard, `None` before the first claim
+    /// counted in it
+    pub async fn get_claim_shard(
+        &self,
+        farmer: &Pubkey,
+    ) -> Result<Option<ClaimShard>, RewardPoolClientError> {
+        let address = self.accounts.claim_shard(farmer);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                ClaimShard::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the pool's consolidated claim totals, `None` before the
+    /// first consolidation
+    pub async fn get_claim_ledger(&self) -> Result<Option<ClaimLedger>, RewardPoolClientError> {
+        let address = self.accounts.claim_ledger();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                ClaimLedger::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the receipt of a reward recorded under `idempotency_key`,
+    /// `None` if no reward was recorded under it
+    pub async fn get_reward_receipt(
+        &self,
+        idempotency_key: &[u8; 32],
+    ) -> Result<Option<RewardReceipt>, RewardPoolClientError> {
+        let address = self.accounts.reward_receipt(idempotency_key);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                RewardReceipt::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the record of the farmer's withdrawal under `nonce`, `None`
+    /// if the farmer never withdrew under it or closed the record
+    pub async fn get_withdrawal_record(
+        &self,
+        farmer: &Pubkey,
+        nonce: u64,
+    ) -> Result<Option<WithdrawalRecord>, RewardPoolClientError> {
+        let address = self.accounts.withdrawal_record(farmer, nonce);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                WithdrawalRecord::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the pool's authority recovery settings, `None` if they were
+    /// never set
+    pub async fn get_authority_recovery(
+        &self,
+    ) -> Result<Option<AuthorityRecovery>, RewardPoolClientError> {
+        let address = self.accounts.authority_recovery();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                AuthorityRecovery::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the pool's withdrawal ramp, `None` if it never resumed with one
+    pub async fn get_withdrawal_ramp(
+        &self,
+    ) -> Result<Option<WithdrawalRamp>, RewardPoolClientError> {
+        let address = self.accounts.withdrawal_ramp();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                WithdrawalRamp::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches a farmer's payout queue ticket, `None` if they never took one
+    pub async fn get_payout_ticket(
+        &self,
+        farmer: &Pubkey,
+    ) -> Result<Option<PayoutTicket>, RewardPoolClientError> {
+        let address = self.accounts.payout_ticket(farmer);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                PayoutTicket::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches the verifier whose signature recording rewards requires,
+    /// `None` if the pool has none
+    pub async fn get_verifier(&self) -> Result<Option<Pubkey>, RewardPoolClientError> {
+        let address = self.accounts.pool_verifier();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        Ok(account
+            .map(|account| {
+                PoolVerifier::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()?
+            .and_then(|pool_verifier| pool_verifier.verifier()))
+    }
+
+    /// Fetches a farmer's state, returning `None` if the farmer has no
+    /// activity in the pool yet
+    pub async fn get_farmer_state(
+        &self,
+        farmer: &Pubkey,
+    ) -> Result<Option<FarmerState>, RewardPoolClientError> {
+        let address = self.accounts.farmer_state(farmer);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                FarmerState::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches a farmer's withdrawal history, returning `None` if the farmer
+    /// never withdrew
+    pub async fn get_withdrawal_history(
+        &self,
+        farmer: &Pubkey,
+    ) -> Result<Option<WithdrawalHistory>, RewardPoolClientError> {
+        let address = self.accounts.withdrawal_history(farmer);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                WithdrawalHistory::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches a farmer's withdrawal whitelist, returning `None` if the
+    /// farmer never registered a destination
+    pub async fn get_withdrawal_whitelist(
+        &self,
+        farmer: &Pubkey,
+    ) -> Result<Option<WithdrawalWhitelist>, RewardPoolClientError> {
+        let address = self.accounts.withdrawal_whitelist(farmer);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                WithdrawalWhitelist::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    /// Fetches a farmer's payout address registration, returning `None` if
+    /// the farmer never registered one
+    pub async fn get_payout_address(
+        &self,
+        farmer: &Pubkey,
+    ) -> Result<Option<PayoutAddress>, RewardPoolClientError> {
+        let address = self.accounts.payout_address(farmer);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                PayoutAddress::deserialize(&mut account.data.as_slice())
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(address))
+            })
+            .transpose()
+    }
+
+    async fn get_account_data(&self, address: &Pubkey) -> Result<Vec<u8>, RewardPoolClientError> {
+        self.rpc
+            .get_account_with_commitment(address, self.rpc.commitment())
+            .await?
+            .value
+            .map(|account| account.data)
+            .ok_or(RewardPoolClientError::AccountNotFound(*address))
+    }
+}
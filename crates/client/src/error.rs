@@ -0,0 +1,33 @@
+use solana_program::{message::CompileError, program_error::ProgramError, pubkey::Pubkey};
+use solana_rpc_client_api::client_error::Error as RpcClientError;
+use solana_signer::SignerError;
+use solana_transaction_error::TransactionError;
+
+// Client errors
+#[derive(thiserror::Error, Debug)]
+pub enum RewardPoolClientError {
+    #[error("RPC error: {0}")]
+    Rpc(Box<RpcClientError>),
+    #[error("Signing error: {0}")]
+    Signer(#[from] SignerError),
+    #[error("Account not found: {0}")]
+    AccountNotFound(Pubkey),
+    #[error("Invalid account data for {0}")]
+    InvalidAccountData(Pubkey),
+    #[error("Program error: {0}")]
+    Program(#[from] ProgramError),
+    #[error("Simulation failed: {0}")]
+    Simulation(TransactionError),
+    #[error("Missing or invalid return data")]
+    InvalidReturnData,
+    #[error("Message compilation failed: {0}")]
+    Compile(#[from] CompileError),
+    #[error("Reward entry {0} does not fit in a transaction on its own")]
+    EntryTooLarge(usize),
+}
+
+impl From<RpcClientError> for RewardPoolClientError {
+    fn from(e: RpcClientError) -> Self {
+        RewardPoolClientError::Rpc(Box::new(e))
+    }
+}
@@ -0,0 +1,3669 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use reward_pool::{
+    instruction::{v2::RewardPoolInstruction, VersionedInstruction},
+    seeds, DailyStats, DonationTarget, GuildMember, ImportedBalance, Milestone, MilestoneBonuses,
+    Parameter, ParameterValues, ScheduledAction, WithdrawalRecord, WithdrawalSplit,
+};
+use solana_loader_v3_interface::get_program_data_address;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+// Accounts shared by every instruction targeting a given pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolAccounts {
+    pub program_id: Pubkey,
+    pub pool: Pubkey,
+    pub reward_mint: Pubkey,
+    /// Treasury paying the pool's rewards, its treasury vault
+    pub platform_treasury: Pubkey,
+    /// Platform namespace the pool was created in, if any
+    pub platform_id: Option<u64>,
+    /// Protocol treasury sharing the platform fees of a pool created
+    /// through the factory
+    pub protocol_treasury: Option<Pubkey>,
+}
+
+impl PoolAccounts {
+    /// Pool accounts for the canonical program deployment
+    pub fn new(pool: Pubkey, reward_mint: Pubkey) -> Self {
+        Self::with_program_id(reward_pool::id(), pool, reward_mint)
+    }
+
+    /// Pool accounts for a specific program deployment
+    pub fn with_program_id(program_id: Pubkey, pool: Pubkey, reward_mint: Pubkey) -> Self {
+        Self {
+            program_id,
+            pool,
+            reward_mint,
+            platform_treasury: seeds::find_vault_address_with_program_id(&pool, &program_id).0,
+            platform_id: None,
+            protocol_treasury: None,
+        }
+    }
+
+    /// Accounts of the pool of a reward mint for the canonical program
+    /// deployment, at the address `initialize_pool` creates it
+    pub fn for_mint(reward_mint: Pubkey) -> Self {
+        Self::for_mint_with_program_id(reward_pool::id(), reward_mint)
+    }
+
+    /// Accounts of the pool of a reward mint for a specific program
+    /// deployment
+    pub fn for_mint_with_program_id(program_id: Pubkey, reward_mint: Pubkey) -> Self {
+        let pool = seeds::find_pool_address_with_program_id(&reward_mint, &program_id).0;
+        Self::with_program_id(program_id, pool, reward_mint)
+    }
+
+    /// Accounts of a platform's pool for the canonical program deployment
+    pub fn for_platform(platform_id: u64, reward_mint: Pubkey) -> Self {
+        Self::for_platform_with_program_id(reward_pool::id(), platform_id, reward_mint)
+    }
+
+    /// Accounts of a platform's pool for a specific program deployment
+    pub fn for_platform_with_program_id(
+        program_id: Pubkey,
+        platform_id: u64,
+        reward_mint: Pubkey,
+    ) -> Self {
+        let pool = seeds::find_platform_pool_address_with_program_id(
+            platform_id,
+            &reward_mint,
+            &program_id,
+        )
+        .0;
+        Self {
+            platform_id: Some(platform_id),
+            ..Self::with_program_id(program_id, pool, reward_mint)
+        }
+    }
+
+    /// Accounts of a pool `creator` made through the factory for the
+    /// canonical program deployment
+    pub fn for_factory(
+        creator: &Pubkey,
+        reward_mint: Pubkey,
+        seed: u64,
+        protocol_treasury: Pubkey,
+    ) -> Self {
+        Self::for_factory_with_program_id(
+            reward_pool::id(),
+            creator,
+            reward_mint,
+            seed,
+            protocol_treasury,
+        )
+    }
+
+    /// Accounts of a pool `creator` made through the factory for a specific
+    /// program deployment
+    pub fn for_factory_with_program_id(
+        program_id: Pubkey,
+        creator: &Pubkey,
+        reward_mint: Pubkey,
+        seed: u64,
+        protocol_treasury: Pubkey,
+    ) -> Self {
+        let pool = seeds::find_factory_pool_address_with_program_id(
+            creator,
+            &reward_mint,
+            seed,
+            &program_id,
+        )
+        .0;
+        Self {
+            protocol_treasury: Some(protocol_treasury),
+            ..Self::with_program_id(program_id, pool, reward_mint)
+        }
+    }
+
+    /// Protocol-wide configuration of the pool factory
+    pub fn protocol_config(&self) -> Pubkey {
+        seeds::find_protocol_config_address_with_program_id(&self.program_id).0
+    }
+
+    /// Factory record of the pool, empty unless the pool was created
+    /// through the factory
+    pub fn factory_record(&self) -> Pubkey {
+        seeds::find_factory_record_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Token account of the protocol treasury receiving the pool's protocol
+    /// fees, `None` for pools not created through the factory
+    pub fn protocol_fee_account(&self) -> Option<Pubkey> {
+        self.protocol_treasury
+            .map(|treasury| get_associated_token_address(&treasury, &self.reward_mint))
+    }
+
+    /// Namespace account of the pool's platform, `None` outside a namespace
+    pub fn platform(&self) -> Option<Pubkey> {
+        self.platform_id.map(|platform_id| {
+            seeds::find_platform_address_with_program_id(platform_id, &self.program_id).0
+        })
+    }
+
+    /// Associated token account of `owner` for the pool's reward mint
+    pub fn reward_account(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address(owner, &self.reward_mint)
+    }
+
+    /// State account of a farmer in the pool
+    pub fn farmer_state(&self, farmer: &Pubkey) -> Pubkey {
+        seeds::find_farmer_state_address_with_program_id(&self.pool, farmer, &self.program_id).0
+    }
+
+    /// Stats account of the pool for a unix day
+    pub fn daily_stats(&self, day: i64) -> Pubkey {
+        seeds::find_daily_stats_address_with_program_id(&self.pool, day, &self.program_id).0
+    }
+
+    /// Withdrawal destination whitelist of a farmer in the pool
+    pub fn withdrawal_whitelist(&self, farmer: &Pubkey) -> Pubkey {
+        seeds::find_withdrawal_whitelist_address_with_program_id(
+            &self.pool,
+            farmer,
+            &self.program_id,
+        )
+        .0
+    }
+
+    /// Payout address registration of a farmer in the pool
+    pub fn payout_address(&self, farmer: &Pubkey) -> Pubkey {
+        seeds::find_payout_address_address_with_program_id(&self.pool, farmer, &self.program_id).0
+    }
+
+    /// Recent withdrawals of a farmer in the pool
+    pub fn withdrawal_history(&self, farmer: &Pubkey) -> Pubkey {
+        seeds::find_withdrawal_history_address_with_program_id(&self.pool, farmer, &self.program_id)
+            .0
+    }
+
+    /// Charity account farmers of the pool can donate to
+    pub fn pool_charity(&self) -> Pubkey {
+        seeds::find_pool_charity_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Policy allowing or forbidding pending reward transfers in the pool
+    pub fn pending_transfer_policy(&self) -> Pubkey {
+        seeds::find_pending_transfer_policy_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Verifier whose signature recording rewards in the pool requires
+    pub fn pool_verifier(&self) -> Pubkey {
+        seeds::find_pool_verifier_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Maker-checker configuration of the pool
+    pub fn reward_approval(&self) -> Pubkey {
+        seeds::find_reward_approval_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Program whose attestations recording rewards in the pool requires
+    pub fn pool_attestor(&self) -> Pubkey {
+        seeds::find_pool_attestor_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Instruction counters account of the pool
+    pub fn instruction_counters(&self) -> Pubkey {
+        seeds::find_instruction_counters_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Governed treasury vault token account of the pool
+    pub fn treasury_vault(&self) -> Pubkey {
+        seeds::find_vault_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Treasury co-signer configuration of the pool
+    pub fn treasury_governance(&self) -> Pubkey {
+        seeds::find_treasury_governance_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Upgrade authority PDA through which the pool governs the program
+    pub fn upgrade_authority(&self) -> Pubkey {
+        seeds::find_upgrade_authority_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Tunable limits account of the pool
+    pub fn parameters(&self) -> Pubkey {
+        seeds::find_parameters_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Previous values of the pool's parameters
+    pub fn parameter_history(&self) -> Pubkey {
+        seeds::find_parameter_history_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Display name and metadata URI account of the pool
+    pub fn pool_metadata(&self) -> Pubkey {
+        seeds::find_pool_metadata_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// State commitment written when the pool is exported
+    pub fn state_export(&self) -> Pubkey {
+        seeds::find_state_export_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Record of a farmer balance imported into the pool
+    pub fn imported_balance(&self, farmer: &Pubkey) -> Pubkey {
+        seeds::find_imported_balance_address_with_program_id(&self.pool, farmer, &self.program_id).0
+    }
+
+    /// Archival record of the pool
+    pub fn pool_archive(&self) -> Pubkey {
+        seeds::find_pool_archive_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Pending reward of a farmer for a task
+    pub fn pending_reward(&self, farmer: &Pubkey, task_id: &str) -> Pubkey {
+        seeds::find_pending_reward_address_with_program_id(
+            &self.pool,
+            farmer,
+            task_id,
+            &self.program_id,
+        )
+        .0
+    }
+
+    /// Open challenge of a farmer's pending reward for a task
+    pub fn reward_challenge(&self, farmer: &Pubkey, task_id: &str) -> Pubkey {
+        seeds::find_reward_challenge_address_with_program_id(
+            &self.pool,
+            &self.pending_reward(farmer, task_id),
+            &self.program_id,
+        )
+        .0
+    }
+
+    /// Budget locked for a task of the pool
+    pub fn task_budget(&self, task_id: &str) -> Pubkey {
+        seeds::find_task_budget_address_with_program_id(&self.pool, task_id, &self.program_id).0
+    }
+
+    /// Token account holding the pool's locked task budgets
+    pub fn budget_escrow(&self) -> Pubkey {
+        seeds::find_budget_escrow_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Reward voucher of the pool
+    pub fn voucher(&self, voucher_id: u64) -> Pubkey {
+        seeds::find_voucher_address_with_program_id(&self.pool, voucher_id, &self.program_id).0
+    }
+
+    /// NFT mint of a reward voucher of the pool
+    pub fn voucher_mint(&self, voucher_id: u64) -> Pubkey {
+        seeds::find_voucher_mint_address_with_program_id(
+            &self.voucher(voucher_id),
+            &self.program_id,
+        )
+        .0
+    }
+
+    /// Token account holding the pool's unredeemed vouchers
+    pub fn voucher_escrow(&self) -> Pubkey {
+        seeds::find_voucher_escrow_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Payout queue ordering the pool's claims after a pause
+    pub fn payout_queue(&self) -> Pubkey {
+        seeds::find_payout_queue_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Farmer's ticket in the pool's payout queue
+    pub fn payout_ticket(&self, farmer: &Pubkey) -> Pubkey {
+        seeds::find_payout_ticket_address_with_program_id(&self.pool, farmer, &self.program_id).0
+    }
+
+    /// Per-epoch cap on the pool's claims after a ramped resume
+    pub fn withdrawal_ramp(&self) -> Pubkey {
+        seeds::find_withdrawal_ramp_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Dead-man switch of the pool's platform authority
+    pub fn authority_recovery(&self) -> Pubkey {
+        seeds::find_authority_recovery_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Every change of the pool's platform authority
+    pub fn key_history(&self) -> Pubkey {
+        seeds::find_key_history_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Guardians who can recover a farmer's rewards to a new wallet
+    pub fn farmer_recovery(&self, farmer: &Pubkey) -> Pubkey {
+        seeds::find_farmer_recovery_address_with_program_id(&self.pool, farmer, &self.program_id).0
+    }
+
+    /// Lamport fee of the pool's withdrawals
+    pub fn withdrawal_fee(&self) -> Pubkey {
+        seeds::find_withdrawal_fee_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Account collecting the pool's lamport withdrawal fees
+    pub fn fee_vault(&self) -> Pubkey {
+        seeds::find_fee_vault_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Guild of the pool led by `authority`
+    pub fn guild(&self, authority: &Pubkey) -> Pubkey {
+        seeds::find_guild_address_with_program_id(&self.pool, authority, &self.program_id).0
+    }
+
+    /// Token account holding the rewards of `guild`
+    pub fn guild_vault(&self, guild: &Pubkey) -> Pubkey {
+        seeds::find_guild_vault_address_with_program_id(guild, &self.program_id).0
+    }
+
+    /// Fee totals at the last close of the pool's fee invoices
+    pub fn fee_ledger(&self) -> Pubkey {
+        seeds::find_fee_ledger_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Fee invoice `number` of the pool
+    pub fn fee_invoice(&self, number: u64) -> Pubkey {
+        seeds::find_fee_invoice_address_with_program_id(&self.pool, number, &self.program_id).0
+    }
+
+    /// Accumulators of the pool's time-weighted average reward rate
+    pub fn reward_rate(&self) -> Pubkey {
+        seeds::find_reward_rate_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Claim shard counting the farmer's claims
+    pub fn claim_shard(&self, farmer: &Pubkey) -> Pubkey {
+        self.claim_shard_at(seeds::claim_shard_index(farmer))
+    }
+
+    /// Claim shard `shard` of the pool
+    pub fn claim_shard_at(&self, shard: u8) -> Pubkey {
+        seeds::find_claim_shard_address_with_program_id(&self.pool, shard, &self.program_id).0
+    }
+
+    /// Claim totals of the pool as of the last consolidation of its shards
+    pub fn claim_ledger(&self) -> Pubkey {
+        seeds::find_claim_ledger_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Receipt of a reward recorded under `idempotency_key`
+    pub fn reward_receipt(&self, idempotency_key: &[u8; 32]) -> Pubkey {
+        seeds::find_reward_receipt_address_with_program_id(
+            &self.pool,
+            idempotency_key,
+            &self.program_id,
+        )
+        .0
+    }
+
+    /// Record of a farmer's withdrawal under `nonce`
+    pub fn withdrawal_record(&self, farmer: &Pubkey, nonce: u64) -> Pubkey {
+        seeds::find_withdrawal_record_address_with_program_id(
+            &self.pool,
+            farmer,
+            nonce,
+            &self.program_id,
+        )
+        .0
+    }
+
+    /// Campaign account of the pool
+    pub fn campaign(&self, campaign_id: u64) -> Pubkey {
+        seeds::find_campaign_address_with_program_id(&self.pool, campaign_id, &self.program_id).0
+    }
+
+    /// Buyback-and-burn configuration of the pool
+    pub fn buyback(&self) -> Pubkey {
+        seeds::find_buyback_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Token account the pool's buyback swaps from
+    pub fn buyback_source(&self) -> Pubkey {
+        seeds::find_buyback_source_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Token account the pool's buyback receives and burns
+    pub fn buyback_output(&self) -> Pubkey {
+        seeds::find_buyback_output_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Treasury swap limits of the pool
+    pub fn treasury_swap_policy(&self) -> Pubkey {
+        seeds::find_treasury_swap_policy_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Token account holding the pool's treasury swapped into `mint`
+    pub fn treasury_reserve(&self, mint: &Pubkey) -> Pubkey {
+        seeds::find_treasury_reserve_address_with_program_id(&self.pool, mint, &self.program_id).0
+    }
+
+    /// Platform fee discounts by staking tier of the pool
+    pub fn fee_discounts(&self) -> Pubkey {
+        seeds::find_fee_discounts_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Interest policy of the pool's unclaimed rewards
+    pub fn interest_policy(&self) -> Pubkey {
+        seeds::find_interest_policy_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Token account paying the pool's interest
+    pub fn bonus_vault(&self) -> Pubkey {
+        seeds::find_bonus_vault_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Share of the pool's withdrawals burned
+    pub fn withdraw_burn(&self) -> Pubkey {
+        seeds::find_withdraw_burn_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Reward governor of the pool
+    pub fn reward_governor(&self) -> Pubkey {
+        seeds::find_reward_governor_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Secondary mint configuration of the pool
+    pub fn secondary_reward(&self) -> Pubkey {
+        seeds::find_secondary_reward_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Token account backing the pool's secondary rewards
+    pub fn secondary_vault(&self) -> Pubkey {
+        seeds::find_secondary_vault_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Secondary rewards of a farmer
+    pub fn secondary_balance(&self, farmer: &Pubkey) -> Pubkey {
+        seeds::find_secondary_balance_address_with_program_id(&self.pool, farmer, &self.program_id)
+            .0
+    }
+
+    /// Milestone bonuses of the pool
+    pub fn milestone_bonuses(&self) -> Pubkey {
+        seeds::find_milestone_bonuses_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Lifetime earnings and milestones of a farmer
+    pub fn farmer_stats(&self, farmer: &Pubkey) -> Pubkey {
+        seeds::find_farmer_stats_address_with_program_id(&self.pool, farmer, &self.program_id).0
+    }
+
+    /// Pending reward booking a farmer's bonus for reaching the milestone
+    /// after their first `milestones_reached`
+    pub fn milestone_pending_reward(&self, farmer: &Pubkey, milestones_reached: u8) -> Pubkey {
+        self.pending_reward(farmer, &MilestoneBonuses::task_id(milestones_reached))
+    }
+
+    /// Protocol-owned liquidity configuration of the pool
+    pub fn protocol_liquidity(&self) -> Pubkey {
+        seeds::find_protocol_liquidity_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Token account collecting the pool's platform fees, which fund its
+    /// buybacks, liquidity contributions and treasury swaps
+    pub fn platform_fee_vault(&self) -> Pubkey {
+        seeds::find_platform_fee_vault_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Token account funding the pool's liquidity contributions
+    pub fn pol_vault(&self) -> Pubkey {
+        seeds::find_pol_vault_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Token account holding the paired side of the pool's liquidity
+    pub fn pol_pair_vault(&self) -> Pubkey {
+        seeds::find_pol_pair_vault_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Token account holding the pool's LP tokens
+    pub fn pol_lp_vault(&self) -> Pubkey {
+        seeds::find_pol_lp_vault_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Fee rebate configuration of the pool
+    pub fn fee_rebate(&self) -> Pubkey {
+        seeds::find_fee_rebate_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Token account funding the pool's fee rebates
+    pub fn rebate_vault(&self) -> Pubkey {
+        seeds::find_rebate_vault_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Platform fees per task type of the pool
+    pub fn fee_overrides(&self) -> Pubkey {
+        seeds::find_fee_overrides_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Pause-only guardian set of the pool
+    pub fn guardians(&self) -> Pubkey {
+        seeds::find_guardians_address_with_program_id(&self.pool, &self.program_id).0
+    }
+
+    /// Queued admin action of the pool
+    pub fn pending_action(&self, action_id: u64) -> Pubkey {
+        seeds::find_pending_action_address_with_program_id(&self.pool, action_id, &self.program_id)
+            .0
+    }
+
+    /// Treasury withdrawal proposal of the pool
+    pub fn treasury_proposal(&self, proposal_id: u64) -> Pubkey {
+        seeds::find_treasury_proposal_address_with_program_id(
+            &self.pool,
+            proposal_id,
+            &self.program_id,
+        )
+        .0
+    }
+}
+
+// Optional analytics attribution and evidence of a reward
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RewardAttribution {
+    pub campaign_id: Option<u64>,
+    pub platform_id: Option<u64>,
+    /// Digest of the task output, e.g. of its IPFS CID
+    pub proof_hash: Option<[u8; 32]>,
+    /// Task type, charged its fee override if it has one
+    pub task_type: Option<u32>,
+    /// Kicker in the pool's secondary mint, 0 for none
+    pub secondary_amount: u64,
+    /// Caller-chosen key making retries of the reward a no-op
+    pub idempotency_key: Option<[u8; 32]>,
+}
+
+/// Unix day of the local clock, used to pick the stats account. The program
+/// checks it against the cluster clock, so transactions built right at
+/// midnight may need to be rebuilt.
+pub fn current_day() -> i64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64);
+    DailyStats::day_of(now)
+}
+
+/// Creates an `InitializePool` instruction. The program creates the pool at
+/// the address derived from the reward mint, so `accounts` should come from
+/// `PoolAccounts::for_mint`.
+pub fn initialize_pool(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    platform_fee_percentage: u8,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new_readonly(accounts.reward_mint, false),
+            AccountMeta::new(accounts.platform_treasury, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::InitializePool {
+            platform_fee_percentage,
+            name: None,
+            uri: None,
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `InitializePool` instruction that also sets the pool's
+/// display name and metadata URI
+pub fn initialize_pool_with_metadata(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    platform_fee_percentage: u8,
+    name: &str,
+    uri: &str,
+) -> Instruction {
+    let mut instruction = initialize_pool(accounts, platform_authority, platform_fee_percentage);
+    instruction
+        .accounts
+        .push(AccountMeta::new(accounts.pool_metadata(), false));
+    instruction.data = RewardPoolInstruction::InitializePool {
+        platform_fee_percentage,
+        name: Some(name.to_string()),
+        uri: Some(uri.to_string()),
+    }
+    .pack();
+    instruction
+}
+
+/// Accounts every `RecordReward` passes, before its feature accounts
+const RECORD_REWARD_ACCOUNTS: usize = 29;
+
+/// Creates a `RecordReward` instruction booking the reward as the farmer's
+/// pending reward for the task, withdrawn with `withdraw_reward` or claimed
+/// with `claim_many`. `stats_day` selects the daily stats account and must
+/// be the cluster's current unix day. The instruction passes the feature
+/// accounts its attribution and the pool's kind need; pools with milestone
+/// bonuses or a fee rebate need `milestone_recorded_reward` or
+/// `rebate_recorded_reward`, and pools with an attestor, fee discounts or
+/// a verifier need `attest_recorded_reward`, `stake_recorded_reward` or
+/// `verify_recorded_reward`. Under treasury governance, rewards above the
+/// approval threshold need `approve_recorded_reward`.
+pub fn record_reward(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    farmer: &Pubkey,
+    amount: u64,
+    task_id: String,
+    attribution: RewardAttribution,
+    stats_day: i64,
+) -> Instruction {
+    let mut instruction = Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.platform_treasury, false),
+            // Unused reward account slot
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new_readonly(accounts.reward_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            // Unused associated token account program slot
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.daily_stats(stats_day), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+            AccountMeta::new(accounts.farmer_state(farmer), false),
+            AccountMeta::new_readonly(accounts.pool_verifier(), false),
+            AccountMeta::new_readonly(accounts.pool_attestor(), false),
+            // Attestation slot, ignored unless the pool has an attestor
+            AccountMeta::new_readonly(accounts.pool_attestor(), false),
+            AccountMeta::new(accounts.task_budget(&task_id), false),
+            AccountMeta::new(accounts.budget_escrow(), false),
+            AccountMeta::new_readonly(accounts.reward_approval(), false),
+            AccountMeta::new(accounts.pending_reward(farmer, &task_id), false),
+            AccountMeta::new(accounts.platform_fee_vault(), false),
+            AccountMeta::new_readonly(accounts.fee_overrides(), false),
+            // Platform slot, ignored outside a platform namespace
+            AccountMeta::new(accounts.platform().unwrap_or(accounts.pool), false),
+            // Campaign slot, ignored without a campaign id
+            AccountMeta::new(
+                attribution
+                    .campaign_id
+                    .map_or(accounts.pool, |campaign_id| accounts.campaign(campaign_id)),
+                false,
+            ),
+            AccountMeta::new_readonly(accounts.fee_discounts(), false),
+            // Staking account slot, ignored unless the pool has fee discounts
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.reward_governor(), false),
+            AccountMeta::new(accounts.milestone_bonuses(), false),
+            AccountMeta::new(accounts.fee_rebate(), false),
+            AccountMeta::new(accounts.reward_rate(), false),
+            AccountMeta::new_readonly(accounts.treasury_governance(), false),
+        ],
+        data: RewardPoolInstruction::RecordReward {
+            amount,
+            farmer_pubkey: *farmer,
+            task_id,
+            campaign_id: attribution.campaign_id,
+            platform_id: attribution.platform_id,
+            proof_hash: attribution.proof_hash,
+            task_type: attribution.task_type,
+            secondary_amount: attribution.secondary_amount,
+            idempotency_key: attribution.idempotency_key,
+        }
+        .pack(),
+    };
+
+    if let Some(key) = attribution.idempotency_key {
+        set_reward_feature(
+            &mut instruction,
+            29,
+            AccountMeta::new(accounts.reward_receipt(&key), false),
+        );
+    }
+    if attribution.secondary_amount > 0 {
+        set_secondary_features(&mut instruction, accounts, farmer);
+    }
+    if let Some(protocol_fee_account) = accounts.protocol_fee_account() {
+        set_reward_feature(
+            &mut instruction,
+            37,
+            AccountMeta::new(accounts.factory_record(), false),
+        );
+        set_reward_feature(
+            &mut instruction,
+            38,
+            AccountMeta::new_readonly(accounts.protocol_config(), false),
+        );
+        set_reward_feature(
+            &mut instruction,
+            39,
+            AccountMeta::new(protocol_fee_account, false),
+        );
+    }
+    instruction
+}
+
+/// Sets the feature account at `index` of a `RecordReward` instruction,
+/// first passing the pool in the feature slots up to it the instruction
+/// does not reach yet. Signers added to the instruction stay after them.
+fn set_reward_feature(record_reward: &mut Instruction, index: usize, meta: AccountMeta) {
+    let pool = record_reward.accounts[1].pubkey;
+    let mut end = record_reward
+        .accounts
+        .iter()
+        .skip(RECORD_REWARD_ACCOUNTS)
+        .position(|meta| meta.is_signer)
+        .map_or(record_reward.accounts.len(), |signers| {
+            RECORD_REWARD_ACCOUNTS + signers
+        });
+    while end <= index {
+        record_reward
+            .accounts
+            .insert(end, AccountMeta::new_readonly(pool, false));
+        end += 1;
+    }
+    record_reward.accounts[index] = meta;
+}
+
+// The secondary reward, vault and balance a kicker or fee rebate books to
+fn set_secondary_features(
+    record_reward: &mut Instruction,
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+) {
+    set_reward_feature(
+        record_reward,
+        33,
+        AccountMeta::new(accounts.secondary_reward(), false),
+    );
+    set_reward_feature(
+        record_reward,
+        34,
+        AccountMeta::new(accounts.secondary_vault(), false),
+    );
+    set_reward_feature(
+        record_reward,
+        35,
+        AccountMeta::new(accounts.secondary_balance(farmer), false),
+    );
+}
+
+/// Accounts of a `RecordReward` shared by every reward of the pool, for an
+/// address lookup table: with every account inline, a `RecordReward` is
+/// larger than a legacy transaction may be. Farmer, task and day accounts
+/// stay inline.
+pub fn record_reward_lookup_addresses(accounts: &PoolAccounts) -> Vec<Pubkey> {
+    vec![
+        accounts.pool,
+        accounts.platform_treasury,
+        accounts.reward_mint,
+        spl_token::id(),
+        solana_system_interface::program::id(),
+        accounts.instruction_counters(),
+        accounts.pool_verifier(),
+        accounts.pool_attestor(),
+        accounts.budget_escrow(),
+        accounts.reward_approval(),
+        accounts.platform_fee_vault(),
+        accounts.fee_overrides(),
+        accounts.fee_discounts(),
+        accounts.reward_governor(),
+        accounts.secondary_reward(),
+        accounts.secondary_vault(),
+        accounts.milestone_bonuses(),
+        accounts.fee_rebate(),
+        accounts.rebate_vault(),
+        accounts.factory_record(),
+        accounts.protocol_config(),
+        accounts.reward_rate(),
+        accounts.treasury_governance(),
+    ]
+}
+
+/// Accounts of a `WithdrawReward` after its optional sponsor and further
+/// split destinations: the payout queue, the farmer's payout ticket, the
+/// withdrawal ramp, the interest policy, the bonus vault, the pending reward
+/// and the record of the nonce
+pub(crate) const WITHDRAW_TRAILING_ACCOUNTS: usize = 7;
+
+/// Accounts of a `WithdrawReward` up to its cosigner slot, before the
+/// optional sponsor
+pub(crate) const WITHDRAW_LEADING_ACCOUNTS: usize = 19;
+
+/// Creates a `WithdrawReward` instruction paying the farmer's pending
+/// reward for `task_id`, of `amount`, from the treasury vault to
+/// `destination`. The farmer pays for the daily stats account if it is the
+/// first withdrawal or reward of the day, for the record of `nonce`, and
+/// the pool's lamport withdrawal fee if it has one. Every withdrawal of a
+/// farmer needs its own nonce.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_reward(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    task_id: &str,
+    destination: &Pubkey,
+    amount: u64,
+    nonce: u64,
+    stats_day: i64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*farmer, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.treasury_vault(), false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new(accounts.reward_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new(accounts.daily_stats(stats_day), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+            AccountMeta::new_readonly(accounts.withdrawal_whitelist(farmer), false),
+            AccountMeta::new_readonly(accounts.parameters(), false),
+            AccountMeta::new_readonly(accounts.payout_address(farmer), false),
+            AccountMeta::new(accounts.farmer_state(farmer), false),
+            AccountMeta::new(accounts.withdrawal_history(farmer), false),
+            AccountMeta::new(accounts.withdraw_burn(), false),
+            AccountMeta::new(accounts.withdrawal_fee(), false),
+            AccountMeta::new(accounts.fee_vault(), false),
+            // Cosigner slot, see `cosigned_withdrawal`
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new_readonly(accounts.payout_queue(), false),
+            AccountMeta::new_readonly(accounts.payout_ticket(farmer), false),
+            AccountMeta::new(accounts.withdrawal_ramp(), false),
+            AccountMeta::new(accounts.interest_policy(), false),
+            AccountMeta::new(accounts.bonus_vault(), false),
+            AccountMeta::new(accounts.pending_reward(farmer, task_id), false),
+            AccountMeta::new(accounts.withdrawal_record(farmer, nonce), false),
+        ],
+        data: RewardPoolInstruction::WithdrawReward {
+            amount,
+            nonce,
+            split: None,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `WithdrawReward` instruction dividing `amount` between the
+/// destinations of `split`, whose shares must add up to `BASIS_POINTS`.
+/// With a `sponsor`, rent is paid as in `sponsored_withdraw_reward`.
+#[allow(clippy::too_many_arguments)]
+pub fn split_withdraw_reward(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    task_id: &str,
+    split: &[WithdrawalSplit],
+    amount: u64,
+    nonce: u64,
+    stats_day: i64,
+    sponsor: Option<&Pubkey>,
+) -> Instruction {
+    let first_destination = split
+        .first()
+        .map_or(Pubkey::default(), |share| share.destination);
+    let mut instruction = match sponsor {
+        Some(sponsor) => sponsored_withdraw_reward(
+            accounts,
+            farmer,
+            task_id,
+            &first_destination,
+            amount,
+            nonce,
+            stats_day,
+            sponsor,
+        ),
+        None => withdraw_reward(
+            accounts,
+            farmer,
+            task_id,
+            &first_destination,
+            amount,
+            nonce,
+            stats_day,
+        ),
+    };
+    // Further destinations follow the sponsor, before the trailing accounts
+    let trailing_index = instruction.accounts.len() - WITHDRAW_TRAILING_ACCOUNTS;
+    instruction.accounts.splice(
+        trailing_index..trailing_index,
+        split
+            .iter()
+            .skip(1)
+            .map(|share| AccountMeta::new(share.destination, false)),
+    );
+    instruction.data = RewardPoolInstruction::WithdrawReward {
+        amount,
+        nonce,
+        split: Some(split.to_vec()),
+    }
+    .pack();
+    instruction
+}
+
+/// Creates a `WithdrawReward` instruction whose rent and lamport withdrawal
+/// fee are paid by `sponsor`, typically also the fee payer, so the farmer
+/// needs no SOL. The program
+/// then only accepts a destination owned by the farmer or on their
+/// whitelist.
+#[allow(clippy::too_many_arguments)]
+pub fn sponsored_withdraw_reward(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    task_id: &str,
+    destination: &Pubkey,
+    amount: u64,
+    nonce: u64,
+    stats_day: i64,
+    sponsor: &Pubkey,
+) -> Instruction {
+    let mut instruction = withdraw_reward(
+        accounts,
+        farmer,
+        task_id,
+        destination,
+        amount,
+        nonce,
+        stats_day,
+    );
+    instruction.accounts[0] = AccountMeta::new_readonly(*farmer, true);
+    let trailing_index = instruction.accounts.len() - WITHDRAW_TRAILING_ACCOUNTS;
+    instruction
+        .accounts
+        .insert(trailing_index, AccountMeta::new(*sponsor, true));
+    instruction
+}
+
+/// Adds the farmer's withdrawal cosigner (see `FarmerState::cosigner`) to
+/// a `WithdrawReward`, `ClaimMany`, `ClaimAcrossPools`, `SetGuildSplit` or
+/// `TransferPending` instruction, as the program requires once the farmer
+/// set one; it has to sign the transaction as well
+pub fn cosigned_withdrawal(mut instruction: Instruction, cosigner: &Pubkey) -> Instruction {
+    let cosigner_index = match VersionedInstruction::unpack(&instruction.data)
+        .map(VersionedInstruction::into_latest)
+    {
+        Ok(RewardPoolInstruction::WithdrawReward { .. }) => 18,
+        Ok(RewardPoolInstruction::ClaimMany) => 20,
+        Ok(RewardPoolInstruction::ClaimAcrossPools { .. }) => 3,
+        Ok(RewardPoolInstruction::SetGuildSplit { .. }) => 8,
+        Ok(RewardPoolInstruction::TransferPending { .. }) => 10,
+        _ => panic!("the instruction takes no withdrawal cosigner"),
+    };
+    instruction.accounts[cosigner_index] = AccountMeta::new_readonly(*cosigner, true);
+    instruction
+}
+
+/// Creates an `UpdatePlatformFee` instruction
+pub fn update_platform_fee(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    new_fee_percentage: u8,
+) -> Instruction {
+    let mut instruction = admin_instruction(
+        accounts,
+        platform_authority,
+        RewardPoolInstruction::UpdatePlatformFee { new_fee_percentage },
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(accounts.parameters(), false));
+    instruction
+}
+
+/// Creates a `PausePool` instruction
+pub fn pause_pool(accounts: &PoolAccounts, platform_authority: &Pubkey) -> Instruction {
+    admin_instruction(
+        accounts,
+        platform_authority,
+        RewardPoolInstruction::PausePool,
+    )
+}
+
+/// Creates a `PausePool` instruction signed by one of the pool's guardians
+pub fn guardian_pause_pool(accounts: &PoolAccounts, guardian: &Pubkey) -> Instruction {
+    let mut instruction = pause_pool(accounts, guardian);
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(accounts.guardians(), false));
+    instruction
+}
+
+/// Creates a `ResumePool` instruction, opening the payout queue's recovery
+/// window if the pool has one
+pub fn resume_pool(accounts: &PoolAccounts, platform_authority: &Pubkey) -> Instruction {
+    let mut instruction = admin_instruction(
+        accounts,
+        platform_authority,
+        RewardPoolInstruction::ResumePool,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new(accounts.payout_queue(), false));
+    instruction
+}
+
+/// Creates a `ResumeWithRamp` instruction capping claims at
+/// `initial_epoch_cap` in the current epoch, `epoch_cap_step` more in each
+/// following one, for `ramp_epochs` epochs
+pub fn resume_with_ramp(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    initial_epoch_cap: u64,
+    epoch_cap_step: u64,
+    ramp_epochs: u16,
+) -> Instruction {
+    let mut instruction = admin_instruction(
+        accounts,
+        platform_authority,
+        RewardPoolInstruction::ResumeWithRamp {
+            initial_epoch_cap,
+            epoch_cap_step,
+            ramp_epochs,
+        },
+    );
+    instruction.accounts.extend([
+        AccountMeta::new(accounts.withdrawal_ramp(), false),
+        AccountMeta::new(accounts.payout_queue(), false),
+    ]);
+    instruction
+}
+
+/// Creates a `SetRecoveryAuthority` instruction; `Pubkey::default()` as
+/// `recovery_authority` turns the dead-man switch off
+pub fn set_recovery_authority(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    recovery_authority: &Pubkey,
+    inactivity_timeout: i64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.authority_recovery(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetRecoveryAuthority {
+            recovery_authority: *recovery_authority,
+            inactivity_timeout,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `ClaimDormantAuthority` instruction handing the pool to its
+/// recovery authority
+pub fn claim_dormant_authority(
+    accounts: &PoolAccounts,
+    recovery_authority: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*recovery_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.authority_recovery(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+            AccountMeta::new(accounts.key_history(), false),
+        ],
+        data: RewardPoolInstruction::ClaimDormantAuthority.pack(),
+    }
+}
+
+/// Creates a `SetRecoveryGuardians` instruction naming the `threshold` of
+/// `guardians` who can recover the farmer's rewards, or turning recovery off
+/// with no guardians
+pub fn set_recovery_guardians(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    guardians: &[Pubkey],
+    threshold: u8,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*farmer, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.farmer_recovery(farmer), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetRecoveryGuardians {
+            guardians: guardians.to_vec(),
+            threshold,
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `InitiateFarmerRecovery` instruction signed by `guardian`,
+/// who pays, and `other_guardians`, starting the recovery of `farmer` to
+/// `new_wallet`
+pub fn initiate_farmer_recovery(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    guardian: &Pubkey,
+    other_guardians: &[Pubkey],
+    new_wallet: &Pubkey,
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*guardian, true),
+        AccountMeta::new_readonly(accounts.pool, false),
+        AccountMeta::new(accounts.farmer_recovery(farmer), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+    ];
+    metas.extend(
+        other_guardians
+            .iter()
+            .map(|guardian| AccountMeta::new_readonly(*guardian, true)),
+    );
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::InitiateFarmerRecovery {
+            new_wallet: *new_wallet,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `CancelFarmerRecovery` instruction stopping a recovery of the
+/// farmer's rewards
+pub fn cancel_farmer_recovery(accounts: &PoolAccounts, farmer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*farmer, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.farmer_recovery(farmer), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::CancelFarmerRecovery.pack(),
+    }
+}
+
+/// Creates a `CompleteFarmerRecovery` instruction moving the farmer state and
+/// the pending rewards of `task_ids` from `farmer` to `new_wallet`
+pub fn complete_farmer_recovery(
+    accounts: &PoolAccounts,
+    payer: &Pubkey,
+    farmer: &Pubkey,
+    new_wallet: &Pubkey,
+    task_ids: &[&str],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(accounts.pool, false),
+        AccountMeta::new_readonly(accounts.farmer_recovery(farmer), false),
+        AccountMeta::new(accounts.farmer_state(farmer), false),
+        AccountMeta::new(accounts.farmer_state(new_wallet), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+    ];
+    for task_id in task_ids {
+        metas.push(AccountMeta::new(
+            accounts.pending_reward(farmer, task_id),
+            false,
+        ));
+        metas.push(AccountMeta::new(
+            accounts.pending_reward(new_wallet, task_id),
+            false,
+        ));
+    }
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::CompleteFarmerRecovery.pack(),
+    }
+}
+
+/// Creates a `GetFarmerPending` view instruction over `pending_accounts`
+pub fn get_farmer_pending(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    pending_accounts: &[Pubkey],
+    cursor: u32,
+    limit: u8,
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new_readonly(accounts.pool, false),
+        AccountMeta::new_readonly(accounts.farmer_state(farmer), false),
+    ];
+    metas.extend(
+        pending_accounts
+            .iter()
+            .map(|pending| AccountMeta::new_readonly(*pending, false)),
+    );
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::GetFarmerPending {
+            farmer: *farmer,
+            cursor,
+            limit,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `SweepDelegatedRewards` instruction draining reward accounts
+/// delegated to `custodian` into `destination`. Without address lookup
+/// tables a transaction fits about twenty reward accounts.
+pub fn sweep_delegated_rewards(
+    accounts: &PoolAccounts,
+    custodian: &Pubkey,
+    destination: &Pubkey,
+    reward_accounts: &[Pubkey],
+    stats_day: i64,
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*custodian, true),
+        AccountMeta::new(accounts.pool, false),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(accounts.daily_stats(stats_day), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+    ];
+    metas.extend(
+        reward_accounts
+            .iter()
+            .map(|reward_account| AccountMeta::new(*reward_account, false)),
+    );
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::SweepDelegatedRewards.pack(),
+    }
+}
+
+/// Creates a `ClaimMany` instruction paying the farmer's `pending_rewards`
+/// (at most `MAX_CLAIM_BATCH`) and their interest into their reward account.
+/// Farmers owed secondary rewards also need `claim_secondary_rewards`.
+pub fn claim_many(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    pending_rewards: &[Pubkey],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*farmer, true),
+        AccountMeta::new(accounts.pool, false),
+        AccountMeta::new(accounts.treasury_vault(), false),
+        AccountMeta::new(accounts.reward_account(farmer), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.claim_shard(farmer), false),
+        AccountMeta::new(accounts.farmer_state(farmer), false),
+        AccountMeta::new(accounts.interest_policy(), false),
+        AccountMeta::new(accounts.bonus_vault(), false),
+        AccountMeta::new(accounts.secondary_reward(), false),
+        AccountMeta::new(accounts.secondary_vault(), false),
+        AccountMeta::new(accounts.secondary_balance(farmer), false),
+        // Secondary token account slot, ignored unless the farmer is owed
+        // secondary rewards
+        AccountMeta::new_readonly(accounts.pool, false),
+        AccountMeta::new_readonly(accounts.payout_queue(), false),
+        AccountMeta::new_readonly(accounts.payout_ticket(farmer), false),
+        AccountMeta::new(accounts.withdrawal_ramp(), false),
+        // Guild vault slot, see `guild_split_claim`
+        AccountMeta::new_readonly(accounts.pool, false),
+        AccountMeta::new_readonly(accounts.payout_address(farmer), false),
+        AccountMeta::new_readonly(accounts.withdrawal_whitelist(farmer), false),
+        // Cosigner slot, see `cosigned_withdrawal`
+        AccountMeta::new_readonly(accounts.pool, false),
+        AccountMeta::new(accounts.withdraw_burn(), false),
+        AccountMeta::new(accounts.reward_mint, false),
+        AccountMeta::new(accounts.withdrawal_fee(), false),
+        AccountMeta::new(accounts.fee_vault(), false),
+    ];
+    metas.extend(
+        pending_rewards
+            .iter()
+            .map(|pending_reward| AccountMeta::new(*pending_reward, false)),
+    );
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::ClaimMany.pack(),
+    }
+}
+
+/// Creates a `ClaimAcrossPools` instruction paying the farmer's pending
+/// rewards of each pool in `claims`, with their interest, into the farmer's
+/// reward account of that pool's mint. The pools must be of the same program
+/// deployment.
+pub fn claim_across_pools(
+    program_id: &Pubkey,
+    farmer: &Pubkey,
+    claims: &[(PoolAccounts, Vec<Pubkey>)],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*farmer, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        // Cosigner slot, see `cosigned_withdrawal`
+        AccountMeta::new_readonly(*program_id, false),
+    ];
+    for (accounts, pending_rewards) in claims {
+        metas.extend([
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.treasury_vault(), false),
+            AccountMeta::new(accounts.reward_account(farmer), false),
+            AccountMeta::new(accounts.farmer_state(farmer), false),
+            AccountMeta::new(accounts.interest_policy(), false),
+            AccountMeta::new(accounts.bonus_vault(), false),
+            AccountMeta::new_readonly(accounts.payout_queue(), false),
+            AccountMeta::new_readonly(accounts.payout_ticket(farmer), false),
+            AccountMeta::new(accounts.withdrawal_ramp(), false),
+            AccountMeta::new_readonly(accounts.payout_address(farmer), false),
+            AccountMeta::new_readonly(accounts.withdrawal_whitelist(farmer), false),
+            AccountMeta::new(accounts.withdraw_burn(), false),
+            AccountMeta::new(accounts.reward_mint, false),
+            AccountMeta::new(accounts.withdrawal_fee(), false),
+            AccountMeta::new(accounts.fee_vault(), false),
+        ]);
+        metas.extend(
+            pending_rewards
+                .iter()
+                .map(|pending_reward| AccountMeta::new(*pending_reward, false)),
+        );
+    }
+
+    Instruction {
+        program_id: *program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::ClaimAcrossPools {
+            pending_counts: claims
+                .iter()
+                .map(|(_, pending_rewards)| pending_rewards.len() as u8)
+                .collect(),
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `AddWithdrawalDestination` instruction. The destination can
+/// be withdrawn to once the pool's whitelist activation delay has passed.
+pub fn add_withdrawal_destination(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    let mut instruction = whitelist_instruction(
+        accounts,
+        farmer,
+        RewardPoolInstruction::AddWithdrawalDestination {
+            destination: *destination,
+        },
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(accounts.parameters(), false));
+    instruction
+}
+
+/// Creates a `RemoveWithdrawalDestination` instruction
+pub fn remove_withdrawal_destination(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    whitelist_instruction(
+        accounts,
+        farmer,
+        RewardPoolInstruction::RemoveWithdrawalDestination {
+            destination: *destination,
+        },
+    )
+}
+
+/// Creates a `GetPoolStats` view instruction
+pub fn get_pool_stats(accounts: &PoolAccounts) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new_readonly(accounts.instruction_counters(), false),
+            AccountMeta::new_readonly(accounts.withdraw_burn(), false),
+        ],
+        data: RewardPoolInstruction::GetPoolStats.pack(),
+    }
+}
+
+/// Creates a `GetFeeInvoice` view instruction for invoice `number`
+pub fn get_fee_invoice(accounts: &PoolAccounts, number: u64) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new_readonly(accounts.fee_invoice(number), false),
+            AccountMeta::new_readonly(accounts.fee_invoice(number.saturating_sub(1)), false),
+        ],
+        data: RewardPoolInstruction::GetFeeInvoice { number }.pack(),
+    }
+}
+
+/// Creates a `GetRewardRate` view instruction for the last `window` seconds
+pub fn get_reward_rate(accounts: &PoolAccounts, window: i64) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new_readonly(accounts.reward_rate(), false),
+        ],
+        data: RewardPoolInstruction::GetRewardRate { window }.pack(),
+    }
+}
+
+/// Creates a `GetWithdrawalHistory` view instruction
+pub fn get_withdrawal_history(accounts: &PoolAccounts, farmer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new_readonly(accounts.withdrawal_history(farmer), false),
+        ],
+        data: RewardPoolInstruction::GetWithdrawalHistory { farmer: *farmer }.pack(),
+    }
+}
+
+/// Creates a `ConfigureTreasuryGovernance` instruction, which also creates
+/// the treasury vault
+pub fn configure_treasury_governance(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    approvers: Vec<Pubkey>,
+    required_approvals: u8,
+    approval_threshold: u64,
+    execution_delay: i64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.treasury_governance(), false),
+            AccountMeta::new_readonly(accounts.treasury_vault(), false),
+            AccountMeta::new_readonly(accounts.reward_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::ConfigureTreasuryGovernance {
+            approvers,
+            required_approvals,
+            approval_threshold,
+            execution_delay,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `ProposeTreasuryWithdrawal` instruction. `proposal_id` must be
+/// the governance's current `proposal_count`.
+pub fn propose_treasury_withdrawal(
+    accounts: &PoolAccounts,
+    proposer: &Pubkey,
+    proposal_id: u64,
+    destination: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*proposer, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.treasury_governance(), false),
+            AccountMeta::new(accounts.treasury_proposal(proposal_id), false),
+            AccountMeta::new_readonly(*destination, false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::ProposeTreasuryWithdrawal { amount }.pack(),
+    }
+}
+
+/// Creates an `ApproveTreasuryWithdrawal` instruction
+pub fn approve_treasury_withdrawal(
+    accounts: &PoolAccounts,
+    approver: &Pubkey,
+    proposal_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*approver, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new_readonly(accounts.treasury_governance(), false),
+            AccountMeta::new(accounts.treasury_proposal(proposal_id), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::ApproveTreasuryWithdrawal.pack(),
+    }
+}
+
+/// Creates an `ExecuteTreasuryWithdrawal` instruction paying out to the
+/// proposal's `destination`
+pub fn execute_treasury_withdrawal(
+    accounts: &PoolAccounts,
+    executor: &Pubkey,
+    proposal_id: u64,
+    destination: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*executor, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.treasury_governance(), false),
+            AccountMeta::new(accounts.treasury_proposal(proposal_id), false),
+            AccountMeta::new(accounts.treasury_vault(), false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::ExecuteTreasuryWithdrawal.pack(),
+    }
+}
+
+/// Creates a `SetUpgradeAuthority` instruction handing the program's
+/// upgrade authority from the pool's PDA to `new_authority`, or a
+/// `RemoveUpgradeAuthority` one when `new_authority` is `None`. Every
+/// `approvers` key must sign.
+pub fn set_upgrade_authority(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    new_authority: Option<&Pubkey>,
+    approvers: &[Pubkey],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*platform_authority, true),
+        AccountMeta::new(accounts.pool, false),
+        AccountMeta::new_readonly(accounts.treasury_governance(), false),
+        AccountMeta::new(get_program_data_address(&accounts.program_id), false),
+        AccountMeta::new_readonly(accounts.upgrade_authority(), false),
+        AccountMeta::new_readonly(solana_sdk_ids::bpf_loader_upgradeable::id(), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+    ];
+    if let Some(new_authority) = new_authority {
+        metas.push(AccountMeta::new_readonly(*new_authority, false));
+    }
+    metas.extend(
+        approvers
+            .iter()
+            .map(|approver| AccountMeta::new_readonly(*approver, true)),
+    );
+
+    let instruction = if new_authority.is_some() {
+        RewardPoolInstruction::SetUpgradeAuthority
+    } else {
+        RewardPoolInstruction::RemoveUpgradeAuthority
+    };
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: instruction.pack(),
+    }
+}
+
+/// Creates an `UpdateParameters` instruction replacing the pool's tunable
+/// limits. The platform authority and every `approvers` key must sign.
+pub fn update_parameters(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    values: ParameterValues,
+    approvers: &[Pubkey],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*platform_authority, true),
+        AccountMeta::new(accounts.pool, false),
+        AccountMeta::new_readonly(accounts.treasury_governance(), false),
+        AccountMeta::new(accounts.parameters(), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+        AccountMeta::new(accounts.parameter_history(), false),
+    ];
+    metas.extend(
+        approvers
+            .iter()
+            .map(|approver| AccountMeta::new_readonly(*approver, true)),
+    );
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::UpdateParameters { values }.pack(),
+    }
+}
+
+/// Creates a `SetGuardians` instruction replacing the keys allowed to pause
+/// the pool. An empty list revokes them all.
+pub fn set_guardians(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    guardians: &[Pubkey],
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.guardians(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetGuardians {
+            guardians: guardians.to_vec(),
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `ScheduleAction` instruction queueing `action` until `eta`.
+/// `approvers` must sign when the action requires approvals.
+pub fn schedule_action(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    action_id: u64,
+    eta: i64,
+    action: ScheduledAction,
+    approvers: &[Pubkey],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*platform_authority, true),
+        AccountMeta::new(accounts.pool, false),
+        AccountMeta::new(accounts.pending_action(action_id), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+    ];
+    if action.requires_approvals() {
+        metas.push(AccountMeta::new_readonly(
+            accounts.treasury_governance(),
+            false,
+        ));
+        metas.extend(
+            approvers
+                .iter()
+                .map(|approver| AccountMeta::new_readonly(*approver, true)),
+        );
+    }
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::ScheduleAction {
+            action_id,
+            eta,
+            action,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `CancelAction` instruction. `payer` is the account that paid
+/// for the pending action, refunded its rent.
+pub fn cancel_action(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    action_id: u64,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.pending_action(action_id), false),
+            AccountMeta::new(*payer, false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::CancelAction.pack(),
+    }
+}
+
+/// Creates an `ExecuteAction` instruction running the queued `action`,
+/// which determines the trailing accounts. `payer` is refunded the pending
+/// action's rent. For `SwapTreasury`, callers append the DEX program and
+/// the accounts of the swap.
+pub fn execute_action(
+    accounts: &PoolAccounts,
+    executor: &Pubkey,
+    action_id: u64,
+    payer: &Pubkey,
+    action: &ScheduledAction,
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*executor, true),
+        AccountMeta::new(accounts.pool, false),
+        AccountMeta::new(accounts.pending_action(action_id), false),
+        AccountMeta::new(*payer, false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+    ];
+    match action {
+        ScheduledAction::UpdatePlatformFee { .. } => {
+            metas.push(AccountMeta::new_readonly(accounts.parameters(), false));
+        }
+        ScheduledAction::UpdateParameters { .. } => {
+            metas.push(AccountMeta::new(accounts.parameters(), false));
+            metas.push(AccountMeta::new(accounts.parameter_history(), false));
+        }
+        ScheduledAction::SetPlatformTreasury { platform_treasury } => {
+            metas.push(AccountMeta::new_readonly(*platform_treasury, false));
+        }
+        ScheduledAction::SetPlatformAuthority { .. }
+        | ScheduledAction::RotatePlatformAuthority { .. } => {
+            metas.push(AccountMeta::new(accounts.key_history(), false));
+        }
+        ScheduledAction::SwapTreasury { output_mint, .. } => {
+            metas.push(AccountMeta::new(accounts.treasury_swap_policy(), false));
+            metas.push(AccountMeta::new(accounts.platform_fee_vault(), false));
+            metas.push(AccountMeta::new(
+                accounts.treasury_reserve(output_mint),
+                false,
+            ));
+            metas.push(AccountMeta::new_readonly(*output_mint, false));
+            metas.push(AccountMeta::new_readonly(spl_token::id(), false));
+        }
+    }
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::ExecuteAction.pack(),
+    }
+}
+
+/// Creates a `RollbackParameter` instruction restoring the value
+/// `parameter` had before its last change
+pub fn rollback_parameter(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    parameter: Parameter,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.parameters(), false),
+            AccountMeta::new(accounts.parameter_history(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::RollbackParameter { parameter }.pack(),
+    }
+}
+
+/// Creates a `PauseAll` instruction pausing every pool in `pools` that
+/// `platform_authority` administers
+pub fn pause_all(
+    program_id: &Pubkey,
+    platform_authority: &Pubkey,
+    pools: &[Pubkey],
+) -> Instruction {
+    let mut metas = vec![AccountMeta::new_readonly(*platform_authority, true)];
+    metas.extend(pools.iter().map(|pool| AccountMeta::new(*pool, false)));
+
+    Instruction {
+        program_id: *program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::PauseAll.pack(),
+    }
+}
+
+/// Creates a `SetFeeAll` instruction setting the fee of every pool in
+/// `pools` that `platform_authority` administers
+pub fn set_fee_all(
+    program_id: &Pubkey,
+    platform_authority: &Pubkey,
+    pools: &[Pubkey],
+    new_fee_percentage: u8,
+) -> Instruction {
+    let mut metas = vec![AccountMeta::new_readonly(*platform_authority, true)];
+    for pool in pools {
+        metas.push(AccountMeta::new(*pool, false));
+        metas.push(AccountMeta::new_readonly(
+            seeds::find_parameters_address_with_program_id(pool, program_id).0,
+            false,
+        ));
+    }
+
+    Instruction {
+        program_id: *program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::SetFeeAll { new_fee_percentage }.pack(),
+    }
+}
+
+/// Creates a `SetPoolMetadata` instruction. Empty strings clear the name or
+/// URI.
+pub fn set_pool_metadata(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    name: &str,
+    uri: &str,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.pool_metadata(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetPoolMetadata {
+            name: name.to_string(),
+            uri: uri.to_string(),
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `ExportState` instruction freezing the pool for good. Build the
+/// commitment with `migration::BalanceTree`.
+pub fn export_state(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    merkle_root: [u8; 32],
+    farmer_count: u32,
+    total_amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.state_export(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::ExportState {
+            merkle_root,
+            farmer_count,
+            total_amount,
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `ImportState` instruction booking the balance `source` (the
+/// exported pool of the previous deployment) owed a farmer as their pending
+/// reward for `ImportedBalance::TASK_ID`
+pub fn import_state(
+    accounts: &PoolAccounts,
+    source: &PoolAccounts,
+    platform_authority: &Pubkey,
+    farmer: &Pubkey,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new_readonly(source.state_export(), false),
+            AccountMeta::new(accounts.imported_balance(farmer), false),
+            AccountMeta::new_readonly(accounts.platform_treasury, false),
+            AccountMeta::new(
+                accounts.pending_reward(farmer, ImportedBalance::TASK_ID),
+                false,
+            ),
+            AccountMeta::new_readonly(accounts.reward_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::ImportState {
+            source_program_id: source.program_id,
+            farmer: *farmer,
+            amount,
+            proof,
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `ArchivePool` instruction. Farmers can withdraw until
+/// `claim_deadline` (unix timestamp); nothing else can change the pool.
+pub fn archive_pool(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    claim_deadline: i64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.pool_archive(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::ArchivePool { claim_deadline }.pack(),
+    }
+}
+
+/// Creates a `ClosePool` instruction, signed by the authority that archived
+/// the pool, which receives the rent and the lamport withdrawal fees. The
+/// tokens left in the pool's vaults are swept to `destination`, a token
+/// account of the reward mint.
+pub fn close_pool(
+    accounts: &PoolAccounts,
+    archived_by: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*archived_by, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.pool_archive(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+            AccountMeta::new(accounts.treasury_vault(), false),
+            AccountMeta::new(accounts.platform_fee_vault(), false),
+            AccountMeta::new(accounts.bonus_vault(), false),
+            AccountMeta::new(accounts.fee_vault(), false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: RewardPoolInstruction::ClosePool.pack(),
+    }
+}
+
+/// Creates a `RegisterPayoutAddress` instruction locking the farmer's
+/// withdrawals to `payout_address` after the pool's activation delay.
+/// `Pubkey::default()` unregisters.
+pub fn register_payout_address(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    payout_address: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*farmer, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.payout_address(farmer), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+            AccountMeta::new_readonly(accounts.parameters(), false),
+        ],
+        data: RewardPoolInstruction::RegisterPayoutAddress {
+            payout_address: *payout_address,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `SetCharityAccount` instruction. `charity` must be a token
+/// account of the pool's reward mint.
+pub fn set_charity_account(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    charity: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.pool_charity(), false),
+            AccountMeta::new_readonly(*charity, false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetCharityAccount.pack(),
+    }
+}
+
+/// Creates a `DonateReward` instruction forfeiting the farmer's pending
+/// reward of `task_id`. `charity` is the pool's charity token account,
+/// required when donating to `DonationTarget::Charity`.
+pub fn donate_reward(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    task_id: &str,
+    target: DonationTarget,
+    charity: Option<&Pubkey>,
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*farmer, true),
+        AccountMeta::new(accounts.pool, false),
+        AccountMeta::new(accounts.pending_reward(farmer, task_id), false),
+        AccountMeta::new(accounts.treasury_vault(), false),
+        AccountMeta::new(accounts.reward_mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+    ];
+    if let Some(charity) = charity {
+        metas.push(AccountMeta::new_readonly(accounts.pool_charity(), false));
+        metas.push(AccountMeta::new(*charity, false));
+    }
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::DonateReward { target }.pack(),
+    }
+}
+
+/// Creates a `CloseFarmerAccounts` instruction. `rent_recipient` is the
+/// payer recorded in the farmer's state (see
+/// `RewardPoolClient::get_farmer_state`), or the farmer when it has none.
+pub fn close_farmer_accounts(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    rent_recipient: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*farmer, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.reward_account(farmer), false),
+            AccountMeta::new(accounts.farmer_state(farmer), false),
+            AccountMeta::new(*rent_recipient, false),
+            AccountMeta::new(accounts.withdrawal_whitelist(farmer), false),
+            AccountMeta::new(accounts.payout_address(farmer), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::CloseFarmerAccounts.pack(),
+    }
+}
+
+/// Creates a `CloseWithdrawalRecords` instruction closing the farmer's
+/// withdrawal `records`, as fetched with
+/// `RewardPoolClient::get_withdrawal_record`, and refunding their rent to
+/// whoever paid it
+pub fn close_withdrawal_records(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    records: &[WithdrawalRecord],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*farmer, true),
+        AccountMeta::new_readonly(accounts.pool, false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+        AccountMeta::new(accounts.withdrawal_history(farmer), false),
+    ];
+    metas.extend(records.iter().flat_map(|record| {
+        [
+            AccountMeta::new(accounts.withdrawal_record(farmer, record.nonce), false),
+            AccountMeta::new(record.payer, false),
+        ]
+    }));
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::CloseWithdrawalRecords.pack(),
+    }
+}
+
+/// Creates a `SetAutoForward` instruction forwarding the farmer's future
+/// rewards to the token account `destination`, or turning auto-forward off
+/// with `None`
+pub fn set_auto_forward(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    destination: Option<&Pubkey>,
+) -> Instruction {
+    // The destination account is ignored when turning auto-forward off
+    let destination_account = destination
+        .copied()
+        .unwrap_or(accounts.reward_account(farmer));
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*farmer, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.farmer_state(farmer), false),
+            AccountMeta::new_readonly(destination_account, false),
+            AccountMeta::new_readonly(accounts.withdrawal_whitelist(farmer), false),
+            AccountMeta::new_readonly(accounts.payout_address(farmer), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetAutoForward {
+            enabled: destination.is_some(),
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `SetPendingTransferPolicy` instruction
+pub fn set_pending_transfer_policy(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    transfers_disabled: bool,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.pending_transfer_policy(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetPendingTransferPolicy { transfers_disabled }.pack(),
+    }
+}
+
+/// Creates a `TransferPending` instruction reassigning the farmer's pending
+/// reward of `task_id` to `new_farmer`
+pub fn transfer_pending(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    task_id: &str,
+    new_farmer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*farmer, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.pending_reward(farmer, task_id), false),
+            AccountMeta::new(accounts.pending_reward(new_farmer, task_id), false),
+            AccountMeta::new_readonly(accounts.pending_transfer_policy(), false),
+            AccountMeta::new_readonly(accounts.withdrawal_whitelist(farmer), false),
+            AccountMeta::new_readonly(accounts.payout_address(farmer), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+            AccountMeta::new_readonly(accounts.farmer_state(farmer), false),
+            // Cosigner slot, see `cosigned_withdrawal`
+            AccountMeta::new_readonly(accounts.pool, false),
+        ],
+        data: RewardPoolInstruction::TransferPending {
+            new_farmer: *new_farmer,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `SetAutoClaim` instruction letting anyone claim the farmer's
+/// pending rewards to their payout address once they reach `threshold`, or
+/// turning auto-claim off with 0
+pub fn set_auto_claim(accounts: &PoolAccounts, farmer: &Pubkey, threshold: u64) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*farmer, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.farmer_state(farmer), false),
+            AccountMeta::new_readonly(accounts.payout_address(farmer), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetAutoClaim { threshold }.pack(),
+    }
+}
+
+/// Creates a `CrankAutoClaim` instruction paying the farmer's
+/// `pending_rewards` (at most `MAX_CLAIM_BATCH`) to `destination`, a token
+/// account of their payout address, and the tip to `cranker_token_account`
+pub fn crank_auto_claim(
+    accounts: &PoolAccounts,
+    cranker: &Pubkey,
+    farmer: &Pubkey,
+    destination: &Pubkey,
+    cranker_token_account: &Pubkey,
+    pending_rewards: &[Pubkey],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*cranker, true),
+        AccountMeta::new(accounts.pool, false),
+        AccountMeta::new(accounts.treasury_vault(), false),
+        AccountMeta::new_readonly(*farmer, false),
+        AccountMeta::new(accounts.farmer_state(farmer), false),
+        AccountMeta::new_readonly(accounts.withdrawal_whitelist(farmer), false),
+        AccountMeta::new_readonly(accounts.payout_address(farmer), false),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new(*cranker_token_account, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.claim_shard(farmer), false),
+        AccountMeta::new(accounts.interest_policy(), false),
+        AccountMeta::new(accounts.bonus_vault(), false),
+        AccountMeta::new_readonly(accounts.payout_queue(), false),
+        AccountMeta::new_readonly(accounts.payout_ticket(farmer), false),
+        AccountMeta::new(accounts.withdrawal_ramp(), false),
+        // Guild vault slot, see `guild_split_claim`
+        AccountMeta::new_readonly(accounts.pool, false),
+        AccountMeta::new(accounts.withdraw_burn(), false),
+        AccountMeta::new(accounts.reward_mint, false),
+        AccountMeta::new(accounts.withdrawal_fee(), false),
+        AccountMeta::new(accounts.fee_vault(), false),
+    ];
+    metas.extend(
+        pending_rewards
+            .iter()
+            .map(|pending_reward| AccountMeta::new(*pending_reward, false)),
+    );
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::CrankAutoClaim.pack(),
+    }
+}
+
+/// Points a `ClaimMany` instruction at the farmer's auto-forward
+/// destination (see `RewardPoolClient::get_farmer_state`) instead of their
+/// reward account, as the program requires while auto-forward is on
+pub fn forwarded_claim(mut claim_many: Instruction, destination: &Pubkey) -> Instruction {
+    claim_many.accounts[3].pubkey = *destination;
+    claim_many
+}
+
+/// Passes the farmer's stats and the pending reward booking their next
+/// milestone bonus, given the milestones they reached so far (see
+/// `RewardPoolClient::get_farmer_stats`), to a `RecordReward` instruction,
+/// as the program requires when the pool has milestone bonuses
+pub fn milestone_recorded_reward(
+    mut record_reward: Instruction,
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    milestones_reached: u8,
+) -> Instruction {
+    set_reward_feature(
+        &mut record_reward,
+        31,
+        AccountMeta::new(accounts.farmer_stats(farmer), false),
+    );
+    set_reward_feature(
+        &mut record_reward,
+        32,
+        AccountMeta::new(
+            accounts.milestone_pending_reward(farmer, milestones_reached),
+            false,
+        ),
+    );
+    record_reward
+}
+
+/// Passes the rebate vault and the farmer's secondary accounts to a
+/// `RecordReward` instruction, as the program requires when the pool has a
+/// fee rebate (see `RewardPoolClient::get_fee_rebate`)
+pub fn rebate_recorded_reward(
+    mut record_reward: Instruction,
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+) -> Instruction {
+    set_secondary_features(&mut record_reward, accounts, farmer);
+    set_reward_feature(
+        &mut record_reward,
+        36,
+        AccountMeta::new(accounts.rebate_vault(), false),
+    );
+    record_reward
+}
+
+/// Sets the farmer's token account of the pool's secondary mint (see
+/// `RewardPoolClient::get_secondary_reward`) a `ClaimMany` instruction pays
+/// their secondary rewards to
+pub fn claim_secondary_rewards(
+    mut claim_many: Instruction,
+    secondary_account: &Pubkey,
+) -> Instruction {
+    claim_many.accounts[13] = AccountMeta::new(*secondary_account, false);
+    claim_many
+}
+
+/// Adds the farmer's guild vault (see `FarmerState::guild_vault`) to a
+/// `ClaimMany` or `CrankAutoClaim` instruction, as the program requires
+/// once the farmer set a guild split
+pub fn guild_split_claim(mut claim: Instruction, guild_vault: &Pubkey) -> Instruction {
+    claim.accounts[17] = AccountMeta::new(*guild_vault, false);
+    claim
+}
+
+/// Passes the farmer's staking account (see `FeeDiscounts::stake_address`
+/// and `RewardPoolClient::get_fee_discounts`) to a `RecordReward`
+/// instruction, as the program requires when the pool has fee discounts
+pub fn stake_recorded_reward(
+    mut record_reward: Instruction,
+    stake_account: &Pubkey,
+) -> Instruction {
+    record_reward.accounts[23].pubkey = *stake_account;
+    record_reward
+}
+
+/// Passes the task's attestation account (see
+/// `RewardPoolClient::get_attestor`) to a `RecordReward` instruction, as the
+/// program requires when the pool has an attestor
+pub fn attest_recorded_reward(mut record_reward: Instruction, attestation: &Pubkey) -> Instruction {
+    record_reward.accounts[13].pubkey = *attestation;
+    record_reward
+}
+
+/// Has a `RecordReward` instruction tag itself with the task's
+/// `clones:task:<hash>` memo (see `reward_pool::memo::task_memo`)
+pub fn memo_recorded_reward(mut record_reward: Instruction) -> Instruction {
+    set_reward_feature(
+        &mut record_reward,
+        30,
+        AccountMeta::new_readonly(spl_memo::id(), false),
+    );
+    record_reward
+}
+
+/// Adds the pool verifier's signature (see `RewardPoolClient::get_verifier`)
+/// to a `RecordReward` instruction, as the program requires when the pool
+/// has a verifier
+pub fn verify_recorded_reward(mut record_reward: Instruction, verifier: &Pubkey) -> Instruction {
+    record_reward
+        .accounts
+        .push(AccountMeta::new_readonly(*verifier, true));
+    record_reward
+}
+
+/// Adds treasury approvers' signatures to a `RecordReward` instruction, as
+/// the program requires under treasury governance for rewards above the
+/// approval threshold. A platform authority that is an approver counts
+/// without being added.
+pub fn approve_recorded_reward(
+    mut record_reward: Instruction,
+    approvers: &[Pubkey],
+) -> Instruction {
+    record_reward.accounts.extend(
+        approvers
+            .iter()
+            .map(|approver| AccountMeta::new_readonly(*approver, true)),
+    );
+    record_reward
+}
+
+/// Creates a `LockTaskBudget` instruction moving `amount` from the treasury
+/// vault into escrow for the task until `expires_at`
+pub fn lock_task_budget(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    task_id: &str,
+    amount: u64,
+    expires_at: i64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.treasury_vault(), false),
+            AccountMeta::new(accounts.budget_escrow(), false),
+            AccountMeta::new(accounts.task_budget(task_id), false),
+            AccountMeta::new_readonly(accounts.reward_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::LockTaskBudget {
+            task_id: task_id.to_string(),
+            amount,
+            expires_at,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `ReleaseUnusedBudget` instruction. `payer` is the account that
+/// locked the budget (see `RewardPoolClient::get_task_budget`).
+pub fn release_unused_budget(
+    accounts: &PoolAccounts,
+    caller: &Pubkey,
+    task_id: &str,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*caller, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.treasury_vault(), false),
+            AccountMeta::new(accounts.budget_escrow(), false),
+            AccountMeta::new(accounts.task_budget(task_id), false),
+            AccountMeta::new(*payer, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::ReleaseUnusedBudget.pack(),
+    }
+}
+
+/// Creates an `IssueVoucher` instruction moving `amount` from the treasury
+/// vault into the voucher escrow and minting the voucher's NFT to
+/// `recipient`'s associated token account
+pub fn issue_voucher(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    recipient: &Pubkey,
+    voucher_id: u64,
+    amount: u64,
+) -> Instruction {
+    let voucher_mint = accounts.voucher_mint(voucher_id);
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.treasury_vault(), false),
+            AccountMeta::new(accounts.voucher_escrow(), false),
+            AccountMeta::new(accounts.voucher(voucher_id), false),
+            AccountMeta::new(voucher_mint, false),
+            AccountMeta::new_readonly(*recipient, false),
+            AccountMeta::new(
+                get_associated_token_address(recipient, &voucher_mint),
+                false,
+            ),
+            AccountMeta::new_readonly(accounts.reward_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::IssueVoucher { voucher_id, amount }.pack(),
+    }
+}
+
+/// Creates a `RedeemVoucher` instruction burning the voucher NFT held in
+/// `holder`'s associated token account and paying its tokens to
+/// `destination`
+pub fn redeem_voucher(
+    accounts: &PoolAccounts,
+    holder: &Pubkey,
+    voucher_id: u64,
+    destination: &Pubkey,
+) -> Instruction {
+    let voucher_mint = accounts.voucher_mint(voucher_id);
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*holder, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.voucher(voucher_id), false),
+            AccountMeta::new(voucher_mint, false),
+            AccountMeta::new(get_associated_token_address(holder, &voucher_mint), false),
+            AccountMeta::new(accounts.voucher_escrow(), false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::RedeemVoucher.pack(),
+    }
+}
+
+/// Creates a `SetPayoutQueue` instruction; a `recovery_window` of 0 turns
+/// the queue off
+pub fn set_payout_queue(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    recovery_window: i64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.payout_queue(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetPayoutQueue { recovery_window }.pack(),
+    }
+}
+
+/// Creates a `TakePayoutTicket` instruction scoring the farmer's ticket by
+/// the oldest claimable reward among `pending_rewards`
+pub fn take_payout_ticket(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    pending_rewards: &[Pubkey],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*farmer, true),
+        AccountMeta::new_readonly(accounts.pool, false),
+        AccountMeta::new(accounts.payout_queue(), false),
+        AccountMeta::new(accounts.payout_ticket(farmer), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+    ];
+    metas.extend(
+        pending_rewards
+            .iter()
+            .map(|pending_reward| AccountMeta::new_readonly(*pending_reward, false)),
+    );
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::TakePayoutTicket.pack(),
+    }
+}
+
+/// Creates a `ChallengeReward` instruction locking the farmer's pending
+/// reward for the task; `challenger` puts up `CHALLENGE_BOND` lamports
+pub fn challenge_reward(
+    accounts: &PoolAccounts,
+    challenger: &Pubkey,
+    farmer: &Pubkey,
+    task_id: &str,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*challenger, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.pending_reward(farmer, task_id), false),
+            AccountMeta::new(accounts.reward_challenge(farmer, task_id), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::ChallengeReward.pack(),
+    }
+}
+
+/// Creates a `ResolveChallenge` instruction. `resolver` is the platform
+/// authority or the pool verifier, and `challenger` the account that opened
+/// the challenge (see `RewardPoolClient::get_reward_challenge`).
+pub fn resolve_challenge(
+    accounts: &PoolAccounts,
+    resolver: &Pubkey,
+    farmer: &Pubkey,
+    task_id: &str,
+    challenger: &Pubkey,
+    upheld: bool,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*resolver, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new_readonly(accounts.pool_verifier(), false),
+            AccountMeta::new(accounts.pending_reward(farmer, task_id), false),
+            AccountMeta::new(accounts.reward_challenge(farmer, task_id), false),
+            AccountMeta::new(*challenger, false),
+            AccountMeta::new(*farmer, false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::ResolveChallenge { upheld }.pack(),
+    }
+}
+
+/// Creates a `SetRewardApprover` instruction turning maker-checker mode on
+/// with `approver`, or off with `None`
+pub fn set_reward_approver(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    approver: Option<&Pubkey>,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.reward_approval(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetRewardApprover {
+            approver: approver.copied(),
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `ApproveReward` instruction making the farmer's pending
+/// reward for the task claimable
+pub fn approve_reward(
+    accounts: &PoolAccounts,
+    approver: &Pubkey,
+    farmer: &Pubkey,
+    task_id: &str,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*approver, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.reward_approval(), false),
+            AccountMeta::new(accounts.pending_reward(farmer, task_id), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::ApproveReward.pack(),
+    }
+}
+
+/// Creates a `SetTaskTypeFee` instruction charging rewards of `task_type`
+/// `fee_percentage`, or removing its override with `None`
+pub fn set_task_type_fee(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    task_type: u32,
+    fee_percentage: Option<u8>,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new_readonly(accounts.parameters(), false),
+            AccountMeta::new(accounts.fee_overrides(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetTaskTypeFee {
+            task_type,
+            fee_percentage,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `RegisterPlatform` instruction claiming `platform_id` for
+/// `operator`
+pub fn register_platform(program_id: &Pubkey, operator: &Pubkey, platform_id: u64) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*operator, true),
+            AccountMeta::new(
+                seeds::find_platform_address_with_program_id(platform_id, program_id).0,
+                false,
+            ),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        ],
+        data: RewardPoolInstruction::RegisterPlatform { platform_id }.pack(),
+    }
+}
+
+/// Creates a `SetPlatformOperator` instruction handing `platform_id` from
+/// `operator` to `new_operator`
+pub fn set_platform_operator(
+    program_id: &Pubkey,
+    operator: &Pubkey,
+    platform_id: u64,
+    new_operator: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*operator, true),
+            AccountMeta::new(
+                seeds::find_platform_address_with_program_id(platform_id, program_id).0,
+                false,
+            ),
+        ],
+        data: RewardPoolInstruction::SetPlatformOperator {
+            operator: *new_operator,
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `InitializePlatformPool` instruction for pool accounts built
+/// with `PoolAccounts::for_platform`
+pub fn initialize_platform_pool(
+    accounts: &PoolAccounts,
+    operator: &Pubkey,
+    platform_id: u64,
+    platform_fee_percentage: u8,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*operator, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new_readonly(accounts.reward_mint, false),
+            AccountMeta::new(accounts.platform_treasury, false),
+            AccountMeta::new(
+                seeds::find_platform_address_with_program_id(platform_id, &accounts.program_id).0,
+                false,
+            ),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: RewardPoolInstruction::InitializePlatformPool {
+            platform_id,
+            platform_fee_percentage,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `SetProtocolConfig` instruction, signed by the program's
+/// upgrade authority when it creates the configuration and by the current
+/// admin afterwards
+pub fn set_protocol_config(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    admin: &Pubkey,
+    treasury: &Pubkey,
+    creation_fee: u64,
+    protocol_fee_share_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*signer, true),
+            AccountMeta::new(
+                seeds::find_protocol_config_address_with_program_id(program_id).0,
+                false,
+            ),
+            AccountMeta::new_readonly(get_program_data_address(program_id), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        ],
+        data: RewardPoolInstruction::SetProtocolConfig {
+            admin: *admin,
+            treasury: *treasury,
+            creation_fee,
+            protocol_fee_share_bps,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `CreatePool` instruction for pool accounts built with
+/// `PoolAccounts::for_factory`, `creator` paying the creation fee
+pub fn create_pool(
+    accounts: &PoolAccounts,
+    creator: &Pubkey,
+    seed: u64,
+    platform_fee_percentage: u8,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*creator, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new_readonly(accounts.reward_mint, false),
+            AccountMeta::new(accounts.platform_treasury, false),
+            AccountMeta::new(accounts.factory_record(), false),
+            AccountMeta::new(accounts.protocol_config(), false),
+            AccountMeta::new(accounts.protocol_treasury.unwrap_or_default(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: RewardPoolInstruction::CreatePool {
+            seed,
+            platform_fee_percentage,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `CreateCampaign` instruction locking `budget` from the
+/// treasury vault. Rewards attributed to the campaign draw on it between
+/// `starts_at` and `ends_at`, and must be of one of `task_types` unless it
+/// is empty.
+pub fn create_campaign(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    campaign_id: u64,
+    budget: u64,
+    starts_at: i64,
+    ends_at: i64,
+    task_types: Vec<u32>,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.campaign(campaign_id), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+            AccountMeta::new(accounts.treasury_vault(), false),
+            AccountMeta::new(accounts.budget_escrow(), false),
+            AccountMeta::new_readonly(accounts.reward_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: RewardPoolInstruction::CreateCampaign {
+            campaign_id,
+            budget,
+            starts_at,
+            ends_at,
+            task_types,
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `EndCampaign` instruction closing the campaign to new rewards
+pub fn end_campaign(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    campaign_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.campaign(campaign_id), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::EndCampaign.pack(),
+    }
+}
+
+/// Creates a `FinalizeCampaign` instruction returning the unspent budget of
+/// a campaign past its end to the treasury vault. Anyone can send it.
+pub fn finalize_campaign(
+    accounts: &PoolAccounts,
+    caller: &Pubkey,
+    campaign_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*caller, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.campaign(campaign_id), false),
+            AccountMeta::new(accounts.treasury_vault(), false),
+            AccountMeta::new(accounts.budget_escrow(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::FinalizeCampaign.pack(),
+    }
+}
+
+/// Creates a `ConfigureBuyback` instruction pointing the pool's buyback
+/// crank at `dex_program`, or disabling it with `None`. The platform
+/// authority and every `approvers` key must sign.
+pub fn configure_buyback(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    dex_program: Option<Pubkey>,
+    governance_mint: &Pubkey,
+    slice_bps: u16,
+    min_output_per_million: u64,
+    approvers: &[Pubkey],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*platform_authority, true),
+        AccountMeta::new(accounts.pool, false),
+        AccountMeta::new_readonly(accounts.treasury_governance(), false),
+        AccountMeta::new(accounts.buyback(), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+        AccountMeta::new(accounts.buyback_source(), false),
+        AccountMeta::new(accounts.buyback_output(), false),
+        AccountMeta::new_readonly(accounts.reward_mint, false),
+        AccountMeta::new_readonly(*governance_mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    metas.extend(
+        approvers
+            .iter()
+            .map(|approver| AccountMeta::new_readonly(*approver, true)),
+    );
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::ConfigureBuyback {
+            dex_program,
+            governance_mint: *governance_mint,
+            slice_bps,
+            min_output_per_million,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a permissionless `RunBuyback` instruction. `swap_data` and
+/// `swap_accounts` form the DEX swap from the buyback source to the buyback
+/// output, the program signs for the source.
+pub fn run_buyback(
+    accounts: &PoolAccounts,
+    caller: &Pubkey,
+    governance_mint: &Pubkey,
+    dex_program: &Pubkey,
+    swap_data: Vec<u8>,
+    swap_accounts: &[AccountMeta],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*caller, true),
+        AccountMeta::new_readonly(accounts.pool, false),
+        AccountMeta::new(accounts.buyback(), false),
+        AccountMeta::new(accounts.platform_fee_vault(), false),
+        AccountMeta::new(accounts.buyback_source(), false),
+        AccountMeta::new(accounts.buyback_output(), false),
+        AccountMeta::new(*governance_mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+        AccountMeta::new_readonly(*dex_program, false),
+    ];
+    // The program signs for the source, the transaction must not
+    metas.extend(swap_accounts.iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != accounts.buyback_source(),
+        ..meta.clone()
+    }));
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::RunBuyback { swap_data }.pack(),
+    }
+}
+
+/// Creates a `SetTreasurySwapPolicy` instruction allowing queued
+/// `SwapTreasury` actions to swap up to `epoch_limit_bps` of the platform
+/// fee vault per epoch into `stable_mints` through `dex_program`, or disabling
+/// them with `None`. The platform authority and every `approvers` key must
+/// sign.
+pub fn set_treasury_swap_policy(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    dex_program: Option<Pubkey>,
+    stable_mints: Vec<Pubkey>,
+    epoch_limit_bps: u16,
+    approvers: &[Pubkey],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*platform_authority, true),
+        AccountMeta::new(accounts.pool, false),
+        AccountMeta::new_readonly(accounts.treasury_governance(), false),
+        AccountMeta::new(accounts.treasury_swap_policy(), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+    ];
+    metas.extend(
+        approvers
+            .iter()
+            .map(|approver| AccountMeta::new_readonly(*approver, true)),
+    );
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::SetTreasurySwapPolicy {
+            dex_program,
+            stable_mints,
+            epoch_limit_bps,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `SetFeeDiscounts` instruction waiving `discounts_bps[tier]` of
+/// the platform fee for farmers whose `[stake_seed, farmer]` account of
+/// `staking_program` holds `tier` at `tier_offset`, or removing the
+/// discounts with `None`
+pub fn set_fee_discounts(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    staking_program: Option<Pubkey>,
+    stake_seed: Vec<u8>,
+    tier_offset: u16,
+    discounts_bps: Vec<u16>,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.fee_discounts(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetFeeDiscounts {
+            staking_program,
+            stake_seed,
+            tier_offset,
+            discounts_bps,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `SetInterestPolicy` instruction letting unclaimed rewards
+/// accrue `rate_bps` a year for up to `max_accrual_secs`, or turning
+/// interest off with a rate of 0. The first call creates the bonus vault.
+pub fn set_interest_policy(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    reward_mint: &Pubkey,
+    rate_bps: u16,
+    max_accrual_secs: i64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.interest_policy(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+            AccountMeta::new(accounts.bonus_vault(), false),
+            AccountMeta::new_readonly(*reward_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: RewardPoolInstruction::SetInterestPolicy {
+            rate_bps,
+            max_accrual_secs,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `SetWithdrawBurn` instruction burning `withdraw_burn_bps` of
+/// every withdrawal and claim, or stopping the burn with 0
+pub fn set_withdraw_burn(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    withdraw_burn_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.withdraw_burn(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetWithdrawBurn { withdraw_burn_bps }.pack(),
+    }
+}
+
+/// Creates a `SetWithdrawalFee` instruction charging `fee_lamports` to
+/// every withdrawal and claim, except those below `small_claim_threshold`
+/// with `exempt_small_claims`, or stopping the fee with 0
+pub fn set_withdrawal_fee(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    fee_lamports: u64,
+    exempt_small_claims: bool,
+    small_claim_threshold: u64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.withdrawal_fee(), false),
+            AccountMeta::new(accounts.fee_vault(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetWithdrawalFee {
+            fee_lamports,
+            exempt_small_claims,
+            small_claim_threshold,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `CollectWithdrawalFees` instruction moving the lamports
+/// collected in the pool's fee vault to `destination`
+pub fn collect_withdrawal_fees(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.fee_vault(), false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::CollectWithdrawalFees.pack(),
+    }
+}
+
+/// Creates a `SetWithdrawalCosigner` instruction requiring `cosigner`'s
+/// signature on the farmer's withdrawals, or removing it with
+/// `Pubkey::default()`. `cosigner_signer` is the new cosigner, or the
+/// current one to remove it at once; `None` schedules a delayed removal.
+pub fn set_withdrawal_cosigner(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    cosigner: &Pubkey,
+    cosigner_signer: Option<&Pubkey>,
+) -> Instruction {
+    let cosigner_meta = match cosigner_signer {
+        Some(signer) => AccountMeta::new_readonly(*signer, true),
+        None => AccountMeta::new_readonly(accounts.pool, false),
+    };
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*farmer, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.farmer_state(farmer), false),
+            cosigner_meta,
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetWithdrawalCosigner {
+            cosigner: *cosigner,
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `ExportSnapshot` instruction copying the pool's accounts and
+/// its stats of `stats_days` into `buffer`. Create the buffer first, owned
+/// by the program and `PoolSnapshot::full_len(stats_days.len())` bytes long.
+pub fn export_snapshot(
+    accounts: &PoolAccounts,
+    exporter: &Pubkey,
+    buffer: &Pubkey,
+    stats_days: &[i64],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new_readonly(*exporter, true),
+        AccountMeta::new_readonly(accounts.pool, false),
+        AccountMeta::new(*buffer, true),
+        AccountMeta::new_readonly(accounts.parameters(), false),
+        AccountMeta::new_readonly(accounts.instruction_counters(), false),
+    ];
+    metas.extend(
+        stats_days
+            .iter()
+            .map(|day| AccountMeta::new_readonly(accounts.daily_stats(*day), false)),
+    );
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::ExportSnapshot.pack(),
+    }
+}
+
+/// Creates a `SetGuildSplit` instruction paying `guild_share_bps` of the
+/// farmer's claims to `guild_vault`, or removing the split with 0 and no
+/// vault
+pub fn set_guild_split(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    guild_vault: Option<&Pubkey>,
+    guild_share_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*farmer, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.farmer_state(farmer), false),
+            AccountMeta::new_readonly(*guild_vault.unwrap_or(&accounts.pool), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+            AccountMeta::new_readonly(accounts.payout_address(farmer), false),
+            AccountMeta::new_readonly(accounts.withdrawal_whitelist(farmer), false),
+            // Cosigner slot, see `cosigned_withdrawal`
+            AccountMeta::new_readonly(accounts.pool, false),
+        ],
+        data: RewardPoolInstruction::SetGuildSplit { guild_share_bps }.pack(),
+    }
+}
+
+/// Creates a `SetGuildMembers` instruction setting the members of the
+/// authority's guild and their shares, creating the guild on first use
+pub fn set_guild_members(
+    accounts: &PoolAccounts,
+    authority: &Pubkey,
+    members: &[GuildMember],
+) -> Instruction {
+    let guild = accounts.guild(authority);
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(guild, false),
+            AccountMeta::new(accounts.guild_vault(&guild), false),
+            AccountMeta::new_readonly(accounts.reward_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetGuildMembers {
+            members: members.to_vec(),
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `LeaveGuild` instruction removing `member` from `guild`
+pub fn leave_guild(accounts: &PoolAccounts, member: &Pubkey, guild: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*member, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(*guild, false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::LeaveGuild.pack(),
+    }
+}
+
+/// Creates a `DistributeGuildRewards` instruction paying `guild`'s vault out
+/// to `member_accounts`, the members' token accounts in the guild's member
+/// order
+pub fn distribute_guild_rewards(
+    accounts: &PoolAccounts,
+    payer: &Pubkey,
+    guild: &Pubkey,
+    member_accounts: &[Pubkey],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(accounts.pool, false),
+        AccountMeta::new(*guild, false),
+        AccountMeta::new(accounts.guild_vault(guild), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+    ];
+    metas.extend(
+        member_accounts
+            .iter()
+            .map(|member_account| AccountMeta::new(*member_account, false)),
+    );
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::DistributeGuildRewards.pack(),
+    }
+}
+
+/// Creates a `CloseFeeInvoice` instruction closing invoice `number`, the
+/// fee ledger's `invoice_count`
+pub fn close_fee_invoice(accounts: &PoolAccounts, payer: &Pubkey, number: u64) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.fee_ledger(), false),
+            AccountMeta::new(accounts.fee_invoice(number), false),
+            AccountMeta::new_readonly(accounts.withdrawal_fee(), false),
+            AccountMeta::new_readonly(accounts.withdraw_burn(), false),
+            AccountMeta::new_readonly(accounts.buyback(), false),
+            AccountMeta::new_readonly(accounts.fee_rebate(), false),
+            AccountMeta::new_readonly(accounts.factory_record(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::CloseFeeInvoice.pack(),
+    }
+}
+
+/// Creates a `ConsolidateClaimShards` instruction consolidating all of the
+/// pool's claim shards
+pub fn consolidate_claim_shards(accounts: &PoolAccounts, payer: &Pubkey) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(accounts.pool, false),
+        AccountMeta::new(accounts.claim_ledger(), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+    ];
+    metas.extend(
+        (0..seeds::CLAIM_SHARD_COUNT)
+            .map(|shard| AccountMeta::new(accounts.claim_shard_at(shard), false)),
+    );
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::ConsolidateClaimShards.pack(),
+    }
+}
+
+/// Creates a `FundTreasury` instruction moving `amount` from the funder's
+/// `source` token account into the pool's treasury vault
+pub fn fund_treasury(
+    accounts: &PoolAccounts,
+    funder: &Pubkey,
+    source: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*funder, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.treasury_vault(), false),
+            AccountMeta::new(*source, false),
+            AccountMeta::new_readonly(accounts.reward_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::FundTreasury { amount }.pack(),
+    }
+}
+
+/// Creates a `SetRewardGovernor` instruction scaling recorded rewards
+/// between `floor_bps` and `ceiling_bps` while the platform treasury's
+/// runway is below `min_runway_days`, or turning the governor off with 0
+pub fn set_reward_governor(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    min_runway_days: u32,
+    floor_bps: u16,
+    ceiling_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.reward_governor(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetRewardGovernor {
+            min_runway_days,
+            floor_bps,
+            ceiling_bps,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `SetSecondaryMint` instruction paying secondary rewards of the
+/// pool in `secondary_mint`, which cannot change afterwards
+pub fn set_secondary_mint(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    secondary_mint: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.secondary_reward(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+            AccountMeta::new(accounts.secondary_vault(), false),
+            AccountMeta::new_readonly(*secondary_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: RewardPoolInstruction::SetSecondaryMint.pack(),
+    }
+}
+
+/// Creates a `SetMilestoneBonuses` instruction booking farmers each bonus
+/// as their lifetime earnings cross its threshold, or turning milestone
+/// bonuses off with none
+pub fn set_milestone_bonuses(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    milestones: Vec<Milestone>,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.milestone_bonuses(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetMilestoneBonuses { milestones }.pack(),
+    }
+}
+
+/// Creates a `SetFeeRebate` instruction rebating `rebate_bps` of each
+/// platform fee to the farmer in `governance_mint`, the pool's secondary
+/// mint, at `rate_per_million` governance tokens per million reward tokens
+pub fn set_fee_rebate(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    governance_mint: &Pubkey,
+    rebate_bps: u16,
+    rate_per_million: u64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.fee_rebate(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+            AccountMeta::new_readonly(accounts.secondary_reward(), false),
+            AccountMeta::new(accounts.rebate_vault(), false),
+            AccountMeta::new_readonly(*governance_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: RewardPoolInstruction::SetFeeRebate {
+            rebate_bps,
+            rate_per_million,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `SetProtocolLiquidity` instruction contributing `pol_bps` of
+/// the pool's fees as liquidity of `pair_mint` through `amm_programs`, or
+/// disabling contributions with none. The platform authority and every
+/// `approvers` key must sign.
+#[allow(clippy::too_many_arguments)]
+pub fn set_protocol_liquidity(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    amm_programs: Vec<Pubkey>,
+    pair_mint: &Pubkey,
+    lp_mint: &Pubkey,
+    pol_bps: u16,
+    min_lp_per_million: u64,
+    approvers: &[Pubkey],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*platform_authority, true),
+        AccountMeta::new(accounts.pool, false),
+        AccountMeta::new_readonly(accounts.treasury_governance(), false),
+        AccountMeta::new(accounts.protocol_liquidity(), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+        AccountMeta::new(accounts.pol_vault(), false),
+        AccountMeta::new(accounts.pol_pair_vault(), false),
+        AccountMeta::new(accounts.pol_lp_vault(), false),
+        AccountMeta::new_readonly(accounts.reward_mint, false),
+        AccountMeta::new_readonly(*pair_mint, false),
+        AccountMeta::new_readonly(*lp_mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    metas.extend(
+        approvers
+            .iter()
+            .map(|approver| AccountMeta::new_readonly(*approver, true)),
+    );
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::SetProtocolLiquidity {
+            amm_programs,
+            pol_bps,
+            min_lp_per_million,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a permissionless `ContributeLiquidity` instruction. `swap_data`
+/// and `swap_accounts` form the AMM swap from the POL vault to the POL pair
+/// vault, left empty to deposit without swapping, and `deposit_data` and
+/// `deposit_accounts` the deposit of both vaults into the AMM pool, minting
+/// to the POL LP vault. The program signs for both vaults.
+pub fn contribute_liquidity(
+    accounts: &PoolAccounts,
+    caller: &Pubkey,
+    amm_program: &Pubkey,
+    swap_data: Vec<u8>,
+    swap_accounts: &[AccountMeta],
+    deposit_data: Vec<u8>,
+    deposit_accounts: &[AccountMeta],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*caller, true),
+        AccountMeta::new_readonly(accounts.pool, false),
+        AccountMeta::new(accounts.protocol_liquidity(), false),
+        AccountMeta::new(accounts.platform_fee_vault(), false),
+        AccountMeta::new(accounts.pol_vault(), false),
+        AccountMeta::new(accounts.pol_pair_vault(), false),
+        AccountMeta::new(accounts.pol_lp_vault(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+        AccountMeta::new_readonly(*amm_program, false),
+    ];
+    // The program signs for the vaults, the transaction must not
+    let signed = [accounts.pol_vault(), accounts.pol_pair_vault()];
+    metas.extend(
+        swap_accounts
+            .iter()
+            .chain(deposit_accounts)
+            .map(|meta| AccountMeta {
+                is_signer: meta.is_signer && !signed.contains(&meta.pubkey),
+                ..meta.clone()
+            }),
+    );
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::ContributeLiquidity {
+            swap_data,
+            swap_account_count: swap_accounts.len() as u8,
+            deposit_data,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `SetAttestor` instruction requiring attestations of
+/// `attestor_program`, read at `data_offset` of its accounts, or removing
+/// the attestor with `None`
+pub fn set_attestor(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    attestor_program: Option<&Pubkey>,
+    data_offset: u16,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.pool_attestor(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: RewardPoolInstruction::SetAttestor {
+            attestor_program: attestor_program.copied(),
+            data_offset,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `SetVerifier` instruction. Replacing or removing a verifier
+/// needs the signature of `current_verifier`.
+pub fn set_verifier(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    verifier: Option<&Pubkey>,
+    current_verifier: Option<&Pubkey>,
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*platform_authority, true),
+        AccountMeta::new(accounts.pool, false),
+        AccountMeta::new(accounts.pool_verifier(), false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+    ];
+    if let Some(current_verifier) = current_verifier {
+        metas.push(AccountMeta::new_readonly(*current_verifier, true));
+    }
+
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: metas,
+        data: RewardPoolInstruction::SetVerifier {
+            verifier: verifier.copied(),
+        }
+        .pack(),
+    }
+}
+
+fn admin_instruction(
+    accounts: &PoolAccounts,
+    platform_authority: &Pubkey,
+    instruction: RewardPoolInstruction,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*platform_authority, true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        ],
+        data: instruction.pack(),
+    }
+}
+
+fn whitelist_instruction(
+    accounts: &PoolAccounts,
+    farmer: &Pubkey,
+    instruction: RewardPoolInstruction,
+) -> Instruction {
+    Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(*farmer, true),
+            AccountMeta::new_readonly(accounts.pool, false),
+            AccountMeta::new(accounts.withdrawal_whitelist(farmer), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new(accounts.instruction_counters(), false),
+        ],
+        data: instruction.pack(),
+    }
+}
@@ -0,0 +1,37 @@
+//! Rust client for the reward pool program.
+//!
+//! [`instructions`] builds individual program instructions and [`TxBuilder`]
+//! composes them with the compute budget, account setup and memo
+//! instructions integrators usually need around them. [`RewardPoolClient`]
+//! adds RPC-backed flows such as [`RewardPoolClient::withdraw_with_retry`].
+
+pub mod batch;
+pub mod client;
+pub mod error;
+pub mod instructions;
+pub mod migration;
+#[cfg(test)]
+mod mock_rpc;
+pub mod nonce;
+pub mod partial;
+pub mod priority_fee;
+pub mod snapshot;
+pub mod summary;
+pub mod tx_builder;
+pub mod versioned;
+pub mod views;
+pub mod withdraw;
+
+pub use batch::{BatchConfig, BatchReport, LookupTableStrategy, RewardEntry};
+pub use client::RewardPoolClient;
+pub use error::RewardPoolClientError;
+pub use instructions::{PoolAccounts, RewardAttribution};
+pub use migration::BalanceTree;
+pub use nonce::DurableNonce;
+pub use partial::{PreparedTransaction, WithdrawalIntent};
+pub use priority_fee::PriorityFeeConfig;
+pub use reward_pool::{self, seeds};
+pub use snapshot::{diff_snapshots, fetch_snapshot, AccountSnapshot, SnapshotDiff};
+pub use summary::{summarize_instruction, summarize_message, MessageSummary};
+pub use tx_builder::TxBuilder;
+pub use withdraw::{RetryConfig, WithdrawFailure, WithdrawOutcome, WithdrawProblem};
@@ -0,0 +1,5 @@
+// Farmer balance tree committed to by `ExportState`
+//
+// The tree lives in the `reward-pool-merkle-utils` crate, next to the tools
+// building roots and proofs from allocation files.
+pub use reward_pool_merkle_utils::BalanceTree;
@@ -0,0 +1,50 @@
+//! Canned RPC responses for unit tests of the RPC-backed flows
+
+use std::sync::Arc;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde_json::{json, Value};
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client::{mock_sender::MocksMap, nonblocking::rpc_client::RpcClient};
+use solana_rpc_client_api::request::RpcRequest;
+
+use crate::{client::RewardPoolClient, instructions::PoolAccounts};
+
+/// Accounts of the pool every test client is bound to
+pub fn pool_accounts() -> PoolAccounts {
+    PoolAccounts::for_mint(Pubkey::new_from_array([1; 32]))
+}
+
+/// Client answering the queued `mocks` in order, per request, and the mock
+/// sender's defaults once they run out. `url` picks the defaults' flavour,
+/// e.g. "sig_not_found" for transactions that never land.
+pub fn client(url: &str, mocks: Vec<(RpcRequest, Value)>) -> RewardPoolClient {
+    let mocks: MocksMap = mocks.into_iter().collect();
+    let rpc = RpcClient::new_mock_with_mocks_map(url.to_string(), mocks);
+    RewardPoolClient::new(Arc::new(rpc), pool_accounts())
+}
+
+/// `getAccountInfo` response for an account owned by `owner`
+pub fn account(owner: &Pubkey, data: &[u8]) -> Value {
+    json!({
+        "context": { "slot": 1 },
+        "value": {
+            "lamports": 1_000_000,
+            "data": [BASE64_STANDARD.encode(data), "base64"],
+            "owner": owner.to_string(),
+            "executable": false,
+            "rentEpoch": 0,
+            "space": data.len(),
+        },
+    })
+}
+
+/// `getAccountInfo` response for an account that does not exist
+pub fn no_account() -> Value {
+    json!({ "context": { "slot": 1 }, "value": null })
+}
+
+/// Response wrapping `value` with a context, e.g. for `isBlockhashValid`
+pub fn with_context(value: Value) -> Value {
+    json!({ "context": { "slot": 1 }, "value": value })
+}
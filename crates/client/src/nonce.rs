@@ -0,0 +1,281 @@
+use solana_nonce::{
+    state::{Data as NonceData, State as NonceState},
+    versions::Versions as NonceVersions,
+};
+use solana_program::{hash::Hash, instruction::Instruction, message::Message, pubkey::Pubkey};
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_system_interface::instruction as system_instruction;
+use solana_transaction::Transaction;
+
+use crate::{client::RewardPoolClient, error::RewardPoolClientError};
+
+// Durable nonce a transaction is built against
+//
+// The transaction's blockhash is the nonce value instead of a recent
+// blockhash, so it can be signed offline and submitted any time later, until
+// the nonce is advanced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurableNonce {
+    /// Nonce account
+    pub account: Pubkey,
+    /// Nonce authority, signs the `AdvanceNonceAccount` instruction
+    pub authority: Pubkey,
+}
+
+impl DurableNonce {
+    pub fn new(account: Pubkey, authority: Pubkey) -> Self {
+        Self { account, authority }
+    }
+
+    /// Instruction that has to come first in every transaction using the nonce
+    pub fn advance_instruction(&self) -> Instruction {
+        system_instruction::advance_nonce_account(&self.account, &self.authority)
+    }
+}
+
+/// Instructions creating and initializing a nonce account funded with
+/// `lamports`, which must cover its rent exemption. `nonce_account` signs.
+pub fn create_nonce_account(
+    payer: &Pubkey,
+    nonce_account: &Pubkey,
+    authority: &Pubkey,
+    lamports: u64,
+) -> Vec<Instruction> {
+    system_instruction::create_nonce_account(payer, nonce_account, authority, lamports)
+}
+
+/// Instruction handing the nonce account over to a new authority
+pub fn authorize_nonce_account(
+    nonce_account: &Pubkey,
+    authority: &Pubkey,
+    new_authority: &Pubkey,
+) -> Instruction {
+    system_instruction::authorize_nonce_account(nonce_account, authority, new_authority)
+}
+
+/// Instruction withdrawing lamports from the nonce account; withdrawing the
+/// whole balance closes it
+pub fn withdraw_nonce_account(
+    nonce_account: &Pubkey,
+    authority: &Pubkey,
+    to: &Pubkey,
+    lamports: u64,
+) -> Instruction {
+    system_instruction::withdraw_nonce_account(nonce_account, authority, to, lamports)
+}
+
+/// Decodes initialized nonce account data
+pub fn nonce_data(data: &[u8]) -> Option<NonceData> {
+    match bincode::deserialize::<NonceVersions>(data).ok()?.state() {
+        NonceState::Initialized(data) => Some(data.clone()),
+        NonceState::Uninitialized => None,
+    }
+}
+
+impl RewardPoolClient {
+    /// Fetches and decodes a nonce account
+    pub async fn get_nonce_data(
+        &self,
+        nonce_account: &Pubkey,
+    ) -> Result<NonceData, RewardPoolClientError> {
+        let account = self
+            .rpc()
+            .get_account_with_commitment(nonce_account, self.rpc().commitment())
+            .await?
+            .value
+            .ok_or(RewardPoolClientError::AccountNotFound(*nonce_account))?;
+
+        if account.owner != solana_system_interface::program::id() {
+            return Err(RewardPoolClientError::InvalidAccountData(*nonce_account));
+        }
+        nonce_data(&account.data).ok_or(RewardPoolClientError::InvalidAccountData(*nonce_account))
+    }
+
+    /// Current nonce value, to pass as the blockhash of a transaction built
+    /// with `TxBuilder::with_durable_nonce`
+    pub async fn get_nonce(&self, nonce_account: &Pubkey) -> Result<Hash, RewardPoolClientError> {
+        Ok(self.get_nonce_data(nonce_account).await?.blockhash())
+    }
+
+    /// Creates a rent-exempt nonce account controlled by `authority`
+    pub async fn create_nonce_account(
+        &self,
+        payer: &dyn Signer,
+        nonce_account: &dyn Signer,
+        authority: &Pubkey,
+    ) -> Result<Signature, RewardPoolClientError> {
+        let lamports = self
+            .rpc()
+            .get_minimum_balance_for_rent_exemption(NonceState::size())
+            .await?;
+        let instructions = create_nonce_account(
+            &payer.pubkey(),
+            &nonce_account.pubkey(),
+            authority,
+            lamports,
+        );
+
+        let blockhash = self.rpc().get_latest_blockhash().await?;
+        let message = Message::new(&instructions, Some(&payer.pubkey()));
+        let mut transaction = Transaction::new_unsigned(message);
+        transaction.try_sign(&[payer, nonce_account], blockhash)?;
+        Ok(self
+            .rpc()
+            .send_and_confirm_transaction(&transaction)
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_nonce::state::DurableNonce as NonceValue;
+    use solana_rpc_client_api::request::RpcRequest;
+    use solana_system_interface::instruction::SystemInstruction;
+
+    use super::*;
+    use crate::{
+        mock_rpc::{account, client, no_account, pool_accounts},
+        tx_builder::TxBuilder,
+    };
+
+    fn nonce_account_data(authority: &Pubkey, blockhash: &Hash) -> Vec<u8> {
+        let state =
+            NonceState::new_initialized(authority, NonceValue::from_blockhash(blockhash), 5_000);
+        bincode::serialize(&NonceVersions::new(state)).unwrap()
+    }
+
+    fn uninitialized_nonce_account_data() -> Vec<u8> {
+        bincode::serialize(&NonceVersions::new(NonceState::Uninitialized)).unwrap()
+    }
+
+    #[test]
+    fn nonce_data_decodes_only_initialized_accounts() {
+        let authority = Pubkey::new_unique();
+        let blockhash = Hash::new_from_array([7; 32]);
+
+        let data = nonce_data(&nonce_account_data(&authority, &blockhash)).unwrap();
+        assert_eq!(data.authority, authority);
+        assert_eq!(
+            data.blockhash(),
+            *NonceValue::from_blockhash(&blockhash).as_hash()
+        );
+        assert_eq!(nonce_data(&uninitialized_nonce_account_data()), None);
+        assert_eq!(nonce_data(&[1, 2, 3]), None);
+        assert_eq!(nonce_data(&[]), None);
+    }
+
+    #[tokio::test]
+    async fn get_nonce_returns_the_stored_nonce_value() {
+        let nonce_account = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let blockhash = Hash::new_from_array([7; 32]);
+        let data = nonce_account_data(&authority, &blockhash);
+        let client = client(
+            "succeeds",
+            vec![(
+                RpcRequest::GetAccountInfo,
+                account(&solana_system_interface::program::id(), &data),
+            )],
+        );
+
+        assert_eq!(
+            client.get_nonce(&nonce_account).await.unwrap(),
+            *NonceValue::from_blockhash(&blockhash).as_hash()
+        );
+    }
+
+    #[tokio::test]
+    async fn an_account_the_system_program_does_not_own_is_not_a_nonce_account() {
+        let nonce_account = Pubkey::new_unique();
+        // Nonce-shaped data, but in an account of another program
+        let data = nonce_account_data(&Pubkey::new_unique(), &Hash::new_from_array([7; 32]));
+        let client = client(
+            "succeeds",
+            vec![(
+                RpcRequest::GetAccountInfo,
+                account(&reward_pool::id(), &data),
+            )],
+        );
+
+        assert!(matches!(
+            client.get_nonce_data(&nonce_account).await,
+            Err(RewardPoolClientError::InvalidAccountData(address)) if address == nonce_account
+        ));
+    }
+
+    #[tokio::test]
+    async fn an_uninitialized_nonce_account_has_no_nonce() {
+        let nonce_account = Pubkey::new_unique();
+        let client = client(
+            "succeeds",
+            vec![(
+                RpcRequest::GetAccountInfo,
+                account(
+                    &solana_system_interface::program::id(),
+                    &uninitialized_nonce_account_data(),
+                ),
+            )],
+        );
+
+        assert!(matches!(
+            client.get_nonce(&nonce_account).await,
+            Err(RewardPoolClientError::InvalidAccountData(address)) if address == nonce_account
+        ));
+    }
+
+    #[tokio::test]
+    async fn a_missing_nonce_account_is_not_found() {
+        let nonce_account = Pubkey::new_unique();
+        let client = client("succeeds", vec![(RpcRequest::GetAccountInfo, no_account())]);
+
+        assert!(matches!(
+            client.get_nonce(&nonce_account).await,
+            Err(RewardPoolClientError::AccountNotFound(address)) if address == nonce_account
+        ));
+    }
+
+    #[test]
+    fn the_nonce_is_advanced_first_and_its_value_is_the_blockhash() {
+        let payer = Pubkey::new_unique();
+        let nonce = DurableNonce::new(Pubkey::new_unique(), Pubkey::new_unique());
+        let nonce_value = Hash::new_from_array([9; 32]);
+
+        let message = TxBuilder::new(pool_accounts(), payer)
+            .with_compute_unit_limit(200_000)
+            .with_durable_nonce(nonce)
+            .withdraw("task-1", 1_000, 1)
+            .build(nonce_value);
+
+        assert_eq!(message.recent_blockhash, nonce_value);
+        let advance = &message.instructions[0];
+        assert_eq!(
+            *advance.program_id(&message.account_keys),
+            solana_system_interface::program::id()
+        );
+        assert_eq!(
+            bincode::deserialize::<SystemInstruction>(&advance.data).unwrap(),
+            SystemInstruction::AdvanceNonceAccount
+        );
+        assert_eq!(
+            message.account_keys[advance.accounts[0] as usize],
+            nonce.account
+        );
+        assert_eq!(
+            message.account_keys[advance.accounts[2] as usize],
+            nonce.authority
+        );
+        assert!(message.is_signer(advance.accounts[2] as usize));
+    }
+
+    #[test]
+    fn no_nonce_advance_without_a_durable_nonce() {
+        let message = TxBuilder::new(pool_accounts(), Pubkey::new_unique())
+            .withdraw("task-1", 1_000, 1)
+            .build(Hash::new_from_array([9; 32]));
+
+        assert!(message.instructions.iter().all(|instruction| {
+            *instruction.program_id(&message.account_keys) != solana_system_interface::program::id()
+        }));
+    }
+}
@@ -0,0 +1,505 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use reward_pool::instruction::{v2::RewardPoolInstruction, VersionedInstruction};
+use solana_program::{instruction::CompiledInstruction, pubkey::Pubkey};
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::{client::RewardPoolClient, error::RewardPoolClientError, instructions::PoolAccounts};
+
+// Withdrawal signed by the platform as fee payer, waiting for the farmer
+//
+// The blockhash is fixed when the transaction is prepared: the farmer
+// countersigns exactly the message the platform signed, and both sides know
+// it expires after `last_valid_block_height`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreparedTransaction {
+    pub transaction: Transaction,
+    /// Last block height at which the transaction can land
+    pub last_valid_block_height: u64,
+}
+
+impl PreparedTransaction {
+    /// Wire-format transaction as base64, the encoding wallets accept
+    pub fn encode(&self) -> String {
+        encode_transaction(&self.transaction)
+    }
+}
+
+/// Serializes a (partially) signed transaction as base64
+pub fn encode_transaction(transaction: &Transaction) -> String {
+    BASE64.encode(bincode::serialize(transaction).expect("transaction serialization is infallible"))
+}
+
+/// Parses a base64 transaction produced by `encode_transaction`
+pub fn decode_transaction(encoded: &str) -> Option<Transaction> {
+    let bytes = BASE64.decode(encoded).ok()?;
+    bincode::deserialize(&bytes).ok()
+}
+
+// What the farmer agreed to withdraw, checked before countersigning
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WithdrawalIntent {
+    pub accounts: PoolAccounts,
+    pub farmer: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+// Reason a prepared transaction does not match the farmer's intent
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum VerificationIssue {
+    #[error("Instruction {index} calls unexpected program {program_id}")]
+    UnexpectedProgram { index: usize, program_id: Pubkey },
+    #[error("Instruction {index} could not be decoded")]
+    UndecodableInstruction { index: usize },
+    #[error("Transaction contains {count} withdrawals, expected exactly one")]
+    WithdrawalCount { count: usize },
+    #[error("Withdrawal of {actual} instead of {expected}")]
+    WrongAmount { expected: u64, actual: u64 },
+    #[error("Withdrawal account {name} is {actual} instead of {expected}")]
+    WrongAccount {
+        name: &'static str,
+        expected: Pubkey,
+        actual: Pubkey,
+    },
+    #[error("Instruction {index} requires the farmer's signature")]
+    FarmerSignsOtherInstruction { index: usize },
+    #[error("Farmer is the fee payer")]
+    FarmerPaysFees,
+    #[error("Invalid signature of {0}")]
+    InvalidSignature(Pubkey),
+}
+
+/// Checks a prepared withdrawal against the farmer's intent without any
+/// network access, so wallets can run it before countersigning. The farmer
+/// may only sign the withdrawal itself; other instructions are limited to
+/// compute budget, idempotent ATA creation and memos.
+pub fn verify_withdrawal_transaction(
+    transaction: &Transaction,
+    intent: &WithdrawalIntent,
+) -> Result<(), Vec<VerificationIssue>> {
+    let message = &transaction.message;
+    let mut issues = Vec::new();
+    let mut withdrawals = 0;
+
+    if message.account_keys.first() == Some(&intent.farmer) {
+        issues.push(VerificationIssue::FarmerPaysFees);
+    }
+
+    for (index, instruction) in message.instructions.iter().enumerate() {
+        let program_id = *instruction.program_id(&message.account_keys);
+        let key = |position: usize| {
+            instruction
+                .accounts
+                .get(position)
+                .and_then(|&account| message.account_keys.get(account as usize))
+                .copied()
+                .unwrap_or_default()
+        };
+
+        if program_id == intent.accounts.program_id {
+            match VersionedInstruction::unpack(&instruction.data).map(|i| i.into_latest()) {
+                Ok(RewardPoolInstruction::WithdrawReward { amount, .. }) => {
+                    withdrawals += 1;
+                    if amount != intent.amount {
+                        issues.push(VerificationIssue::WrongAmount {
+                            expected: intent.amount,
+                            actual: amount,
+                        });
+                    }
+                    for (name, position, expected) in [
+                        ("farmer", 0, intent.farmer),
+                        ("pool", 1, intent.accounts.pool),
+                        ("destination", 3, intent.destination),
+                    ] {
+                        let actual = key(position);
+                        if actual != expected {
+                            issues.push(VerificationIssue::WrongAccount {
+                                name,
+                                expected,
+                                actual,
+                            });
+                        }
+                    }
+                    continue;
+                }
+                Ok(_) => {}
+                Err(_) => issues.push(VerificationIssue::UndecodableInstruction { index }),
+            }
+        } else if !is_allowed_program(&program_id) {
+            issues.push(VerificationIssue::UnexpectedProgram { index, program_id });
+        }
+
+        // Creating the farmer's ATA names them as its wallet, which does not
+        // sign for it; only the account funding it does
+        let farmer_signs = if spl_associated_token_account::check_id(&program_id) {
+            key(0) == intent.farmer
+        } else {
+            signs_for(message, instruction, &intent.farmer)
+        };
+        if farmer_signs {
+            issues.push(VerificationIssue::FarmerSignsOtherInstruction { index });
+        }
+    }
+
+    if withdrawals != 1 {
+        issues.push(VerificationIssue::WithdrawalCount { count: withdrawals });
+    }
+
+    // Signatures already present must be valid; missing ones are expected
+    for ((signature, key), valid) in transaction
+        .signatures
+        .iter()
+        .zip(&message.account_keys)
+        .zip(transaction.verify_with_results())
+    {
+        if *signature != Signature::default() && !valid {
+            issues.push(VerificationIssue::InvalidSignature(*key));
+        }
+    }
+
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
+/// Adds the farmer's signature to a prepared transaction, keeping the
+/// blockhash the platform signed
+pub fn countersign(
+    transaction: &mut Transaction,
+    farmer: &dyn Signer,
+) -> Result<(), RewardPoolClientError> {
+    let blockhash = transaction.message.recent_blockhash;
+    transaction.try_partial_sign(&[farmer], blockhash)?;
+    Ok(())
+}
+
+fn is_allowed_program(program_id: &Pubkey) -> bool {
+    solana_compute_budget_interface::check_id(program_id)
+        || spl_associated_token_account::check_id(program_id)
+        || spl_memo::check_id(program_id)
+}
+
+fn signs_for(
+    message: &solana_program::message::Message,
+    instruction: &CompiledInstruction,
+    signer: &Pubkey,
+) -> bool {
+    instruction.accounts.iter().any(|&account| {
+        let account = account as usize;
+        message.is_signer(account) && message.account_keys.get(account) == Some(signer)
+    })
+}
+
+impl RewardPoolClient {
+    /// Prepares a withdrawal of the farmer's pending reward for `task_id`,
+    /// paid and signed by `payer`, that `farmer` only has to countersign.
+    /// The destination is the farmer's reward token account of
+    /// `destination_owner`, created if missing.
+    pub async fn prepare_withdrawal(
+        &self,
+        payer: &dyn Signer,
+        farmer: &Pubkey,
+        task_id: &str,
+        destination_owner: &Pubkey,
+        amount: u64,
+        nonce: u64,
+    ) -> Result<PreparedTransaction, RewardPoolClientError> {
+        let builder = self
+            .tx_builder(payer.pubkey())
+            .authority(*farmer)
+            .destination_owner(*destination_owner)
+            .ensure_destination_ata()
+            .withdraw(task_id, amount, nonce);
+
+        let (blockhash, last_valid_block_height) = self
+            .rpc()
+            .get_latest_blockhash_with_commitment(self.rpc().commitment())
+            .await?;
+        let mut transaction = Transaction::new_unsigned(builder.build(blockhash));
+        transaction.try_partial_sign(&[payer], blockhash)?;
+
+        Ok(PreparedTransaction {
+            transaction,
+            last_valid_block_height,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_keypair::Keypair;
+    use solana_program::{hash::Hash, instruction::Instruction};
+    use solana_signer::SignerError;
+    use solana_system_interface::instruction as system_instruction;
+
+    use super::*;
+    use crate::{
+        instructions,
+        mock_rpc::{client, pool_accounts},
+        tx_builder::TxBuilder,
+    };
+
+    const AMOUNT: u64 = 1_000;
+
+    struct Withdrawal {
+        payer: Keypair,
+        farmer: Keypair,
+        intent: WithdrawalIntent,
+    }
+
+    impl Withdrawal {
+        fn new() -> Self {
+            let farmer = Keypair::new();
+            let accounts = pool_accounts();
+            Self {
+                payer: Keypair::new(),
+                intent: WithdrawalIntent {
+                    accounts,
+                    farmer: farmer.pubkey(),
+                    destination: accounts.reward_account(&farmer.pubkey()),
+                    amount: AMOUNT,
+                },
+                farmer,
+            }
+        }
+
+        /// The withdrawal of `amount` the platform would prepare, with
+        /// `extra` instructions appended and signed by the payer
+        fn prepare(&self, amount: u64, extra: &[Instruction]) -> Transaction {
+            let mut builder = TxBuilder::new(self.intent.accounts, self.payer.pubkey())
+                .authority(self.farmer.pubkey())
+                .ensure_destination_ata()
+                .withdraw("task-1", amount, 1);
+            for instruction in extra {
+                builder = builder.instruction(instruction.clone());
+            }
+            let blockhash = Hash::new_from_array([4; 32]);
+            let mut transaction = Transaction::new_unsigned(builder.build(blockhash));
+            transaction
+                .try_partial_sign(&[&self.payer], blockhash)
+                .unwrap();
+            transaction
+        }
+
+        fn verify(&self, transaction: &Transaction) -> Result<(), Vec<VerificationIssue>> {
+            verify_withdrawal_transaction(transaction, &self.intent)
+        }
+    }
+
+    #[test]
+    fn a_prepared_withdrawal_verifies_and_is_complete_once_countersigned() {
+        let withdrawal = Withdrawal::new();
+        let mut transaction = withdrawal.prepare(AMOUNT, &[]);
+        assert_eq!(withdrawal.verify(&transaction), Ok(()));
+        assert!(!transaction.is_signed());
+
+        countersign(&mut transaction, &withdrawal.farmer).unwrap();
+        assert_eq!(withdrawal.verify(&transaction), Ok(()));
+        assert!(transaction.is_signed());
+        assert!(transaction.verify().is_ok());
+    }
+
+    #[test]
+    fn encoding_round_trips_a_partially_signed_transaction() {
+        let withdrawal = Withdrawal::new();
+        let transaction = withdrawal.prepare(AMOUNT, &[]);
+
+        assert_eq!(
+            decode_transaction(&encode_transaction(&transaction)),
+            Some(transaction)
+        );
+        assert_eq!(decode_transaction("not base64!"), None);
+        assert_eq!(decode_transaction(&BASE64.encode([1, 2, 3])), None);
+    }
+
+    #[test]
+    fn a_signer_missing_from_the_transaction_cannot_countersign() {
+        let withdrawal = Withdrawal::new();
+        let mut transaction = withdrawal.prepare(AMOUNT, &[]);
+
+        let error = countersign(&mut transaction, &Keypair::new()).unwrap_err();
+        assert!(matches!(
+            error,
+            RewardPoolClientError::Signer(SignerError::KeypairPubkeyMismatch)
+        ));
+        assert!(!transaction.is_signed());
+    }
+
+    #[test]
+    fn a_different_amount_is_rejected() {
+        let withdrawal = Withdrawal::new();
+        let transaction = withdrawal.prepare(AMOUNT + 1, &[]);
+
+        assert_eq!(
+            withdrawal.verify(&transaction),
+            Err(vec![VerificationIssue::WrongAmount {
+                expected: AMOUNT,
+                actual: AMOUNT + 1,
+            }])
+        );
+    }
+
+    #[test]
+    fn a_different_destination_is_rejected() {
+        let withdrawal = Withdrawal::new();
+        let transaction = withdrawal.prepare(AMOUNT, &[]);
+        let destination = Pubkey::new_unique();
+        let intent = WithdrawalIntent {
+            destination,
+            ..withdrawal.intent
+        };
+
+        assert_eq!(
+            verify_withdrawal_transaction(&transaction, &intent),
+            Err(vec![VerificationIssue::WrongAccount {
+                name: "destination",
+                expected: destination,
+                actual: withdrawal.intent.destination,
+            }])
+        );
+    }
+
+    #[test]
+    fn the_farmer_may_not_pay_the_fees() {
+        let withdrawal = Withdrawal::new();
+        let message = TxBuilder::new(withdrawal.intent.accounts, withdrawal.farmer.pubkey())
+            .withdraw("task-1", AMOUNT, 1)
+            .build(Hash::new_from_array([4; 32]));
+
+        assert_eq!(
+            withdrawal.verify(&Transaction::new_unsigned(message)),
+            Err(vec![VerificationIssue::FarmerPaysFees])
+        );
+    }
+
+    #[test]
+    fn the_farmer_may_not_sign_anything_but_the_withdrawal() {
+        let withdrawal = Withdrawal::new();
+        let drain = system_instruction::transfer(
+            &withdrawal.farmer.pubkey(),
+            &withdrawal.payer.pubkey(),
+            1,
+        );
+        let transaction = withdrawal.prepare(AMOUNT, &[drain]);
+        let index = transaction.message.instructions.len() - 1;
+
+        assert_eq!(
+            withdrawal.verify(&transaction),
+            Err(vec![
+                VerificationIssue::UnexpectedProgram {
+                    index,
+                    program_id: solana_system_interface::program::id(),
+                },
+                VerificationIssue::FarmerSignsOtherInstruction { index },
+            ])
+        );
+    }
+
+    #[test]
+    fn the_farmer_may_not_fund_an_associated_token_account() {
+        let withdrawal = Withdrawal::new();
+        let farmer = withdrawal.farmer.pubkey();
+        let create =
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &farmer,
+                &Pubkey::new_unique(),
+                &withdrawal.intent.accounts.reward_mint,
+                &spl_token::id(),
+            );
+        let transaction = withdrawal.prepare(AMOUNT, &[create]);
+        let index = transaction.message.instructions.len() - 1;
+
+        assert_eq!(
+            withdrawal.verify(&transaction),
+            Err(vec![VerificationIssue::FarmerSignsOtherInstruction {
+                index
+            }])
+        );
+    }
+
+    #[test]
+    fn exactly_one_withdrawal_is_expected() {
+        let withdrawal = Withdrawal::new();
+        let second = instructions::withdraw_reward(
+            &withdrawal.intent.accounts,
+            &withdrawal.farmer.pubkey(),
+            "task-2",
+            &withdrawal.intent.destination,
+            AMOUNT,
+            2,
+            0,
+        );
+        let transaction = withdrawal.prepare(AMOUNT, &[second]);
+
+        assert_eq!(
+            withdrawal.verify(&transaction),
+            Err(vec![VerificationIssue::WithdrawalCount { count: 2 }])
+        );
+    }
+
+    #[test]
+    fn an_unknown_reward_pool_instruction_is_rejected() {
+        let withdrawal = Withdrawal::new();
+        let unknown = Instruction::new_with_bytes(
+            withdrawal.intent.accounts.program_id,
+            &[u8::MAX, u8::MAX],
+            vec![],
+        );
+        let transaction = withdrawal.prepare(AMOUNT, &[unknown]);
+        let index = transaction.message.instructions.len() - 1;
+
+        assert_eq!(
+            withdrawal.verify(&transaction),
+            Err(vec![VerificationIssue::UndecodableInstruction { index }])
+        );
+    }
+
+    #[test]
+    fn a_tampered_signature_is_rejected() {
+        let withdrawal = Withdrawal::new();
+        let mut transaction = withdrawal.prepare(AMOUNT, &[]);
+        transaction.signatures[0] = Signature::from([1; 64]);
+
+        assert_eq!(
+            withdrawal.verify(&transaction),
+            Err(vec![VerificationIssue::InvalidSignature(
+                withdrawal.payer.pubkey()
+            )])
+        );
+    }
+
+    #[tokio::test]
+    async fn prepare_withdrawal_signs_for_the_payer_only() {
+        let withdrawal = Withdrawal::new();
+        let client = client("succeeds", vec![]);
+
+        let prepared = client
+            .prepare_withdrawal(
+                &withdrawal.payer,
+                &withdrawal.farmer.pubkey(),
+                "task-1",
+                &withdrawal.farmer.pubkey(),
+                AMOUNT,
+                1,
+            )
+            .await
+            .unwrap();
+
+        let transaction = &prepared.transaction;
+        assert_eq!(
+            transaction.message.account_keys[0],
+            withdrawal.payer.pubkey()
+        );
+        assert_ne!(transaction.signatures[0], Signature::default());
+        assert_eq!(transaction.signatures[1], Signature::default());
+        assert_eq!(withdrawal.verify(transaction), Ok(()));
+        assert_eq!(
+            decode_transaction(&prepared.encode()).as_ref(),
+            Some(transaction)
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_system_interface::instruction::SystemInstruction;
+
+use crate::{client::RewardPoolClient, error::RewardPoolClientError, tx_builder::TxBuilder};
+
+// Priority fee policy
+#[derive(Debug, Clone)]
+pub struct PriorityFeeConfig {
+    /// Percentile (0-100) of recent prioritization fees to match
+    pub percentile: u8,
+    /// Compute unit limit requested for the transaction
+    pub compute_unit_limit: u32,
+    /// Lower bound for the unit price, in micro-lamports
+    pub min_micro_lamports: u64,
+    /// Upper bound for the unit price, in micro-lamports
+    pub max_micro_lamports: u64,
+}
+
+impl Default for PriorityFeeConfig {
+    fn default() -> Self {
+        Self {
+            percentile: 75,
+            compute_unit_limit: 200_000,
+            min_micro_lamports: 1_000,
+            max_micro_lamports: 1_000_000,
+        }
+    }
+}
+
+/// Nearest-rank percentile of a fee sample, 0 for an empty sample
+pub fn fee_percentile(fees: &[u64], percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+
+    let mut sorted = fees.to_vec();
+    sorted.sort_unstable();
+    let rank = (sorted.len() * percentile.min(100) as usize).div_ceil(100);
+    sorted[rank.saturating_sub(1)]
+}
+
+/// Prepends compute unit limit and price instructions, replacing any compute
+/// budget instruction already present (the runtime rejects duplicates). A
+/// leading nonce advance stays first, as durable nonce transactions require.
+pub fn prepend_compute_budget(
+    instructions: &[Instruction],
+    compute_unit_limit: u32,
+    micro_lamports: u64,
+) -> Vec<Instruction> {
+    let (advance_nonce, instructions) = match instructions.split_first() {
+        Some((first, rest)) if is_advance_nonce(first) => (Some(first), rest),
+        _ => (None, instructions),
+    };
+
+    let mut prepended: Vec<Instruction> = advance_nonce.into_iter().cloned().collect();
+    prepended.push(ComputeBudgetInstruction::set_compute_unit_limit(
+        compute_unit_limit,
+    ));
+    prepended.push(ComputeBudgetInstruction::set_compute_unit_price(
+        micro_lamports,
+    ));
+    prepended.extend(
+        instructions
+            .iter()
+            .filter(|instruction| {
+                !solana_compute_budget_interface::check_id(&instruction.program_id)
+            })
+            .cloned(),
+    );
+    prepended
+}
+
+pub(crate) fn is_advance_nonce(instruction: &Instruction) -> bool {
+    solana_system_interface::program::check_id(&instruction.program_id)
+        && matches!(
+            bincode::deserialize(&instruction.data),
+            Ok(SystemInstruction::AdvanceNonceAccount)
+        )
+}
+
+/// Accounts written by a set of instructions, the ones priority fees are
+/// contended on
+pub fn writable_accounts(instructions: &[Instruction]) -> Vec<Pubkey> {
+    let mut accounts: Vec<Pubkey> = Vec::new();
+    for meta in instructions
+        .iter()
+        .flat_map(|instruction| &instruction.accounts)
+    {
+        if meta.is_writable && !accounts.contains(&meta.pubkey) {
+            accounts.push(meta.pubkey);
+        }
+    }
+    accounts
+}
+
+impl RewardPoolClient {
+    /// Estimates a unit price from the recent prioritization fees paid for
+    /// the given writable accounts
+    pub async fn estimate_priority_fee(
+        &self,
+        writable_accounts: &[Pubkey],
+        config: &PriorityFeeConfig,
+    ) -> Result<u64, RewardPoolClientError> {
+        let fees: Vec<u64> = self
+            .rpc()
+            .get_recent_prioritization_fees(writable_accounts)
+            .await?
+            .into_iter()
+            .map(|fee| fee.prioritization_fee)
+            .collect();
+
+        Ok(fee_percentile(&fees, config.percentile)
+            .clamp(config.min_micro_lamports, config.max_micro_lamports))
+    }
+
+    /// Prepends compute budget instructions priced from recent fees to an
+    /// arbitrary instruction list
+    pub async fn attach_priority_fee(
+        &self,
+        instructions: &[Instruction],
+        config: &PriorityFeeConfig,
+    ) -> Result<Vec<Instruction>, RewardPoolClientError> {
+        let micro_lamports = self
+            .estimate_priority_fee(&writable_accounts(instructions), config)
+            .await?;
+        Ok(prepend_compute_budget(
+            instructions,
+            config.compute_unit_limit,
+            micro_lamports,
+        ))
+    }
+
+    /// Prices a transaction builder from recent fees
+    pub async fn with_priority_fee(
+        &self,
+        builder: TxBuilder,
+        config: &PriorityFeeConfig,
+    ) -> Result<TxBuilder, RewardPoolClientError> {
+        let micro_lamports = self
+            .estimate_priority_fee(&writable_accounts(&builder.instructions()), config)
+            .await?;
+        Ok(builder
+            .with_compute_unit_limit(config.compute_unit_limit)
+            .with_compute_unit_price(micro_lamports))
+    }
+}
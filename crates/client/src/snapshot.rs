@@ -0,0 +1,452 @@
+use std::collections::BTreeMap;
+
+use reward_pool_parsers::parse_account;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client_api::{
+    config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    request::RpcRequest,
+    response::{OptionalContext, RpcKeyedAccount},
+};
+
+use crate::{client::RewardPoolClient, error::RewardPoolClientError};
+
+// Decoded state of every program account at one slot
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AccountSnapshot {
+    pub program_id: String,
+    /// Slot the accounts were read at
+    pub slot: u64,
+    /// Accounts by address
+    pub accounts: BTreeMap<String, SnapshotAccount>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SnapshotAccount {
+    /// Account type, `Unknown` if no layout matched
+    pub kind: String,
+    pub lamports: u64,
+    /// Decoded fields, or the raw data as hex for unknown accounts
+    pub fields: Value,
+}
+
+// Differences between two snapshots
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SnapshotDiff {
+    pub from_slot: u64,
+    pub to_slot: u64,
+    pub accounts: Vec<AccountDiff>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum AccountDiff {
+    Added {
+        address: String,
+        kind: String,
+    },
+    Removed {
+        address: String,
+        kind: String,
+    },
+    Changed {
+        address: String,
+        kind: String,
+        fields: Vec<FieldChange>,
+    },
+}
+
+// A changed leaf value, addressed by its path (`fields.total[2]`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub path: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+impl RewardPoolClient {
+    /// Fetches and decodes every account owned by the client's program
+    pub async fn snapshot(&self) -> Result<AccountSnapshot, RewardPoolClientError> {
+        fetch_snapshot(self.rpc(), &self.accounts().program_id).await
+    }
+}
+
+/// Fetches and decodes every account owned by a program, at a single slot
+pub async fn fetch_snapshot(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+) -> Result<AccountSnapshot, RewardPoolClientError> {
+    let config = RpcProgramAccountsConfig {
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(rpc.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        with_context: Some(true),
+        ..RpcProgramAccountsConfig::default()
+    };
+    let response: OptionalContext<Vec<RpcKeyedAccount>> = rpc
+        .send(
+            RpcRequest::GetProgramAccounts,
+            json!([program_id.to_string(), config]),
+        )
+        .await?;
+    let (slot, keyed_accounts) = match response {
+        OptionalContext::Context(response) => (response.context.slot, response.value),
+        OptionalContext::NoContext(value) => (rpc.get_slot().await?, value),
+    };
+
+    let accounts = keyed_accounts
+        .into_iter()
+        .map(|keyed| {
+            let data = keyed.account.data.decode().unwrap_or_default();
+            let account = match parse_account(&data) {
+                Some(parsed) => SnapshotAccount {
+                    kind: parsed.kind,
+                    lamports: keyed.account.lamports,
+                    fields: parsed.fields,
+                },
+                None => SnapshotAccount {
+                    kind: "Unknown".to_string(),
+                    lamports: keyed.account.lamports,
+                    fields: Value::String(reward_pool_parsers::json::hex(&data)),
+                },
+            };
+            (keyed.pubkey, account)
+        })
+        .collect();
+
+    Ok(AccountSnapshot {
+        program_id: program_id.to_string(),
+        slot,
+        accounts,
+    })
+}
+
+/// Field-level differences between two snapshots, sorted by address
+pub fn diff_snapshots(old: &AccountSnapshot, new: &AccountSnapshot) -> SnapshotDiff {
+    let mut accounts = Vec::new();
+
+    for (address, old_account) in &old.accounts {
+        match new.accounts.get(address) {
+            None => accounts.push(AccountDiff::Removed {
+                address: address.clone(),
+                kind: old_account.kind.clone(),
+            }),
+            Some(new_account) => {
+                let mut fields = Vec::new();
+                if old_account.kind != new_account.kind {
+                    diff_values(
+                        "kind",
+                        &json!(old_account.kind),
+                        &json!(new_account.kind),
+                        &mut fields,
+                    );
+                }
+                diff_values(
+                    "lamports",
+                    &json!(old_account.lamports),
+                    &json!(new_account.lamports),
+                    &mut fields,
+                );
+                diff_values(
+                    "fields",
+                    &old_account.fields,
+                    &new_account.fields,
+                    &mut fields,
+                );
+                if !fields.is_empty() {
+                    accounts.push(AccountDiff::Changed {
+                        address: address.clone(),
+                        kind: new_account.kind.clone(),
+                        fields,
+                    });
+                }
+            }
+        }
+    }
+    for (address, new_account) in &new.accounts {
+        if !old.accounts.contains_key(address) {
+            accounts.push(AccountDiff::Added {
+                address: address.clone(),
+                kind: new_account.kind.clone(),
+            });
+        }
+    }
+    accounts.sort_by(|a, b| diff_address(a).cmp(diff_address(b)));
+
+    SnapshotDiff {
+        from_slot: old.slot,
+        to_slot: new.slot,
+        accounts,
+    }
+}
+
+fn diff_address(diff: &AccountDiff) -> &str {
+    match diff {
+        AccountDiff::Added { address, .. }
+        | AccountDiff::Removed { address, .. }
+        | AccountDiff::Changed { address, .. } => address,
+    }
+}
+
+fn diff_values(path: &str, old: &Value, new: &Value, changes: &mut Vec<FieldChange>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                let new_value = new_map.get(key).unwrap_or(&Value::Null);
+                diff_values(&format!("{path}.{key}"), old_value, new_value, changes);
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    diff_values(&format!("{path}.{key}"), &Value::Null, new_value, changes);
+                }
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items)) => {
+            for index in 0..old_items.len().max(new_items.len()) {
+                diff_values(
+                    &format!("{path}[{index}]"),
+                    old_items.get(index).unwrap_or(&Value::Null),
+                    new_items.get(index).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ if old != new => changes.push(FieldChange {
+            path: path.to_string(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use reward_pool::{PoolAddressKind, RewardPool};
+
+    use super::*;
+    use crate::mock_rpc::{client, pool_accounts};
+
+    fn account(kind: &str, lamports: u64, fields: Value) -> SnapshotAccount {
+        SnapshotAccount {
+            kind: kind.to_string(),
+            lamports,
+            fields,
+        }
+    }
+
+    fn snapshot(slot: u64, accounts: &[(&str, SnapshotAccount)]) -> AccountSnapshot {
+        AccountSnapshot {
+            program_id: reward_pool::id().to_string(),
+            slot,
+            accounts: accounts
+                .iter()
+                .map(|(address, account)| (address.to_string(), account.clone()))
+                .collect(),
+        }
+    }
+
+    fn change(path: &str, old: Value, new: Value) -> FieldChange {
+        FieldChange {
+            path: path.to_string(),
+            old,
+            new,
+        }
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_differences() {
+        let pool = account("RewardPool", 10, json!({ "is_paused": false }));
+        let old = snapshot(1, &[("a", pool.clone())]);
+        let new = snapshot(2, &[("a", pool)]);
+
+        assert_eq!(
+            diff_snapshots(&old, &new),
+            SnapshotDiff {
+                from_slot: 1,
+                to_slot: 2,
+                accounts: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn added_removed_and_changed_accounts_are_sorted_by_address() {
+        let old = snapshot(
+            1,
+            &[
+                ("c", account("DailyStats", 10, json!({ "day": 1 }))),
+                ("b", account("FarmerState", 10, json!({}))),
+            ],
+        );
+        let new = snapshot(
+            2,
+            &[
+                ("c", account("DailyStats", 12, json!({ "day": 1 }))),
+                ("a", account("PendingReward", 10, json!({}))),
+            ],
+        );
+
+        assert_eq!(
+            diff_snapshots(&old, &new).accounts,
+            vec![
+                AccountDiff::Added {
+                    address: "a".to_string(),
+                    kind: "PendingReward".to_string(),
+                },
+                AccountDiff::Removed {
+                    address: "b".to_string(),
+                    kind: "FarmerState".to_string(),
+                },
+                AccountDiff::Changed {
+                    address: "c".to_string(),
+                    kind: "DailyStats".to_string(),
+                    fields: vec![change("lamports", json!(10), json!(12))],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_changes_are_addressed_by_their_path() {
+        let old = snapshot(
+            1,
+            &[(
+                "a",
+                account(
+                    "FeeLedger",
+                    10,
+                    json!({ "totals": [1, 2, 3], "policy": { "rate": 5, "old": true } }),
+                ),
+            )],
+        );
+        let new = snapshot(
+            2,
+            &[(
+                "a",
+                account(
+                    "FeeLedger",
+                    10,
+                    json!({ "totals": [1, 2, 4, 5], "policy": { "rate": 5, "new": 1 } }),
+                ),
+            )],
+        );
+
+        let AccountDiff::Changed { mut fields, .. } = diff_snapshots(&old, &new).accounts.remove(0)
+        else {
+            panic!("expected a changed account");
+        };
+        // Keys are visited in the map's order, which depends on serde_json
+        // features
+        fields.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(
+            fields,
+            vec![
+                change("fields.policy.new", Value::Null, json!(1)),
+                change("fields.policy.old", json!(true), Value::Null),
+                change("fields.totals[2]", json!(3), json!(4)),
+                change("fields.totals[3]", Value::Null, json!(5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_reused_address_of_another_kind_reports_the_kind_change() {
+        let old = snapshot(1, &[("a", account("Unknown", 10, json!("00ff")))]);
+        let new = snapshot(2, &[("a", account("FarmerState", 10, json!({ "n": 1 })))]);
+
+        assert_eq!(
+            diff_snapshots(&old, &new).accounts,
+            vec![AccountDiff::Changed {
+                address: "a".to_string(),
+                kind: "FarmerState".to_string(),
+                fields: vec![
+                    change("kind", json!("Unknown"), json!("FarmerState")),
+                    change("fields", json!("00ff"), json!({ "n": 1 })),
+                ],
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetched_accounts_are_decoded_or_kept_as_hex() {
+        let pool = RewardPool {
+            platform_authority: Pubkey::new_from_array([2; 32]),
+            reward_mint: pool_accounts().reward_mint,
+            platform_treasury: Pubkey::new_from_array([3; 32]),
+            platform_fee_percentage: 5,
+            total_rewards_distributed: 0,
+            total_platform_fees_collected: 0,
+            is_paused: false,
+            bump_seed: 255,
+            address_kind: PoolAddressKind::Mint,
+            address_creator: Pubkey::default(),
+            address_seed: 0,
+            outstanding_rewards: 0,
+            last_authority_action_at: 0,
+            sequence: 0,
+        };
+        let pool_data = borsh::to_vec(&pool).unwrap();
+        let keyed = |address: &Pubkey, data: &[u8]| {
+            json!({
+                "pubkey": address.to_string(),
+                "account": {
+                    "lamports": 7,
+                    "data": [BASE64_STANDARD.encode(data), "base64"],
+                    "owner": reward_pool::id().to_string(),
+                    "executable": false,
+                    "rentEpoch": 0,
+                    "space": data.len(),
+                },
+            })
+        };
+        let accounts = pool_accounts();
+        let unknown = Pubkey::new_unique();
+        let client = client(
+            "succeeds",
+            vec![(
+                RpcRequest::GetProgramAccounts,
+                json!({
+                    "context": { "slot": 42 },
+                    "value": [keyed(&accounts.pool, &pool_data), keyed(&unknown, &[0xde, 0xad])],
+                }),
+            )],
+        );
+
+        let snapshot = client.snapshot().await.unwrap();
+        assert_eq!(snapshot.slot, 42);
+        assert_eq!(snapshot.program_id, reward_pool::id().to_string());
+        let pool = &snapshot.accounts[&accounts.pool.to_string()];
+        assert_eq!(pool.kind, "RewardPool");
+        assert_eq!(pool.lamports, 7);
+        assert_eq!(pool.fields["platform_fee_percentage"], json!(5));
+        assert_eq!(
+            snapshot.accounts[&unknown.to_string()],
+            account("Unknown", 7, json!("dead"))
+        );
+    }
+
+    #[test]
+    fn snapshots_and_diffs_round_trip_through_json() {
+        let old = snapshot(1, &[("a", account("FarmerState", 10, json!({ "n": 1 })))]);
+        let new = snapshot(2, &[("a", account("FarmerState", 10, json!({ "n": 2 })))]);
+        let diff = diff_snapshots(&old, &new);
+
+        let encoded = serde_json::to_value(&diff).unwrap();
+        assert_eq!(encoded["accounts"][0]["change"], json!("changed"));
+        assert_eq!(
+            serde_json::from_value::<SnapshotDiff>(encoded).unwrap(),
+            diff
+        );
+        assert_eq!(
+            serde_json::from_str::<AccountSnapshot>(&serde_json::to_string(&old).unwrap()).unwrap(),
+            old
+        );
+    }
+}
@@ -0,0 +1,1441 @@
+use std::fmt;
+
+use reward_pool::{
+    instruction::{v2::RewardPoolInstruction, VersionedInstruction},
+    memo::task_memo,
+    ParameterValues, ScheduledAction,
+};
+use solana_program::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey::Pubkey,
+};
+
+use reward_pool_parsers::json::hex;
+
+use crate::{
+    instructions::{WITHDRAW_LEADING_ACCOUNTS, WITHDRAW_TRAILING_ACCOUNTS},
+    priority_fee::is_advance_nonce,
+};
+
+// Canonical, human-readable summaries of what a transaction does
+//
+// Hardware wallet users review these before signing offline. Reward pool
+// instructions are decoded with `VersionedInstruction::unpack`, exactly as
+// the program decodes them, and rendering is deterministic: fixed field
+// order, raw base-unit amounts and base58 addresses.
+
+// One labelled value of an instruction summary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummaryField {
+    pub label: &'static str,
+    pub value: String,
+}
+
+// What a single instruction does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionSummary {
+    /// Program the instruction calls, e.g. `Reward pool`
+    pub program: String,
+    /// Instruction name, e.g. `WithdrawReward`
+    pub action: String,
+    pub fields: Vec<SummaryField>,
+}
+
+// Until when a transaction can land
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expiry {
+    /// Recent blockhash valid up to this block height
+    BlockHeight(u64),
+    /// Recent blockhash whose expiry is not known offline
+    Blockhash(Hash),
+    /// Never expires until the nonce account is advanced
+    DurableNonce(Pubkey),
+}
+
+// Everything a signer should review about a message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageSummary {
+    pub fee_payer: Pubkey,
+    pub expiry: Expiry,
+    pub instructions: Vec<InstructionSummary>,
+}
+
+/// Summarizes an instruction, decoding it when it targets `program_id`
+pub fn summarize_instruction(program_id: &Pubkey, instruction: &Instruction) -> InstructionSummary {
+    if instruction.program_id == *program_id {
+        return match VersionedInstruction::unpack(&instruction.data) {
+            Ok(decoded) => summarize_reward_pool(decoded.into_latest(), &instruction.accounts),
+            Err(_) => InstructionSummary {
+                program: "Reward pool".to_string(),
+                action: "Unknown instruction".to_string(),
+                fields: Vec::new(),
+            },
+        };
+    }
+
+    let (program, action) = if solana_compute_budget_interface::check_id(&instruction.program_id) {
+        ("Compute budget", "Set compute budget")
+    } else if spl_associated_token_account::check_id(&instruction.program_id) {
+        ("Associated token account", "Create token account")
+    } else if spl_memo::check_id(&instruction.program_id) {
+        ("Memo", "Memo")
+    } else if is_advance_nonce(instruction) {
+        ("System", "Advance nonce")
+    } else {
+        return InstructionSummary {
+            program: instruction.program_id.to_string(),
+            action: "Unknown instruction".to_string(),
+            fields: Vec::new(),
+        };
+    };
+
+    let fields = if spl_memo::check_id(&instruction.program_id) {
+        vec![field(
+            "Text",
+            String::from_utf8_lossy(&instruction.data).into_owned(),
+        )]
+    } else if spl_associated_token_account::check_id(&instruction.program_id) {
+        // Funder, token account, owner, mint
+        account_fields(&instruction.accounts, &[("Account", 1), ("Owner", 2)])
+    } else {
+        Vec::new()
+    };
+
+    InstructionSummary {
+        program: program.to_string(),
+        action: action.to_string(),
+        fields,
+    }
+}
+
+/// Summarizes every instruction of a message. `last_valid_block_height`
+/// is the expiry returned with the blockhash, if known.
+pub fn summarize_message(
+    program_id: &Pubkey,
+    message: &Message,
+    last_valid_block_height: Option<u64>,
+) -> MessageSummary {
+    let instructions: Vec<Instruction> = message
+        .instructions
+        .iter()
+        .map(|compiled| Instruction {
+            program_id: *compiled.program_id(&message.account_keys),
+            accounts: compiled
+                .accounts
+                .iter()
+                .map(|&index| {
+                    let index = index as usize;
+                    AccountMeta {
+                        pubkey: message.account_keys[index],
+                        is_signer: message.is_signer(index),
+                        is_writable: message.is_maybe_writable(index, None),
+                    }
+                })
+                .collect(),
+            data: compiled.data.clone(),
+        })
+        .collect();
+
+    let expiry = match instructions.first() {
+        Some(first) if is_advance_nonce(first) => Expiry::DurableNonce(
+            first
+                .accounts
+                .first()
+                .map(|meta| meta.pubkey)
+                .unwrap_or_default(),
+        ),
+        _ => last_valid_block_height.map_or(
+            Expiry::Blockhash(message.recent_blockhash),
+            Expiry::BlockHeight,
+        ),
+    };
+
+    MessageSummary {
+        fee_payer: message.account_keys.first().copied().unwrap_or_default(),
+        expiry,
+        instructions: instructions
+            .iter()
+            .map(|instruction| summarize_instruction(program_id, instruction))
+            .collect(),
+    }
+}
+
+fn summarize_reward_pool(
+    instruction: RewardPoolInstruction,
+    accounts: &[AccountMeta],
+) -> InstructionSummary {
+    let (action, positions, mut fields): (&str, &[(&str, usize)], Vec<SummaryField>) =
+        match instruction {
+            RewardPoolInstruction::InitializePool {
+                platform_fee_percentage,
+                name,
+                uri,
+            } => {
+                let mut fields = vec![field("Platform fee", format!("{platform_fee_percentage}%"))];
+                if let Some(name) = name {
+                    fields.push(field("Name", name));
+                }
+                if let Some(uri) = uri {
+                    fields.push(field("URI", uri));
+                }
+                (
+                    "InitializePool",
+                    &[("Pool", 1), ("Authority", 0), ("Mint", 2), ("Treasury", 3)],
+                    fields,
+                )
+            }
+            RewardPoolInstruction::RecordReward {
+                amount,
+                farmer_pubkey,
+                task_id,
+                campaign_id,
+                platform_id,
+                proof_hash,
+                task_type,
+                secondary_amount,
+                idempotency_key,
+            } => {
+                let memo = accounts
+                    .get(30)
+                    .filter(|account| spl_memo::check_id(&account.pubkey))
+                    .map(|_| task_memo(&task_id));
+                let mut fields = vec![
+                    field("Farmer", farmer_pubkey.to_string()),
+                    field("Amount", amount.to_string()),
+                    field("Task", task_id),
+                ];
+                if let Some(memo) = memo {
+                    fields.push(field("Memo", memo));
+                }
+                if let Some(campaign_id) = campaign_id {
+                    fields.push(field("Campaign", campaign_id.to_string()));
+                }
+                if let Some(platform_id) = platform_id {
+                    fields.push(field("Platform", platform_id.to_string()));
+                }
+                if let Some(proof_hash) = proof_hash {
+                    fields.push(field("Proof", hex(&proof_hash)));
+                }
+                if let Some(task_type) = task_type {
+                    fields.push(field("Task type", task_type.to_string()));
+                }
+                if secondary_amount > 0 {
+                    fields.push(field("Secondary amount", secondary_amount.to_string()));
+                }
+                if let Some(idempotency_key) = idempotency_key {
+                    fields.push(field("Idempotency key", hex(&idempotency_key)));
+                }
+                // The verifier and treasury approvers follow the governance
+                for cosigner in accounts.iter().skip(40) {
+                    fields.push(field("Co-signer", cosigner.pubkey.to_string()));
+                }
+                ("RecordReward", &[("Pool", 1)], fields)
+            }
+            RewardPoolInstruction::WithdrawReward {
+                amount,
+                nonce,
+                split,
+            } => {
+                let mut fields = vec![
+                    field("Amount", amount.to_string()),
+                    field("Nonce", nonce.to_string()),
+                ];
+                // Further split destinations take the sponsor's place when
+                // there is none, and the trailing accounts come last
+                let further_destinations = split
+                    .as_ref()
+                    .map_or(0, |split| split.len().saturating_sub(1));
+                let sponsored = accounts.len()
+                    > WITHDRAW_LEADING_ACCOUNTS + further_destinations + WITHDRAW_TRAILING_ACCOUNTS;
+                for share in split.iter().flatten() {
+                    fields.push(field(
+                        "Split",
+                        format!("{} ({} bps)", share.destination, share.share_bps),
+                    ));
+                }
+                (
+                    "WithdrawReward",
+                    if sponsored {
+                        &[
+                            ("Pool", 1),
+                            ("Farmer", 0),
+                            ("Destination", 3),
+                            ("Sponsor", 19),
+                        ]
+                    } else {
+                        &[("Pool", 1), ("Farmer", 0), ("Destination", 3)]
+                    },
+                    fields,
+                )
+            }
+            RewardPoolInstruction::UpdatePlatformFee { new_fee_percentage } => (
+                "UpdatePlatformFee",
+                &[("Pool", 1)],
+                vec![field("New platform fee", format!("{new_fee_percentage}%"))],
+            ),
+            RewardPoolInstruction::PausePool => ("PausePool", &[("Pool", 1)], Vec::new()),
+            RewardPoolInstruction::ResumePool => ("ResumePool", &[("Pool", 1)], Vec::new()),
+            RewardPoolInstruction::GetFarmerPending { farmer, .. } => (
+                "GetFarmerPending",
+                &[("Pool", 0)],
+                vec![field("Farmer", farmer.to_string())],
+            ),
+            RewardPoolInstruction::GetPoolStats => ("GetPoolStats", &[("Pool", 0)], Vec::new()),
+            RewardPoolInstruction::GetWithdrawalHistory { farmer } => (
+                "GetWithdrawalHistory",
+                &[("Pool", 0)],
+                vec![field("Farmer", farmer.to_string())],
+            ),
+            RewardPoolInstruction::SweepDelegatedRewards => (
+                "SweepDelegatedRewards",
+                &[("Pool", 1), ("Custodian", 0), ("Destination", 2)],
+                vec![field(
+                    "Reward accounts",
+                    accounts.len().saturating_sub(7).to_string(),
+                )],
+            ),
+            RewardPoolInstruction::AddWithdrawalDestination { destination } => (
+                "AddWithdrawalDestination",
+                &[("Pool", 1), ("Farmer", 0)],
+                vec![field("Destination", destination.to_string())],
+            ),
+            RewardPoolInstruction::RemoveWithdrawalDestination { destination } => (
+                "RemoveWithdrawalDestination",
+                &[("Pool", 1), ("Farmer", 0)],
+                vec![field("Destination", destination.to_string())],
+            ),
+            RewardPoolInstruction::ConfigureTreasuryGovernance {
+                approvers,
+                required_approvals,
+                approval_threshold,
+                execution_delay,
+            } => {
+                let mut fields: Vec<SummaryField> = approvers
+                    .iter()
+                    .map(|approver| field("Approver", approver.to_string()))
+                    .collect();
+                fields.extend([
+                    field("Required approvals", required_approvals.to_string()),
+                    field("Approval threshold", approval_threshold.to_string()),
+                    field("Execution delay", format!("{execution_delay}s")),
+                ]);
+                (
+                    "ConfigureTreasuryGovernance",
+                    &[("Pool", 1), ("Authority", 0), ("Vault", 3)],
+                    fields,
+                )
+            }
+            RewardPoolInstruction::ProposeTreasuryWithdrawal { amount } => (
+                "ProposeTreasuryWithdrawal",
+                &[("Pool", 1), ("Destination", 4), ("Proposer", 0)],
+                vec![field("Amount", amount.to_string())],
+            ),
+            RewardPoolInstruction::ApproveTreasuryWithdrawal => (
+                "ApproveTreasuryWithdrawal",
+                &[("Pool", 1), ("Proposal", 3), ("Approver", 0)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::ExecuteTreasuryWithdrawal => (
+                "ExecuteTreasuryWithdrawal",
+                &[("Pool", 1), ("Proposal", 3), ("Destination", 5)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::SetUpgradeAuthority => (
+                "SetUpgradeAuthority",
+                &[("Pool", 1), ("New upgrade authority", 8)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::RemoveUpgradeAuthority => (
+                "RemoveUpgradeAuthority",
+                &[("Pool", 1)],
+                vec![field(
+                    "New upgrade authority",
+                    "none, program becomes immutable",
+                )],
+            ),
+            RewardPoolInstruction::SetGuardians { guardians } => {
+                let fields = if guardians.is_empty() {
+                    vec![field("Guardians", "none")]
+                } else {
+                    guardians
+                        .iter()
+                        .map(|guardian| field("Guardian", guardian.to_string()))
+                        .collect()
+                };
+                ("SetGuardians", &[("Pool", 1), ("Authority", 0)], fields)
+            }
+            RewardPoolInstruction::UpdateParameters { values } => (
+                "UpdateParameters",
+                &[("Pool", 1), ("Authority", 0)],
+                parameter_fields(&values),
+            ),
+            RewardPoolInstruction::ScheduleAction {
+                action_id,
+                eta,
+                action,
+            } => {
+                let mut fields = vec![
+                    field("Action id", action_id.to_string()),
+                    field("ETA", eta.to_string()),
+                ];
+                fields.append(&mut action_fields(&action));
+                ("ScheduleAction", &[("Pool", 1), ("Authority", 0)], fields)
+            }
+            RewardPoolInstruction::RollbackParameter { parameter } => (
+                "RollbackParameter",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![field("Parameter", format!("{parameter:?}"))],
+            ),
+            RewardPoolInstruction::PauseAll => (
+                "PauseAll",
+                &[("Authority", 0)],
+                vec![field("Pools", accounts.len().saturating_sub(1).to_string())],
+            ),
+            RewardPoolInstruction::SetFeeAll { new_fee_percentage } => (
+                "SetFeeAll",
+                &[("Authority", 0)],
+                vec![
+                    field("Pools", (accounts.len().saturating_sub(1) / 2).to_string()),
+                    field("New platform fee", format!("{new_fee_percentage}%")),
+                ],
+            ),
+            RewardPoolInstruction::SetPoolMetadata { name, uri } => (
+                "SetPoolMetadata",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![field("Name", name), field("URI", uri)],
+            ),
+            RewardPoolInstruction::ExportState {
+                merkle_root,
+                farmer_count,
+                total_amount,
+            } => (
+                "ExportState",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![
+                    field("Merkle root", Hash::new_from_array(merkle_root).to_string()),
+                    field("Farmers", farmer_count.to_string()),
+                    field("Total owed", total_amount.to_string()),
+                ],
+            ),
+            RewardPoolInstruction::ImportState {
+                source_program_id,
+                farmer,
+                amount,
+                ..
+            } => (
+                "ImportState",
+                &[("Pool", 1), ("Authority", 0), ("State export", 2)],
+                vec![
+                    field("Source program", source_program_id.to_string()),
+                    field("Farmer", farmer.to_string()),
+                    field("Amount", amount.to_string()),
+                ],
+            ),
+            RewardPoolInstruction::ArchivePool { claim_deadline } => (
+                "ArchivePool",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![field("Claim deadline", claim_deadline.to_string())],
+            ),
+            RewardPoolInstruction::ClosePool => (
+                "ClosePool",
+                &[("Pool", 1), ("Authority", 0), ("Destination", 8)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::ClaimMany => (
+                "ClaimMany",
+                &[("Pool", 1), ("Farmer", 0), ("Destination", 3)],
+                vec![field(
+                    "Pending rewards",
+                    accounts.len().saturating_sub(25).to_string(),
+                )],
+            ),
+            RewardPoolInstruction::RegisterPayoutAddress { payout_address } => (
+                "RegisterPayoutAddress",
+                &[("Pool", 1), ("Farmer", 0)],
+                vec![field("Payout address", payout_address.to_string())],
+            ),
+            RewardPoolInstruction::SetCharityAccount => (
+                "SetCharityAccount",
+                &[("Pool", 1), ("Charity", 3), ("Authority", 0)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::DonateReward { target } => (
+                "DonateReward",
+                &[
+                    ("Pool", 1),
+                    ("Pending reward", 2),
+                    ("Farmer", 0),
+                    ("Charity", 9),
+                ],
+                vec![field("Target", format!("{target:?}"))],
+            ),
+            RewardPoolInstruction::CloseFarmerAccounts => (
+                "CloseFarmerAccounts",
+                &[("Pool", 1), ("Rent recipient", 4), ("Farmer", 0)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::CloseWithdrawalRecords => (
+                "CloseWithdrawalRecords",
+                &[("Pool", 1), ("Farmer", 0)],
+                vec![field(
+                    "Records",
+                    (accounts.len().saturating_sub(5) / 2).to_string(),
+                )],
+            ),
+            RewardPoolInstruction::SetAutoForward { enabled } => (
+                "SetAutoForward",
+                &[("Pool", 1), ("Destination", 3), ("Farmer", 0)],
+                vec![field("Enabled", enabled.to_string())],
+            ),
+            RewardPoolInstruction::SetPendingTransferPolicy { transfers_disabled } => (
+                "SetPendingTransferPolicy",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![field("Transfers disabled", transfers_disabled.to_string())],
+            ),
+            RewardPoolInstruction::TransferPending { new_farmer } => (
+                "TransferPending",
+                &[("Pool", 1), ("Pending reward", 2), ("Farmer", 0)],
+                vec![field("New farmer", new_farmer.to_string())],
+            ),
+            RewardPoolInstruction::SetAutoClaim { threshold } => (
+                "SetAutoClaim",
+                &[("Pool", 1), ("Farmer", 0)],
+                vec![field("Threshold", threshold.to_string())],
+            ),
+            RewardPoolInstruction::CrankAutoClaim => (
+                "CrankAutoClaim",
+                &[
+                    ("Pool", 1),
+                    ("Farmer", 3),
+                    ("Destination", 7),
+                    ("Cranker", 0),
+                ],
+                vec![field(
+                    "Pending rewards",
+                    accounts.len().saturating_sub(22).to_string(),
+                )],
+            ),
+            RewardPoolInstruction::SetVerifier { verifier } => (
+                "SetVerifier",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![field(
+                    "Verifier",
+                    verifier.map_or_else(|| "none".to_string(), |verifier| verifier.to_string()),
+                )],
+            ),
+            RewardPoolInstruction::SetAttestor {
+                attestor_program,
+                data_offset,
+            } => (
+                "SetAttestor",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![
+                    field(
+                        "Attestor",
+                        attestor_program.map_or_else(
+                            || "none".to_string(),
+                            |attestor_program| attestor_program.to_string(),
+                        ),
+                    ),
+                    field("Data offset", data_offset.to_string()),
+                ],
+            ),
+            RewardPoolInstruction::LockTaskBudget {
+                task_id,
+                amount,
+                expires_at,
+            } => (
+                "LockTaskBudget",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![
+                    field("Task", task_id),
+                    field("Amount", amount.to_string()),
+                    field("Expires at", expires_at.to_string()),
+                ],
+            ),
+            RewardPoolInstruction::ReleaseUnusedBudget => (
+                "ReleaseUnusedBudget",
+                &[("Pool", 1), ("Task budget", 4), ("Caller", 0)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::ChallengeReward => (
+                "ChallengeReward",
+                &[("Pool", 1), ("Pending reward", 2), ("Challenger", 0)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::ResolveChallenge { upheld } => (
+                "ResolveChallenge",
+                &[("Pool", 1), ("Pending reward", 3), ("Resolver", 0)],
+                vec![field("Upheld", upheld.to_string())],
+            ),
+            RewardPoolInstruction::SetRewardApprover { approver } => (
+                "SetRewardApprover",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![field(
+                    "Approver",
+                    approver.map_or_else(|| "none".to_string(), |approver| approver.to_string()),
+                )],
+            ),
+            RewardPoolInstruction::ApproveReward => (
+                "ApproveReward",
+                &[("Pool", 1), ("Pending reward", 3), ("Approver", 0)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::SetTaskTypeFee {
+                task_type,
+                fee_percentage,
+            } => (
+                "SetTaskTypeFee",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![
+                    field("Task type", task_type.to_string()),
+                    field(
+                        "Fee",
+                        fee_percentage.map_or_else(
+                            || "pool default".to_string(),
+                            |fee_percentage| format!("{fee_percentage}%"),
+                        ),
+                    ),
+                ],
+            ),
+            RewardPoolInstruction::RegisterPlatform { platform_id } => (
+                "RegisterPlatform",
+                &[("Platform", 1), ("Operator", 0)],
+                vec![field("Platform id", platform_id.to_string())],
+            ),
+            RewardPoolInstruction::SetPlatformOperator { operator } => (
+                "SetPlatformOperator",
+                &[("Platform", 1), ("Operator", 0)],
+                vec![field("New operator", operator.to_string())],
+            ),
+            RewardPoolInstruction::InitializePlatformPool {
+                platform_id,
+                platform_fee_percentage,
+            } => (
+                "InitializePlatformPool",
+                &[("Pool", 1), ("Operator", 0), ("Mint", 2), ("Treasury", 3)],
+                vec![
+                    field("Platform id", platform_id.to_string()),
+                    field("Platform fee", format!("{platform_fee_percentage}%")),
+                ],
+            ),
+            RewardPoolInstruction::CreateCampaign {
+                campaign_id,
+                budget,
+                starts_at,
+                ends_at,
+                task_types,
+            } => {
+                let mut fields = vec![
+                    field("Campaign", campaign_id.to_string()),
+                    field("Budget", budget.to_string()),
+                    field("Starts at", starts_at.to_string()),
+                    field("Ends at", ends_at.to_string()),
+                ];
+                if !task_types.is_empty() {
+                    let task_types: Vec<String> = task_types
+                        .iter()
+                        .map(|task_type| task_type.to_string())
+                        .collect();
+                    fields.push(field("Task types", task_types.join(", ")));
+                }
+                (
+                    "CreateCampaign",
+                    &[("Pool", 1), ("Campaign account", 2), ("Authority", 0)],
+                    fields,
+                )
+            }
+            RewardPoolInstruction::EndCampaign => (
+                "EndCampaign",
+                &[("Pool", 1), ("Campaign account", 2), ("Authority", 0)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::FinalizeCampaign => (
+                "FinalizeCampaign",
+                &[("Pool", 1), ("Campaign account", 2), ("Caller", 0)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::ConfigureBuyback {
+                dex_program,
+                governance_mint,
+                slice_bps,
+                min_output_per_million,
+            } => (
+                "ConfigureBuyback",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![
+                    field(
+                        "DEX program",
+                        dex_program.map_or_else(
+                            || "none".to_string(),
+                            |dex_program| dex_program.to_string(),
+                        ),
+                    ),
+                    field("Governance mint", governance_mint.to_string()),
+                    field("Slice (bps)", slice_bps.to_string()),
+                    field("Min output per million", min_output_per_million.to_string()),
+                ],
+            ),
+            RewardPoolInstruction::RunBuyback { swap_data } => (
+                "RunBuyback",
+                &[("Pool", 1), ("DEX program", 10), ("Caller", 0)],
+                vec![field("Swap data", format!("{} bytes", swap_data.len()))],
+            ),
+            RewardPoolInstruction::SetTreasurySwapPolicy {
+                dex_program,
+                stable_mints,
+                epoch_limit_bps,
+            } => {
+                let mut fields = vec![field(
+                    "DEX program",
+                    dex_program
+                        .map_or_else(|| "none".to_string(), |dex_program| dex_program.to_string()),
+                )];
+                fields.extend(
+                    stable_mints
+                        .iter()
+                        .map(|mint| field("Stable mint", mint.to_string())),
+                );
+                fields.push(field("Epoch limit (bps)", epoch_limit_bps.to_string()));
+                (
+                    "SetTreasurySwapPolicy",
+                    &[("Pool", 1), ("Authority", 0)],
+                    fields,
+                )
+            }
+            RewardPoolInstruction::SetFeeDiscounts {
+                staking_program,
+                tier_offset,
+                discounts_bps,
+                ..
+            } => {
+                let mut fields = vec![
+                    field(
+                        "Staking program",
+                        staking_program.map_or_else(
+                            || "none".to_string(),
+                            |staking_program| staking_program.to_string(),
+                        ),
+                    ),
+                    field("Tier offset", tier_offset.to_string()),
+                ];
+                fields.extend(
+                    discounts_bps
+                        .iter()
+                        .enumerate()
+                        .map(|(tier, discount_bps)| {
+                            field("Tier discount", format!("tier {tier}: {discount_bps} bps"))
+                        }),
+                );
+                ("SetFeeDiscounts", &[("Pool", 1), ("Authority", 0)], fields)
+            }
+            RewardPoolInstruction::SetInterestPolicy {
+                rate_bps,
+                max_accrual_secs,
+            } => (
+                "SetInterestPolicy",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![
+                    field("Yearly rate", format!("{rate_bps} bps")),
+                    field("Max accrual", format!("{max_accrual_secs} s")),
+                ],
+            ),
+            RewardPoolInstruction::SetWithdrawBurn { withdraw_burn_bps } => (
+                "SetWithdrawBurn",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![field("Withdraw burn", format!("{withdraw_burn_bps} bps"))],
+            ),
+            RewardPoolInstruction::SetRewardGovernor {
+                min_runway_days,
+                floor_bps,
+                ceiling_bps,
+            } => (
+                "SetRewardGovernor",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![
+                    field("Min runway", format!("{min_runway_days} days")),
+                    field("Floor", format!("{floor_bps} bps")),
+                    field("Ceiling", format!("{ceiling_bps} bps")),
+                ],
+            ),
+            RewardPoolInstruction::SetSecondaryMint => (
+                "SetSecondaryMint",
+                &[("Pool", 1), ("Secondary mint", 6), ("Authority", 0)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::SetProtocolLiquidity {
+                amm_programs,
+                pol_bps,
+                min_lp_per_million,
+            } => {
+                let mut fields: Vec<SummaryField> = amm_programs
+                    .iter()
+                    .map(|amm_program| field("AMM program", amm_program.to_string()))
+                    .collect();
+                fields.push(field("POL share (bps)", pol_bps.to_string()));
+                fields.push(field("Min LP per million", min_lp_per_million.to_string()));
+                (
+                    "SetProtocolLiquidity",
+                    &[
+                        ("Pool", 1),
+                        ("Pair mint", 10),
+                        ("LP mint", 11),
+                        ("Authority", 0),
+                    ],
+                    fields,
+                )
+            }
+            RewardPoolInstruction::ContributeLiquidity {
+                swap_data,
+                deposit_data,
+                ..
+            } => (
+                "ContributeLiquidity",
+                &[("Pool", 1), ("AMM program", 10), ("Caller", 0)],
+                vec![
+                    field("Swap data", format!("{} bytes", swap_data.len())),
+                    field("Deposit data", format!("{} bytes", deposit_data.len())),
+                ],
+            ),
+            RewardPoolInstruction::SetFeeRebate {
+                rebate_bps,
+                rate_per_million,
+            } => (
+                "SetFeeRebate",
+                &[("Pool", 1), ("Governance mint", 7), ("Authority", 0)],
+                vec![
+                    field("Rebate (bps)", rebate_bps.to_string()),
+                    field("Rate per million", rate_per_million.to_string()),
+                ],
+            ),
+            RewardPoolInstruction::SetProtocolConfig {
+                admin,
+                treasury,
+                creation_fee,
+                protocol_fee_share_bps,
+            } => (
+                "SetProtocolConfig",
+                &[("Signer", 0)],
+                vec![
+                    field("Admin", admin.to_string()),
+                    field("Treasury", treasury.to_string()),
+                    field("Creation fee (lamports)", creation_fee.to_string()),
+                    field(
+                        "Protocol fee share (bps)",
+                        protocol_fee_share_bps.to_string(),
+                    ),
+                ],
+            ),
+            RewardPoolInstruction::CreatePool {
+                seed,
+                platform_fee_percentage,
+            } => (
+                "CreatePool",
+                &[("Pool", 1), ("Creator", 0), ("Mint", 2), ("Treasury", 3)],
+                vec![
+                    field("Seed", seed.to_string()),
+                    field("Platform fee", format!("{platform_fee_percentage}%")),
+                ],
+            ),
+            RewardPoolInstruction::ClaimAcrossPools { pending_counts } => (
+                "ClaimAcrossPools",
+                &[("Farmer", 0)],
+                vec![
+                    field("Pools", pending_counts.len().to_string()),
+                    field(
+                        "Pending rewards",
+                        pending_counts
+                            .iter()
+                            .map(|&count| count as u32)
+                            .sum::<u32>()
+                            .to_string(),
+                    ),
+                ],
+            ),
+            RewardPoolInstruction::IssueVoucher { voucher_id, amount } => (
+                "IssueVoucher",
+                &[("Pool", 1), ("Recipient", 6), ("Authority", 0)],
+                vec![
+                    field("Voucher", voucher_id.to_string()),
+                    field("Amount", amount.to_string()),
+                ],
+            ),
+            RewardPoolInstruction::RedeemVoucher => (
+                "RedeemVoucher",
+                &[("Pool", 1), ("Voucher", 2), ("Recipient", 6), ("Holder", 0)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::SetPayoutQueue { recovery_window } => (
+                "SetPayoutQueue",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![field("Recovery window", format!("{recovery_window}s"))],
+            ),
+            RewardPoolInstruction::ResumeWithRamp {
+                initial_epoch_cap,
+                epoch_cap_step,
+                ramp_epochs,
+            } => (
+                "ResumeWithRamp",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![
+                    field("Initial epoch cap", initial_epoch_cap.to_string()),
+                    field("Epoch cap step", epoch_cap_step.to_string()),
+                    field("Ramp epochs", ramp_epochs.to_string()),
+                ],
+            ),
+            RewardPoolInstruction::SetRecoveryAuthority {
+                recovery_authority,
+                inactivity_timeout,
+            } => (
+                "SetRecoveryAuthority",
+                &[("Pool", 1), ("Authority", 0)],
+                vec![
+                    field("Recovery authority", recovery_authority.to_string()),
+                    field("Inactivity timeout", format!("{inactivity_timeout}s")),
+                ],
+            ),
+            RewardPoolInstruction::ClaimDormantAuthority => (
+                "ClaimDormantAuthority",
+                &[("Pool", 1), ("Recovery authority", 0)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::SetRecoveryGuardians {
+                guardians,
+                threshold,
+            } => {
+                let mut fields: Vec<_> = guardians
+                    .iter()
+                    .map(|guardian| field("Guardian", guardian.to_string()))
+                    .collect();
+                if guardians.is_empty() {
+                    fields.push(field("Guardians", "none, recovery disabled"));
+                } else {
+                    fields.push(field("Threshold", threshold.to_string()));
+                }
+                (
+                    "SetRecoveryGuardians",
+                    &[("Pool", 1), ("Farmer", 0)],
+                    fields,
+                )
+            }
+            RewardPoolInstruction::InitiateFarmerRecovery { new_wallet } => (
+                "InitiateFarmerRecovery",
+                &[("Pool", 1), ("Guardian", 0)],
+                vec![
+                    field("New wallet", new_wallet.to_string()),
+                    field(
+                        "Guardian signers",
+                        (accounts.len().saturating_sub(5) + 1).to_string(),
+                    ),
+                ],
+            ),
+            RewardPoolInstruction::CancelFarmerRecovery => (
+                "CancelFarmerRecovery",
+                &[("Pool", 1), ("Farmer", 0)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::CompleteFarmerRecovery => (
+                "CompleteFarmerRecovery",
+                &[("Pool", 1), ("Payer", 0)],
+                vec![field(
+                    "Pending rewards",
+                    (accounts.len().saturating_sub(7) / 2).to_string(),
+                )],
+            ),
+            RewardPoolInstruction::SetWithdrawalFee {
+                fee_lamports,
+                exempt_small_claims,
+                small_claim_threshold,
+            } => (
+                "SetWithdrawalFee",
+                &[("Pool", 1), ("Authority", 0)],
+                if exempt_small_claims {
+                    vec![
+                        field("Fee (lamports)", fee_lamports.to_string()),
+                        field("Exempt below", small_claim_threshold.to_string()),
+                    ]
+                } else {
+                    vec![field("Fee (lamports)", fee_lamports.to_string())]
+                },
+            ),
+            RewardPoolInstruction::CollectWithdrawalFees => (
+                "CollectWithdrawalFees",
+                &[("Pool", 1), ("Destination", 3), ("Authority", 0)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::SetWithdrawalCosigner { cosigner } => (
+                "SetWithdrawalCosigner",
+                &[("Pool", 1), ("Farmer", 0)],
+                vec![field("Cosigner", cosigner.to_string())],
+            ),
+            RewardPoolInstruction::ExportSnapshot => (
+                "ExportSnapshot",
+                &[("Pool", 1), ("Exporter", 0), ("Buffer", 2)],
+                vec![field(
+                    "Stats days",
+                    accounts.len().saturating_sub(5).to_string(),
+                )],
+            ),
+            RewardPoolInstruction::SetGuildSplit { guild_share_bps } => (
+                "SetGuildSplit",
+                &[("Pool", 1), ("Farmer", 0), ("Guild vault", 3)],
+                vec![field("Guild share (bps)", guild_share_bps.to_string())],
+            ),
+            RewardPoolInstruction::SetGuildMembers { members } => (
+                "SetGuildMembers",
+                &[("Pool", 1), ("Authority", 0), ("Guild", 2)],
+                members
+                    .iter()
+                    .map(|member| {
+                        field(
+                            "Member",
+                            format!("{} ({} bps)", member.member, member.share_bps),
+                        )
+                    })
+                    .collect(),
+            ),
+            RewardPoolInstruction::LeaveGuild => (
+                "LeaveGuild",
+                &[("Pool", 1), ("Member", 0), ("Guild", 2)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::DistributeGuildRewards => (
+                "DistributeGuildRewards",
+                &[("Pool", 1), ("Payer", 0), ("Guild", 2)],
+                vec![field(
+                    "Members",
+                    accounts.len().saturating_sub(7).to_string(),
+                )],
+            ),
+            RewardPoolInstruction::CloseFeeInvoice => (
+                "CloseFeeInvoice",
+                &[("Pool", 1), ("Payer", 0), ("Invoice", 3)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::GetFeeInvoice { number } => (
+                "GetFeeInvoice",
+                &[("Pool", 0), ("Invoice", 1)],
+                vec![field("Number", number.to_string())],
+            ),
+            RewardPoolInstruction::GetRewardRate { window } => (
+                "GetRewardRate",
+                &[("Pool", 0)],
+                vec![field("Window", format!("{window}s"))],
+            ),
+            RewardPoolInstruction::ConsolidateClaimShards => (
+                "ConsolidateClaimShards",
+                &[("Pool", 1), ("Payer", 0)],
+                vec![field(
+                    "Claim shards",
+                    accounts.len().saturating_sub(5).to_string(),
+                )],
+            ),
+            RewardPoolInstruction::FundTreasury { amount } => (
+                "FundTreasury",
+                &[("Pool", 1), ("Funder", 0), ("Program treasury", 2)],
+                vec![field("Amount", amount.to_string())],
+            ),
+            RewardPoolInstruction::TakePayoutTicket => (
+                "TakePayoutTicket",
+                &[("Pool", 1), ("Farmer", 0)],
+                vec![field(
+                    "Pending rewards",
+                    accounts.len().saturating_sub(6).to_string(),
+                )],
+            ),
+            RewardPoolInstruction::SetMilestoneBonuses { milestones } => (
+                "SetMilestoneBonuses",
+                &[("Pool", 1), ("Authority", 0)],
+                milestones
+                    .iter()
+                    .map(|milestone| {
+                        field(
+                            "Milestone",
+                            format!("{} earned: {} bonus", milestone.threshold, milestone.bonus),
+                        )
+                    })
+                    .collect(),
+            ),
+            RewardPoolInstruction::CancelAction => (
+                "CancelAction",
+                &[("Pool", 1), ("Action", 2), ("Authority", 0)],
+                Vec::new(),
+            ),
+            RewardPoolInstruction::ExecuteAction => (
+                "ExecuteAction",
+                &[("Pool", 1), ("Action", 2), ("Executor", 0)],
+                Vec::new(),
+            ),
+        };
+
+    let mut summary_fields = account_fields(accounts, positions);
+    summary_fields.append(&mut fields);
+    InstructionSummary {
+        program: "Reward pool".to_string(),
+        action: action.to_string(),
+        fields: summary_fields,
+    }
+}
+
+fn parameter_fields(values: &ParameterValues) -> Vec<SummaryField> {
+    vec![
+        field(
+            "Minimum withdrawal",
+            values.minimum_withdrawal_amount.to_string(),
+        ),
+        field(
+            "Whitelist activation delay",
+            format!("{}s", values.whitelist_activation_delay),
+        ),
+        field(
+            "Maximum platform fee",
+            format!("{}%", values.max_platform_fee_percentage),
+        ),
+    ]
+}
+
+fn action_fields(action: &ScheduledAction) -> Vec<SummaryField> {
+    match action {
+        ScheduledAction::UpdatePlatformFee { new_fee_percentage } => vec![
+            field("Action", "UpdatePlatformFee"),
+            field("New platform fee", format!("{new_fee_percentage}%")),
+        ],
+        ScheduledAction::UpdateParameters { values } => {
+            let mut fields = vec![field("Action", "UpdateParameters")];
+            fields.append(&mut parameter_fields(values));
+            fields
+        }
+        ScheduledAction::SetPlatformTreasury { platform_treasury } => vec![
+            field("Action", "SetPlatformTreasury"),
+            field("New platform treasury", platform_treasury.to_string()),
+        ],
+        ScheduledAction::SetPlatformAuthority { platform_authority } => vec![
+            field("Action", "SetPlatformAuthority"),
+            field("New platform authority", platform_authority.to_string()),
+        ],
+        ScheduledAction::RotatePlatformAuthority {
+            platform_authority,
+            reason_hash,
+        } => vec![
+            field("Action", "RotatePlatformAuthority"),
+            field("New platform authority", platform_authority.to_string()),
+            field(
+                "Reason hash",
+                Hash::new_from_array(*reason_hash).to_string(),
+            ),
+        ],
+        ScheduledAction::SwapTreasury {
+            output_mint,
+            amount,
+            min_output,
+            swap_data,
+        } => vec![
+            field("Action", "SwapTreasury"),
+            field("Output mint", output_mint.to_string()),
+            field("Amount", amount.to_string()),
+            field("Min output", min_output.to_string()),
+            field("Swap data", format!("{} bytes", swap_data.len())),
+        ],
+    }
+}
+
+fn account_fields(
+    accounts: &[AccountMeta],
+    positions: &[(&'static str, usize)],
+) -> Vec<SummaryField> {
+    positions
+        .iter()
+        .filter_map(|&(label, position)| {
+            accounts
+                .get(position)
+                .map(|meta| field(label, meta.pubkey.to_string()))
+        })
+        .collect()
+}
+
+fn field(label: &'static str, value: impl Into<String>) -> SummaryField {
+    SummaryField {
+        label,
+        value: value.into(),
+    }
+}
+
+impl fmt::Display for InstructionSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.program, self.action)?;
+        for field in &self.fields {
+            writeln!(f, "  {}: {}", field.label, field.value)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Expiry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BlockHeight(height) => write!(f, "block height {height}"),
+            Self::Blockhash(blockhash) => write!(f, "about 150 blocks after {blockhash}"),
+            Self::DurableNonce(account) => write!(f, "when nonce {account} advances"),
+        }
+    }
+}
+
+impl fmt::Display for MessageSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Fee payer: {}", self.fee_payer)?;
+        writeln!(f, "Expires: {}", self.expiry)?;
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            write!(f, "#{} {}", index + 1, instruction)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_compute_budget_interface::ComputeBudgetInstruction;
+    use solana_system_interface::instruction as system_instruction;
+
+    use reward_pool::WithdrawalSplit;
+
+    use super::*;
+    use crate::{instructions, mock_rpc::pool_accounts, nonce::DurableNonce};
+
+    fn value<'a>(summary: &'a InstructionSummary, label: &str) -> Option<&'a str> {
+        summary
+            .fields
+            .iter()
+            .find(|field| field.label == label)
+            .map(|field| field.value.as_str())
+    }
+
+    #[test]
+    fn a_withdrawal_lists_its_accounts_amount_and_nonce() {
+        let accounts = pool_accounts();
+        let farmer = Pubkey::new_unique();
+        let destination = accounts.reward_account(&farmer);
+        let instruction =
+            instructions::withdraw_reward(&accounts, &farmer, "task-1", &destination, 1_500, 7, 0);
+
+        let summary = summarize_instruction(&accounts.program_id, &instruction);
+        assert_eq!(summary.program, "Reward pool");
+        assert_eq!(summary.action, "WithdrawReward");
+        let labels: Vec<_> = summary.fields.iter().map(|field| field.label).collect();
+        assert_eq!(labels, ["Pool", "Farmer", "Destination", "Amount", "Nonce"]);
+        assert_eq!(
+            value(&summary, "Pool"),
+            Some(accounts.pool.to_string().as_str())
+        );
+        assert_eq!(value(&summary, "Farmer"), Some(farmer.to_string().as_str()));
+        assert_eq!(
+            value(&summary, "Destination"),
+            Some(destination.to_string().as_str())
+        );
+        assert_eq!(value(&summary, "Amount"), Some("1500"));
+        assert_eq!(value(&summary, "Nonce"), Some("7"));
+    }
+
+    #[test]
+    fn a_sponsored_withdrawal_names_its_sponsor() {
+        let accounts = pool_accounts();
+        let farmer = Pubkey::new_unique();
+        let sponsor = Pubkey::new_unique();
+        let instruction = instructions::sponsored_withdraw_reward(
+            &accounts,
+            &farmer,
+            "task-1",
+            &accounts.reward_account(&farmer),
+            1_500,
+            7,
+            0,
+            &sponsor,
+        );
+
+        let summary = summarize_instruction(&accounts.program_id, &instruction);
+        assert_eq!(
+            value(&summary, "Sponsor"),
+            Some(sponsor.to_string().as_str())
+        );
+    }
+
+    #[test]
+    fn a_split_withdrawal_is_only_sponsored_with_a_sponsor() {
+        let accounts = pool_accounts();
+        let farmer = Pubkey::new_unique();
+        let sponsor = Pubkey::new_unique();
+        let split = [
+            WithdrawalSplit {
+                destination: Pubkey::new_unique(),
+                share_bps: 6_000,
+            },
+            WithdrawalSplit {
+                destination: Pubkey::new_unique(),
+                share_bps: 4_000,
+            },
+        ];
+        let withdraw = |sponsor| {
+            let instruction = instructions::split_withdraw_reward(
+                &accounts, &farmer, "task-1", &split, 1_500, 7, 0, sponsor,
+            );
+            summarize_instruction(&accounts.program_id, &instruction)
+        };
+
+        let summary = withdraw(None);
+        assert_eq!(value(&summary, "Sponsor"), None);
+        let shares: Vec<_> = summary
+            .fields
+            .iter()
+            .filter(|field| field.label == "Split")
+            .map(|field| field.value.clone())
+            .collect();
+        assert_eq!(
+            shares,
+            [
+                format!("{} (6000 bps)", split[0].destination),
+                format!("{} (4000 bps)", split[1].destination),
+            ]
+        );
+        let summary = withdraw(Some(&sponsor));
+        assert_eq!(
+            value(&summary, "Sponsor"),
+            Some(sponsor.to_string().as_str())
+        );
+    }
+
+    #[test]
+    fn a_claim_counts_the_pending_rewards_it_pays() {
+        let accounts = pool_accounts();
+        let farmer = Pubkey::new_unique();
+        let pending_rewards = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let instruction = instructions::claim_many(&accounts, &farmer, &pending_rewards);
+
+        let summary = summarize_instruction(&accounts.program_id, &instruction);
+        assert_eq!(summary.action, "ClaimMany");
+        assert_eq!(value(&summary, "Pending rewards"), Some("2"));
+    }
+
+    #[test]
+    fn an_unknown_instruction_tag_is_not_decoded() {
+        let accounts = pool_accounts();
+        let instruction = Instruction::new_with_bytes(accounts.program_id, &[u8::MAX], vec![]);
+
+        let summary = summarize_instruction(&accounts.program_id, &instruction);
+        assert_eq!(summary.program, "Reward pool");
+        assert_eq!(summary.action, "Unknown instruction");
+        assert!(summary.fields.is_empty());
+    }
+
+    #[test]
+    fn instructions_of_a_different_program_id_are_not_decoded() {
+        let accounts = pool_accounts();
+        let instruction = instructions::pause_pool(&accounts, &Pubkey::new_unique());
+        let other_program = Pubkey::new_unique();
+
+        let summary = summarize_instruction(&other_program, &instruction);
+        assert_eq!(summary.program, accounts.program_id.to_string());
+        assert_eq!(summary.action, "Unknown instruction");
+    }
+
+    #[test]
+    fn supporting_instructions_are_named_and_arbitrary_ones_are_flagged() {
+        let accounts = pool_accounts();
+        let owner = Pubkey::new_unique();
+        let create_ata =
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &Pubkey::new_unique(),
+                &owner,
+                &accounts.reward_mint,
+                &spl_token::id(),
+            );
+        let summary = summarize_instruction(&accounts.program_id, &create_ata);
+        assert_eq!(summary.action, "Create token account");
+        assert_eq!(
+            value(&summary, "Account"),
+            Some(accounts.reward_account(&owner).to_string().as_str())
+        );
+        assert_eq!(value(&summary, "Owner"), Some(owner.to_string().as_str()));
+
+        let memo = spl_memo::build_memo(b"payout \xff", &[]);
+        let summary = summarize_instruction(&accounts.program_id, &memo);
+        assert_eq!(value(&summary, "Text"), Some("payout \u{fffd}"));
+
+        let transfer = system_instruction::transfer(&owner, &Pubkey::new_unique(), 1);
+        let summary = summarize_instruction(&accounts.program_id, &transfer);
+        assert_eq!(
+            summary.program,
+            solana_system_interface::program::id().to_string()
+        );
+        assert_eq!(summary.action, "Unknown instruction");
+    }
+
+    #[test]
+    fn a_durable_nonce_message_never_expires_until_the_nonce_advances() {
+        let accounts = pool_accounts();
+        let payer = Pubkey::new_unique();
+        let nonce = DurableNonce::new(Pubkey::new_unique(), payer);
+        let message = Message::new(
+            &[
+                nonce.advance_instruction(),
+                instructions::pause_pool(&accounts, &payer),
+            ],
+            Some(&payer),
+        );
+
+        let summary = summarize_message(&accounts.program_id, &message, Some(100));
+        assert_eq!(summary.fee_payer, payer);
+        assert_eq!(summary.expiry, Expiry::DurableNonce(nonce.account));
+        assert_eq!(summary.instructions[0].action, "Advance nonce");
+        assert_eq!(summary.instructions[1].action, "PausePool");
+    }
+
+    #[test]
+    fn a_blockhash_message_expires_at_the_known_height_if_any() {
+        let accounts = pool_accounts();
+        let payer = Pubkey::new_unique();
+        let blockhash = Hash::new_from_array([5; 32]);
+        let message = Message::new_with_blockhash(
+            &[instructions::pause_pool(&accounts, &payer)],
+            Some(&payer),
+            &blockhash,
+        );
+
+        assert_eq!(
+            summarize_message(&accounts.program_id, &message, Some(100)).expiry,
+            Expiry::BlockHeight(100)
+        );
+        assert_eq!(
+            summarize_message(&accounts.program_id, &message, None).expiry,
+            Expiry::Blockhash(blockhash)
+        );
+    }
+
+    #[test]
+    fn rendering_is_deterministic() {
+        let accounts = pool_accounts();
+        let payer = Pubkey::new_unique();
+        let message = Message::new(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+                spl_memo::build_memo(b"hello", &[]),
+            ],
+            Some(&payer),
+        );
+
+        let summary = summarize_message(&accounts.program_id, &message, Some(42));
+        assert_eq!(
+            summary.to_string(),
+            format!(
+                "Fee payer: {payer}\n\
+                 Expires: block height 42\n\
+                 #1 Compute budget: Set compute budget\n\
+                 #2 Memo: Memo\n  Text: hello\n"
+            )
+        );
+        assert_eq!(
+            summary.to_string(),
+            summarize_message(&accounts.program_id, &message, Some(42)).to_string()
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_program::{
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, AddressLookupTableAccount, Message, VersionedMessage},
+    pubkey::Pubkey,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::{
+    error::RewardPoolClientError,
+    instructions::{self, PoolAccounts, RewardAttribution},
+    nonce::DurableNonce,
+};
+
+// Fluent transaction composer
+//
+// Instructions are collected into phases and emitted in the order the
+// runtime and wallets expect, whatever order the builder methods are called
+// in: nonce advance, compute budget, account setup, reward pool
+// instructions, memos.
+#[derive(Debug, Clone)]
+pub struct TxBuilder {
+    accounts: PoolAccounts,
+    payer: Pubkey,
+    authority: Pubkey,
+    destination_owner: Option<Pubkey>,
+    verifier: Option<Pubkey>,
+    task_memos: bool,
+    withdrawal_cosigner: Option<Pubkey>,
+    guild_vault: Option<Pubkey>,
+    stats_day: Option<i64>,
+    durable_nonce: Option<DurableNonce>,
+    compute_unit_limit: Option<u32>,
+    compute_unit_price: Option<u64>,
+    setup: Vec<Instruction>,
+    instructions: Vec<Instruction>,
+    memos: Vec<Instruction>,
+}
+
+impl TxBuilder {
+    /// Starts a transaction against `accounts`, paid and signed by `payer`
+    pub fn new(accounts: PoolAccounts, payer: Pubkey) -> Self {
+        Self {
+            accounts,
+            payer,
+            authority: payer,
+            destination_owner: None,
+            verifier: None,
+            task_memos: false,
+            withdrawal_cosigner: None,
+            guild_vault: None,
+            stats_day: None,
+            durable_nonce: None,
+            compute_unit_limit: None,
+            compute_unit_price: None,
+            setup: Vec::new(),
+            instructions: Vec::new(),
+            memos: Vec::new(),
+        }
+    }
+
+    /// Sets the signer of reward pool instructions (platform authority or
+    /// farmer) when it differs from the fee payer
+    pub fn authority(mut self, authority: Pubkey) -> Self {
+        self.authority = authority;
+        self
+    }
+
+    /// Sends withdrawals to the reward token account of `owner` instead of
+    /// the authority's own account
+    pub fn destination_owner(mut self, owner: Pubkey) -> Self {
+        self.destination_owner = Some(owner);
+        self
+    }
+
+    /// Has `verifier` co-sign recorded rewards, for pools with a verifier;
+    /// it has to sign the transaction as well
+    pub fn verifier(mut self, verifier: Pubkey) -> Self {
+        self.verifier = Some(verifier);
+        self
+    }
+
+    /// Has recorded rewards tag their transfer with the task's memo, for
+    /// accounting tools
+    pub fn task_memos(mut self) -> Self {
+        self.task_memos = true;
+        self
+    }
+
+    /// Has `cosigner` co-sign withdrawals and claims, for farmers with a
+    /// withdrawal cosigner; it has to sign the transaction as well
+    pub fn withdrawal_cosigner(mut self, cosigner: Pubkey) -> Self {
+        self.withdrawal_cosigner = Some(cosigner);
+        self
+    }
+
+    /// Pays the guild's share of claims to `guild_vault`, for farmers with a
+    /// guild split
+    pub fn guild_vault(mut self, guild_vault: Pubkey) -> Self {
+        self.guild_vault = Some(guild_vault);
+        self
+    }
+
+    /// Uses the stats account of a specific unix day instead of the one of
+    /// the local clock's current day
+    pub fn stats_day(mut self, day: i64) -> Self {
+        self.stats_day = Some(day);
+        self
+    }
+
+    /// Builds against a durable nonce: the transaction starts by advancing
+    /// the nonce and must be built with the nonce value (see
+    /// `RewardPoolClient::get_nonce`) as its blockhash. The payer still pays
+    /// the fees; the nonce authority has to sign as well when it differs.
+    pub fn with_durable_nonce(mut self, nonce: DurableNonce) -> Self {
+        self.durable_nonce = Some(nonce);
+        self
+    }
+
+    /// Requests a specific compute unit limit
+    pub fn with_compute_unit_limit(mut self, units: u32) -> Self {
+        self.compute_unit_limit = Some(units);
+        self
+    }
+
+    /// Attaches a priority fee, in micro-lamports per compute unit
+    pub fn with_compute_unit_price(mut self, micro_lamports: u64) -> Self {
+        self.compute_unit_price = Some(micro_lamports);
+        self
+    }
+
+    /// Creates the withdrawal destination token account if it does not exist
+    pub fn ensure_destination_ata(self) -> Self {
+        let owner = self.resolved_destination_owner();
+        self.ensure_ata(owner)
+    }
+
+    /// Creates the reward token account of `farmer` if it does not exist
+    pub fn ensure_reward_ata(self, farmer: Pubkey) -> Self {
+        self.ensure_ata(farmer)
+    }
+
+    /// Records a reward for `farmer`
+    pub fn record_reward(self, farmer: Pubkey, amount: u64, task_id: impl Into<String>) -> Self {
+        self.record_attributed_reward(farmer, amount, task_id, RewardAttribution::default())
+    }
+
+    /// Records a reward for `farmer` attributed to a campaign and/or platform
+    pub fn record_attributed_reward(
+        mut self,
+        farmer: Pubkey,
+        amount: u64,
+        task_id: impl Into<String>,
+        attribution: RewardAttribution,
+    ) -> Self {
+        let mut instruction = instructions::record_reward(
+            &self.accounts,
+            &self.authority,
+            &farmer,
+            amount,
+            task_id.into(),
+            attribution,
+            self.resolved_stats_day(),
+        );
+        if let Some(verifier) = &self.verifier {
+            instruction = instructions::verify_recorded_reward(instruction, verifier);
+        }
+        if self.task_memos {
+            instruction = instructions::memo_recorded_reward(instruction);
+        }
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Withdraws the authority's pending reward for `task_id`, of `amount`,
+    /// from the treasury vault to the destination token account. When the
+    /// payer is not the authority, the payer sponsors the withdrawal's rent
+    /// as well as its fees.
+    pub fn withdraw(mut self, task_id: &str, amount: u64, nonce: u64) -> Self {
+        let destination = self
+            .accounts
+            .reward_account(&self.resolved_destination_owner());
+        let mut instruction = if self.payer == self.authority {
+            instructions::withdraw_reward(
+                &self.accounts,
+                &self.authority,
+                task_id,
+                &destination,
+                amount,
+                nonce,
+                self.resolved_stats_day(),
+            )
+        } else {
+            instructions::sponsored_withdraw_reward(
+                &self.accounts,
+                &self.authority,
+                task_id,
+                &destination,
+                amount,
+                nonce,
+                self.resolved_stats_day(),
+                &self.payer,
+            )
+        };
+        if let Some(cosigner) = &self.withdrawal_cosigner {
+            instruction = instructions::cosigned_withdrawal(instruction, cosigner);
+        }
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Claims the authority's pending rewards into their reward account, at
+    /// most `MAX_CLAIM_BATCH` per call
+    pub fn claim_many(mut self, pending_rewards: &[Pubkey]) -> Self {
+        let mut instruction =
+            instructions::claim_many(&self.accounts, &self.authority, pending_rewards);
+        if let Some(guild_vault) = &self.guild_vault {
+            instruction = instructions::guild_split_claim(instruction, guild_vault);
+        }
+        if let Some(cosigner) = &self.withdrawal_cosigner {
+            instruction = instructions::cosigned_withdrawal(instruction, cosigner);
+        }
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Updates the pool's platform fee
+    pub fn update_platform_fee(mut self, new_fee_percentage: u8) -> Self {
+        let instruction =
+            instructions::update_platform_fee(&self.accounts, &self.authority, new_fee_percentage);
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Pauses the pool
+    pub fn pause_pool(mut self) -> Self {
+        let instruction = instructions::pause_pool(&self.accounts, &self.authority);
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Resumes the pool
+    pub fn resume_pool(mut self) -> Self {
+        let instruction = instructions::resume_pool(&self.accounts, &self.authority);
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Drains reward accounts delegated to the authority into `destination`.
+    /// Large batches need `build_v0` with a lookup table holding the
+    /// reward accounts.
+    pub fn sweep_delegated_rewards(
+        mut self,
+        destination: Pubkey,
+        reward_accounts: &[Pubkey],
+    ) -> Self {
+        let instruction = instructions::sweep_delegated_rewards(
+            &self.accounts,
+            &self.authority,
+            &destination,
+            reward_accounts,
+            self.resolved_stats_day(),
+        );
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Appends an arbitrary instruction after the reward pool instructions
+    pub fn instruction(mut self, instruction: Instruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Tags the transaction with an SPL memo
+    pub fn with_memo(mut self, memo: impl AsRef<str>) -> Self {
+        self.memos
+            .push(spl_memo::build_memo(memo.as_ref().as_bytes(), &[]));
+        self
+    }
+
+    /// Instructions in execution order
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let mut instructions = Vec::new();
+        if let Some(nonce) = &self.durable_nonce {
+            instructions.push(nonce.advance_instruction());
+        }
+        if let Some(units) = self.compute_unit_limit {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(units));
+        }
+        if let Some(micro_lamports) = self.compute_unit_price {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+                micro_lamports,
+            ));
+        }
+        instructions.extend(self.setup.iter().cloned());
+        instructions.extend(self.instructions.iter().cloned());
+        instructions.extend(self.memos.iter().cloned());
+        instructions
+    }
+
+    /// Compiles a ready-to-sign legacy message, accepted by every wallet.
+    /// With a durable nonce, `recent_blockhash` is the nonce value.
+    pub fn build(&self, recent_blockhash: Hash) -> Message {
+        Message::new_with_blockhash(&self.instructions(), Some(&self.payer), &recent_blockhash)
+    }
+
+    /// Compiles a ready-to-sign v0 message. Accounts found in
+    /// `lookup_tables` are referenced by index instead of being inlined,
+    /// except signers and program ids, which the runtime requires inline.
+    pub fn build_v0(
+        &self,
+        recent_blockhash: Hash,
+        lookup_tables: &[AddressLookupTableAccount],
+    ) -> Result<VersionedMessage, RewardPoolClientError> {
+        let message = v0::Message::try_compile(
+            &self.payer,
+            &self.instructions(),
+            lookup_tables,
+            recent_blockhash,
+        )?;
+        Ok(VersionedMessage::V0(message))
+    }
+
+    fn resolved_destination_owner(&self) -> Pubkey {
+        self.destination_owner.unwrap_or(self.authority)
+    }
+
+    fn resolved_stats_day(&self) -> i64 {
+        self.stats_day.unwrap_or_else(instructions::current_day)
+    }
+
+    fn ensure_ata(mut self, owner: Pubkey) -> Self {
+        let instruction = create_associated_token_account_idempotent(
+            &self.payer,
+            &owner,
+            &self.accounts.reward_mint,
+            &spl_token::id(),
+        );
+        if !self.setup.contains(&instruction) {
+            self.setup.push(instruction);
+        }
+        self
+    }
+}
@@ -0,0 +1,66 @@
+use solana_address_lookup_table_interface::state::AddressLookupTable;
+use solana_program::{message::AddressLookupTableAccount, pubkey::Pubkey};
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_transaction::versioned::VersionedTransaction;
+
+use crate::{client::RewardPoolClient, error::RewardPoolClientError, tx_builder::TxBuilder};
+
+impl RewardPoolClient {
+    /// Fetches address lookup tables so v0 messages can reference their
+    /// addresses. Tables that are missing or not lookup tables are errors.
+    pub async fn fetch_lookup_tables(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<AddressLookupTableAccount>, RewardPoolClientError> {
+        let accounts = self
+            .rpc()
+            .get_multiple_accounts_with_commitment(addresses, self.rpc().commitment())
+            .await?
+            .value;
+
+        addresses
+            .iter()
+            .zip(accounts)
+            .map(|(address, account)| {
+                let account = account.ok_or(RewardPoolClientError::AccountNotFound(*address))?;
+                let table = AddressLookupTable::deserialize(&account.data)
+                    .map_err(|_| RewardPoolClientError::InvalidAccountData(*address))?;
+                Ok(AddressLookupTableAccount {
+                    key: *address,
+                    addresses: table.addresses.to_vec(),
+                })
+            })
+            .collect()
+    }
+
+    /// Compiles a builder into a v0 transaction against the given lookup
+    /// tables and signs it with a fresh blockhash
+    pub async fn build_v0_transaction(
+        &self,
+        builder: &TxBuilder,
+        lookup_table_addresses: &[Pubkey],
+        signers: &[&dyn Signer],
+    ) -> Result<VersionedTransaction, RewardPoolClientError> {
+        let lookup_tables = self.fetch_lookup_tables(lookup_table_addresses).await?;
+        let blockhash = self.rpc().get_latest_blockhash().await?;
+        let message = builder.build_v0(blockhash, &lookup_tables)?;
+        Ok(VersionedTransaction::try_new(message, signers)?)
+    }
+
+    /// Builds, signs and sends a v0 transaction, waiting for confirmation
+    pub async fn send_v0(
+        &self,
+        builder: &TxBuilder,
+        lookup_table_addresses: &[Pubkey],
+        signers: &[&dyn Signer],
+    ) -> Result<Signature, RewardPoolClientError> {
+        let transaction = self
+            .build_v0_transaction(builder, lookup_table_addresses, signers)
+            .await?;
+        Ok(self
+            .rpc()
+            .send_and_confirm_transaction(&transaction)
+            .await?)
+    }
+}
@@ -0,0 +1,197 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use borsh::BorshDeserialize;
+use reward_pool::{
+    seeds,
+    views::{
+        FarmerPendingSummary, FeeInvoiceReport, PoolStats, RewardRateReport,
+        WithdrawalHistorySummary, MAX_PENDING_PAGE_SIZE,
+    },
+    PendingReward,
+};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_program::{instruction::Instruction, message::Message, pubkey::Pubkey};
+use solana_rpc_client_api::{
+    config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSimulateTransactionConfig},
+    filter::{Memcmp, RpcFilterType},
+};
+use solana_transaction::Transaction;
+
+use crate::{client::RewardPoolClient, error::RewardPoolClientError, instructions};
+
+impl RewardPoolClient {
+    /// Runs a view instruction in simulation and decodes its return data.
+    /// `payer` only has to exist; nothing is signed or sent.
+    pub async fn simulate_view<T: BorshDeserialize>(
+        &self,
+        instruction: Instruction,
+        payer: &Pubkey,
+    ) -> Result<T, RewardPoolClientError> {
+        let transaction = Transaction::new_unsigned(Message::new(&[instruction], Some(payer)));
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(self.rpc().commitment()),
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let result = self
+            .rpc()
+            .simulate_transaction_with_config(&transaction, config)
+            .await?
+            .value;
+
+        if let Some(error) = result.err {
+            return Err(RewardPoolClientError::Simulation(error));
+        }
+        let return_data = result
+            .return_data
+            .filter(|data| data.program_id == self.accounts().program_id.to_string())
+            .ok_or(RewardPoolClientError::InvalidReturnData)?;
+        let bytes = BASE64
+            .decode(&return_data.data.0)
+            .map_err(|_| RewardPoolClientError::InvalidReturnData)?;
+        T::try_from_slice(&bytes).map_err(|_| RewardPoolClientError::InvalidReturnData)
+    }
+
+    /// Summarizes one page of a farmer's pending rewards. The farmer is used
+    /// as the simulation fee payer.
+    pub async fn get_farmer_pending(
+        &self,
+        farmer: &Pubkey,
+        pending_accounts: &[Pubkey],
+        cursor: u32,
+        limit: u8,
+    ) -> Result<FarmerPendingSummary, RewardPoolClientError> {
+        let instruction = instructions::get_farmer_pending(
+            self.accounts(),
+            farmer,
+            pending_accounts,
+            cursor,
+            limit,
+        );
+        self.simulate_view(instruction, farmer).await
+    }
+
+    /// Reads the pool totals and lifetime instruction counts
+    pub async fn pool_stats(&self, payer: &Pubkey) -> Result<PoolStats, RewardPoolClientError> {
+        self.simulate_view(instructions::get_pool_stats(self.accounts()), payer)
+            .await
+    }
+
+    /// Reads fee invoice `number` of the pool with its seal and its chaining
+    /// to the previous invoice checked
+    pub async fn fee_invoice_report(
+        &self,
+        number: u64,
+        payer: &Pubkey,
+    ) -> Result<FeeInvoiceReport, RewardPoolClientError> {
+        self.simulate_view(
+            instructions::get_fee_invoice(self.accounts(), number),
+            payer,
+        )
+        .await
+    }
+
+    /// Reads the pool's time-weighted average reward rate over the last
+    /// `window` seconds
+    pub async fn reward_rate(
+        &self,
+        window: i64,
+        payer: &Pubkey,
+    ) -> Result<RewardRateReport, RewardPoolClientError> {
+        self.simulate_view(
+            instructions::get_reward_rate(self.accounts(), window),
+            payer,
+        )
+        .await
+    }
+
+    /// Reads a farmer's recent withdrawals, newest first. The farmer is used
+    /// as the simulation fee payer.
+    pub async fn withdrawal_history(
+        &self,
+        farmer: &Pubkey,
+    ) -> Result<WithdrawalHistorySummary, RewardPoolClientError> {
+        self.simulate_view(
+            instructions::get_withdrawal_history(self.accounts(), farmer),
+            farmer,
+        )
+        .await
+    }
+
+    /// Summarizes all of a farmer's pending rewards, walking every page
+    pub async fn farmer_pending_summary(
+        &self,
+        farmer: &Pubkey,
+    ) -> Result<FarmerPendingSummary, RewardPoolClientError> {
+        let pending_accounts = self.find_pending_reward_accounts(farmer).await?;
+        let mut total = FarmerPendingSummary {
+            farmer: *farmer,
+            pending_count: 0,
+            pending_amount: 0,
+            next_cursor: None,
+            last_recorded_at: 0,
+            last_claimed_at: 0,
+        };
+
+        // Only the page being walked is passed, keeping transactions small.
+        // A farmer without pending rewards still gets one empty page, which
+        // reports the activity times.
+        let mut pages: Vec<&[Pubkey]> = pending_accounts
+            .chunks(MAX_PENDING_PAGE_SIZE as usize)
+            .collect();
+        if pages.is_empty() {
+            pages.push(&[]);
+        }
+        for page in pages {
+            let summary = self
+                .get_farmer_pending(farmer, page, 0, MAX_PENDING_PAGE_SIZE)
+                .await?;
+            total.pending_count += summary.pending_count;
+            total.pending_amount = total.pending_amount.saturating_add(summary.pending_amount);
+            total.last_recorded_at = summary.last_recorded_at;
+            total.last_claimed_at = summary.last_claimed_at;
+        }
+
+        Ok(total)
+    }
+
+    /// Lists the pending reward accounts of a farmer in this client's pool
+    pub async fn find_pending_reward_accounts(
+        &self,
+        farmer: &Pubkey,
+    ) -> Result<Vec<Pubkey>, RewardPoolClientError> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                0,
+                farmer.as_ref(),
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let accounts = self
+            .rpc()
+            .get_program_accounts_with_config(&self.accounts().program_id, config)
+            .await?;
+
+        // Other account types may start with the same key; only keep the
+        // ones sitting at this pool's pending reward address
+        Ok(accounts
+            .into_iter()
+            .filter(|(address, account)| {
+                PendingReward::deserialize(&mut account.data.as_slice()).is_ok_and(|pending| {
+                    seeds::find_pending_reward_address_with_program_id(
+                        &self.accounts().pool,
+                        farmer,
+                        &pending.task_id,
+                        &self.accounts().program_id,
+                    )
+                    .0 == *address
+                })
+            })
+            .map(|(address, _)| address)
+            .collect())
+    }
+}
@@ -0,0 +1,721 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client_api::config::RpcSendTransactionConfig;
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+
+use crate::{
+    client::RewardPoolClient, error::RewardPoolClientError, priority_fee::PriorityFeeConfig,
+};
+
+// Retry policy for `withdraw_with_retry`
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Maximum number of submission attempts
+    pub max_attempts: u32,
+    /// Delay before the second attempt, doubled after every failure
+    pub initial_backoff: Duration,
+    /// Upper bound for the backoff delay
+    pub max_backoff: Duration,
+    /// Delay between two signature status polls
+    pub poll_interval: Duration,
+    /// Priority fee attached to every signed transaction, if any
+    pub priority_fee: Option<PriorityFeeConfig>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            poll_interval: Duration::from_millis(500),
+            priority_fee: Some(PriorityFeeConfig::default()),
+        }
+    }
+}
+
+// Final result of a withdrawal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WithdrawOutcome {
+    /// The withdrawal landed and reached the client's commitment level
+    Confirmed(Signature),
+    /// The cluster reported the transaction as already processed
+    AlreadyProcessed(Signature),
+    /// The withdrawal was rejected or could not be submitted
+    Failed(WithdrawFailure),
+}
+
+// Reason a withdrawal did not go through
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum WithdrawFailure {
+    #[error("Pool paused")]
+    PoolPaused,
+    #[error("Amount {amount} is below the minimum withdrawal of {minimum}")]
+    BelowMinimum { amount: u64, minimum: u64 },
+    #[error("Claimable pending reward of {available} does not match the {requested} requested")]
+    InsufficientBalance { available: u64, requested: u64 },
+    #[error("Destination {0} is not an active whitelisted destination")]
+    DestinationNotWhitelisted(Pubkey),
+    #[error("Destination {destination} is not owned by the payout address {payout_address}")]
+    DestinationNotPayoutAddress {
+        destination: Pubkey,
+        payout_address: Pubkey,
+    },
+    #[error("Simulation failed: {error}")]
+    Simulation {
+        error: TransactionError,
+        logs: Vec<String>,
+    },
+    #[error("Transaction failed: {0}")]
+    Transaction(TransactionError),
+    #[error("Gave up after {attempts} attempts: {last_error}")]
+    RetriesExhausted { attempts: u32, last_error: String },
+    #[error("Client error: {0}")]
+    Client(String),
+}
+
+// Predictable reason the program would reject a withdrawal
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum WithdrawProblem {
+    #[error("Pool paused")]
+    PoolPaused,
+    #[error("Amount {amount} is below the minimum withdrawal of {minimum}")]
+    BelowMinimum { amount: u64, minimum: u64 },
+    #[error("Claimable pending reward of {available} does not match the {requested} requested")]
+    InsufficientBalance { available: u64, requested: u64 },
+    #[error("Destination {0} is not an active whitelisted destination")]
+    DestinationNotWhitelisted(Pubkey),
+    #[error("Destination {destination} is not owned by the payout address {payout_address}")]
+    DestinationNotPayoutAddress {
+        destination: Pubkey,
+        payout_address: Pubkey,
+    },
+}
+
+impl From<WithdrawProblem> for WithdrawFailure {
+    fn from(problem: WithdrawProblem) -> Self {
+        match problem {
+            WithdrawProblem::PoolPaused => WithdrawFailure::PoolPaused,
+            WithdrawProblem::BelowMinimum { amount, minimum } => {
+                WithdrawFailure::BelowMinimum { amount, minimum }
+            }
+            WithdrawProblem::InsufficientBalance {
+                available,
+                requested,
+            } => WithdrawFailure::InsufficientBalance {
+                available,
+                requested,
+            },
+            WithdrawProblem::DestinationNotWhitelisted(destination) => {
+                WithdrawFailure::DestinationNotWhitelisted(destination)
+            }
+            WithdrawProblem::DestinationNotPayoutAddress {
+                destination,
+                payout_address,
+            } => WithdrawFailure::DestinationNotPayoutAddress {
+                destination,
+                payout_address,
+            },
+        }
+    }
+}
+
+impl RewardPoolClient {
+    /// Runs the checks `WithdrawReward` performs against the current
+    /// on-chain state and returns every one that would fail, without sending
+    /// anything. An empty list means the withdrawal of the farmer's pending
+    /// reward for `task_id` is expected to succeed. Whitelist and payout
+    /// address activation are checked against the local clock.
+    pub async fn preflight_withdraw(
+        &self,
+        farmer: &Pubkey,
+        task_id: &str,
+        destination: &Pubkey,
+        amount: u64,
+    ) -> Result<Vec<WithdrawProblem>, RewardPoolClientError> {
+        let mut problems = Vec::new();
+
+        let pool = self.get_pool().await?;
+        if pool.is_paused {
+            problems.push(WithdrawProblem::PoolPaused);
+        }
+
+        let minimum = self.get_parameters().await?.minimum_withdrawal_amount;
+        if amount < minimum {
+            problems.push(WithdrawProblem::BelowMinimum { amount, minimum });
+        }
+
+        // The withdrawal pays out the whole reward, once it is claimable
+        let available = self
+            .get_pending_reward(farmer, task_id)
+            .await?
+            .filter(|pending| {
+                !pending.is_withdrawn && !pending.is_challenged && !pending.awaiting_approval
+            })
+            .map_or(0, |pending| pending.amount);
+        if available != amount {
+            problems.push(WithdrawProblem::InsufficientBalance {
+                available,
+                requested: amount,
+            });
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64);
+        if let Some(whitelist) = self.get_withdrawal_whitelist(farmer).await? {
+            if !whitelist.allows(destination, now) {
+                problems.push(WithdrawProblem::DestinationNotWhitelisted(*destination));
+            }
+        }
+
+        let payout_address = self
+            .get_payout_address(farmer)
+            .await?
+            .and_then(|registration| registration.effective(now));
+        if let Some(payout_address) = payout_address {
+            let owner = self
+                .get_token_account(destination)
+                .await?
+                .map(|account| account.owner);
+            if owner != Some(payout_address) {
+                problems.push(WithdrawProblem::DestinationNotPayoutAddress {
+                    destination: *destination,
+                    payout_address,
+                });
+            }
+        }
+
+        Ok(problems)
+    }
+
+    /// Withdraws the farmer's pending reward for `task_id`, of `amount`, to
+    /// their own associated token account.
+    ///
+    /// The pool state and balance are checked and the transaction is
+    /// simulated before anything is sent. A signed transaction is rebroadcast
+    /// as-is while its blockhash is valid and only re-signed with a fresh
+    /// blockhash once it has expired without landing, so the same withdrawal
+    /// can never be executed twice.
+    pub async fn withdraw_with_retry(
+        &self,
+        farmer: &dyn Signer,
+        task_id: &str,
+        amount: u64,
+        nonce: u64,
+        config: &RetryConfig,
+    ) -> WithdrawOutcome {
+        match self
+            .check_withdrawal(&farmer.pubkey(), task_id, amount)
+            .await
+        {
+            Ok(Some(failure)) => return WithdrawOutcome::Failed(failure),
+            Ok(None) => {}
+            Err(error) => {
+                return WithdrawOutcome::Failed(WithdrawFailure::Client(error.to_string()))
+            }
+        }
+
+        let mut pending = None;
+        let mut signatures = Vec::new();
+        let mut backoff = config.initial_backoff;
+        let mut last_error = String::new();
+
+        for attempt in 1..=config.max_attempts {
+            let result = self
+                .attempt_withdrawal(
+                    farmer,
+                    task_id,
+                    amount,
+                    nonce,
+                    config,
+                    &mut pending,
+                    &mut signatures,
+                )
+                .await;
+
+            match result {
+                Ok(Some(outcome)) => return outcome,
+                Ok(None) => last_error = "blockhash expired before confirmation".to_string(),
+                Err(error) => last_error = error.to_string(),
+            }
+
+            if attempt < config.max_attempts {
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(config.max_backoff);
+            }
+        }
+
+        WithdrawOutcome::Failed(WithdrawFailure::RetriesExhausted {
+            attempts: config.max_attempts,
+            last_error,
+        })
+    }
+
+    // Validates the withdrawal against the current on-chain state
+    async fn check_withdrawal(
+        &self,
+        farmer: &Pubkey,
+        task_id: &str,
+        amount: u64,
+    ) -> Result<Option<WithdrawFailure>, RewardPoolClientError> {
+        let destination = self.accounts().reward_account(farmer);
+        let problems = self
+            .preflight_withdraw(farmer, task_id, &destination, amount)
+            .await?;
+        Ok(problems.into_iter().next().map(WithdrawFailure::from))
+    }
+
+    // Sends (or rebroadcasts) the withdrawal and waits for it to land.
+    // Returns `None` if its blockhash expired first.
+    #[allow(clippy::too_many_arguments)]
+    async fn attempt_withdrawal(
+        &self,
+        farmer: &dyn Signer,
+        task_id: &str,
+        amount: u64,
+        nonce: u64,
+        config: &RetryConfig,
+        pending: &mut Option<Transaction>,
+        signatures: &mut Vec<Signature>,
+    ) -> Result<Option<WithdrawOutcome>, RewardPoolClientError> {
+        // An earlier attempt may have landed after we stopped waiting for it
+        if let Some(outcome) = self.find_landed(signatures).await? {
+            return Ok(Some(outcome));
+        }
+
+        let transaction = match pending.take() {
+            Some(transaction) if self.is_blockhash_valid(&transaction).await? => transaction,
+            _ => {
+                let mut builder = self
+                    .tx_builder(farmer.pubkey())
+                    .ensure_destination_ata()
+                    .withdraw(task_id, amount, nonce);
+                if let Some(priority_fee) = &config.priority_fee {
+                    builder = self.with_priority_fee(builder, priority_fee).await?;
+                }
+
+                let blockhash = self.rpc().get_latest_blockhash().await?;
+                let message = builder.build(blockhash);
+                let mut transaction = Transaction::new_unsigned(message);
+                transaction.try_sign(&[farmer], blockhash)?;
+
+                let simulation = self.rpc().simulate_transaction(&transaction).await?.value;
+                if let Some(error) = simulation.err {
+                    let outcome = if error == TransactionError::AlreadyProcessed {
+                        WithdrawOutcome::AlreadyProcessed(transaction.signatures[0])
+                    } else {
+                        WithdrawOutcome::Failed(WithdrawFailure::Simulation {
+                            error,
+                            logs: simulation.logs.unwrap_or_default(),
+                        })
+                    };
+                    return Ok(Some(outcome));
+                }
+
+                signatures.push(transaction.signatures[0]);
+                transaction
+            }
+        };
+
+        let signature = transaction.signatures[0];
+        *pending = Some(transaction.clone());
+
+        let send_config = RpcSendTransactionConfig {
+            skip_preflight: true,
+            ..RpcSendTransactionConfig::default()
+        };
+        if let Err(error) = self
+            .rpc()
+            .send_transaction_with_config(&transaction, send_config)
+            .await
+        {
+            if error.get_transaction_error() == Some(TransactionError::AlreadyProcessed) {
+                return Ok(Some(WithdrawOutcome::AlreadyProcessed(signature)));
+            }
+            return Err(error.into());
+        }
+
+        loop {
+            if let Some(outcome) = self.find_landed(signatures).await? {
+                return Ok(Some(outcome));
+            }
+            if !self.is_blockhash_valid(&transaction).await? {
+                // It may still have landed in the last valid block
+                return self.find_landed(signatures).await;
+            }
+            tokio::time::sleep(config.poll_interval).await;
+        }
+    }
+
+    async fn find_landed(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Option<WithdrawOutcome>, RewardPoolClientError> {
+        if signatures.is_empty() {
+            return Ok(None);
+        }
+
+        let statuses = self.rpc().get_signature_statuses(signatures).await?.value;
+        let landed = signatures
+            .iter()
+            .zip(statuses)
+            .find_map(|(signature, status)| {
+                status
+                    .filter(|status| status.satisfies_commitment(self.rpc().commitment()))
+                    .map(|status| match status.err {
+                        None => WithdrawOutcome::Confirmed(*signature),
+                        Some(error) => WithdrawOutcome::Failed(WithdrawFailure::Transaction(error)),
+                    })
+            });
+
+        Ok(landed)
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<bool, RewardPoolClientError> {
+        Ok(self
+            .rpc()
+            .is_blockhash_valid(
+                &transaction.message.recent_blockhash,
+                self.rpc().commitment(),
+            )
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reward_pool::{
+        PendingReward, PoolAddressKind, RewardOrigin, RewardPool, MINIMUM_WITHDRAWAL_AMOUNT,
+    };
+    use serde_json::{json, Value};
+    use solana_keypair::Keypair;
+    use solana_rpc_client_api::request::RpcRequest;
+
+    use super::*;
+    use crate::mock_rpc::{account, client, no_account, pool_accounts, with_context};
+
+    const TASK_ID: &str = "task-1";
+    const AMOUNT: u64 = MINIMUM_WITHDRAWAL_AMOUNT;
+
+    fn pool(is_paused: bool) -> Value {
+        let pool = RewardPool {
+            platform_authority: Pubkey::new_from_array([2; 32]),
+            reward_mint: pool_accounts().reward_mint,
+            platform_treasury: Pubkey::new_from_array([3; 32]),
+            platform_fee_percentage: 5,
+            total_rewards_distributed: 0,
+            total_platform_fees_collected: 0,
+            is_paused,
+            bump_seed: 255,
+            address_kind: PoolAddressKind::Mint,
+            address_creator: Pubkey::default(),
+            address_seed: 0,
+            outstanding_rewards: AMOUNT,
+            last_authority_action_at: 0,
+            sequence: 0,
+        };
+        account(&reward_pool::id(), &borsh::to_vec(&pool).unwrap())
+    }
+
+    fn pending_reward(farmer: &Pubkey, is_challenged: bool) -> Value {
+        let pending = PendingReward {
+            farmer_pubkey: *farmer,
+            amount: AMOUNT,
+            task_id: TASK_ID.to_string(),
+            recorded_at: 0,
+            is_withdrawn: false,
+            campaign_id: None,
+            platform_id: None,
+            proof_hash: None,
+            is_challenged,
+            awaiting_approval: false,
+            origin: RewardOrigin::Task,
+            interest_owed: 0,
+        };
+        account(&reward_pool::id(), &borsh::to_vec(&pending).unwrap())
+    }
+
+    /// Account reads of a preflight against a pool without parameters, a
+    /// whitelist or a payout address
+    fn preflight_reads(pool: Value, pending_reward: Value) -> Vec<(RpcRequest, Value)> {
+        [
+            pool,
+            no_account(),
+            pending_reward,
+            no_account(),
+            no_account(),
+        ]
+        .into_iter()
+        .map(|value| (RpcRequest::GetAccountInfo, value))
+        .collect()
+    }
+
+    fn fast_retries(max_attempts: u32) -> RetryConfig {
+        RetryConfig {
+            max_attempts,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            poll_interval: Duration::ZERO,
+            priority_fee: None,
+        }
+    }
+
+    #[test]
+    fn every_problem_maps_to_the_matching_failure() {
+        let destination = Pubkey::new_unique();
+        let payout_address = Pubkey::new_unique();
+        let cases = [
+            (WithdrawProblem::PoolPaused, WithdrawFailure::PoolPaused),
+            (
+                WithdrawProblem::BelowMinimum {
+                    amount: 1,
+                    minimum: 2,
+                },
+                WithdrawFailure::BelowMinimum {
+                    amount: 1,
+                    minimum: 2,
+                },
+            ),
+            (
+                WithdrawProblem::InsufficientBalance {
+                    available: 3,
+                    requested: 4,
+                },
+                WithdrawFailure::InsufficientBalance {
+                    available: 3,
+                    requested: 4,
+                },
+            ),
+            (
+                WithdrawProblem::DestinationNotWhitelisted(destination),
+                WithdrawFailure::DestinationNotWhitelisted(destination),
+            ),
+            (
+                WithdrawProblem::DestinationNotPayoutAddress {
+                    destination,
+                    payout_address,
+                },
+                WithdrawFailure::DestinationNotPayoutAddress {
+                    destination,
+                    payout_address,
+                },
+            ),
+        ];
+        for (problem, failure) in cases {
+            assert_eq!(problem.to_string(), failure.to_string());
+            assert_eq!(WithdrawFailure::from(problem), failure);
+        }
+    }
+
+    #[tokio::test]
+    async fn preflight_passes_a_claimable_reward() {
+        let farmer = Pubkey::new_unique();
+        let client = client(
+            "succeeds",
+            preflight_reads(pool(false), pending_reward(&farmer, false)),
+        );
+        let destination = client.accounts().reward_account(&farmer);
+
+        let problems = client
+            .preflight_withdraw(&farmer, TASK_ID, &destination, AMOUNT)
+            .await
+            .unwrap();
+        assert_eq!(problems, vec![]);
+    }
+
+    #[tokio::test]
+    async fn preflight_reports_every_failing_check() {
+        let farmer = Pubkey::new_unique();
+        let client = client("succeeds", preflight_reads(pool(true), no_account()));
+        let destination = client.accounts().reward_account(&farmer);
+
+        let problems = client
+            .preflight_withdraw(&farmer, TASK_ID, &destination, AMOUNT - 1)
+            .await
+            .unwrap();
+        assert_eq!(
+            problems,
+            vec![
+                WithdrawProblem::PoolPaused,
+                WithdrawProblem::BelowMinimum {
+                    amount: AMOUNT - 1,
+                    minimum: MINIMUM_WITHDRAWAL_AMOUNT,
+                },
+                WithdrawProblem::InsufficientBalance {
+                    available: 0,
+                    requested: AMOUNT - 1,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn preflight_does_not_count_a_challenged_reward_as_available() {
+        let farmer = Pubkey::new_unique();
+        let client = client(
+            "succeeds",
+            preflight_reads(pool(false), pending_reward(&farmer, true)),
+        );
+        let destination = client.accounts().reward_account(&farmer);
+
+        let problems = client
+            .preflight_withdraw(&farmer, TASK_ID, &destination, AMOUNT)
+            .await
+            .unwrap();
+        assert_eq!(
+            problems,
+            vec![WithdrawProblem::InsufficientBalance {
+                available: 0,
+                requested: AMOUNT,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn preflight_fails_without_the_pool() {
+        let client = client("succeeds", vec![]);
+        let farmer = Pubkey::new_unique();
+
+        let error = client
+            .preflight_withdraw(&farmer, TASK_ID, &farmer, AMOUNT)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            RewardPoolClientError::AccountNotFound(pool) if pool == pool_accounts().pool
+        ));
+    }
+
+    #[tokio::test]
+    async fn a_failing_check_stops_the_withdrawal_before_it_is_sent() {
+        let farmer = Keypair::new();
+        let client = client(
+            "succeeds",
+            preflight_reads(pool(true), pending_reward(&farmer.pubkey(), false)),
+        );
+
+        let outcome = client
+            .withdraw_with_retry(&farmer, TASK_ID, AMOUNT, 1, &fast_retries(3))
+            .await;
+        assert_eq!(
+            outcome,
+            WithdrawOutcome::Failed(WithdrawFailure::PoolPaused)
+        );
+    }
+
+    #[tokio::test]
+    async fn a_landed_withdrawal_is_confirmed() {
+        let farmer = Keypair::new();
+        let client = client(
+            "succeeds",
+            preflight_reads(pool(false), pending_reward(&farmer.pubkey(), false)),
+        );
+
+        let outcome = client
+            .withdraw_with_retry(&farmer, TASK_ID, AMOUNT, 1, &fast_retries(3))
+            .await;
+        assert!(matches!(outcome, WithdrawOutcome::Confirmed(_)));
+    }
+
+    #[tokio::test]
+    async fn a_rejected_simulation_is_not_retried() {
+        let farmer = Keypair::new();
+        let mut mocks = preflight_reads(pool(false), pending_reward(&farmer.pubkey(), false));
+        mocks.push((
+            RpcRequest::SimulateTransaction,
+            with_context(json!({
+                "err": "InsufficientFundsForFee",
+                "logs": ["out of lamports"],
+                "accounts": null,
+                "unitsConsumed": 0,
+                "returnData": null,
+            })),
+        ));
+        let client = client("succeeds", mocks);
+
+        let outcome = client
+            .withdraw_with_retry(&farmer, TASK_ID, AMOUNT, 1, &fast_retries(3))
+            .await;
+        assert_eq!(
+            outcome,
+            WithdrawOutcome::Failed(WithdrawFailure::Simulation {
+                error: TransactionError::InsufficientFundsForFee,
+                logs: vec!["out of lamports".to_string()],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn an_already_processed_simulation_is_reported_as_such() {
+        let farmer = Keypair::new();
+        let mut mocks = preflight_reads(pool(false), pending_reward(&farmer.pubkey(), false));
+        mocks.push((
+            RpcRequest::SimulateTransaction,
+            with_context(json!({
+                "err": "AlreadyProcessed",
+                "logs": null,
+                "accounts": null,
+                "unitsConsumed": 0,
+                "returnData": null,
+            })),
+        ));
+        let client = client("succeeds", mocks);
+
+        let outcome = client
+            .withdraw_with_retry(&farmer, TASK_ID, AMOUNT, 1, &fast_retries(3))
+            .await;
+        assert!(matches!(outcome, WithdrawOutcome::AlreadyProcessed(_)));
+    }
+
+    #[tokio::test]
+    async fn retries_run_out_when_every_blockhash_expires_unlanded() {
+        let farmer = Keypair::new();
+        let mut mocks = preflight_reads(pool(false), pending_reward(&farmer.pubkey(), false));
+        // One check when the first transaction is polled, then one before
+        // and one after rebroadcasting on the second attempt
+        for _ in 0..3 {
+            mocks.push((RpcRequest::IsBlockhashValid, with_context(json!(false))));
+        }
+        let client = client("sig_not_found", mocks);
+
+        let outcome = client
+            .withdraw_with_retry(&farmer, TASK_ID, AMOUNT, 1, &fast_retries(2))
+            .await;
+        assert_eq!(
+            outcome,
+            WithdrawOutcome::Failed(WithdrawFailure::RetriesExhausted {
+                attempts: 2,
+                last_error: "blockhash expired before confirmation".to_string(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_run_out_on_a_persistent_rpc_error() {
+        let farmer = Keypair::new();
+        let mocks = preflight_reads(pool(false), pending_reward(&farmer.pubkey(), false));
+        // `isBlockhashValid` has no mocked response, so every poll errors
+        let client = client("sig_not_found", mocks);
+
+        let outcome = client
+            .withdraw_with_retry(&farmer, TASK_ID, AMOUNT, 1, &fast_retries(3))
+            .await;
+        let WithdrawOutcome::Failed(WithdrawFailure::RetriesExhausted {
+            attempts,
+            last_error,
+        }) = outcome
+        else {
+            panic!("expected the retries to run out, got {outcome:?}");
+        };
+        assert_eq!(attempts, 3);
+        assert!(!last_error.is_empty());
+    }
+}
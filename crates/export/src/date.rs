@@ -0,0 +1,110 @@
+use reward_pool::SECONDS_PER_DAY;
+
+/// Parses a `YYYY-MM-DD` UTC date or a unix timestamp into a unix timestamp
+pub fn parse_date(input: &str) -> Option<i64> {
+    if let Ok(timestamp) = input.parse::<i64>() {
+        return Some(timestamp);
+    }
+
+    let mut parts = input.splitn(3, '-');
+    let year = parts.next()?.parse::<i64>().ok()?;
+    let month = parts.next()?.parse::<u32>().ok()?;
+    let day = parts.next()?.parse::<u32>().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    Some(days_from_civil(year, month, day) * SECONDS_PER_DAY)
+}
+
+/// Formats a unix timestamp as a `YYYY-MM-DD` UTC date
+pub fn format_date(timestamp: i64) -> String {
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(SECONDS_PER_DAY));
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+// Howard Hinnant's days_from_civil / civil_from_days
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_parse_to_midnight_utc() {
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(parse_date("2024-03-01"), Some(1_709_251_200));
+        assert_eq!(parse_date("2024-02-29"), Some(1_709_164_800));
+    }
+
+    #[test]
+    fn timestamps_pass_through() {
+        assert_eq!(parse_date("1709251200"), Some(1_709_251_200));
+        assert_eq!(parse_date("-86400"), Some(-SECONDS_PER_DAY));
+    }
+
+    #[test]
+    fn out_of_range_and_malformed_dates_are_rejected() {
+        for input in [
+            "2024-13-01",
+            "2024-00-10",
+            "2024-01-32",
+            "2024-01-00",
+            "2024-01",
+            "2024/01/01",
+            "",
+        ] {
+            assert_eq!(parse_date(input), None, "{input}");
+        }
+    }
+
+    #[test]
+    fn formatting_drops_the_time_of_day() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(SECONDS_PER_DAY - 1), "1970-01-01");
+        assert_eq!(format_date(1_709_251_199), "2024-02-29");
+    }
+
+    #[test]
+    fn dates_before_the_epoch_round_trip() {
+        assert_eq!(format_date(-1), "1969-12-31");
+        assert_eq!(parse_date("1969-12-31"), Some(-SECONDS_PER_DAY));
+        assert_eq!(format_date(parse_date("1900-03-01").unwrap()), "1900-03-01");
+    }
+
+    #[test]
+    fn every_day_round_trips() {
+        let start = parse_date("1999-12-25").unwrap();
+        for day in 0..3_000 {
+            let timestamp = start + day * SECONDS_PER_DAY;
+            assert_eq!(parse_date(&format_date(timestamp)), Some(timestamp));
+        }
+    }
+}
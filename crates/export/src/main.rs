@@ -0,0 +1,159 @@
+//! Exports the rewards and withdrawals of a pool for accounting.
+//!
+//! ```text
+//! export --rpc <URL> --pool <ADDRESS> [--format csv|jsonl]
+//!        [--from <DATE>] [--to <DATE>] [--out <FILE>] [--invoices]
+//! ```
+//!
+//! Dates are `YYYY-MM-DD` (UTC) or unix timestamps; `--from` is inclusive
+//! and `--to` exclusive, so `--from 2025-01-01 --to 2025-02-01` exports
+//! January. Rows go to stdout unless `--out` is given.
+//!
+//! `--invoices` exports the pool's fee invoices instead, one row per
+//! invoice closed in the range with its seal and chaining checked.
+
+mod date;
+mod row;
+mod sink;
+
+use std::{env, error::Error, fs::File, io, process, sync::Arc};
+
+use borsh::BorshDeserialize;
+use reward_pool::{seeds, FeeInvoice, FeeLedger};
+use reward_pool_indexer::Indexer;
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+
+use date::parse_date;
+use row::InvoiceRow;
+use sink::{ExportSink, Format};
+
+const USAGE: &str = "usage: export --rpc <URL> --pool <ADDRESS> [--format csv|jsonl] \
+                     [--from <DATE>] [--to <DATE>] [--out <FILE>] [--invoices]";
+
+struct Options {
+    rpc_url: String,
+    pool: Pubkey,
+    program_id: Pubkey,
+    format: Format,
+    from: Option<i64>,
+    to: Option<i64>,
+    out: Option<String>,
+    invoices: bool,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut rpc_url = None;
+    let mut pool = None;
+    let mut program_id = reward_pool::id();
+    let mut format = Format::Csv;
+    let mut from = None;
+    let mut to = None;
+    let mut out = None;
+    let mut invoices = false;
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {flag}"));
+        match flag.as_str() {
+            "--rpc" => rpc_url = Some(value()?.clone()),
+            "--pool" => pool = Some(parse_pubkey(value()?)?),
+            "--program-id" => program_id = parse_pubkey(value()?)?,
+            "--format" => {
+                format = match value()?.as_str() {
+                    "csv" => Format::Csv,
+                    "jsonl" => Format::JsonLines,
+                    other => return Err(format!("unknown format {other}")),
+                }
+            }
+            "--from" => from = Some(parse_date(value()?).ok_or("invalid --from date")?),
+            "--to" => to = Some(parse_date(value()?).ok_or("invalid --to date")?),
+            "--out" => out = Some(value()?.clone()),
+            "--invoices" => invoices = true,
+            other => return Err(format!("unknown argument {other}")),
+        }
+    }
+
+    Ok(Options {
+        rpc_url: rpc_url.ok_or("--rpc is required")?,
+        pool: pool.ok_or("--pool is required")?,
+        program_id,
+        format,
+        from,
+        to,
+        out,
+        invoices,
+    })
+}
+
+fn parse_pubkey(input: &str) -> Result<Pubkey, String> {
+    input
+        .parse()
+        .map_err(|_| format!("invalid address {input}"))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = parse_options(&args).unwrap_or_else(|error| {
+        eprintln!("{error}\n{USAGE}");
+        process::exit(2);
+    });
+
+    let writer: Box<dyn io::Write + Send> = match &options.out {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+    let mut sink = ExportSink::new(writer, options.format, options.from, options.to);
+
+    let rpc = Arc::new(RpcClient::new(options.rpc_url.clone()));
+    if options.invoices {
+        export_invoices(&rpc, &options, &mut sink).await?;
+        sink.flush()?;
+        eprintln!("Exported {} rows", sink.rows());
+        return Ok(());
+    }
+
+    let mut indexer = Indexer::new(rpc, options.program_id, sink);
+    indexer
+        .backfill_since(&options.pool, None, options.from)
+        .await?;
+
+    let mut sink = indexer.into_sink();
+    sink.flush()?;
+    eprintln!("Exported {} rows", sink.rows());
+    Ok(())
+}
+
+// Writes every fee invoice the pool closed, oldest first
+async fn export_invoices(
+    rpc: &RpcClient,
+    options: &Options,
+    sink: &mut ExportSink,
+) -> Result<(), Box<dyn Error>> {
+    let (ledger_address, _) =
+        seeds::find_fee_ledger_address_with_program_id(&options.pool, &options.program_id);
+    // Pools that never closed an invoice have no ledger
+    let Some(ledger_account) = rpc
+        .get_account_with_commitment(&ledger_address, rpc.commitment())
+        .await?
+        .value
+    else {
+        return Ok(());
+    };
+    let ledger = FeeLedger::try_from_slice(&ledger_account.data)?;
+
+    let mut previous_seal = [0; 32];
+    for number in 0..ledger.invoice_count {
+        let (invoice_address, _) = seeds::find_fee_invoice_address_with_program_id(
+            &options.pool,
+            number,
+            &options.program_id,
+        );
+        let invoice_account = rpc.get_account(&invoice_address).await?;
+        let invoice = FeeInvoice::try_from_slice(&invoice_account.data)?;
+        sink.write_invoice(&InvoiceRow::from_invoice(&invoice, &previous_seal))?;
+        previous_seal = invoice.seal;
+    }
+    Ok(())
+}
@@ -0,0 +1,336 @@
+use reward_pool::{events::RewardPoolEvent, FeeInvoice};
+use reward_pool_indexer::{IndexedRecord, RecordData};
+use serde::Serialize;
+
+use crate::date::format_date;
+
+// One accounting line: a recorded reward, a withdrawal or a custodian sweep
+//
+// Columns are flat and the same for every kind so that CSV files load into
+// spreadsheets as-is; fields that do not apply are left empty.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub date: String,
+    pub block_time: i64,
+    pub slot: u64,
+    pub signature: String,
+    pub kind: &'static str,
+    pub pool: String,
+    pub farmer: String,
+    pub amount: u64,
+    pub platform_fee: Option<u64>,
+    pub task_id: Option<String>,
+    pub campaign_id: Option<u64>,
+    pub platform_id: Option<u64>,
+    /// Hex digest of the evidence of the work rewarded
+    pub proof_hash: Option<String>,
+    pub destination: Option<String>,
+    pub nonce: Option<u64>,
+}
+
+impl Row {
+    /// Accounting line of a record, if it is a reward, withdrawal or sweep
+    /// event with a known block time. Sweeps list the custodian as farmer.
+    pub fn from_record(record: &IndexedRecord) -> Option<Self> {
+        let RecordData::Event(event) = &record.data else {
+            return None;
+        };
+        let block_time = record.block_time?;
+        let base = Row {
+            date: format_date(block_time),
+            block_time,
+            slot: record.slot,
+            signature: record.signature.to_string(),
+            kind: "",
+            pool: String::new(),
+            farmer: String::new(),
+            amount: 0,
+            platform_fee: None,
+            task_id: None,
+            campaign_id: None,
+            platform_id: None,
+            proof_hash: None,
+            destination: None,
+            nonce: None,
+        };
+
+        match event {
+            RewardPoolEvent::RewardRecorded {
+                pool,
+                farmer,
+                task_id,
+                amount,
+                platform_fee,
+                campaign_id,
+                platform_id,
+                proof_hash,
+                ..
+            } => Some(Row {
+                kind: "reward",
+                pool: pool.to_string(),
+                farmer: farmer.to_string(),
+                amount: *amount,
+                platform_fee: Some(*platform_fee),
+                task_id: Some(task_id.clone()),
+                campaign_id: *campaign_id,
+                platform_id: *platform_id,
+                proof_hash: proof_hash.as_ref().map(|proof_hash| to_hex(proof_hash)),
+                ..base
+            }),
+            RewardPoolEvent::RewardWithdrawn {
+                pool,
+                farmer,
+                destination,
+                amount,
+                nonce,
+                ..
+            } => Some(Row {
+                kind: "withdrawal",
+                pool: pool.to_string(),
+                farmer: farmer.to_string(),
+                amount: *amount,
+                destination: Some(destination.to_string()),
+                nonce: Some(*nonce),
+                ..base
+            }),
+            RewardPoolEvent::RewardsSwept {
+                pool,
+                custodian,
+                destination,
+                total_amount,
+                ..
+            } => Some(Row {
+                kind: "sweep",
+                pool: pool.to_string(),
+                farmer: custodian.to_string(),
+                amount: *total_amount,
+                destination: Some(destination.to_string()),
+                ..base
+            }),
+            _ => None,
+        }
+    }
+}
+
+// One fee invoice of the pool, with a column per fee category
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InvoiceRow {
+    pub date: String,
+    pub pool: String,
+    pub number: u64,
+    pub epoch: u64,
+    pub start_at: i64,
+    pub end_at: i64,
+    pub start_slot: u64,
+    pub end_slot: u64,
+    pub platform_fees: u64,
+    pub withdrawal_fee_lamports: u64,
+    pub withdraw_burned: u64,
+    pub buyback_burned: u64,
+    pub fee_rebates: u64,
+    pub protocol_fees: u64,
+    /// Hex digest sealing the invoice
+    pub seal: String,
+    /// Whether the seal matches the invoice's contents
+    pub sealed: bool,
+    /// Whether the invoice chains to `previous_seal`, the seal of the
+    /// invoice before it
+    pub chained: bool,
+}
+
+impl InvoiceRow {
+    /// Accounting line of `invoice`, dated at its close
+    pub fn from_invoice(invoice: &FeeInvoice, previous_seal: &[u8; 32]) -> Self {
+        InvoiceRow {
+            date: format_date(invoice.end_at),
+            pool: invoice.pool.to_string(),
+            number: invoice.number,
+            epoch: invoice.epoch,
+            start_at: invoice.start_at,
+            end_at: invoice.end_at,
+            start_slot: invoice.start_slot,
+            end_slot: invoice.end_slot,
+            platform_fees: invoice.fees.platform_fees,
+            withdrawal_fee_lamports: invoice.fees.withdrawal_fee_lamports,
+            withdraw_burned: invoice.fees.withdraw_burned,
+            buyback_burned: invoice.fees.buyback_burned,
+            fee_rebates: invoice.fees.fee_rebates,
+            protocol_fees: invoice.fees.protocol_fees,
+            seal: to_hex(&invoice.seal),
+            sealed: invoice.is_sealed(),
+            chained: invoice.previous_seal == *previous_seal,
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use reward_pool::{instruction::v2::RewardPoolInstruction, FeeTotals};
+    use solana_program::pubkey::Pubkey;
+
+    use super::*;
+
+    const BLOCK_TIME: i64 = 1_709_251_200;
+
+    fn record(data: RecordData) -> IndexedRecord {
+        IndexedRecord {
+            signature: Default::default(),
+            slot: 42,
+            block_time: Some(BLOCK_TIME),
+            index: 0,
+            data,
+        }
+    }
+
+    fn reward_recorded(pool: Pubkey, farmer: Pubkey) -> RewardPoolEvent {
+        RewardPoolEvent::RewardRecorded {
+            pool,
+            farmer,
+            task_id: "task-1".to_string(),
+            amount: 1_000,
+            platform_fee: 50,
+            campaign_id: Some(7),
+            platform_id: None,
+            task_type: None,
+            fee_discount_bps: 0,
+            governor_factor_bps: 10_000,
+            secondary_amount: 0,
+            proof_hash: Some([0xab; 32]),
+            verifier: None,
+            attestation: None,
+            from_budget: false,
+            awaiting_approval: false,
+            last_recorded_at: BLOCK_TIME,
+            last_claimed_at: 0,
+        }
+    }
+
+    #[test]
+    fn a_recorded_reward_keeps_its_fee_and_attribution() {
+        let (pool, farmer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let row = Row::from_record(&record(RecordData::Event(reward_recorded(pool, farmer))))
+            .expect("reward row");
+
+        assert_eq!(row.kind, "reward");
+        assert_eq!(row.date, "2024-03-01");
+        assert_eq!((row.block_time, row.slot), (BLOCK_TIME, 42));
+        assert_eq!(row.pool, pool.to_string());
+        assert_eq!(row.farmer, farmer.to_string());
+        assert_eq!(row.amount, 1_000);
+        assert_eq!(row.platform_fee, Some(50));
+        assert_eq!(row.task_id.as_deref(), Some("task-1"));
+        assert_eq!((row.campaign_id, row.platform_id), (Some(7), None));
+        assert_eq!(row.proof_hash, Some("ab".repeat(32)));
+        assert_eq!((row.destination, row.nonce), (None, None));
+    }
+
+    #[test]
+    fn a_withdrawal_lists_its_destination_and_nonce() {
+        let destination = Pubkey::new_unique();
+        let event = RewardPoolEvent::RewardWithdrawn {
+            pool: Pubkey::new_unique(),
+            farmer: Pubkey::new_unique(),
+            destination,
+            amount: 950,
+            nonce: 3,
+            last_recorded_at: 0,
+            last_claimed_at: BLOCK_TIME,
+        };
+        let row = Row::from_record(&record(RecordData::Event(event))).expect("withdrawal row");
+
+        assert_eq!(row.kind, "withdrawal");
+        assert_eq!(row.amount, 950);
+        assert_eq!(row.destination, Some(destination.to_string()));
+        assert_eq!(row.nonce, Some(3));
+        assert_eq!((row.platform_fee, row.task_id), (None, None));
+    }
+
+    #[test]
+    fn a_sweep_lists_the_custodian_as_farmer() {
+        let custodian = Pubkey::new_unique();
+        let event = RewardPoolEvent::RewardsSwept {
+            pool: Pubkey::new_unique(),
+            custodian,
+            destination: Pubkey::new_unique(),
+            account_count: 4,
+            total_amount: 12_000,
+        };
+        let row = Row::from_record(&record(RecordData::Event(event))).expect("sweep row");
+
+        assert_eq!(row.kind, "sweep");
+        assert_eq!(row.farmer, custodian.to_string());
+        assert_eq!(row.amount, 12_000);
+        assert_eq!(row.nonce, None);
+    }
+
+    #[test]
+    fn other_records_have_no_row() {
+        let pool = Pubkey::new_unique();
+        let paused = record(RecordData::Event(RewardPoolEvent::PoolPaused { pool }));
+        assert_eq!(Row::from_record(&paused), None);
+
+        let instruction = record(RecordData::Instruction {
+            instruction: RewardPoolInstruction::PausePool,
+            accounts: vec![pool],
+        });
+        assert_eq!(Row::from_record(&instruction), None);
+
+        let mut undated = record(RecordData::Event(reward_recorded(pool, pool)));
+        undated.block_time = None;
+        assert_eq!(Row::from_record(&undated), None);
+    }
+
+    fn invoice(previous_seal: [u8; 32]) -> FeeInvoice {
+        let mut invoice = FeeInvoice {
+            pool: Pubkey::new_unique(),
+            number: 2,
+            epoch: 600,
+            start_slot: 1_000,
+            start_at: BLOCK_TIME - 86_400,
+            end_slot: 2_000,
+            end_at: BLOCK_TIME,
+            fees: FeeTotals {
+                platform_fees: 500,
+                ..FeeTotals::default()
+            },
+            previous_seal,
+            seal: [0; 32],
+            bump_seed: 255,
+            sequence: 0,
+        };
+        invoice.seal = invoice.compute_seal();
+        invoice
+    }
+
+    #[test]
+    fn an_invoice_row_checks_its_seal_and_chain() {
+        let previous_seal = [7; 32];
+        let invoice = invoice(previous_seal);
+        let row = InvoiceRow::from_invoice(&invoice, &previous_seal);
+
+        assert_eq!(row.date, "2024-03-01");
+        assert_eq!(row.number, 2);
+        assert_eq!(row.platform_fees, 500);
+        assert_eq!(row.seal, to_hex(&invoice.seal));
+        assert!(row.sealed);
+        assert!(row.chained);
+    }
+
+    #[test]
+    fn a_tampered_or_unchained_invoice_is_flagged() {
+        let previous_seal = [7; 32];
+        let mut tampered = invoice(previous_seal);
+        tampered.fees.platform_fees += 1;
+        let row = InvoiceRow::from_invoice(&tampered, &previous_seal);
+        assert!(!row.sealed);
+        assert!(row.chained);
+
+        let row = InvoiceRow::from_invoice(&invoice(previous_seal), &[8; 32]);
+        assert!(row.sealed);
+        assert!(!row.chained);
+    }
+}
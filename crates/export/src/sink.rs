@@ -0,0 +1,233 @@
+use std::io::{BufWriter, Write};
+
+use reward_pool_indexer::{IndexedRecord, IndexerError, RecordSink};
+use serde::Serialize;
+
+use crate::row::{InvoiceRow, Row};
+
+// Output file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    JsonLines,
+}
+
+enum Output {
+    Csv(Box<csv::Writer<Box<dyn Write + Send>>>),
+    JsonLines(BufWriter<Box<dyn Write + Send>>),
+}
+
+// Writes the rewards and withdrawals, or the fee invoices, within a block
+// time range
+pub struct ExportSink {
+    output: Output,
+    from: Option<i64>,
+    to: Option<i64>,
+    rows: usize,
+}
+
+impl ExportSink {
+    /// Exports rows with `from <= block_time < to` to `writer`
+    pub fn new(
+        writer: Box<dyn Write + Send>,
+        format: Format,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> Self {
+        let output = match format {
+            Format::Csv => Output::Csv(Box::new(csv::Writer::from_writer(writer))),
+            Format::JsonLines => Output::JsonLines(BufWriter::new(writer)),
+        };
+        Self {
+            output,
+            from,
+            to,
+            rows: 0,
+        }
+    }
+
+    /// Number of rows written so far
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn flush(&mut self) -> Result<(), IndexerError> {
+        match &mut self.output {
+            Output::Csv(writer) => writer.flush()?,
+            Output::JsonLines(writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+
+    /// Writes `row` if the invoice was closed within the range
+    pub fn write_invoice(&mut self, row: &InvoiceRow) -> Result<(), IndexerError> {
+        if !self.in_range(row.end_at) {
+            return Ok(());
+        }
+        self.serialize(row)
+    }
+
+    fn in_range(&self, block_time: i64) -> bool {
+        self.from.is_none_or(|from| block_time >= from) && self.to.is_none_or(|to| block_time < to)
+    }
+
+    fn serialize(&mut self, row: &impl Serialize) -> Result<(), IndexerError> {
+        match &mut self.output {
+            Output::Csv(writer) => writer
+                .serialize(row)
+                .map_err(|e| IndexerError::Sink(e.to_string()))?,
+            Output::JsonLines(writer) => {
+                serde_json::to_writer(&mut *writer, row)
+                    .map_err(|e| IndexerError::Sink(e.to_string()))?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+}
+
+impl RecordSink for ExportSink {
+    async fn write(&mut self, record: &IndexedRecord) -> Result<(), IndexerError> {
+        let Some(row) = Row::from_record(record) else {
+            return Ok(());
+        };
+        if !self.in_range(row.block_time) {
+            return Ok(());
+        }
+        self.serialize(&row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use reward_pool::events::RewardPoolEvent;
+    use reward_pool_indexer::RecordData;
+    use solana_program::pubkey::Pubkey;
+
+    use super::*;
+
+    // Writer whose output the test reads back once the sink is flushed
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Shared {
+        fn text(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    fn withdrawal(block_time: Option<i64>, amount: u64) -> IndexedRecord {
+        IndexedRecord {
+            signature: Default::default(),
+            slot: 1,
+            block_time,
+            index: 0,
+            data: RecordData::Event(RewardPoolEvent::RewardWithdrawn {
+                pool: Pubkey::new_unique(),
+                farmer: Pubkey::new_unique(),
+                destination: Pubkey::new_unique(),
+                amount,
+                nonce: 0,
+                last_recorded_at: 0,
+                last_claimed_at: 0,
+            }),
+        }
+    }
+
+    async fn export(format: Format, from: Option<i64>, to: Option<i64>) -> (usize, String) {
+        let output = Shared::default();
+        let mut sink = ExportSink::new(Box::new(output.clone()), format, from, to);
+        for (block_time, amount) in [
+            (Some(99), 1),
+            (Some(100), 2),
+            (Some(199), 3),
+            (Some(200), 4),
+        ] {
+            sink.write(&withdrawal(block_time, amount)).await.unwrap();
+        }
+        sink.write(&withdrawal(None, 5)).await.unwrap();
+        sink.flush().unwrap();
+        (sink.rows(), output.text())
+    }
+
+    #[tokio::test]
+    async fn rows_outside_the_range_are_skipped() {
+        let (rows, text) = export(Format::JsonLines, Some(100), Some(200)).await;
+        assert_eq!(rows, 2);
+        let amounts: Vec<u64> = text
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<serde_json::Value>(line).unwrap()["amount"]
+                    .as_u64()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(amounts, [2, 3]);
+    }
+
+    #[tokio::test]
+    async fn an_open_range_exports_every_dated_row() {
+        let (rows, text) = export(Format::JsonLines, None, None).await;
+        assert_eq!(rows, 4);
+        assert_eq!(text.lines().count(), 4);
+    }
+
+    #[tokio::test]
+    async fn csv_output_has_one_header_line() {
+        let (rows, text) = export(Format::Csv, Some(100), None).await;
+        assert_eq!(rows, 3);
+        let mut lines = text.lines();
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("date,block_time,slot,signature,kind,"));
+        assert_eq!(lines.count(), 3);
+    }
+
+    #[test]
+    fn invoices_are_filtered_by_their_close() {
+        let output = Shared::default();
+        let mut sink = ExportSink::new(Box::new(output.clone()), Format::Csv, Some(100), Some(200));
+        let row = |end_at| InvoiceRow {
+            date: String::new(),
+            pool: String::new(),
+            number: 0,
+            epoch: 0,
+            start_at: 0,
+            end_at,
+            start_slot: 0,
+            end_slot: 0,
+            platform_fees: 0,
+            withdrawal_fee_lamports: 0,
+            withdraw_burned: 0,
+            buyback_burned: 0,
+            fee_rebates: 0,
+            protocol_fees: 0,
+            seal: String::new(),
+            sealed: true,
+            chained: true,
+        };
+        for end_at in [50, 150, 250] {
+            sink.write_invoice(&row(end_at)).unwrap();
+        }
+        sink.flush().unwrap();
+        assert_eq!(sink.rows(), 1);
+        assert_eq!(output.text().lines().count(), 2);
+    }
+}
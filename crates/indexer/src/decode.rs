@@ -0,0 +1,323 @@
+use reward_pool::instruction::{v2::RewardPoolInstruction, VersionedInstruction};
+use reward_pool_parsers::decode_events;
+use solana_program::pubkey::Pubkey;
+use solana_transaction_status_client_types::{
+    EncodedConfirmedTransactionWithStatusMeta, UiInstruction, UiLoadedAddresses,
+};
+
+use crate::{
+    error::IndexerError,
+    record::{IndexedRecord, RecordData},
+};
+
+/// Decodes reward pool instruction data, whatever its encoding version
+pub fn decode_instruction(data: &[u8]) -> Option<RewardPoolInstruction> {
+    VersionedInstruction::unpack(data)
+        .ok()
+        .map(VersionedInstruction::into_latest)
+}
+
+/// Extracts every reward pool instruction and event from a fetched
+/// transaction. Failed transactions yield no records.
+pub fn decode_transaction(
+    program_id: &Pubkey,
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Result<Vec<IndexedRecord>, IndexerError> {
+    let meta = transaction.transaction.meta.as_ref();
+    if meta.is_some_and(|meta| meta.err.is_some()) {
+        return Ok(Vec::new());
+    }
+
+    let decoded = transaction
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| {
+            IndexerError::UndecodableTransaction(format!("slot {}", transaction.slot))
+        })?;
+    let signature = decoded.signatures[0];
+
+    let mut account_keys = decoded.message.static_account_keys().to_vec();
+    if let Some(loaded) =
+        meta.and_then(|meta| Option::<&UiLoadedAddresses>::from(meta.loaded_addresses.as_ref()))
+    {
+        account_keys.extend(
+            loaded
+                .writable
+                .iter()
+                .chain(&loaded.readonly)
+                .filter_map(|key| key.parse::<Pubkey>().ok()),
+        );
+    }
+    let resolve = |indexes: &[u8]| -> Vec<Pubkey> {
+        indexes
+            .iter()
+            .filter_map(|index| account_keys.get(*index as usize).copied())
+            .collect()
+    };
+
+    let inner_instructions = meta
+        .and_then(|meta| Option::<&Vec<_>>::from(meta.inner_instructions.as_ref()))
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let mut data = Vec::new();
+    for (position, instruction) in decoded.message.instructions().iter().enumerate() {
+        if account_keys.get(instruction.program_id_index as usize) == Some(program_id) {
+            if let Some(decoded) = decode_instruction(&instruction.data) {
+                data.push(RecordData::Instruction {
+                    instruction: decoded,
+                    accounts: resolve(&instruction.accounts),
+                });
+            }
+        }
+
+        let inner = inner_instructions
+            .iter()
+            .filter(|inner| inner.index as usize == position)
+            .flat_map(|inner| &inner.instructions);
+        for instruction in inner {
+            let UiInstruction::Compiled(instruction) = instruction else {
+                continue;
+            };
+            if account_keys.get(instruction.program_id_index as usize) != Some(program_id) {
+                continue;
+            }
+            let decoded = bs58::decode(&instruction.data)
+                .into_vec()
+                .ok()
+                .and_then(|bytes| decode_instruction(&bytes));
+            if let Some(decoded) = decoded {
+                data.push(RecordData::Instruction {
+                    instruction: decoded,
+                    accounts: resolve(&instruction.accounts),
+                });
+            }
+        }
+    }
+
+    let logs = meta
+        .and_then(|meta| Option::<&Vec<String>>::from(meta.log_messages.as_ref()))
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    data.extend(
+        decode_events(program_id, logs)
+            .into_iter()
+            .map(RecordData::Event),
+    );
+
+    Ok(data
+        .into_iter()
+        .enumerate()
+        .map(|(index, data)| IndexedRecord {
+            signature,
+            slot: transaction.slot,
+            block_time: transaction.block_time,
+            index: index as u32,
+            data,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use reward_pool::events::RewardPoolEvent;
+    use serde_json::{json, Value};
+    use solana_program::{
+        instruction::{AccountMeta, Instruction},
+        message::Message,
+    };
+    use solana_transaction::Transaction;
+
+    use super::*;
+
+    const PROGRAM_ID: Pubkey = reward_pool::ID;
+
+    fn encoded_transaction(transaction: &Transaction, meta: Value) -> Value {
+        let bytes = bincode::serialize(transaction).unwrap();
+        json!({
+            "slot": 5,
+            "blockTime": 100,
+            "transaction": [BASE64_STANDARD.encode(bytes), "base64"],
+            "meta": meta,
+        })
+    }
+
+    fn meta(err: Value, inner_instructions: Value, logs: Vec<String>) -> Value {
+        json!({
+            "err": err,
+            "status": if err.is_null() { json!({ "Ok": null }) } else { json!({ "Err": err }) },
+            "fee": 5_000,
+            "preBalances": [],
+            "postBalances": [],
+            "innerInstructions": inner_instructions,
+            "logMessages": logs,
+        })
+    }
+
+    fn decode(transaction: Value) -> Result<Vec<IndexedRecord>, IndexerError> {
+        decode_transaction(&PROGRAM_ID, &serde_json::from_value(transaction).unwrap())
+    }
+
+    fn pause(pool: Pubkey, authority: Pubkey) -> Instruction {
+        Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &RewardPoolInstruction::PausePool.pack(),
+            vec![
+                AccountMeta::new(authority, true),
+                AccountMeta::new(pool, false),
+            ],
+        )
+    }
+
+    fn program_data(event: &RewardPoolEvent) -> String {
+        format!(
+            "Program data: {}",
+            BASE64_STANDARD.encode(borsh::to_vec(event).unwrap())
+        )
+    }
+
+    #[test]
+    fn instructions_are_decoded_with_their_accounts() {
+        let authority = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let other_program = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &RewardPoolInstruction::PausePool.pack(),
+            vec![],
+        );
+        let unknown = Instruction::new_with_bytes(PROGRAM_ID, &[0xff], vec![]);
+        let transaction = Transaction::new_unsigned(Message::new(
+            &[other_program, pause(pool, authority), unknown],
+            Some(&authority),
+        ));
+
+        let records = decode(encoded_transaction(
+            &transaction,
+            meta(Value::Null, json!([]), vec![]),
+        ))
+        .unwrap();
+        assert_eq!(
+            records,
+            vec![IndexedRecord {
+                signature: transaction.signatures[0],
+                slot: 5,
+                block_time: Some(100),
+                index: 0,
+                data: RecordData::Instruction {
+                    instruction: RewardPoolInstruction::PausePool,
+                    accounts: vec![authority, pool],
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn cpi_instructions_follow_their_parent_and_events_come_last() {
+        let authority = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let message = Message::new(&[pause(pool, authority)], Some(&authority));
+        let program_index = message
+            .account_keys
+            .iter()
+            .position(|key| *key == PROGRAM_ID)
+            .unwrap();
+        let transaction = Transaction::new_unsigned(message);
+        let inner = json!([{
+            "index": 0,
+            "instructions": [{
+                "programIdIndex": program_index,
+                "accounts": [0],
+                "data": bs58::encode(RewardPoolInstruction::ResumePool.pack()).into_string(),
+                "stackHeight": 2,
+            }],
+        }]);
+        let logs = vec![
+            format!("Program {PROGRAM_ID} invoke [1]"),
+            program_data(&RewardPoolEvent::PoolPaused { pool }),
+            format!("Program {PROGRAM_ID} success"),
+        ];
+
+        let records = decode(encoded_transaction(
+            &transaction,
+            meta(Value::Null, inner, logs),
+        ))
+        .unwrap();
+        let data: Vec<_> = records.iter().map(|record| &record.data).collect();
+        assert_eq!(
+            data,
+            [
+                &RecordData::Instruction {
+                    instruction: RewardPoolInstruction::PausePool,
+                    accounts: vec![authority, pool],
+                },
+                &RecordData::Instruction {
+                    instruction: RewardPoolInstruction::ResumePool,
+                    accounts: vec![authority],
+                },
+                &RecordData::Event(RewardPoolEvent::PoolPaused { pool }),
+            ]
+        );
+        let indexes: Vec<_> = records.iter().map(|record| record.index).collect();
+        assert_eq!(indexes, [0, 1, 2]);
+        assert_eq!(records[2].kind(), "event");
+        assert_eq!(records[2].to_json()["data"]["name"], json!("PoolPaused"));
+        assert_eq!(records[0].to_json()["data"]["name"], json!("PausePool"));
+    }
+
+    #[test]
+    fn a_failed_transaction_yields_no_records() {
+        let authority = Pubkey::new_unique();
+        let transaction = Transaction::new_unsigned(Message::new(
+            &[pause(Pubkey::new_unique(), authority)],
+            Some(&authority),
+        ));
+
+        let records = decode(encoded_transaction(
+            &transaction,
+            meta(json!("AccountInUse"), json!([]), vec![]),
+        ))
+        .unwrap();
+        assert_eq!(records, vec![]);
+    }
+
+    #[test]
+    fn an_undecodable_transaction_is_an_error() {
+        let transaction = json!({
+            "slot": 5,
+            "blockTime": null,
+            "transaction": ["bm90IGEgdHJhbnNhY3Rpb24=", "base64"],
+            "meta": null,
+        });
+
+        assert!(matches!(
+            decode(transaction),
+            Err(IndexerError::UndecodableTransaction(slot)) if slot == "slot 5"
+        ));
+    }
+
+    #[test]
+    fn every_instruction_encoding_decodes_to_the_latest_form() {
+        let v2 = RewardPoolInstruction::PausePool.pack();
+        let v1 = VersionedInstruction::V1(
+            reward_pool::instruction::v1::RewardPoolInstruction::PausePool,
+        )
+        .pack();
+
+        assert_eq!(
+            decode_instruction(&v2),
+            Some(RewardPoolInstruction::PausePool)
+        );
+        assert_eq!(
+            decode_instruction(&v1),
+            Some(RewardPoolInstruction::PausePool)
+        );
+        assert_eq!(
+            decode_instruction(&v1[1..]),
+            Some(RewardPoolInstruction::PausePool)
+        );
+        assert_eq!(decode_instruction(&[]), None);
+        assert_eq!(decode_instruction(&[0xf2, 0xff]), None);
+    }
+}
@@ -0,0 +1,22 @@
+use solana_rpc_client_api::client_error::Error as RpcClientError;
+
+// Indexer errors
+#[derive(thiserror::Error, Debug)]
+pub enum IndexerError {
+    #[error("RPC error: {0}")]
+    Rpc(Box<RpcClientError>),
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+    #[error("Undecodable transaction: {0}")]
+    UndecodableTransaction(String),
+    #[error("Sink error: {0}")]
+    Sink(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<RpcClientError> for IndexerError {
+    fn from(e: RpcClientError) -> Self {
+        IndexerError::Rpc(Box::new(e))
+    }
+}
@@ -0,0 +1,145 @@
+use std::{sync::Arc, time::Duration};
+
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client::{
+    nonblocking::rpc_client::RpcClient, rpc_client::GetConfirmedSignaturesForAddress2Config,
+};
+use solana_rpc_client_api::config::RpcTransactionConfig;
+use solana_signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+
+use crate::{decode::decode_transaction, error::IndexerError, sink::RecordSink};
+
+/// Page size of `getSignaturesForAddress`
+const SIGNATURE_PAGE_SIZE: usize = 1000;
+
+// Fetches program transactions and feeds their decoded records to a sink
+pub struct Indexer<S> {
+    rpc: Arc<RpcClient>,
+    program_id: Pubkey,
+    sink: S,
+}
+
+impl<S: RecordSink> Indexer<S> {
+    pub fn new(rpc: Arc<RpcClient>, program_id: Pubkey, sink: S) -> Self {
+        Self {
+            rpc,
+            program_id,
+            sink,
+        }
+    }
+
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+
+    /// Indexes every transaction touching `address` after `until` (or since
+    /// the beginning of history), oldest first. Returns the newest signature
+    /// seen, to resume from.
+    pub async fn backfill(
+        &mut self,
+        address: &Pubkey,
+        until: Option<Signature>,
+    ) -> Result<Option<Signature>, IndexerError> {
+        self.backfill_since(address, until, None).await
+    }
+
+    /// Like [`Indexer::backfill`], but stops walking back through history
+    /// at the first transaction older than `min_block_time`
+    pub async fn backfill_since(
+        &mut self,
+        address: &Pubkey,
+        until: Option<Signature>,
+        min_block_time: Option<i64>,
+    ) -> Result<Option<Signature>, IndexerError> {
+        let mut signatures = Vec::new();
+        let mut before = None;
+
+        'pages: loop {
+            let page = self
+                .rpc
+                .get_signatures_for_address_with_config(
+                    address,
+                    GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until,
+                        limit: Some(SIGNATURE_PAGE_SIZE),
+                        commitment: Some(self.rpc.commitment()),
+                    },
+                )
+                .await?;
+            let page_len = page.len();
+
+            for status in page {
+                if min_block_time
+                    .zip(status.block_time)
+                    .is_some_and(|(min, block_time)| block_time < min)
+                {
+                    break 'pages;
+                }
+                let signature = status
+                    .signature
+                    .parse::<Signature>()
+                    .map_err(|_| IndexerError::InvalidSignature(status.signature.clone()))?;
+                if status.err.is_none() {
+                    signatures.push(signature);
+                }
+                before = Some(signature);
+            }
+
+            if page_len < SIGNATURE_PAGE_SIZE {
+                break;
+            }
+        }
+
+        let newest = signatures.first().copied().or(until);
+        for signature in signatures.iter().rev() {
+            self.index_transaction(signature).await?;
+        }
+
+        Ok(newest)
+    }
+
+    /// Backfills from `until`, then keeps polling for new transactions
+    pub async fn follow(
+        &mut self,
+        address: &Pubkey,
+        mut until: Option<Signature>,
+        poll_interval: Duration,
+    ) -> Result<(), IndexerError> {
+        loop {
+            until = self.backfill(address, until).await?;
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Fetches, decodes and writes a single transaction. Returns the number
+    /// of records written.
+    pub async fn index_transaction(
+        &mut self,
+        signature: &Signature,
+    ) -> Result<usize, IndexerError> {
+        let transaction = self
+            .rpc
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(self.rpc.commitment()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await?;
+
+        let records = decode_transaction(&self.program_id, &transaction)?;
+        for record in &records {
+            self.sink.write(record).await?;
+        }
+
+        Ok(records.len())
+    }
+}
@@ -0,0 +1,18 @@
+//! Rebuilds reward pool history from chain data.
+//!
+//! [`Indexer`] backfills and follows the transactions of a program address,
+//! [`decode`] turns them into typed [`IndexedRecord`]s (instructions and
+//! emitted events) and a [`RecordSink`] stores them.
+
+pub mod decode;
+pub mod error;
+pub mod indexer;
+pub mod record;
+pub mod sink;
+
+pub use error::IndexerError;
+pub use indexer::Indexer;
+pub use record::{IndexedRecord, RecordData};
+#[cfg(feature = "postgres")]
+pub use sink::PostgresSink;
+pub use sink::{ChannelSink, RecordSink, StdoutSink};
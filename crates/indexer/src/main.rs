@@ -0,0 +1,37 @@
+//! Prints reward pool history as JSON lines.
+//!
+//! Usage: `reward-pool-indexer [RPC_URL] [--follow]`. The program id is read
+//! from `PROGRAM_ID` and defaults to the canonical deployment.
+
+use std::{env, sync::Arc, time::Duration};
+
+use reward_pool_indexer::{Indexer, IndexerError, StdoutSink};
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+
+const DEFAULT_RPC_URL: &str = "http://127.0.0.1:8899";
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[tokio::main]
+async fn main() -> Result<(), IndexerError> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let follow = args.iter().any(|arg| arg == "--follow");
+    let rpc_url = args
+        .iter()
+        .find(|arg| !arg.starts_with("--"))
+        .cloned()
+        .unwrap_or_else(|| DEFAULT_RPC_URL.to_string());
+    let program_id = env::var("PROGRAM_ID")
+        .ok()
+        .and_then(|id| id.parse::<Pubkey>().ok())
+        .unwrap_or(reward_pool::id());
+
+    let rpc = Arc::new(RpcClient::new(rpc_url));
+    let mut indexer = Indexer::new(rpc, program_id, StdoutSink::new());
+
+    if follow {
+        indexer.follow(&program_id, None, POLL_INTERVAL).await
+    } else {
+        indexer.backfill(&program_id, None).await.map(|_| ())
+    }
+}
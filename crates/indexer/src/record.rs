@@ -0,0 +1,71 @@
+use reward_pool::{events::RewardPoolEvent, instruction::v2::RewardPoolInstruction};
+use reward_pool_parsers::{
+    json::{split_variant, to_json},
+    Event,
+};
+use serde_json::{json, Value};
+use solana_program::pubkey::Pubkey;
+use solana_signature::Signature;
+
+// A decoded piece of reward pool history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedRecord {
+    pub signature: Signature,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// Position of the record within its transaction
+    pub index: u32,
+    pub data: RecordData,
+}
+
+// Record payloads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordData {
+    /// A reward pool instruction, top-level or invoked through CPI
+    Instruction {
+        instruction: RewardPoolInstruction,
+        accounts: Vec<Pubkey>,
+    },
+    /// An event emitted by the program
+    Event(RewardPoolEvent),
+}
+
+impl IndexedRecord {
+    /// `"instruction"` or `"event"`
+    pub fn kind(&self) -> &'static str {
+        match self.data {
+            RecordData::Instruction { .. } => "instruction",
+            RecordData::Event(_) => "event",
+        }
+    }
+
+    /// JSON rendering of the record payload
+    pub fn payload(&self) -> Value {
+        match &self.data {
+            RecordData::Instruction {
+                instruction,
+                accounts,
+            } => {
+                let (name, args) = split_variant(to_json(instruction));
+                json!({
+                    "name": name,
+                    "args": args,
+                    "accounts": accounts.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                })
+            }
+            RecordData::Event(event) => json!(Event::from(event)),
+        }
+    }
+
+    /// JSON rendering of the whole record
+    pub fn to_json(&self) -> Value {
+        json!({
+            "signature": self.signature.to_string(),
+            "slot": self.slot,
+            "block_time": self.block_time,
+            "index": self.index,
+            "kind": self.kind(),
+            "data": self.payload(),
+        })
+    }
+}
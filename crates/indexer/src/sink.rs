@@ -0,0 +1,140 @@
+use std::future::Future;
+
+use tokio::{
+    io::{AsyncWriteExt, Stdout},
+    sync::mpsc,
+};
+
+use crate::{error::IndexerError, record::IndexedRecord};
+
+// Destination of indexed records
+pub trait RecordSink: Send {
+    /// Stores one record. Records are written in chain order and a record
+    /// may be written again after a restart, so sinks should be idempotent
+    /// on `(signature, index)`.
+    fn write(
+        &mut self,
+        record: &IndexedRecord,
+    ) -> impl Future<Output = Result<(), IndexerError>> + Send;
+}
+
+// Writes records to stdout as JSON lines
+pub struct StdoutSink {
+    stdout: Stdout,
+}
+
+impl StdoutSink {
+    pub fn new() -> Self {
+        Self {
+            stdout: tokio::io::stdout(),
+        }
+    }
+}
+
+impl Default for StdoutSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecordSink for StdoutSink {
+    async fn write(&mut self, record: &IndexedRecord) -> Result<(), IndexerError> {
+        let mut line = record.to_json().to_string();
+        line.push('\n');
+        self.stdout.write_all(line.as_bytes()).await?;
+        self.stdout.flush().await?;
+        Ok(())
+    }
+}
+
+// Forwards records to an in-process consumer
+pub struct ChannelSink {
+    sender: mpsc::Sender<IndexedRecord>,
+}
+
+impl ChannelSink {
+    pub fn new(sender: mpsc::Sender<IndexedRecord>) -> Self {
+        Self { sender }
+    }
+
+    /// Creates a sink and the receiving end of its channel
+    pub fn channel(capacity: usize) -> (Self, mpsc::Receiver<IndexedRecord>) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        (Self::new(sender), receiver)
+    }
+}
+
+impl RecordSink for ChannelSink {
+    async fn write(&mut self, record: &IndexedRecord) -> Result<(), IndexerError> {
+        self.sender
+            .send(record.clone())
+            .await
+            .map_err(|_| IndexerError::Sink("channel closed".to_string()))
+    }
+}
+
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresSink;
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use tokio_postgres::Client;
+
+    use super::RecordSink;
+    use crate::{error::IndexerError, record::IndexedRecord};
+
+    const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS reward_pool_records (
+        signature TEXT NOT NULL,
+        record_index INTEGER NOT NULL,
+        slot BIGINT NOT NULL,
+        block_time BIGINT,
+        kind TEXT NOT NULL,
+        data JSONB NOT NULL,
+        PRIMARY KEY (signature, record_index)
+    )";
+
+    const INSERT_RECORD: &str = "INSERT INTO reward_pool_records
+        (signature, record_index, slot, block_time, kind, data)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (signature, record_index) DO NOTHING";
+
+    // Stores records in the `reward_pool_records` table
+    pub struct PostgresSink {
+        client: Client,
+    }
+
+    impl PostgresSink {
+        /// Wraps a connected client, creating the records table if needed
+        pub async fn new(client: Client) -> Result<Self, IndexerError> {
+            client
+                .batch_execute(CREATE_TABLE)
+                .await
+                .map_err(sink_error)?;
+            Ok(Self { client })
+        }
+    }
+
+    impl RecordSink for PostgresSink {
+        async fn write(&mut self, record: &IndexedRecord) -> Result<(), IndexerError> {
+            self.client
+                .execute(
+                    INSERT_RECORD,
+                    &[
+                        &record.signature.to_string(),
+                        &(record.index as i32),
+                        &(record.slot as i64),
+                        &record.block_time,
+                        &record.kind(),
+                        &record.payload(),
+                    ],
+                )
+                .await
+                .map_err(sink_error)?;
+            Ok(())
+        }
+    }
+
+    fn sink_error(e: tokio_postgres::Error) -> IndexerError {
+        IndexerError::Sink(e.to_string())
+    }
+}
@@ -0,0 +1,1376 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use solana_pubkey::Pubkey;
+
+use crate::{INSTRUCTION_TAG_V1, INSTRUCTION_TAG_V2};
+
+// Instruction decoding for wallet UIs
+//
+// Wallets cannot pull in the program crate, so the instruction wire format
+// is decoded here by hand: a namespace tag byte (or none, for legacy V1
+// payloads) followed by the Borsh encoding of the instruction. Values
+// borrow from the instruction data and amounts are raw base units.
+
+// Accounts of an unsponsored `WithdrawReward` to a single destination: 19
+// up to the cosigner slot, then the payout queue, the farmer's payout
+// ticket, the withdrawal ramp, the interest policy, the bonus vault, the
+// pending reward withdrawn and the record of the nonce
+const WITHDRAW_ACCOUNTS: usize = 26;
+
+// Labelled value shown to the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayValue<'a> {
+    Address(Pubkey),
+    /// Token amount in base units
+    Amount(u64),
+    Percentage(u8),
+    Number(u64),
+    Text(&'a str),
+    /// 32-byte digest, shown as hex
+    Hash([u8; 32]),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayField<'a> {
+    pub label: &'static str,
+    pub value: DisplayValue<'a>,
+}
+
+// Decoded instruction, ready to be rendered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayInstruction<'a> {
+    /// Human-readable action, e.g. `Withdraw reward`
+    pub action: &'static str,
+    /// Fields in display order: pool first, then amount and recipient when
+    /// the instruction has them, then the remaining arguments
+    pub fields: Vec<DisplayField<'a>>,
+}
+
+impl<'a> DisplayInstruction<'a> {
+    /// Value of the field with `label`, if present
+    pub fn field(&self, label: &str) -> Option<DisplayValue<'a>> {
+        self.fields
+            .iter()
+            .find(|field| field.label == label)
+            .map(|field| field.value)
+    }
+
+    /// Reward pool the instruction acts on
+    pub fn pool(&self) -> Option<Pubkey> {
+        match self.field("Pool") {
+            Some(DisplayValue::Address(pool)) => Some(pool),
+            _ => None,
+        }
+    }
+
+    /// Token amount the instruction moves or records
+    pub fn amount(&self) -> Option<u64> {
+        match self.field("Amount") {
+            Some(DisplayValue::Amount(amount)) => Some(amount),
+            _ => None,
+        }
+    }
+
+    /// Account or farmer receiving the funds
+    pub fn recipient(&self) -> Option<Pubkey> {
+        match self.field("Recipient") {
+            Some(DisplayValue::Address(recipient)) => Some(recipient),
+            _ => None,
+        }
+    }
+}
+
+/// Decodes reward pool instruction data into labelled fields. `accounts`
+/// are the instruction's account keys in order. Returns `None` if the data
+/// is not a valid instruction, exactly when the program would reject it.
+pub fn decode_for_display<'a>(
+    data: &'a [u8],
+    accounts: &[Pubkey],
+) -> Option<DisplayInstruction<'a>> {
+    let (&tag, rest) = data.split_first()?;
+    let (v2, payload) = match tag {
+        INSTRUCTION_TAG_V1 => (false, rest),
+        INSTRUCTION_TAG_V2 => (true, rest),
+        _ => (false, data),
+    };
+
+    let mut reader = Reader { data: payload };
+    let variant = reader.u8()?;
+    let mut display = Builder {
+        accounts,
+        fields: Vec::new(),
+    };
+
+    let action = match (variant, v2) {
+        (0, _) => {
+            display.account("Pool", 1);
+            display.value("Fee", DisplayValue::Percentage(reader.u8()?));
+            display.account("Authority", 0);
+            display.account("Mint", 2);
+            display.account("Treasury", 3);
+            if v2 {
+                if let Some(name) = reader.option_string()? {
+                    display.value("Name", DisplayValue::Text(name));
+                }
+                if let Some(uri) = reader.option_string()? {
+                    display.value("URI", DisplayValue::Text(uri));
+                }
+            }
+            "Initialize pool"
+        }
+        (1, _) => {
+            display.account("Pool", 1);
+            display.value("Amount", DisplayValue::Amount(reader.u64()?));
+            display.value("Recipient", DisplayValue::Address(reader.pubkey()?));
+            display.value("Task", DisplayValue::Text(reader.string()?));
+            if v2 {
+                if let Some(campaign_id) = reader.option_u64()? {
+                    display.value("Campaign", DisplayValue::Number(campaign_id));
+                }
+                if let Some(platform_id) = reader.option_u64()? {
+                    display.value("Platform", DisplayValue::Number(platform_id));
+                }
+                if let Some(proof_hash) = reader.option_hash()? {
+                    display.value("Proof", DisplayValue::Hash(proof_hash));
+                }
+                if let Some(task_type) = reader.option_u32()? {
+                    display.value("Task type", DisplayValue::Number(task_type.into()));
+                }
+                let secondary_amount = reader.u64()?;
+                if secondary_amount > 0 {
+                    display.value("Secondary amount", DisplayValue::Amount(secondary_amount));
+                }
+                if let Some(idempotency_key) = reader.option_hash()? {
+                    display.value("Idempotency key", DisplayValue::Hash(idempotency_key));
+                }
+            }
+            for position in 40..accounts.len() {
+                display.account("Co-signer", position);
+            }
+            "Record reward"
+        }
+        (2, _) => {
+            display.account("Pool", 1);
+            display.value("Amount", DisplayValue::Amount(reader.u64()?));
+            display.account("Recipient", 3);
+            display.account("Farmer", 0);
+            display.value("Nonce", DisplayValue::Number(reader.u64()?));
+            let mut further_destinations = 0;
+            if v2 {
+                match reader.u8()? {
+                    0 => {}
+                    1 => {
+                        let count = reader.u32()? as usize;
+                        for _ in 0..count {
+                            display.value("Split to", DisplayValue::Address(reader.pubkey()?));
+                            display
+                                .value("Share (bps)", DisplayValue::Number(reader.u16()?.into()));
+                        }
+                        further_destinations = count.saturating_sub(1);
+                    }
+                    _ => return None,
+                }
+            }
+            // Further split destinations take the sponsor's place when
+            // there is none, and the trailing accounts come last
+            if accounts.len() > WITHDRAW_ACCOUNTS + further_destinations {
+                display.account("Sponsor", 19);
+            }
+            "Withdraw reward"
+        }
+        (3, _) => {
+            display.account("Pool", 1);
+            display.value("New fee", DisplayValue::Percentage(reader.u8()?));
+            "Update platform fee"
+        }
+        (4, _) => {
+            display.account("Pool", 1);
+            "Pause pool"
+        }
+        (5, _) => {
+            display.account("Pool", 1);
+            "Resume pool"
+        }
+        (6, true) => {
+            display.account("Pool", 0);
+            display.value("Farmer", DisplayValue::Address(reader.pubkey()?));
+            reader.u32()?;
+            reader.u8()?;
+            "Get farmer pending rewards"
+        }
+        (7, true) => {
+            display.account("Pool", 0);
+            "Get pool stats"
+        }
+        (38, true) => {
+            display.account("Pool", 0);
+            display.value("Farmer", DisplayValue::Address(reader.pubkey()?));
+            "Get withdrawal history"
+        }
+        (8, true) => {
+            display.account("Pool", 1);
+            display.account("Recipient", 2);
+            display.account("Custodian", 0);
+            display.value(
+                "Reward accounts",
+                DisplayValue::Number(accounts.len().saturating_sub(7) as u64),
+            );
+            "Sweep delegated rewards"
+        }
+        (9, true) => {
+            display.account("Pool", 1);
+            display.value("Destination", DisplayValue::Address(reader.pubkey()?));
+            display.account("Farmer", 0);
+            "Add withdrawal destination"
+        }
+        (10, true) => {
+            display.account("Pool", 1);
+            display.value("Destination", DisplayValue::Address(reader.pubkey()?));
+            display.account("Farmer", 0);
+            "Remove withdrawal destination"
+        }
+        (11, true) => {
+            display.account("Pool", 1);
+            let approver_count = reader.u32()?;
+            for _ in 0..approver_count {
+                display.value("Approver", DisplayValue::Address(reader.pubkey()?));
+            }
+            display.value(
+                "Required approvals",
+                DisplayValue::Number(reader.u8()?.into()),
+            );
+            display.value("Approval threshold", DisplayValue::Amount(reader.u64()?));
+            display.value(
+                "Execution delay (seconds)",
+                DisplayValue::Number(reader.u64()?),
+            );
+            "Configure treasury governance"
+        }
+        (12, true) => {
+            display.account("Pool", 1);
+            display.value("Amount", DisplayValue::Amount(reader.u64()?));
+            display.account("Recipient", 4);
+            display.account("Proposer", 0);
+            "Propose treasury withdrawal"
+        }
+        (13, true) => {
+            display.account("Pool", 1);
+            display.account("Proposal", 3);
+            display.account("Approver", 0);
+            "Approve treasury withdrawal"
+        }
+        (14, true) => {
+            display.account("Pool", 1);
+            display.account("Recipient", 5);
+            display.account("Proposal", 3);
+            "Execute treasury withdrawal"
+        }
+        (15, true) => {
+            display.account("Pool", 1);
+            display.account("New upgrade authority", 8);
+            "Set program upgrade authority"
+        }
+        (16, true) => {
+            display.account("Pool", 1);
+            "Remove program upgrade authority"
+        }
+        (17, true) => {
+            display.account("Pool", 1);
+            display.value("Minimum withdrawal", DisplayValue::Amount(reader.u64()?));
+            display.value(
+                "Whitelist delay (seconds)",
+                DisplayValue::Number(reader.u64()?),
+            );
+            display.value("Maximum fee", DisplayValue::Percentage(reader.u8()?));
+            "Update pool parameters"
+        }
+        (18, true) => {
+            display.account("Pool", 1);
+            let guardian_count = reader.u32()?;
+            for _ in 0..guardian_count {
+                display.value("Guardian", DisplayValue::Address(reader.pubkey()?));
+            }
+            "Set pool guardians"
+        }
+        (19, true) => {
+            display.account("Pool", 1);
+            display.value("Action id", DisplayValue::Number(reader.u64()?));
+            display.value("ETA", DisplayValue::Number(reader.u64()?));
+            match reader.u8()? {
+                0 => {
+                    display.value("Action", DisplayValue::Text("Update platform fee"));
+                    display.value("New fee", DisplayValue::Percentage(reader.u8()?));
+                }
+                1 => {
+                    display.value("Action", DisplayValue::Text("Update pool parameters"));
+                    display.value("Minimum withdrawal", DisplayValue::Amount(reader.u64()?));
+                    display.value(
+                        "Whitelist delay (seconds)",
+                        DisplayValue::Number(reader.u64()?),
+                    );
+                    display.value("Maximum fee", DisplayValue::Percentage(reader.u8()?));
+                }
+                2 => {
+                    display.value("Action", DisplayValue::Text("Set platform treasury"));
+                    display.value("New treasury", DisplayValue::Address(reader.pubkey()?));
+                }
+                3 => {
+                    display.value("Action", DisplayValue::Text("Set platform authority"));
+                    display.value("New authority", DisplayValue::Address(reader.pubkey()?));
+                }
+                4 => {
+                    display.value("Action", DisplayValue::Text("Swap treasury"));
+                    display.value("Output mint", DisplayValue::Address(reader.pubkey()?));
+                    display.value("Amount", DisplayValue::Amount(reader.u64()?));
+                    display.value("Min output", DisplayValue::Number(reader.u64()?));
+                    let swap_data = reader.bytes()?;
+                    display.value(
+                        "Swap data bytes",
+                        DisplayValue::Number(swap_data.len() as u64),
+                    );
+                }
+                5 => {
+                    display.value("Action", DisplayValue::Text("Rotate platform authority"));
+                    display.value("New authority", DisplayValue::Address(reader.pubkey()?));
+                    display.value("Reason hash", DisplayValue::Hash(reader.take::<32>()?));
+                }
+                _ => return None,
+            }
+            "Schedule admin action"
+        }
+        (20, true) => {
+            display.account("Pool", 1);
+            display.account("Action", 2);
+            "Cancel admin action"
+        }
+        (21, true) => {
+            display.account("Pool", 1);
+            display.account("Action", 2);
+            "Execute admin action"
+        }
+        (22, true) => {
+            display.account("Pool", 1);
+            let parameter = match reader.u8()? {
+                0 => "Minimum withdrawal",
+                1 => "Whitelist delay",
+                2 => "Maximum fee",
+                _ => return None,
+            };
+            display.value("Parameter", DisplayValue::Text(parameter));
+            "Roll back pool parameter"
+        }
+        (23, true) => {
+            display.value(
+                "Pools",
+                DisplayValue::Number(accounts.len().saturating_sub(1) as u64),
+            );
+            "Pause pools"
+        }
+        (24, true) => {
+            display.value(
+                "Pools",
+                DisplayValue::Number((accounts.len().saturating_sub(1) / 2) as u64),
+            );
+            display.value("New fee", DisplayValue::Percentage(reader.u8()?));
+            "Update platform fee of pools"
+        }
+        (25, true) => {
+            display.account("Pool", 1);
+            display.value("Name", DisplayValue::Text(reader.string()?));
+            display.value("URI", DisplayValue::Text(reader.string()?));
+            "Set pool metadata"
+        }
+        (26, true) => {
+            display.account("Pool", 1);
+            reader.take::<32>()?;
+            display.value("Farmers", DisplayValue::Number(reader.u32()?.into()));
+            display.value("Total owed", DisplayValue::Amount(reader.u64()?));
+            "Export pool state"
+        }
+        (27, true) => {
+            display.account("Pool", 1);
+            display.value("Source program", DisplayValue::Address(reader.pubkey()?));
+            display.value("Recipient", DisplayValue::Address(reader.pubkey()?));
+            display.value("Amount", DisplayValue::Amount(reader.u64()?));
+            let proof_len = reader.u32()?;
+            for _ in 0..proof_len {
+                reader.take::<32>()?;
+            }
+            "Import farmer balance"
+        }
+        (28, true) => {
+            display.account("Pool", 1);
+            display.value("Claim deadline", DisplayValue::Number(reader.u64()?));
+            "Archive pool"
+        }
+        (29, true) => {
+            display.account("Pool", 1);
+            "Close pool"
+        }
+        (30, true) => {
+            display.account("Pool", 1);
+            display.account("Recipient", 3);
+            display.account("Farmer", 0);
+            display.value(
+                "Pending rewards",
+                DisplayValue::Number(accounts.len().saturating_sub(25) as u64),
+            );
+            "Claim pending rewards"
+        }
+        (31, true) => {
+            display.account("Pool", 1);
+            display.value("Payout address", DisplayValue::Address(reader.pubkey()?));
+            display.account("Farmer", 0);
+            "Register payout address"
+        }
+        (32, true) => {
+            display.account("Pool", 1);
+            display.account("Charity", 3);
+            "Set charity account"
+        }
+        (33, true) => {
+            display.account("Pool", 1);
+            let target = match reader.u8()? {
+                0 => "Pool",
+                1 => "Charity",
+                2 => "Burn",
+                _ => return None,
+            };
+            display.value("Donate to", DisplayValue::Text(target));
+            display.account("Charity", 9);
+            display.account("Farmer", 0);
+            "Donate pending reward"
+        }
+        (34, true) => {
+            display.account("Pool", 1);
+            display.account("Rent recipient", 4);
+            display.account("Farmer", 0);
+            "Close farmer accounts"
+        }
+        (35, true) => {
+            display.account("Pool", 1);
+            let action = match reader.u8()? {
+                0 => "Disable auto-forward",
+                1 => {
+                    display.account("Destination", 3);
+                    "Enable auto-forward"
+                }
+                _ => return None,
+            };
+            display.account("Farmer", 0);
+            action
+        }
+        (36, true) => {
+            display.account("Pool", 1);
+            let action = match reader.u8()? {
+                0 => "Allow pending reward transfers",
+                1 => "Disable pending reward transfers",
+                _ => return None,
+            };
+            display.account("Authority", 0);
+            action
+        }
+        (37, true) => {
+            display.account("Pool", 1);
+            display.account("Pending reward", 2);
+            display.value("New farmer", DisplayValue::Address(reader.pubkey()?));
+            display.account("Farmer", 0);
+            "Transfer pending reward"
+        }
+        (39, true) => {
+            display.account("Pool", 1);
+            let threshold = reader.u64()?;
+            display.account("Farmer", 0);
+            if threshold == 0 {
+                "Disable auto-claim"
+            } else {
+                display.value("Threshold", DisplayValue::Amount(threshold));
+                "Enable auto-claim"
+            }
+        }
+        (40, true) => {
+            display.account("Pool", 1);
+            display.account("Farmer", 3);
+            display.account("Recipient", 7);
+            display.value(
+                "Pending rewards",
+                DisplayValue::Number(accounts.len().saturating_sub(22) as u64),
+            );
+            display.account("Cranker", 0);
+            "Auto-claim pending rewards"
+        }
+        (41, true) => {
+            display.account("Pool", 1);
+            let action = match reader.u8()? {
+                0 => "Remove verifier",
+                1 => {
+                    display.value("Verifier", DisplayValue::Address(reader.pubkey()?));
+                    "Set verifier"
+                }
+                _ => return None,
+            };
+            display.account("Authority", 0);
+            action
+        }
+        (42, true) => {
+            display.account("Pool", 1);
+            let action = match reader.u8()? {
+                0 => "Remove attestor",
+                1 => {
+                    display.value("Attestor", DisplayValue::Address(reader.pubkey()?));
+                    "Set attestor"
+                }
+                _ => return None,
+            };
+            display.value("Data offset", DisplayValue::Number(reader.u16()?.into()));
+            display.account("Authority", 0);
+            action
+        }
+        (43, true) => {
+            display.account("Pool", 1);
+            display.value("Task", DisplayValue::Text(reader.string()?));
+            display.value("Amount", DisplayValue::Amount(reader.u64()?));
+            // Unix timestamp, never negative for a budget that can be locked
+            display.value("Expires at", DisplayValue::Number(reader.u64()?));
+            display.account("Authority", 0);
+            "Lock task budget"
+        }
+        (44, true) => {
+            display.account("Pool", 1);
+            display.account("Task budget", 4);
+            display.account("Caller", 0);
+            "Release unused budget"
+        }
+        (45, true) => {
+            display.account("Pool", 1);
+            display.account("Pending reward", 2);
+            display.account("Challenger", 0);
+            "Challenge reward"
+        }
+        (46, true) => {
+            display.account("Pool", 1);
+            display.account("Pending reward", 3);
+            let action = match reader.u8()? {
+                0 => "Reject reward challenge",
+                1 => "Uphold reward challenge",
+                _ => return None,
+            };
+            display.account("Resolver", 0);
+            action
+        }
+        (47, true) => {
+            display.account("Pool", 1);
+            let action = match reader.u8()? {
+                0 => "Remove reward approver",
+                1 => {
+                    display.value("Approver", DisplayValue::Address(reader.pubkey()?));
+                    "Set reward approver"
+                }
+                _ => return None,
+            };
+            display.account("Authority", 0);
+            action
+        }
+        (48, true) => {
+            display.account("Pool", 1);
+            display.account("Pending reward", 3);
+            display.account("Approver", 0);
+            "Approve reward"
+        }
+        (49, true) => {
+            display.account("Pool", 1);
+            display.value("Task type", DisplayValue::Number(reader.u32()?.into()));
+            let action = match reader.u8()? {
+                0 => "Remove task type fee",
+                1 => {
+                    display.value("Fee", DisplayValue::Percentage(reader.u8()?));
+                    "Set task type fee"
+                }
+                _ => return None,
+            };
+            display.account("Authority", 0);
+            action
+        }
+        (50, true) => {
+            display.account("Platform", 1);
+            display.value("Platform id", DisplayValue::Number(reader.u64()?));
+            display.account("Operator", 0);
+            "Register platform"
+        }
+        (51, true) => {
+            display.account("Platform", 1);
+            display.value("New operator", DisplayValue::Address(reader.pubkey()?));
+            display.account("Operator", 0);
+            "Set platform operator"
+        }
+        (52, true) => {
+            display.account("Pool", 1);
+            display.value("Platform id", DisplayValue::Number(reader.u64()?));
+            display.value("Fee", DisplayValue::Percentage(reader.u8()?));
+            display.account("Operator", 0);
+            display.account("Mint", 2);
+            display.account("Treasury", 3);
+            "Initialize platform pool"
+        }
+        (53, true) => {
+            display.account("Pool", 1);
+            display.value("Campaign", DisplayValue::Number(reader.u64()?));
+            display.value("Budget", DisplayValue::Amount(reader.u64()?));
+            display.value("Starts at", DisplayValue::Number(reader.u64()?));
+            display.value("Ends at", DisplayValue::Number(reader.u64()?));
+            let task_type_count = reader.u32()?;
+            for _ in 0..task_type_count {
+                display.value("Task type", DisplayValue::Number(reader.u32()?.into()));
+            }
+            display.account("Authority", 0);
+            "Create campaign"
+        }
+        (54, true) => {
+            display.account("Pool", 1);
+            display.account("Campaign", 2);
+            display.account("Authority", 0);
+            "End campaign"
+        }
+        (55, true) => {
+            display.account("Pool", 1);
+            display.account("Campaign", 2);
+            display.account("Caller", 0);
+            "Finalize campaign"
+        }
+        (56, true) => {
+            display.account("Pool", 1);
+            let action = match reader.u8()? {
+                0 => "Disable buyback",
+                1 => {
+                    display.value("DEX program", DisplayValue::Address(reader.pubkey()?));
+                    "Configure buyback"
+                }
+                _ => return None,
+            };
+            display.value("Governance mint", DisplayValue::Address(reader.pubkey()?));
+            display.value("Slice (bps)", DisplayValue::Number(reader.u16()?.into()));
+            display.value(
+                "Min output per million",
+                DisplayValue::Number(reader.u64()?),
+            );
+            display.account("Authority", 0);
+            action
+        }
+        (57, true) => {
+            display.account("Pool", 1);
+            let swap_data = reader.bytes()?;
+            display.value(
+                "Swap data bytes",
+                DisplayValue::Number(swap_data.len() as u64),
+            );
+            display.account("DEX program", 10);
+            display.account("Caller", 0);
+            "Run buyback"
+        }
+        (58, true) => {
+            display.account("Pool", 1);
+            let action = match reader.u8()? {
+                0 => "Disable treasury swaps",
+                1 => {
+                    display.value("DEX program", DisplayValue::Address(reader.pubkey()?));
+                    "Set treasury swap policy"
+                }
+                _ => return None,
+            };
+            let mint_count = reader.u32()?;
+            for _ in 0..mint_count {
+                display.value("Stable mint", DisplayValue::Address(reader.pubkey()?));
+            }
+            display.value(
+                "Epoch limit (bps)",
+                DisplayValue::Number(reader.u16()?.into()),
+            );
+            display.account("Authority", 0);
+            action
+        }
+        (59, true) => {
+            display.account("Pool", 1);
+            let action = match reader.u8()? {
+                0 => "Remove fee discounts",
+                1 => {
+                    display.value("Staking program", DisplayValue::Address(reader.pubkey()?));
+                    "Set fee discounts"
+                }
+                _ => return None,
+            };
+            reader.bytes()?;
+            display.value("Tier offset", DisplayValue::Number(reader.u16()?.into()));
+            let tier_count = reader.u32()?;
+            for _ in 0..tier_count {
+                display.value(
+                    "Tier discount (bps)",
+                    DisplayValue::Number(reader.u16()?.into()),
+                );
+            }
+            display.account("Authority", 0);
+            action
+        }
+        (60, true) => {
+            display.account("Pool", 1);
+            display.value(
+                "Yearly rate (bps)",
+                DisplayValue::Number(reader.u16()?.into()),
+            );
+            display.value("Max accrual (seconds)", DisplayValue::Number(reader.u64()?));
+            display.account("Authority", 0);
+            "Set interest policy"
+        }
+        (61, true) => {
+            display.account("Pool", 1);
+            display.value(
+                "Withdraw burn (bps)",
+                DisplayValue::Number(reader.u16()?.into()),
+            );
+            display.account("Authority", 0);
+            "Set withdraw burn"
+        }
+        (62, true) => {
+            display.account("Pool", 1);
+            display.value(
+                "Min runway (days)",
+                DisplayValue::Number(reader.u32()?.into()),
+            );
+            display.value("Floor (bps)", DisplayValue::Number(reader.u16()?.into()));
+            display.value("Ceiling (bps)", DisplayValue::Number(reader.u16()?.into()));
+            display.account("Authority", 0);
+            "Set reward governor"
+        }
+        (63, true) => {
+            display.account("Pool", 1);
+            display.account("Secondary mint", 6);
+            display.account("Authority", 0);
+            "Set secondary mint"
+        }
+        (64, true) => {
+            display.account("Pool", 1);
+            let milestone_count = reader.u32()?;
+            for _ in 0..milestone_count {
+                display.value("Milestone threshold", DisplayValue::Number(reader.u64()?));
+                display.value("Milestone bonus", DisplayValue::Number(reader.u64()?));
+            }
+            display.account("Authority", 0);
+            if milestone_count == 0 {
+                "Remove milestone bonuses"
+            } else {
+                "Set milestone bonuses"
+            }
+        }
+        (65, true) => {
+            display.account("Pool", 1);
+            let amm_program_count = reader.u32()?;
+            for _ in 0..amm_program_count {
+                display.value("AMM program", DisplayValue::Address(reader.pubkey()?));
+            }
+            let pol_bps = reader.u16()?;
+            display.value("POL share (bps)", DisplayValue::Number(pol_bps.into()));
+            display.value("Min LP per million", DisplayValue::Number(reader.u64()?));
+            display.account("Pair mint", 10);
+            display.account("LP mint", 11);
+            display.account("Authority", 0);
+            if amm_program_count == 0 || pol_bps == 0 {
+                "Disable protocol-owned liquidity"
+            } else {
+                "Set protocol-owned liquidity"
+            }
+        }
+        (66, true) => {
+            display.account("Pool", 1);
+            let swap_data = reader.bytes()?;
+            display.value(
+                "Swap data bytes",
+                DisplayValue::Number(swap_data.len() as u64),
+            );
+            reader.u8()?;
+            let deposit_data = reader.bytes()?;
+            display.value(
+                "Deposit data bytes",
+                DisplayValue::Number(deposit_data.len() as u64),
+            );
+            display.account("AMM program", 10);
+            display.account("Caller", 0);
+            "Contribute liquidity"
+        }
+        (67, true) => {
+            display.account("Pool", 1);
+            let rebate_bps = reader.u16()?;
+            display.value("Rebate (bps)", DisplayValue::Number(rebate_bps.into()));
+            display.value("Rate per million", DisplayValue::Number(reader.u64()?));
+            display.account("Governance mint", 7);
+            display.account("Authority", 0);
+            if rebate_bps == 0 {
+                "Disable fee rebate"
+            } else {
+                "Set fee rebate"
+            }
+        }
+        (68, true) => {
+            display.value("Admin", DisplayValue::Address(reader.pubkey()?));
+            display.value("Treasury", DisplayValue::Address(reader.pubkey()?));
+            display.value(
+                "Creation fee (lamports)",
+                DisplayValue::Number(reader.u64()?),
+            );
+            display.value(
+                "Protocol fee share (bps)",
+                DisplayValue::Number(reader.u16()?.into()),
+            );
+            display.account("Signer", 0);
+            "Set protocol config"
+        }
+        (69, true) => {
+            display.account("Pool", 1);
+            display.value("Seed", DisplayValue::Number(reader.u64()?));
+            display.value("Fee", DisplayValue::Percentage(reader.u8()?));
+            display.account("Creator", 0);
+            display.account("Mint", 2);
+            display.account("Treasury", 3);
+            "Create pool"
+        }
+        (70, true) => {
+            display.account("Farmer", 0);
+            // Each pool's group is its fifteen accounts and its pending rewards
+            let mut position = 4;
+            for &pending_count in reader.bytes()? {
+                display.account("Pool", position);
+                display.account("Recipient", position + 2);
+                display.value(
+                    "Pending rewards",
+                    DisplayValue::Number(pending_count.into()),
+                );
+                position += 15 + pending_count as usize;
+            }
+            "Claim across pools"
+        }
+        (71, true) => {
+            display.account("Pool", 1);
+            display.value("Voucher", DisplayValue::Number(reader.u64()?));
+            display.value("Amount", DisplayValue::Amount(reader.u64()?));
+            display.account("Recipient", 6);
+            display.account("Authority", 0);
+            "Issue voucher"
+        }
+        (72, true) => {
+            display.account("Pool", 1);
+            display.account("Voucher", 2);
+            display.account("Recipient", 6);
+            display.account("Holder", 0);
+            "Redeem voucher"
+        }
+        (73, true) => {
+            display.account("Pool", 1);
+            display.value(
+                "Recovery window (seconds)",
+                DisplayValue::Number(reader.u64()?),
+            );
+            display.account("Authority", 0);
+            "Set payout queue"
+        }
+        (74, true) => {
+            display.account("Pool", 1);
+            display.value(
+                "Pending rewards",
+                DisplayValue::Number(accounts.len().saturating_sub(6) as u64),
+            );
+            display.account("Farmer", 0);
+            "Take payout ticket"
+        }
+        (75, true) => {
+            display.account("Pool", 1);
+            display.value("Initial epoch cap", DisplayValue::Amount(reader.u64()?));
+            display.value("Epoch cap step", DisplayValue::Amount(reader.u64()?));
+            display.value("Ramp epochs", DisplayValue::Number(reader.u16()?.into()));
+            display.account("Authority", 0);
+            "Resume pool with ramp"
+        }
+        (76, true) => {
+            display.account("Pool", 1);
+            display.value(
+                "Recovery authority",
+                DisplayValue::Address(reader.pubkey()?),
+            );
+            display.value(
+                "Inactivity timeout (seconds)",
+                DisplayValue::Number(reader.u64()?),
+            );
+            display.account("Authority", 0);
+            "Set recovery authority"
+        }
+        (77, true) => {
+            display.account("Pool", 1);
+            display.account("Recovery authority", 0);
+            "Claim dormant authority"
+        }
+        (78, true) => {
+            display.account("Pool", 1);
+            let guardian_count = reader.u32()?;
+            for _ in 0..guardian_count {
+                display.value("Guardian", DisplayValue::Address(reader.pubkey()?));
+            }
+            display.value("Threshold", DisplayValue::Number(reader.u8()?.into()));
+            display.account("Farmer", 0);
+            "Set recovery guardians"
+        }
+        (79, true) => {
+            display.account("Pool", 1);
+            display.value("New wallet", DisplayValue::Address(reader.pubkey()?));
+            display.account("Guardian", 0);
+            "Initiate farmer recovery"
+        }
+        (80, true) => {
+            display.account("Pool", 1);
+            display.account("Farmer", 0);
+            "Cancel farmer recovery"
+        }
+        (81, true) => {
+            display.account("Pool", 1);
+            display.account("Payer", 0);
+            "Complete farmer recovery"
+        }
+        (82, true) => {
+            display.account("Pool", 1);
+            display.value("Fee (lamports)", DisplayValue::Number(reader.u64()?));
+            let exempt_small_claims = match reader.u8()? {
+                0 => false,
+                1 => true,
+                _ => return None,
+            };
+            let small_claim_threshold = reader.u64()?;
+            if exempt_small_claims {
+                display.value("Exempt below", DisplayValue::Amount(small_claim_threshold));
+            }
+            display.account("Authority", 0);
+            "Set withdrawal fee"
+        }
+        (83, true) => {
+            display.account("Pool", 1);
+            display.account("Destination", 3);
+            display.account("Authority", 0);
+            "Collect withdrawal fees"
+        }
+        (84, true) => {
+            display.account("Pool", 1);
+            let cosigner = reader.pubkey()?;
+            display.account("Farmer", 0);
+            if cosigner == Pubkey::default() {
+                "Remove withdrawal cosigner"
+            } else {
+                display.value("Cosigner", DisplayValue::Address(cosigner));
+                "Set withdrawal cosigner"
+            }
+        }
+        (85, true) => {
+            display.account("Pool", 1);
+            display.account("Snapshot buffer", 2);
+            display.account("Exporter", 0);
+            "Export pool snapshot"
+        }
+        (86, true) => {
+            display.account("Pool", 1);
+            let guild_share_bps = reader.u16()?;
+            display.account("Farmer", 0);
+            if guild_share_bps == 0 {
+                "Remove guild split"
+            } else {
+                display.account("Guild vault", 3);
+                display.value(
+                    "Guild share (bps)",
+                    DisplayValue::Number(guild_share_bps.into()),
+                );
+                "Set guild split"
+            }
+        }
+        (87, true) => {
+            display.account("Pool", 1);
+            display.account("Guild", 2);
+            let member_count = reader.u32()?;
+            for _ in 0..member_count {
+                display.value("Member", DisplayValue::Address(reader.pubkey()?));
+                display.value("Share (bps)", DisplayValue::Number(reader.u16()?.into()));
+            }
+            display.account("Authority", 0);
+            "Set guild members"
+        }
+        (88, true) => {
+            display.account("Pool", 1);
+            display.account("Guild", 2);
+            display.account("Member", 0);
+            "Leave guild"
+        }
+        (89, true) => {
+            display.account("Pool", 1);
+            display.account("Guild", 2);
+            display.account("Payer", 0);
+            "Distribute guild rewards"
+        }
+        (90, true) => {
+            display.account("Pool", 1);
+            display.account("Fee invoice", 3);
+            display.account("Payer", 0);
+            "Close fee invoice"
+        }
+        (91, true) => {
+            display.account("Pool", 0);
+            display.value("Invoice", DisplayValue::Number(reader.u64()?));
+            "Get fee invoice"
+        }
+        (92, true) => {
+            display.account("Pool", 0);
+            display.value("Window (seconds)", DisplayValue::Number(reader.u64()?));
+            "Get reward rate"
+        }
+        (93, true) => {
+            display.account("Pool", 1);
+            display.account("Payer", 0);
+            "Consolidate claim shards"
+        }
+        (94, true) => {
+            display.account("Pool", 1);
+            display.value("Amount", DisplayValue::Amount(reader.u64()?));
+            display.account("Funder", 0);
+            "Fund program treasury"
+        }
+        _ => return None,
+    };
+
+    // Borsh rejects trailing bytes, so does the program
+    if !reader.data.is_empty() {
+        return None;
+    }
+
+    Some(DisplayInstruction {
+        action,
+        fields: display.fields,
+    })
+}
+
+impl fmt::Display for DisplayValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Address(address) => write!(f, "{address}"),
+            Self::Amount(amount) | Self::Number(amount) => write!(f, "{amount}"),
+            Self::Percentage(percentage) => write!(f, "{percentage}%"),
+            Self::Text(text) => f.write_str(text),
+            Self::Hash(hash) => hash.iter().try_for_each(|byte| write!(f, "{byte:02x}")),
+        }
+    }
+}
+
+impl fmt::Display for DisplayInstruction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.action)?;
+        for field in &self.fields {
+            writeln!(f, "  {}: {}", field.label, field.value)?;
+        }
+        Ok(())
+    }
+}
+
+struct Builder<'a, 'k> {
+    accounts: &'k [Pubkey],
+    fields: Vec<DisplayField<'a>>,
+}
+
+impl<'a> Builder<'a, '_> {
+    fn value(&mut self, label: &'static str, value: DisplayValue<'a>) {
+        self.fields.push(DisplayField { label, value });
+    }
+
+    // Optional accounts are skipped when absent
+    fn account(&mut self, label: &'static str, position: usize) {
+        if let Some(&address) = self.accounts.get(position) {
+            self.value(label, DisplayValue::Address(address));
+        }
+    }
+}
+
+// Minimal Borsh reader over the fixed-width and length-prefixed types the
+// instructions use
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let (bytes, rest) = self.data.split_first_chunk::<N>()?;
+        self.data = rest;
+        Some(*bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take::<1>().map(|[byte]| byte)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take().map(u16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take().map(u64::from_le_bytes)
+    }
+
+    fn pubkey(&mut self) -> Option<Pubkey> {
+        self.take::<32>().map(Pubkey::new_from_array)
+    }
+
+    fn bytes(&mut self) -> Option<&'a [u8]> {
+        let len = self.u32()? as usize;
+        if self.data.len() < len {
+            return None;
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(bytes)
+    }
+
+    fn string(&mut self) -> Option<&'a str> {
+        core::str::from_utf8(self.bytes()?).ok()
+    }
+
+    fn option_string(&mut self) -> Option<Option<&'a str>> {
+        match self.u8()? {
+            0 => Some(None),
+            1 => self.string().map(Some),
+            _ => None,
+        }
+    }
+
+    fn option_u32(&mut self) -> Option<Option<u32>> {
+        match self.u8()? {
+            0 => Some(None),
+            1 => self.u32().map(Some),
+            _ => None,
+        }
+    }
+
+    fn option_u64(&mut self) -> Option<Option<u64>> {
+        match self.u8()? {
+            0 => Some(None),
+            1 => self.u64().map(Some),
+            _ => None,
+        }
+    }
+
+    fn option_hash(&mut self) -> Option<Option<[u8; 32]>> {
+        match self.u8()? {
+            0 => Some(None),
+            1 => self.take().map(Some),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec, vec::Vec};
+
+    use super::*;
+
+    fn keys(count: usize) -> Vec<Pubkey> {
+        (0..count)
+            .map(|index| Pubkey::new_from_array([index as u8 + 1; 32]))
+            .collect()
+    }
+
+    /// V2 `WithdrawReward` data, optionally split between `split`
+    fn withdraw(amount: u64, nonce: u64, split: Option<&[(Pubkey, u16)]>) -> Vec<u8> {
+        let mut data = vec![INSTRUCTION_TAG_V2, 2];
+        data.extend(amount.to_le_bytes());
+        data.extend(nonce.to_le_bytes());
+        match split {
+            None => data.push(0),
+            Some(split) => {
+                data.push(1);
+                data.extend((split.len() as u32).to_le_bytes());
+                for (destination, share_bps) in split {
+                    data.extend(destination.to_bytes());
+                    data.extend(share_bps.to_le_bytes());
+                }
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn a_withdrawal_shows_its_pool_amount_recipient_and_nonce() {
+        let accounts = keys(WITHDRAW_ACCOUNTS);
+        let data = withdraw(1_500, 7, None);
+
+        let display = decode_for_display(&data, &accounts).unwrap();
+        assert_eq!(display.action, "Withdraw reward");
+        assert_eq!(display.pool(), Some(accounts[1]));
+        assert_eq!(display.amount(), Some(1_500));
+        assert_eq!(display.recipient(), Some(accounts[3]));
+        assert_eq!(
+            display.field("Farmer"),
+            Some(DisplayValue::Address(accounts[0]))
+        );
+        assert_eq!(display.field("Nonce"), Some(DisplayValue::Number(7)));
+        assert_eq!(display.field("Sponsor"), None);
+    }
+
+    #[test]
+    fn a_sponsor_is_shown_only_when_present() {
+        let split = [
+            (Pubkey::new_from_array([101; 32]), 6_000),
+            (Pubkey::new_from_array([102; 32]), 4_000),
+        ];
+        for (data, unsponsored) in [
+            (withdraw(1_500, 7, None), WITHDRAW_ACCOUNTS),
+            (withdraw(1_500, 7, Some(&split)), WITHDRAW_ACCOUNTS + 1),
+        ] {
+            let display = decode_for_display(&data, &keys(unsponsored)).unwrap();
+            assert_eq!(display.field("Sponsor"), None);
+
+            let accounts = keys(unsponsored + 1);
+            let display = decode_for_display(&data, &accounts).unwrap();
+            assert_eq!(
+                display.field("Sponsor"),
+                Some(DisplayValue::Address(accounts[19]))
+            );
+        }
+    }
+
+    #[test]
+    fn split_destinations_are_listed_in_order() {
+        let split = [
+            (Pubkey::new_from_array([101; 32]), 6_000),
+            (Pubkey::new_from_array([102; 32]), 4_000),
+        ];
+        let data = withdraw(1_500, 7, Some(&split));
+
+        let display = decode_for_display(&data, &keys(WITHDRAW_ACCOUNTS + 1)).unwrap();
+        let destinations: Vec<_> = display
+            .fields
+            .iter()
+            .filter(|field| field.label == "Split to")
+            .map(|field| field.value)
+            .collect();
+        assert_eq!(
+            destinations,
+            [
+                DisplayValue::Address(split[0].0),
+                DisplayValue::Address(split[1].0),
+            ]
+        );
+    }
+
+    #[test]
+    fn legacy_payloads_decode_with_or_without_the_v1_tag() {
+        let mut payload = vec![2];
+        payload.extend(1_500u64.to_le_bytes());
+        payload.extend(7u64.to_le_bytes());
+        let mut tagged = vec![INSTRUCTION_TAG_V1];
+        tagged.extend(&payload);
+        let accounts = keys(WITHDRAW_ACCOUNTS);
+
+        for data in [payload, tagged] {
+            let display = decode_for_display(&data, &accounts).unwrap();
+            assert_eq!(display.action, "Withdraw reward");
+            assert_eq!(display.amount(), Some(1_500));
+            assert_eq!(display.field("Nonce"), Some(DisplayValue::Number(7)));
+        }
+    }
+
+    #[test]
+    fn an_unknown_instruction_tag_is_rejected() {
+        let accounts = keys(4);
+        assert_eq!(
+            decode_for_display(&[INSTRUCTION_TAG_V2, 250], &accounts),
+            None
+        );
+        assert_eq!(decode_for_display(&[250], &accounts), None);
+        // Get pool stats only exists in the V2 namespace
+        assert_eq!(
+            decode_for_display(&[INSTRUCTION_TAG_V2, 7], &accounts)
+                .unwrap()
+                .action,
+            "Get pool stats"
+        );
+        assert_eq!(
+            decode_for_display(&[INSTRUCTION_TAG_V1, 7], &accounts),
+            None
+        );
+    }
+
+    #[test]
+    fn malformed_data_is_rejected_like_the_program_would() {
+        let accounts = keys(WITHDRAW_ACCOUNTS);
+        let data = withdraw(1_500, 7, None);
+
+        assert_eq!(decode_for_display(&[], &accounts), None);
+        assert_eq!(decode_for_display(&[INSTRUCTION_TAG_V2], &accounts), None);
+        // Truncated
+        assert_eq!(decode_for_display(&data[..data.len() - 1], &accounts), None);
+        // Trailing bytes
+        let mut trailing = data.clone();
+        trailing.push(0);
+        assert_eq!(decode_for_display(&trailing, &accounts), None);
+        // Option tag other than 0 or 1
+        let mut bad_option = data;
+        *bad_option.last_mut().unwrap() = 2;
+        assert_eq!(decode_for_display(&bad_option, &accounts), None);
+    }
+
+    #[test]
+    fn a_task_id_that_is_not_utf8_is_rejected() {
+        let mut data = vec![1];
+        data.extend(1_000u64.to_le_bytes());
+        data.extend([9; 32]);
+        data.extend(2u32.to_le_bytes());
+
+        let mut valid = data.clone();
+        valid.extend(b"t1");
+        let display = decode_for_display(&valid, &keys(4)).unwrap();
+        assert_eq!(display.action, "Record reward");
+        assert_eq!(display.field("Task"), Some(DisplayValue::Text("t1")));
+
+        data.extend([0xff, 0xfe]);
+        assert_eq!(decode_for_display(&data, &keys(4)), None);
+    }
+
+    #[test]
+    fn missing_optional_accounts_are_skipped() {
+        let data = withdraw(1_500, 7, None);
+
+        let display = decode_for_display(&data, &keys(2)).unwrap();
+        assert_eq!(display.pool(), Some(keys(2)[1]));
+        assert_eq!(display.recipient(), None);
+        assert_eq!(display.amount(), Some(1_500));
+    }
+
+    #[test]
+    fn values_render_for_display() {
+        let mut hash = [0; 32];
+        hash[0] = 0xab;
+        hash[31] = 0x01;
+        assert_eq!(DisplayValue::Percentage(5).to_string(), "5%");
+        assert_eq!(DisplayValue::Amount(1_500).to_string(), "1500");
+        assert_eq!(DisplayValue::Text("task").to_string(), "task");
+        assert_eq!(
+            DisplayValue::Hash(hash).to_string(),
+            alloc::format!("ab{}01", "00".repeat(30))
+        );
+
+        let display = DisplayInstruction {
+            action: "Pause pool",
+            fields: vec![DisplayField {
+                label: "Pool",
+                value: DisplayValue::Address(Pubkey::new_from_array([1; 32])),
+            }],
+        };
+        assert_eq!(
+            display.to_string(),
+            alloc::format!("Pause pool\n  Pool: {}\n", Pubkey::new_from_array([1; 32]))
+        );
+    }
+}
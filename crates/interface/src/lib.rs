@@ -0,0 +1,22 @@
+//! Program id, PDA derivation and instruction display decoding of the
+//! reward pool program.
+//!
+//! This crate is `no_std` (with `alloc`) and depends only on `solana-pubkey`
+//! and `solana-sha256-hasher`. The program compiles against it, so addresses
+//! derived by any consumer are guaranteed to match the on-chain ones.
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod display;
+pub mod memo;
+pub mod merkle;
+pub mod seeds;
+
+/// Tag byte for instruction payloads explicitly encoded with the V1 namespace
+pub const INSTRUCTION_TAG_V1: u8 = 0xF1;
+/// Tag byte for instruction payloads encoded with the V2 namespace
+pub const INSTRUCTION_TAG_V2: u8 = 0xF2;
+
+solana_pubkey::declare_id!("5TL4wjCFTU99EN6t7fMT4uu4zojuhNuYPpPiiU6a2nTF");
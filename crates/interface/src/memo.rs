@@ -0,0 +1,40 @@
+use alloc::string::String;
+use core::fmt::Write;
+
+use solana_sha256_hasher::hash;
+
+// Transfer memos
+//
+// Accounting tools attribute token transfers, not program events, so the
+// program can tag a reward transfer with an SPL Memo naming its task. The
+// task id itself may be private, so the memo carries its hash, the same one
+// task-keyed PDAs are derived from.
+
+/// Prefix of the memo tagging a reward transfer with its task
+pub const TASK_MEMO_PREFIX: &str = "clones:task:";
+
+/// Memo tagging a reward transfer with its task:
+/// `clones:task:<hex of sha256(task_id)>`
+pub fn task_memo(task_id: &str) -> String {
+    let mut memo = String::with_capacity(TASK_MEMO_PREFIX.len() + 64);
+    memo.push_str(TASK_MEMO_PREFIX);
+    for byte in hash(task_id.as_bytes()).to_bytes() {
+        // Writing to a `String` cannot fail
+        let _ = write!(memo, "{byte:02x}");
+    }
+    memo
+}
+
+/// Task hash a memo written by `task_memo` carries, `None` for any other
+/// memo
+pub fn parse_task_memo(memo: &str) -> Option<[u8; 32]> {
+    let hex = memo.strip_prefix(TASK_MEMO_PREFIX)?;
+    if hex.len() != 64 || !hex.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut task_hash = [0; 32];
+    for (byte, index) in task_hash.iter_mut().zip((0..64).step_by(2)) {
+        *byte = u8::from_str_radix(&hex[index..index + 2], 16).ok()?;
+    }
+    Some(task_hash)
+}
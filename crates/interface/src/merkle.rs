@@ -0,0 +1,44 @@
+use solana_pubkey::Pubkey;
+use solana_sha256_hasher::hashv;
+
+// Farmer balance commitments
+//
+// A pool exporting its state commits to the farmer balances it owes with the
+// root of a merkle tree over `(farmer, amount)` leaves. Leaves and inner
+// nodes are hashed with distinct prefixes so a node can never be passed off
+// as a leaf, and the two children of a node are hashed in sorted order so
+// proofs need no left/right flags.
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Hash of the leaf committing to a farmer's balance
+pub fn balance_leaf(farmer: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, farmer.as_ref(), &amount.to_le_bytes()]).to_bytes()
+}
+
+/// Hash of the inner node above two sibling hashes
+pub fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if left <= right {
+        (left, right)
+    } else {
+        (right, left)
+    };
+    hashv(&[NODE_PREFIX, first, second]).to_bytes()
+}
+
+/// Checks that `proof` (sibling hashes from the leaf up) links the farmer's
+/// balance to `root`
+pub fn verify_balance_proof(
+    root: &[u8; 32],
+    farmer: &Pubkey,
+    amount: u64,
+    proof: &[[u8; 32]],
+) -> bool {
+    let computed = proof
+        .iter()
+        .fold(balance_leaf(farmer, amount), |node, sibling| {
+            hash_pair(&node, sibling)
+        });
+    computed == *root
+}
@@ -0,0 +1,1337 @@
+use solana_pubkey::Pubkey;
+use solana_sha256_hasher::hash;
+
+// PDA seeds
+//
+// Every program-derived address used by the reward pool is derived here, so
+// the program and off-chain clients can never disagree on a seed layout.
+
+/// Seed prefix of the pool account: `[POOL_SEED, reward_mint]`
+pub const POOL_SEED: &[u8] = b"reward_pool";
+/// Seed prefix of the pool token vault: `[VAULT_SEED, pool]`
+pub const VAULT_SEED: &[u8] = b"vault";
+/// Seed prefix of the per-farmer state account: `[FARMER_STATE_SEED, pool, farmer]`
+pub const FARMER_STATE_SEED: &[u8] = b"farmer_state";
+/// Seed prefix of the per-day stats account: `[DAILY_STATS_SEED, pool, day as i64 LE]`
+pub const DAILY_STATS_SEED: &[u8] = b"daily_stats";
+/// Seed prefix of a pending reward: `[PENDING_REWARD_SEED, pool, farmer, sha256(task_id)]`
+pub const PENDING_REWARD_SEED: &[u8] = b"pending_reward";
+/// Seed prefix of a reward distribution: `[DISTRIBUTION_SEED, pool, distribution_id as u64 LE]`
+pub const DISTRIBUTION_SEED: &[u8] = b"distribution";
+/// Seed prefix of a distribution's claim bitmap: `[CLAIM_BITMAP_SEED, distribution]`
+pub const CLAIM_BITMAP_SEED: &[u8] = b"claim_bitmap";
+/// Seed prefix of the instruction execution counters: `[INSTRUCTION_COUNTERS_SEED, pool]`
+pub const INSTRUCTION_COUNTERS_SEED: &[u8] = b"instruction_counters";
+/// Seed prefix of a farmer's withdrawal destination whitelist: `[WITHDRAWAL_WHITELIST_SEED, pool, farmer]`
+pub const WITHDRAWAL_WHITELIST_SEED: &[u8] = b"withdrawal_whitelist";
+/// Seed prefix of the treasury co-signer configuration: `[TREASURY_GOVERNANCE_SEED, pool]`
+pub const TREASURY_GOVERNANCE_SEED: &[u8] = b"treasury_governance";
+/// Seed prefix of a treasury withdrawal proposal: `[TREASURY_PROPOSAL_SEED, pool, proposal_id as u64 LE]`
+pub const TREASURY_PROPOSAL_SEED: &[u8] = b"treasury_proposal";
+/// Seed prefix of the program upgrade authority governed by a pool: `[UPGRADE_AUTHORITY_SEED, pool]`
+pub const UPGRADE_AUTHORITY_SEED: &[u8] = b"upgrade_authority";
+/// Seed prefix of the pool parameter registry: `[PARAMETERS_SEED, pool]`
+pub const PARAMETERS_SEED: &[u8] = b"parameters";
+/// Seed prefix of the previous values of the pool parameters: `[PARAMETER_HISTORY_SEED, pool]`
+pub const PARAMETER_HISTORY_SEED: &[u8] = b"parameter_history";
+/// Seed prefix of the pool's display name and metadata URI: `[POOL_METADATA_SEED, pool]`
+pub const POOL_METADATA_SEED: &[u8] = b"pool_metadata";
+/// Seed prefix of the pool's pause-only guardians: `[GUARDIANS_SEED, pool]`
+pub const GUARDIANS_SEED: &[u8] = b"guardians";
+/// Seed prefix of a queued admin action: `[PENDING_ACTION_SEED, pool, action_id as u64 LE]`
+pub const PENDING_ACTION_SEED: &[u8] = b"pending_action";
+/// Seed prefix of the state commitment of an exported pool: `[STATE_EXPORT_SEED, pool]`
+pub const STATE_EXPORT_SEED: &[u8] = b"state_export";
+/// Seed prefix of a farmer balance imported from a previous deployment: `[IMPORTED_BALANCE_SEED, pool, farmer]`
+pub const IMPORTED_BALANCE_SEED: &[u8] = b"imported_balance";
+/// Seed prefix of the archival record of a pool: `[POOL_ARCHIVE_SEED, pool]`
+pub const POOL_ARCHIVE_SEED: &[u8] = b"pool_archive";
+/// Seed prefix of the wallet a farmer's withdrawals are locked to: `[PAYOUT_ADDRESS_SEED, pool, farmer]`
+pub const PAYOUT_ADDRESS_SEED: &[u8] = b"payout_address";
+/// Seed prefix of the charity account farmers can donate to: `[POOL_CHARITY_SEED, pool]`
+pub const POOL_CHARITY_SEED: &[u8] = b"pool_charity";
+/// Seed prefix of the pool's pending reward transfer policy: `[PENDING_TRANSFER_POLICY_SEED, pool]`
+pub const PENDING_TRANSFER_POLICY_SEED: &[u8] = b"pending_transfer_policy";
+/// Seed prefix of a farmer's recent withdrawals: `[WITHDRAWAL_HISTORY_SEED, pool, farmer]`
+pub const WITHDRAWAL_HISTORY_SEED: &[u8] = b"withdrawal_history";
+/// Seed prefix of the verifier attesting a pool's rewards: `[POOL_VERIFIER_SEED, pool]`
+pub const POOL_VERIFIER_SEED: &[u8] = b"pool_verifier";
+/// Seed prefix of the program whose attestations gate a pool's rewards: `[POOL_ATTESTOR_SEED, pool]`
+pub const POOL_ATTESTOR_SEED: &[u8] = b"pool_attestor";
+/// Seed prefix of the budget locked for a task: `[TASK_BUDGET_SEED, pool, sha256(task_id)]`
+pub const TASK_BUDGET_SEED: &[u8] = b"task_budget";
+/// Seed prefix of the token account holding a pool's locked task budgets: `[BUDGET_ESCROW_SEED, pool]`
+pub const BUDGET_ESCROW_SEED: &[u8] = b"budget_escrow";
+/// Seed prefix of the open challenge of a pending reward: `[REWARD_CHALLENGE_SEED, pool, pending_reward]`
+pub const REWARD_CHALLENGE_SEED: &[u8] = b"reward_challenge";
+/// Seed prefix of the maker-checker configuration of a pool: `[REWARD_APPROVAL_SEED, pool]`
+pub const REWARD_APPROVAL_SEED: &[u8] = b"reward_approval";
+/// Seed prefix of a pool's platform fees per task type: `[FEE_OVERRIDES_SEED, pool]`
+pub const FEE_OVERRIDES_SEED: &[u8] = b"fee_overrides";
+/// Seed prefix of a platform namespace: `[PLATFORM_SEED, platform_id]`
+pub const PLATFORM_SEED: &[u8] = b"platform";
+/// Seed prefix of a pool's campaigns: `[CAMPAIGN_SEED, pool, campaign_id]`
+pub const CAMPAIGN_SEED: &[u8] = b"campaign";
+/// Seed prefix of a pool's buyback-and-burn configuration: `[BUYBACK_SEED, pool]`
+pub const BUYBACK_SEED: &[u8] = b"buyback";
+/// Seed prefix of the token account a pool's buyback swaps from: `[BUYBACK_SOURCE_SEED, pool]`
+pub const BUYBACK_SOURCE_SEED: &[u8] = b"buyback_source";
+/// Seed prefix of the token account a pool's buyback receives and burns: `[BUYBACK_OUTPUT_SEED, pool]`
+pub const BUYBACK_OUTPUT_SEED: &[u8] = b"buyback_output";
+/// Seed prefix of a pool's treasury swap limits: `[TREASURY_SWAP_POLICY_SEED, pool]`
+pub const TREASURY_SWAP_POLICY_SEED: &[u8] = b"treasury_swap_policy";
+/// Seed prefix of the token accounts holding a pool's swapped treasury: `[TREASURY_RESERVE_SEED, pool, mint]`
+pub const TREASURY_RESERVE_SEED: &[u8] = b"treasury_reserve";
+/// Seed prefix of a pool's platform fee discounts by staking tier: `[FEE_DISCOUNTS_SEED, pool]`
+pub const FEE_DISCOUNTS_SEED: &[u8] = b"fee_discounts";
+
+/// Seed prefix of a pool's interest on unclaimed rewards: `[INTEREST_POLICY_SEED, pool]`
+pub const INTEREST_POLICY_SEED: &[u8] = b"interest_policy";
+
+/// Seed prefix of the self-owned token account paying a pool's interest: `[BONUS_VAULT_SEED, pool]`
+pub const BONUS_VAULT_SEED: &[u8] = b"bonus_vault";
+
+/// Seed prefix of the share of a pool's withdrawals burned: `[WITHDRAW_BURN_SEED, pool]`
+pub const WITHDRAW_BURN_SEED: &[u8] = b"withdraw_burn";
+
+/// Seed prefix of a pool's reward governor: `[REWARD_GOVERNOR_SEED, pool]`
+pub const REWARD_GOVERNOR_SEED: &[u8] = b"reward_governor";
+
+/// Seed prefix of a pool's secondary mint configuration: `[SECONDARY_REWARD_SEED, pool]`
+pub const SECONDARY_REWARD_SEED: &[u8] = b"secondary_reward";
+
+/// Seed prefix of the self-owned token account backing a pool's secondary rewards: `[SECONDARY_VAULT_SEED, pool]`
+pub const SECONDARY_VAULT_SEED: &[u8] = b"secondary_vault";
+
+/// Seed prefix of a farmer's secondary rewards: `[SECONDARY_BALANCE_SEED, pool, farmer]`
+pub const SECONDARY_BALANCE_SEED: &[u8] = b"secondary_balance";
+
+/// Seed prefix of a pool's milestone bonuses: `[MILESTONE_BONUSES_SEED, pool]`
+pub const MILESTONE_BONUSES_SEED: &[u8] = b"milestone_bonuses";
+
+/// Seed prefix of a farmer's lifetime earnings: `[FARMER_STATS_SEED, pool, farmer]`
+pub const FARMER_STATS_SEED: &[u8] = b"farmer_stats";
+/// Seed prefix of a pool's protocol-owned liquidity: `[PROTOCOL_LIQUIDITY_SEED, pool]`
+pub const PROTOCOL_LIQUIDITY_SEED: &[u8] = b"protocol_liquidity";
+/// Seed prefix of the token account funding a pool's liquidity contributions: `[POL_VAULT_SEED, pool]`
+pub const POL_VAULT_SEED: &[u8] = b"pol_vault";
+/// Seed prefix of the token account holding the paired side of a pool's liquidity: `[POL_PAIR_VAULT_SEED, pool]`
+pub const POL_PAIR_VAULT_SEED: &[u8] = b"pol_pair_vault";
+/// Seed prefix of the token account holding a pool's LP tokens: `[POL_LP_VAULT_SEED, pool]`
+pub const POL_LP_VAULT_SEED: &[u8] = b"pol_lp_vault";
+/// Seed prefix of a pool's fee rebate configuration: `[FEE_REBATE_SEED, pool]`
+pub const FEE_REBATE_SEED: &[u8] = b"fee_rebate";
+/// Seed prefix of the token account funding a pool's fee rebates: `[REBATE_VAULT_SEED, pool]`
+pub const REBATE_VAULT_SEED: &[u8] = b"rebate_vault";
+/// Seed of the protocol-wide configuration of the pool factory: `[PROTOCOL_CONFIG_SEED]`
+pub const PROTOCOL_CONFIG_SEED: &[u8] = b"protocol_config";
+/// Seed prefix of a pool created through the factory: `[FACTORY_POOL_SEED, creator, reward_mint, seed as u64 LE]`
+pub const FACTORY_POOL_SEED: &[u8] = b"factory_pool";
+/// Seed prefix of the factory record of a pool: `[FACTORY_RECORD_SEED, pool]`
+pub const FACTORY_RECORD_SEED: &[u8] = b"factory_record";
+/// Seed prefix of a reward voucher: `[VOUCHER_SEED, pool, voucher_id as u64 LE]`
+pub const VOUCHER_SEED: &[u8] = b"voucher";
+/// Seed prefix of the NFT mint of a reward voucher: `[VOUCHER_MINT_SEED, voucher]`
+pub const VOUCHER_MINT_SEED: &[u8] = b"voucher_mint";
+/// Seed prefix of the token account holding a pool's unredeemed vouchers: `[VOUCHER_ESCROW_SEED, pool]`
+pub const VOUCHER_ESCROW_SEED: &[u8] = b"voucher_escrow";
+/// Seed prefix of a pool's payout queue: `[PAYOUT_QUEUE_SEED, pool]`
+pub const PAYOUT_QUEUE_SEED: &[u8] = b"payout_queue";
+/// Seed prefix of a farmer's payout queue ticket: `[PAYOUT_TICKET_SEED, pool, farmer]`
+pub const PAYOUT_TICKET_SEED: &[u8] = b"payout_ticket";
+/// Seed prefix of a pool's withdrawal ramp: `[WITHDRAWAL_RAMP_SEED, pool]`
+pub const WITHDRAWAL_RAMP_SEED: &[u8] = b"withdrawal_ramp";
+/// Seed prefix of a pool's authority recovery settings: `[AUTHORITY_RECOVERY_SEED, pool]`
+pub const AUTHORITY_RECOVERY_SEED: &[u8] = b"authority_recovery";
+/// Seed prefix of a pool's platform authority history: `[KEY_HISTORY_SEED, pool]`
+pub const KEY_HISTORY_SEED: &[u8] = b"key_history";
+/// Seed prefix of a farmer's recovery guardians: `[FARMER_RECOVERY_SEED, pool, farmer]`
+pub const FARMER_RECOVERY_SEED: &[u8] = b"farmer_recovery";
+/// Seed prefix of a pool's lamport withdrawal fee: `[WITHDRAWAL_FEE_SEED, pool]`
+pub const WITHDRAWAL_FEE_SEED: &[u8] = b"withdrawal_fee";
+/// Seed prefix of the account collecting a pool's lamport fees: `[FEE_VAULT_SEED, pool]`
+pub const FEE_VAULT_SEED: &[u8] = b"fee_vault";
+/// Seed prefix of a guild: `[GUILD_SEED, pool, authority]`
+pub const GUILD_SEED: &[u8] = b"guild";
+/// Seed prefix of the token account holding a guild's rewards: `[GUILD_VAULT_SEED, guild]`
+pub const GUILD_VAULT_SEED: &[u8] = b"guild_vault";
+/// Seed prefix of a pool's fee invoicing state: `[FEE_LEDGER_SEED, pool]`
+pub const FEE_LEDGER_SEED: &[u8] = b"fee_ledger";
+/// Seed prefix of a pool's fee invoice: `[FEE_INVOICE_SEED, pool, number (u64 LE)]`
+pub const FEE_INVOICE_SEED: &[u8] = b"fee_invoice";
+/// Seed prefix of a pool's reward rate accumulators: `[REWARD_RATE_SEED, pool]`
+pub const REWARD_RATE_SEED: &[u8] = b"reward_rate";
+/// Seed prefix of a pool's claim shard: `[CLAIM_SHARD_SEED, pool, shard (u8)]`
+pub const CLAIM_SHARD_SEED: &[u8] = b"claim_shard";
+/// Seed prefix of a pool's consolidated claim totals: `[CLAIM_LEDGER_SEED, pool]`
+pub const CLAIM_LEDGER_SEED: &[u8] = b"claim_ledger";
+/// Seed prefix of a reward receipt: `[REWARD_RECEIPT_SEED, pool, idempotency_key]`
+pub const REWARD_RECEIPT_SEED: &[u8] = b"reward_receipt";
+/// Seed prefix of a withdrawal record: `[WITHDRAWAL_RECORD_SEED, pool, farmer, nonce (u64 LE)]`
+pub const WITHDRAWAL_RECORD_SEED: &[u8] = b"withdrawal_record";
+/// Seed prefix of the token account collecting a pool's platform fees: `[PLATFORM_FEE_VAULT_SEED, pool]`
+pub const PLATFORM_FEE_VAULT_SEED: &[u8] = b"platform_fee_vault";
+
+/// Number of claim shards of a pool. Farmers are spread over them by the
+/// hash of their key, see [`claim_shard_index`].
+pub const CLAIM_SHARD_COUNT: u8 = 16;
+
+/// Derives the pool address for a reward mint
+pub fn find_pool_address(reward_mint: &Pubkey) -> (Pubkey, u8) {
+    find_pool_address_with_program_id(reward_mint, &crate::id())
+}
+
+/// Derives the pool address for a reward mint under a specific program id
+pub fn find_pool_address_with_program_id(
+    reward_mint: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_SEED, reward_mint.as_ref()], program_id)
+}
+
+/// Derives the address of a platform's pool for a reward mint
+pub fn find_platform_pool_address(platform_id: u64, reward_mint: &Pubkey) -> (Pubkey, u8) {
+    find_platform_pool_address_with_program_id(platform_id, reward_mint, &crate::id())
+}
+
+/// Derives the address of a platform's pool for a reward mint under a
+/// specific program id
+pub fn find_platform_pool_address_with_program_id(
+    platform_id: u64,
+    reward_mint: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[POOL_SEED, &platform_id.to_le_bytes(), reward_mint.as_ref()],
+        program_id,
+    )
+}
+
+/// Derives the token vault address of a pool
+pub fn find_vault_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_vault_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the token vault address of a pool under a specific program id
+pub fn find_vault_address_with_program_id(pool: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the state account of a farmer within a pool
+pub fn find_farmer_state_address(pool: &Pubkey, farmer: &Pubkey) -> (Pubkey, u8) {
+    find_farmer_state_address_with_program_id(pool, farmer, &crate::id())
+}
+
+/// Derives the state account of a farmer within a pool under a specific program id
+pub fn find_farmer_state_address_with_program_id(
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FARMER_STATE_SEED, pool.as_ref(), farmer.as_ref()],
+        program_id,
+    )
+}
+
+/// Derives the stats account of a pool for a unix day
+pub fn find_daily_stats_address(pool: &Pubkey, day: i64) -> (Pubkey, u8) {
+    find_daily_stats_address_with_program_id(pool, day, &crate::id())
+}
+
+/// Derives the stats account of a pool for a unix day under a specific program id
+pub fn find_daily_stats_address_with_program_id(
+    pool: &Pubkey,
+    day: i64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[DAILY_STATS_SEED, pool.as_ref(), &day.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Derives the pending reward account of a farmer's task. The task id is
+/// hashed since it may be longer than a seed.
+pub fn find_pending_reward_address(pool: &Pubkey, farmer: &Pubkey, task_id: &str) -> (Pubkey, u8) {
+    find_pending_reward_address_with_program_id(pool, farmer, task_id, &crate::id())
+}
+
+/// Derives the pending reward account of a farmer's task under a specific program id
+pub fn find_pending_reward_address_with_program_id(
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    task_id: &str,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PENDING_REWARD_SEED,
+            pool.as_ref(),
+            farmer.as_ref(),
+            hash(task_id.as_bytes()).as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Derives a reward distribution of a pool
+pub fn find_distribution_address(pool: &Pubkey, distribution_id: u64) -> (Pubkey, u8) {
+    find_distribution_address_with_program_id(pool, distribution_id, &crate::id())
+}
+
+/// Derives a reward distribution of a pool under a specific program id
+pub fn find_distribution_address_with_program_id(
+    pool: &Pubkey,
+    distribution_id: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            DISTRIBUTION_SEED,
+            pool.as_ref(),
+            &distribution_id.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Derives the claim bitmap of a distribution
+pub fn find_claim_bitmap_address(distribution: &Pubkey) -> (Pubkey, u8) {
+    find_claim_bitmap_address_with_program_id(distribution, &crate::id())
+}
+
+/// Derives the claim bitmap of a distribution under a specific program id
+pub fn find_claim_bitmap_address_with_program_id(
+    distribution: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CLAIM_BITMAP_SEED, distribution.as_ref()], program_id)
+}
+
+/// Derives the instruction execution counters of a pool
+pub fn find_instruction_counters_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_instruction_counters_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the instruction execution counters of a pool under a specific program id
+pub fn find_instruction_counters_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[INSTRUCTION_COUNTERS_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the withdrawal destination whitelist of a farmer within a pool
+pub fn find_withdrawal_whitelist_address(pool: &Pubkey, farmer: &Pubkey) -> (Pubkey, u8) {
+    find_withdrawal_whitelist_address_with_program_id(pool, farmer, &crate::id())
+}
+
+/// Derives the withdrawal destination whitelist of a farmer within a pool under a specific program id
+pub fn find_withdrawal_whitelist_address_with_program_id(
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[WITHDRAWAL_WHITELIST_SEED, pool.as_ref(), farmer.as_ref()],
+        program_id,
+    )
+}
+
+/// Derives the treasury co-signer configuration of a pool
+pub fn find_treasury_governance_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_treasury_governance_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the treasury co-signer configuration of a pool under a specific program id
+pub fn find_treasury_governance_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_GOVERNANCE_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives a treasury withdrawal proposal of a pool
+pub fn find_treasury_proposal_address(pool: &Pubkey, proposal_id: u64) -> (Pubkey, u8) {
+    find_treasury_proposal_address_with_program_id(pool, proposal_id, &crate::id())
+}
+
+/// Derives a treasury withdrawal proposal of a pool under a specific program id
+pub fn find_treasury_proposal_address_with_program_id(
+    pool: &Pubkey,
+    proposal_id: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            TREASURY_PROPOSAL_SEED,
+            pool.as_ref(),
+            &proposal_id.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Derives the program upgrade authority governed by a pool
+pub fn find_upgrade_authority_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_upgrade_authority_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the program upgrade authority governed by a pool under a specific program id
+pub fn find_upgrade_authority_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[UPGRADE_AUTHORITY_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the parameter registry of a pool
+pub fn find_parameters_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_parameters_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the parameter registry of a pool under a specific program id
+pub fn find_parameters_address_with_program_id(pool: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PARAMETERS_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the parameter history of a pool
+pub fn find_parameter_history_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_parameter_history_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the parameter history of a pool under a specific program id
+pub fn find_parameter_history_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PARAMETER_HISTORY_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the guardian set of a pool
+pub fn find_guardians_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_guardians_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the guardian set of a pool under a specific program id
+pub fn find_guardians_address_with_program_id(pool: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GUARDIANS_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives a queued admin action of a pool
+pub fn find_pending_action_address(pool: &Pubkey, action_id: u64) -> (Pubkey, u8) {
+    find_pending_action_address_with_program_id(pool, action_id, &crate::id())
+}
+
+/// Derives a queued admin action of a pool under a specific program id
+pub fn find_pending_action_address_with_program_id(
+    pool: &Pubkey,
+    action_id: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PENDING_ACTION_SEED, pool.as_ref(), &action_id.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Derives the metadata account of a pool
+pub fn find_pool_metadata_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_pool_metadata_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the metadata account of a pool under a specific program id
+pub fn find_pool_metadata_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_METADATA_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the state commitment of an exported pool
+pub fn find_state_export_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_state_export_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the state commitment of an exported pool under a specific program id
+pub fn find_state_export_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STATE_EXPORT_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the record of a farmer balance imported into a pool
+pub fn find_imported_balance_address(pool: &Pubkey, farmer: &Pubkey) -> (Pubkey, u8) {
+    find_imported_balance_address_with_program_id(pool, farmer, &crate::id())
+}
+
+/// Derives the record of a farmer balance imported into a pool under a specific program id
+pub fn find_imported_balance_address_with_program_id(
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[IMPORTED_BALANCE_SEED, pool.as_ref(), farmer.as_ref()],
+        program_id,
+    )
+}
+
+/// Derives the archival record of a pool
+pub fn find_pool_archive_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_pool_archive_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the archival record of a pool under a specific program id
+pub fn find_pool_archive_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_ARCHIVE_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the payout address registration of a farmer within a pool
+pub fn find_payout_address_address(pool: &Pubkey, farmer: &Pubkey) -> (Pubkey, u8) {
+    find_payout_address_address_with_program_id(pool, farmer, &crate::id())
+}
+
+/// Derives the payout address registration of a farmer within a pool under a specific program id
+pub fn find_payout_address_address_with_program_id(
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PAYOUT_ADDRESS_SEED, pool.as_ref(), farmer.as_ref()],
+        program_id,
+    )
+}
+
+/// Derives the charity configuration of a pool
+pub fn find_pool_charity_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_pool_charity_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the charity configuration of a pool under a specific program id
+pub fn find_pool_charity_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_CHARITY_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the pending reward transfer policy of a pool
+pub fn find_pending_transfer_policy_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_pending_transfer_policy_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the pending reward transfer policy of a pool under a specific program id
+pub fn find_pending_transfer_policy_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PENDING_TRANSFER_POLICY_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the recent withdrawals of a farmer in a pool
+pub fn find_withdrawal_history_address(pool: &Pubkey, farmer: &Pubkey) -> (Pubkey, u8) {
+    find_withdrawal_history_address_with_program_id(pool, farmer, &crate::id())
+}
+
+/// Derives the recent withdrawals of a farmer in a pool under a specific program id
+pub fn find_withdrawal_history_address_with_program_id(
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[WITHDRAWAL_HISTORY_SEED, pool.as_ref(), farmer.as_ref()],
+        program_id,
+    )
+}
+
+/// Derives the reward verifier configuration of a pool
+pub fn find_pool_verifier_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_pool_verifier_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the reward verifier configuration of a pool under a specific program id
+pub fn find_pool_verifier_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_VERIFIER_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the reward attestor configuration of a pool
+pub fn find_pool_attestor_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_pool_attestor_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the reward attestor configuration of a pool under a specific program id
+pub fn find_pool_attestor_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_ATTESTOR_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the budget locked for a task. The task id is hashed since it may
+/// be longer than a seed.
+pub fn find_task_budget_address(pool: &Pubkey, task_id: &str) -> (Pubkey, u8) {
+    find_task_budget_address_with_program_id(pool, task_id, &crate::id())
+}
+
+/// Derives the budget locked for a task under a specific program id
+pub fn find_task_budget_address_with_program_id(
+    pool: &Pubkey,
+    task_id: &str,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            TASK_BUDGET_SEED,
+            pool.as_ref(),
+            hash(task_id.as_bytes()).as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Derives the token account holding a pool's locked task budgets
+pub fn find_budget_escrow_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_budget_escrow_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the token account holding a pool's locked task budgets under a specific program id
+pub fn find_budget_escrow_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BUDGET_ESCROW_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the open challenge of a pending reward
+pub fn find_reward_challenge_address(pool: &Pubkey, pending_reward: &Pubkey) -> (Pubkey, u8) {
+    find_reward_challenge_address_with_program_id(pool, pending_reward, &crate::id())
+}
+
+/// Derives the open challenge of a pending reward under a specific program id
+pub fn find_reward_challenge_address_with_program_id(
+    pool: &Pubkey,
+    pending_reward: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            REWARD_CHALLENGE_SEED,
+            pool.as_ref(),
+            pending_reward.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Derives the maker-checker configuration of a pool
+pub fn find_reward_approval_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_reward_approval_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the maker-checker configuration of a pool under a specific program id
+pub fn find_reward_approval_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REWARD_APPROVAL_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the platform fees per task type of a pool
+pub fn find_fee_overrides_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_fee_overrides_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the platform fees per task type of a pool under a specific program id
+pub fn find_fee_overrides_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FEE_OVERRIDES_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the namespace account of a platform
+pub fn find_platform_address(platform_id: u64) -> (Pubkey, u8) {
+    find_platform_address_with_program_id(platform_id, &crate::id())
+}
+
+/// Derives the namespace account of a platform under a specific program id
+pub fn find_platform_address_with_program_id(
+    platform_id: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PLATFORM_SEED, &platform_id.to_le_bytes()], program_id)
+}
+
+/// Derives a campaign account of a pool
+pub fn find_campaign_address(pool: &Pubkey, campaign_id: u64) -> (Pubkey, u8) {
+    find_campaign_address_with_program_id(pool, campaign_id, &crate::id())
+}
+
+/// Derives a campaign account of a pool under a specific program id
+pub fn find_campaign_address_with_program_id(
+    pool: &Pubkey,
+    campaign_id: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CAMPAIGN_SEED, pool.as_ref(), &campaign_id.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Derives the buyback-and-burn configuration of a pool
+pub fn find_buyback_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_buyback_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the buyback-and-burn configuration of a pool under a specific program id
+pub fn find_buyback_address_with_program_id(pool: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BUYBACK_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the token account a pool's buyback swaps from
+pub fn find_buyback_source_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_buyback_source_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the token account a pool's buyback swaps from under a specific program id
+pub fn find_buyback_source_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BUYBACK_SOURCE_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the token account a pool's buyback receives and burns
+pub fn find_buyback_output_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_buyback_output_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the token account a pool's buyback receives and burns under a specific program id
+pub fn find_buyback_output_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BUYBACK_OUTPUT_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the treasury swap limits of a pool
+pub fn find_treasury_swap_policy_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_treasury_swap_policy_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the treasury swap limits of a pool under a specific program id
+pub fn find_treasury_swap_policy_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SWAP_POLICY_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the token account holding a pool's treasury swapped into `mint`
+pub fn find_treasury_reserve_address(pool: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    find_treasury_reserve_address_with_program_id(pool, mint, &crate::id())
+}
+
+/// Derives the token account holding a pool's treasury swapped into `mint` under a specific program id
+pub fn find_treasury_reserve_address_with_program_id(
+    pool: &Pubkey,
+    mint: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TREASURY_RESERVE_SEED, pool.as_ref(), mint.as_ref()],
+        program_id,
+    )
+}
+
+/// Derives the platform fee discounts by staking tier of a pool
+pub fn find_fee_discounts_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_fee_discounts_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the platform fee discounts by staking tier of a pool under a specific program id
+pub fn find_fee_discounts_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FEE_DISCOUNTS_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the interest policy of a pool
+pub fn find_interest_policy_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_interest_policy_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the interest policy of a pool under a specific program id
+pub fn find_interest_policy_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[INTEREST_POLICY_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the bonus vault paying a pool's interest
+pub fn find_bonus_vault_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_bonus_vault_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the bonus vault paying a pool's interest under a specific program id
+pub fn find_bonus_vault_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BONUS_VAULT_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the withdrawal burn configuration of a pool
+pub fn find_withdraw_burn_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_withdraw_burn_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the withdrawal burn configuration of a pool under a specific program id
+pub fn find_withdraw_burn_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WITHDRAW_BURN_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the reward governor of a pool
+pub fn find_reward_governor_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_reward_governor_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the reward governor of a pool under a specific program id
+pub fn find_reward_governor_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REWARD_GOVERNOR_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the secondary mint configuration of a pool
+pub fn find_secondary_reward_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_secondary_reward_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the secondary mint configuration of a pool under a specific program id
+pub fn find_secondary_reward_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SECONDARY_REWARD_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the token account backing a pool's secondary rewards
+pub fn find_secondary_vault_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_secondary_vault_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the token account backing a pool's secondary rewards under a specific program id
+pub fn find_secondary_vault_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SECONDARY_VAULT_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the secondary rewards of a farmer in a pool
+pub fn find_secondary_balance_address(pool: &Pubkey, farmer: &Pubkey) -> (Pubkey, u8) {
+    find_secondary_balance_address_with_program_id(pool, farmer, &crate::id())
+}
+
+/// Derives the secondary rewards of a farmer in a pool under a specific program id
+pub fn find_secondary_balance_address_with_program_id(
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SECONDARY_BALANCE_SEED, pool.as_ref(), farmer.as_ref()],
+        program_id,
+    )
+}
+
+/// Derives the milestone bonuses of a pool
+pub fn find_milestone_bonuses_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_milestone_bonuses_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the milestone bonuses of a pool under a specific program id
+pub fn find_milestone_bonuses_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MILESTONE_BONUSES_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the lifetime earnings of a farmer in a pool
+pub fn find_farmer_stats_address(pool: &Pubkey, farmer: &Pubkey) -> (Pubkey, u8) {
+    find_farmer_stats_address_with_program_id(pool, farmer, &crate::id())
+}
+
+/// Derives the lifetime earnings of a farmer in a pool under a specific program id
+pub fn find_farmer_stats_address_with_program_id(
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FARMER_STATS_SEED, pool.as_ref(), farmer.as_ref()],
+        program_id,
+    )
+}
+
+/// Derives the protocol-owned liquidity of a pool
+pub fn find_protocol_liquidity_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_protocol_liquidity_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the protocol-owned liquidity of a pool under a specific program id
+pub fn find_protocol_liquidity_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROTOCOL_LIQUIDITY_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the token account funding a pool's liquidity contributions
+pub fn find_pol_vault_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_pol_vault_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the token account funding a pool's liquidity contributions under a specific program id
+pub fn find_pol_vault_address_with_program_id(pool: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POL_VAULT_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the token account holding the paired side of a pool's liquidity
+pub fn find_pol_pair_vault_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_pol_pair_vault_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the token account holding the paired side of a pool's liquidity under a specific program id
+pub fn find_pol_pair_vault_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POL_PAIR_VAULT_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the token account holding a pool's LP tokens
+pub fn find_pol_lp_vault_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_pol_lp_vault_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the token account holding a pool's LP tokens under a specific program id
+pub fn find_pol_lp_vault_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POL_LP_VAULT_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the fee rebate configuration of a pool
+pub fn find_fee_rebate_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_fee_rebate_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the fee rebate configuration of a pool under a specific program id
+pub fn find_fee_rebate_address_with_program_id(pool: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FEE_REBATE_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the token account funding a pool's fee rebates
+pub fn find_rebate_vault_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_rebate_vault_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the token account funding a pool's fee rebates under a specific program id
+pub fn find_rebate_vault_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REBATE_VAULT_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the protocol-wide configuration of the pool factory
+pub fn find_protocol_config_address() -> (Pubkey, u8) {
+    find_protocol_config_address_with_program_id(&crate::id())
+}
+
+/// Derives the protocol-wide configuration of the pool factory under a
+/// specific program id
+pub fn find_protocol_config_address_with_program_id(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROTOCOL_CONFIG_SEED], program_id)
+}
+
+/// Derives the address of a pool a creator made through the factory
+pub fn find_factory_pool_address(
+    creator: &Pubkey,
+    reward_mint: &Pubkey,
+    seed: u64,
+) -> (Pubkey, u8) {
+    find_factory_pool_address_with_program_id(creator, reward_mint, seed, &crate::id())
+}
+
+/// Derives the address of a pool a creator made through the factory under a
+/// specific program id
+pub fn find_factory_pool_address_with_program_id(
+    creator: &Pubkey,
+    reward_mint: &Pubkey,
+    seed: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            FACTORY_POOL_SEED,
+            creator.as_ref(),
+            reward_mint.as_ref(),
+            &seed.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Derives the factory record of a pool
+pub fn find_factory_record_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_factory_record_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the factory record of a pool under a specific program id
+pub fn find_factory_record_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FACTORY_RECORD_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives a reward voucher of a pool
+pub fn find_voucher_address(pool: &Pubkey, voucher_id: u64) -> (Pubkey, u8) {
+    find_voucher_address_with_program_id(pool, voucher_id, &crate::id())
+}
+
+/// Derives a reward voucher of a pool under a specific program id
+pub fn find_voucher_address_with_program_id(
+    pool: &Pubkey,
+    voucher_id: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VOUCHER_SEED, pool.as_ref(), &voucher_id.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Derives the NFT mint of a reward voucher
+pub fn find_voucher_mint_address(voucher: &Pubkey) -> (Pubkey, u8) {
+    find_voucher_mint_address_with_program_id(voucher, &crate::id())
+}
+
+/// Derives the NFT mint of a reward voucher under a specific program id
+pub fn find_voucher_mint_address_with_program_id(
+    voucher: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOUCHER_MINT_SEED, voucher.as_ref()], program_id)
+}
+
+/// Derives the token account holding a pool's unredeemed vouchers
+pub fn find_voucher_escrow_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_voucher_escrow_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the token account holding a pool's unredeemed vouchers under a
+/// specific program id
+pub fn find_voucher_escrow_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOUCHER_ESCROW_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the payout queue of a pool
+pub fn find_payout_queue_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_payout_queue_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the payout queue of a pool under a specific program id
+pub fn find_payout_queue_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PAYOUT_QUEUE_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives a farmer's payout queue ticket
+pub fn find_payout_ticket_address(pool: &Pubkey, farmer: &Pubkey) -> (Pubkey, u8) {
+    find_payout_ticket_address_with_program_id(pool, farmer, &crate::id())
+}
+
+/// Derives a farmer's payout queue ticket under a specific program id
+pub fn find_payout_ticket_address_with_program_id(
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PAYOUT_TICKET_SEED, pool.as_ref(), farmer.as_ref()],
+        program_id,
+    )
+}
+
+/// Derives the withdrawal ramp of a pool
+pub fn find_withdrawal_ramp_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_withdrawal_ramp_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the withdrawal ramp of a pool under a specific program id
+pub fn find_withdrawal_ramp_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WITHDRAWAL_RAMP_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the authority recovery settings of a pool
+pub fn find_authority_recovery_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_authority_recovery_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the authority recovery settings of a pool under a specific
+/// program id
+pub fn find_authority_recovery_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUTHORITY_RECOVERY_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the platform authority history of a pool
+pub fn find_key_history_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_key_history_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the platform authority history of a pool under a specific
+/// program id
+pub fn find_key_history_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[KEY_HISTORY_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the recovery guardians of a farmer in a pool
+pub fn find_farmer_recovery_address(pool: &Pubkey, farmer: &Pubkey) -> (Pubkey, u8) {
+    find_farmer_recovery_address_with_program_id(pool, farmer, &crate::id())
+}
+
+/// Derives the recovery guardians of a farmer in a pool under a specific
+/// program id
+pub fn find_farmer_recovery_address_with_program_id(
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FARMER_RECOVERY_SEED, pool.as_ref(), farmer.as_ref()],
+        program_id,
+    )
+}
+
+/// Derives the lamport withdrawal fee configuration of a pool
+pub fn find_withdrawal_fee_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_withdrawal_fee_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the lamport withdrawal fee configuration of a pool under a
+/// specific program id
+pub fn find_withdrawal_fee_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WITHDRAWAL_FEE_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the account collecting a pool's lamport fees
+pub fn find_fee_vault_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_fee_vault_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the account collecting a pool's lamport fees under a specific
+/// program id
+pub fn find_fee_vault_address_with_program_id(pool: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FEE_VAULT_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the guild of a pool led by `authority`
+pub fn find_guild_address(pool: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
+    find_guild_address_with_program_id(pool, authority, &crate::id())
+}
+
+/// Derives the guild of a pool led by `authority` under a specific program
+/// id
+pub fn find_guild_address_with_program_id(
+    pool: &Pubkey,
+    authority: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GUILD_SEED, pool.as_ref(), authority.as_ref()], program_id)
+}
+
+/// Derives the token account holding a guild's rewards
+pub fn find_guild_vault_address(guild: &Pubkey) -> (Pubkey, u8) {
+    find_guild_vault_address_with_program_id(guild, &crate::id())
+}
+
+/// Derives the token account holding a guild's rewards under a specific
+/// program id
+pub fn find_guild_vault_address_with_program_id(
+    guild: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GUILD_VAULT_SEED, guild.as_ref()], program_id)
+}
+
+/// Derives the fee invoicing state of a pool
+pub fn find_fee_ledger_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_fee_ledger_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the fee invoicing state of a pool under a specific program id
+pub fn find_fee_ledger_address_with_program_id(pool: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FEE_LEDGER_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives fee invoice `number` of a pool
+pub fn find_fee_invoice_address(pool: &Pubkey, number: u64) -> (Pubkey, u8) {
+    find_fee_invoice_address_with_program_id(pool, number, &crate::id())
+}
+
+/// Derives fee invoice `number` of a pool under a specific program id
+pub fn find_fee_invoice_address_with_program_id(
+    pool: &Pubkey,
+    number: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FEE_INVOICE_SEED, pool.as_ref(), &number.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Derives the reward rate accumulators of a pool
+pub fn find_reward_rate_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_reward_rate_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the reward rate accumulators of a pool under a specific program
+/// id
+pub fn find_reward_rate_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REWARD_RATE_SEED, pool.as_ref()], program_id)
+}
+
+/// Claim shard a farmer's claims are counted in, from the first byte of the
+/// hash of their key
+pub fn claim_shard_index(farmer: &Pubkey) -> u8 {
+    hash(farmer.as_ref()).as_ref()[0] % CLAIM_SHARD_COUNT
+}
+
+/// Derives claim shard `shard` of a pool
+pub fn find_claim_shard_address(pool: &Pubkey, shard: u8) -> (Pubkey, u8) {
+    find_claim_shard_address_with_program_id(pool, shard, &crate::id())
+}
+
+/// Derives claim shard `shard` of a pool under a specific program id
+pub fn find_claim_shard_address_with_program_id(
+    pool: &Pubkey,
+    shard: u8,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CLAIM_SHARD_SEED, pool.as_ref(), &[shard]], program_id)
+}
+
+/// Derives the consolidated claim totals of a pool
+pub fn find_claim_ledger_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_claim_ledger_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the consolidated claim totals of a pool under a specific program
+/// id
+pub fn find_claim_ledger_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CLAIM_LEDGER_SEED, pool.as_ref()], program_id)
+}
+
+/// Derives the receipt of the reward recorded under an idempotency key
+pub fn find_reward_receipt_address(pool: &Pubkey, idempotency_key: &[u8; 32]) -> (Pubkey, u8) {
+    find_reward_receipt_address_with_program_id(pool, idempotency_key, &crate::id())
+}
+
+/// Derives the receipt of the reward recorded under an idempotency key
+/// under a specific program id
+pub fn find_reward_receipt_address_with_program_id(
+    pool: &Pubkey,
+    idempotency_key: &[u8; 32],
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[REWARD_RECEIPT_SEED, pool.as_ref(), idempotency_key],
+        program_id,
+    )
+}
+
+/// Derives the record of a farmer's withdrawal under a nonce
+pub fn find_withdrawal_record_address(pool: &Pubkey, farmer: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    find_withdrawal_record_address_with_program_id(pool, farmer, nonce, &crate::id())
+}
+
+/// Derives the record of a farmer's withdrawal under a nonce under a
+/// specific program id
+pub fn find_withdrawal_record_address_with_program_id(
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    nonce: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            WITHDRAWAL_RECORD_SEED,
+            pool.as_ref(),
+            farmer.as_ref(),
+            &nonce.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Derives the token account collecting a pool's platform fees
+pub fn find_platform_fee_vault_address(pool: &Pubkey) -> (Pubkey, u8) {
+    find_platform_fee_vault_address_with_program_id(pool, &crate::id())
+}
+
+/// Derives the token account collecting a pool's platform fees under a
+/// specific program id
+pub fn find_platform_fee_vault_address_with_program_id(
+    pool: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PLATFORM_FEE_VAULT_SEED, pool.as_ref()], program_id)
+}
@@ -0,0 +1,662 @@
+//! Bootstraps a reward pool environment on a local validator.
+//!
+//! ```text
+//! bootstrap [--rpc <URL>] [--program <SO> | --program-id <ADDRESS>]
+//!           [--program-keypair <FILE>] [--farmers <N>] [--fee <PERCENT>]
+//!           [--keypair-dir <DIR>] [--out <FILE>] [--mocks]
+//! ```
+//!
+//! Deploys the program from `--program` (`target/deploy/reward_pool.so` by
+//! default) at the address of `--program-keypair` or a fresh one, unless
+//! `--program-id` names a deployment the validator already has, e.g. one
+//! loaded with `solana-test-validator --bpf-program`. It then creates a
+//! reward mint, initializes a pool with a funded platform treasury, and
+//! registers sample farmers by recording a first reward for each, in v0
+//! transactions against a lookup table of the pool's accounts. Every
+//! address is printed as JSON for the front-end; `--keypair-dir` also saves
+//! the keypairs it generated, so the environment can be driven afterwards.
+//!
+//! With `--mocks`, it also deploys the mock attestor and price feed from
+//! `target/deploy`, makes the mock attestor the pool's attestor, attests
+//! the sample tasks before recording them, and creates a price feed of the
+//! reward token at 1.00.
+
+use std::{
+    env,
+    error::Error,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
+
+use reward_pool::PLATFORM_FEE_PERCENTAGE;
+use reward_pool_client::{instructions, PoolAccounts, RewardAttribution};
+use serde::Serialize;
+use solana_address_lookup_table_interface::instruction::{
+    create_lookup_table, extend_lookup_table,
+};
+use solana_commitment_config::CommitmentConfig;
+use solana_keypair::{read_keypair_file, write_keypair_file, Keypair};
+use solana_loader_v3_interface::{
+    instruction as loader_instruction, state::UpgradeableLoaderState,
+};
+use solana_program::{
+    instruction::Instruction,
+    message::{v0, AddressLookupTableAccount, VersionedMessage},
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_transaction::{versioned::VersionedTransaction, Transaction};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token::state::Mint;
+
+const USAGE: &str = "usage: bootstrap [--rpc <URL>] [--program <SO> | --program-id <ADDRESS>]\n                 \
+                     [--program-keypair <FILE>] [--farmers <N>] [--fee <PERCENT>]\n                 \
+                     [--keypair-dir <DIR>] [--out <FILE>] [--mocks]";
+
+const DEFAULT_RPC_URL: &str = "http://127.0.0.1:8899";
+const DEFAULT_PROGRAM: &str = "target/deploy/reward_pool.so";
+const DEFAULT_FARMERS: u8 = 3;
+const MOCK_ATTESTOR_PROGRAM: &str = "target/deploy/mock_attestor.so";
+const MOCK_PRICE_FEED_PROGRAM: &str = "target/deploy/mock_price_feed.so";
+
+const DECIMALS: u8 = 6;
+const TOKEN: u64 = 10u64.pow(DECIMALS as u32);
+const TREASURY_FUNDS: u64 = 1_000_000 * TOKEN;
+const SAMPLE_REWARD: u64 = 100 * TOKEN;
+
+/// Sample price of the reward token, 1.00 at Pyth's usual exponent
+const SAMPLE_PRICE: i64 = 100_000_000;
+const SAMPLE_PRICE_CONFIDENCE: u64 = 50_000;
+const SAMPLE_PRICE_EXPONENT: i32 = -8;
+
+const PAYER_AIRDROP: u64 = 100 * LAMPORTS_PER_SOL;
+const PLATFORM_AUTHORITY_LAMPORTS: u64 = 10 * LAMPORTS_PER_SOL;
+const FARMER_LAMPORTS: u64 = LAMPORTS_PER_SOL;
+
+/// Program bytes per buffer write, leaving room in the transaction for the
+/// signature, accounts and instruction header
+const WRITE_CHUNK: usize = 900;
+/// Addresses added per lookup table extension transaction
+const EXTEND_CHUNK: usize = 20;
+
+enum Program {
+    Deploy {
+        path: PathBuf,
+        keypair: Option<PathBuf>,
+    },
+    Deployed(Pubkey),
+}
+
+struct Options {
+    rpc_url: String,
+    program: Program,
+    farmers: u8,
+    platform_fee_percentage: u8,
+    keypair_dir: Option<PathBuf>,
+    out: Option<String>,
+    mocks: bool,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut rpc_url = DEFAULT_RPC_URL.to_string();
+    let mut program_path = None;
+    let mut program_keypair = None;
+    let mut program_id = None;
+    let mut farmers = DEFAULT_FARMERS;
+    let mut platform_fee_percentage = PLATFORM_FEE_PERCENTAGE;
+    let mut keypair_dir = None;
+    let mut out = None;
+    let mut mocks = false;
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {flag}"));
+        match flag.as_str() {
+            "--rpc" => rpc_url = value()?.clone(),
+            "--program" => program_path = Some(PathBuf::from(value()?)),
+            "--program-keypair" => program_keypair = Some(PathBuf::from(value()?)),
+            "--program-id" => {
+                let input = value()?;
+                program_id = Some(
+                    input
+                        .parse()
+                        .map_err(|_| format!("invalid address {input}"))?,
+                )
+            }
+            "--farmers" => {
+                let input = value()?;
+                farmers = input
+                    .parse()
+                    .map_err(|_| format!("invalid farmer count {input}"))?
+            }
+            "--fee" => {
+                let input = value()?;
+                platform_fee_percentage = input
+                    .parse()
+                    .ok()
+                    .filter(|percent| *percent <= 100)
+                    .ok_or(format!("invalid fee percentage {input}"))?
+            }
+            "--keypair-dir" => keypair_dir = Some(PathBuf::from(value()?)),
+            "--out" => out = Some(value()?.clone()),
+            "--mocks" => mocks = true,
+            other => return Err(format!("unknown argument {other}")),
+        }
+    }
+
+    let program = match (program_id, program_path, program_keypair) {
+        (Some(program_id), None, None) => Program::Deployed(program_id),
+        (Some(_), _, _) => {
+            return Err(
+                "--program-id cannot be combined with --program or --program-keypair".into(),
+            )
+        }
+        (None, path, keypair) => Program::Deploy {
+            path: path.unwrap_or_else(|| PathBuf::from(DEFAULT_PROGRAM)),
+            keypair,
+        },
+    };
+    Ok(Options {
+        rpc_url,
+        program,
+        farmers,
+        platform_fee_percentage,
+        keypair_dir,
+        out,
+        mocks,
+    })
+}
+
+/// Addresses of the bootstrapped environment, as the front-end reads them
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Environment {
+    rpc_url: String,
+    program_id: String,
+    payer: String,
+    reward_mint: String,
+    decimals: u8,
+    mint_authority: String,
+    platform_authority: String,
+    platform_treasury: String,
+    pool: String,
+    platform_fee_percentage: u8,
+    instruction_counters: String,
+    farmers: Vec<Farmer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mocks: Option<Mocks>,
+}
+
+/// Mock programs deployed with `--mocks`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Mocks {
+    attestor_program: String,
+    price_feed_program: String,
+    price_feed: String,
+    price_feed_authority: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Farmer {
+    wallet: String,
+    reward_account: String,
+    farmer_state: String,
+    sample_task_id: String,
+}
+
+/// A fresh keypair for `name`, saved to `keypair_dir` if there is one
+fn new_keypair(keypair_dir: Option<&Path>, name: &str) -> Result<Keypair, Box<dyn Error>> {
+    let keypair = Keypair::new();
+    if let Some(dir) = keypair_dir {
+        let path = dir.join(format!("{name}.json"));
+        write_keypair_file(&keypair, &path)
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+    }
+    Ok(keypair)
+}
+
+struct Bootstrap {
+    rpc: RpcClient,
+    payer: Keypair,
+    keypair_dir: Option<PathBuf>,
+}
+
+impl Bootstrap {
+    async fn send(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Signature, Box<dyn Error>> {
+        let blockhash = self.rpc.get_latest_blockhash().await?;
+        let mut keypairs = vec![&self.payer];
+        keypairs.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &keypairs,
+            blockhash,
+        );
+        Ok(self.rpc.send_and_confirm_transaction(&transaction).await?)
+    }
+
+    /// Sends `instructions` in a v0 transaction, looking accounts up in
+    /// `lookup_table`
+    async fn send_v0(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+        lookup_table: &AddressLookupTableAccount,
+    ) -> Result<Signature, Box<dyn Error>> {
+        let blockhash = self.rpc.get_latest_blockhash().await?;
+        let message = v0::Message::try_compile(
+            &self.payer.pubkey(),
+            instructions,
+            std::slice::from_ref(lookup_table),
+            blockhash,
+        )?;
+        let mut keypairs: Vec<&dyn Signer> = vec![&self.payer];
+        keypairs.extend(signers.iter().map(|signer| *signer as &dyn Signer));
+        let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), &keypairs)?;
+        Ok(self.rpc.send_and_confirm_transaction(&transaction).await?)
+    }
+
+    /// Creates a lookup table of the payer holding `addresses`, and waits
+    /// until they can be looked up
+    async fn create_lookup_table(
+        &self,
+        addresses: Vec<Pubkey>,
+    ) -> Result<AddressLookupTableAccount, Box<dyn Error>> {
+        let payer = self.payer.pubkey();
+        let recent_slot = self.rpc.get_slot().await?;
+        let (create, key) = create_lookup_table(payer, payer, recent_slot);
+        self.send(&[create], &[]).await?;
+        for chunk in addresses.chunks(EXTEND_CHUNK) {
+            let extend = extend_lookup_table(key, payer, Some(payer), chunk.to_vec());
+            self.send(&[extend], &[]).await?;
+        }
+
+        // Addresses added in a slot become usable in the next one
+        let extended_at = self.rpc.get_slot().await?;
+        while self.rpc.get_slot().await? <= extended_at {
+            tokio::time::sleep(Duration::from_millis(400)).await;
+        }
+        Ok(AddressLookupTableAccount { key, addresses })
+    }
+
+    /// Sends every transaction, signed by the payer alone, before waiting
+    /// for any, as the writes of a program buffer are independent of each
+    /// other
+    async fn send_all(&self, transactions: Vec<Vec<Instruction>>) -> Result<(), Box<dyn Error>> {
+        let blockhash = self.rpc.get_latest_blockhash().await?;
+        let mut pending = Vec::with_capacity(transactions.len());
+        for instructions in transactions {
+            let transaction = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&self.payer.pubkey()),
+                &[&self.payer],
+                blockhash,
+            );
+            pending.push(self.rpc.send_transaction(&transaction).await?);
+        }
+
+        while !pending.is_empty() {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            let mut unconfirmed = Vec::new();
+            for signatures in pending.chunks(256) {
+                let statuses = self.rpc.get_signature_statuses(signatures).await?.value;
+                for (signature, status) in signatures.iter().zip(statuses) {
+                    match status {
+                        Some(status) if status.err.is_some() => {
+                            return Err(format!("{signature} failed: {:?}", status.err).into())
+                        }
+                        Some(status)
+                            if status.satisfies_commitment(CommitmentConfig::confirmed()) => {}
+                        _ => unconfirmed.push(*signature),
+                    }
+                }
+            }
+            if !unconfirmed.is_empty()
+                && !self
+                    .rpc
+                    .is_blockhash_valid(&blockhash, CommitmentConfig::processed())
+                    .await?
+            {
+                return Err(
+                    format!("{} transactions expired unconfirmed", unconfirmed.len()).into(),
+                );
+            }
+            pending = unconfirmed;
+        }
+        Ok(())
+    }
+
+    async fn airdrop(&self, recipient: &Pubkey, lamports: u64) -> Result<(), Box<dyn Error>> {
+        let signature = self.rpc.request_airdrop(recipient, lamports).await?;
+        self.rpc.poll_for_signature(&signature).await?;
+        Ok(())
+    }
+
+    async fn fund(&self, recipient: &Pubkey, lamports: u64) -> Result<(), Box<dyn Error>> {
+        let transfer = solana_system_interface::instruction::transfer(
+            &self.payer.pubkey(),
+            recipient,
+            lamports,
+        );
+        self.send(&[transfer], &[]).await?;
+        Ok(())
+    }
+
+    fn keypair(&self, name: &str) -> Result<Keypair, Box<dyn Error>> {
+        new_keypair(self.keypair_dir.as_deref(), name)
+    }
+
+    /// Deploys `path` through the upgradeable loader at `program`'s address,
+    /// with the payer as upgrade authority
+    #[allow(deprecated)] // Local validators still deploy through loader-v3
+    async fn deploy(&self, path: &Path, program: &Keypair) -> Result<(), Box<dyn Error>> {
+        let program_data =
+            fs::read(path).map_err(|error| format!("{}: {error}", path.display()))?;
+        let buffer = Keypair::new();
+        let payer = self.payer.pubkey();
+
+        let buffer_lamports = self
+            .rpc
+            .get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_buffer(
+                program_data.len(),
+            ))
+            .await?;
+        let create_buffer = loader_instruction::create_buffer(
+            &payer,
+            &buffer.pubkey(),
+            &payer,
+            buffer_lamports,
+            program_data.len(),
+        )?;
+        self.send(&create_buffer, &[&buffer]).await?;
+
+        let writes = program_data
+            .chunks(WRITE_CHUNK)
+            .enumerate()
+            .map(|(index, chunk)| {
+                vec![loader_instruction::write(
+                    &buffer.pubkey(),
+                    &payer,
+                    (index * WRITE_CHUNK) as u32,
+                    chunk.to_vec(),
+                )]
+            })
+            .collect();
+        self.send_all(writes).await?;
+
+        let program_lamports = self
+            .rpc
+            .get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_program())
+            .await?;
+        // Room for upgrades that grow the program
+        let deploy = loader_instruction::deploy_with_max_program_len(
+            &payer,
+            &program.pubkey(),
+            &buffer.pubkey(),
+            &payer,
+            program_lamports,
+            program_data.len() * 2,
+        )?;
+        self.send(&deploy, &[program]).await?;
+        Ok(())
+    }
+
+    async fn create_mint(&self, mint: &Keypair) -> Result<(), Box<dyn Error>> {
+        let lamports = self
+            .rpc
+            .get_minimum_balance_for_rent_exemption(Mint::LEN)
+            .await?;
+        let instructions = [
+            solana_system_interface::instruction::create_account(
+                &self.payer.pubkey(),
+                &mint.pubkey(),
+                lamports,
+                Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &self.payer.pubkey(),
+                None,
+                DECIMALS,
+            )?,
+        ];
+        self.send(&instructions, &[mint]).await?;
+        Ok(())
+    }
+}
+
+async fn bootstrap(options: &Options) -> Result<Environment, Box<dyn Error>> {
+    if let Program::Deploy { path, .. } = &options.program {
+        if !path.is_file() {
+            return Err(format!(
+                "{} not found; build the program with `cargo build-sbf` or pass --program",
+                path.display()
+            )
+            .into());
+        }
+    }
+    if options.mocks {
+        for path in [MOCK_ATTESTOR_PROGRAM, MOCK_PRICE_FEED_PROGRAM] {
+            if !Path::new(path).is_file() {
+                return Err(
+                    format!("{path} not found; build the mocks with `cargo build-sbf`").into(),
+                );
+            }
+        }
+    }
+    if let Some(dir) = &options.keypair_dir {
+        fs::create_dir_all(dir).map_err(|error| format!("{}: {error}", dir.display()))?;
+    }
+    let bootstrap = Bootstrap {
+        rpc: RpcClient::new_with_commitment(options.rpc_url.clone(), CommitmentConfig::confirmed()),
+        payer: new_keypair(options.keypair_dir.as_deref(), "payer")?,
+        keypair_dir: options.keypair_dir.clone(),
+    };
+    let payer = bootstrap.payer.pubkey();
+    bootstrap.airdrop(&payer, PAYER_AIRDROP).await?;
+    eprintln!("Funded payer {payer}");
+
+    let program_id = match &options.program {
+        Program::Deployed(program_id) => {
+            let account = bootstrap.rpc.get_account(program_id).await?;
+            if !account.executable {
+                return Err(format!("{program_id} is not a deployed program").into());
+            }
+            *program_id
+        }
+        Program::Deploy { path, keypair } => {
+            let program = match keypair {
+                Some(keypair) => read_keypair_file(keypair)
+                    .map_err(|error| format!("{}: {error}", keypair.display()))?,
+                None => bootstrap.keypair("program")?,
+            };
+            bootstrap.deploy(path, &program).await?;
+            eprintln!("Deployed {} at {}", path.display(), program.pubkey());
+            program.pubkey()
+        }
+    };
+
+    let reward_mint = bootstrap.keypair("reward-mint")?;
+    bootstrap.create_mint(&reward_mint).await?;
+    eprintln!("Created reward mint {}", reward_mint.pubkey());
+
+    let platform_authority = bootstrap.keypair("platform-authority")?;
+    bootstrap
+        .fund(&platform_authority.pubkey(), PLATFORM_AUTHORITY_LAMPORTS)
+        .await?;
+    let accounts = PoolAccounts::for_mint_with_program_id(program_id, reward_mint.pubkey());
+    let platform_treasury = accounts.platform_treasury;
+    let initialize = [
+        instructions::initialize_pool(
+            &accounts,
+            &platform_authority.pubkey(),
+            options.platform_fee_percentage,
+        ),
+        spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &reward_mint.pubkey(),
+            &platform_treasury,
+            &payer,
+            &[],
+            TREASURY_FUNDS,
+        )?,
+    ];
+    bootstrap.send(&initialize, &[&platform_authority]).await?;
+    eprintln!("Initialized and funded pool {}", accounts.pool);
+
+    let mut attestor_program = None;
+    let mocks = if options.mocks {
+        let attestor = bootstrap.keypair("mock-attestor")?;
+        bootstrap
+            .deploy(Path::new(MOCK_ATTESTOR_PROGRAM), &attestor)
+            .await?;
+        let set_attestor = instructions::set_attestor(
+            &accounts,
+            &platform_authority.pubkey(),
+            Some(&attestor.pubkey()),
+            0,
+        );
+        bootstrap
+            .send(&[set_attestor], &[&platform_authority])
+            .await?;
+        eprintln!("Deployed mock attestor {}", attestor.pubkey());
+        attestor_program = Some(attestor.pubkey());
+
+        let price_feed_program = bootstrap.keypair("mock-price-feed")?;
+        bootstrap
+            .deploy(Path::new(MOCK_PRICE_FEED_PROGRAM), &price_feed_program)
+            .await?;
+        let price_feed = bootstrap.keypair("price-feed")?;
+        let create_price_feed = mock_price_feed::create_price_feed(mock_price_feed::set_price(
+            &price_feed_program.pubkey(),
+            &payer,
+            &price_feed.pubkey(),
+            SAMPLE_PRICE,
+            SAMPLE_PRICE_CONFIDENCE,
+            SAMPLE_PRICE_EXPONENT,
+            None,
+        ));
+        bootstrap.send(&[create_price_feed], &[&price_feed]).await?;
+        eprintln!("Created price feed {}", price_feed.pubkey());
+
+        Some(Mocks {
+            attestor_program: attestor.pubkey().to_string(),
+            price_feed_program: price_feed_program.pubkey().to_string(),
+            price_feed: price_feed.pubkey().to_string(),
+            price_feed_authority: payer.to_string(),
+        })
+    } else {
+        None
+    };
+
+    // A RecordReward with every account inline does not fit a transaction
+    let lookup_table = bootstrap
+        .create_lookup_table(instructions::record_reward_lookup_addresses(&accounts))
+        .await?;
+    eprintln!("Created lookup table {}", lookup_table.key);
+
+    let mut farmers = Vec::new();
+    for index in 0..options.farmers {
+        let farmer = bootstrap.keypair(&format!("farmer-{index}"))?;
+        bootstrap.fund(&farmer.pubkey(), FARMER_LAMPORTS).await?;
+
+        // A first reward creates the farmer's state; their reward account
+        // is created up front since RecordReward expects it once they are
+        // paid
+        let sample_task_id = format!("bootstrap-{index}");
+        let mut register = vec![create_associated_token_account_idempotent(
+            &payer,
+            &farmer.pubkey(),
+            &reward_mint.pubkey(),
+            &spl_token::id(),
+        )];
+        let mut record_reward = instructions::record_reward(
+            &accounts,
+            &platform_authority.pubkey(),
+            &farmer.pubkey(),
+            SAMPLE_REWARD,
+            sample_task_id.clone(),
+            RewardAttribution::default(),
+            instructions::current_day(),
+        );
+        if let Some(attestor_program) = &attestor_program {
+            register.push(mock_attestor::set_attestation(
+                attestor_program,
+                &payer,
+                &sample_task_id,
+                &farmer.pubkey(),
+                SAMPLE_REWARD,
+                0,
+            ));
+            let (attestation, _) = mock_attestor::find_attestation_address(
+                attestor_program,
+                &sample_task_id,
+                &farmer.pubkey(),
+            );
+            record_reward = instructions::attest_recorded_reward(record_reward, &attestation);
+        }
+        register.push(record_reward);
+        bootstrap
+            .send_v0(&register, &[&platform_authority], &lookup_table)
+            .await?;
+        eprintln!("Registered farmer {}", farmer.pubkey());
+
+        farmers.push(Farmer {
+            wallet: farmer.pubkey().to_string(),
+            reward_account: accounts.reward_account(&farmer.pubkey()).to_string(),
+            farmer_state: accounts.farmer_state(&farmer.pubkey()).to_string(),
+            sample_task_id,
+        });
+    }
+
+    Ok(Environment {
+        rpc_url: options.rpc_url.clone(),
+        program_id: program_id.to_string(),
+        payer: payer.to_string(),
+        reward_mint: reward_mint.pubkey().to_string(),
+        decimals: DECIMALS,
+        mint_authority: payer.to_string(),
+        platform_authority: platform_authority.pubkey().to_string(),
+        platform_treasury: platform_treasury.to_string(),
+        pool: accounts.pool.to_string(),
+        platform_fee_percentage: options.platform_fee_percentage,
+        instruction_counters: accounts.instruction_counters().to_string(),
+        farmers,
+        mocks,
+    })
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = parse_options(&args).unwrap_or_else(|error| {
+        eprintln!("{error}\n{USAGE}");
+        process::exit(2);
+    });
+
+    let environment = bootstrap(&options).await?;
+    let writer: Box<dyn io::Write> = match &options.out {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+    serde_json::to_writer_pretty(writer, &environment)?;
+    if options.out.is_none() {
+        println!();
+    }
+    Ok(())
+}
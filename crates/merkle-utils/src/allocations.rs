@@ -0,0 +1,182 @@
+use std::{collections::HashSet, fs, io, path::Path};
+
+use serde::Deserialize;
+use solana_program::pubkey::Pubkey;
+use thiserror::Error;
+
+// Allocation files
+//
+// Balances come from a CSV file with a `farmer,amount` header or a JSON
+// array of `{"farmer": ..., "amount": ...}` objects, told apart by the
+// file extension. Amounts are raw base units; whitespace around CSV fields
+// is ignored.
+
+/// Reasons an allocation file cannot be turned into a tree
+#[derive(Debug, Error)]
+pub enum AllocationError {
+    #[error("cannot read allocations: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid CSV allocations: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("invalid JSON allocations: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("allocation files must end in .csv or .json")]
+    UnknownFormat,
+    #[error("invalid farmer address {0}")]
+    InvalidFarmer(String),
+    #[error("farmer {0} is allocated more than once")]
+    DuplicateFarmer(Pubkey),
+    #[error("allocations add up to more than u64::MAX")]
+    TotalOverflow,
+}
+
+#[derive(Deserialize)]
+struct Allocation {
+    farmer: String,
+    amount: u64,
+}
+
+/// Reads the balances of an allocation file, one per farmer
+pub fn read_allocations(path: &Path) -> Result<Vec<(Pubkey, u64)>, AllocationError> {
+    let allocations: Vec<Allocation> = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_path(path)?
+            .deserialize()
+            .collect::<Result<_, _>>()?,
+        Some("json") => serde_json::from_str(&fs::read_to_string(path)?)?,
+        _ => return Err(AllocationError::UnknownFormat),
+    };
+
+    let mut farmers = HashSet::with_capacity(allocations.len());
+    let mut total: u64 = 0;
+    allocations
+        .into_iter()
+        .map(|allocation| {
+            let farmer: Pubkey = allocation
+                .farmer
+                .trim()
+                .parse()
+                .map_err(|_| AllocationError::InvalidFarmer(allocation.farmer.clone()))?;
+            // `BalanceTree` holds one leaf per farmer, and its total must
+            // fit the `u64` the program counts in
+            if !farmers.insert(farmer) {
+                return Err(AllocationError::DuplicateFarmer(farmer));
+            }
+            total = total
+                .checked_add(allocation.amount)
+                .ok_or(AllocationError::TotalOverflow)?;
+            Ok((farmer, allocation.amount))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Allocation file in the temp directory, removed when dropped
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, contents: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("merkle-utils-{}-{name}", std::process::id()));
+            fs::write(&path, contents).unwrap();
+            Self(path)
+        }
+
+        fn read(&self) -> Result<Vec<(Pubkey, u64)>, AllocationError> {
+            read_allocations(&self.0)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn farmer(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    #[test]
+    fn csv_and_json_files_read_the_same_balances() {
+        let csv = TempFile::new(
+            "same.csv",
+            &format!("farmer,amount\n{}, 100\n {} ,200\n", farmer(1), farmer(2)),
+        );
+        let json = TempFile::new(
+            "same.json",
+            &format!(
+                r#"[{{"farmer":"{}","amount":100}},{{"farmer":"{}","amount":200}}]"#,
+                farmer(1),
+                farmer(2)
+            ),
+        );
+
+        let expected = vec![(farmer(1), 100), (farmer(2), 200)];
+        assert_eq!(csv.read().unwrap(), expected);
+        assert_eq!(json.read().unwrap(), expected);
+    }
+
+    #[test]
+    fn a_farmer_allocated_twice_is_rejected() {
+        let file = TempFile::new(
+            "duplicate.csv",
+            &format!("farmer,amount\n{},1\n{},2\n", farmer(1), farmer(1)),
+        );
+
+        assert!(matches!(
+            file.read(),
+            Err(AllocationError::DuplicateFarmer(duplicate)) if duplicate == farmer(1)
+        ));
+    }
+
+    #[test]
+    fn a_total_beyond_u64_is_rejected() {
+        let file = TempFile::new(
+            "overflow.csv",
+            &format!(
+                "farmer,amount\n{},{}\n{},1\n",
+                farmer(1),
+                u64::MAX,
+                farmer(2)
+            ),
+        );
+
+        assert!(matches!(file.read(), Err(AllocationError::TotalOverflow)));
+    }
+
+    #[test]
+    fn malformed_files_are_rejected() {
+        let invalid_farmer = TempFile::new("farmer.csv", "farmer,amount\nnobody,1\n");
+        assert!(matches!(
+            invalid_farmer.read(),
+            Err(AllocationError::InvalidFarmer(farmer)) if farmer == "nobody"
+        ));
+
+        let negative = TempFile::new(
+            "negative.csv",
+            &format!("farmer,amount\n{},-1\n", farmer(1)),
+        );
+        assert!(matches!(negative.read(), Err(AllocationError::Csv(_))));
+
+        let not_a_list = TempFile::new("object.json", "{}");
+        assert!(matches!(not_a_list.read(), Err(AllocationError::Json(_))));
+
+        let unknown = TempFile::new("balances.txt", "");
+        assert!(matches!(
+            unknown.read(),
+            Err(AllocationError::UnknownFormat)
+        ));
+
+        assert!(matches!(
+            read_allocations(Path::new("/nonexistent/balances.json")),
+            Err(AllocationError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,21 @@
+//! Off-chain tooling for the reward pool's balance merkle trees.
+//!
+//! [`BalanceTree`] builds trees with the hashing of
+//! `reward_pool_interface::merkle`, the code the program verifies proofs
+//! with, so roots and proofs always match on chain. [`read_allocations`]
+//! loads balances from CSV or JSON, and [`ProofFile`] holds a root with the
+//! proof of every balance. The `merkle-utils` binary wraps them:
+//!
+//! ```text
+//! merkle-utils root <ALLOCATIONS>
+//! merkle-utils proofs <ALLOCATIONS> [--out <FILE>]
+//! merkle-utils verify <PROOFS> [--root <ROOT>]
+//! ```
+
+pub mod allocations;
+pub mod proofs;
+pub mod tree;
+
+pub use allocations::{read_allocations, AllocationError};
+pub use proofs::{parse_hash, Claim, InvalidClaim, ProofFile};
+pub use tree::BalanceTree;
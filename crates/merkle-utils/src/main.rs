@@ -0,0 +1,124 @@
+//! Builds balance merkle roots and proofs, and verifies proof files.
+//!
+//! ```text
+//! merkle-utils root <ALLOCATIONS>
+//! merkle-utils proofs <ALLOCATIONS> [--out <FILE>]
+//! merkle-utils verify <PROOFS> [--root <ROOT>]
+//! ```
+//!
+//! Allocations are a `.csv` file with a `farmer,amount` header or a `.json`
+//! array of `{"farmer", "amount"}` objects. `root` prints the base58 root to
+//! pass to `ExportState`; `proofs` writes the root and every farmer's proof
+//! as JSON, to stdout unless `--out` is given. `verify` checks every proof
+//! of a proofs file against `--root`, or the file's own root, and exits
+//! with status 1 if any fails.
+
+use std::{error::Error, fs, path::Path, process};
+
+use reward_pool_merkle_utils::{parse_hash, read_allocations, BalanceTree, ProofFile};
+
+const USAGE: &str = "usage: merkle-utils root <ALLOCATIONS>\n       \
+                     merkle-utils proofs <ALLOCATIONS> [--out <FILE>]\n       \
+                     merkle-utils verify <PROOFS> [--root <ROOT>]";
+
+enum Command {
+    Root {
+        allocations: String,
+    },
+    Proofs {
+        allocations: String,
+        out: Option<String>,
+    },
+    Verify {
+        proofs: String,
+        root: Option<[u8; 32]>,
+    },
+}
+
+fn parse_command(args: &[String]) -> Result<Command, String> {
+    let (command, rest) = args.split_first().ok_or("missing command")?;
+    let (path, flags) = rest
+        .split_first()
+        .ok_or(format!("missing file for {command}"))?;
+
+    let mut out = None;
+    let mut root = None;
+    let mut flags = flags.iter();
+    while let Some(flag) = flags.next() {
+        let value = flags.next().ok_or(format!("missing value for {flag}"))?;
+        match (command.as_str(), flag.as_str()) {
+            ("proofs", "--out") => out = Some(value.clone()),
+            ("verify", "--root") => {
+                root = Some(parse_hash(value).ok_or(format!("invalid root {value}"))?)
+            }
+            _ => return Err(format!("unknown argument {flag}")),
+        }
+    }
+
+    let path = path.clone();
+    match command.as_str() {
+        "root" => Ok(Command::Root { allocations: path }),
+        "proofs" => Ok(Command::Proofs {
+            allocations: path,
+            out,
+        }),
+        "verify" => Ok(Command::Verify { proofs: path, root }),
+        other => Err(format!("unknown command {other}")),
+    }
+}
+
+fn build_tree(allocations: &str) -> Result<BalanceTree, Box<dyn Error>> {
+    let tree = BalanceTree::new(read_allocations(Path::new(allocations))?);
+    eprintln!(
+        "{} farmers, {} tokens in total",
+        tree.farmer_count(),
+        tree.total_amount()
+    );
+    Ok(tree)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = parse_command(&args).unwrap_or_else(|error| {
+        eprintln!("{error}\n{USAGE}");
+        process::exit(2);
+    });
+
+    if let Err(error) = run(command) {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
+
+fn run(command: Command) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Root { allocations } => {
+            let tree = build_tree(&allocations)?;
+            println!("{}", ProofFile::from_tree(&tree).root);
+        }
+        Command::Proofs { allocations, out } => {
+            let tree = build_tree(&allocations)?;
+            let json = serde_json::to_string_pretty(&ProofFile::from_tree(&tree))?;
+            match out {
+                Some(path) => fs::write(path, json + "\n")?,
+                None => println!("{json}"),
+            }
+        }
+        Command::Verify { proofs, root } => {
+            let proof_file: ProofFile = serde_json::from_str(&fs::read_to_string(proofs)?)?;
+            let invalid = proof_file.verify(root.as_ref())?;
+            for claim in &invalid {
+                println!("{}: {}", claim.farmer, claim.reason);
+            }
+            eprintln!(
+                "{} of {} proofs valid",
+                proof_file.claims.len() - invalid.len(),
+                proof_file.claims.len()
+            );
+            if !invalid.is_empty() {
+                process::exit(1);
+            }
+        }
+    }
+    Ok(())
+}
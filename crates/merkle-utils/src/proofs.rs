@@ -0,0 +1,182 @@
+use reward_pool_interface::merkle::verify_balance_proof;
+use serde::{Deserialize, Serialize};
+use solana_program::{hash::Hash, pubkey::Pubkey};
+
+use crate::BalanceTree;
+
+// Proof files
+//
+// Everything a claimant needs to prove their balance, written as JSON with
+// hashes and addresses in base58 like the rest of the tooling. The file is
+// self-contained: `verify` checks it against its own root, or against the
+// root committed on chain.
+
+/// A tree's root and the proof of every balance in it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofFile {
+    pub root: String,
+    pub farmer_count: u32,
+    pub total_amount: u64,
+    pub claims: Vec<Claim>,
+}
+
+/// One farmer's balance and its proof, sibling hashes from the leaf up
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Claim {
+    pub farmer: String,
+    pub amount: u64,
+    pub proof: Vec<String>,
+}
+
+/// A claim of a proof file that does not link to the root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidClaim {
+    pub farmer: String,
+    pub reason: &'static str,
+}
+
+impl ProofFile {
+    /// Proofs of every balance of `tree`, in farmer order
+    pub fn from_tree(tree: &BalanceTree) -> Self {
+        let claims = tree
+            .balances()
+            .iter()
+            .map(|(farmer, _)| {
+                let (amount, proof) = tree.proof(farmer).expect("farmer is in the tree");
+                Claim {
+                    farmer: farmer.to_string(),
+                    amount,
+                    proof: proof
+                        .iter()
+                        .map(|sibling| Hash::new_from_array(*sibling).to_string())
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            root: Hash::new_from_array(tree.root()).to_string(),
+            farmer_count: tree.farmer_count(),
+            total_amount: tree.total_amount(),
+            claims,
+        }
+    }
+
+    /// Checks every claim against `root`, the file's own root if `None`,
+    /// with the program's own verification. Returns the claims that fail.
+    pub fn verify(&self, root: Option<&[u8; 32]>) -> Result<Vec<InvalidClaim>, String> {
+        let root = match root {
+            Some(root) => *root,
+            None => parse_hash(&self.root).ok_or(format!("invalid root {}", self.root))?,
+        };
+
+        Ok(self
+            .claims
+            .iter()
+            .filter_map(|claim| {
+                let reason = match claim.check(&root) {
+                    Ok(true) => return None,
+                    Ok(false) => "proof does not lead to the root",
+                    Err(reason) => reason,
+                };
+                Some(InvalidClaim {
+                    farmer: claim.farmer.clone(),
+                    reason,
+                })
+            })
+            .collect())
+    }
+}
+
+impl Claim {
+    /// Whether the claim links to `root`, or why it cannot be checked
+    pub fn check(&self, root: &[u8; 32]) -> Result<bool, &'static str> {
+        let farmer: Pubkey = self.farmer.parse().map_err(|_| "invalid farmer address")?;
+        let proof = self
+            .proof
+            .iter()
+            .map(|sibling| parse_hash(sibling).ok_or("invalid proof hash"))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(verify_balance_proof(root, &farmer, self.amount, &proof))
+    }
+}
+
+/// Parses a base58 hash, as printed for roots and proofs
+pub fn parse_hash(input: &str) -> Option<[u8; 32]> {
+    input.parse::<Hash>().ok().map(|hash| hash.to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proof_file() -> ProofFile {
+        ProofFile::from_tree(&BalanceTree::new(
+            (1..=5)
+                .map(|index| (Pubkey::new_from_array([index; 32]), index as u64 * 100))
+                .collect(),
+        ))
+    }
+
+    fn invalid(claim: &Claim, reason: &'static str) -> InvalidClaim {
+        InvalidClaim {
+            farmer: claim.farmer.clone(),
+            reason,
+        }
+    }
+
+    #[test]
+    fn a_generated_file_verifies_against_its_own_root() {
+        let file = proof_file();
+        let root = parse_hash(&file.root).unwrap();
+
+        assert_eq!(file.farmer_count, 5);
+        assert_eq!(file.total_amount, 1_500);
+        assert_eq!(file.verify(None), Ok(vec![]));
+        assert_eq!(file.verify(Some(&root)), Ok(vec![]));
+    }
+
+    #[test]
+    fn every_claim_fails_against_another_root() {
+        let file = proof_file();
+
+        let failures = file.verify(Some(&[7; 32])).unwrap();
+        assert_eq!(failures.len(), file.claims.len());
+        assert!(failures
+            .iter()
+            .all(|failure| failure.reason == "proof does not lead to the root"));
+    }
+
+    #[test]
+    fn tampered_claims_are_reported_with_their_reason() {
+        let mut file = proof_file();
+        file.claims[0].amount += 1;
+        file.claims[1].farmer = "not an address".to_string();
+        file.claims[2].proof[0] = "not a hash".to_string();
+
+        assert_eq!(
+            file.verify(None),
+            Ok(vec![
+                invalid(&file.claims[0], "proof does not lead to the root"),
+                invalid(&file.claims[1], "invalid farmer address"),
+                invalid(&file.claims[2], "invalid proof hash"),
+            ])
+        );
+    }
+
+    #[test]
+    fn an_invalid_root_cannot_be_verified_against() {
+        let mut file = proof_file();
+        file.root = "root".to_string();
+
+        assert_eq!(file.verify(None), Err("invalid root root".to_string()));
+    }
+
+    #[test]
+    fn a_file_round_trips_through_json() {
+        let file = proof_file();
+        let json = serde_json::to_string(&file).unwrap();
+
+        assert_eq!(serde_json::from_str::<ProofFile>(&json).unwrap(), file);
+    }
+}
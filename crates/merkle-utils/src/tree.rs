@@ -0,0 +1,154 @@
+use reward_pool_interface::merkle::{balance_leaf, hash_pair};
+use solana_program::pubkey::Pubkey;
+
+// Farmer balance tree committed to by `ExportState`
+//
+// The exporter builds the tree from the balances the pool owes and submits
+// its root; whoever runs the import on the new deployment rebuilds the same
+// tree from the same balances to get each farmer's proof. A node without a
+// sibling moves up a level unchanged.
+#[derive(Debug, Clone)]
+pub struct BalanceTree {
+    balances: Vec<(Pubkey, u64)>,
+    /// Levels from the leaves up to the root
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl BalanceTree {
+    /// Builds the tree over `balances`, one entry per farmer. Entries are
+    /// sorted by farmer so the root does not depend on their order.
+    pub fn new(mut balances: Vec<(Pubkey, u64)>) -> Self {
+        balances.sort_by_key(|(farmer, _)| *farmer);
+
+        let mut levels = vec![balances
+            .iter()
+            .map(|(farmer, amount)| balance_leaf(farmer, *amount))
+            .collect::<Vec<_>>()];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let level = levels.last().expect("checked above");
+            let parents = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_pair(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(parents);
+        }
+
+        Self { balances, levels }
+    }
+
+    /// Root to pass to `ExportState`, all zeros for an empty tree
+    pub fn root(&self) -> [u8; 32] {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Balances in the tree, sorted by farmer
+    pub fn balances(&self) -> &[(Pubkey, u64)] {
+        &self.balances
+    }
+
+    /// Farmers in the tree
+    pub fn farmer_count(&self) -> u32 {
+        self.balances.len() as u32
+    }
+
+    /// Sum of the balances in the tree
+    pub fn total_amount(&self) -> u64 {
+        self.balances.iter().map(|(_, amount)| amount).sum()
+    }
+
+    /// Balance of `farmer` and its proof for `ImportState`, `None` if the
+    /// farmer is not in the tree
+    pub fn proof(&self, farmer: &Pubkey) -> Option<(u64, Vec<[u8; 32]>)> {
+        let mut index = self
+            .balances
+            .binary_search_by_key(farmer, |(farmer, _)| *farmer)
+            .ok()?;
+        let amount = self.balances[index].1;
+
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        Some((amount, proof))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reward_pool_interface::merkle::verify_balance_proof;
+
+    use super::*;
+
+    fn balances(count: u8) -> Vec<(Pubkey, u64)> {
+        (1..=count)
+            .map(|index| (Pubkey::new_from_array([index; 32]), index as u64 * 100))
+            .collect()
+    }
+
+    #[test]
+    fn every_proof_of_an_uneven_tree_verifies() {
+        for count in 1..=9 {
+            let tree = BalanceTree::new(balances(count));
+            for (farmer, amount) in balances(count) {
+                let (proven, proof) = tree.proof(&farmer).unwrap();
+                assert_eq!(proven, amount);
+                assert!(verify_balance_proof(&tree.root(), &farmer, amount, &proof));
+                assert!(!verify_balance_proof(
+                    &tree.root(),
+                    &farmer,
+                    amount + 1,
+                    &proof
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn a_single_balance_is_its_own_root() {
+        let (farmer, amount) = balances(1)[0];
+        let tree = BalanceTree::new(vec![(farmer, amount)]);
+
+        assert_eq!(tree.root(), balance_leaf(&farmer, amount));
+        assert_eq!(tree.proof(&farmer), Some((amount, vec![])));
+    }
+
+    #[test]
+    fn an_empty_tree_has_a_zero_root_and_no_proofs() {
+        let tree = BalanceTree::new(vec![]);
+
+        assert_eq!(tree.root(), [0; 32]);
+        assert_eq!(tree.farmer_count(), 0);
+        assert_eq!(tree.total_amount(), 0);
+        assert_eq!(tree.proof(&Pubkey::new_from_array([1; 32])), None);
+    }
+
+    #[test]
+    fn the_root_does_not_depend_on_the_order_of_the_balances() {
+        let mut reversed = balances(5);
+        reversed.reverse();
+
+        let tree = BalanceTree::new(reversed);
+        assert_eq!(tree.root(), BalanceTree::new(balances(5)).root());
+        assert_eq!(tree.balances(), balances(5));
+        assert_eq!(tree.farmer_count(), 5);
+        assert_eq!(tree.total_amount(), 1_500);
+    }
+
+    #[test]
+    fn a_farmer_outside_the_tree_has_no_proof() {
+        let tree = BalanceTree::new(balances(3));
+
+        assert_eq!(tree.proof(&Pubkey::new_from_array([9; 32])), None);
+    }
+}
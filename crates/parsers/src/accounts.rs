@@ -0,0 +1,117 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use reward_pool::{
+    AuthorityRecovery, BuybackConfig, Campaign, ClaimLedger, ClaimShard, DailyStats, FactoryPool,
+    FarmerRecovery, FarmerState, FarmerStats, FeeDiscounts, FeeInvoice, FeeLedger, FeeOverrides,
+    FeeRebate, Guild, ImportedBalance, InstructionCounters, InterestPolicy, KeyHistory,
+    MilestoneBonuses, ParameterHistory, PayoutAddress, PayoutQueue, PayoutTicket, PendingAction,
+    PendingReward, PendingTransferPolicy, Platform, PoolArchive, PoolAttestor, PoolCharity,
+    PoolGuardians, PoolMetadata, PoolParameters, PoolSnapshot, PoolVerifier, ProtocolConfig,
+    ProtocolLiquidity, RewardApproval, RewardChallenge, RewardGovernor, RewardPool, RewardRate,
+    RewardReceipt, SecondaryBalance, SecondaryReward, StateExport, TaskBudget, TreasuryGovernance,
+    TreasuryProposal, TreasurySwapPolicy, Voucher, WithdrawBurn, WithdrawalFee, WithdrawalHistory,
+    WithdrawalRamp, WithdrawalRecord, WithdrawalWhitelist,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::json::to_json;
+
+// Program account decoded from its layout
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ParsedAccountData {
+    /// Account type, e.g. `RewardPool`
+    pub kind: String,
+    /// Account fields by name
+    pub fields: Value,
+}
+
+/// Decodes a program-owned account. Accounts carry no discriminator, so the
+/// first layout that consumes the data exactly wins.
+pub fn parse_account(data: &[u8]) -> Option<ParsedAccountData> {
+    decode::<RewardPool>("RewardPool", data)
+        .or_else(|| decode::<DailyStats>("DailyStats", data))
+        .or_else(|| decode::<InstructionCounters>("InstructionCounters", data))
+        .or_else(|| decode::<WithdrawalWhitelist>("WithdrawalWhitelist", data))
+        .or_else(|| decode::<TreasuryGovernance>("TreasuryGovernance", data))
+        .or_else(|| decode::<TreasuryProposal>("TreasuryProposal", data))
+        .or_else(|| decode::<PoolParameters>("PoolParameters", data))
+        .or_else(|| decode::<ParameterHistory>("ParameterHistory", data))
+        .or_else(|| decode::<PoolGuardians>("PoolGuardians", data))
+        .or_else(|| decode::<PendingAction>("PendingAction", data))
+        .or_else(|| decode::<PoolMetadata>("PoolMetadata", data))
+        .or_else(|| decode::<StateExport>("StateExport", data))
+        .or_else(|| decode::<FarmerState>("FarmerState", data))
+        .or_else(|| decode::<ImportedBalance>("ImportedBalance", data))
+        .or_else(|| decode::<PoolArchive>("PoolArchive", data))
+        .or_else(|| decode::<PayoutAddress>("PayoutAddress", data))
+        .or_else(|| decode::<PoolCharity>("PoolCharity", data))
+        .or_else(|| decode::<PendingTransferPolicy>("PendingTransferPolicy", data))
+        .or_else(|| decode::<PoolVerifier>("PoolVerifier", data))
+        .or_else(|| decode::<PoolAttestor>("PoolAttestor", data))
+        .or_else(|| decode::<TaskBudget>("TaskBudget", data))
+        .or_else(|| decode::<RewardChallenge>("RewardChallenge", data))
+        .or_else(|| decode::<RewardApproval>("RewardApproval", data))
+        .or_else(|| decode::<FeeOverrides>("FeeOverrides", data))
+        .or_else(|| decode::<Platform>("Platform", data))
+        .or_else(|| decode::<Campaign>("Campaign", data))
+        .or_else(|| decode::<BuybackConfig>("BuybackConfig", data))
+        .or_else(|| decode::<TreasurySwapPolicy>("TreasurySwapPolicy", data))
+        .or_else(|| decode::<FeeDiscounts>("FeeDiscounts", data))
+        .or_else(|| decode::<InterestPolicy>("InterestPolicy", data))
+        .or_else(|| decode::<WithdrawBurn>("WithdrawBurn", data))
+        .or_else(|| decode::<RewardGovernor>("RewardGovernor", data))
+        .or_else(|| decode::<SecondaryReward>("SecondaryReward", data))
+        .or_else(|| decode::<SecondaryBalance>("SecondaryBalance", data))
+        .or_else(|| decode::<MilestoneBonuses>("MilestoneBonuses", data))
+        .or_else(|| decode::<FarmerStats>("FarmerStats", data))
+        .or_else(|| decode::<ProtocolLiquidity>("ProtocolLiquidity", data))
+        .or_else(|| decode::<FeeRebate>("FeeRebate", data))
+        .or_else(|| decode::<ProtocolConfig>("ProtocolConfig", data))
+        .or_else(|| decode::<FactoryPool>("FactoryPool", data))
+        .or_else(|| decode::<Voucher>("Voucher", data))
+        .or_else(|| decode::<PayoutQueue>("PayoutQueue", data))
+        .or_else(|| decode::<PayoutTicket>("PayoutTicket", data))
+        .or_else(|| decode::<WithdrawalRamp>("WithdrawalRamp", data))
+        .or_else(|| decode::<AuthorityRecovery>("AuthorityRecovery", data))
+        .or_else(|| decode::<KeyHistory>("KeyHistory", data))
+        .or_else(|| decode::<FarmerRecovery>("FarmerRecovery", data))
+        .or_else(|| decode::<WithdrawalFee>("WithdrawalFee", data))
+        .or_else(|| decode::<PoolSnapshot>("PoolSnapshot", data))
+        .or_else(|| decode::<Guild>("Guild", data))
+        .or_else(|| decode::<FeeLedger>("FeeLedger", data))
+        .or_else(|| decode::<FeeInvoice>("FeeInvoice", data))
+        .or_else(|| decode::<RewardRate>("RewardRate", data))
+        .or_else(|| decode::<ClaimShard>("ClaimShard", data))
+        .or_else(|| decode::<ClaimLedger>("ClaimLedger", data))
+        .or_else(|| decode::<RewardReceipt>("RewardReceipt", data))
+        .or_else(|| decode_legacy_counters(data))
+        .or_else(|| decode::<WithdrawalHistory>("WithdrawalHistory", data))
+        .or_else(|| decode::<WithdrawalRecord>("WithdrawalRecord", data))
+        .or_else(|| decode::<PendingReward>("PendingReward", data))
+}
+
+fn decode<T: BorshDeserialize + BorshSerialize + BorshSchema>(
+    kind: &str,
+    data: &[u8],
+) -> Option<ParsedAccountData> {
+    T::try_from_slice(data)
+        .ok()
+        .map(|account| ParsedAccountData {
+            kind: kind.to_string(),
+            fields: to_json(&account),
+        })
+}
+
+// Counters accounts created before the counters grew, decoded with the
+// missing slots zeroed
+fn decode_legacy_counters(data: &[u8]) -> Option<ParsedAccountData> {
+    if !InstructionCounters::is_legacy_len(data.len()) {
+        return None;
+    }
+    InstructionCounters::unpack(data)
+        .ok()
+        .map(|counters| ParsedAccountData {
+            kind: "InstructionCounters".to_string(),
+            fields: to_json(&counters),
+        })
+}
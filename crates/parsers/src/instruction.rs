@@ -0,0 +1,1426 @@
+use reward_pool::instruction::{VersionedInstruction, INSTRUCTION_TAG_V1, INSTRUCTION_TAG_V2};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_program::instruction::AccountMeta;
+
+use crate::json::{hex, split_variant, to_json};
+
+// Human-readable instruction
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ParsedInstruction {
+    /// Instruction variant, e.g. `RecordReward`, or `Unknown`
+    pub name: String,
+    pub encoding: InstructionEncoding,
+    /// Arguments by name, in their latest form. Undecodable data is kept
+    /// as a hex string.
+    pub args: Value,
+    pub accounts: Vec<ParsedAccount>,
+}
+
+// Namespace an instruction payload was encoded with
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstructionEncoding {
+    /// Untagged payload from clients predating versioned encoding
+    Legacy,
+    V1,
+    V2,
+    Unknown,
+}
+
+// Instruction account with its role
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ParsedAccount {
+    pub name: String,
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// Decodes instruction data and labels its accounts
+pub fn parse_instruction(data: &[u8], accounts: &[AccountMeta]) -> ParsedInstruction {
+    let Ok(instruction) = VersionedInstruction::unpack(data) else {
+        return ParsedInstruction {
+            name: "Unknown".to_string(),
+            encoding: InstructionEncoding::Unknown,
+            args: Value::String(hex(data)),
+            accounts: label_accounts("Unknown", accounts),
+        };
+    };
+
+    let encoding = match data.first() {
+        Some(&INSTRUCTION_TAG_V1) => InstructionEncoding::V1,
+        Some(&INSTRUCTION_TAG_V2) => InstructionEncoding::V2,
+        _ => InstructionEncoding::Legacy,
+    };
+    let (name, args) = split_variant(to_json(&instruction.into_latest()));
+    let accounts = label_accounts(&name, accounts);
+
+    ParsedInstruction {
+        name,
+        encoding,
+        args,
+        accounts,
+    }
+}
+
+/// Account names of an instruction, in order, followed by the label of its
+/// variable-length tail if it has one
+pub fn account_names(instruction: &str) -> (&'static [&'static str], &'static str) {
+    match instruction {
+        "InitializePool" => (
+            &[
+                "platform_authority",
+                "pool",
+                "reward_mint",
+                "platform_treasury",
+                "rent_sysvar",
+                "system_program",
+                "token_program",
+                "associated_token_program",
+                "instruction_counters",
+                "pool_metadata",
+            ],
+            "account",
+        ),
+        "RecordReward" => (
+            &[
+                "platform_authority",
+                "pool",
+                "platform_treasury",
+                "farmer_reward_account",
+                "reward_mint",
+                "token_program",
+                "associated_token_program",
+                "daily_stats",
+                "system_program",
+                "instruction_counters",
+                "farmer_state",
+                "pool_verifier",
+                "pool_attestor",
+                "attestation",
+                "task_budget",
+                "budget_escrow",
+                "reward_approval",
+                "pending_reward",
+                "platform_fee_vault",
+                "fee_overrides",
+                "platform",
+                "campaign",
+                "fee_discounts",
+                "stake_account",
+                "reward_governor",
+                "milestone_bonuses",
+                "fee_rebate",
+                "reward_rate",
+                "treasury_governance",
+                "reward_receipt",
+                "memo_program",
+                "farmer_stats",
+                "milestone_pending_reward",
+                "secondary_reward",
+                "secondary_vault",
+                "secondary_balance",
+                "rebate_vault",
+                "factory_record",
+                "protocol_config",
+                "protocol_fee_account",
+            ],
+            "cosigner",
+        ),
+        "WithdrawReward" => (
+            &[
+                "farmer",
+                "pool",
+                "treasury_vault",
+                "destination",
+                "reward_mint",
+                "token_program",
+                "associated_token_program",
+                "daily_stats",
+                "system_program",
+                "instruction_counters",
+                "withdrawal_whitelist",
+                "parameters",
+                "payout_address",
+                "farmer_state",
+                "withdrawal_history",
+                "withdraw_burn",
+                "withdrawal_fee",
+                "fee_vault",
+                "withdrawal_cosigner",
+            ],
+            "split_destination",
+        ),
+        "UpdatePlatformFee" => (
+            &[
+                "platform_authority",
+                "pool",
+                "instruction_counters",
+                "system_program",
+                "parameters",
+            ],
+            "account",
+        ),
+        "PausePool" => (
+            &[
+                "platform_authority",
+                "pool",
+                "instruction_counters",
+                "system_program",
+                "guardians",
+            ],
+            "account",
+        ),
+        "ResumePool" => (
+            &[
+                "platform_authority",
+                "pool",
+                "instruction_counters",
+                "system_program",
+                "payout_queue",
+            ],
+            "account",
+        ),
+        "GetFarmerPending" => (&["pool", "farmer_state"], "pending_reward"),
+        "GetPoolStats" => (
+            &["pool", "instruction_counters", "withdraw_burn"],
+            "account",
+        ),
+        "GetWithdrawalHistory" => (&["pool", "withdrawal_history"], "account"),
+        "SweepDelegatedRewards" => (
+            &[
+                "custodian",
+                "pool",
+                "destination",
+                "token_program",
+                "daily_stats",
+                "system_program",
+                "instruction_counters",
+            ],
+            "reward_account",
+        ),
+        "AddWithdrawalDestination" => (
+            &[
+                "farmer",
+                "pool",
+                "withdrawal_whitelist",
+                "system_program",
+                "instruction_counters",
+                "parameters",
+            ],
+            "account",
+        ),
+        "RemoveWithdrawalDestination" => (
+            &[
+                "farmer",
+                "pool",
+                "withdrawal_whitelist",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ConfigureTreasuryGovernance" => (
+            &[
+                "platform_authority",
+                "pool",
+                "treasury_governance",
+                "treasury_vault",
+                "reward_mint",
+                "token_program",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ProposeTreasuryWithdrawal" => (
+            &[
+                "proposer",
+                "pool",
+                "treasury_governance",
+                "treasury_proposal",
+                "destination",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ApproveTreasuryWithdrawal" => (
+            &[
+                "approver",
+                "pool",
+                "treasury_governance",
+                "treasury_proposal",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ExecuteTreasuryWithdrawal" => (
+            &[
+                "executor",
+                "pool",
+                "treasury_governance",
+                "treasury_proposal",
+                "treasury_vault",
+                "destination",
+                "token_program",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "SetUpgradeAuthority" => (
+            &[
+                "platform_authority",
+                "pool",
+                "treasury_governance",
+                "program_data",
+                "upgrade_authority",
+                "bpf_loader_upgradeable",
+                "system_program",
+                "instruction_counters",
+                "new_upgrade_authority",
+            ],
+            "approver",
+        ),
+        "RemoveUpgradeAuthority" => (
+            &[
+                "platform_authority",
+                "pool",
+                "treasury_governance",
+                "program_data",
+                "upgrade_authority",
+                "bpf_loader_upgradeable",
+                "system_program",
+                "instruction_counters",
+            ],
+            "approver",
+        ),
+        "UpdateParameters" => (
+            &[
+                "platform_authority",
+                "pool",
+                "treasury_governance",
+                "parameters",
+                "system_program",
+                "instruction_counters",
+                "parameter_history",
+            ],
+            "approver",
+        ),
+        "SetGuardians" => (
+            &[
+                "platform_authority",
+                "pool",
+                "guardians",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ScheduleAction" => (
+            &[
+                "platform_authority",
+                "pool",
+                "pending_action",
+                "system_program",
+                "instruction_counters",
+                "treasury_governance",
+            ],
+            "approver",
+        ),
+        "CancelAction" => (
+            &[
+                "platform_authority",
+                "pool",
+                "pending_action",
+                "payer",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ExecuteAction" => (
+            &[
+                "executor",
+                "pool",
+                "pending_action",
+                "payer",
+                "system_program",
+                "instruction_counters",
+            ],
+            "action_account",
+        ),
+        "RollbackParameter" => (
+            &[
+                "platform_authority",
+                "pool",
+                "parameters",
+                "parameter_history",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "PauseAll" => (&["platform_authority"], "pool"),
+        "SetFeeAll" => (&["platform_authority"], "pool_or_parameters"),
+        "ExportState" => (
+            &[
+                "platform_authority",
+                "pool",
+                "state_export",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ImportState" => (
+            &[
+                "platform_authority",
+                "pool",
+                "state_export",
+                "imported_balance",
+                "platform_treasury",
+                "pending_reward",
+                "reward_mint",
+                "token_program",
+                "associated_token_program",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ArchivePool" => (
+            &[
+                "platform_authority",
+                "pool",
+                "pool_archive",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ClosePool" => (
+            &[
+                "archived_by",
+                "pool",
+                "pool_archive",
+                "instruction_counters",
+                "treasury_vault",
+                "platform_fee_vault",
+                "bonus_vault",
+                "fee_vault",
+                "destination",
+                "token_program",
+            ],
+            "account",
+        ),
+        "ClaimMany" => (
+            &[
+                "farmer",
+                "pool",
+                "treasury_vault",
+                "farmer_reward_account",
+                "token_program",
+                "system_program",
+                "claim_shard",
+                "farmer_state",
+                "interest_policy",
+                "bonus_vault",
+                "secondary_reward",
+                "secondary_vault",
+                "secondary_balance",
+                "secondary_account",
+                "payout_queue",
+                "payout_ticket",
+                "withdrawal_ramp",
+                "guild_vault",
+                "payout_address",
+                "withdrawal_whitelist",
+                "withdrawal_cosigner",
+                "withdraw_burn",
+                "reward_mint",
+                "withdrawal_fee",
+                "fee_vault",
+            ],
+            "pending_reward",
+        ),
+        "ClaimAcrossPools" => (
+            &[
+                "farmer",
+                "token_program",
+                "system_program",
+                "withdrawal_cosigner",
+            ],
+            "claim_group_account",
+        ),
+        "RegisterPayoutAddress" => (
+            &[
+                "farmer",
+                "pool",
+                "payout_address",
+                "system_program",
+                "instruction_counters",
+                "parameters",
+            ],
+            "account",
+        ),
+        "SetCharityAccount" => (
+            &[
+                "platform_authority",
+                "pool",
+                "pool_charity",
+                "charity",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "DonateReward" => (
+            &[
+                "farmer",
+                "pool",
+                "pending_reward",
+                "treasury_vault",
+                "reward_mint",
+                "token_program",
+                "system_program",
+                "instruction_counters",
+                "pool_charity",
+                "charity",
+            ],
+            "account",
+        ),
+        "CloseFarmerAccounts" => (
+            &[
+                "farmer",
+                "pool",
+                "farmer_reward_account",
+                "farmer_state",
+                "rent_recipient",
+                "withdrawal_whitelist",
+                "payout_address",
+                "token_program",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "CloseWithdrawalRecords" => (
+            &[
+                "farmer",
+                "pool",
+                "system_program",
+                "instruction_counters",
+                "withdrawal_history",
+            ],
+            "withdrawal_record",
+        ),
+        "SetAutoForward" => (
+            &[
+                "farmer",
+                "pool",
+                "farmer_state",
+                "destination",
+                "withdrawal_whitelist",
+                "payout_address",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "SetPendingTransferPolicy" => (
+            &[
+                "platform_authority",
+                "pool",
+                "pending_transfer_policy",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "TransferPending" => (
+            &[
+                "farmer",
+                "pool",
+                "pending_reward",
+                "new_pending_reward",
+                "pending_transfer_policy",
+                "withdrawal_whitelist",
+                "payout_address",
+                "system_program",
+                "instruction_counters",
+                "farmer_state",
+                "withdrawal_cosigner",
+            ],
+            "account",
+        ),
+        "SetAutoClaim" => (
+            &[
+                "farmer",
+                "pool",
+                "farmer_state",
+                "payout_address",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "CrankAutoClaim" => (
+            &[
+                "cranker",
+                "pool",
+                "treasury_vault",
+                "farmer",
+                "farmer_state",
+                "withdrawal_whitelist",
+                "payout_address",
+                "destination",
+                "cranker_token_account",
+                "token_program",
+                "system_program",
+                "claim_shard",
+                "interest_policy",
+                "bonus_vault",
+                "payout_queue",
+                "payout_ticket",
+                "withdrawal_ramp",
+                "guild_vault",
+                "withdraw_burn",
+                "reward_mint",
+                "withdrawal_fee",
+                "fee_vault",
+            ],
+            "pending_reward",
+        ),
+        "SetVerifier" => (
+            &[
+                "platform_authority",
+                "pool",
+                "pool_verifier",
+                "system_program",
+                "instruction_counters",
+                "current_verifier",
+            ],
+            "account",
+        ),
+        "SetAttestor" => (
+            &[
+                "platform_authority",
+                "pool",
+                "pool_attestor",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "LockTaskBudget" => (
+            &[
+                "platform_authority",
+                "pool",
+                "treasury_vault",
+                "budget_escrow",
+                "task_budget",
+                "reward_mint",
+                "token_program",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ReleaseUnusedBudget" => (
+            &[
+                "caller",
+                "pool",
+                "treasury_vault",
+                "budget_escrow",
+                "task_budget",
+                "payer",
+                "token_program",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ChallengeReward" => (
+            &[
+                "challenger",
+                "pool",
+                "pending_reward",
+                "reward_challenge",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ResolveChallenge" => (
+            &[
+                "resolver",
+                "pool",
+                "pool_verifier",
+                "pending_reward",
+                "reward_challenge",
+                "challenger",
+                "farmer",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "SetRewardApprover" => (
+            &[
+                "platform_authority",
+                "pool",
+                "reward_approval",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ApproveReward" => (
+            &[
+                "approver",
+                "pool",
+                "reward_approval",
+                "pending_reward",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "SetTaskTypeFee" => (
+            &[
+                "platform_authority",
+                "pool",
+                "parameters",
+                "fee_overrides",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "RegisterPlatform" => (&["operator", "platform", "system_program"], "account"),
+        "SetPlatformOperator" => (&["operator", "platform"], "account"),
+        "InitializePlatformPool" => (
+            &[
+                "operator",
+                "pool",
+                "reward_mint",
+                "platform_treasury",
+                "platform",
+                "system_program",
+                "instruction_counters",
+                "token_program",
+            ],
+            "account",
+        ),
+        "CreateCampaign" => (
+            &[
+                "platform_authority",
+                "pool",
+                "campaign",
+                "system_program",
+                "instruction_counters",
+                "treasury_vault",
+                "budget_escrow",
+                "reward_mint",
+                "token_program",
+            ],
+            "account",
+        ),
+        "EndCampaign" => (
+            &[
+                "platform_authority",
+                "pool",
+                "campaign",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "FinalizeCampaign" => (
+            &[
+                "caller",
+                "pool",
+                "campaign",
+                "treasury_vault",
+                "budget_escrow",
+                "token_program",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ConfigureBuyback" => (
+            &[
+                "platform_authority",
+                "pool",
+                "treasury_governance",
+                "buyback",
+                "system_program",
+                "instruction_counters",
+                "buyback_source",
+                "buyback_output",
+                "reward_mint",
+                "governance_mint",
+                "token_program",
+            ],
+            "approver",
+        ),
+        "RunBuyback" => (
+            &[
+                "caller",
+                "pool",
+                "buyback",
+                "platform_fee_vault",
+                "buyback_source",
+                "buyback_output",
+                "governance_mint",
+                "token_program",
+                "system_program",
+                "instruction_counters",
+                "dex_program",
+            ],
+            "swap_account",
+        ),
+        "SetTreasurySwapPolicy" => (
+            &[
+                "platform_authority",
+                "pool",
+                "treasury_governance",
+                "treasury_swap_policy",
+                "system_program",
+                "instruction_counters",
+            ],
+            "approver",
+        ),
+        "SetFeeDiscounts" => (
+            &[
+                "platform_authority",
+                "pool",
+                "fee_discounts",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "SetInterestPolicy" => (
+            &[
+                "platform_authority",
+                "pool",
+                "interest_policy",
+                "system_program",
+                "instruction_counters",
+                "bonus_vault",
+                "reward_mint",
+                "token_program",
+            ],
+            "account",
+        ),
+        "SetWithdrawBurn" => (
+            &[
+                "platform_authority",
+                "pool",
+                "withdraw_burn",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "SetRewardGovernor" => (
+            &[
+                "platform_authority",
+                "pool",
+                "reward_governor",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "SetSecondaryMint" => (
+            &[
+                "platform_authority",
+                "pool",
+                "secondary_reward",
+                "system_program",
+                "instruction_counters",
+                "secondary_vault",
+                "secondary_mint",
+                "token_program",
+            ],
+            "account",
+        ),
+        "SetMilestoneBonuses" => (
+            &[
+                "platform_authority",
+                "pool",
+                "milestone_bonuses",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "SetProtocolLiquidity" => (
+            &[
+                "platform_authority",
+                "pool",
+                "treasury_governance",
+                "protocol_liquidity",
+                "system_program",
+                "instruction_counters",
+                "pol_vault",
+                "pol_pair_vault",
+                "pol_lp_vault",
+                "reward_mint",
+                "pair_mint",
+                "lp_mint",
+                "token_program",
+            ],
+            "approver",
+        ),
+        "ContributeLiquidity" => (
+            &[
+                "caller",
+                "pool",
+                "protocol_liquidity",
+                "platform_fee_vault",
+                "pol_vault",
+                "pol_pair_vault",
+                "pol_lp_vault",
+                "token_program",
+                "system_program",
+                "instruction_counters",
+                "amm_program",
+            ],
+            "amm_account",
+        ),
+        "SetFeeRebate" => (
+            &[
+                "platform_authority",
+                "pool",
+                "fee_rebate",
+                "system_program",
+                "instruction_counters",
+                "secondary_reward",
+                "rebate_vault",
+                "governance_mint",
+                "token_program",
+            ],
+            "account",
+        ),
+        "SetProtocolConfig" => (
+            &[
+                "signer",
+                "protocol_config",
+                "program_data",
+                "system_program",
+            ],
+            "account",
+        ),
+        "CreatePool" => (
+            &[
+                "creator",
+                "pool",
+                "reward_mint",
+                "platform_treasury",
+                "factory_record",
+                "protocol_config",
+                "protocol_treasury",
+                "system_program",
+                "instruction_counters",
+                "token_program",
+            ],
+            "account",
+        ),
+        "IssueVoucher" => (
+            &[
+                "platform_authority",
+                "pool",
+                "treasury_vault",
+                "voucher_escrow",
+                "voucher",
+                "voucher_mint",
+                "recipient",
+                "recipient_voucher_account",
+                "reward_mint",
+                "token_program",
+                "associated_token_program",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "RedeemVoucher" => (
+            &[
+                "holder",
+                "pool",
+                "voucher",
+                "voucher_mint",
+                "holder_voucher_account",
+                "voucher_escrow",
+                "destination",
+                "token_program",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "SetPayoutQueue" => (
+            &[
+                "platform_authority",
+                "pool",
+                "payout_queue",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ResumeWithRamp" => (
+            &[
+                "platform_authority",
+                "pool",
+                "instruction_counters",
+                "system_program",
+                "withdrawal_ramp",
+                "payout_queue",
+            ],
+            "account",
+        ),
+        "TakePayoutTicket" => (
+            &[
+                "farmer",
+                "pool",
+                "payout_queue",
+                "payout_ticket",
+                "system_program",
+                "instruction_counters",
+            ],
+            "pending_reward",
+        ),
+        "SetRecoveryAuthority" => (
+            &[
+                "platform_authority",
+                "pool",
+                "authority_recovery",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ClaimDormantAuthority" => (
+            &[
+                "recovery_authority",
+                "pool",
+                "authority_recovery",
+                "system_program",
+                "instruction_counters",
+                "key_history",
+            ],
+            "account",
+        ),
+        "SetRecoveryGuardians" => (
+            &[
+                "farmer",
+                "pool",
+                "farmer_recovery",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "InitiateFarmerRecovery" => (
+            &[
+                "guardian",
+                "pool",
+                "farmer_recovery",
+                "system_program",
+                "instruction_counters",
+            ],
+            "guardian",
+        ),
+        "CancelFarmerRecovery" => (
+            &[
+                "farmer",
+                "pool",
+                "farmer_recovery",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "CompleteFarmerRecovery" => (
+            &[
+                "payer",
+                "pool",
+                "farmer_recovery",
+                "farmer_state",
+                "new_farmer_state",
+                "system_program",
+                "instruction_counters",
+            ],
+            "pending_reward",
+        ),
+        "SetWithdrawalFee" => (
+            &[
+                "platform_authority",
+                "pool",
+                "withdrawal_fee",
+                "fee_vault",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "CollectWithdrawalFees" => (
+            &[
+                "platform_authority",
+                "pool",
+                "fee_vault",
+                "destination",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "SetWithdrawalCosigner" => (
+            &[
+                "farmer",
+                "pool",
+                "farmer_state",
+                "cosigner",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "ExportSnapshot" => (
+            &[
+                "exporter",
+                "pool",
+                "snapshot_buffer",
+                "parameters",
+                "instruction_counters",
+            ],
+            "daily_stats",
+        ),
+        "SetGuildSplit" => (
+            &[
+                "farmer",
+                "pool",
+                "farmer_state",
+                "guild_vault",
+                "system_program",
+                "instruction_counters",
+                "payout_address",
+                "withdrawal_whitelist",
+                "withdrawal_cosigner",
+            ],
+            "account",
+        ),
+        "SetGuildMembers" => (
+            &[
+                "authority",
+                "pool",
+                "guild",
+                "guild_vault",
+                "reward_mint",
+                "token_program",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "LeaveGuild" => (
+            &[
+                "member",
+                "pool",
+                "guild",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "DistributeGuildRewards" => (
+            &[
+                "payer",
+                "pool",
+                "guild",
+                "guild_vault",
+                "token_program",
+                "system_program",
+                "instruction_counters",
+            ],
+            "member_token_account",
+        ),
+        "CloseFeeInvoice" => (
+            &[
+                "payer",
+                "pool",
+                "fee_ledger",
+                "fee_invoice",
+                "withdrawal_fee",
+                "withdraw_burn",
+                "buyback",
+                "fee_rebate",
+                "factory_record",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "GetFeeInvoice" => (&["pool", "fee_invoice", "previous_fee_invoice"], "account"),
+        "GetRewardRate" => (&["pool", "reward_rate"], "account"),
+        "ConsolidateClaimShards" => (
+            &[
+                "payer",
+                "pool",
+                "claim_ledger",
+                "system_program",
+                "instruction_counters",
+            ],
+            "claim_shard",
+        ),
+        "FundTreasury" => (
+            &[
+                "funder",
+                "pool",
+                "treasury_vault",
+                "source",
+                "reward_mint",
+                "token_program",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        "SetPoolMetadata" => (
+            &[
+                "platform_authority",
+                "pool",
+                "pool_metadata",
+                "system_program",
+                "instruction_counters",
+            ],
+            "account",
+        ),
+        _ => (&[], "account"),
+    }
+}
+
+fn label_accounts(instruction: &str, accounts: &[AccountMeta]) -> Vec<ParsedAccount> {
+    let (mut names, tail) = account_names(instruction);
+    // A reward's optional feature accounts end at its first cosigner
+    const RECORD_REWARD_ACCOUNTS: usize = 29;
+    if instruction == "RecordReward" {
+        let features_end = accounts
+            .iter()
+            .enumerate()
+            .skip(RECORD_REWARD_ACCOUNTS)
+            .find(|(_, meta)| meta.is_signer)
+            .map_or(accounts.len(), |(index, _)| index);
+        names = &names[..features_end.min(names.len())];
+    }
+    let mut tail_names =
+        accounts
+            .len()
+            .checked_sub(names.len())
+            .map_or_else(Vec::new, |tail_len| {
+                (0..tail_len)
+                    .map(|index| format!("{tail}_{index}"))
+                    .collect()
+            });
+    // A withdrawal's tail starts with its sponsor, the one signing, and
+    // ends with the payout queue accounts, the withdrawal ramp, the interest
+    // accounts and the pending reward it pays out
+    const WITHDRAW_TRAILING_NAMES: [&str; 7] = [
+        "payout_queue",
+        "payout_ticket",
+        "withdrawal_ramp",
+        "interest_policy",
+        "bonus_vault",
+        "pending_reward",
+        "withdrawal_record",
+    ];
+    if instruction == "WithdrawReward" && tail_names.len() >= WITHDRAW_TRAILING_NAMES.len() {
+        let sponsored = accounts[names.len()].is_signer;
+        let destination_count =
+            tail_names.len() - WITHDRAW_TRAILING_NAMES.len() - usize::from(sponsored);
+        tail_names = sponsored
+            .then(|| "sponsor".to_string())
+            .into_iter()
+            .chain((0..destination_count).map(|index| format!("{tail}_{index}")))
+            .chain(WITHDRAW_TRAILING_NAMES.map(str::to_string))
+            .collect();
+    }
+    // Each withdrawal record closed is followed by the payer of its rent
+    if instruction == "CloseWithdrawalRecords" {
+        tail_names = (0..tail_names.len())
+            .map(|index| match index % 2 {
+                0 => format!("{tail}_{}", index / 2),
+                _ => format!("rent_recipient_{}", index / 2),
+            })
+            .collect();
+    }
+
+    accounts
+        .iter()
+        .enumerate()
+        .map(|(index, meta)| ParsedAccount {
+            name: names
+                .get(index)
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| tail_names[index - names.len()].clone()),
+            pubkey: meta.pubkey.to_string(),
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use reward_pool::{
+        instruction::{v1, v2::RewardPoolInstruction},
+        WithdrawalSplit,
+    };
+    use serde_json::json;
+    use solana_program::pubkey::Pubkey;
+
+    use super::*;
+
+    fn metas(count: usize) -> Vec<AccountMeta> {
+        (0..count)
+            .map(|_| AccountMeta::new(Pubkey::new_unique(), false))
+            .collect()
+    }
+
+    fn names(parsed: &ParsedInstruction) -> Vec<&str> {
+        parsed
+            .accounts
+            .iter()
+            .map(|account| account.name.as_str())
+            .collect()
+    }
+
+    fn withdraw(split: Option<Vec<WithdrawalSplit>>) -> Vec<u8> {
+        RewardPoolInstruction::WithdrawReward {
+            amount: 1_500,
+            nonce: 7,
+            split,
+        }
+        .pack()
+    }
+
+    #[test]
+    fn an_unknown_instruction_tag_is_kept_as_hex() {
+        let accounts = metas(2);
+        let parsed = parse_instruction(&[INSTRUCTION_TAG_V2, 0xff, 0x01], &accounts);
+
+        assert_eq!(parsed.name, "Unknown");
+        assert_eq!(parsed.encoding, InstructionEncoding::Unknown);
+        assert_eq!(parsed.args, json!("f2ff01"));
+        assert_eq!(names(&parsed), ["account_0", "account_1"]);
+        assert_eq!(parsed.accounts[0].pubkey, accounts[0].pubkey.to_string());
+    }
+
+    #[test]
+    fn empty_and_trailing_data_are_unknown() {
+        assert_eq!(parse_instruction(&[], &[]).name, "Unknown");
+
+        let mut data = withdraw(None);
+        data.push(0);
+        assert_eq!(parse_instruction(&data, &[]).name, "Unknown");
+    }
+
+    #[test]
+    fn the_encoding_follows_the_namespace_tag() {
+        let tagged = VersionedInstruction::V1(v1::RewardPoolInstruction::PausePool).pack();
+        let legacy = &tagged[1..];
+        let v2 = RewardPoolInstruction::PausePool.pack();
+
+        for (data, encoding) in [
+            (&tagged[..], InstructionEncoding::V1),
+            (legacy, InstructionEncoding::Legacy),
+            (&v2[..], InstructionEncoding::V2),
+        ] {
+            let parsed = parse_instruction(data, &metas(2));
+            assert_eq!(parsed.name, "PausePool");
+            assert_eq!(parsed.encoding, encoding);
+            assert_eq!(parsed.args, json!({}));
+            assert_eq!(names(&parsed), ["platform_authority", "pool"]);
+        }
+    }
+
+    #[test]
+    fn arguments_are_rendered_by_name() {
+        let parsed = parse_instruction(&withdraw(None), &metas(26));
+
+        assert_eq!(parsed.name, "WithdrawReward");
+        assert_eq!(
+            parsed.args,
+            json!({ "amount": 1_500, "nonce": 7, "split": null })
+        );
+    }
+
+    #[test]
+    fn an_unsponsored_withdrawal_ends_with_its_trailing_accounts() {
+        let parsed = parse_instruction(&withdraw(None), &metas(26));
+
+        let names = names(&parsed);
+        assert_eq!(names[18], "withdrawal_cosigner");
+        assert_eq!(
+            names[19..],
+            [
+                "payout_queue",
+                "payout_ticket",
+                "withdrawal_ramp",
+                "interest_policy",
+                "bonus_vault",
+                "pending_reward",
+                "withdrawal_record",
+            ]
+        );
+    }
+
+    #[test]
+    fn a_sponsored_split_withdrawal_names_the_sponsor_and_destinations() {
+        let split = vec![
+            WithdrawalSplit {
+                destination: Pubkey::new_unique(),
+                share_bps: 5_000,
+            };
+            3
+        ];
+        let mut accounts = metas(29);
+        accounts[19].is_signer = true;
+        let parsed = parse_instruction(&withdraw(Some(split)), &accounts);
+
+        let names = names(&parsed);
+        assert_eq!(
+            names[19..22],
+            ["sponsor", "split_destination_0", "split_destination_1"]
+        );
+        assert_eq!(names[22], "payout_queue");
+        assert_eq!(names[28], "withdrawal_record");
+    }
+
+    #[test]
+    fn accounts_beyond_the_known_ones_are_numbered() {
+        let parsed = parse_instruction(&RewardPoolInstruction::PausePool.pack(), &metas(7));
+
+        assert_eq!(
+            names(&parsed),
+            [
+                "platform_authority",
+                "pool",
+                "instruction_counters",
+                "system_program",
+                "guardians",
+                "account_0",
+                "account_1",
+            ]
+        );
+    }
+}
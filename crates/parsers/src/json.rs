@@ -0,0 +1,178 @@
+use borsh::{
+    schema::{BorshSchemaContainer, Declaration, Definition, Fields},
+    BorshSchema, BorshSerialize,
+};
+use serde_json::{json, Map, Value};
+use solana_program::pubkey::Pubkey;
+
+// Schema-driven JSON rendering
+//
+// Wire types are rendered by walking their Borsh schema rather than with
+// hand-written mappings, so new instructions, events and account fields show
+// up in parsed output without touching this crate. Pubkeys render as base58
+// and byte arrays as hex; enums follow serde's externally tagged layout.
+
+/// Renders any Borsh wire type as JSON
+pub fn to_json<T: BorshSerialize + BorshSchema>(value: &T) -> Value {
+    let schema = BorshSchemaContainer::for_type::<T>();
+    let bytes = borsh::to_vec(value).expect("serialization into a Vec is infallible");
+    let mut reader = Reader(&bytes);
+
+    reader
+        .value(&schema, schema.declaration())
+        .unwrap_or_else(|| json!(hex(&bytes)))
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Option<&[u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn uint(&mut self, width: usize) -> Option<u128> {
+        let bytes = self.take(width)?;
+        let mut buf = [0u8; 16];
+        buf[..width].copy_from_slice(bytes);
+        Some(u128::from_le_bytes(buf))
+    }
+
+    fn value(&mut self, schema: &BorshSchemaContainer, declaration: &Declaration) -> Option<Value> {
+        if declaration == "Pubkey" {
+            let bytes: [u8; 32] = self.take(32)?.try_into().ok()?;
+            return Some(json!(Pubkey::new_from_array(bytes).to_string()));
+        }
+        if declaration == "String" {
+            let len = self.uint(4)? as usize;
+            return Some(json!(String::from_utf8_lossy(self.take(len)?)));
+        }
+
+        match schema.get_definition(declaration)? {
+            Definition::Primitive(size) => self.primitive(declaration, *size as usize),
+            Definition::Sequence {
+                length_width,
+                length_range,
+                elements,
+            } => {
+                let len = match *length_width {
+                    0 => *length_range.end() as usize,
+                    width => self.uint(width as usize)? as usize,
+                };
+                if elements == "u8" {
+                    return Some(json!(hex(self.take(len)?)));
+                }
+                let items = (0..len)
+                    .map(|_| self.value(schema, elements))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Value::Array(items))
+            }
+            Definition::Tuple { elements } => {
+                let items = elements
+                    .iter()
+                    .map(|element| self.value(schema, element))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Value::Array(items))
+            }
+            Definition::Enum {
+                tag_width,
+                variants,
+            } => {
+                let tag = self.uint(*tag_width as usize)? as i64;
+                let (_, name, variant) = variants.iter().find(|(value, _, _)| *value == tag)?;
+
+                if declaration.starts_with("Option<") {
+                    return match name.as_str() {
+                        "None" => Some(Value::Null),
+                        _ => self.fields_of(schema, variant)?.into_iter().next(),
+                    };
+                }
+
+                let fields = self.value(schema, variant)?;
+                match fields {
+                    Value::Object(ref map) if map.is_empty() => Some(json!(name)),
+                    Value::Array(ref items) if items.is_empty() => Some(json!(name)),
+                    fields => Some(json!({ name.as_str(): fields })),
+                }
+            }
+            Definition::Struct { fields } => match fields {
+                Fields::NamedFields(fields) => {
+                    let mut map = Map::new();
+                    for (name, field) in fields {
+                        map.insert(name.clone(), self.value(schema, field)?);
+                    }
+                    Some(Value::Object(map))
+                }
+                Fields::UnnamedFields(fields) if fields.len() == 1 => {
+                    self.value(schema, &fields[0])
+                }
+                Fields::UnnamedFields(fields) => {
+                    let items = fields
+                        .iter()
+                        .map(|field| self.value(schema, field))
+                        .collect::<Option<Vec<_>>>()?;
+                    Some(Value::Array(items))
+                }
+                Fields::Empty => Some(Value::Object(Map::new())),
+            },
+        }
+    }
+
+    // Values of a tuple-like variant body, used for `Option<T>`
+    fn fields_of(
+        &mut self,
+        schema: &BorshSchemaContainer,
+        declaration: &Declaration,
+    ) -> Option<Vec<Value>> {
+        match schema.get_definition(declaration)? {
+            Definition::Struct {
+                fields: Fields::UnnamedFields(fields),
+            } => fields
+                .iter()
+                .map(|field| self.value(schema, field))
+                .collect(),
+            _ => self.value(schema, declaration).map(|value| vec![value]),
+        }
+    }
+
+    fn primitive(&mut self, declaration: &Declaration, size: usize) -> Option<Value> {
+        let raw = self.uint(size)?;
+        let value = match declaration.as_str() {
+            "bool" => json!(raw != 0),
+            "u8" | "u16" | "u32" | "u64" => json!(raw as u64),
+            "i8" => json!(raw as u8 as i8),
+            "i16" => json!(raw as u16 as i16),
+            "i32" => json!(raw as u32 as i32),
+            "i64" => json!(raw as u64 as i64),
+            "u128" => json!(raw.to_string()),
+            "i128" => json!((raw as i128).to_string()),
+            "f32" => json!(f32::from_bits(raw as u32)),
+            "f64" => json!(f64::from_bits(raw as u64)),
+            "()" => Value::Null,
+            _ => json!(raw.to_string()),
+        };
+        Some(value)
+    }
+}
+
+/// Splits an externally tagged enum rendering into its variant name and
+/// fields, unit variants having no fields
+pub fn split_variant(value: Value) -> (String, Value) {
+    match value {
+        Value::String(name) => (name, Value::Object(Map::new())),
+        Value::Object(map) if map.len() == 1 => {
+            let (name, fields) = map.into_iter().next().expect("map has one entry");
+            (name, fields)
+        }
+        other => (String::new(), other),
+    }
+}
+
+/// Lowercase hex rendering of raw bytes
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
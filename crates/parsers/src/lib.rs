@@ -0,0 +1,14 @@
+//! Human-readable decoding of reward pool instructions and events.
+//!
+//! [`parse_instruction`], [`parse_logs`] and [`parse_account`] return plain
+//! serde types meant for explorers and wallets; [`json::to_json`] renders
+//! any program wire type or account from its Borsh schema.
+
+pub mod accounts;
+pub mod instruction;
+pub mod json;
+pub mod logs;
+
+pub use accounts::{parse_account, ParsedAccountData};
+pub use instruction::{parse_instruction, InstructionEncoding, ParsedAccount, ParsedInstruction};
+pub use logs::{decode_events, parse_logs, parse_logs_for_program, Event};
@@ -0,0 +1,140 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use borsh::BorshDeserialize;
+use reward_pool::events::RewardPoolEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_program::pubkey::Pubkey;
+
+use crate::json::{split_variant, to_json};
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+// Human-readable event
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Event {
+    /// Event variant, e.g. `RewardRecorded`
+    pub name: String,
+    /// Event fields by name
+    pub data: Value,
+}
+
+impl From<&RewardPoolEvent> for Event {
+    fn from(event: &RewardPoolEvent) -> Self {
+        let (name, data) = split_variant(to_json(event));
+        Self { name, data }
+    }
+}
+
+/// Parses the events emitted by the canonical deployment
+pub fn parse_logs(logs: &[String]) -> Vec<Event> {
+    parse_logs_for_program(&reward_pool::id(), logs)
+}
+
+/// Parses the events emitted by a specific deployment
+pub fn parse_logs_for_program(program_id: &Pubkey, logs: &[String]) -> Vec<Event> {
+    decode_events(program_id, logs)
+        .iter()
+        .map(Event::from)
+        .collect()
+}
+
+/// Decodes the events emitted by `program_id` from a transaction's logs.
+///
+/// Invocations are tracked so that `Program data:` lines written by other
+/// programs, including ones invoked through CPI, are ignored.
+pub fn decode_events(program_id: &Pubkey, logs: &[String]) -> Vec<RewardPoolEvent> {
+    let program_id = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        if let Some(data) = line.strip_prefix(PROGRAM_DATA_PREFIX) {
+            if stack.last() == Some(&program_id.as_str()) {
+                events.extend(
+                    data.split(' ')
+                        .filter_map(|field| BASE64.decode(field).ok())
+                        .filter_map(|bytes| RewardPoolEvent::try_from_slice(&bytes).ok()),
+                );
+            }
+            continue;
+        }
+
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        let mut words = rest.split(' ');
+        let (Some(program), Some(action)) = (words.next(), words.next()) else {
+            continue;
+        };
+        match action {
+            "invoke" => stack.push(program),
+            "success" | "failed:" => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn program_data(event: &RewardPoolEvent) -> String {
+        format!(
+            "{PROGRAM_DATA_PREFIX}{}",
+            BASE64.encode(borsh::to_vec(event).unwrap())
+        )
+    }
+
+    #[test]
+    fn only_events_logged_by_the_program_itself_are_decoded() {
+        let program_id = reward_pool::id().to_string();
+        let other_program = Pubkey::new_unique().to_string();
+        let pool = Pubkey::new_unique();
+        let paused = RewardPoolEvent::PoolPaused { pool };
+        let resumed = RewardPoolEvent::PoolResumed { pool };
+        let logs = [
+            format!("Program {other_program} invoke [1]"),
+            program_data(&paused),
+            format!("Program {other_program} success"),
+            format!("Program {program_id} invoke [1]"),
+            format!("Program {other_program} invoke [2]"),
+            program_data(&paused),
+            format!("Program {other_program} success"),
+            program_data(&resumed),
+            format!("Program {program_id} success"),
+        ];
+
+        assert_eq!(
+            parse_logs(&logs),
+            vec![Event {
+                name: "PoolResumed".to_string(),
+                data: json!({ "pool": pool.to_string() }),
+            }]
+        );
+    }
+
+    #[test]
+    fn undecodable_program_data_is_skipped() {
+        let program_id = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let logs = [
+            format!("Program {program_id} invoke [1]"),
+            format!("{PROGRAM_DATA_PREFIX}not-base64 {}", BASE64.encode([0xff])),
+            program_data(&RewardPoolEvent::PoolPaused { pool }),
+            format!("Program {program_id} failed: custom program error: 0x1"),
+            program_data(&RewardPoolEvent::PoolPaused { pool }),
+        ];
+
+        assert_eq!(
+            decode_events(&program_id, &logs),
+            vec![RewardPoolEvent::PoolPaused { pool }]
+        );
+        assert_eq!(parse_logs(&logs), vec![]);
+    }
+}
@@ -0,0 +1,193 @@
+//! Dumps the Borsh schema of every reward pool wire type as JSON so that
+//! non-Rust clients can generate their (de)serializers instead of
+//! hand-writing byte offsets.
+//!
+//! Usage: `cargo run -p schema-gen -- [OUT_DIR]` (defaults to `schemas/`).
+
+use std::{env, fs, path::Path};
+
+use borsh::{
+    schema::{BorshSchemaContainer, Definition, Fields},
+    BorshSchema,
+};
+use reward_pool::{
+    events::RewardPoolEvent,
+    instruction::{v1, v2},
+    views::{FarmerPendingSummary, FeeInvoiceReport, PoolStats, RewardRateReport},
+    AuthorityRecovery, BuybackConfig, Campaign, ClaimLedger, ClaimShard, DailyStats, FactoryPool,
+    FarmerRecovery, FarmerState, FarmerStats, FeeDiscounts, FeeInvoice, FeeLedger, FeeOverrides,
+    FeeRebate, Guild, ImportedBalance, InstructionCounters, InterestPolicy, KeyHistory,
+    KeyRotation, MilestoneBonuses, ParameterHistory, PayoutAddress, PayoutQueue, PayoutTicket,
+    PendingAction, PendingReward, PendingTransferPolicy, Platform, PoolArchive, PoolAttestor,
+    PoolCharity, PoolGuardians, PoolMetadata, PoolParameters, PoolSnapshot, PoolVerifier,
+    ProtocolConfig, ProtocolLiquidity, RewardApproval, RewardChallenge, RewardGovernor, RewardPool,
+    RewardRate, RewardReceipt, SecondaryBalance, SecondaryReward, StateExport, TaskAttestation,
+    TaskBudget, TreasuryGovernance, TreasuryProposal, TreasurySwapPolicy, Voucher, WithdrawBurn,
+    WithdrawalFee, WithdrawalHistory, WithdrawalRamp, WithdrawalRecord,
+    WithdrawalWhitelist,
+};
+use serde_json::{json, Map, Value};
+
+fn main() -> std::io::Result<()> {
+    let out_dir = env::args().nth(1).unwrap_or_else(|| "schemas".to_string());
+    let out_dir = Path::new(&out_dir);
+    fs::create_dir_all(out_dir)?;
+
+    let schemas = [
+        ("instruction_v1", container::<v1::RewardPoolInstruction>()),
+        ("instruction_v2", container::<v2::RewardPoolInstruction>()),
+        ("reward_pool", container::<RewardPool>()),
+        ("daily_stats", container::<DailyStats>()),
+        ("farmer_state", container::<FarmerState>()),
+        ("instruction_counters", container::<InstructionCounters>()),
+        ("pending_reward", container::<PendingReward>()),
+        ("withdrawal_record", container::<WithdrawalRecord>()),
+        ("withdrawal_history", container::<WithdrawalHistory>()),
+        ("withdrawal_whitelist", container::<WithdrawalWhitelist>()),
+        ("treasury_governance", container::<TreasuryGovernance>()),
+        ("treasury_proposal", container::<TreasuryProposal>()),
+        ("pool_parameters", container::<PoolParameters>()),
+        ("parameter_history", container::<ParameterHistory>()),
+        ("pool_guardians", container::<PoolGuardians>()),
+        ("pending_action", container::<PendingAction>()),
+        ("pool_metadata", container::<PoolMetadata>()),
+        ("state_export", container::<StateExport>()),
+        ("imported_balance", container::<ImportedBalance>()),
+        ("pool_archive", container::<PoolArchive>()),
+        ("payout_address", container::<PayoutAddress>()),
+        ("pool_charity", container::<PoolCharity>()),
+        ("pool_verifier", container::<PoolVerifier>()),
+        ("pool_attestor", container::<PoolAttestor>()),
+        ("task_attestation", container::<TaskAttestation>()),
+        ("task_budget", container::<TaskBudget>()),
+        ("reward_challenge", container::<RewardChallenge>()),
+        ("reward_approval", container::<RewardApproval>()),
+        ("fee_overrides", container::<FeeOverrides>()),
+        ("platform", container::<Platform>()),
+        ("campaign", container::<Campaign>()),
+        ("buyback_config", container::<BuybackConfig>()),
+        ("treasury_swap_policy", container::<TreasurySwapPolicy>()),
+        ("fee_discounts", container::<FeeDiscounts>()),
+        ("interest_policy", container::<InterestPolicy>()),
+        ("withdraw_burn", container::<WithdrawBurn>()),
+        ("reward_governor", container::<RewardGovernor>()),
+        ("secondary_reward", container::<SecondaryReward>()),
+        ("secondary_balance", container::<SecondaryBalance>()),
+        ("milestone_bonuses", container::<MilestoneBonuses>()),
+        ("farmer_stats", container::<FarmerStats>()),
+        ("protocol_liquidity", container::<ProtocolLiquidity>()),
+        ("fee_rebate", container::<FeeRebate>()),
+        ("protocol_config", container::<ProtocolConfig>()),
+        ("factory_pool", container::<FactoryPool>()),
+        ("voucher", container::<Voucher>()),
+        ("payout_queue", container::<PayoutQueue>()),
+        ("payout_ticket", container::<PayoutTicket>()),
+        ("withdrawal_ramp", container::<WithdrawalRamp>()),
+        ("authority_recovery", container::<AuthorityRecovery>()),
+        ("key_history", container::<KeyHistory>()),
+        ("farmer_recovery", container::<FarmerRecovery>()),
+        ("withdrawal_fee", container::<WithdrawalFee>()),
+        ("pool_snapshot", container::<PoolSnapshot>()),
+        ("guild", container::<Guild>()),
+        ("fee_ledger", container::<FeeLedger>()),
+        ("fee_invoice", container::<FeeInvoice>()),
+        ("reward_rate", container::<RewardRate>()),
+        ("claim_shard", container::<ClaimShard>()),
+        ("claim_ledger", container::<ClaimLedger>()),
+        ("reward_receipt", container::<RewardReceipt>()),
+        ("key_rotation", container::<KeyRotation>()),
+        (
+            "pending_transfer_policy",
+            container::<PendingTransferPolicy>(),
+        ),
+        ("event", container::<RewardPoolEvent>()),
+        (
+            "farmer_pending_summary",
+            container::<FarmerPendingSummary>(),
+        ),
+        ("pool_stats", container::<PoolStats>()),
+        ("fee_invoice_report", container::<FeeInvoiceReport>()),
+        ("reward_rate_report", container::<RewardRateReport>()),
+    ];
+
+    for (name, schema) in schemas {
+        let path = out_dir.join(format!("{name}.json"));
+        let json = serde_json::to_string_pretty(&schema_to_json(&schema))?;
+        fs::write(&path, json + "\n")?;
+        println!("wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn container<T: BorshSchema>() -> BorshSchemaContainer {
+    BorshSchemaContainer::for_type::<T>()
+}
+
+fn schema_to_json(schema: &BorshSchemaContainer) -> Value {
+    let definitions: Map<String, Value> = schema
+        .definitions()
+        .map(|(declaration, definition)| (declaration.clone(), definition_to_json(definition)))
+        .collect();
+
+    json!({
+        "declaration": schema.declaration(),
+        "definitions": definitions,
+    })
+}
+
+fn definition_to_json(definition: &Definition) -> Value {
+    match definition {
+        Definition::Primitive(size) => json!({
+            "kind": "primitive",
+            "size": size,
+        }),
+        Definition::Sequence {
+            length_width,
+            length_range,
+            elements,
+        } => json!({
+            "kind": "sequence",
+            "length_width": length_width,
+            "length_range": [length_range.start(), length_range.end()],
+            "elements": elements,
+        }),
+        Definition::Tuple { elements } => json!({
+            "kind": "tuple",
+            "elements": elements,
+        }),
+        Definition::Enum {
+            tag_width,
+            variants,
+        } => json!({
+            "kind": "enum",
+            "tag_width": tag_width,
+            "variants": variants
+                .iter()
+                .map(|(discriminant, name, declaration)| json!({
+                    "discriminant": discriminant,
+                    "name": name,
+                    "type": declaration,
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        Definition::Struct { fields } => json!({
+            "kind": "struct",
+            "fields": fields_to_json(fields),
+        }),
+    }
+}
+
+fn fields_to_json(fields: &Fields) -> Value {
+    match fields {
+        Fields::NamedFields(fields) => fields
+            .iter()
+            .map(|(name, declaration)| json!({ "name": name, "type": declaration }))
+            .collect(),
+        Fields::UnnamedFields(fields) => fields
+            .iter()
+            .map(|declaration| json!({ "type": declaration }))
+            .collect(),
+        Fields::Empty => json!([]),
+    }
+}
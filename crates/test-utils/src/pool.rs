@@ -0,0 +1,229 @@
+//! Pool bootstrapping for program tests.
+
+use reward_pool::PLATFORM_FEE_PERCENTAGE;
+use reward_pool_client::{instructions, PoolAccounts};
+use solana_keypair::Keypair;
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_signer::Signer;
+
+use crate::{airdrop, fixture_keypair, process_instructions, token};
+
+// Roles the fixture's keypairs are derived for
+const PLATFORM_AUTHORITY: u8 = 1;
+const MINT_AUTHORITY: u8 = 2;
+const REWARD_MINT: u8 = 4;
+const FARMER: u8 = 5;
+
+/// Lamports the platform authority starts with, for the rent of the
+/// accounts it creates
+const PLATFORM_AUTHORITY_LAMPORTS: u64 = 10 * LAMPORTS_PER_SOL;
+
+/// A `ProgramTest` running the reward pool program natively, next to the
+/// token programs `solana-program-test` always loads and the mock attestor,
+/// price feed and DEX programs at their declared ids
+pub fn program_test() -> ProgramTest {
+    let mut program_test = reward_pool::test_support::program_test();
+    program_test.add_program(
+        "mock_attestor",
+        mock_attestor::id(),
+        processor!(mock_attestor::process_instruction),
+    );
+    program_test.add_program(
+        "mock_price_feed",
+        mock_price_feed::id(),
+        processor!(mock_price_feed::process_instruction),
+    );
+    program_test.add_program(
+        "mock_dex",
+        mock_dex::id(),
+        processor!(mock_dex::process_instruction),
+    );
+    program_test
+}
+
+/// Builder of a pool with its reward mint
+#[derive(Debug, Clone)]
+pub struct PoolFixture {
+    seed: u8,
+    platform_fee_percentage: u8,
+    decimals: u8,
+    treasury_funds: u64,
+    vault_funds: u64,
+}
+
+impl Default for PoolFixture {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            platform_fee_percentage: PLATFORM_FEE_PERCENTAGE,
+            decimals: 6,
+            treasury_funds: 0,
+            vault_funds: 0,
+        }
+    }
+}
+
+impl PoolFixture {
+    /// A pool at the default platform fee, with a 6-decimal reward mint and
+    /// nothing funded
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Derives every keypair from `seed`; fixtures with different seeds
+    /// can live in the same bank
+    pub fn with_seed(mut self, seed: u8) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Platform fee of the pool. The program charges whole percents.
+    pub fn with_fee_percentage(mut self, platform_fee_percentage: u8) -> Self {
+        self.platform_fee_percentage = platform_fee_percentage;
+        self
+    }
+
+    /// Decimals of the reward mint
+    pub fn with_decimals(mut self, decimals: u8) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Mints `amount` to the platform treasury, the pool's treasury vault,
+    /// which pays rewards
+    pub fn with_funded_treasury(mut self, amount: u64) -> Self {
+        self.treasury_funds = amount;
+        self
+    }
+
+    /// Puts the treasury vault under governance, with the platform
+    /// authority as its only approver, and mints `amount` more to it
+    pub fn with_funded_vault(mut self, amount: u64) -> Self {
+        self.vault_funds = amount;
+        self
+    }
+
+    /// Starts [`program_test`] and bootstraps the pool in it
+    pub async fn start(self) -> Result<(ProgramTestContext, TestPool), BanksClientError> {
+        let mut context = program_test().start_with_context().await;
+        let pool = self.bootstrap(&mut context).await?;
+        Ok((context, pool))
+    }
+
+    /// Bootstraps the pool in a bank the caller started, e.g. from a
+    /// [`program_test`] with programs of their own added
+    pub async fn bootstrap(
+        &self,
+        context: &mut ProgramTestContext,
+    ) -> Result<TestPool, BanksClientError> {
+        let platform_authority = fixture_keypair(self.seed, PLATFORM_AUTHORITY, 0);
+        let mint_authority = fixture_keypair(self.seed, MINT_AUTHORITY, 0);
+        let reward_mint = fixture_keypair(self.seed, REWARD_MINT, 0);
+
+        airdrop(
+            context,
+            &platform_authority.pubkey(),
+            PLATFORM_AUTHORITY_LAMPORTS,
+        )
+        .await?;
+
+        token::create_mint(
+            context,
+            &reward_mint,
+            &mint_authority.pubkey(),
+            self.decimals,
+        )
+        .await?;
+        let accounts = PoolAccounts::for_mint(reward_mint.pubkey());
+        let initialize_pool = instructions::initialize_pool(
+            &accounts,
+            &platform_authority.pubkey(),
+            self.platform_fee_percentage,
+        );
+        process_instructions(context, &[initialize_pool], &[&platform_authority]).await?;
+
+        let test_pool = TestPool {
+            accounts,
+            platform_authority,
+            mint_authority,
+            seed: self.seed,
+        };
+        if self.treasury_funds > 0 {
+            test_pool
+                .mint_to(
+                    context,
+                    &test_pool.accounts.platform_treasury,
+                    self.treasury_funds,
+                )
+                .await?;
+        }
+        if self.vault_funds > 0 {
+            let configure_governance = instructions::configure_treasury_governance(
+                &test_pool.accounts,
+                &test_pool.platform_authority.pubkey(),
+                vec![test_pool.platform_authority.pubkey()],
+                1,
+                0,
+                0,
+            );
+            process_instructions(
+                context,
+                &[configure_governance],
+                &[&test_pool.platform_authority],
+            )
+            .await?;
+            test_pool
+                .mint_to(
+                    context,
+                    &test_pool.accounts.treasury_vault(),
+                    self.vault_funds,
+                )
+                .await?;
+        }
+        Ok(test_pool)
+    }
+}
+
+/// A bootstrapped pool and the keypairs controlling it
+#[derive(Debug)]
+pub struct TestPool {
+    pub accounts: PoolAccounts,
+    pub platform_authority: Keypair,
+    pub mint_authority: Keypair,
+    seed: u8,
+}
+
+impl TestPool {
+    /// Farmer `index` of the fixture, the same on every run
+    pub fn farmer(&self, index: u8) -> Keypair {
+        fixture_keypair(self.seed, FARMER, index)
+    }
+
+    /// Mints `amount` reward tokens to `destination`
+    pub async fn mint_to(
+        &self,
+        context: &mut ProgramTestContext,
+        destination: &Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        token::mint_to(
+            context,
+            &self.accounts.reward_mint,
+            &self.mint_authority,
+            destination,
+            amount,
+        )
+        .await
+    }
+
+    /// Creates the reward token account of `owner` unless it exists, and
+    /// returns its address
+    pub async fn create_reward_account(
+        &self,
+        context: &mut ProgramTestContext,
+        owner: &Pubkey,
+    ) -> Result<Pubkey, BanksClientError> {
+        token::create_associated_token_account(context, owner, &self.accounts.reward_mint).await
+    }
+}
@@ -0,0 +1,127 @@
+//! Mint and token account setup for program tests.
+
+use solana_keypair::Keypair;
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_signer::Signer;
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account_idempotent,
+};
+use spl_token::state::{Account as TokenAccount, Mint};
+
+use crate::process_instructions;
+
+/// Creates `mint` with `decimals`, minted by `mint_authority`
+pub async fn create_mint(
+    context: &mut ProgramTestContext,
+    mint: &Keypair,
+    mint_authority: &Pubkey,
+    decimals: u8,
+) -> Result<(), BanksClientError> {
+    let rent = context.banks_client.get_rent().await?;
+    let instructions = [
+        solana_system_interface::instruction::create_account(
+            &context.payer.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(Mint::LEN),
+            Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint2(
+            &spl_token::id(),
+            &mint.pubkey(),
+            mint_authority,
+            None,
+            decimals,
+        )
+        .expect("the token program id is valid"),
+    ];
+    process_instructions(context, &instructions, &[mint]).await
+}
+
+/// Creates the associated token account of `owner` for `mint` unless it
+/// exists, and returns its address
+pub async fn create_associated_token_account(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Pubkey, BanksClientError> {
+    let instruction = create_associated_token_account_idempotent(
+        &context.payer.pubkey(),
+        owner,
+        mint,
+        &spl_token::id(),
+    );
+    process_instructions(context, &[instruction], &[]).await?;
+    Ok(get_associated_token_address(owner, mint))
+}
+
+/// Creates `account`, a token account of `mint` owned by `owner` at an
+/// address other than their associated one
+pub async fn create_token_account(
+    context: &mut ProgramTestContext,
+    account: &Keypair,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> Result<(), BanksClientError> {
+    let rent = context.banks_client.get_rent().await?;
+    let instructions = [
+        solana_system_interface::instruction::create_account(
+            &context.payer.pubkey(),
+            &account.pubkey(),
+            rent.minimum_balance(TokenAccount::LEN),
+            TokenAccount::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_account3(
+            &spl_token::id(),
+            &account.pubkey(),
+            mint,
+            owner,
+        )
+        .expect("the token program id is valid"),
+    ];
+    process_instructions(context, &instructions, &[account]).await
+}
+
+/// Mints `amount` of `mint` to `destination`
+pub async fn mint_to(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    mint_authority: &Keypair,
+    destination: &Pubkey,
+    amount: u64,
+) -> Result<(), BanksClientError> {
+    let instruction = spl_token::instruction::mint_to(
+        &spl_token::id(),
+        mint,
+        destination,
+        &mint_authority.pubkey(),
+        &[],
+        amount,
+    )
+    .expect("the token program id is valid");
+    process_instructions(context, &[instruction], &[mint_authority]).await
+}
+
+/// Supply of `mint`, 0 if it does not exist
+pub async fn mint_supply(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+) -> Result<u64, BanksClientError> {
+    let account = context.banks_client.get_account(*mint).await?;
+    Ok(account
+        .and_then(|account| Mint::unpack(&account.data).ok())
+        .map_or(0, |mint| mint.supply))
+}
+
+/// Token balance of `address`, 0 if it does not exist
+pub async fn token_balance(
+    context: &mut ProgramTestContext,
+    address: &Pubkey,
+) -> Result<u64, BanksClientError> {
+    let account = context.banks_client.get_account(*address).await?;
+    Ok(account
+        .and_then(|account| TokenAccount::unpack(&account.data).ok())
+        .map_or(0, |account| account.amount))
+}
@@ -0,0 +1,247 @@
+//! Closing an archived pool. Farmers keep withdrawing until every reward
+//! owed is paid out, and what the vaults hold after that is swept to the
+//! authority that archived the pool rather than stranded with them.
+
+use reward_pool::{DailyStats, PendingReward, RewardPoolError};
+use reward_pool_client::{instructions, RewardAttribution};
+use reward_pool_test_utils::{
+    airdrop, assert_pool_error, get_state, process_instructions, token, BanksClientError,
+    PoolFixture, ProgramTestContext, TestPool,
+};
+use solana_keypair::Keypair;
+use solana_program::{
+    clock::Clock, instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+};
+use solana_signer::Signer;
+
+const TREASURY_FUNDS: u64 = 10_000_000;
+const BONUS: u64 = 500_000;
+const REWARD: u64 = 1_000_000;
+const TASK_ID: &str = "task-1";
+const FEE_LAMPORTS: u64 = 5_000;
+const CLAIM_PERIOD: i64 = 86_400;
+
+/// An archived pool, with interest and a lamport withdrawal fee, owing its
+/// first farmer a reward
+struct Archive {
+    context: ProgramTestContext,
+    pool: TestPool,
+    farmer: Keypair,
+    authority: Keypair,
+    /// Reward token account of the authority the vaults are swept to
+    destination: Pubkey,
+}
+
+impl Archive {
+    async fn new() -> Self {
+        let (mut context, pool) = PoolFixture::new()
+            .with_funded_treasury(TREASURY_FUNDS)
+            .start()
+            .await
+            .expect("fixture bootstraps");
+        let farmer = pool.farmer(0);
+        airdrop(&mut context, &farmer.pubkey(), LAMPORTS_PER_SOL)
+            .await
+            .expect("farmer funded");
+        pool.create_reward_account(&mut context, &farmer.pubkey())
+            .await
+            .expect("reward account created");
+        let authority = pool.platform_authority.insecure_clone();
+        let destination = pool
+            .create_reward_account(&mut context, &authority.pubkey())
+            .await
+            .expect("destination created");
+        let mut archive = Self {
+            context,
+            pool,
+            farmer,
+            authority,
+            destination,
+        };
+
+        let accounts = archive.pool.accounts;
+        let authority = archive.authority.pubkey();
+        let set_interest = instructions::set_interest_policy(
+            &accounts,
+            &authority,
+            &accounts.reward_mint,
+            1_000,
+            CLAIM_PERIOD,
+        );
+        let set_fee =
+            instructions::set_withdrawal_fee(&accounts, &authority, FEE_LAMPORTS, false, 0);
+        archive
+            .process(&[set_interest, set_fee])
+            .await
+            .expect("interest and fee set");
+        archive
+            .pool
+            .mint_to(&mut archive.context, &accounts.bonus_vault(), BONUS)
+            .await
+            .expect("bonus vault funded");
+
+        let stats_day = DailyStats::day_of(archive.now().await);
+        let record = instructions::record_reward(
+            &accounts,
+            &authority,
+            &archive.farmer.pubkey(),
+            REWARD,
+            TASK_ID.to_string(),
+            RewardAttribution::default(),
+            stats_day,
+        );
+        archive.process(&[record]).await.expect("reward recorded");
+
+        let claim_deadline = archive.now().await + CLAIM_PERIOD;
+        let archive_pool = instructions::archive_pool(&accounts, &authority, claim_deadline);
+        archive.process(&[archive_pool]).await.expect("archived");
+        archive
+    }
+
+    /// Processes `instructions` signed by the authority
+    async fn process(&mut self, instructions: &[Instruction]) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        process_instructions(&mut self.context, instructions, &[&authority]).await
+    }
+
+    async fn now(&mut self) -> i64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        clock.unix_timestamp
+    }
+
+    /// Moves the bank's clock `seconds` forward, on a new slot so that
+    /// retrying a rejected instruction is not a duplicate transaction
+    async fn advance_clock(&mut self, seconds: i64) {
+        let slot = self
+            .context
+            .banks_client
+            .get_root_slot()
+            .await
+            .expect("slot");
+        self.context.warp_to_slot(slot + 2).expect("warped");
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    async fn balance(&mut self, account: &Pubkey) -> u64 {
+        token::token_balance(&mut self.context, account)
+            .await
+            .expect("balance read")
+    }
+
+    async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(*address)
+            .await
+            .expect("lamports read")
+    }
+
+    fn close_pool(&self) -> Instruction {
+        instructions::close_pool(
+            &self.pool.accounts,
+            &self.authority.pubkey(),
+            &self.destination,
+        )
+    }
+
+    /// Withdraws the farmer's reward, paying the fee into the fee vault
+    async fn withdraw(&mut self) {
+        let accounts = self.pool.accounts;
+        let pending: PendingReward = get_state(
+            &mut self.context.banks_client,
+            &accounts.pending_reward(&self.farmer.pubkey(), TASK_ID),
+        )
+        .await;
+        let stats_day = DailyStats::day_of(self.now().await);
+        let withdraw = instructions::withdraw_reward(
+            &accounts,
+            &self.farmer.pubkey(),
+            TASK_ID,
+            &accounts.reward_account(&self.farmer.pubkey()),
+            pending.amount,
+            1,
+            stats_day,
+        );
+        let farmer = self.farmer.insecure_clone();
+        process_instructions(&mut self.context, &[withdraw], &[&farmer])
+            .await
+            .expect("withdrawn after the deadline");
+    }
+}
+
+#[tokio::test]
+async fn closing_waits_for_every_reward_owed() {
+    let mut archive = Archive::new().await;
+    assert_pool_error(
+        archive.process(&[archive.close_pool()]).await,
+        RewardPoolError::ClaimPeriodNotOver,
+    );
+
+    archive.advance_clock(CLAIM_PERIOD).await;
+    assert_pool_error(
+        archive.process(&[archive.close_pool()]).await,
+        RewardPoolError::RewardsOutstanding,
+    );
+
+    archive.withdraw().await;
+    let close = archive.close_pool();
+    archive.process(&[close]).await.expect("pool closed");
+}
+
+#[tokio::test]
+async fn closing_sweeps_the_vaults_to_the_archiving_authority() {
+    let mut archive = Archive::new().await;
+    archive.advance_clock(CLAIM_PERIOD).await;
+    archive.withdraw().await;
+
+    let accounts = archive.pool.accounts;
+    let vaults = [
+        accounts.treasury_vault(),
+        accounts.platform_fee_vault(),
+        accounts.bonus_vault(),
+    ];
+    let mut tokens = 0;
+    for vault in &vaults {
+        tokens += archive.balance(vault).await;
+    }
+    assert!(tokens > 0, "the treasury keeps what farmers were not owed");
+    let closed = [
+        accounts.pool,
+        accounts.pool_archive(),
+        accounts.instruction_counters(),
+        accounts.fee_vault(),
+    ];
+    let mut lamports = 0;
+    for address in vaults.iter().chain(&closed) {
+        lamports += archive.lamports(address).await;
+    }
+    assert!(archive.lamports(&accounts.fee_vault()).await > FEE_LAMPORTS);
+    let authority = archive.authority.pubkey();
+    let destination = archive.destination;
+    let before = archive.lamports(&authority).await;
+
+    let close = archive.close_pool();
+    archive.process(&[close]).await.expect("pool closed");
+
+    assert_eq!(archive.balance(&destination).await, tokens);
+    for address in vaults.iter().chain(&closed) {
+        assert_eq!(archive.lamports(address).await, 0);
+    }
+    assert_eq!(archive.lamports(&authority).await, before + lamports);
+}
+
+#[tokio::test]
+async fn closing_rejects_a_vault_of_another_pool() {
+    let mut archive = Archive::new().await;
+    archive.advance_clock(CLAIM_PERIOD).await;
+    archive.withdraw().await;
+
+    let mut close = archive.close_pool();
+    close.accounts[6].pubkey = archive.pool.accounts.treasury_vault();
+    assert_pool_error(
+        archive.process(&[close]).await,
+        RewardPoolError::InvalidBonusVaultAccount,
+    );
+}
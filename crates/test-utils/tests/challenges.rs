@@ -0,0 +1,240 @@
+//! Challenges against pending rewards. Anyone can lock a reward inside its
+//! challenge window by putting up a bond; the platform authority or the
+//! pool verifier then voids the reward, or rejects the challenge and pays
+//! the bond to the farmer.
+
+use reward_pool::{
+    DailyStats, PendingReward, RewardChallenge, RewardPoolError, CHALLENGE_BOND, CHALLENGE_WINDOW,
+};
+use reward_pool_client::{instructions, RewardAttribution};
+use reward_pool_test_utils::{
+    airdrop, assert_pool_error, fixture_keypair, get_state, process_instructions, BanksClientError,
+    PoolFixture, ProgramTestContext, TestPool,
+};
+use solana_keypair::Keypair;
+use solana_program::{
+    clock::Clock, instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+};
+use solana_signer::Signer;
+
+const TREASURY_FUNDS: u64 = 10_000_000;
+const REWARD: u64 = 100_000;
+const TASK_ID: &str = "task-1";
+
+// Keypair role of the accounts the tests add to the fixture's
+const CHALLENGER: u8 = 100;
+
+/// A funded pool, its first farmer with a reward recorded under `TASK_ID`,
+/// and a funded challenger
+struct Dispute {
+    context: ProgramTestContext,
+    pool: TestPool,
+    farmer: Keypair,
+    challenger: Keypair,
+}
+
+impl Dispute {
+    async fn new() -> Self {
+        let (mut context, pool) = PoolFixture::new()
+            .with_funded_treasury(TREASURY_FUNDS)
+            .start()
+            .await
+            .expect("fixture bootstraps");
+        let farmer = pool.farmer(0);
+        let challenger = fixture_keypair(0, CHALLENGER, 0);
+        for signer in [&farmer, &challenger] {
+            airdrop(&mut context, &signer.pubkey(), LAMPORTS_PER_SOL)
+                .await
+                .expect("signer funded");
+        }
+        pool.create_reward_account(&mut context, &farmer.pubkey())
+            .await
+            .expect("reward account created");
+
+        let clock: Clock = context.banks_client.get_sysvar().await.expect("clock");
+        let record = instructions::record_reward(
+            &pool.accounts,
+            &pool.platform_authority.pubkey(),
+            &farmer.pubkey(),
+            REWARD,
+            TASK_ID.to_string(),
+            RewardAttribution::default(),
+            DailyStats::day_of(clock.unix_timestamp),
+        );
+        process_instructions(&mut context, &[record], &[&pool.platform_authority])
+            .await
+            .expect("reward recorded");
+
+        Self {
+            context,
+            pool,
+            farmer,
+            challenger,
+        }
+    }
+
+    async fn process(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        process_instructions(&mut self.context, &[instruction], signers).await
+    }
+
+    /// Moves the bank's clock `seconds` forward, on a new slot so that
+    /// retrying a rejected instruction is not a duplicate transaction
+    async fn advance_clock(&mut self, seconds: i64) {
+        let slot = self
+            .context
+            .banks_client
+            .get_root_slot()
+            .await
+            .expect("slot");
+        self.context.warp_to_slot(slot + 2).expect("warped");
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(*address)
+            .await
+            .expect("lamports read")
+    }
+
+    fn pending_reward(&self) -> Pubkey {
+        self.pool
+            .accounts
+            .pending_reward(&self.farmer.pubkey(), TASK_ID)
+    }
+
+    fn reward_challenge(&self) -> Pubkey {
+        self.pool
+            .accounts
+            .reward_challenge(&self.farmer.pubkey(), TASK_ID)
+    }
+
+    async fn challenge(&mut self) -> Result<(), BanksClientError> {
+        let challenge = instructions::challenge_reward(
+            &self.pool.accounts,
+            &self.challenger.pubkey(),
+            &self.farmer.pubkey(),
+            TASK_ID,
+        );
+        let challenger = self.challenger.insecure_clone();
+        self.process(challenge, &[&challenger]).await
+    }
+
+    async fn resolve(&mut self, resolver: &Keypair, upheld: bool) -> Result<(), BanksClientError> {
+        let resolve = instructions::resolve_challenge(
+            &self.pool.accounts,
+            &resolver.pubkey(),
+            &self.farmer.pubkey(),
+            TASK_ID,
+            &self.challenger.pubkey(),
+            upheld,
+        );
+        self.process(resolve, &[resolver]).await
+    }
+
+    async fn claim(&mut self) -> Result<(), BanksClientError> {
+        let claim = instructions::claim_many(
+            &self.pool.accounts,
+            &self.farmer.pubkey(),
+            &[self.pending_reward()],
+        );
+        let farmer = self.farmer.insecure_clone();
+        self.process(claim, &[&farmer]).await
+    }
+}
+
+#[tokio::test]
+async fn an_upheld_challenge_voids_the_reward() {
+    let mut dispute = Dispute::new().await;
+    let authority = dispute.pool.platform_authority.insecure_clone();
+    let challenger = dispute.challenger.pubkey();
+    let pending_reward = dispute.pending_reward();
+    let reward_challenge = dispute.reward_challenge();
+
+    dispute.challenge().await.expect("reward challenged");
+    let challenge: RewardChallenge =
+        get_state(&mut dispute.context.banks_client, &reward_challenge).await;
+    assert_eq!(challenge.challenger, challenger);
+    assert_eq!(challenge.bond, CHALLENGE_BOND);
+    let pending: PendingReward =
+        get_state(&mut dispute.context.banks_client, &pending_reward).await;
+    assert!(pending.is_challenged);
+    assert_eq!(challenge.amount, pending.amount);
+
+    // The reward is locked, and a second challenge would only add a bond
+    assert_pool_error(dispute.claim().await, RewardPoolError::NoPendingRewards);
+    dispute.advance_clock(0).await;
+    assert_pool_error(dispute.challenge().await, RewardPoolError::RewardChallenged);
+
+    // Only the platform authority or the pool verifier decides
+    let challenger_keypair = dispute.challenger.insecure_clone();
+    assert_pool_error(
+        dispute.resolve(&challenger_keypair, true).await,
+        RewardPoolError::InvalidAuthority,
+    );
+
+    let challenge_lamports = dispute.lamports(&reward_challenge).await;
+    let challenger_lamports = dispute.lamports(&challenger).await;
+    dispute
+        .resolve(&authority, true)
+        .await
+        .expect("challenge upheld");
+    assert_eq!(dispute.lamports(&pending_reward).await, 0);
+    assert_eq!(dispute.lamports(&reward_challenge).await, 0);
+    assert_eq!(
+        dispute.lamports(&challenger).await,
+        challenger_lamports + challenge_lamports
+    );
+}
+
+#[tokio::test]
+async fn a_rejected_challenge_pays_the_bond_to_the_farmer() {
+    let mut dispute = Dispute::new().await;
+    let authority = dispute.pool.platform_authority.insecure_clone();
+    let farmer = dispute.farmer.pubkey();
+    let challenger = dispute.challenger.pubkey();
+    let pending_reward = dispute.pending_reward();
+    let reward_challenge = dispute.reward_challenge();
+
+    dispute.challenge().await.expect("reward challenged");
+    let challenge_lamports = dispute.lamports(&reward_challenge).await;
+    let challenger_lamports = dispute.lamports(&challenger).await;
+    let farmer_lamports = dispute.lamports(&farmer).await;
+    dispute
+        .resolve(&authority, false)
+        .await
+        .expect("challenge rejected");
+
+    assert_eq!(dispute.lamports(&reward_challenge).await, 0);
+    assert_eq!(
+        dispute.lamports(&challenger).await,
+        challenger_lamports + challenge_lamports - CHALLENGE_BOND
+    );
+    assert_eq!(
+        dispute.lamports(&farmer).await,
+        farmer_lamports + CHALLENGE_BOND
+    );
+    let pending: PendingReward =
+        get_state(&mut dispute.context.banks_client, &pending_reward).await;
+    assert!(!pending.is_challenged);
+    dispute.claim().await.expect("reward claimed");
+}
+
+#[tokio::test]
+async fn rewards_can_only_be_challenged_within_the_window() {
+    let mut dispute = Dispute::new().await;
+
+    dispute.advance_clock(CHALLENGE_WINDOW + 1).await;
+    assert_pool_error(
+        dispute.challenge().await,
+        RewardPoolError::ChallengeWindowClosed,
+    );
+    dispute.claim().await.expect("reward claimed");
+}
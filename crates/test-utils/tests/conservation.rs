@@ -0,0 +1,434 @@
+//! Conservation-of-tokens invariants over random instruction sequences.
+//!
+//! Each case bootstraps a pool and applies a random sequence of rewards,
+//! withdrawals, approvals, claims and admin changes to it. Many of them
+//! fail (paused pool, short balances, nothing to claim); whatever the
+//! outcome, after every step no reward token may have been created or
+//! destroyed except by the fixture's mints and recorded burns, what
+//! farmers were credited must be in their accounts, burned or pending,
+//! and the vault must cover every reward still pending. A failing case
+//! prints its seed; `CONSERVATION_SEED=<seed>` replays it alone and
+//! `CONSERVATION_CASES` runs more cases.
+
+use borsh::BorshDeserialize;
+use reward_pool::{DailyStats, PendingReward, RewardPool, WithdrawBurn};
+use reward_pool_client::{instructions, RewardAttribution};
+use reward_pool_test_utils::{
+    airdrop, fixture_keypair, process_instructions, token, PoolFixture, ProgramTestContext,
+    TestPool,
+};
+use solana_keypair::Keypair;
+use solana_program::{
+    clock::Clock, instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+};
+use solana_signer::Signer;
+
+const DEFAULT_CASES: u64 = 12;
+const STEPS: usize = 40;
+const FARMERS: usize = 3;
+const TREASURY_FUNDS: u64 = 20_000_000;
+const VAULT_FUNDS: u64 = 1_000_000;
+
+// Keypair roles of the accounts the harness adds to the fixture's
+const APPROVER: u8 = 100;
+const DESTINATION: u8 = 101;
+
+/// SplitMix64, so a case replays from its seed alone
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+#[derive(Debug)]
+enum Operation {
+    Record { farmer: usize, amount: u64 },
+    Withdraw { pending: usize },
+    SetFee(u8),
+    SetWithdrawBurn(u16),
+    SetApprover(bool),
+    Approve { pending: usize },
+    ClaimMany { farmer: usize, pending: Vec<usize> },
+    Pause,
+    Resume,
+}
+
+impl Operation {
+    fn random(rng: &mut Rng, pending: &[(usize, String)]) -> Self {
+        let farmer = rng.below(FARMERS as u64) as usize;
+        let pending_count = pending.len() as u64;
+        match rng.below(20) {
+            7..=10 if pending_count > 0 => Self::Withdraw {
+                pending: rng.below(pending_count) as usize,
+            },
+            11 => Self::SetFee(rng.below(101) as u8),
+            12 => Self::SetWithdrawBurn(rng.below(1_001) as u16),
+            13 => Self::SetApprover(rng.below(2) == 0),
+            14..=16 if pending_count > 0 => Self::Approve {
+                pending: rng.below(pending_count) as usize,
+            },
+            17 if pending_count > 0 => {
+                // The pending rewards of one farmer, sometimes with another
+                // one that may be someone else's or already in the list;
+                // the program must refuse the first and not pay the second
+                // twice
+                let farmer = pending[rng.below(pending_count) as usize].0;
+                let mut indices: Vec<usize> = (0..pending.len())
+                    .filter(|&index| pending[index].0 == farmer)
+                    .take(reward_pool::MAX_CLAIM_BATCH - 1)
+                    .collect();
+                if rng.below(4) == 0 {
+                    indices.push(rng.below(pending_count) as usize);
+                }
+                Self::ClaimMany {
+                    farmer,
+                    pending: indices,
+                }
+            }
+            18 => Self::Pause,
+            19 => Self::Resume,
+            _ => Self::Record {
+                farmer,
+                amount: 1 + rng.below(TREASURY_FUNDS / 8),
+            },
+        }
+    }
+}
+
+struct Harness {
+    context: ProgramTestContext,
+    pool: TestPool,
+    approver: Keypair,
+    farmers: Vec<Keypair>,
+    destinations: Vec<Pubkey>,
+    /// Farmer index and task id of every reward recorded
+    pending: Vec<(usize, String)>,
+    /// Gross amount of every reward recorded, before fees
+    recorded: u64,
+    tasks: u64,
+    nonce: u64,
+}
+
+impl Harness {
+    async fn new() -> Self {
+        let (mut context, pool) = PoolFixture::new()
+            .with_funded_treasury(TREASURY_FUNDS)
+            .with_funded_vault(VAULT_FUNDS)
+            .start()
+            .await
+            .expect("fixture bootstraps");
+
+        let approver = fixture_keypair(0, APPROVER, 0);
+        let mut farmers = Vec::new();
+        let mut destinations = Vec::new();
+        for index in 0..FARMERS as u8 {
+            let farmer = pool.farmer(index);
+            airdrop(&mut context, &farmer.pubkey(), LAMPORTS_PER_SOL)
+                .await
+                .expect("farmer funded");
+            // RecordReward expects the reward account to exist once the
+            // farmer has been rewarded; creating it up front keeps the
+            // sequences about token movements
+            pool.create_reward_account(&mut context, &farmer.pubkey())
+                .await
+                .expect("reward account created");
+            let destination = fixture_keypair(0, DESTINATION, index);
+            token::create_token_account(
+                &mut context,
+                &destination,
+                &pool.accounts.reward_mint,
+                &farmer.pubkey(),
+            )
+            .await
+            .expect("destination created");
+            farmers.push(farmer);
+            destinations.push(destination.pubkey());
+        }
+
+        Self {
+            context,
+            pool,
+            approver,
+            farmers,
+            destinations,
+            pending: Vec::new(),
+            recorded: 0,
+            tasks: 0,
+            nonce: 0,
+        }
+    }
+
+    async fn stats_day(&mut self) -> i64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        DailyStats::day_of(clock.unix_timestamp)
+    }
+
+    async fn process(&mut self, instruction: Instruction, signer: &Keypair) -> bool {
+        process_instructions(&mut self.context, &[instruction], &[signer])
+            .await
+            .is_ok()
+    }
+
+    /// Applies `operation`, returning whether its transaction succeeded
+    async fn apply(&mut self, operation: &Operation) -> bool {
+        let accounts = self.pool.accounts;
+        let authority = self.pool.platform_authority.insecure_clone();
+        match *operation {
+            Operation::Record { farmer, amount } => {
+                self.tasks += 1;
+                let task_id = format!("task-{}", self.tasks);
+                let stats_day = self.stats_day().await;
+                let instruction = instructions::record_reward(
+                    &accounts,
+                    &authority.pubkey(),
+                    &self.farmers[farmer].pubkey(),
+                    amount,
+                    task_id.clone(),
+                    RewardAttribution::default(),
+                    stats_day,
+                );
+                let succeeded = self.process(instruction, &authority).await;
+                if succeeded {
+                    self.recorded += amount;
+                    self.pending.push((farmer, task_id));
+                }
+                succeeded
+            }
+            Operation::Withdraw { pending } => {
+                self.nonce += 1;
+                let stats_day = self.stats_day().await;
+                let (farmer, task_id) = self.pending[pending].clone();
+                let farmer_keypair = self.farmers[farmer].insecure_clone();
+                let amount = self
+                    .read::<PendingReward>(
+                        accounts.pending_reward(&farmer_keypair.pubkey(), &task_id),
+                    )
+                    .await
+                    .expect("pending reward exists")
+                    .amount;
+                let instruction = instructions::withdraw_reward(
+                    &accounts,
+                    &farmer_keypair.pubkey(),
+                    &task_id,
+                    &self.destinations[farmer],
+                    amount,
+                    self.nonce,
+                    stats_day,
+                );
+                self.process(instruction, &farmer_keypair).await
+            }
+            Operation::SetFee(fee_percentage) => {
+                let instruction = instructions::update_platform_fee(
+                    &accounts,
+                    &authority.pubkey(),
+                    fee_percentage,
+                );
+                self.process(instruction, &authority).await
+            }
+            Operation::SetWithdrawBurn(withdraw_burn_bps) => {
+                let instruction = instructions::set_withdraw_burn(
+                    &accounts,
+                    &authority.pubkey(),
+                    withdraw_burn_bps,
+                );
+                self.process(instruction, &authority).await
+            }
+            Operation::SetApprover(enabled) => {
+                let approver = self.approver.pubkey();
+                let instruction = instructions::set_reward_approver(
+                    &accounts,
+                    &authority.pubkey(),
+                    enabled.then_some(&approver),
+                );
+                self.process(instruction, &authority).await
+            }
+            Operation::Approve { pending } => {
+                let (farmer, task_id) = self.pending[pending].clone();
+                let approver = self.approver.insecure_clone();
+                let instruction = instructions::approve_reward(
+                    &accounts,
+                    &approver.pubkey(),
+                    &self.farmers[farmer].pubkey(),
+                    &task_id,
+                );
+                self.process(instruction, &approver).await
+            }
+            Operation::ClaimMany {
+                farmer,
+                ref pending,
+            } => {
+                let farmer_keypair = self.farmers[farmer].insecure_clone();
+                // Other farmers' pending rewards are passed too, which the
+                // program must refuse
+                let pending_rewards: Vec<Pubkey> = pending
+                    .iter()
+                    .map(|&index| {
+                        let (owner, task_id) = &self.pending[index];
+                        accounts.pending_reward(&self.farmers[*owner].pubkey(), task_id)
+                    })
+                    .collect();
+                let instruction =
+                    instructions::claim_many(&accounts, &farmer_keypair.pubkey(), &pending_rewards);
+                self.process(instruction, &farmer_keypair).await
+            }
+            Operation::Pause => {
+                let instruction = instructions::pause_pool(&accounts, &authority.pubkey());
+                self.process(instruction, &authority).await
+            }
+            Operation::Resume => {
+                let instruction = instructions::resume_pool(&accounts, &authority.pubkey());
+                self.process(instruction, &authority).await
+            }
+        }
+    }
+
+    async fn balance(&mut self, address: &Pubkey) -> u64 {
+        token::token_balance(&mut self.context, address)
+            .await
+            .expect("balance read")
+    }
+
+    async fn read<T: BorshDeserialize>(&mut self, address: Pubkey) -> Option<T> {
+        self.context
+            .banks_client
+            .get_account(address)
+            .await
+            .expect("account read")
+            .map(|account| T::try_from_slice(&account.data).expect("account decodes"))
+    }
+
+    /// Checks every invariant, describing the first one broken
+    async fn check_invariants(&mut self) -> Result<(), String> {
+        let accounts = self.pool.accounts;
+        let pool: RewardPool = self.read(accounts.pool).await.expect("pool exists");
+        let burned = self
+            .read::<WithdrawBurn>(accounts.withdraw_burn())
+            .await
+            .map_or(0, |withdraw_burn| withdraw_burn.total_burned);
+
+        let supply = token::mint_supply(&mut self.context, &accounts.reward_mint)
+            .await
+            .expect("supply read");
+        // The platform treasury is the vault
+        let vault = self.balance(&accounts.treasury_vault()).await;
+        let fee_vault = self.balance(&accounts.platform_fee_vault()).await;
+        let mut farmer_holdings = 0;
+        for index in 0..FARMERS {
+            let reward_account = accounts.reward_account(&self.farmers[index].pubkey());
+            let destination = self.destinations[index];
+            farmer_holdings += self.balance(&reward_account).await;
+            farmer_holdings += self.balance(&destination).await;
+        }
+
+        // No token is minted or burned except by the fixture and recorded
+        // withdrawal burns
+        let minted = TREASURY_FUNDS + VAULT_FUNDS;
+        if supply + burned != minted {
+            return Err(format!(
+                "supply {supply} + burned {burned} != minted {minted}"
+            ));
+        }
+        // Every token is in an account the sequence can reach
+        if vault + fee_vault + farmer_holdings != supply {
+            return Err(format!(
+                "vault {vault} + fee vault {fee_vault} + farmers {farmer_holdings} != supply {supply}"
+            ));
+        }
+        // Every fee collected left the vault for the fee vault
+        if fee_vault != pool.total_platform_fees_collected {
+            return Err(format!(
+                "fee vault {fee_vault} != fees {}",
+                pool.total_platform_fees_collected
+            ));
+        }
+        if pool.total_rewards_distributed + pool.total_platform_fees_collected != self.recorded {
+            return Err(format!(
+                "distributed {} + fees {} != recorded {}",
+                pool.total_rewards_distributed, pool.total_platform_fees_collected, self.recorded
+            ));
+        }
+
+        // The vault covers every pending reward not yet claimed, as long as
+        // the platform recorded no more than it funded: fees leave for the
+        // fee vault, so they cannot cover rewards recorded beyond the funds
+        let mut liabilities = 0;
+        for (farmer, task_id) in self.pending.clone() {
+            let address = accounts.pending_reward(&self.farmers[farmer].pubkey(), &task_id);
+            let pending: PendingReward = self.read(address).await.expect("pending reward exists");
+            if !pending.is_withdrawn {
+                liabilities += pending.amount;
+            }
+        }
+        if self.recorded <= minted && vault < liabilities {
+            return Err(format!("vault {vault} < pending liabilities {liabilities}"));
+        }
+        // What farmers were credited is in their accounts, burned, or in
+        // the vault waiting to be claimed
+        if farmer_holdings + burned + liabilities != pool.total_rewards_distributed {
+            return Err(format!(
+                "farmers {farmer_holdings} + burned {burned} + pending {liabilities} != distributed {}",
+                pool.total_rewards_distributed
+            ));
+        }
+        Ok(())
+    }
+}
+
+async fn run_case(seed: u64) {
+    let mut rng = Rng(seed);
+    let mut harness = Harness::new().await;
+    harness
+        .check_invariants()
+        .await
+        .unwrap_or_else(|broken| panic!("seed {seed}: broken after bootstrap: {broken}"));
+
+    // Half the cases start in maker-checker mode, so rewards pile up in the
+    // vault waiting for approval and claims
+    let mut history = Vec::new();
+    if seed % 2 == 1 {
+        let operation = Operation::SetApprover(true);
+        let succeeded = harness.apply(&operation).await;
+        history.push(format!("{operation:?} -> {succeeded}"));
+    }
+    for _ in 0..STEPS {
+        let operation = Operation::random(&mut rng, &harness.pending);
+        let succeeded = harness.apply(&operation).await;
+        history.push(format!("{operation:?} -> {succeeded}"));
+        if let Err(broken) = harness.check_invariants().await {
+            panic!("seed {seed}: {broken}\nsteps:\n{}", history.join("\n"));
+        }
+    }
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name).ok().map(|value| {
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("{name} must be a number"))
+    })
+}
+
+#[test]
+fn random_sequences_conserve_tokens() {
+    let seeds: Vec<u64> = match env_u64("CONSERVATION_SEED") {
+        Some(seed) => vec![seed],
+        None => (0..env_u64("CONSERVATION_CASES").unwrap_or(DEFAULT_CASES)).collect(),
+    };
+    let runtime = solana_program_test::tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+    for seed in seeds {
+        runtime.block_on(run_case(seed));
+    }
+}
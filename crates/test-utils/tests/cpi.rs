@@ -0,0 +1,253 @@
+//! The pool behind a partner program's CPI, tested the way an integrator
+//! would: the partner registered next to the pool, a fixture pool
+//! bootstrapped in the same bank, and the pool's state and errors checked
+//! with the program's `test-support` helpers.
+
+use reward_pool::{DailyStats, PendingReward, RewardPool, RewardPoolError};
+use reward_pool_client::{instructions, RewardAttribution};
+use reward_pool_test_utils::{
+    airdrop, assert_instruction_error, assert_pool_error, get_state, process_instructions,
+    program_test, token, PoolFixture,
+};
+use solana_keypair::Keypair;
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+};
+use solana_program_test::processor;
+use solana_signer::Signer;
+
+const TREASURY_FUNDS: u64 = 10_000_000;
+const REWARD: u64 = 100_000;
+
+const PARTNER_PROGRAM: Pubkey = Pubkey::new_from_array([7; 32]);
+
+/// A partner program passing its instruction data and accounts on to the
+/// pool, which is its first account
+fn process_partner_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (pool_program, accounts) = accounts.split_first().expect("pool program passed");
+    let instruction = Instruction {
+        program_id: *pool_program.key,
+        accounts: accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: *account.key,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: instruction_data.to_vec(),
+    };
+    invoke(&instruction, accounts)
+}
+
+/// `instruction` routed through the partner program
+fn through_partner(instruction: Instruction) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(instruction.program_id, false)];
+    accounts.extend(instruction.accounts);
+    Instruction {
+        program_id: PARTNER_PROGRAM,
+        accounts,
+        data: instruction.data,
+    }
+}
+
+#[tokio::test]
+async fn pool_is_driven_through_a_partner_cpi() {
+    let mut program_test = program_test();
+    program_test.add_program(
+        "partner",
+        PARTNER_PROGRAM,
+        processor!(process_partner_instruction),
+    );
+    let mut context = program_test.start_with_context().await;
+    let pool = PoolFixture::new()
+        .bootstrap(&mut context)
+        .await
+        .expect("fixture bootstraps");
+    let authority = &pool.platform_authority;
+
+    let update_fee = instructions::update_platform_fee(&pool.accounts, &authority.pubkey(), 5);
+    process_instructions(&mut context, &[through_partner(update_fee)], &[authority])
+        .await
+        .expect("fee updated through the partner");
+    let pool_state: RewardPool = get_state(&mut context.banks_client, &pool.accounts.pool).await;
+    assert_eq!(pool_state.platform_fee_percentage, 5);
+
+    // The pool's errors come back through the CPI unchanged
+    let excessive_fee = instructions::update_platform_fee(&pool.accounts, &authority.pubkey(), 101);
+    assert_pool_error(
+        process_instructions(
+            &mut context,
+            &[through_partner(excessive_fee)],
+            &[authority],
+        )
+        .await,
+        RewardPoolError::InvalidPlatformFee,
+    );
+}
+
+const AGENT_PROGRAM: Pubkey = Pubkey::new_from_array([8; 32]);
+const AGENT_WALLET_SEED: &[u8] = b"wallet";
+
+/// Wallet of the agent program, a PDA that can only sign through it
+fn agent_wallet() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AGENT_WALLET_SEED], &AGENT_PROGRAM)
+}
+
+/// An agent program passing its instruction on to the pool like the
+/// partner program, signing for its wallet wherever the wallet appears
+fn process_agent_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (wallet, bump_seed) = agent_wallet();
+    let (pool_program, accounts) = accounts.split_first().expect("pool program passed");
+    let instruction = Instruction {
+        program_id: *pool_program.key,
+        accounts: accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: *account.key,
+                is_signer: account.is_signer || *account.key == wallet,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: instruction_data.to_vec(),
+    };
+    invoke_signed(
+        &instruction,
+        accounts,
+        &[&[AGENT_WALLET_SEED, &[bump_seed]]],
+    )
+}
+
+/// `instruction` routed through the agent program, which signs for its
+/// wallet instead of the transaction
+fn through_agent(instruction: Instruction) -> Instruction {
+    let (wallet, _) = agent_wallet();
+    let mut accounts = vec![AccountMeta::new_readonly(instruction.program_id, false)];
+    accounts.extend(instruction.accounts.into_iter().map(|mut account| {
+        account.is_signer &= account.pubkey != wallet;
+        account
+    }));
+    Instruction {
+        program_id: AGENT_PROGRAM,
+        accounts,
+        data: instruction.data,
+    }
+}
+
+#[tokio::test]
+async fn program_owned_farmer_withdraws_through_its_program() {
+    let mut program_test = program_test();
+    program_test.add_program(
+        "agent",
+        AGENT_PROGRAM,
+        processor!(process_agent_instruction),
+    );
+    let mut context = program_test.start_with_context().await;
+    let pool = PoolFixture::new()
+        .with_funded_treasury(TREASURY_FUNDS)
+        .bootstrap(&mut context)
+        .await
+        .expect("fixture bootstraps");
+    let authority = &pool.platform_authority;
+    let (wallet, _) = agent_wallet();
+    let day = DailyStats::day_of(
+        context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .expect("clock readable")
+            .unix_timestamp,
+    );
+
+    // The reward account of an off-curve owner is an ordinary ATA
+    let reward_account = pool
+        .create_reward_account(&mut context, &wallet)
+        .await
+        .expect("reward account created");
+    assert_eq!(reward_account, pool.accounts.reward_account(&wallet));
+    let record = instructions::record_reward(
+        &pool.accounts,
+        &authority.pubkey(),
+        &wallet,
+        REWARD,
+        "agent-task".to_string(),
+        RewardAttribution::default(),
+        day,
+    );
+    process_instructions(&mut context, &[record], &[authority])
+        .await
+        .expect("reward recorded for the wallet");
+
+    let pending: PendingReward = get_state(
+        &mut context.banks_client,
+        &pool.accounts.pending_reward(&wallet, "agent-task"),
+    )
+    .await;
+    let earned = pending.amount;
+
+    // The wallet holds no lamports, so a sponsor pays the rent and the
+    // destination must belong to the wallet
+    let destination = Keypair::new();
+    token::create_token_account(
+        &mut context,
+        &destination,
+        &pool.accounts.reward_mint,
+        &wallet,
+    )
+    .await
+    .expect("destination created");
+    let sponsor = Keypair::new();
+    airdrop(&mut context, &sponsor.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .expect("sponsor funded");
+    let withdraw = instructions::sponsored_withdraw_reward(
+        &pool.accounts,
+        &wallet,
+        "agent-task",
+        &destination.pubkey(),
+        earned,
+        0,
+        day,
+        &sponsor.pubkey(),
+    );
+    process_instructions(&mut context, &[through_agent(withdraw)], &[&sponsor])
+        .await
+        .expect("withdrawn through the agent");
+    assert_eq!(
+        token::token_balance(&mut context, &destination.pubkey())
+            .await
+            .expect("destination readable"),
+        earned
+    );
+
+    // Outside the agent nobody can sign for the wallet
+    let mut unsigned = instructions::sponsored_withdraw_reward(
+        &pool.accounts,
+        &wallet,
+        "agent-task",
+        &destination.pubkey(),
+        earned,
+        1,
+        day,
+        &sponsor.pubkey(),
+    );
+    unsigned.accounts[0].is_signer = false;
+    assert_instruction_error(
+        process_instructions(&mut context, &[unsigned], &[&sponsor]).await,
+        InstructionError::MissingRequiredSignature,
+    );
+}
@@ -0,0 +1,154 @@
+//! The platform authority's dead-man switch. Every instruction the
+//! authority signs on a pool keeps it active, whether it is counted in the
+//! pool's instruction counters or spans several pools, and the recovery
+//! authority takes over only after a full timeout without one.
+
+use reward_pool::{RewardPool, RewardPoolError, MIN_INACTIVITY_TIMEOUT};
+use reward_pool_client::instructions;
+use reward_pool_test_utils::{
+    airdrop, assert_pool_error, fixture_keypair, get_state, process_instructions, BanksClientError,
+    PoolFixture, ProgramTestContext, TestPool,
+};
+use solana_keypair::Keypair;
+use solana_program::{clock::Clock, instruction::Instruction, native_token::LAMPORTS_PER_SOL};
+use solana_signer::Signer;
+
+// Keypair role of the accounts the tests add to the fixture's
+const RECOVERY_AUTHORITY: u8 = 100;
+
+/// A pool whose switch hands it to a funded recovery authority after
+/// `MIN_INACTIVITY_TIMEOUT`
+struct Switch {
+    context: ProgramTestContext,
+    pool: TestPool,
+    recovery_authority: Keypair,
+}
+
+impl Switch {
+    async fn new() -> Self {
+        let (mut context, pool) = PoolFixture::new()
+            .start()
+            .await
+            .expect("fixture bootstraps");
+        let recovery_authority = fixture_keypair(0, RECOVERY_AUTHORITY, 0);
+        airdrop(&mut context, &recovery_authority.pubkey(), LAMPORTS_PER_SOL)
+            .await
+            .expect("recovery authority funded");
+
+        let arm = instructions::set_recovery_authority(
+            &pool.accounts,
+            &pool.platform_authority.pubkey(),
+            &recovery_authority.pubkey(),
+            MIN_INACTIVITY_TIMEOUT,
+        );
+        process_instructions(&mut context, &[arm], &[&pool.platform_authority])
+            .await
+            .expect("switch armed");
+
+        Self {
+            context,
+            pool,
+            recovery_authority,
+        }
+    }
+
+    async fn process_as_authority(
+        &mut self,
+        instruction: Instruction,
+    ) -> Result<(), BanksClientError> {
+        let authority = self.pool.platform_authority.insecure_clone();
+        process_instructions(&mut self.context, &[instruction], &[&authority]).await
+    }
+
+    /// Moves the bank's clock `seconds` forward, on a new slot so that
+    /// retrying a rejected instruction is not a duplicate transaction
+    async fn advance_clock(&mut self, seconds: i64) {
+        let slot = self
+            .context
+            .banks_client
+            .get_root_slot()
+            .await
+            .expect("slot");
+        self.context.warp_to_slot(slot + 2).expect("warped");
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    async fn pool_state(&mut self) -> RewardPool {
+        get_state(&mut self.context.banks_client, &self.pool.accounts.pool).await
+    }
+
+    async fn claim(&mut self) -> Result<(), BanksClientError> {
+        let claim = instructions::claim_dormant_authority(
+            &self.pool.accounts,
+            &self.recovery_authority.pubkey(),
+        );
+        let recovery_authority = self.recovery_authority.insecure_clone();
+        process_instructions(&mut self.context, &[claim], &[&recovery_authority]).await
+    }
+
+    /// Acts as the authority just before the timeout runs out, checks the
+    /// action restarted it, then lets it run out
+    async fn assert_action_keeps_the_authority(&mut self, action: Instruction) {
+        self.advance_clock(MIN_INACTIVITY_TIMEOUT - 60).await;
+        self.process_as_authority(action)
+            .await
+            .expect("authority acted");
+        let clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        assert_eq!(
+            self.pool_state().await.last_authority_action_at,
+            clock.unix_timestamp
+        );
+
+        self.advance_clock(120).await;
+        assert_pool_error(self.claim().await, RewardPoolError::AuthorityNotDormant);
+
+        self.advance_clock(MIN_INACTIVITY_TIMEOUT).await;
+        self.claim().await.expect("dormant authority replaced");
+        assert_eq!(
+            self.pool_state().await.platform_authority,
+            self.recovery_authority.pubkey()
+        );
+    }
+}
+
+#[tokio::test]
+async fn the_switch_fires_after_a_timeout_without_action() {
+    let mut switch = Switch::new().await;
+
+    switch.advance_clock(MIN_INACTIVITY_TIMEOUT - 60).await;
+    assert_pool_error(switch.claim().await, RewardPoolError::AuthorityNotDormant);
+
+    switch.advance_clock(120).await;
+    switch.claim().await.expect("dormant authority replaced");
+    assert_eq!(
+        switch.pool_state().await.platform_authority,
+        switch.recovery_authority.pubkey()
+    );
+}
+
+#[tokio::test]
+async fn instructions_without_the_counters_account_keep_the_authority_active() {
+    let mut switch = Switch::new().await;
+
+    // The V1 account list, ending before the counters account
+    let mut pause = instructions::pause_pool(
+        &switch.pool.accounts,
+        &switch.pool.platform_authority.pubkey(),
+    );
+    pause.accounts.truncate(2);
+    switch.assert_action_keeps_the_authority(pause).await;
+}
+
+#[tokio::test]
+async fn batches_across_pools_keep_the_authority_active() {
+    let mut switch = Switch::new().await;
+
+    let pause_all = instructions::pause_all(
+        &switch.pool.accounts.program_id,
+        &switch.pool.platform_authority.pubkey(),
+        &[switch.pool.accounts.pool],
+    );
+    switch.assert_action_keeps_the_authority(pause_all).await;
+}
@@ -0,0 +1,1680 @@
+//! Table-driven coverage of the program's validation branches.
+//!
+//! Each row takes one instruction from a valid baseline and breaks one
+//! precondition at a time: a signer that does not sign or is someone else,
+//! a program account reassigned to another owner, a paused pool, a value
+//! out of range or accounts aliasing each other. Every case must fail with
+//! exactly the error listed, and the untouched baseline must succeed
+//! afterwards, so each error is down to its corruption alone.
+//!
+//! The harness checks the table itself too: a row must break every signer
+//! and every program-owned account of its baseline, and every instruction
+//! must have a row or be listed in `NOT_COVERED` with the reason.
+
+use std::collections::BTreeSet;
+
+use borsh::schema::{BorshSchemaContainer, Definition};
+use reward_pool::{
+    instruction::v2::RewardPoolInstruction, state::ParameterValues, DailyStats, GuildMember,
+    RewardPoolError, MAX_RECOVERY_WINDOW, MINIMUM_WITHDRAWAL_AMOUNT, MIN_INACTIVITY_TIMEOUT,
+    PLATFORM_FEE_PERCENTAGE,
+};
+use reward_pool_client::{instructions, RewardAttribution};
+use reward_pool_test_utils::{
+    airdrop, fixture_keypair, token, BanksClientError, PoolFixture, ProgramTestContext, TestPool,
+};
+use solana_account::AccountSharedData;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_keypair::Keypair;
+use solana_program::{
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+
+const TREASURY_FUNDS: u64 = 10_000_000;
+const VAULT_FUNDS: u64 = 1_000_000;
+const REWARD: u64 = 100_000;
+
+// Keypair roles of the accounts the harness adds to the fixture's
+const APPROVER: u8 = 100;
+const INTRUDER: u8 = 101;
+const DESTINATION: u8 = 102;
+const NEW_MINT: u8 = 103;
+
+/// Task of the reward every setup past `Setup::Pool` records
+const RECORDED_TASK: &str = "recorded-task";
+/// Task of the reward awaiting approval
+const PENDING_TASK: &str = "pending-task";
+/// Task of the baseline `RecordReward`
+const NEW_TASK: &str = "new-task";
+
+/// Instructions without a row yet, and why
+const NOT_COVERED: &[(&str, &str)] = &[
+    (
+        "ConfigureTreasuryGovernance",
+        "configured once, by the fixture",
+    ),
+    ("SweepDelegatedRewards", "needs delegated reward accounts"),
+    (
+        "ApproveTreasuryWithdrawal",
+        "needs a second approver, covered in governance.rs",
+    ),
+    (
+        "ExecuteTreasuryWithdrawal",
+        "needs an approved proposal, covered in governance.rs",
+    ),
+    ("SetUpgradeAuthority", "needs the upgradeable loader"),
+    ("RemoveUpgradeAuthority", "needs the upgradeable loader"),
+    ("ScheduleAction", "needs the action queue"),
+    ("CancelAction", "needs a scheduled action"),
+    ("ExecuteAction", "needs a ripe scheduled action"),
+    ("RollbackParameter", "needs a parameter history"),
+    ("PauseAll", "spans several pools"),
+    ("SetFeeAll", "spans several pools"),
+    ("ExportState", "freezes the pool, covered in migration.rs"),
+    (
+        "ImportState",
+        "needs an exported pool, covered in migration.rs",
+    ),
+    ("ClosePool", "needs an archived pool past its deadline"),
+    ("SetCharityAccount", "needs a charity token account"),
+    ("DonateReward", "needs a charity account"),
+    ("CloseFarmerAccounts", "needs an emptied farmer"),
+    (
+        "CloseWithdrawalRecords",
+        "needs records a month old, covered in payouts.rs",
+    ),
+    ("SetAutoForward", "needs a forward destination"),
+    ("TransferPending", "needs a transfer policy"),
+    ("CrankAutoClaim", "needs auto-claim and a payout address"),
+    ("ReleaseUnusedBudget", "needs an expired budget"),
+    (
+        "ChallengeReward",
+        "needs a challenge window, covered in challenges.rs",
+    ),
+    (
+        "ResolveChallenge",
+        "needs an open challenge, covered in challenges.rs",
+    ),
+    ("RegisterPlatform", "involves no pool"),
+    ("SetPlatformOperator", "needs a registered platform"),
+    ("InitializePlatformPool", "needs a registered platform"),
+    ("CreateCampaign", "needs a campaign budget"),
+    ("EndCampaign", "needs a campaign"),
+    ("FinalizeCampaign", "needs an ended campaign"),
+    ("ConfigureBuyback", "needs a governance mint"),
+    ("RunBuyback", "needs a DEX program"),
+    ("SetTreasurySwapPolicy", "needs reserve mints"),
+    ("SetFeeDiscounts", "needs a stake program"),
+    (
+        "SetInterestPolicy",
+        "needs a bonus vault, covered in interest.rs",
+    ),
+    ("SetRewardGovernor", "needs a treasury runway"),
+    ("SetSecondaryMint", "needs a secondary mint"),
+    ("SetMilestoneBonuses", "needs milestone pending rewards"),
+    ("SetProtocolLiquidity", "needs an AMM program"),
+    ("ContributeLiquidity", "needs an AMM program"),
+    ("SetFeeRebate", "needs a rebate vault"),
+    ("SetProtocolConfig", "needs the upgradeable loader"),
+    ("CreatePool", "needs a protocol config"),
+    ("ClaimAcrossPools", "spans several pools"),
+    ("RedeemVoucher", "needs an issued voucher"),
+    (
+        "TakePayoutTicket",
+        "needs a paused pool with a payout queue",
+    ),
+    (
+        "ClaimDormantAuthority",
+        "needs an authority inactive for the recovery timeout",
+    ),
+    (
+        "InitiateFarmerRecovery",
+        "needs recovery guardians set by the farmer",
+    ),
+    (
+        "CancelFarmerRecovery",
+        "needs a recovery started by the guardians",
+    ),
+    (
+        "CompleteFarmerRecovery",
+        "needs a recovery past its challenge delay",
+    ),
+    (
+        "CollectWithdrawalFees",
+        "needs a fee vault holding withdrawal fees",
+    ),
+    (
+        "ExportSnapshot",
+        "needs a buffer account owned by the program",
+    ),
+    ("LeaveGuild", "needs a guild with members"),
+    (
+        "DistributeGuildRewards",
+        "needs a guild vault holding rewards",
+    ),
+    ("GetFeeInvoice", "a view of a closed invoice"),
+    ("GetRewardRate", "a view of an optional account"),
+    ("GetFarmerPending", "a view over remaining accounts"),
+    ("GetWithdrawalHistory", "a view of an optional account"),
+];
+
+/// State a row's baseline runs against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Setup {
+    /// A pool with a funded treasury and vault; the farmer has a reward
+    /// account and a destination token account
+    Pool,
+    /// Plus a reward recorded for the farmer
+    Rewarded,
+    /// Plus an approver, and a reward awaiting their approval
+    AwaitingApproval,
+    /// Plus that reward approved
+    Approved,
+    /// Plus the farmer's destination token account whitelisted
+    Whitelisted,
+}
+
+/// One precondition broken
+#[derive(Clone, Copy)]
+enum Corruption {
+    /// The key of account `i` does not sign
+    Unsigned(usize),
+    /// Account `i` is replaced by an unrelated key that signs
+    WrongSigner(usize),
+    /// Account `i` is handed to the system program for the case
+    WrongOwner(usize),
+    /// Account `i` is replaced by an unrelated key that does not sign
+    WrongAccount(usize),
+    /// Account `i` is replaced by account `j` of the same instruction
+    Alias(usize, usize),
+    /// Account `i` is replaced by a copy of it at an unrelated key
+    Copied(usize),
+    /// The pool is paused for the case
+    Paused,
+    /// Another instruction, built with an out-of-range value
+    Value(&'static str, fn(&Env) -> Instruction),
+}
+
+impl Corruption {
+    fn describe(&self) -> String {
+        match self {
+            Self::Unsigned(index) => format!("account {index} unsigned"),
+            Self::WrongSigner(index) => format!("account {index} signed by someone else"),
+            Self::WrongOwner(index) => format!("account {index} owned by the system program"),
+            Self::WrongAccount(index) => format!("account {index} replaced"),
+            Self::Alias(index, with) => format!("account {index} aliasing account {with}"),
+            Self::Copied(index) => format!("account {index} copied to another key"),
+            Self::Paused => "pool paused".to_string(),
+            Self::Value(label, _) => label.to_string(),
+        }
+    }
+}
+
+struct Row {
+    instruction: &'static str,
+    setup: Setup,
+    build: fn(&Env) -> Instruction,
+    /// Program accounts the instruction never reads, so none of their
+    /// corruptions matters
+    unread: &'static [usize],
+    cases: Vec<(Corruption, InstructionError)>,
+}
+
+const fn custom(error: RewardPoolError) -> InstructionError {
+    InstructionError::Custom(error as u32)
+}
+
+use Corruption::*;
+use RewardPoolError::*;
+
+fn rows() -> Vec<Row> {
+    vec![
+        Row {
+            instruction: "InitializePool",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                let accounts = env.new_pool_accounts();
+                instructions::initialize_pool(&accounts, &env.authority(), 10)
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongAccount(1), custom(InvalidPoolAccount)),
+                (WrongAccount(3), custom(InvalidProgramTreasuryAccount)),
+                (WrongAccount(6), custom(InvalidProgramTreasuryAccount)),
+                (
+                    Value("fee above 100%", |env| {
+                        let accounts = env.new_pool_accounts();
+                        instructions::initialize_pool(&accounts, &env.authority(), 101)
+                    }),
+                    custom(InvalidPlatformFee),
+                ),
+                (
+                    Value("pool already initialized", |env| {
+                        instructions::initialize_pool(&env.pool.accounts, &env.authority(), 10)
+                    }),
+                    custom(PoolAlreadyInitialized),
+                ),
+            ],
+        },
+        Row {
+            instruction: "RecordReward",
+            setup: Setup::Rewarded,
+            unread: &[],
+            build: |env| env.record_reward(REWARD),
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (Copied(1), custom(InvalidPoolAccount)),
+                (WrongAccount(2), custom(InvalidPlatformTreasury)),
+                (WrongOwner(7), InstructionError::IncorrectProgramId),
+                (WrongOwner(9), InstructionError::IncorrectProgramId),
+                (WrongOwner(10), InstructionError::IncorrectProgramId),
+                (WrongAccount(7), custom(InvalidStatsAccount)),
+                (WrongAccount(10), custom(InvalidFarmerStateAccount)),
+                (WrongOwner(27), custom(InvalidRewardRateAccount)),
+                (WrongAccount(12), custom(InvalidPoolAttestorAccount)),
+                (WrongAccount(27), custom(InvalidRewardRateAccount)),
+                (WrongOwner(28), InstructionError::IncorrectProgramId),
+                (WrongAccount(28), custom(InvalidGovernanceAccount)),
+                (Alias(10, 7), custom(InvalidFarmerStateAccount)),
+                (Paused, custom(PoolPaused)),
+                (
+                    Value("receipt of another idempotency key", |env| {
+                        let mut instruction = instructions::record_reward(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            &env.farmer.pubkey(),
+                            REWARD,
+                            NEW_TASK.to_string(),
+                            RewardAttribution {
+                                idempotency_key: Some([7; 32]),
+                                ..RewardAttribution::default()
+                            },
+                            env.stats_day(),
+                        );
+                        instruction.accounts[29].pubkey =
+                            env.pool.accounts.reward_receipt(&[8; 32]);
+                        instruction
+                    }),
+                    custom(InvalidRewardReceiptAccount),
+                ),
+                (
+                    Value("idempotency key without the receipt account", |env| {
+                        let mut instruction = instructions::record_reward(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            &env.farmer.pubkey(),
+                            REWARD,
+                            NEW_TASK.to_string(),
+                            RewardAttribution {
+                                idempotency_key: Some([7; 32]),
+                                ..RewardAttribution::default()
+                            },
+                            env.stats_day(),
+                        );
+                        instruction.accounts.truncate(29);
+                        instruction
+                    }),
+                    InstructionError::NotEnoughAccountKeys,
+                ),
+            ],
+        },
+        Row {
+            instruction: "WithdrawReward",
+            setup: Setup::Rewarded,
+            unread: &[],
+            build: |env| env.withdraw_reward(farmer_amount(REWARD)),
+            cases: vec![
+                (Unsigned(0), InstructionError::MissingRequiredSignature),
+                (WrongSigner(0), custom(InvalidFarmerStateAccount)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(7), InstructionError::IncorrectProgramId),
+                (WrongOwner(9), InstructionError::IncorrectProgramId),
+                (WrongOwner(13), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), InstructionError::InvalidAccountData),
+                (WrongAccount(16), custom(InvalidWithdrawalFeeAccount)),
+                (WrongAccount(19), custom(InvalidPayoutQueueAccount)),
+                (WrongAccount(21), custom(InvalidWithdrawalRampAccount)),
+                (WrongAccount(22), custom(InvalidInterestPolicyAccount)),
+                (WrongOwner(24), custom(InvalidPendingRewardAccount)),
+                (WrongAccount(24), custom(InvalidPendingRewardAccount)),
+                (WrongAccount(25), custom(InvalidWithdrawalRecordAccount)),
+                (Alias(3, 1), InstructionError::InvalidAccountData),
+                (Paused, custom(PoolPaused)),
+                (
+                    Value("amount below the minimum", |env| {
+                        env.withdraw_reward(MINIMUM_WITHDRAWAL_AMOUNT - 1)
+                    }),
+                    custom(InsufficientAmount),
+                ),
+                (
+                    Value("amount other than the pending reward's", |env| {
+                        env.withdraw_reward(REWARD)
+                    }),
+                    custom(WithdrawalAmountMismatch),
+                ),
+            ],
+        },
+        Row {
+            instruction: "UpdatePlatformFee",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| instructions::update_platform_fee(&env.pool.accounts, &env.authority(), 5),
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(2), InstructionError::IncorrectProgramId),
+                (WrongAccount(4), custom(InvalidParametersAccount)),
+                (
+                    Value("fee above the maximum", |env| {
+                        instructions::update_platform_fee(&env.pool.accounts, &env.authority(), 101)
+                    }),
+                    custom(InvalidPlatformFee),
+                ),
+            ],
+        },
+        Row {
+            instruction: "PausePool",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| instructions::pause_pool(&env.pool.accounts, &env.authority()),
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (Copied(1), custom(InvalidPoolAccount)),
+                (WrongOwner(2), InstructionError::IncorrectProgramId),
+            ],
+        },
+        Row {
+            instruction: "ResumePool",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| instructions::resume_pool(&env.pool.accounts, &env.authority()),
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(2), InstructionError::IncorrectProgramId),
+            ],
+        },
+        Row {
+            instruction: "ResumeWithRamp",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::resume_with_ramp(&env.pool.accounts, &env.authority(), 1_000, 500, 4)
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(2), InstructionError::IncorrectProgramId),
+                (WrongAccount(4), custom(InvalidWithdrawalRampAccount)),
+                (
+                    Value("zero first epoch cap", |env| {
+                        instructions::resume_with_ramp(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            0,
+                            500,
+                            4,
+                        )
+                    }),
+                    custom(InvalidWithdrawalRamp),
+                ),
+            ],
+        },
+        Row {
+            instruction: "GetPoolStats",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| instructions::get_pool_stats(&env.pool.accounts),
+            cases: vec![
+                (WrongOwner(0), InstructionError::IncorrectProgramId),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongAccount(1), custom(InvalidCountersAccount)),
+            ],
+        },
+        Row {
+            instruction: "AddWithdrawalDestination",
+            setup: Setup::Rewarded,
+            unread: &[1],
+            build: |env| {
+                instructions::add_withdrawal_destination(
+                    &env.pool.accounts,
+                    &env.farmer.pubkey(),
+                    &env.destination,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), InstructionError::MissingRequiredSignature),
+                (WrongSigner(0), custom(InvalidWhitelistAccount)),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidWhitelistAccount)),
+                (Alias(2, 1), custom(InvalidWhitelistAccount)),
+            ],
+        },
+        Row {
+            instruction: "RemoveWithdrawalDestination",
+            setup: Setup::Whitelisted,
+            unread: &[1],
+            build: |env| {
+                instructions::remove_withdrawal_destination(
+                    &env.pool.accounts,
+                    &env.farmer.pubkey(),
+                    &env.destination,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), InstructionError::MissingRequiredSignature),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongOwner(2), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidWhitelistAccount)),
+            ],
+        },
+        Row {
+            instruction: "ProposeTreasuryWithdrawal",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::propose_treasury_withdrawal(
+                    &env.pool.accounts,
+                    &env.authority(),
+                    0,
+                    &env.destination,
+                    VAULT_FUNDS,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), InstructionError::MissingRequiredSignature),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(2), InstructionError::IncorrectProgramId),
+                (WrongOwner(6), InstructionError::IncorrectProgramId),
+                (WrongAccount(3), custom(InvalidProposalAccount)),
+            ],
+        },
+        Row {
+            instruction: "UpdateParameters",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::update_parameters(
+                    &env.pool.accounts,
+                    &env.authority(),
+                    ParameterValues::default(),
+                    &[env.authority()],
+                )
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(2), InstructionError::IncorrectProgramId),
+                (WrongOwner(5), InstructionError::IncorrectProgramId),
+                (WrongAccount(3), custom(InvalidParametersAccount)),
+                (
+                    Value("no approvals", |env| {
+                        instructions::update_parameters(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            ParameterValues::default(),
+                            &[],
+                        )
+                    }),
+                    custom(InsufficientApprovals),
+                ),
+                (
+                    Value("fee cap above 100%", |env| {
+                        instructions::update_parameters(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            ParameterValues {
+                                max_platform_fee_percentage: 101,
+                                ..ParameterValues::default()
+                            },
+                            &[env.authority()],
+                        )
+                    }),
+                    custom(InvalidParameterValues),
+                ),
+            ],
+        },
+        Row {
+            instruction: "SetGuardians",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::set_guardians(
+                    &env.pool.accounts,
+                    &env.authority(),
+                    &[env.approver.pubkey()],
+                )
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidGuardiansAccount)),
+                (
+                    Value("more guardians than slots", |env| {
+                        instructions::set_guardians(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            &[Pubkey::new_unique(); 4],
+                        )
+                    }),
+                    custom(InvalidGuardianConfig),
+                ),
+            ],
+        },
+        Row {
+            instruction: "SetPoolMetadata",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::set_pool_metadata(
+                    &env.pool.accounts,
+                    &env.authority(),
+                    "Pool",
+                    "https://example.com/pool.json",
+                )
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidMetadataAccount)),
+                (
+                    Value("name too long", |env| {
+                        instructions::set_pool_metadata(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            &"n".repeat(256),
+                            "",
+                        )
+                    }),
+                    custom(MetadataTooLong),
+                ),
+            ],
+        },
+        Row {
+            instruction: "ArchivePool",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::archive_pool(
+                    &env.pool.accounts,
+                    &env.authority(),
+                    env.now + reward_pool::SECONDS_PER_DAY,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidArchiveAccount)),
+                (Paused, custom(PoolPaused)),
+                (
+                    Value("deadline in the past", |env| {
+                        instructions::archive_pool(&env.pool.accounts, &env.authority(), env.now)
+                    }),
+                    custom(InvalidClaimDeadline),
+                ),
+            ],
+        },
+        Row {
+            instruction: "ClaimMany",
+            setup: Setup::Approved,
+            unread: &[],
+            build: |env| env.claim_many(&[env.pending_reward()]),
+            cases: vec![
+                (Unsigned(0), InstructionError::MissingRequiredSignature),
+                (WrongSigner(0), custom(InvalidFarmerStateAccount)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(7), InstructionError::IncorrectProgramId),
+                (WrongOwner(25), custom(InvalidPendingRewardAccount)),
+                (WrongAccount(2), custom(InvalidVaultAccount)),
+                (WrongAccount(6), custom(InvalidClaimShardAccount)),
+                (WrongAccount(18), custom(InvalidPayoutAddressAccount)),
+                (WrongAccount(19), custom(InvalidWhitelistAccount)),
+                (Alias(3, 2), custom(DestinationNotOwnedByFarmer)),
+                (Paused, custom(PoolPaused)),
+                (
+                    Value("no pending rewards", |env| env.claim_many(&[])),
+                    custom(NoPendingRewards),
+                ),
+            ],
+        },
+        Row {
+            instruction: "RegisterPayoutAddress",
+            setup: Setup::Pool,
+            unread: &[1],
+            build: |env| {
+                instructions::register_payout_address(
+                    &env.pool.accounts,
+                    &env.farmer.pubkey(),
+                    &env.farmer.pubkey(),
+                )
+            },
+            cases: vec![
+                (Unsigned(0), InstructionError::MissingRequiredSignature),
+                (WrongSigner(0), custom(InvalidPayoutAddressAccount)),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidPayoutAddressAccount)),
+            ],
+        },
+        Row {
+            instruction: "SetPendingTransferPolicy",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::set_pending_transfer_policy(
+                    &env.pool.accounts,
+                    &env.authority(),
+                    true,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidPendingTransferPolicyAccount)),
+            ],
+        },
+        Row {
+            instruction: "SetAutoClaim",
+            setup: Setup::Rewarded,
+            unread: &[],
+            build: |env| instructions::set_auto_claim(&env.pool.accounts, &env.farmer.pubkey(), 0),
+            cases: vec![
+                (Unsigned(0), InstructionError::MissingRequiredSignature),
+                (WrongSigner(0), custom(InvalidFarmerStateAccount)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(2), InstructionError::IncorrectProgramId),
+                (WrongOwner(5), InstructionError::IncorrectProgramId),
+                (
+                    Value("threshold without a payout address", |env| {
+                        instructions::set_auto_claim(
+                            &env.pool.accounts,
+                            &env.farmer.pubkey(),
+                            REWARD,
+                        )
+                    }),
+                    custom(AutoClaimRequiresPayoutAddress),
+                ),
+            ],
+        },
+        Row {
+            instruction: "SetVerifier",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::set_verifier(
+                    &env.pool.accounts,
+                    &env.authority(),
+                    Some(&env.approver.pubkey()),
+                    None,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidPoolVerifierAccount)),
+            ],
+        },
+        Row {
+            instruction: "SetAttestor",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::set_attestor(
+                    &env.pool.accounts,
+                    &env.authority(),
+                    Some(&mock_attestor_id()),
+                    0,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidPoolAttestorAccount)),
+                (
+                    Value("program as its own attestor", |env| {
+                        instructions::set_attestor(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            Some(&reward_pool::id()),
+                            0,
+                        )
+                    }),
+                    custom(InvalidAttestorProgram),
+                ),
+            ],
+        },
+        Row {
+            instruction: "LockTaskBudget",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::lock_task_budget(
+                    &env.pool.accounts,
+                    &env.authority(),
+                    NEW_TASK,
+                    REWARD,
+                    env.now + reward_pool::SECONDS_PER_DAY,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(8), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidVaultAccount)),
+                (WrongAccount(4), custom(InvalidTaskBudgetAccount)),
+                (Paused, custom(PoolPaused)),
+                (
+                    Value("expiry in the past", |env| {
+                        instructions::lock_task_budget(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            NEW_TASK,
+                            REWARD,
+                            env.now,
+                        )
+                    }),
+                    custom(InvalidTaskBudget),
+                ),
+            ],
+        },
+        Row {
+            instruction: "IssueVoucher",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::issue_voucher(
+                    &env.pool.accounts,
+                    &env.authority(),
+                    &env.farmer.pubkey(),
+                    1,
+                    REWARD,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(12), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidVaultAccount)),
+                (WrongAccount(3), custom(InvalidVoucherEscrowAccount)),
+                (WrongAccount(4), custom(InvalidVoucherAccount)),
+                (WrongAccount(5), custom(InvalidVoucherAccount)),
+                (WrongAccount(8), custom(InvalidVoucherEscrowAccount)),
+                (Paused, custom(PoolPaused)),
+                (
+                    Value("zero amount", |env| {
+                        instructions::issue_voucher(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            &env.farmer.pubkey(),
+                            1,
+                            0,
+                        )
+                    }),
+                    custom(InvalidVoucherAmount),
+                ),
+            ],
+        },
+        Row {
+            instruction: "SetPayoutQueue",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::set_payout_queue(&env.pool.accounts, &env.authority(), 3_600)
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidPayoutQueueAccount)),
+                (
+                    Value("window above the cap", |env| {
+                        instructions::set_payout_queue(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            MAX_RECOVERY_WINDOW + 1,
+                        )
+                    }),
+                    custom(InvalidRecoveryWindow),
+                ),
+            ],
+        },
+        Row {
+            instruction: "SetRecoveryAuthority",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::set_recovery_authority(
+                    &env.pool.accounts,
+                    &env.authority(),
+                    &env.approver.pubkey(),
+                    MIN_INACTIVITY_TIMEOUT,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidAuthorityRecoveryAccount)),
+                (
+                    Value("timeout below the minimum", |env| {
+                        instructions::set_recovery_authority(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            &env.approver.pubkey(),
+                            MIN_INACTIVITY_TIMEOUT - 1,
+                        )
+                    }),
+                    custom(InvalidInactivityTimeout),
+                ),
+            ],
+        },
+        Row {
+            instruction: "SetRecoveryGuardians",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::set_recovery_guardians(
+                    &env.pool.accounts,
+                    &env.farmer.pubkey(),
+                    &[env.approver.pubkey()],
+                    1,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), InstructionError::MissingRequiredSignature),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidFarmerRecoveryAccount)),
+                (
+                    Value("threshold above the guardian count", |env| {
+                        instructions::set_recovery_guardians(
+                            &env.pool.accounts,
+                            &env.farmer.pubkey(),
+                            &[env.approver.pubkey()],
+                            2,
+                        )
+                    }),
+                    custom(InvalidRecoveryGuardians),
+                ),
+                (
+                    Value("farmer among the guardians", |env| {
+                        instructions::set_recovery_guardians(
+                            &env.pool.accounts,
+                            &env.farmer.pubkey(),
+                            &[env.farmer.pubkey()],
+                            1,
+                        )
+                    }),
+                    custom(InvalidRecoveryGuardians),
+                ),
+            ],
+        },
+        Row {
+            instruction: "SetWithdrawalCosigner",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::set_withdrawal_cosigner(
+                    &env.pool.accounts,
+                    &env.farmer.pubkey(),
+                    &env.approver.pubkey(),
+                    Some(&env.approver.pubkey()),
+                )
+            },
+            cases: vec![
+                (Unsigned(0), InstructionError::MissingRequiredSignature),
+                (Unsigned(3), custom(InvalidWithdrawalCosigner)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(5), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidFarmerStateAccount)),
+                (
+                    Value("farmer as their own cosigner", |env| {
+                        instructions::set_withdrawal_cosigner(
+                            &env.pool.accounts,
+                            &env.farmer.pubkey(),
+                            &env.farmer.pubkey(),
+                            None,
+                        )
+                    }),
+                    custom(InvalidWithdrawalCosigner),
+                ),
+                (
+                    Value("removing an unset cosigner", |env| {
+                        instructions::set_withdrawal_cosigner(
+                            &env.pool.accounts,
+                            &env.farmer.pubkey(),
+                            &Pubkey::default(),
+                            None,
+                        )
+                    }),
+                    custom(NoWithdrawalCosigner),
+                ),
+            ],
+        },
+        Row {
+            instruction: "SetGuildSplit",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::set_guild_split(
+                    &env.pool.accounts,
+                    &env.farmer.pubkey(),
+                    Some(&env.destination),
+                    2_500,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), InstructionError::MissingRequiredSignature),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(5), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidFarmerStateAccount)),
+                (WrongAccount(3), custom(InvalidGuildVault)),
+                (WrongAccount(6), custom(InvalidPayoutAddressAccount)),
+                (WrongAccount(7), custom(InvalidWhitelistAccount)),
+                (
+                    Value("share above 100%", |env| {
+                        instructions::set_guild_split(
+                            &env.pool.accounts,
+                            &env.farmer.pubkey(),
+                            Some(&env.destination),
+                            10_001,
+                        )
+                    }),
+                    custom(InvalidGuildShare),
+                ),
+            ],
+        },
+        Row {
+            instruction: "SetGuildMembers",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| env.set_guild_members(&[(env.farmer.pubkey(), 10_000)]),
+            cases: vec![
+                (Unsigned(0), InstructionError::MissingRequiredSignature),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(7), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidGuildAccount)),
+                (WrongAccount(3), custom(InvalidGuildVault)),
+                (
+                    Value("shares above 100%", |env| {
+                        env.set_guild_members(&[
+                            (env.farmer.pubkey(), 6_000),
+                            (env.approver.pubkey(), 5_000),
+                        ])
+                    }),
+                    custom(InvalidGuildMembers),
+                ),
+                (
+                    Value("a member twice", |env| {
+                        env.set_guild_members(&[
+                            (env.farmer.pubkey(), 5_000),
+                            (env.farmer.pubkey(), 5_000),
+                        ])
+                    }),
+                    custom(InvalidGuildMembers),
+                ),
+            ],
+        },
+        Row {
+            instruction: "CloseFeeInvoice",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::close_fee_invoice(&env.pool.accounts, &env.farmer.pubkey(), 0)
+            },
+            cases: vec![
+                (Unsigned(0), InstructionError::MissingRequiredSignature),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(10), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidFeeLedgerAccount)),
+                (WrongAccount(3), custom(InvalidFeeInvoiceAccount)),
+                (WrongAccount(4), custom(InvalidWithdrawalFeeAccount)),
+                (WrongAccount(8), custom(InvalidFactoryRecordAccount)),
+                (
+                    Value("invoice number ahead of the ledger", |env| {
+                        instructions::close_fee_invoice(&env.pool.accounts, &env.farmer.pubkey(), 1)
+                    }),
+                    custom(InvalidFeeInvoiceAccount),
+                ),
+            ],
+        },
+        Row {
+            instruction: "ConsolidateClaimShards",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::consolidate_claim_shards(&env.pool.accounts, &env.farmer.pubkey())
+            },
+            cases: vec![
+                (Unsigned(0), InstructionError::MissingRequiredSignature),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidClaimLedgerAccount)),
+                (WrongAccount(4), custom(InvalidCountersAccount)),
+                (
+                    Value("more accounts than claim shards", |env| {
+                        let mut instruction = instructions::consolidate_claim_shards(
+                            &env.pool.accounts,
+                            &env.farmer.pubkey(),
+                        );
+                        instruction.accounts.push(instruction.accounts[5].clone());
+                        instruction
+                    }),
+                    custom(InvalidClaimShardAccount),
+                ),
+            ],
+        },
+        Row {
+            instruction: "FundTreasury",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::fund_treasury(
+                    &env.pool.accounts,
+                    &env.farmer.pubkey(),
+                    &env.destination,
+                    1,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), InstructionError::MissingRequiredSignature),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(7), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidProgramTreasuryAccount)),
+                (WrongAccount(4), custom(InvalidProgramTreasuryAccount)),
+                (WrongAccount(7), custom(InvalidCountersAccount)),
+            ],
+        },
+        Row {
+            instruction: "SetRewardApprover",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::set_reward_approver(
+                    &env.pool.accounts,
+                    &env.authority(),
+                    Some(&env.approver.pubkey()),
+                )
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidRewardApprovalAccount)),
+                (
+                    Value("recorder as approver", |env| {
+                        instructions::set_reward_approver(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            Some(&env.authority()),
+                        )
+                    }),
+                    custom(ApproverIsRecorder),
+                ),
+            ],
+        },
+        Row {
+            instruction: "ApproveReward",
+            setup: Setup::AwaitingApproval,
+            unread: &[],
+            build: |env| {
+                instructions::approve_reward(
+                    &env.pool.accounts,
+                    &env.approver.pubkey(),
+                    &env.farmer.pubkey(),
+                    PENDING_TASK,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(2), custom(InvalidRewardApprovalAccount)),
+                (WrongOwner(3), custom(InvalidPendingRewardAccount)),
+                (WrongOwner(5), InstructionError::IncorrectProgramId),
+                (
+                    Value("reward not awaiting approval", |env| {
+                        instructions::approve_reward(
+                            &env.pool.accounts,
+                            &env.approver.pubkey(),
+                            &env.farmer.pubkey(),
+                            RECORDED_TASK,
+                        )
+                    }),
+                    custom(RewardNotAwaitingApproval),
+                ),
+            ],
+        },
+        Row {
+            instruction: "SetTaskTypeFee",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::set_task_type_fee(&env.pool.accounts, &env.authority(), 7, Some(5))
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(5), InstructionError::IncorrectProgramId),
+                (WrongAccount(3), custom(InvalidFeeOverridesAccount)),
+                (
+                    Value("fee above the maximum", |env| {
+                        instructions::set_task_type_fee(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            7,
+                            Some(101),
+                        )
+                    }),
+                    custom(InvalidPlatformFee),
+                ),
+                (
+                    Value("removing a missing override", |env| {
+                        instructions::set_task_type_fee(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            7,
+                            None,
+                        )
+                    }),
+                    custom(FeeOverrideNotFound),
+                ),
+            ],
+        },
+        Row {
+            instruction: "SetWithdrawBurn",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| instructions::set_withdraw_burn(&env.pool.accounts, &env.authority(), 100),
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(4), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidWithdrawBurnAccount)),
+                (
+                    Value("burn of the whole withdrawal", |env| {
+                        instructions::set_withdraw_burn(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            reward_pool::BASIS_POINTS,
+                        )
+                    }),
+                    custom(InvalidWithdrawBurn),
+                ),
+            ],
+        },
+        Row {
+            instruction: "SetWithdrawalFee",
+            setup: Setup::Pool,
+            unread: &[],
+            build: |env| {
+                instructions::set_withdrawal_fee(
+                    &env.pool.accounts,
+                    &env.authority(),
+                    5_000,
+                    true,
+                    MINIMUM_WITHDRAWAL_AMOUNT,
+                )
+            },
+            cases: vec![
+                (Unsigned(0), custom(InvalidAuthority)),
+                (WrongSigner(0), custom(InvalidAuthority)),
+                (WrongOwner(1), InstructionError::IncorrectProgramId),
+                (WrongOwner(5), InstructionError::IncorrectProgramId),
+                (WrongAccount(2), custom(InvalidWithdrawalFeeAccount)),
+                (WrongAccount(3), custom(InvalidFeeVaultAccount)),
+                (
+                    Value("fee above the maximum", |env| {
+                        instructions::set_withdrawal_fee(
+                            &env.pool.accounts,
+                            &env.authority(),
+                            reward_pool::MAX_WITHDRAWAL_FEE_LAMPORTS + 1,
+                            false,
+                            0,
+                        )
+                    }),
+                    custom(InvalidWithdrawalFee),
+                ),
+            ],
+        },
+    ]
+}
+
+/// The farmer's share of a reward of `amount`, after the platform fee
+fn farmer_amount(amount: u64) -> u64 {
+    amount - amount * PLATFORM_FEE_PERCENTAGE as u64 / 100
+}
+
+fn mock_attestor_id() -> Pubkey {
+    mock_attestor::id()
+}
+
+/// A bank set up for one row, with the keys its instructions may need
+struct Env {
+    context: ProgramTestContext,
+    pool: TestPool,
+    farmer: Keypair,
+    approver: Keypair,
+    intruder: Keypair,
+    /// Reward mint no pool was initialized for
+    new_mint: Pubkey,
+    /// Token account of the farmer's, other than their reward account
+    destination: Pubkey,
+    now: i64,
+    /// Makes every transaction unique, so no case is mistaken for one
+    /// already processed
+    nonce: u64,
+}
+
+impl Env {
+    async fn new(setup: Setup) -> Self {
+        let (mut context, pool) = PoolFixture::new()
+            .with_funded_treasury(TREASURY_FUNDS)
+            .with_funded_vault(VAULT_FUNDS)
+            .start()
+            .await
+            .expect("fixture bootstraps");
+
+        let farmer = pool.farmer(0);
+        let approver = fixture_keypair(0, APPROVER, 0);
+        let intruder = fixture_keypair(0, INTRUDER, 0);
+        for key in [farmer.pubkey(), approver.pubkey(), intruder.pubkey()] {
+            airdrop(&mut context, &key, LAMPORTS_PER_SOL)
+                .await
+                .expect("funded");
+        }
+        pool.create_reward_account(&mut context, &farmer.pubkey())
+            .await
+            .expect("reward account created");
+        let destination = fixture_keypair(0, DESTINATION, 0);
+        token::create_token_account(
+            &mut context,
+            &destination,
+            &pool.accounts.reward_mint,
+            &farmer.pubkey(),
+        )
+        .await
+        .expect("destination created");
+        // Funds FundTreasury's baseline
+        pool.mint_to(&mut context, &destination.pubkey(), 1)
+            .await
+            .expect("destination funded");
+        // InitializePool's baseline creates the new mint's vault
+        let new_mint = fixture_keypair(0, NEW_MINT, 0);
+        token::create_mint(&mut context, &new_mint, &pool.mint_authority.pubkey(), 6)
+            .await
+            .expect("new mint created");
+        let clock: Clock = context.banks_client.get_sysvar().await.expect("clock");
+
+        let mut env = Self {
+            context,
+            pool,
+            farmer,
+            approver,
+            intruder,
+            new_mint: new_mint.pubkey(),
+            destination: destination.pubkey(),
+            now: clock.unix_timestamp,
+            nonce: 0,
+        };
+        if setup != Setup::Pool {
+            let record = env.record_reward_for(RECORDED_TASK, REWARD);
+            env.expect_success(record, "setup reward").await;
+        }
+        if matches!(setup, Setup::AwaitingApproval | Setup::Approved) {
+            let set_approver = instructions::set_reward_approver(
+                &env.pool.accounts,
+                &env.authority(),
+                Some(&env.approver.pubkey()),
+            );
+            env.expect_success(set_approver, "setup approver").await;
+            let record = env.record_reward_for(PENDING_TASK, REWARD);
+            env.expect_success(record, "setup pending reward").await;
+        }
+        if setup == Setup::Whitelisted {
+            let whitelist = instructions::add_withdrawal_destination(
+                &env.pool.accounts,
+                &env.farmer.pubkey(),
+                &env.destination,
+            );
+            env.expect_success(whitelist, "setup whitelist").await;
+        }
+        if setup == Setup::Approved {
+            let approve = instructions::approve_reward(
+                &env.pool.accounts,
+                &env.approver.pubkey(),
+                &env.farmer.pubkey(),
+                PENDING_TASK,
+            );
+            env.expect_success(approve, "setup approval").await;
+        }
+        env
+    }
+
+    fn authority(&self) -> Pubkey {
+        self.pool.platform_authority.pubkey()
+    }
+
+    fn new_pool_accounts(&self) -> reward_pool_client::PoolAccounts {
+        reward_pool_client::PoolAccounts::for_mint(self.new_mint)
+    }
+
+    fn stats_day(&self) -> i64 {
+        DailyStats::day_of(self.now)
+    }
+
+    fn record_reward_for(&self, task_id: &str, amount: u64) -> Instruction {
+        instructions::record_reward(
+            &self.pool.accounts,
+            &self.authority(),
+            &self.farmer.pubkey(),
+            amount,
+            task_id.to_string(),
+            RewardAttribution::default(),
+            self.stats_day(),
+        )
+    }
+
+    fn record_reward(&self, amount: u64) -> Instruction {
+        self.record_reward_for(NEW_TASK, amount)
+    }
+
+    fn withdraw_reward(&self, amount: u64) -> Instruction {
+        instructions::withdraw_reward(
+            &self.pool.accounts,
+            &self.farmer.pubkey(),
+            RECORDED_TASK,
+            &self.destination,
+            amount,
+            1,
+            self.stats_day(),
+        )
+    }
+
+    fn pending_reward(&self) -> Pubkey {
+        self.pool
+            .accounts
+            .pending_reward(&self.farmer.pubkey(), PENDING_TASK)
+    }
+
+    fn set_guild_members(&self, members: &[(Pubkey, u16)]) -> Instruction {
+        let members: Vec<GuildMember> = members
+            .iter()
+            .map(|&(member, share_bps)| GuildMember { member, share_bps })
+            .collect();
+        instructions::set_guild_members(&self.pool.accounts, &self.farmer.pubkey(), &members)
+    }
+
+    fn claim_many(&self, pending_rewards: &[Pubkey]) -> Instruction {
+        instructions::claim_many(&self.pool.accounts, &self.farmer.pubkey(), pending_rewards)
+    }
+
+    /// Keypair of `key` among those the harness controls
+    fn keypair(&self, key: &Pubkey) -> Option<&Keypair> {
+        [
+            &self.pool.platform_authority,
+            &self.farmer,
+            &self.approver,
+            &self.intruder,
+        ]
+        .into_iter()
+        .find(|keypair| keypair.pubkey() == *key)
+    }
+
+    /// Processes `instruction`, signed by every key it marks as a signer
+    async fn process(&mut self, instruction: Instruction) -> Result<(), BanksClientError> {
+        self.nonce += 1;
+        let mut signers = vec![&self.context.payer];
+        for meta in instruction.accounts.iter().filter(|meta| meta.is_signer) {
+            let keypair = self
+                .keypair(&meta.pubkey)
+                .unwrap_or_else(|| panic!("no keypair for signer {}", meta.pubkey));
+            if !signers.iter().any(|signer| signer.pubkey() == meta.pubkey) {
+                signers.push(keypair);
+            }
+        }
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_price(self.nonce),
+                instruction,
+            ],
+            Some(&self.context.payer.pubkey()),
+            &signers,
+            blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    async fn expect_success(&mut self, instruction: Instruction, label: &str) {
+        if let Err(error) = self.process(instruction).await {
+            panic!("{label} failed: {error}");
+        }
+    }
+
+    async fn set_paused(&mut self, paused: bool) {
+        let instruction = if paused {
+            instructions::pause_pool(&self.pool.accounts, &self.authority())
+        } else {
+            instructions::resume_pool(&self.pool.accounts, &self.authority())
+        };
+        self.expect_success(instruction, "pause toggle").await;
+    }
+
+    /// Runs `baseline` broken by `corruption`, returning the error it failed
+    /// with, or `None` if it succeeded
+    async fn run_case(
+        &mut self,
+        baseline: &Instruction,
+        corruption: Corruption,
+    ) -> Option<InstructionError> {
+        let mut instruction = baseline.clone();
+        let mut restore = None;
+        match corruption {
+            Unsigned(index) => {
+                // A key signs if any of its metas does
+                let key = instruction.accounts[index].pubkey;
+                for meta in instruction.accounts.iter_mut() {
+                    if meta.pubkey == key {
+                        meta.is_signer = false;
+                    }
+                }
+            }
+            WrongSigner(index) => {
+                instruction.accounts[index].pubkey = self.intruder.pubkey();
+                instruction.accounts[index].is_signer = true;
+            }
+            WrongOwner(index) => {
+                let key = instruction.accounts[index].pubkey;
+                let account = self
+                    .context
+                    .banks_client
+                    .get_account(key)
+                    .await
+                    .expect("account fetched")
+                    .expect("account exists");
+                let mut reassigned = account.clone();
+                reassigned.owner = solana_system_interface::program::id();
+                self.context
+                    .set_account(&key, &AccountSharedData::from(reassigned));
+                restore = Some((key, account));
+            }
+            WrongAccount(index) => instruction.accounts[index].pubkey = Pubkey::new_unique(),
+            Alias(index, with) => {
+                instruction.accounts[index].pubkey = instruction.accounts[with].pubkey
+            }
+            Copied(index) => {
+                let account = self
+                    .context
+                    .banks_client
+                    .get_account(instruction.accounts[index].pubkey)
+                    .await
+                    .expect("account fetched")
+                    .expect("account exists");
+                let copy = Pubkey::new_unique();
+                self.context
+                    .set_account(&copy, &AccountSharedData::from(account));
+                instruction.accounts[index].pubkey = copy;
+            }
+            Paused => self.set_paused(true).await,
+            Value(_, build) => instruction = build(self),
+        }
+
+        let result = self.process(instruction).await;
+
+        if let Some((key, account)) = restore {
+            self.context
+                .set_account(&key, &AccountSharedData::from(account));
+        }
+        if let Paused = corruption {
+            self.set_paused(false).await;
+        }
+
+        match result {
+            Ok(()) => None,
+            Err(error) => Some(match error.unwrap() {
+                TransactionError::InstructionError(_, error) => error,
+                error => panic!("transaction-level error {error}"),
+            }),
+        }
+    }
+}
+
+/// Preconditions of `baseline` every row must break: its signers and the
+/// accounts the program owns, each at its first position
+async fn required_cases(env: &mut Env, row: &Row, baseline: &Instruction) -> Vec<String> {
+    let mut required = Vec::new();
+    let payer = env.context.payer.pubkey();
+    for (index, meta) in baseline.accounts.iter().enumerate() {
+        let repeated = baseline.accounts[..index]
+            .iter()
+            .any(|earlier| earlier.pubkey == meta.pubkey);
+        if repeated || row.unread.contains(&index) {
+            continue;
+        }
+        if meta.pubkey != payer
+            && baseline
+                .accounts
+                .iter()
+                .any(|other| other.pubkey == meta.pubkey && other.is_signer)
+        {
+            required.push(Unsigned(index).describe());
+        }
+        let account = env
+            .context
+            .banks_client
+            .get_account(meta.pubkey)
+            .await
+            .expect("account fetched");
+        if account.is_some_and(|account| account.owner == reward_pool::id()) {
+            required.push(WrongOwner(index).describe());
+        }
+    }
+    required
+}
+
+fn instruction_names() -> BTreeSet<String> {
+    let schema = BorshSchemaContainer::for_type::<RewardPoolInstruction>();
+    match schema.get_definition(schema.declaration()) {
+        Some(Definition::Enum { variants, .. }) => {
+            variants.iter().map(|(_, name, _)| name.clone()).collect()
+        }
+        _ => panic!("instructions are not an enum"),
+    }
+}
+
+#[test]
+fn every_instruction_has_a_row_or_a_reason() {
+    let covered: BTreeSet<String> = rows()
+        .iter()
+        .map(|row| row.instruction.to_string())
+        .chain(NOT_COVERED.iter().map(|(name, _)| name.to_string()))
+        .collect();
+    let names = instruction_names();
+    let missing: Vec<_> = names.difference(&covered).collect();
+    let unknown: Vec<_> = covered.difference(&names).collect();
+    assert!(
+        missing.is_empty() && unknown.is_empty(),
+        "instructions without a row: {missing:?}; rows of unknown instructions: {unknown:?}"
+    );
+}
+
+#[tokio::test]
+async fn every_corruption_fails_with_its_error() {
+    let mut failures = Vec::new();
+    for row in rows() {
+        let mut env = Env::new(row.setup).await;
+        let baseline = (row.build)(&env);
+
+        let listed: BTreeSet<String> = row
+            .cases
+            .iter()
+            .map(|(corruption, _)| corruption.describe())
+            .collect();
+        for required in required_cases(&mut env, &row, &baseline).await {
+            if !listed.contains(&required) {
+                failures.push(format!("{}: no case for {required}", row.instruction));
+            }
+        }
+
+        for (corruption, expected) in &row.cases {
+            let actual = env.run_case(&baseline, *corruption).await;
+            if actual.as_ref() != Some(expected) {
+                failures.push(format!(
+                    "{} with {}: expected {expected:?}, got {}",
+                    row.instruction,
+                    corruption.describe(),
+                    actual.map_or("success".to_string(), |error| format!("{error:?}")),
+                ));
+            }
+        }
+
+        if let Err(error) = env.process(baseline).await {
+            failures.push(format!("{} baseline failed: {error}", row.instruction));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "\n{} error path(s) off:\n  {}\n",
+        failures.len(),
+        failures.join("\n  ")
+    );
+}
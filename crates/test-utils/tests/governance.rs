@@ -0,0 +1,403 @@
+//! Treasury governance over every way funds leave the treasury vault.
+//! Withdrawals taking a day's total above the approval threshold wait for
+//! the approvers and the execution delay, and rewards above it need the
+//! approvers' signatures, so no single key can move more than the threshold
+//! a day. Tokens owed to farmers never leave through a withdrawal.
+
+use reward_pool::{DailyStats, PendingReward, RewardPool, RewardPoolError, TREASURY_EPOCH};
+use reward_pool_client::{instructions, RewardAttribution};
+use reward_pool_test_utils::{
+    airdrop, assert_pool_error, fixture_keypair, get_state, process_instructions, token,
+    BanksClientError, PoolFixture, ProgramTestContext, TestPool,
+};
+use solana_keypair::Keypair;
+use solana_program::{
+    clock::Clock, instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+};
+use solana_signer::Signer;
+
+const TREASURY_FUNDS: u64 = 10_000_000;
+const THRESHOLD: u64 = 100_000;
+const DELAY: i64 = 3_600;
+const REQUIRED_APPROVALS: u8 = 2;
+
+// Keypair roles of the accounts the tests add to the fixture's
+const APPROVER: u8 = 100;
+const INTRUDER: u8 = 101;
+const DESTINATION: u8 = 102;
+
+/// A funded pool whose vault needs two of three approvers above
+/// `THRESHOLD`, and a token account to withdraw to
+struct GovernedPool {
+    context: ProgramTestContext,
+    pool: TestPool,
+    approvers: Vec<Keypair>,
+    intruder: Keypair,
+    destination: Pubkey,
+}
+
+impl GovernedPool {
+    async fn new() -> Self {
+        let (mut context, pool) = PoolFixture::new()
+            .with_funded_treasury(TREASURY_FUNDS)
+            .start()
+            .await
+            .expect("fixture bootstraps");
+        let approvers: Vec<Keypair> = (0..3).map(|i| fixture_keypair(0, APPROVER, i)).collect();
+        let intruder = fixture_keypair(0, INTRUDER, 0);
+        for signer in approvers.iter().chain([&intruder]) {
+            airdrop(&mut context, &signer.pubkey(), LAMPORTS_PER_SOL)
+                .await
+                .expect("signer funded");
+        }
+
+        let authority = pool.platform_authority.insecure_clone();
+        let configure = instructions::configure_treasury_governance(
+            &pool.accounts,
+            &authority.pubkey(),
+            approvers.iter().map(Signer::pubkey).collect(),
+            REQUIRED_APPROVALS,
+            THRESHOLD,
+            DELAY,
+        );
+        process_instructions(&mut context, &[configure], &[&authority])
+            .await
+            .expect("governance configured");
+
+        let destination = fixture_keypair(0, DESTINATION, 0);
+        token::create_token_account(
+            &mut context,
+            &destination,
+            &pool.accounts.reward_mint,
+            &authority.pubkey(),
+        )
+        .await
+        .expect("destination created");
+
+        Self {
+            context,
+            pool,
+            approvers,
+            intruder,
+            destination: destination.pubkey(),
+        }
+    }
+
+    async fn process(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        process_instructions(&mut self.context, &[instruction], signers).await
+    }
+
+    async fn process_as_authority(
+        &mut self,
+        instruction: Instruction,
+    ) -> Result<(), BanksClientError> {
+        let authority = self.pool.platform_authority.insecure_clone();
+        self.process(instruction, &[&authority]).await
+    }
+
+    /// Moves to a new slot, so that retrying a rejected instruction is not
+    /// a duplicate transaction
+    async fn next_slot(&mut self) {
+        let slot = self
+            .context
+            .banks_client
+            .get_root_slot()
+            .await
+            .expect("slot");
+        self.context.warp_to_slot(slot + 2).expect("warped");
+    }
+
+    async fn advance_clock(&mut self, seconds: i64) {
+        self.next_slot().await;
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    async fn balance(&mut self, account: &Pubkey) -> u64 {
+        token::token_balance(&mut self.context, account)
+            .await
+            .expect("balance read")
+    }
+
+    async fn pool_state(&mut self) -> RewardPool {
+        get_state(&mut self.context.banks_client, &self.pool.accounts.pool).await
+    }
+
+    async fn propose(&mut self, proposal_id: u64, amount: u64) {
+        let propose = instructions::propose_treasury_withdrawal(
+            &self.pool.accounts,
+            &self.pool.platform_authority.pubkey(),
+            proposal_id,
+            &self.destination,
+            amount,
+        );
+        self.process_as_authority(propose)
+            .await
+            .expect("withdrawal proposed");
+    }
+
+    async fn approve(
+        &mut self,
+        proposal_id: u64,
+        approver: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let approve = instructions::approve_treasury_withdrawal(
+            &self.pool.accounts,
+            &approver.pubkey(),
+            proposal_id,
+        );
+        self.process(approve, &[approver]).await
+    }
+
+    async fn execute(&mut self, proposal_id: u64) -> Result<(), BanksClientError> {
+        let execute = instructions::execute_treasury_withdrawal(
+            &self.pool.accounts,
+            &self.pool.platform_authority.pubkey(),
+            proposal_id,
+            &self.destination,
+        );
+        self.process_as_authority(execute).await
+    }
+
+    /// A `RecordReward` of `amount` for farmer 0 under `task_id`, co-signed
+    /// by `cosigners`
+    async fn record(
+        &mut self,
+        amount: u64,
+        task_id: &str,
+        cosigners: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        let record = instructions::record_reward(
+            &self.pool.accounts,
+            &self.pool.platform_authority.pubkey(),
+            &self.pool.farmer(0).pubkey(),
+            amount,
+            task_id.to_string(),
+            RewardAttribution::default(),
+            DailyStats::day_of(clock.unix_timestamp),
+        );
+        let cosigner_keys: Vec<Pubkey> =
+            cosigners.iter().map(|cosigner| cosigner.pubkey()).collect();
+        let record = instructions::approve_recorded_reward(record, &cosigner_keys);
+        let authority = self.pool.platform_authority.insecure_clone();
+        let mut signers = vec![&authority];
+        signers.extend_from_slice(cosigners);
+        self.process(record, &signers).await
+    }
+}
+
+#[tokio::test]
+async fn withdrawals_up_to_the_threshold_execute_at_once() {
+    let mut governed = GovernedPool::new().await;
+    governed.propose(0, THRESHOLD).await;
+
+    governed.execute(0).await.expect("withdrawal executed");
+    let destination = governed.destination;
+    assert_eq!(governed.balance(&destination).await, THRESHOLD);
+
+    governed.next_slot().await;
+    assert_pool_error(
+        governed.execute(0).await,
+        RewardPoolError::ProposalAlreadyExecuted,
+    );
+}
+
+#[tokio::test]
+async fn larger_withdrawals_wait_for_the_approvers_and_the_delay() {
+    let mut governed = GovernedPool::new().await;
+    let approvers: Vec<Keypair> = governed
+        .approvers
+        .iter()
+        .map(Keypair::insecure_clone)
+        .collect();
+    let intruder = governed.intruder.insecure_clone();
+    let destination = governed.destination;
+    governed.propose(0, THRESHOLD + 1).await;
+
+    assert_pool_error(
+        governed.execute(0).await,
+        RewardPoolError::InsufficientApprovals,
+    );
+    assert_pool_error(
+        governed.approve(0, &intruder).await,
+        RewardPoolError::NotAnApprover,
+    );
+    governed
+        .approve(0, &approvers[0])
+        .await
+        .expect("first approval");
+    governed.next_slot().await;
+    assert_pool_error(
+        governed.approve(0, &approvers[0]).await,
+        RewardPoolError::AlreadyApproved,
+    );
+    governed.next_slot().await;
+    assert_pool_error(
+        governed.execute(0).await,
+        RewardPoolError::InsufficientApprovals,
+    );
+
+    governed
+        .approve(0, &approvers[2])
+        .await
+        .expect("second approval");
+    governed.next_slot().await;
+    assert_pool_error(
+        governed.execute(0).await,
+        RewardPoolError::ProposalNotExecutable,
+    );
+    assert_eq!(governed.balance(&destination).await, 0);
+
+    governed.advance_clock(DELAY).await;
+    governed.execute(0).await.expect("withdrawal executed");
+    assert_eq!(governed.balance(&destination).await, THRESHOLD + 1);
+}
+
+#[tokio::test]
+async fn withdrawals_split_below_the_threshold_need_the_approvers_once_they_add_up() {
+    let mut governed = GovernedPool::new().await;
+    let approvers: Vec<Keypair> = governed
+        .approvers
+        .iter()
+        .map(Keypair::insecure_clone)
+        .collect();
+    let destination = governed.destination;
+    let part = THRESHOLD / 2;
+    for proposal_id in 0..3 {
+        governed.propose(proposal_id, part).await;
+    }
+
+    governed.execute(0).await.expect("first part executed");
+    governed.execute(1).await.expect("second part executed");
+    assert_pool_error(
+        governed.execute(2).await,
+        RewardPoolError::InsufficientApprovals,
+    );
+    for approver in &approvers[..2] {
+        governed
+            .approve(2, approver)
+            .await
+            .expect("third part approved");
+    }
+    governed.advance_clock(DELAY).await;
+    governed.execute(2).await.expect("approved part executed");
+    assert_eq!(governed.balance(&destination).await, 3 * part);
+
+    // The next day starts from nothing again
+    governed.propose(3, THRESHOLD).await;
+    governed.next_slot().await;
+    assert_pool_error(
+        governed.execute(3).await,
+        RewardPoolError::InsufficientApprovals,
+    );
+    governed.advance_clock(TREASURY_EPOCH).await;
+    governed
+        .execute(3)
+        .await
+        .expect("withdrawal of a new day executed");
+}
+
+#[tokio::test]
+async fn withdrawals_leave_the_tokens_owed_to_farmers() {
+    let mut governed = GovernedPool::new().await;
+    let approvers: Vec<Keypair> = governed
+        .approvers
+        .iter()
+        .map(Keypair::insecure_clone)
+        .collect();
+    let vault = governed.pool.accounts.treasury_vault();
+    governed
+        .record(THRESHOLD, "owed", &[])
+        .await
+        .expect("reward recorded");
+    let owed = governed.pool_state().await.outstanding_rewards;
+    assert!(owed > 0);
+
+    let available = governed.balance(&vault).await - owed;
+    governed.propose(0, available + 1).await;
+    for approver in &approvers[..2] {
+        governed
+            .approve(0, approver)
+            .await
+            .expect("withdrawal approved");
+    }
+    governed.advance_clock(DELAY).await;
+    assert_pool_error(
+        governed.execute(0).await,
+        RewardPoolError::InsufficientTreasuryFunds,
+    );
+
+    governed.propose(1, available).await;
+    for approver in &approvers[..2] {
+        governed
+            .approve(1, approver)
+            .await
+            .expect("withdrawal approved");
+    }
+    governed.advance_clock(DELAY).await;
+    governed.execute(1).await.expect("withdrawal executed");
+    assert_eq!(governed.balance(&vault).await, owed);
+}
+
+#[tokio::test]
+async fn rewards_above_the_threshold_need_the_approvers() {
+    let mut governed = GovernedPool::new().await;
+    let approvers: Vec<Keypair> = governed
+        .approvers
+        .iter()
+        .map(Keypair::insecure_clone)
+        .collect();
+    let intruder = governed.intruder.insecure_clone();
+
+    governed
+        .record(THRESHOLD, "within-threshold", &[])
+        .await
+        .expect("reward up to the threshold recorded by the authority alone");
+
+    assert_pool_error(
+        governed.record(THRESHOLD + 1, "above-threshold", &[]).await,
+        RewardPoolError::InsufficientApprovals,
+    );
+    assert_pool_error(
+        governed
+            .record(THRESHOLD + 1, "above-threshold", &[&approvers[0]])
+            .await,
+        RewardPoolError::InsufficientApprovals,
+    );
+    // Signers that are not approvers do not count
+    assert_pool_error(
+        governed
+            .record(
+                THRESHOLD + 1,
+                "above-threshold",
+                &[&approvers[0], &intruder],
+            )
+            .await,
+        RewardPoolError::InsufficientApprovals,
+    );
+
+    governed
+        .record(
+            THRESHOLD + 1,
+            "above-threshold",
+            &[&approvers[1], &approvers[0]],
+        )
+        .await
+        .expect("approved reward recorded");
+    let farmer = governed.pool.farmer(0).pubkey();
+    let pending: PendingReward = get_state(
+        &mut governed.context.banks_client,
+        &governed
+            .pool
+            .accounts
+            .pending_reward(&farmer, "above-threshold"),
+    )
+    .await;
+    assert!(pending.amount > 0 && !pending.is_withdrawn);
+}
@@ -0,0 +1,389 @@
+//! Interest on unclaimed pending rewards. Withdrawals and claims accrue the
+//! pool's yearly rates for as long as a reward waited, up to the policy's
+//! cap, and take it from the bonus vault; what the vault cannot pay stays
+//! owed in the pending reward rather than failing the claim.
+
+use reward_pool::{
+    DailyStats, InterestPolicy, PendingReward, RewardPoolError, MAX_INTEREST_RATE_BPS,
+    SECONDS_PER_YEAR,
+};
+use reward_pool_client::{instructions, RewardAttribution};
+use reward_pool_test_utils::{
+    airdrop, assert_pool_error, fixture_keypair, get_state, process_instructions, token,
+    BanksClientError, PoolFixture, ProgramTestContext, TestPool,
+};
+use solana_keypair::Keypair;
+use solana_program::{
+    clock::Clock, instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+};
+use solana_signer::Signer;
+
+const TREASURY_FUNDS: u64 = 10_000_000;
+const REWARD: u64 = 1_000_000;
+const TASK_ID: &str = "task-1";
+const RATE_BPS: u16 = 1_000;
+
+// Keypair role of the accounts the tests add to the fixture's
+const INTRUDER: u8 = 100;
+
+/// A funded pool and its first farmer, with a reward account
+struct Savings {
+    context: ProgramTestContext,
+    pool: TestPool,
+    farmer: Keypair,
+}
+
+impl Savings {
+    async fn new() -> Self {
+        let (mut context, pool) = PoolFixture::new()
+            .with_funded_treasury(TREASURY_FUNDS)
+            .start()
+            .await
+            .expect("fixture bootstraps");
+        let farmer = pool.farmer(0);
+        airdrop(&mut context, &farmer.pubkey(), LAMPORTS_PER_SOL)
+            .await
+            .expect("farmer funded");
+        pool.create_reward_account(&mut context, &farmer.pubkey())
+            .await
+            .expect("reward account created");
+        Self {
+            context,
+            pool,
+            farmer,
+        }
+    }
+
+    async fn process(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        process_instructions(&mut self.context, &[instruction], signers).await
+    }
+
+    async fn now(&mut self) -> i64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        clock.unix_timestamp
+    }
+
+    /// Moves the bank's clock `seconds` forward, on a new slot so that
+    /// retrying a rejected instruction is not a duplicate transaction
+    async fn advance_clock(&mut self, seconds: i64) {
+        let slot = self
+            .context
+            .banks_client
+            .get_root_slot()
+            .await
+            .expect("slot");
+        self.context.warp_to_slot(slot + 2).expect("warped");
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    async fn balance(&mut self, account: &Pubkey) -> u64 {
+        token::token_balance(&mut self.context, account)
+            .await
+            .expect("balance read")
+    }
+
+    async fn policy(&mut self) -> InterestPolicy {
+        get_state(
+            &mut self.context.banks_client,
+            &self.pool.accounts.interest_policy(),
+        )
+        .await
+    }
+
+    fn set_interest_policy(
+        &self,
+        platform_authority: &Pubkey,
+        rate_bps: u16,
+        max_accrual_secs: i64,
+    ) -> Instruction {
+        instructions::set_interest_policy(
+            &self.pool.accounts,
+            platform_authority,
+            &self.pool.accounts.reward_mint,
+            rate_bps,
+            max_accrual_secs,
+        )
+    }
+
+    /// Sets `RATE_BPS` for up to `max_accrual_secs` and puts `bonus` in the
+    /// bonus vault
+    async fn pay_interest(&mut self, max_accrual_secs: i64, bonus: u64) {
+        let authority = self.pool.platform_authority.insecure_clone();
+        let set = self.set_interest_policy(&authority.pubkey(), RATE_BPS, max_accrual_secs);
+        self.process(set, &[&authority])
+            .await
+            .expect("interest policy set");
+        let bonus_vault = self.pool.accounts.bonus_vault();
+        self.pool
+            .mint_to(&mut self.context, &bonus_vault, bonus)
+            .await
+            .expect("bonus vault funded");
+    }
+
+    /// Records `REWARD` for the farmer and returns its pending reward
+    async fn record(&mut self) -> PendingReward {
+        let stats_day = DailyStats::day_of(self.now().await);
+        let record = instructions::record_reward(
+            &self.pool.accounts,
+            &self.pool.platform_authority.pubkey(),
+            &self.farmer.pubkey(),
+            REWARD,
+            TASK_ID.to_string(),
+            RewardAttribution::default(),
+            stats_day,
+        );
+        let authority = self.pool.platform_authority.insecure_clone();
+        self.process(record, &[&authority])
+            .await
+            .expect("reward recorded");
+        self.pending().await
+    }
+
+    async fn pending(&mut self) -> PendingReward {
+        get_state(
+            &mut self.context.banks_client,
+            &self
+                .pool
+                .accounts
+                .pending_reward(&self.farmer.pubkey(), TASK_ID),
+        )
+        .await
+    }
+
+    async fn claim(&mut self) -> Result<(), BanksClientError> {
+        let pending_reward = self
+            .pool
+            .accounts
+            .pending_reward(&self.farmer.pubkey(), TASK_ID);
+        let claim = instructions::claim_many(
+            &self.pool.accounts,
+            &self.farmer.pubkey(),
+            &[pending_reward],
+        );
+        let farmer = self.farmer.insecure_clone();
+        self.process(claim, &[&farmer]).await
+    }
+}
+
+#[tokio::test]
+async fn only_the_platform_authority_sets_a_bounded_rate() {
+    let mut savings = Savings::new().await;
+    let authority = savings.pool.platform_authority.insecure_clone();
+    let intruder = fixture_keypair(0, INTRUDER, 0);
+    airdrop(&mut savings.context, &intruder.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .expect("intruder funded");
+
+    let set = savings.set_interest_policy(&intruder.pubkey(), RATE_BPS, SECONDS_PER_YEAR);
+    assert_pool_error(
+        savings.process(set, &[&intruder]).await,
+        RewardPoolError::InvalidAuthority,
+    );
+    let set = savings.set_interest_policy(
+        &authority.pubkey(),
+        MAX_INTEREST_RATE_BPS + 1,
+        SECONDS_PER_YEAR,
+    );
+    assert_pool_error(
+        savings.process(set, &[&authority]).await,
+        RewardPoolError::InvalidInterestRate,
+    );
+
+    let set = savings.set_interest_policy(&authority.pubkey(), RATE_BPS, SECONDS_PER_YEAR);
+    savings
+        .process(set, &[&authority])
+        .await
+        .expect("interest policy set");
+    let policy = savings.policy().await;
+    assert_eq!(policy.rate_bps, RATE_BPS);
+    assert_eq!(policy.max_accrual_secs, SECONDS_PER_YEAR);
+    let bonus_vault = savings.pool.accounts.bonus_vault();
+    assert_eq!(savings.balance(&bonus_vault).await, 0);
+
+    // Later calls change the rate of the existing policy
+    let set = savings.set_interest_policy(&authority.pubkey(), 0, SECONDS_PER_YEAR);
+    savings
+        .process(set, &[&authority])
+        .await
+        .expect("interest turned off");
+    assert!(!savings.policy().await.is_enabled());
+}
+
+#[tokio::test]
+async fn claims_take_the_interest_accrued_from_the_bonus_vault() {
+    let mut savings = Savings::new().await;
+    let bonus = REWARD;
+    savings.pay_interest(SECONDS_PER_YEAR, bonus).await;
+    let pending = savings.record().await;
+
+    savings.advance_clock(SECONDS_PER_YEAR / 2).await;
+    let now = savings.now().await;
+    let interest = savings
+        .policy()
+        .await
+        .accrued(pending.amount, pending.recorded_at, now);
+    assert!(interest > 0);
+    savings.claim().await.expect("reward claimed");
+
+    let reward_account = savings
+        .pool
+        .accounts
+        .reward_account(&savings.farmer.pubkey());
+    assert_eq!(
+        savings.balance(&reward_account).await,
+        pending.amount + interest
+    );
+    let bonus_vault = savings.pool.accounts.bonus_vault();
+    assert_eq!(savings.balance(&bonus_vault).await, bonus - interest);
+    let policy = savings.policy().await;
+    assert_eq!(policy.total_paid, interest);
+    assert_eq!(policy.total_shortfall, 0);
+    assert_eq!(policy.claim_count, 1);
+}
+
+#[tokio::test]
+async fn withdrawals_take_the_interest_accrued_from_the_bonus_vault() {
+    let mut savings = Savings::new().await;
+    let bonus = REWARD;
+    savings.pay_interest(SECONDS_PER_YEAR, bonus).await;
+    let pending = savings.record().await;
+
+    savings.advance_clock(SECONDS_PER_YEAR / 2).await;
+    let now = savings.now().await;
+    let interest = savings
+        .policy()
+        .await
+        .accrued(pending.amount, pending.recorded_at, now);
+    assert!(interest > 0);
+    let reward_account = savings
+        .pool
+        .accounts
+        .reward_account(&savings.farmer.pubkey());
+    let withdraw = instructions::withdraw_reward(
+        &savings.pool.accounts,
+        &savings.farmer.pubkey(),
+        TASK_ID,
+        &reward_account,
+        pending.amount,
+        0,
+        DailyStats::day_of(now),
+    );
+    let farmer = savings.farmer.insecure_clone();
+    savings
+        .process(withdraw, &[&farmer])
+        .await
+        .expect("reward withdrawn");
+
+    assert_eq!(
+        savings.balance(&reward_account).await,
+        pending.amount + interest
+    );
+    let bonus_vault = savings.pool.accounts.bonus_vault();
+    assert_eq!(savings.balance(&bonus_vault).await, bonus - interest);
+    assert_eq!(savings.policy().await.total_paid, interest);
+}
+
+#[tokio::test]
+async fn a_new_rate_applies_from_the_time_it_is_set() {
+    let mut savings = Savings::new().await;
+    savings.pay_interest(SECONDS_PER_YEAR, REWARD).await;
+    let pending = savings.record().await;
+
+    // Half a year at `RATE_BPS`, then half a year at nothing
+    savings.advance_clock(SECONDS_PER_YEAR / 2).await;
+    let authority = savings.pool.platform_authority.insecure_clone();
+    let set = savings.set_interest_policy(&authority.pubkey(), 0, SECONDS_PER_YEAR);
+    savings
+        .process(set, &[&authority])
+        .await
+        .expect("interest turned off");
+    let rate_changed_at = savings.now().await;
+    savings.advance_clock(SECONDS_PER_YEAR / 2).await;
+
+    let policy = savings.policy().await;
+    let interest = policy.accrued(pending.amount, pending.recorded_at, savings.now().await);
+    assert_eq!(
+        interest,
+        (pending.amount as u128
+            * RATE_BPS as u128
+            * (rate_changed_at - pending.recorded_at) as u128
+            / (10_000 * SECONDS_PER_YEAR as u128)) as u64
+    );
+    assert!(interest > 0);
+    savings.claim().await.expect("reward claimed");
+
+    let reward_account = savings
+        .pool
+        .accounts
+        .reward_account(&savings.farmer.pubkey());
+    assert_eq!(
+        savings.balance(&reward_account).await,
+        pending.amount + interest
+    );
+}
+
+#[tokio::test]
+async fn claims_beyond_the_bonus_vault_carry_the_shortfall_forward() {
+    let mut savings = Savings::new().await;
+    let bonus = 1_000;
+    let max_accrual_secs = SECONDS_PER_YEAR / 4;
+    savings.pay_interest(max_accrual_secs, bonus).await;
+    let pending = savings.record().await;
+
+    // Accrual stops at the cap, however long the reward waits
+    savings.advance_clock(SECONDS_PER_YEAR).await;
+    let policy = savings.policy().await;
+    let interest = policy.accrued(
+        pending.amount,
+        pending.recorded_at,
+        pending.recorded_at + max_accrual_secs,
+    );
+    assert_eq!(
+        policy.accrued(pending.amount, pending.recorded_at, savings.now().await),
+        interest
+    );
+    assert!(interest > bonus);
+    savings.claim().await.expect("reward claimed");
+
+    let reward_account = savings
+        .pool
+        .accounts
+        .reward_account(&savings.farmer.pubkey());
+    assert_eq!(
+        savings.balance(&reward_account).await,
+        pending.amount + bonus
+    );
+    let policy = savings.policy().await;
+    assert_eq!(policy.total_paid, bonus);
+    assert_eq!(policy.total_shortfall, interest - bonus);
+    assert_eq!(savings.pending().await.interest_owed, interest - bonus);
+
+    // Once the bonus vault is topped up, claiming the withdrawn reward again
+    // pays what it is still owed, and nothing more
+    let bonus_vault = savings.pool.accounts.bonus_vault();
+    savings
+        .pool
+        .mint_to(&mut savings.context, &bonus_vault, interest)
+        .await
+        .expect("bonus vault topped up");
+    savings.advance_clock(60).await;
+    savings.claim().await.expect("interest owed claimed");
+
+    assert_eq!(
+        savings.balance(&reward_account).await,
+        pending.amount + interest
+    );
+    assert_eq!(savings.balance(&bonus_vault).await, bonus);
+    let policy = savings.policy().await;
+    assert_eq!(policy.total_paid, interest);
+    assert_eq!(policy.total_shortfall, 0);
+    assert_eq!(savings.pending().await.interest_owed, 0);
+    savings.advance_clock(60).await;
+    assert_pool_error(savings.claim().await, RewardPoolError::NoPendingRewards);
+}
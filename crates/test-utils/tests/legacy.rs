@@ -0,0 +1,277 @@
+//! Clients built against the V1 instruction set keep their account lists.
+//! Accounts added to a V1 instruction since are optional trailing accounts
+//! for them, so a deployed V1 caller never has to change, except for
+//! `RecordReward`: its V1 list paid outside the treasury vault and is
+//! rejected.
+
+use reward_pool::{
+    instruction::{v1, v2, VersionedInstruction},
+    DailyStats, RewardPool, RewardPoolError,
+};
+use reward_pool_test_utils::{
+    airdrop, assert_instruction_error, assert_pool_error, fixture_keypair, get_state,
+    process_instructions, token, BanksClientError, PoolFixture, ProgramTestContext, TestPool,
+};
+use solana_keypair::Keypair;
+use solana_program::{
+    clock::Clock,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+};
+use solana_signer::Signer;
+
+const AUTHORITY_FUNDS: u64 = 1_000_000;
+const REWARD: u64 = 100_000;
+
+// Keypair role of the farmer's destination account
+const DESTINATION: u8 = 100;
+
+/// A pool whose platform authority holds reward tokens of its own, and its
+/// first farmer, with a reward account
+struct LegacyPool {
+    context: ProgramTestContext,
+    pool: TestPool,
+    farmer: Keypair,
+    /// The authority's token account, its V1 platform treasury
+    authority_account: Pubkey,
+}
+
+impl LegacyPool {
+    async fn new() -> Self {
+        let (mut context, pool) = PoolFixture::new()
+            .start()
+            .await
+            .expect("fixture bootstraps");
+        let authority = pool.platform_authority.pubkey();
+        let authority_account = pool
+            .create_reward_account(&mut context, &authority)
+            .await
+            .expect("authority account created");
+        pool.mint_to(&mut context, &authority_account, AUTHORITY_FUNDS)
+            .await
+            .expect("authority funded");
+        let farmer = pool.farmer(0);
+        airdrop(&mut context, &farmer.pubkey(), LAMPORTS_PER_SOL)
+            .await
+            .expect("farmer funded");
+        pool.create_reward_account(&mut context, &farmer.pubkey())
+            .await
+            .expect("reward account created");
+        Self {
+            context,
+            pool,
+            farmer,
+            authority_account,
+        }
+    }
+
+    async fn process(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        process_instructions(&mut self.context, &[instruction], signers).await
+    }
+
+    async fn process_as_authority(
+        &mut self,
+        instruction: Instruction,
+    ) -> Result<(), BanksClientError> {
+        let authority = self.pool.platform_authority.insecure_clone();
+        self.process(instruction, &[&authority]).await
+    }
+
+    async fn balance(&mut self, account: &Pubkey) -> u64 {
+        token::token_balance(&mut self.context, account)
+            .await
+            .expect("balance read")
+    }
+
+    async fn pool_state(&mut self) -> RewardPool {
+        get_state(&mut self.context.banks_client, &self.pool.accounts.pool).await
+    }
+
+    /// The V1 account list of a `RecordReward` for the farmer
+    fn record_accounts(&self) -> Vec<AccountMeta> {
+        let accounts = &self.pool.accounts;
+        vec![
+            AccountMeta::new(self.pool.platform_authority.pubkey(), true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(self.authority_account, false),
+            AccountMeta::new(accounts.reward_account(&self.farmer.pubkey()), false),
+            AccountMeta::new_readonly(accounts.reward_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ]
+    }
+
+    fn record_reward(&self, data: Vec<u8>, accounts: Vec<AccountMeta>) -> Instruction {
+        Instruction {
+            program_id: self.pool.accounts.program_id,
+            accounts,
+            data,
+        }
+    }
+}
+
+fn v1_record_reward(farmer: &Pubkey) -> v1::RewardPoolInstruction {
+    v1::RewardPoolInstruction::RecordReward {
+        amount: REWARD,
+        farmer_pubkey: *farmer,
+        task_id: "task-1".to_string(),
+    }
+}
+
+/// Encodes a V1 instruction the way clients did before tagging
+fn untagged(instruction: &v1::RewardPoolInstruction) -> Vec<u8> {
+    borsh::to_vec(instruction).expect("instruction serializes")
+}
+
+fn tagged(instruction: v1::RewardPoolInstruction) -> Vec<u8> {
+    VersionedInstruction::V1(instruction).pack()
+}
+
+#[tokio::test]
+async fn v1_rewards_are_rejected_with_the_v1_accounts() {
+    let mut legacy = LegacyPool::new().await;
+    let farmer = legacy.farmer.pubkey();
+    let accounts = legacy.pool.accounts;
+    let clock: Clock = legacy
+        .context
+        .banks_client
+        .get_sysvar()
+        .await
+        .expect("clock");
+    let daily_stats = accounts.daily_stats(DailyStats::day_of(clock.unix_timestamp));
+    let mut with_stats = legacy.record_accounts();
+    with_stats.extend([
+        AccountMeta::new(daily_stats, false),
+        AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        AccountMeta::new(accounts.instruction_counters(), false),
+    ]);
+    let v2 = v2::RewardPoolInstruction::RecordReward {
+        amount: REWARD,
+        farmer_pubkey: farmer,
+        task_id: "task-1".to_string(),
+        campaign_id: None,
+        platform_id: None,
+        proof_hash: None,
+        task_type: None,
+        secondary_amount: 0,
+        idempotency_key: Some([7; 32]),
+    };
+
+    // Whatever the encoding, the authority's own account never pays
+    let records = [
+        (
+            untagged(&v1_record_reward(&farmer)),
+            legacy.record_accounts(),
+        ),
+        (tagged(v1_record_reward(&farmer)), with_stats),
+        (v2.pack(), legacy.record_accounts()),
+    ];
+    for (data, metas) in records {
+        let record = legacy.record_reward(data, metas);
+        assert_instruction_error(
+            legacy.process_as_authority(record).await,
+            InstructionError::NotEnoughAccountKeys,
+        );
+    }
+    let authority_account = legacy.authority_account;
+    assert_eq!(legacy.balance(&authority_account).await, AUTHORITY_FUNDS);
+    assert_eq!(legacy.balance(&accounts.reward_account(&farmer)).await, 0);
+    assert_eq!(legacy.pool_state().await.total_rewards_distributed, 0);
+}
+
+#[tokio::test]
+async fn reward_account_tokens_are_withdrawn_with_the_v1_accounts() {
+    let mut legacy = LegacyPool::new().await;
+    let farmer = legacy.farmer.insecure_clone();
+    let accounts = legacy.pool.accounts;
+    let reward_account = accounts.reward_account(&farmer.pubkey());
+    legacy
+        .pool
+        .mint_to(&mut legacy.context, &reward_account, REWARD)
+        .await
+        .expect("reward account funded");
+
+    let destination = fixture_keypair(0, DESTINATION, 0);
+    token::create_token_account(
+        &mut legacy.context,
+        &destination,
+        &accounts.reward_mint,
+        &farmer.pubkey(),
+    )
+    .await
+    .expect("destination created");
+    let withdraw = Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(farmer.pubkey(), true),
+            AccountMeta::new(accounts.pool, false),
+            AccountMeta::new(reward_account, false),
+            AccountMeta::new(destination.pubkey(), false),
+            AccountMeta::new_readonly(accounts.reward_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: tagged(v1::RewardPoolInstruction::WithdrawReward {
+            amount: REWARD,
+            nonce: 1,
+        }),
+    };
+    legacy
+        .process(withdraw, &[&farmer])
+        .await
+        .expect("tagged V1 withdrawal processed");
+    assert_eq!(legacy.balance(&destination.pubkey()).await, REWARD);
+    assert_eq!(legacy.balance(&reward_account).await, 0);
+}
+
+#[tokio::test]
+async fn v1_admin_instructions_take_their_v1_accounts() {
+    let mut legacy = LegacyPool::new().await;
+    let accounts = legacy.pool.accounts;
+    let authority = legacy.pool.platform_authority.pubkey();
+    let admin = |instruction: v1::RewardPoolInstruction| Instruction {
+        program_id: accounts.program_id,
+        accounts: vec![
+            AccountMeta::new(authority, true),
+            AccountMeta::new(accounts.pool, false),
+        ],
+        data: untagged(&instruction),
+    };
+
+    legacy
+        .process_as_authority(admin(v1::RewardPoolInstruction::PausePool))
+        .await
+        .expect("paused");
+    assert!(legacy.pool_state().await.is_paused);
+    legacy
+        .process_as_authority(admin(v1::RewardPoolInstruction::ResumePool))
+        .await
+        .expect("resumed");
+    assert!(!legacy.pool_state().await.is_paused);
+
+    // Without the parameters account, the pool's maximum fee is unknown
+    let fee_percentage = legacy.pool_state().await.platform_fee_percentage;
+    let raise = admin(v1::RewardPoolInstruction::UpdatePlatformFee {
+        new_fee_percentage: fee_percentage + 1,
+    });
+    assert_pool_error(
+        legacy.process_as_authority(raise).await,
+        RewardPoolError::InvalidPlatformFee,
+    );
+    let lower = admin(v1::RewardPoolInstruction::UpdatePlatformFee {
+        new_fee_percentage: fee_percentage - 1,
+    });
+    legacy
+        .process_as_authority(lower)
+        .await
+        .expect("fee lowered");
+    assert_eq!(
+        legacy.pool_state().await.platform_fee_percentage,
+        fee_percentage - 1
+    );
+}
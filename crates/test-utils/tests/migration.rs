@@ -0,0 +1,244 @@
+//! Migration of a pool's liabilities to a new program deployment. The
+//! program runs twice in the bank, at a previous deployment's id and at its
+//! own: the previous pool exports its farmer balances, and the new one
+//! imports each of them against the export's merkle root.
+
+use borsh::BorshDeserialize;
+use reward_pool::{ImportedBalance, PendingReward, RewardPool, RewardPoolError, StateExport};
+use reward_pool_client::{instructions, BalanceTree, PoolAccounts};
+use reward_pool_test_utils::{
+    assert_pool_error, get_state, process_instructions, program_test, BanksClientError,
+    PoolFixture, ProgramTestContext, TestPool,
+};
+use solana_keypair::Keypair;
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::processor;
+use solana_signer::Signer;
+
+const TREASURY_FUNDS: u64 = 10_000_000;
+const PLATFORM_FEE_PERCENTAGE: u8 = 5;
+
+const PREVIOUS_PROGRAM: Pubkey = Pubkey::new_from_array([9; 32]);
+
+/// A pool on the previous deployment and one of the same reward mint on
+/// this deployment, both administered by the fixture's platform authority,
+/// and the balances the previous pool owes
+struct Migration {
+    context: ProgramTestContext,
+    pool: TestPool,
+    previous: PoolAccounts,
+    balances: BalanceTree,
+}
+
+impl Migration {
+    async fn new() -> Self {
+        let mut program_test = program_test();
+        program_test.add_program(
+            "reward_pool_previous",
+            PREVIOUS_PROGRAM,
+            processor!(reward_pool::process_instruction),
+        );
+        let mut context = program_test.start_with_context().await;
+        let pool = PoolFixture::new()
+            .with_funded_treasury(TREASURY_FUNDS)
+            .bootstrap(&mut context)
+            .await
+            .expect("fixture bootstraps");
+
+        let previous =
+            PoolAccounts::for_mint_with_program_id(PREVIOUS_PROGRAM, pool.accounts.reward_mint);
+        let initialize = instructions::initialize_pool(
+            &previous,
+            &pool.platform_authority.pubkey(),
+            PLATFORM_FEE_PERCENTAGE,
+        );
+        process_instructions(&mut context, &[initialize], &[&pool.platform_authority])
+            .await
+            .expect("previous pool initialized");
+
+        let balances = BalanceTree::new(vec![
+            (pool.farmer(0).pubkey(), 250_000),
+            (pool.farmer(1).pubkey(), 40_000),
+            (pool.farmer(2).pubkey(), 7_500),
+        ]);
+        Self {
+            context,
+            pool,
+            previous,
+            balances,
+        }
+    }
+
+    async fn process_as_authority(
+        &mut self,
+        instruction: Instruction,
+    ) -> Result<(), BanksClientError> {
+        let authority = self.pool.platform_authority.insecure_clone();
+        process_instructions(&mut self.context, &[instruction], &[&authority]).await
+    }
+
+    /// Decodes an account of the previous deployment, which `get_state`
+    /// rejects as owned by another program
+    async fn previous_state<T: BorshDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .expect("account fetched")
+            .unwrap_or_else(|| panic!("no account at {address}"));
+        assert_eq!(account.owner, PREVIOUS_PROGRAM);
+        T::deserialize(&mut account.data.as_slice()).expect("account decodes")
+    }
+
+    /// Moves to a new slot, so that retrying a rejected instruction is not
+    /// a duplicate transaction
+    async fn next_slot(&mut self) {
+        let slot = self
+            .context
+            .banks_client
+            .get_root_slot()
+            .await
+            .expect("slot");
+        self.context.warp_to_slot(slot + 2).expect("warped");
+    }
+
+    fn export(&self) -> Instruction {
+        instructions::export_state(
+            &self.previous,
+            &self.pool.platform_authority.pubkey(),
+            self.balances.root(),
+            self.balances.farmer_count(),
+            self.balances.total_amount(),
+        )
+    }
+
+    /// Imports `amount` for `farmer` from `source` with the tree's proof of
+    /// the farmer's actual balance
+    fn import(&self, source: &PoolAccounts, farmer: &Pubkey, amount: u64) -> Instruction {
+        let (_, proof) = self.balances.proof(farmer).expect("farmer in the tree");
+        instructions::import_state(
+            &self.pool.accounts,
+            source,
+            &self.pool.platform_authority.pubkey(),
+            farmer,
+            amount,
+            proof,
+        )
+    }
+}
+
+#[tokio::test]
+async fn export_freezes_the_pool_under_its_commitment() {
+    let mut migration = Migration::new().await;
+    let previous = migration.previous;
+    let authority = migration.pool.platform_authority.pubkey();
+
+    // Only the platform authority exports
+    let intruder = Keypair::new();
+    let mut export = migration.export();
+    export.accounts[0].pubkey = intruder.pubkey();
+    assert_pool_error(
+        process_instructions(&mut migration.context, &[export], &[&intruder]).await,
+        RewardPoolError::InvalidAuthority,
+    );
+
+    let export = migration.export();
+    migration
+        .process_as_authority(export)
+        .await
+        .expect("state exported");
+    let state_export: StateExport = migration.previous_state(&previous.state_export()).await;
+    assert_eq!(state_export.pool, previous.pool);
+    assert_eq!(state_export.exported_by, authority);
+    assert_eq!(state_export.merkle_root, migration.balances.root());
+    assert_eq!(state_export.farmer_count, 3);
+    assert_eq!(state_export.total_amount, 297_500);
+
+    // The export account administers the pool now, and signs for nothing
+    let pool: RewardPool = migration.previous_state(&previous.pool).await;
+    assert!(pool.is_paused);
+    assert_eq!(pool.platform_authority, previous.state_export());
+    let resume = instructions::resume_pool(&previous, &authority);
+    assert_pool_error(
+        migration.process_as_authority(resume).await,
+        RewardPoolError::InvalidAuthority,
+    );
+}
+
+#[tokio::test]
+async fn exported_balances_are_imported_once_with_their_proof() {
+    let mut migration = Migration::new().await;
+    let previous = migration.previous;
+    let export = migration.export();
+    migration
+        .process_as_authority(export)
+        .await
+        .expect("state exported");
+    let farmer = migration.pool.farmer(1).pubkey();
+    let (amount, _) = migration
+        .balances
+        .proof(&farmer)
+        .expect("farmer in the tree");
+
+    let inflated = migration.import(&previous, &farmer, amount + 1);
+    assert_pool_error(
+        migration.process_as_authority(inflated).await,
+        RewardPoolError::InvalidBalanceProof,
+    );
+
+    let import = migration.import(&previous, &farmer, amount);
+    migration
+        .process_as_authority(import)
+        .await
+        .expect("balance imported");
+    let accounts = migration.pool.accounts;
+    let imported: ImportedBalance = get_state(
+        &mut migration.context.banks_client,
+        &accounts.imported_balance(&farmer),
+    )
+    .await;
+    assert_eq!(imported.source_pool, previous.pool);
+    assert_eq!(imported.amount, amount);
+    let pending: PendingReward = get_state(
+        &mut migration.context.banks_client,
+        &accounts.pending_reward(&farmer, ImportedBalance::TASK_ID),
+    )
+    .await;
+    assert_eq!(pending.farmer_pubkey, farmer);
+    assert_eq!(pending.amount, amount);
+    assert!(!pending.is_withdrawn);
+
+    migration.next_slot().await;
+    let again = migration.import(&previous, &farmer, amount);
+    assert_pool_error(
+        migration.process_as_authority(again).await,
+        RewardPoolError::BalanceAlreadyImported,
+    );
+}
+
+#[tokio::test]
+async fn imports_only_accept_an_export_of_a_previous_deployment() {
+    let mut migration = Migration::new().await;
+    let farmer = migration.pool.farmer(0).pubkey();
+    let (amount, _) = migration
+        .balances
+        .proof(&farmer)
+        .expect("farmer in the tree");
+
+    // A pool of this deployment cannot stand in for the previous one
+    let local = PoolAccounts::for_mint(migration.pool.accounts.reward_mint);
+    let import = migration.import(&local, &farmer, amount);
+    assert_pool_error(
+        migration.process_as_authority(import).await,
+        RewardPoolError::InvalidStateExportAccount,
+    );
+
+    // Nor can a previous pool that has not exported
+    let previous = migration.previous;
+    let import = migration.import(&previous, &farmer, amount);
+    assert_pool_error(
+        migration.process_as_authority(import).await,
+        RewardPoolError::InvalidStateExportAccount,
+    );
+}
@@ -0,0 +1,252 @@
+//! Attestor-gated rewards against the mock attestor, and the mock price
+//! feed's own rules. The attestor cases cover what a partner attestor can
+//! get wrong: no attestation, another farmer's, a ceiling below the reward
+//! and an attestation behind an Anchor-style discriminator.
+
+use borsh::BorshDeserialize;
+use mock_price_feed::PriceFeed;
+use reward_pool::{DailyStats, PendingReward, RewardPoolError};
+use reward_pool_client::{instructions, RewardAttribution};
+use reward_pool_test_utils::{
+    airdrop, assert_pool_error, fixture_keypair, get_state, process_instructions, BanksClientError,
+    PoolFixture, ProgramTestContext, TestPool,
+};
+use solana_keypair::Keypair;
+use solana_program::{clock::Clock, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+use solana_signer::Signer;
+
+const TREASURY_FUNDS: u64 = 10_000_000;
+const REWARD: u64 = 100_000;
+
+// Keypair role of the price feeds
+const PRICE_FEED: u8 = 100;
+
+/// A funded pool requiring attestations of the mock attestor at
+/// `data_offset`, and its first farmer with a reward account
+async fn attested_pool(data_offset: u16) -> (ProgramTestContext, TestPool, Keypair) {
+    let (mut context, pool) = PoolFixture::new()
+        .with_funded_treasury(TREASURY_FUNDS)
+        .start()
+        .await
+        .expect("fixture bootstraps");
+    let set_attestor = instructions::set_attestor(
+        &pool.accounts,
+        &pool.platform_authority.pubkey(),
+        Some(&mock_attestor::id()),
+        data_offset,
+    );
+    process_instructions(&mut context, &[set_attestor], &[&pool.platform_authority])
+        .await
+        .expect("attestor set");
+
+    let farmer = pool.farmer(0);
+    pool.create_reward_account(&mut context, &farmer.pubkey())
+        .await
+        .expect("reward account created");
+    (context, pool, farmer)
+}
+
+async fn attest(
+    context: &mut ProgramTestContext,
+    task_id: &str,
+    farmer: &Pubkey,
+    amount_ceiling: u64,
+    data_offset: u16,
+) -> Pubkey {
+    let set_attestation = mock_attestor::set_attestation(
+        &mock_attestor::id(),
+        &context.payer.pubkey(),
+        task_id,
+        farmer,
+        amount_ceiling,
+        data_offset,
+    );
+    process_instructions(context, &[set_attestation], &[])
+        .await
+        .expect("attestation set");
+    mock_attestor::find_attestation_address(&mock_attestor::id(), task_id, farmer).0
+}
+
+/// Records `REWARD` for `task_id` and `farmer`, passing `attestation`
+async fn record(
+    context: &mut ProgramTestContext,
+    pool: &TestPool,
+    farmer: &Pubkey,
+    task_id: &str,
+    attestation: &Pubkey,
+) -> Result<(), BanksClientError> {
+    let clock: Clock = context.banks_client.get_sysvar().await?;
+    let record_reward = instructions::record_reward(
+        &pool.accounts,
+        &pool.platform_authority.pubkey(),
+        farmer,
+        REWARD,
+        task_id.to_string(),
+        RewardAttribution::default(),
+        DailyStats::day_of(clock.unix_timestamp),
+    );
+    let record_reward = instructions::attest_recorded_reward(record_reward, attestation);
+    process_instructions(context, &[record_reward], &[&pool.platform_authority]).await
+}
+
+#[tokio::test]
+async fn attested_reward_is_recorded() {
+    let (mut context, pool, farmer) = attested_pool(0).await;
+    let attestation = attest(&mut context, "task-1", &farmer.pubkey(), REWARD, 0).await;
+
+    record(
+        &mut context,
+        &pool,
+        &farmer.pubkey(),
+        "task-1",
+        &attestation,
+    )
+    .await
+    .expect("attested reward recorded");
+    let pending: PendingReward = get_state(
+        &mut context.banks_client,
+        &pool.accounts.pending_reward(&farmer.pubkey(), "task-1"),
+    )
+    .await;
+    assert!(pending.amount > 0);
+}
+
+#[tokio::test]
+async fn attestation_is_read_at_the_pool_offset() {
+    let (mut context, pool, farmer) = attested_pool(8).await;
+    let unaligned = attest(&mut context, "task-1", &farmer.pubkey(), REWARD, 0).await;
+    assert_pool_error(
+        record(&mut context, &pool, &farmer.pubkey(), "task-1", &unaligned).await,
+        RewardPoolError::InvalidAttestation,
+    );
+
+    // Rewriting it behind a discriminator makes it readable
+    let attestation = attest(&mut context, "task-1", &farmer.pubkey(), REWARD, 8).await;
+    record(
+        &mut context,
+        &pool,
+        &farmer.pubkey(),
+        "task-1",
+        &attestation,
+    )
+    .await
+    .expect("attested reward recorded");
+}
+
+#[tokio::test]
+async fn unattested_rewards_are_refused() {
+    let (mut context, pool, farmer) = attested_pool(0).await;
+
+    // No attestation at all
+    let missing =
+        mock_attestor::find_attestation_address(&mock_attestor::id(), "task-1", &farmer.pubkey()).0;
+    assert_pool_error(
+        record(&mut context, &pool, &farmer.pubkey(), "task-1", &missing).await,
+        RewardPoolError::InvalidAttestation,
+    );
+
+    // Another farmer's attestation of the same task
+    let other_farmer = pool.farmer(1);
+    let other = attest(&mut context, "task-2", &other_farmer.pubkey(), REWARD, 0).await;
+    assert_pool_error(
+        record(&mut context, &pool, &farmer.pubkey(), "task-2", &other).await,
+        RewardPoolError::InvalidAttestation,
+    );
+
+    // A ceiling below the reward
+    let capped = attest(&mut context, "task-3", &farmer.pubkey(), REWARD - 1, 0).await;
+    assert_pool_error(
+        record(&mut context, &pool, &farmer.pubkey(), "task-3", &capped).await,
+        RewardPoolError::AttestedAmountExceeded,
+    );
+
+    // A closed attestation no longer counts
+    let closed = attest(&mut context, "task-4", &farmer.pubkey(), REWARD, 0).await;
+    let close = mock_attestor::close_attestation(
+        &mock_attestor::id(),
+        &context.payer.pubkey(),
+        "task-4",
+        &farmer.pubkey(),
+    );
+    process_instructions(&mut context, &[close], &[])
+        .await
+        .expect("attestation closed");
+    assert_pool_error(
+        record(&mut context, &pool, &farmer.pubkey(), "task-4", &closed).await,
+        RewardPoolError::InvalidAttestation,
+    );
+}
+
+#[tokio::test]
+async fn price_feed_follows_its_authority() {
+    let (mut context, _pool) = PoolFixture::new()
+        .start()
+        .await
+        .expect("fixture bootstraps");
+    let authority = fixture_keypair(0, PRICE_FEED, 0);
+    let feed = fixture_keypair(0, PRICE_FEED, 1);
+    airdrop(&mut context, &authority.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .expect("authority funded");
+
+    let set_price = |price, publish_time| {
+        mock_price_feed::set_price(
+            &mock_price_feed::id(),
+            &authority.pubkey(),
+            &feed.pubkey(),
+            price,
+            10,
+            -8,
+            publish_time,
+        )
+    };
+    process_instructions(
+        &mut context,
+        &[mock_price_feed::create_price_feed(set_price(
+            150_000_000,
+            None,
+        ))],
+        &[&authority, &feed],
+    )
+    .await
+    .expect("feed created");
+    process_instructions(&mut context, &[set_price(-5, Some(42))], &[&authority])
+        .await
+        .expect("price updated");
+
+    let account = context
+        .banks_client
+        .get_account(feed.pubkey())
+        .await
+        .expect("account fetched")
+        .expect("feed exists");
+    assert_eq!(account.owner, mock_price_feed::id());
+    assert_eq!(
+        PriceFeed::try_from_slice(&account.data).expect("feed decodes"),
+        PriceFeed {
+            authority: authority.pubkey(),
+            price: -5,
+            confidence: 10,
+            exponent: -8,
+            publish_time: 42,
+        }
+    );
+
+    // Only the authority sets the price
+    let intruder = fixture_keypair(0, PRICE_FEED, 2);
+    airdrop(&mut context, &intruder.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .expect("intruder funded");
+    let hijack = mock_price_feed::set_price(
+        &mock_price_feed::id(),
+        &intruder.pubkey(),
+        &feed.pubkey(),
+        1,
+        0,
+        0,
+        None,
+    );
+    process_instructions(&mut context, &[hijack], &[&intruder])
+        .await
+        .expect_err("intruder refused");
+}
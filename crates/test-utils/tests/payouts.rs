@@ -0,0 +1,722 @@
+//! The farmer's payout guards on every path a reward leaves custody by.
+//! Rewards stay in the treasury vault until the farmer takes them out with
+//! `WithdrawReward` or a claim, so a guard one path enforces must hold on
+//! the others too, or a stolen signing key would simply take the other one.
+
+use reward_pool::{
+    DailyStats, PendingReward, RewardPoolError, WithdrawalRecord, WHITELIST_ACTIVATION_DELAY,
+    WITHDRAWAL_RECORD_RETENTION,
+};
+use reward_pool_client::{instructions, RewardAttribution};
+use reward_pool_test_utils::{
+    airdrop, assert_pool_error, fixture_keypair, get_state, process_instructions, token,
+    BanksClientError, PoolFixture, ProgramTestContext, TestPool,
+};
+use solana_keypair::Keypair;
+use solana_program::{
+    clock::Clock, instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+};
+use solana_signer::Signer;
+
+const TREASURY_FUNDS: u64 = 10_000_000;
+const REWARD: u64 = 100_000;
+
+// Keypair roles of the accounts the tests add to the fixture's
+const PAYOUT_WALLET: u8 = 100;
+const TOKEN_ACCOUNT: u8 = 101;
+const EXCHANGE: u8 = 102;
+const COSIGNER: u8 = 103;
+const SPONSOR: u8 = 104;
+
+/// A funded pool and its first farmer, with a reward account
+struct Farm {
+    context: ProgramTestContext,
+    pool: TestPool,
+    farmer: Keypair,
+    /// Token accounts created so far, each from its own keypair
+    token_accounts: u8,
+}
+
+impl Farm {
+    async fn new() -> Self {
+        let (mut context, pool) = PoolFixture::new()
+            .with_funded_treasury(TREASURY_FUNDS)
+            .start()
+            .await
+            .expect("fixture bootstraps");
+        let farmer = pool.farmer(0);
+        airdrop(&mut context, &farmer.pubkey(), LAMPORTS_PER_SOL)
+            .await
+            .expect("farmer funded");
+        pool.create_reward_account(&mut context, &farmer.pubkey())
+            .await
+            .expect("reward account created");
+        Self {
+            context,
+            pool,
+            farmer,
+            token_accounts: 0,
+        }
+    }
+
+    async fn now(&mut self) -> i64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        clock.unix_timestamp
+    }
+
+    /// Moves the bank's clock `seconds` forward, on a new slot so that
+    /// retrying a rejected instruction is not a duplicate transaction
+    async fn advance_clock(&mut self, seconds: i64) {
+        let slot = self
+            .context
+            .banks_client
+            .get_root_slot()
+            .await
+            .expect("slot");
+        self.context.warp_to_slot(slot + 2).expect("warped");
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    async fn process(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        process_instructions(&mut self.context, &[instruction], signers).await
+    }
+
+    /// Records `REWARD` for the farmer under `task_id` and returns the
+    /// amount booked as their pending reward
+    async fn record(&mut self, task_id: &str) -> u64 {
+        let stats_day = DailyStats::day_of(self.now().await);
+        let record = instructions::record_reward(
+            &self.pool.accounts,
+            &self.pool.platform_authority.pubkey(),
+            &self.farmer.pubkey(),
+            REWARD,
+            task_id.to_string(),
+            RewardAttribution::default(),
+            stats_day,
+        );
+        let authority = self.pool.platform_authority.insecure_clone();
+        self.process(record, &[&authority])
+            .await
+            .expect("reward recorded");
+        let pending: PendingReward = get_state(
+            &mut self.context.banks_client,
+            &self
+                .pool
+                .accounts
+                .pending_reward(&self.farmer.pubkey(), task_id),
+        )
+        .await;
+        pending.amount
+    }
+
+    /// Creates a token account of the reward mint owned by `owner`
+    async fn token_account(&mut self, owner: &Pubkey) -> Pubkey {
+        let account = fixture_keypair(0, TOKEN_ACCOUNT, self.token_accounts);
+        self.token_accounts += 1;
+        token::create_token_account(
+            &mut self.context,
+            &account,
+            &self.pool.accounts.reward_mint,
+            owner,
+        )
+        .await
+        .expect("token account created");
+        account.pubkey()
+    }
+
+    async fn balance(&mut self, account: &Pubkey) -> u64 {
+        token::token_balance(&mut self.context, account)
+            .await
+            .expect("balance read")
+    }
+
+    async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(*address)
+            .await
+            .expect("lamports read")
+    }
+
+    /// Registers `payout_address` for the farmer and waits until it is in
+    /// effect
+    async fn lock_to_payout_address(&mut self, payout_address: &Pubkey) {
+        let register = instructions::register_payout_address(
+            &self.pool.accounts,
+            &self.farmer.pubkey(),
+            payout_address,
+        );
+        let farmer = self.farmer.insecure_clone();
+        self.process(register, &[&farmer])
+            .await
+            .expect("payout address registered");
+        self.advance_clock(WHITELIST_ACTIVATION_DELAY).await;
+    }
+
+    /// Requires `cosigner` on the farmer's withdrawals
+    async fn set_cosigner(&mut self, cosigner: &Keypair) {
+        let set_cosigner = instructions::set_withdrawal_cosigner(
+            &self.pool.accounts,
+            &self.farmer.pubkey(),
+            &cosigner.pubkey(),
+            Some(&cosigner.pubkey()),
+        );
+        let farmer = self.farmer.insecure_clone();
+        self.process(set_cosigner, &[&farmer, cosigner])
+            .await
+            .expect("cosigner set");
+    }
+
+    /// Whitelists `destination` for the farmer and waits until it is usable
+    async fn whitelist(&mut self, destination: &Pubkey) {
+        let add = instructions::add_withdrawal_destination(
+            &self.pool.accounts,
+            &self.farmer.pubkey(),
+            destination,
+        );
+        let farmer = self.farmer.insecure_clone();
+        self.process(add, &[&farmer])
+            .await
+            .expect("destination whitelisted");
+        self.advance_clock(WHITELIST_ACTIVATION_DELAY).await;
+    }
+
+    fn claim_many(&self, task_ids: &[&str]) -> Instruction {
+        let pending_rewards: Vec<Pubkey> = task_ids
+            .iter()
+            .map(|task_id| {
+                self.pool
+                    .accounts
+                    .pending_reward(&self.farmer.pubkey(), task_id)
+            })
+            .collect();
+        instructions::claim_many(&self.pool.accounts, &self.farmer.pubkey(), &pending_rewards)
+    }
+
+    async fn withdraw_reward(
+        &mut self,
+        task_id: &str,
+        destination: &Pubkey,
+        amount: u64,
+        nonce: u64,
+    ) -> Instruction {
+        let stats_day = DailyStats::day_of(self.now().await);
+        instructions::withdraw_reward(
+            &self.pool.accounts,
+            &self.farmer.pubkey(),
+            task_id,
+            destination,
+            amount,
+            nonce,
+            stats_day,
+        )
+    }
+}
+
+/// Points a claim at `destination` instead of the farmer's reward account
+fn claim_to(mut claim: Instruction, destination: &Pubkey) -> Instruction {
+    claim.accounts[3].pubkey = *destination;
+    claim
+}
+
+#[tokio::test]
+async fn claims_go_to_the_payout_address_in_effect() {
+    let mut farm = Farm::new().await;
+    let amount = farm.record("task-1").await;
+    let payout_wallet = fixture_keypair(0, PAYOUT_WALLET, 0).pubkey();
+    farm.lock_to_payout_address(&payout_wallet).await;
+    let farmer = farm.farmer.insecure_clone();
+    let reward_account = farm.pool.accounts.reward_account(&farmer.pubkey());
+
+    // Neither path pays the farmer's own account any more
+    let withdraw = farm
+        .withdraw_reward("task-1", &reward_account, amount, 1)
+        .await;
+    assert_pool_error(
+        farm.process(withdraw, &[&farmer]).await,
+        RewardPoolError::DestinationNotPayoutAddress,
+    );
+    let claim = farm.claim_many(&["task-1"]);
+    assert_pool_error(
+        farm.process(claim, &[&farmer]).await,
+        RewardPoolError::DestinationNotPayoutAddress,
+    );
+
+    let cold_account = farm.token_account(&payout_wallet).await;
+    let claim = claim_to(farm.claim_many(&["task-1"]), &cold_account);
+    farm.process(claim, &[&farmer])
+        .await
+        .expect("claimed to the payout address");
+    assert_eq!(farm.balance(&cold_account).await, amount);
+    assert_eq!(farm.balance(&reward_account).await, 0);
+}
+
+#[tokio::test]
+async fn guild_vault_must_belong_to_the_payout_address() {
+    let mut farm = Farm::new().await;
+    let payout_wallet = fixture_keypair(0, PAYOUT_WALLET, 0).pubkey();
+    farm.lock_to_payout_address(&payout_wallet).await;
+    let farmer = farm.farmer.insecure_clone();
+
+    let guild_wallet = Keypair::new().pubkey();
+    let guild_vault = farm.token_account(&guild_wallet).await;
+    let set_split = instructions::set_guild_split(
+        &farm.pool.accounts,
+        &farmer.pubkey(),
+        Some(&guild_vault),
+        2_000,
+    );
+    assert_pool_error(
+        farm.process(set_split, &[&farmer]).await,
+        RewardPoolError::DestinationNotPayoutAddress,
+    );
+
+    let cold_vault = farm.token_account(&payout_wallet).await;
+    let set_split = instructions::set_guild_split(
+        &farm.pool.accounts,
+        &farmer.pubkey(),
+        Some(&cold_vault),
+        2_000,
+    );
+    farm.process(set_split, &[&farmer])
+        .await
+        .expect("split to an account of the payout address");
+}
+
+#[tokio::test]
+async fn claims_only_go_to_whitelisted_destinations() {
+    let mut farm = Farm::new().await;
+    let amount = farm.record("task-1").await;
+    let exchange = fixture_keypair(0, EXCHANGE, 0).pubkey();
+    let deposit_account = farm.token_account(&exchange).await;
+    farm.whitelist(&deposit_account).await;
+    let farmer = farm.farmer.insecure_clone();
+
+    // The farmer's own account is not on the whitelist
+    let claim = farm.claim_many(&["task-1"]);
+    assert_pool_error(
+        farm.process(claim, &[&farmer]).await,
+        RewardPoolError::DestinationNotWhitelisted,
+    );
+    let guild_vault = farm.token_account(&Keypair::new().pubkey()).await;
+    let set_split = instructions::set_guild_split(
+        &farm.pool.accounts,
+        &farmer.pubkey(),
+        Some(&guild_vault),
+        2_000,
+    );
+    assert_pool_error(
+        farm.process(set_split, &[&farmer]).await,
+        RewardPoolError::DestinationNotWhitelisted,
+    );
+
+    let claim = claim_to(farm.claim_many(&["task-1"]), &deposit_account);
+    farm.process(claim, &[&farmer])
+        .await
+        .expect("claimed to the whitelisted account");
+    assert_eq!(farm.balance(&deposit_account).await, amount);
+}
+
+#[tokio::test]
+async fn the_cosigner_signs_every_payout_out_of_custody() {
+    let mut farm = Farm::new().await;
+    let amount = farm.record("task-1").await;
+    let cosigner = fixture_keypair(0, COSIGNER, 0);
+    farm.set_cosigner(&cosigner).await;
+    let farmer = farm.farmer.insecure_clone();
+    let accounts = farm.pool.accounts;
+
+    let claim_across_pools = instructions::claim_across_pools(
+        &accounts.program_id,
+        &farmer.pubkey(),
+        &[(
+            accounts,
+            vec![accounts.pending_reward(&farmer.pubkey(), "task-1")],
+        )],
+    );
+    let guild_vault = farm.token_account(&Keypair::new().pubkey()).await;
+    let set_split =
+        instructions::set_guild_split(&accounts, &farmer.pubkey(), Some(&guild_vault), 2_000);
+    let transfer = instructions::transfer_pending(
+        &accounts,
+        &farmer.pubkey(),
+        "task-1",
+        &Keypair::new().pubkey(),
+    );
+    for instruction in [
+        farm.claim_many(&["task-1"]),
+        claim_across_pools,
+        set_split,
+        transfer,
+    ] {
+        assert_pool_error(
+            farm.process(instruction, &[&farmer]).await,
+            RewardPoolError::MissingWithdrawalCosigner,
+        );
+    }
+
+    let claim = instructions::cosigned_withdrawal(farm.claim_many(&["task-1"]), &cosigner.pubkey());
+    farm.process(claim, &[&farmer, &cosigner])
+        .await
+        .expect("claim cosigned");
+    let reward_account = accounts.reward_account(&farmer.pubkey());
+    assert_eq!(farm.balance(&reward_account).await, amount);
+}
+
+#[tokio::test]
+async fn claims_burn_the_pools_withdraw_burn() {
+    let mut farm = Farm::new().await;
+    let amount = farm.record("task-1").await;
+    let authority = farm.pool.platform_authority.insecure_clone();
+    let set_burn = instructions::set_withdraw_burn(&farm.pool.accounts, &authority.pubkey(), 500);
+    farm.process(set_burn, &[&authority])
+        .await
+        .expect("withdraw burn set");
+    let reward_mint = farm.pool.accounts.reward_mint;
+    let supply = token::mint_supply(&mut farm.context, &reward_mint)
+        .await
+        .expect("supply read");
+
+    let farmer = farm.farmer.insecure_clone();
+    let claim = farm.claim_many(&["task-1"]);
+    farm.process(claim, &[&farmer]).await.expect("claimed");
+
+    let burned = amount * 500 / 10_000;
+    let reward_account = farm.pool.accounts.reward_account(&farmer.pubkey());
+    assert_eq!(farm.balance(&reward_account).await, amount - burned);
+    let supply_after = token::mint_supply(&mut farm.context, &reward_mint)
+        .await
+        .expect("supply read");
+    assert_eq!(supply_after, supply - burned);
+}
+
+#[tokio::test]
+async fn claims_pay_the_pools_withdrawal_fee() {
+    const FEE: u64 = 5_000;
+    let mut farm = Farm::new().await;
+    let amount = farm.record("task-1").await;
+    let authority = farm.pool.platform_authority.insecure_clone();
+    let set_fee =
+        instructions::set_withdrawal_fee(&farm.pool.accounts, &authority.pubkey(), FEE, false, 0);
+    farm.process(set_fee, &[&authority])
+        .await
+        .expect("withdrawal fee set");
+    let fee_vault = farm.pool.accounts.fee_vault();
+    let collected = farm.lamports(&fee_vault).await;
+
+    let farmer = farm.farmer.insecure_clone();
+    let claim = farm.claim_many(&["task-1"]);
+    farm.process(claim, &[&farmer]).await.expect("claimed");
+
+    assert_eq!(farm.lamports(&fee_vault).await, collected + FEE);
+    let reward_account = farm.pool.accounts.reward_account(&farmer.pubkey());
+    assert_eq!(farm.balance(&reward_account).await, amount);
+}
+
+#[tokio::test]
+async fn withdrawals_wait_for_their_turn_in_a_recovery_window() {
+    const RECOVERY_WINDOW: i64 = 3_600;
+    let mut farm = Farm::new().await;
+    let amount = farm.record("task-1").await;
+    let authority = farm.pool.platform_authority.insecure_clone();
+    let accounts = farm.pool.accounts;
+    let set_queue = instructions::set_payout_queue(&accounts, &authority.pubkey(), RECOVERY_WINDOW);
+    farm.process(set_queue, &[&authority])
+        .await
+        .expect("payout queue set");
+
+    // Another farmer queues up while the pool is paused; ours takes no ticket
+    let queued = farm.pool.farmer(1);
+    airdrop(&mut farm.context, &queued.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .expect("queued farmer funded");
+    let stats_day = DailyStats::day_of(farm.now().await);
+    let record = instructions::record_reward(
+        &accounts,
+        &authority.pubkey(),
+        &queued.pubkey(),
+        REWARD,
+        "task-2".to_string(),
+        RewardAttribution::default(),
+        stats_day,
+    );
+    farm.process(record, &[&authority])
+        .await
+        .expect("reward recorded");
+    let pause = instructions::pause_pool(&accounts, &authority.pubkey());
+    farm.process(pause, &[&authority]).await.expect("paused");
+    let take_ticket = instructions::take_payout_ticket(
+        &accounts,
+        &queued.pubkey(),
+        &[accounts.pending_reward(&queued.pubkey(), "task-2")],
+    );
+    farm.process(take_ticket, &[&queued])
+        .await
+        .expect("ticket taken");
+    let resume = instructions::resume_pool(&accounts, &authority.pubkey());
+    farm.process(resume, &[&authority]).await.expect("resumed");
+
+    let farmer = farm.farmer.insecure_clone();
+    let reward_account = accounts.reward_account(&farmer.pubkey());
+    let withdraw = farm
+        .withdraw_reward("task-1", &reward_account, amount, 1)
+        .await;
+    assert_pool_error(
+        farm.process(withdraw, &[&farmer]).await,
+        RewardPoolError::PayoutTurnNotReached,
+    );
+
+    farm.advance_clock(RECOVERY_WINDOW).await;
+    let withdraw = farm
+        .withdraw_reward("task-1", &reward_account, amount, 1)
+        .await;
+    farm.process(withdraw, &[&farmer])
+        .await
+        .expect("withdrawn once the window closed");
+    assert_eq!(farm.balance(&reward_account).await, amount);
+}
+
+#[tokio::test]
+async fn withdrawals_count_against_the_withdrawal_ramp() {
+    let mut farm = Farm::new().await;
+    let amount = farm.record("task-1").await;
+    farm.record("task-2").await;
+    let authority = farm.pool.platform_authority.insecure_clone();
+    let accounts = farm.pool.accounts;
+    let pause = instructions::pause_pool(&accounts, &authority.pubkey());
+    farm.process(pause, &[&authority]).await.expect("paused");
+    let resume = instructions::resume_with_ramp(&accounts, &authority.pubkey(), amount, amount, 4);
+    farm.process(resume, &[&authority])
+        .await
+        .expect("resumed with a ramp");
+
+    let farmer = farm.farmer.insecure_clone();
+    let reward_account = accounts.reward_account(&farmer.pubkey());
+    let withdraw = farm
+        .withdraw_reward("task-1", &reward_account, amount, 1)
+        .await;
+    farm.process(withdraw, &[&farmer])
+        .await
+        .expect("withdrawn within the epoch's cap");
+    let withdraw = farm
+        .withdraw_reward("task-2", &reward_account, amount, 2)
+        .await;
+    assert_pool_error(
+        farm.process(withdraw, &[&farmer]).await,
+        RewardPoolError::WithdrawalRampLimitExceeded,
+    );
+    assert_eq!(farm.balance(&reward_account).await, amount);
+}
+
+#[tokio::test]
+async fn withdrawals_reject_a_nonce_already_withdrawn_under() {
+    let mut farm = Farm::new().await;
+    let amount = farm.record("task-1").await;
+    farm.record("task-2").await;
+    let farmer = farm.farmer.insecure_clone();
+    let reward_account = farm.pool.accounts.reward_account(&farmer.pubkey());
+    let withdraw = farm
+        .withdraw_reward("task-1", &reward_account, amount, 7)
+        .await;
+    farm.process(withdraw, &[&farmer])
+        .await
+        .expect("withdrawn under a fresh nonce");
+
+    let withdraw = farm
+        .withdraw_reward("task-2", &reward_account, amount, 7)
+        .await;
+    assert_pool_error(
+        farm.process(withdraw, &[&farmer]).await,
+        RewardPoolError::InvalidNonce,
+    );
+    assert_eq!(farm.balance(&reward_account).await, amount);
+}
+
+#[tokio::test]
+async fn withdrawal_records_close_after_their_retention() {
+    let mut farm = Farm::new().await;
+    let amount = farm.record("task-1").await;
+    let farmer = farm.farmer.insecure_clone();
+    let accounts = farm.pool.accounts;
+    let reward_account = accounts.reward_account(&farmer.pubkey());
+    let withdraw = farm
+        .withdraw_reward("task-1", &reward_account, amount, 1)
+        .await;
+    farm.process(withdraw, &[&farmer]).await.expect("withdrawn");
+
+    let record = accounts.withdrawal_record(&farmer.pubkey(), 1);
+    let record_state: WithdrawalRecord = get_state(&mut farm.context.banks_client, &record).await;
+    assert_eq!(record_state.payer, farmer.pubkey());
+    let close =
+        instructions::close_withdrawal_records(&accounts, &farmer.pubkey(), &[record_state]);
+    assert_pool_error(
+        farm.process(close.clone(), &[&farmer]).await,
+        RewardPoolError::WithdrawalRecordRetained,
+    );
+
+    farm.advance_clock(WITHDRAWAL_RECORD_RETENTION).await;
+    let rent = farm.lamports(&record).await;
+    let before = farm.lamports(&farmer.pubkey()).await;
+    farm.process(close, &[&farmer])
+        .await
+        .expect("records closed after a month");
+    assert_eq!(farm.lamports(&record).await, 0);
+    assert_eq!(farm.lamports(&farmer.pubkey()).await, before + rent);
+}
+
+#[tokio::test]
+async fn closed_withdrawal_records_keep_their_nonces_spent() {
+    let mut farm = Farm::new().await;
+    let amount = farm.record("task-1").await;
+    farm.record("task-2").await;
+    let farmer = farm.farmer.insecure_clone();
+    let accounts = farm.pool.accounts;
+    let reward_account = accounts.reward_account(&farmer.pubkey());
+    let balance = farm.balance(&reward_account).await;
+    let withdraw = farm
+        .withdraw_reward("task-1", &reward_account, amount, 5)
+        .await;
+    farm.process(withdraw, &[&farmer]).await.expect("withdrawn");
+
+    farm.advance_clock(WITHDRAWAL_RECORD_RETENTION).await;
+    let record: WithdrawalRecord = get_state(
+        &mut farm.context.banks_client,
+        &accounts.withdrawal_record(&farmer.pubkey(), 5),
+    )
+    .await;
+    let close = instructions::close_withdrawal_records(&accounts, &farmer.pubkey(), &[record]);
+    farm.process(close, &[&farmer])
+        .await
+        .expect("record closed");
+
+    // The closed nonce and those below it stay spent, fresh ones above work
+    for nonce in [5, 4] {
+        let replay = farm
+            .withdraw_reward("task-2", &reward_account, amount, nonce)
+            .await;
+        assert_pool_error(
+            farm.process(replay, &[&farmer]).await,
+            RewardPoolError::InvalidNonce,
+        );
+    }
+    let withdraw = farm
+        .withdraw_reward("task-2", &reward_account, amount, 6)
+        .await;
+    farm.process(withdraw, &[&farmer])
+        .await
+        .expect("withdrawn under a nonce above the closed one");
+    assert_eq!(farm.balance(&reward_account).await, balance + amount * 2);
+}
+
+#[tokio::test]
+async fn closing_a_sponsored_withdrawal_record_refunds_the_sponsor() {
+    let mut farm = Farm::new().await;
+    let amount = farm.record("task-1").await;
+    let farmer = farm.farmer.insecure_clone();
+    let accounts = farm.pool.accounts;
+    let sponsor = fixture_keypair(0, SPONSOR, 0);
+    airdrop(&mut farm.context, &sponsor.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .expect("sponsor funded");
+    let reward_account = accounts.reward_account(&farmer.pubkey());
+    let stats_day = DailyStats::day_of(farm.now().await);
+    let withdraw = instructions::sponsored_withdraw_reward(
+        &accounts,
+        &farmer.pubkey(),
+        "task-1",
+        &reward_account,
+        amount,
+        1,
+        stats_day,
+        &sponsor.pubkey(),
+    );
+    farm.process(withdraw, &[&farmer, &sponsor])
+        .await
+        .expect("sponsored withdrawal");
+
+    farm.advance_clock(WITHDRAWAL_RECORD_RETENTION).await;
+    let address = accounts.withdrawal_record(&farmer.pubkey(), 1);
+    let record: WithdrawalRecord = get_state(&mut farm.context.banks_client, &address).await;
+    assert_eq!(record.payer, sponsor.pubkey());
+
+    // The rent cannot be diverted to the farmer
+    let mut diverted = record;
+    diverted.payer = farmer.pubkey();
+    let close = instructions::close_withdrawal_records(&accounts, &farmer.pubkey(), &[diverted]);
+    assert_pool_error(
+        farm.process(close, &[&farmer]).await,
+        RewardPoolError::InvalidRentRecipient,
+    );
+
+    let rent = farm.lamports(&address).await;
+    let before = farm.lamports(&sponsor.pubkey()).await;
+    let close = instructions::close_withdrawal_records(&accounts, &farmer.pubkey(), &[record]);
+    farm.process(close, &[&farmer])
+        .await
+        .expect("record closed");
+    assert_eq!(farm.lamports(&sponsor.pubkey()).await, before + rent);
+}
+
+#[tokio::test]
+async fn transferred_pending_rewards_leave_their_task_spent() {
+    let mut farm = Farm::new().await;
+    let amount = farm.record("task-1").await;
+    let farmer = farm.farmer.insecure_clone();
+    let accounts = farm.pool.accounts;
+    let new_farmer = fixture_keypair(0, PAYOUT_WALLET, 0).pubkey();
+    let transfer =
+        instructions::transfer_pending(&accounts, &farmer.pubkey(), "task-1", &new_farmer);
+    farm.process(transfer, &[&farmer])
+        .await
+        .expect("pending reward transferred");
+
+    let moved: PendingReward = get_state(
+        &mut farm.context.banks_client,
+        &accounts.pending_reward(&new_farmer, "task-1"),
+    )
+    .await;
+    assert_eq!(moved.amount, amount);
+    let tombstone: PendingReward = get_state(
+        &mut farm.context.banks_client,
+        &accounts.pending_reward(&farmer.pubkey(), "task-1"),
+    )
+    .await;
+    assert_eq!(tombstone.farmer_pubkey, farmer.pubkey());
+    assert_eq!(tombstone.amount, 0);
+    assert!(tombstone.is_withdrawn);
+
+    // The task cannot be booked for the old beneficiary a second time
+    let stats_day = DailyStats::day_of(farm.now().await);
+    let record = instructions::record_reward(
+        &accounts,
+        &farm.pool.platform_authority.pubkey(),
+        &farmer.pubkey(),
+        REWARD,
+        "task-1".to_string(),
+        RewardAttribution::default(),
+        stats_day,
+    );
+    let authority = farm.pool.platform_authority.insecure_clone();
+    assert_pool_error(
+        farm.process(record, &[&authority]).await,
+        RewardPoolError::InvalidPendingRewardAccount,
+    );
+    let withdraw = farm
+        .withdraw_reward(
+            "task-1",
+            &accounts.reward_account(&farmer.pubkey()),
+            amount,
+            1,
+        )
+        .await;
+    assert_pool_error(
+        farm.process(withdraw, &[&farmer]).await,
+        RewardPoolError::NoPendingRewards,
+    );
+}
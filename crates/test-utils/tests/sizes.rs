@@ -0,0 +1,164 @@
+//! A `RecordReward` passing only the accounts every reward needs fits a
+//! legacy transaction. Rewards with more feature accounts, and batches of
+//! rewards, are recorded in v0 transactions against a lookup table.
+
+use std::collections::HashSet;
+
+use reward_pool_client::{
+    batch::pack_reward_batches, instructions, BatchConfig, PoolAccounts, RewardAttribution,
+    RewardEntry, TxBuilder,
+};
+use solana_program::{
+    hash::Hash,
+    message::{AddressLookupTableAccount, VersionedMessage},
+    pubkey::Pubkey,
+};
+
+/// Largest serialized transaction the network accepts
+const MAX_TRANSACTION_SIZE: usize = 1232;
+const STATS_DAY: i64 = 20_000;
+
+fn accounts() -> PoolAccounts {
+    PoolAccounts::for_mint(Pubkey::new_from_array([1; 32]))
+}
+
+fn authority() -> Pubkey {
+    Pubkey::new_from_array([2; 32])
+}
+
+fn entry(index: u8) -> RewardEntry {
+    RewardEntry {
+        farmer: Pubkey::new_from_array([10 + index; 32]),
+        amount: 1_000_000,
+        task_id: format!("task-{index}"),
+        attribution: RewardAttribution::default(),
+    }
+}
+
+fn record_reward(entry: &RewardEntry) -> TxBuilder {
+    TxBuilder::new(accounts(), authority())
+        .stats_day(STATS_DAY)
+        .record_reward(entry.farmer, entry.amount, entry.task_id.clone())
+}
+
+fn transaction_size(message: &VersionedMessage) -> usize {
+    1 + message.header().num_required_signatures as usize * 64 + message.serialize().len()
+}
+
+// Everything but signers and invoked programs, which stay inline
+fn lookup_table(builders: &[TxBuilder]) -> AddressLookupTableAccount {
+    let instructions: Vec<_> = builders
+        .iter()
+        .flat_map(|builder| builder.instructions())
+        .collect();
+    let programs: HashSet<Pubkey> = instructions
+        .iter()
+        .map(|instruction| instruction.program_id)
+        .collect();
+    let mut seen = HashSet::new();
+    let addresses = instructions
+        .iter()
+        .flat_map(|instruction| &instruction.accounts)
+        .filter(|meta| !meta.is_signer && !programs.contains(&meta.pubkey))
+        .map(|meta| meta.pubkey)
+        .filter(|address| seen.insert(*address))
+        .collect();
+    AddressLookupTableAccount {
+        key: Pubkey::new_from_array([3; 32]),
+        addresses,
+    }
+}
+
+#[test]
+fn a_reward_fits_a_legacy_transaction() {
+    let message = VersionedMessage::Legacy(record_reward(&entry(0)).build(Hash::default()));
+    assert!(transaction_size(&message) <= MAX_TRANSACTION_SIZE);
+
+    let no_table = pack_reward_batches(
+        &accounts(),
+        &authority(),
+        &[entry(0)],
+        &[],
+        &BatchConfig::default(),
+        STATS_DAY,
+    )
+    .expect("a reward fits without a lookup table");
+    assert_eq!(no_table.len(), 1);
+}
+
+#[test]
+fn a_reward_with_every_feature_account_fits_against_a_lookup_table() {
+    let farmer = entry(0).farmer;
+    let factory_accounts = PoolAccounts::for_factory(
+        &authority(),
+        Pubkey::new_from_array([1; 32]),
+        0,
+        Pubkey::new_from_array([4; 32]),
+    );
+    let record = instructions::record_reward(
+        &factory_accounts,
+        &authority(),
+        &farmer,
+        1_000_000,
+        "task-0".to_string(),
+        RewardAttribution {
+            idempotency_key: Some([7; 32]),
+            ..RewardAttribution::default()
+        },
+        STATS_DAY,
+    );
+    let record = instructions::memo_recorded_reward(record);
+    let record = instructions::milestone_recorded_reward(record, &factory_accounts, &farmer, 0);
+    let record = instructions::rebate_recorded_reward(record, &factory_accounts, &farmer);
+    assert_eq!(record.accounts.len(), 40);
+
+    let builder = TxBuilder::new(factory_accounts, authority()).instruction(record);
+    let message = VersionedMessage::Legacy(builder.build(Hash::default()));
+    assert!(transaction_size(&message) > MAX_TRANSACTION_SIZE);
+    let table = lookup_table(std::slice::from_ref(&builder));
+    let message = builder
+        .build_v0(Hash::default(), std::slice::from_ref(&table))
+        .expect("message compiles");
+    assert!(transaction_size(&message) <= MAX_TRANSACTION_SIZE);
+}
+
+#[test]
+fn a_reward_fits_a_v0_transaction_against_a_lookup_table() {
+    let builder = record_reward(&entry(0));
+    let table = lookup_table(std::slice::from_ref(&builder));
+    let message = builder
+        .build_v0(Hash::default(), std::slice::from_ref(&table))
+        .expect("message compiles");
+    assert!(transaction_size(&message) <= MAX_TRANSACTION_SIZE);
+}
+
+#[test]
+fn a_reward_fits_against_the_pool_lookup_table() {
+    let table = AddressLookupTableAccount {
+        key: Pubkey::new_from_array([3; 32]),
+        addresses: instructions::record_reward_lookup_addresses(&accounts()),
+    };
+    let message = record_reward(&entry(0))
+        .build_v0(Hash::default(), &[table])
+        .expect("message compiles");
+    assert!(transaction_size(&message) <= MAX_TRANSACTION_SIZE);
+}
+
+#[test]
+fn batches_pack_against_a_lookup_table() {
+    let entries: Vec<RewardEntry> = (0..4).map(entry).collect();
+    let builders: Vec<TxBuilder> = entries.iter().map(record_reward).collect();
+    let table = lookup_table(&builders);
+
+    let batches = pack_reward_batches(
+        &accounts(),
+        &authority(),
+        &entries,
+        &[table],
+        &BatchConfig::default(),
+        STATS_DAY,
+    )
+    .expect("every entry fits");
+    assert_eq!(batches.first().map(|batch| batch.start), Some(0));
+    assert_eq!(batches.last().map(|batch| batch.end), Some(entries.len()));
+}
@@ -0,0 +1,489 @@
+//! The cranks swapping protocol funds through a DEX, run against the mock
+//! DEX. Anyone can call them, so what they may spend must never include
+//! the rewards the treasury vault holds for farmers.
+
+use mock_dex::Leg;
+use reward_pool::{DailyStats, RewardPool, RewardPoolError, ScheduledAction, MIN_ACTION_DELAY};
+use reward_pool_client::{instructions, RewardAttribution};
+use reward_pool_test_utils::{
+    assert_pool_error, fixture_keypair, get_state, process_instructions, token, BanksClientError,
+    PoolFixture, ProgramTestContext, TestPool,
+};
+use solana_keypair::Keypair;
+use solana_program::{
+    clock::Clock,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use solana_signer::Signer;
+
+const TREASURY_FUNDS: u64 = 10_000_000;
+const VAULT_FUNDS: u64 = 1_000_000;
+const RESERVE_FUNDS: u64 = 1_000_000_000;
+const REWARD: u64 = 100_000;
+
+// Keypair roles of the accounts the tests add to the fixture's
+const MINT: u8 = 100;
+const MINT_AUTHORITY: u8 = 101;
+const RESERVE: u8 = 102;
+
+/// A pool with a funded treasury vault, next to a DEX holding reserves of
+/// the reward mint, of a second mint and of LP tokens of the pair
+struct Market {
+    context: ProgramTestContext,
+    pool: TestPool,
+    /// Mint the DEX pays out for reward tokens
+    other_mint: Pubkey,
+    lp_mint: Pubkey,
+    mint_authority: Keypair,
+    reward_reserve: Pubkey,
+    other_reserve: Pubkey,
+    lp_reserve: Pubkey,
+}
+
+impl Market {
+    async fn new() -> Self {
+        let (mut context, pool) = PoolFixture::new()
+            .with_funded_treasury(TREASURY_FUNDS)
+            .with_funded_vault(VAULT_FUNDS)
+            .start()
+            .await
+            .expect("fixture bootstraps");
+        let mint_authority = fixture_keypair(0, MINT_AUTHORITY, 0);
+        let (dex_authority, _) = mock_dex::find_authority_address(&mock_dex::id());
+        let reward_reserve = fixture_keypair(0, RESERVE, 0);
+        token::create_token_account(
+            &mut context,
+            &reward_reserve,
+            &pool.accounts.reward_mint,
+            &dex_authority,
+        )
+        .await
+        .expect("reserve created");
+
+        // The other mint and the LP mint come with a funded reserve each
+        let mut mints = Vec::new();
+        for index in 0..2 {
+            let mint = fixture_keypair(0, MINT, index);
+            token::create_mint(&mut context, &mint, &mint_authority.pubkey(), 6)
+                .await
+                .expect("mint created");
+            let reserve = fixture_keypair(0, RESERVE, index + 1);
+            token::create_token_account(&mut context, &reserve, &mint.pubkey(), &dex_authority)
+                .await
+                .expect("reserve created");
+            token::mint_to(
+                &mut context,
+                &mint.pubkey(),
+                &mint_authority,
+                &reserve.pubkey(),
+                RESERVE_FUNDS,
+            )
+            .await
+            .expect("reserve funded");
+            mints.push((mint.pubkey(), reserve.pubkey()));
+        }
+
+        Self {
+            context,
+            pool,
+            other_mint: mints[0].0,
+            lp_mint: mints[1].0,
+            mint_authority,
+            reward_reserve: reward_reserve.pubkey(),
+            other_reserve: mints[0].1,
+            lp_reserve: mints[1].1,
+        }
+    }
+
+    async fn process(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        process_instructions(&mut self.context, &[instruction], signers).await
+    }
+
+    /// Runs an instruction only the platform authority signs
+    async fn process_as_authority(
+        &mut self,
+        instruction: Instruction,
+    ) -> Result<(), BanksClientError> {
+        let authority = self.pool.platform_authority.insecure_clone();
+        self.process(instruction, &[&authority]).await
+    }
+
+    /// Records `REWARD` for farmer 0 under `task_id`, paying its fee
+    async fn record(&mut self, task_id: &str) {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        let record = instructions::record_reward(
+            &self.pool.accounts,
+            &self.pool.platform_authority.pubkey(),
+            &self.pool.farmer(0).pubkey(),
+            REWARD,
+            task_id.to_string(),
+            RewardAttribution::default(),
+            DailyStats::day_of(clock.unix_timestamp),
+        );
+        self.process_as_authority(record)
+            .await
+            .expect("reward recorded");
+    }
+
+    /// Moves the bank's clock `seconds` forward and returns the new time
+    async fn advance_clock(&mut self, seconds: i64) -> i64 {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.expect("clock");
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+        clock.unix_timestamp
+    }
+
+    async fn pool_state(&mut self) -> RewardPool {
+        get_state(&mut self.context.banks_client, &self.pool.accounts.pool).await
+    }
+
+    async fn balance(&mut self, account: &Pubkey) -> u64 {
+        token::token_balance(&mut self.context, account)
+            .await
+            .expect("balance read")
+    }
+
+    async fn supply(&mut self, mint: &Pubkey) -> u64 {
+        token::mint_supply(&mut self.context, mint)
+            .await
+            .expect("supply read")
+    }
+
+    /// A leg paying `amount` out of the DEX reserve `reserve`
+    fn from_reserve(reserve: &Pubkey, destination: &Pubkey, amount: u64) -> Leg {
+        let (dex_authority, _) = mock_dex::find_authority_address(&mock_dex::id());
+        Leg {
+            source: *reserve,
+            destination: *destination,
+            owner: dex_authority,
+            amount,
+        }
+    }
+
+    /// A leg paying `amount` out of `source`, a self-owned vault of the pool
+    fn from_vault(source: &Pubkey, reserve: &Pubkey, amount: u64) -> Leg {
+        Leg {
+            source: *source,
+            destination: *reserve,
+            owner: *source,
+            amount,
+        }
+    }
+
+    /// A swap of `spent` reward tokens out of `source` for `received` of
+    /// the other mint paid to `destination`
+    fn swap(
+        &self,
+        source: &Pubkey,
+        spent: u64,
+        destination: &Pubkey,
+        received: u64,
+    ) -> Instruction {
+        mock_dex::swap(
+            &mock_dex::id(),
+            &[
+                Self::from_vault(source, &self.reward_reserve, spent),
+                Self::from_reserve(&self.other_reserve, destination, received),
+            ],
+        )
+    }
+
+    /// Buys back the other mint through the DEX for all the fees collected
+    async fn configure_buyback(&mut self) {
+        let authority = self.pool.platform_authority.pubkey();
+        let configure = instructions::configure_buyback(
+            &self.pool.accounts,
+            &authority,
+            Some(mock_dex::id()),
+            &self.other_mint,
+            10_000,
+            0,
+            &[authority],
+        );
+        self.process_as_authority(configure)
+            .await
+            .expect("buyback configured");
+    }
+
+    /// Contributes all the fees collected to a pair of the reward and other
+    /// mints through the DEX
+    async fn set_protocol_liquidity(
+        &mut self,
+        amm_program: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let authority = self.pool.platform_authority.pubkey();
+        let set_liquidity = instructions::set_protocol_liquidity(
+            &self.pool.accounts,
+            &authority,
+            vec![amm_program],
+            &self.other_mint,
+            &self.lp_mint,
+            10_000,
+            0,
+            &[authority],
+        );
+        self.process_as_authority(set_liquidity).await
+    }
+
+    /// A contribution swapping half of `contributed` into the other mint
+    /// and depositing both halves for `lp` LP tokens
+    fn contribute_liquidity(&self, contributed: u64, lp: u64) -> Instruction {
+        let accounts = &self.pool.accounts;
+        let half = contributed / 2;
+        let swap = self.swap(
+            &accounts.pol_vault(),
+            half,
+            &accounts.pol_pair_vault(),
+            half,
+        );
+        let deposit = mock_dex::swap(
+            &mock_dex::id(),
+            &[
+                Self::from_vault(
+                    &accounts.pol_vault(),
+                    &self.reward_reserve,
+                    contributed - half,
+                ),
+                Self::from_vault(&accounts.pol_pair_vault(), &self.other_reserve, half),
+                Self::from_reserve(&self.lp_reserve, &accounts.pol_lp_vault(), lp),
+            ],
+        );
+        instructions::contribute_liquidity(
+            accounts,
+            &self.mint_authority.pubkey(),
+            &mock_dex::id(),
+            swap.data,
+            &swap.accounts,
+            deposit.data,
+            &deposit.accounts,
+        )
+    }
+
+    /// Lets queued swaps turn `epoch_limit_bps` of the fee vault into the
+    /// other mint each epoch
+    async fn set_treasury_swap_policy(&mut self, epoch_limit_bps: u16) {
+        let authority = self.pool.platform_authority.pubkey();
+        let set_policy = instructions::set_treasury_swap_policy(
+            &self.pool.accounts,
+            &authority,
+            Some(mock_dex::id()),
+            vec![self.other_mint],
+            epoch_limit_bps,
+            &[authority],
+        );
+        self.process_as_authority(set_policy)
+            .await
+            .expect("treasury swap policy set");
+    }
+
+    /// Queues a swap of `spent` reward tokens for as many of the other
+    /// mint, waits out the queue and returns its execution
+    async fn queue_treasury_swap(&mut self, spent: u64) -> Instruction {
+        let accounts = self.pool.accounts;
+        let reserve = accounts.treasury_reserve(&self.other_mint);
+        let swap = self.swap(&accounts.platform_fee_vault(), spent, &reserve, spent);
+        let action = ScheduledAction::SwapTreasury {
+            output_mint: self.other_mint,
+            amount: spent,
+            min_output: spent,
+            swap_data: swap.data,
+        };
+        let eta = self.advance_clock(0).await + MIN_ACTION_DELAY;
+        let authority = self.pool.platform_authority.pubkey();
+        let schedule =
+            instructions::schedule_action(&accounts, &authority, 1, eta, action.clone(), &[]);
+        self.process_as_authority(schedule)
+            .await
+            .expect("swap queued");
+        self.advance_clock(MIN_ACTION_DELAY).await;
+
+        let mut execute =
+            instructions::execute_action(&accounts, &authority, 1, &authority, &action);
+        execute
+            .accounts
+            .push(AccountMeta::new_readonly(mock_dex::id(), false));
+        // The program signs for the fee vault, the transaction must not
+        execute
+            .accounts
+            .extend(swap.accounts.into_iter().map(|meta| AccountMeta {
+                is_signer: meta.is_signer && meta.pubkey != accounts.platform_fee_vault(),
+                ..meta
+            }));
+        execute
+    }
+
+    fn run_buyback(&self, spent: u64, received: u64) -> Instruction {
+        let swap = self.swap(
+            &self.pool.accounts.buyback_source(),
+            spent,
+            &self.pool.accounts.buyback_output(),
+            received,
+        );
+        instructions::run_buyback(
+            &self.pool.accounts,
+            &self.mint_authority.pubkey(),
+            &self.other_mint,
+            &mock_dex::id(),
+            swap.data,
+            &swap.accounts,
+        )
+    }
+}
+
+#[tokio::test]
+async fn rewards_move_their_fee_to_the_fee_vault() {
+    let mut market = Market::new().await;
+    market.record("task-1").await;
+    market.record("task-2").await;
+
+    let fees = market.pool_state().await.total_platform_fees_collected;
+    assert!(fees > 0);
+    let fee_vault = market.pool.accounts.platform_fee_vault();
+    assert_eq!(market.balance(&fee_vault).await, fees);
+}
+
+#[tokio::test]
+async fn buybacks_spend_the_fee_vault_and_burn_the_output() {
+    let mut market = Market::new().await;
+    market.configure_buyback().await;
+    market.record("task-1").await;
+    let fees = market.pool_state().await.total_platform_fees_collected;
+    let vault = market.pool.accounts.treasury_vault();
+    let vault_before = market.balance(&vault).await;
+    let other_mint = market.other_mint;
+    let supply_before = market.supply(&other_mint).await;
+
+    let run = market.run_buyback(fees, 2 * fees);
+    let caller = market.mint_authority.insecure_clone();
+    market.process(run, &[&caller]).await.expect("buyback run");
+
+    let fee_vault = market.pool.accounts.platform_fee_vault();
+    assert_eq!(market.balance(&fee_vault).await, 0);
+    assert_eq!(market.balance(&vault).await, vault_before);
+    let output = market.pool.accounts.buyback_output();
+    assert_eq!(market.balance(&output).await, 0);
+    assert_eq!(market.supply(&other_mint).await, supply_before - 2 * fees);
+}
+
+#[tokio::test]
+async fn buybacks_never_spend_the_treasury_vault() {
+    let mut market = Market::new().await;
+    market.record("task-1").await;
+    // Fees collected before the crank existed are not bought back, so the
+    // run has nothing to spend but the rewards the vault holds
+    market.configure_buyback().await;
+    let vault = market.pool.accounts.treasury_vault();
+    let vault_before = market.balance(&vault).await;
+
+    let run = market.run_buyback(REWARD, REWARD);
+    let caller = market.mint_authority.insecure_clone();
+    assert_pool_error(
+        market.process(run, &[&caller]).await,
+        RewardPoolError::InsufficientAmount,
+    );
+    assert_eq!(market.balance(&vault).await, vault_before);
+}
+
+#[tokio::test]
+async fn protocol_liquidity_refuses_the_pool_program_as_amm() {
+    let mut market = Market::new().await;
+    let program_id = market.pool.accounts.program_id;
+    assert_pool_error(
+        market.set_protocol_liquidity(program_id).await,
+        RewardPoolError::InvalidProtocolLiquidity,
+    );
+}
+
+#[tokio::test]
+async fn contributions_spend_the_fee_vault_and_keep_the_lp_tokens() {
+    let mut market = Market::new().await;
+    market
+        .set_protocol_liquidity(mock_dex::id())
+        .await
+        .expect("protocol liquidity set");
+    market.record("task-1").await;
+    let fees = market.pool_state().await.total_platform_fees_collected;
+    let vault = market.pool.accounts.treasury_vault();
+    let vault_before = market.balance(&vault).await;
+
+    let contribute = market.contribute_liquidity(fees, 3 * fees);
+    let caller = market.mint_authority.insecure_clone();
+    market
+        .process(contribute, &[&caller])
+        .await
+        .expect("liquidity contributed");
+
+    let accounts = market.pool.accounts;
+    assert_eq!(market.balance(&accounts.platform_fee_vault()).await, 0);
+    assert_eq!(market.balance(&vault).await, vault_before);
+    assert_eq!(market.balance(&accounts.pol_vault()).await, 0);
+    assert_eq!(market.balance(&accounts.pol_lp_vault()).await, 3 * fees);
+}
+
+#[tokio::test]
+async fn contributions_never_spend_the_treasury_vault() {
+    let mut market = Market::new().await;
+    market.record("task-1").await;
+    // As with buybacks, fees collected before contributions existed are
+    // not used
+    market
+        .set_protocol_liquidity(mock_dex::id())
+        .await
+        .expect("protocol liquidity set");
+    let vault = market.pool.accounts.treasury_vault();
+    let vault_before = market.balance(&vault).await;
+
+    let contribute = market.contribute_liquidity(REWARD, REWARD);
+    let caller = market.mint_authority.insecure_clone();
+    assert_pool_error(
+        market.process(contribute, &[&caller]).await,
+        RewardPoolError::InsufficientAmount,
+    );
+    assert_eq!(market.balance(&vault).await, vault_before);
+}
+
+#[tokio::test]
+async fn treasury_swaps_spend_the_fee_vault() {
+    let mut market = Market::new().await;
+    market.set_treasury_swap_policy(10_000).await;
+    market.record("task-1").await;
+    let fees = market.pool_state().await.total_platform_fees_collected;
+    let vault = market.pool.accounts.treasury_vault();
+    let vault_before = market.balance(&vault).await;
+
+    let execute = market.queue_treasury_swap(fees).await;
+    market
+        .process_as_authority(execute)
+        .await
+        .expect("treasury swapped");
+
+    let accounts = market.pool.accounts;
+    assert_eq!(market.balance(&accounts.platform_fee_vault()).await, 0);
+    assert_eq!(market.balance(&vault).await, vault_before);
+    let reserve = accounts.treasury_reserve(&market.other_mint);
+    assert_eq!(market.balance(&reserve).await, fees);
+}
+
+#[tokio::test]
+async fn treasury_swaps_stay_within_the_fee_vault_share() {
+    let mut market = Market::new().await;
+    market.set_treasury_swap_policy(5_000).await;
+    market.record("task-1").await;
+    let fees = market.pool_state().await.total_platform_fees_collected;
+    let vault = market.pool.accounts.treasury_vault();
+    let vault_before = market.balance(&vault).await;
+
+    // Half the fee vault is the epoch's budget, however much the treasury
+    // vault holds
+    let execute = market.queue_treasury_swap(fees).await;
+    assert_pool_error(
+        market.process_as_authority(execute).await,
+        RewardPoolError::TreasurySwapLimitExceeded,
+    );
+    assert_eq!(market.balance(&vault).await, vault_before);
+}
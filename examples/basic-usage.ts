@@ -19,12 +19,11 @@ async function basicUsage() {
 
     // Create keypairs for example
     const platformAuthority = Keypair.generate();
-    const poolAccount = Keypair.generate();
     const rewardMint = new PublicKey('EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v'); // USDC
-    const platformTreasury = new PublicKey('22222222222222222222222222222222');
+    const poolAccount = client.findPoolAddress(rewardMint);
 
     console.log('👑 Platform Authority:', platformAuthority.publicKey.toString());
-    console.log('🏊 Pool Account:', poolAccount.publicKey.toString());
+    console.log('🏊 Pool Account:', poolAccount.toString());
     console.log('🪙 Reward Mint:', rewardMint.toString());
 
     // Example 1: Create initialization instruction
@@ -32,9 +31,7 @@ async function basicUsage() {
 
     const initInstruction = client.createInitializePoolInstruction(
         platformAuthority.publicKey,
-        poolAccount.publicKey,
         rewardMint,
-        platformTreasury,
         10 // 10% platform fee
     );
 
@@ -50,8 +47,7 @@ async function basicUsage() {
 
     const recordInstruction = client.createRecordRewardInstruction(
         platformAuthority.publicKey,
-        poolAccount.publicKey,
-        platformTreasury,
+        poolAccount,
         new PublicKey('44444444444444444444444444444444'), // farmer reward account
         rewardMint,
         amount,
@@ -67,13 +63,13 @@ async function basicUsage() {
     // Example 3: Create withdrawal instruction
     console.log('\n📝 Example 3: Creating withdrawal instruction');
 
-    const withdrawAmount = new BN(500000); // 0.5 USDC
+    const withdrawAmount = new BN(900000); // the pending reward, after the 10% fee
     const nonce = new BN(12345);
 
     const withdrawInstruction = client.createWithdrawRewardInstruction(
         farmerPubkey,
-        poolAccount.publicKey,
-        new PublicKey('55555555555555555555555555555555'), // farmer reward account
+        poolAccount,
+        taskId,
         new PublicKey('66666666666666666666666666666666'), // farmer destination account
         rewardMint,
         withdrawAmount,
@@ -91,7 +87,7 @@ async function basicUsage() {
 
     const updateFeeInstruction = client.createUpdatePlatformFeeInstruction(
         platformAuthority.publicKey,
-        poolAccount.publicKey,
+        poolAccount,
         newFeePercentage
     );
 
@@ -103,12 +99,12 @@ async function basicUsage() {
 
     const pauseInstruction = client.createPausePoolInstruction(
         platformAuthority.publicKey,
-        poolAccount.publicKey
+        poolAccount
     );
 
     const resumeInstruction = client.createResumePoolInstruction(
         platformAuthority.publicKey,
-        poolAccount.publicKey
+        poolAccount
     );
 
     console.log('✅ Pause/resume instructions created');
@@ -0,0 +1,269 @@
+//! Attestor program for tests and local environments.
+//!
+//! Stands in for a partner's attestor behind the reward pool's
+//! `SetAttestor`: anyone can write the `TaskAttestation` of any task and
+//! farmer with any amount ceiling, at any data offset, and close it again.
+//! Attestations live at the PDA of `["attestation", sha256(task_id),
+//! farmer]`, with `data_offset` zero bytes before them, e.g. 8 to mimic an
+//! Anchor discriminator.
+//!
+//! Never deploy this program to a cluster holding real funds: with it as
+//! a pool's attestor, the attestor check accepts whatever anyone writes.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use reward_pool::TaskAttestation;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::instruction as system_instruction;
+
+solana_program::declare_id!("5gXBGX7zQKiX5u6YBgqs79UNGrWXgALNDUikMTx5Kams");
+
+// Program entry point
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub const ATTESTATION_SEED: &[u8] = b"attestation";
+
+/// Serialized size of a `TaskAttestation`
+pub const ATTESTATION_LEN: usize = 32 + 32 + 8;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum MockAttestorInstruction {
+    /// Creates or overwrites the attestation of `task_id` and `farmer`
+    /// Accounts:
+    /// 0. `[signer, writable]` - Payer of the attestation's rent
+    /// 1. `[writable]` - Attestation account
+    /// 2. `[]` - System program
+    SetAttestation {
+        task_id: String,
+        farmer: Pubkey,
+        amount_ceiling: u64,
+        data_offset: u16,
+    },
+    /// Closes the attestation of `task_id` and `farmer`
+    /// Accounts:
+    /// 0. `[writable]` - Recipient of the attestation's rent
+    /// 1. `[writable]` - Attestation account
+    CloseAttestation { task_id: String, farmer: Pubkey },
+}
+
+/// Address of the attestation of `task_id` and `farmer` under `program_id`
+pub fn find_attestation_address(
+    program_id: &Pubkey,
+    task_id: &str,
+    farmer: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ATTESTATION_SEED,
+            hash(task_id.as_bytes()).as_ref(),
+            farmer.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Creates a `SetAttestation` instruction
+pub fn set_attestation(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    task_id: &str,
+    farmer: &Pubkey,
+    amount_ceiling: u64,
+    data_offset: u16,
+) -> Instruction {
+    let (attestation, _) = find_attestation_address(program_id, task_id, farmer);
+    Instruction::new_with_borsh(
+        *program_id,
+        &MockAttestorInstruction::SetAttestation {
+            task_id: task_id.to_string(),
+            farmer: *farmer,
+            amount_ceiling,
+            data_offset,
+        },
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(attestation, false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        ],
+    )
+}
+
+/// Creates a `CloseAttestation` instruction
+pub fn close_attestation(
+    program_id: &Pubkey,
+    recipient: &Pubkey,
+    task_id: &str,
+    farmer: &Pubkey,
+) -> Instruction {
+    let (attestation, _) = find_attestation_address(program_id, task_id, farmer);
+    Instruction::new_with_borsh(
+        *program_id,
+        &MockAttestorInstruction::CloseAttestation {
+            task_id: task_id.to_string(),
+            farmer: *farmer,
+        },
+        vec![
+            AccountMeta::new(*recipient, false),
+            AccountMeta::new(attestation, false),
+        ],
+    )
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = MockAttestorInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    match instruction {
+        MockAttestorInstruction::SetAttestation {
+            task_id,
+            farmer,
+            amount_ceiling,
+            data_offset,
+        } => {
+            msg!("Instruction: SetAttestation");
+            process_set_attestation(
+                program_id,
+                accounts,
+                &task_id,
+                farmer,
+                amount_ceiling,
+                data_offset,
+            )
+        }
+        MockAttestorInstruction::CloseAttestation { task_id, farmer } => {
+            msg!("Instruction: CloseAttestation");
+            process_close_attestation(program_id, accounts, &task_id, &farmer)
+        }
+    }
+}
+
+fn process_set_attestation(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    task_id: &str,
+    farmer: Pubkey,
+    amount_ceiling: u64,
+    data_offset: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer_info = next_account_info(account_info_iter)?;
+    let attestation_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (expected_attestation, bump_seed) = find_attestation_address(program_id, task_id, &farmer);
+    if *attestation_info.key != expected_attestation {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let space = data_offset as usize + ATTESTATION_LEN;
+    let required_lamports = Rent::get()?.minimum_balance(space);
+    if attestation_info.data_is_empty() {
+        let task_hash = hash(task_id.as_bytes());
+        let signer_seeds: &[&[u8]] = &[
+            ATTESTATION_SEED,
+            task_hash.as_ref(),
+            farmer.as_ref(),
+            &[bump_seed],
+        ];
+        invoke_signed(
+            &system_instruction::create_account(
+                payer_info.key,
+                attestation_info.key,
+                required_lamports,
+                space as u64,
+                program_id,
+            ),
+            &[
+                payer_info.clone(),
+                attestation_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+    } else {
+        if attestation_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let missing_lamports = required_lamports.saturating_sub(attestation_info.lamports());
+        if missing_lamports > 0 {
+            invoke(
+                &system_instruction::transfer(
+                    payer_info.key,
+                    attestation_info.key,
+                    missing_lamports,
+                ),
+                &[
+                    payer_info.clone(),
+                    attestation_info.clone(),
+                    system_program_info.clone(),
+                ],
+            )?;
+        }
+        attestation_info.resize(space)?;
+    }
+
+    let attestation = TaskAttestation {
+        task_hash: hash(task_id.as_bytes()).to_bytes(),
+        farmer,
+        amount_ceiling,
+    };
+    let mut data = attestation_info.try_borrow_mut_data()?;
+    data[..data_offset as usize].fill(0);
+    attestation.serialize(&mut &mut data[data_offset as usize..])?;
+
+    msg!(
+        "Attested {} for {} up to {}",
+        task_id,
+        farmer,
+        amount_ceiling
+    );
+    Ok(())
+}
+
+fn process_close_attestation(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    task_id: &str,
+    farmer: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let recipient_info = next_account_info(account_info_iter)?;
+    let attestation_info = next_account_info(account_info_iter)?;
+
+    let (expected_attestation, _) = find_attestation_address(program_id, task_id, farmer);
+    if *attestation_info.key != expected_attestation {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if attestation_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let lamports = attestation_info.lamports();
+    **attestation_info.try_borrow_mut_lamports()? = 0;
+    **recipient_info.try_borrow_mut_lamports()? = recipient_info
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    attestation_info.resize(0)?;
+    attestation_info.assign(&solana_system_interface::program::id());
+
+    msg!("Closed attestation of {} for {}", task_id, farmer);
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! DEX program for tests and local environments.
+//!
+//! Stands in for the DEX and AMM programs the buyback, treasury swap and
+//! protocol-owned liquidity cranks call: `Swap` moves the amounts it is
+//! given between token accounts, whatever a market would pay. Tokens leave
+//! the DEX's reserves, token accounts owned by its authority PDA
+//! `["authority"]`, under the DEX's signature, and any other account under
+//! its owner's, passed on by the caller.
+//!
+//! Never deploy this program to a cluster holding real funds: its reserves
+//! pay anyone.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+solana_program::declare_id!("BR37y8UufoK3Q2py8viY2imSuW9nyDnxPKdawEXFnvKM");
+
+// Program entry point
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// Seed of the PDA owning the DEX's reserves
+pub const AUTHORITY_SEED: &[u8] = b"authority";
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum MockDexInstruction {
+    /// Moves each amount from its source token account to its destination
+    /// Accounts:
+    /// 0. `[]` - Token program
+    /// 1. `[]` - DEX authority (PDA `["authority"]`)
+    ///
+    /// Then for each amount:
+    /// 0. `[writable]` - Source token account
+    /// 1. `[writable]` - Destination token account
+    /// 2. `[signer]` - Owner of the source, the DEX authority for reserves
+    Swap { amounts: Vec<u64> },
+}
+
+/// One leg of a `Swap`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Leg {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    /// Owner of the source, the DEX authority for reserves
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+/// Returns the DEX authority of `program_id`, which owns its reserves
+pub fn find_authority_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUTHORITY_SEED], program_id)
+}
+
+/// Creates a `Swap` instruction. Owners other than the DEX authority are
+/// marked as signers, to be signed for by the caller.
+pub fn swap(program_id: &Pubkey, legs: &[Leg]) -> Instruction {
+    let (authority, _) = find_authority_address(program_id);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(authority, false),
+    ];
+    for leg in legs {
+        accounts.push(AccountMeta::new(leg.source, false));
+        accounts.push(AccountMeta::new(leg.destination, false));
+        accounts.push(AccountMeta::new_readonly(leg.owner, leg.owner != authority));
+    }
+    Instruction::new_with_borsh(
+        *program_id,
+        &MockDexInstruction::Swap {
+            amounts: legs.iter().map(|leg| leg.amount).collect(),
+        },
+        accounts,
+    )
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = MockDexInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    match instruction {
+        MockDexInstruction::Swap { amounts } => {
+            msg!("Instruction: Swap");
+            process_swap(program_id, accounts, &amounts)
+        }
+    }
+}
+
+fn process_swap(program_id: &Pubkey, accounts: &[AccountInfo], amounts: &[u64]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let token_program_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+
+    if *token_program_info.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let (authority, bump_seed) = find_authority_address(program_id);
+    if *authority_info.key != authority {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    for &amount in amounts {
+        let source_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+
+        // The DEX signs for its reserves; any other owner's signature comes
+        // from the caller
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program_info.key,
+                source_info.key,
+                destination_info.key,
+                owner_info.key,
+                &[],
+                amount,
+            )?,
+            &[
+                source_info.clone(),
+                destination_info.clone(),
+                owner_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[&[AUTHORITY_SEED, &[bump_seed]]],
+        )?;
+        msg!(
+            "Moved {} from {} to {}",
+            amount,
+            source_info.key,
+            destination_info.key
+        );
+    }
+    Ok(())
+}
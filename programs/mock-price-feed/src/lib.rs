@@ -0,0 +1,191 @@
+//! Price feed program for tests and local environments.
+//!
+//! Stands in for a Pyth or Switchboard feed: the authority of a feed sets
+//! its price, confidence, exponent and publish time to anything, e.g. a
+//! stale or negative price. A feed is an account of its own keypair,
+//! created by its first `SetPrice`; the value is `price * 10^exponent`.
+//!
+//! Never deploy this program to a cluster holding real funds: a feed says
+//! whatever its authority wants.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::instruction as system_instruction;
+
+solana_program::declare_id!("2MAMKhM5AKCXcg5RBuWdeUi4X5BbZXDFAMqzx2ft7uHV");
+
+// Program entry point
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PriceFeed {
+    /// Only key allowed to set the price
+    pub authority: Pubkey,
+    pub price: i64,
+    /// Confidence interval around `price`, in the same units
+    pub confidence: u64,
+    pub exponent: i32,
+    /// Unix timestamp the price was published at
+    pub publish_time: i64,
+}
+
+impl PriceFeed {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 8 + 8 + 4 + 8;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum MockPriceFeedInstruction {
+    /// Sets the feed's price, creating the feed with the signer as its
+    /// authority if it does not exist. `publish_time` defaults to the
+    /// current clock.
+    /// Accounts:
+    /// 0. `[signer, writable]` - Feed authority, paying the rent of a new feed
+    /// 1. `[writable]` - Price feed account, signer when it is created
+    /// 2. `[]` - System program
+    SetPrice {
+        price: i64,
+        confidence: u64,
+        exponent: i32,
+        publish_time: Option<i64>,
+    },
+}
+
+/// Creates a `SetPrice` instruction for an existing feed; pass it through
+/// `create_price_feed` to create the feed
+pub fn set_price(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    price_feed: &Pubkey,
+    price: i64,
+    confidence: u64,
+    exponent: i32,
+    publish_time: Option<i64>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &MockPriceFeedInstruction::SetPrice {
+            price,
+            confidence,
+            exponent,
+            publish_time,
+        },
+        vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*price_feed, false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        ],
+    )
+}
+
+/// Turns a `SetPrice` instruction into the feed's creation, which the
+/// feed's keypair signs
+pub fn create_price_feed(mut set_price: Instruction) -> Instruction {
+    set_price.accounts[1].is_signer = true;
+    set_price
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = MockPriceFeedInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    match instruction {
+        MockPriceFeedInstruction::SetPrice {
+            price,
+            confidence,
+            exponent,
+            publish_time,
+        } => {
+            msg!("Instruction: SetPrice");
+            process_set_price(
+                program_id,
+                accounts,
+                price,
+                confidence,
+                exponent,
+                publish_time,
+            )
+        }
+    }
+}
+
+fn process_set_price(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    price: i64,
+    confidence: u64,
+    exponent: i32,
+    publish_time: Option<i64>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority_info = next_account_info(account_info_iter)?;
+    let price_feed_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    if !authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if price_feed_info.data_is_empty() {
+        // The feed's keypair signs its creation, as with Pyth's accounts
+        invoke(
+            &system_instruction::create_account(
+                authority_info.key,
+                price_feed_info.key,
+                Rent::get()?.minimum_balance(PriceFeed::LEN),
+                PriceFeed::LEN as u64,
+                program_id,
+            ),
+            &[
+                authority_info.clone(),
+                price_feed_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    } else {
+        if price_feed_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let price_feed = PriceFeed::try_from_slice(&price_feed_info.data.borrow())
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        if price_feed.authority != *authority_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+    }
+
+    let price_feed = PriceFeed {
+        authority: *authority_info.key,
+        price,
+        confidence,
+        exponent,
+        publish_time: match publish_time {
+            Some(publish_time) => publish_time,
+            None => Clock::get()?.unix_timestamp,
+        },
+    };
+    price_feed.serialize(&mut &mut price_feed_info.try_borrow_mut_data()?[..])?;
+
+    msg!(
+        "Price of {} set to {}e{} ± {}",
+        price_feed_info.key,
+        price,
+        exponent,
+        confidence
+    );
+    Ok(())
+}
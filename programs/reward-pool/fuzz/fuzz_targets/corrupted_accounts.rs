@@ -0,0 +1,98 @@
+//! Corrupted valid state: starts from the consistent pool of
+//! `pool_accounts`, truncates, extends, overwrites, re-funds or moves its
+//! accounts, then runs fuzzed instruction data on a fuzzed selection of
+//! them, e.g. a truncated pool or a pending reward with a corrupted task id.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use reward_pool_fuzz::{execute, known_addresses, pool_accounts, AccountInput};
+
+#[derive(Arbitrary, Debug)]
+enum Corruption {
+    Truncate {
+        account: u8,
+        len: u16,
+    },
+    Extend {
+        account: u8,
+        bytes: Vec<u8>,
+    },
+    Overwrite {
+        account: u8,
+        offset: u16,
+        bytes: Vec<u8>,
+    },
+    Lamports {
+        account: u8,
+        lamports: u64,
+    },
+    /// Moves the account to another known address, e.g. a pool to a PDA
+    Rekey {
+        account: u8,
+        key: u8,
+    },
+}
+
+#[derive(Arbitrary, Debug)]
+struct AccountRef {
+    account: u8,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    corruptions: Vec<Corruption>,
+    accounts: Vec<AccountRef>,
+    instruction_data: Vec<u8>,
+    unix_timestamp: i64,
+}
+
+fuzz_target!(|input: Input| {
+    let mut fixture = pool_accounts();
+    let fixture_len = fixture.len();
+    let index = |account: u8| account as usize % fixture_len;
+    for corruption in input.corruptions {
+        match corruption {
+            Corruption::Truncate { account, len } => {
+                let data = &mut fixture[index(account)].data;
+                data.truncate(len as usize);
+            }
+            Corruption::Extend { account, bytes } => {
+                fixture[index(account)].data.extend(bytes);
+            }
+            Corruption::Overwrite {
+                account,
+                offset,
+                bytes,
+            } => {
+                let data = &mut fixture[index(account)].data;
+                for (byte, new) in data.iter_mut().skip(offset as usize).zip(bytes) {
+                    *byte = new;
+                }
+            }
+            Corruption::Lamports { account, lamports } => {
+                fixture[index(account)].lamports = lamports;
+            }
+            Corruption::Rekey { account, key } => {
+                let known = known_addresses();
+                fixture[index(account)].key = known[key as usize % known.len()];
+            }
+        }
+    }
+
+    let accounts: Vec<AccountInput> = input
+        .accounts
+        .iter()
+        .map(|reference| AccountInput {
+            is_signer: reference.is_signer,
+            is_writable: reference.is_writable,
+            ..fixture[index(reference.account)].clone()
+        })
+        .collect();
+
+    // Any error is fine; a panic fails the target
+    let _ = execute(&accounts, &input.instruction_data, input.unix_timestamp);
+});
@@ -0,0 +1,70 @@
+//! Arbitrary accounts and instruction data: keys, owners, flags, lamports
+//! and data are all fuzzed. Keys are mostly drawn from the pool's known
+//! addresses so instructions get past their address checks.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use reward_pool_fuzz::{execute, known_addresses, AccountInput};
+use solana_program::pubkey::Pubkey;
+
+#[derive(Arbitrary, Debug)]
+enum Key {
+    Known(u8),
+    Other([u8; 32]),
+}
+
+#[derive(Arbitrary, Debug)]
+enum Owner {
+    Program,
+    Token,
+    System,
+    Other([u8; 32]),
+}
+
+#[derive(Arbitrary, Debug)]
+struct Account {
+    key: Key,
+    owner: Owner,
+    is_signer: bool,
+    is_writable: bool,
+    executable: bool,
+    lamports: u64,
+    data: Vec<u8>,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    accounts: Vec<Account>,
+    instruction_data: Vec<u8>,
+    unix_timestamp: i64,
+}
+
+fuzz_target!(|input: Input| {
+    let known = known_addresses();
+    let accounts: Vec<AccountInput> = input
+        .accounts
+        .into_iter()
+        .map(|account| AccountInput {
+            key: match account.key {
+                Key::Known(index) => known[index as usize % known.len()],
+                Key::Other(bytes) => Pubkey::new_from_array(bytes),
+            },
+            owner: match account.owner {
+                Owner::Program => reward_pool::id(),
+                Owner::Token => spl_token::id(),
+                Owner::System => solana_system_interface::program::id(),
+                Owner::Other(bytes) => Pubkey::new_from_array(bytes),
+            },
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+            executable: account.executable,
+            lamports: account.lamports,
+            data: account.data,
+        })
+        .collect();
+
+    // Any error is fine; a panic fails the target
+    let _ = execute(&accounts, &input.instruction_data, input.unix_timestamp);
+});
@@ -0,0 +1,485 @@
+//! Harness running the reward pool program on fuzzed accounts and
+//! instruction data.
+//!
+//! [`execute`] lays the accounts out the way the runtime serializes a
+//! program's input, duplicates included, and hands the buffer to the same
+//! `deserialize` the program's entrypoint uses, so account data can be
+//! resized and reassigned like on chain. Syscalls are stubbed: the clock is
+//! the fuzzed timestamp, rent is the default, and system program CPIs
+//! create, fund, allocate and assign accounts. CPIs to any other program
+//! succeed without effect.
+//!
+//! [`pool_accounts`] is a consistent pool, farmer and pending reward for
+//! targets that corrupt valid state rather than generate it from scratch.
+
+use std::sync::{
+    atomic::{AtomicI64, Ordering},
+    Once, OnceLock,
+};
+
+use borsh::BorshSerialize;
+use reward_pool::{
+    seeds, FarmerState, PendingReward, PoolAddressKind, RewardOrigin, RewardPool,
+    PLATFORM_FEE_PERCENTAGE,
+};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::{self, ProgramResult, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER, SUCCESS},
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    program_pack::Pack,
+    program_stubs::{self, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use solana_system_interface::{instruction::SystemInstruction, program as system_program};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+pub const PLATFORM_AUTHORITY: Pubkey = Pubkey::new_from_array([1; 32]);
+pub const FARMER: Pubkey = Pubkey::new_from_array([2; 32]);
+pub const REWARD_MINT: Pubkey = Pubkey::new_from_array([4; 32]);
+
+/// Task of the fixture's pending reward
+pub const TASK_ID: &str = "task-0";
+
+/// Most accounts a transaction can lock
+pub const MAX_ACCOUNTS: usize = 64;
+
+/// Largest account data the harness serializes, well above any account of
+/// the program
+pub const MAX_DATA_LEN: usize = 16 * 1024;
+
+/// An account as passed to the program
+#[derive(Debug, Clone)]
+pub struct AccountInput {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub executable: bool,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+impl AccountInput {
+    fn new(key: Pubkey, owner: Pubkey, lamports: u64, data: Vec<u8>) -> Self {
+        Self {
+            key,
+            owner,
+            is_signer: false,
+            is_writable: true,
+            executable: false,
+            lamports,
+            data,
+        }
+    }
+
+    fn program(key: Pubkey) -> Self {
+        Self {
+            is_writable: false,
+            executable: true,
+            ..Self::new(key, solana_program::bpf_loader::id(), 1, Vec::new())
+        }
+    }
+}
+
+/// Runs the program on `accounts` and `instruction_data` at
+/// `unix_timestamp`. Accounts sharing a key are passed as duplicates of the
+/// first, which decides their flags and contents. Returning at all, with
+/// success or an error, is what the fuzz targets check.
+pub fn execute(
+    accounts: &[AccountInput],
+    instruction_data: &[u8],
+    unix_timestamp: i64,
+) -> ProgramResult {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        program_stubs::set_syscall_stubs(Box::new(FuzzSyscallStubs));
+    });
+    UNIX_TIMESTAMP.store(unix_timestamp, Ordering::Relaxed);
+
+    let mut input = serialize(
+        &accounts[..accounts.len().min(MAX_ACCOUNTS)],
+        instruction_data,
+    );
+    // SAFETY: `input` is laid out as `deserialize` expects, aligned, and
+    // outlives the accounts borrowed from it
+    let (program_id, account_infos, instruction_data) =
+        unsafe { entrypoint::deserialize(input.as_mut_ptr() as *mut u8) };
+    reward_pool::process_instruction(program_id, &account_infos, instruction_data)
+}
+
+/// The runtime's serialization of a program's input, in a buffer aligned
+/// like the one the loader passes
+fn serialize(accounts: &[AccountInput], instruction_data: &[u8]) -> Vec<u128> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(accounts.len() as u64).to_le_bytes());
+    for (index, account) in accounts.iter().enumerate() {
+        if let Some(first) = accounts[..index]
+            .iter()
+            .position(|other| other.key == account.key)
+        {
+            bytes.push(first as u8);
+            bytes.extend_from_slice(&[0; 7]);
+            continue;
+        }
+
+        let data = &account.data[..account.data.len().min(MAX_DATA_LEN)];
+        bytes.push(NON_DUP_MARKER);
+        bytes.push(account.is_signer.into());
+        bytes.push(account.is_writable.into());
+        bytes.push(account.executable.into());
+        // Original data length, which `deserialize` fills in
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(account.key.as_ref());
+        bytes.extend_from_slice(account.owner.as_ref());
+        bytes.extend_from_slice(&account.lamports.to_le_bytes());
+        bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes.resize(
+            (bytes.len() + MAX_PERMITTED_DATA_INCREASE).next_multiple_of(8),
+            0,
+        );
+        // Rent epoch
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+    }
+    bytes.extend_from_slice(&(instruction_data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(instruction_data);
+    bytes.extend_from_slice(reward_pool::id().as_ref());
+
+    let mut input = vec![0u128; bytes.len().div_ceil(16)];
+    // SAFETY: `input` spans at least `bytes.len()` bytes
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), input.as_mut_ptr() as *mut u8, bytes.len());
+    }
+    input
+}
+
+static UNIX_TIMESTAMP: AtomicI64 = AtomicI64::new(0);
+
+struct FuzzSyscallStubs;
+
+impl SyscallStubs for FuzzSyscallStubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock {
+            unix_timestamp: UNIX_TIMESTAMP.load(Ordering::Relaxed),
+            ..Clock::default()
+        };
+        // SAFETY: `Clock::get` passes a `Clock` to fill
+        unsafe { (var_addr as *mut Clock).write(clock) };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        // SAFETY: `Rent::get` passes a `Rent` to fill
+        unsafe { (var_addr as *mut Rent).write(Rent::default()) };
+        SUCCESS
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        if instruction.program_id != system_program::id() {
+            return Ok(());
+        }
+        invoke_system_program(instruction, account_infos, signers_seeds)
+    }
+}
+
+/// The system program instructions the program invokes, checked the way
+/// the system program checks them
+fn invoke_system_program(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let account = |position: usize| {
+        let meta = instruction
+            .accounts
+            .get(position)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        account_infos
+            .iter()
+            .find(|info| *info.key == meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)
+    };
+    let signed = |info: &AccountInfo| {
+        info.is_signer
+            || signers_seeds.iter().any(|seeds| {
+                Pubkey::create_program_address(seeds, &reward_pool::id())
+                    .is_ok_and(|address| address == *info.key)
+            })
+    };
+    let unused = |info: &AccountInfo| info.data_is_empty() && system_program::check_id(info.owner);
+
+    let system_instruction: SystemInstruction = bincode::deserialize(&instruction.data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    match system_instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            let (from, to) = (account(0)?, account(1)?);
+            if !signed(from) || !signed(to) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if to.lamports() > 0 || !unused(to) {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            transfer(from, to, lamports)?;
+            to.resize(space as usize)?;
+            to.assign(&owner);
+        }
+        SystemInstruction::Transfer { lamports } => {
+            let (from, to) = (account(0)?, account(1)?);
+            if !signed(from) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if !unused(from) {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            transfer(from, to, lamports)?;
+        }
+        SystemInstruction::Allocate { space } => {
+            let account = account(0)?;
+            if !signed(account) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if !unused(account) {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            account.resize(space as usize)?;
+        }
+        SystemInstruction::Assign { owner } => {
+            let account = account(0)?;
+            if !signed(account) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if !system_program::check_id(account.owner) {
+                return Err(ProgramError::IllegalOwner);
+            }
+            account.assign(&owner);
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+    Ok(())
+}
+
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    let remaining = from
+        .lamports()
+        .checked_sub(lamports)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    let received = to
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? = received;
+    Ok(())
+}
+
+/// A pool with a funded treasury and vault, a farmer with state, reward
+/// account and a pending reward for [`TASK_ID`], the accounts the pool
+/// creates on demand, and the programs it invokes
+pub fn pool_accounts() -> Vec<AccountInput> {
+    let program_id = reward_pool::id();
+    let (pool_address, pool_bump_seed) =
+        seeds::find_pool_address_with_program_id(&REWARD_MINT, &program_id);
+    let rent = Rent::default();
+    let borsh_account = |key: Pubkey, state: &dyn Fn(&mut Vec<u8>) -> std::io::Result<()>| {
+        let mut data = Vec::new();
+        state(&mut data).expect("fixture state serializes");
+        AccountInput::new(key, program_id, rent.minimum_balance(data.len()), data)
+    };
+    let token_account = |key: Pubkey, owner: Pubkey, amount: u64| {
+        let mut data = vec![0; TokenAccount::LEN];
+        TokenAccount {
+            mint: REWARD_MINT,
+            owner,
+            amount,
+            state: AccountState::Initialized,
+            ..TokenAccount::default()
+        }
+        .pack_into_slice(&mut data);
+        AccountInput::new(key, spl_token::id(), rent.minimum_balance(data.len()), data)
+    };
+    let unused = |key: Pubkey| AccountInput::new(key, system_program::id(), 0, Vec::new());
+
+    let treasury_vault = seeds::find_vault_address_with_program_id(&pool_address, &program_id).0;
+    let pool = RewardPool {
+        platform_authority: PLATFORM_AUTHORITY,
+        reward_mint: REWARD_MINT,
+        platform_treasury: treasury_vault,
+        platform_fee_percentage: PLATFORM_FEE_PERCENTAGE,
+        total_rewards_distributed: 10 * LAMPORTS_PER_SOL,
+        total_platform_fees_collected: LAMPORTS_PER_SOL,
+        is_paused: false,
+        bump_seed: pool_bump_seed,
+        address_kind: PoolAddressKind::Mint,
+        address_creator: Pubkey::default(),
+        address_seed: 0,
+        outstanding_rewards: 0,
+        last_authority_action_at: 0,
+        sequence: 7,
+    };
+    let (farmer_state_address, farmer_state_bump) =
+        seeds::find_farmer_state_address_with_program_id(&pool_address, &FARMER, &program_id);
+    let farmer_state = FarmerState {
+        pool: pool_address,
+        farmer: FARMER,
+        payer: PLATFORM_AUTHORITY,
+        last_recorded_at: 1,
+        last_claimed_at: 0,
+        auto_forward: false,
+        forward_destination: Pubkey::default(),
+        auto_claim_threshold: 0,
+        withdrawal_cosigner: Pubkey::default(),
+        cosigner_removal_at: 0,
+        guild_vault: Pubkey::default(),
+        guild_share_bps: 0,
+        bump_seed: farmer_state_bump,
+        sequence: 1,
+    };
+    let pending_reward = PendingReward {
+        farmer_pubkey: FARMER,
+        amount: 1_000_000,
+        task_id: TASK_ID.to_string(),
+        recorded_at: 1,
+        is_withdrawn: false,
+        campaign_id: None,
+        platform_id: None,
+        proof_hash: None,
+        is_challenged: false,
+        awaiting_approval: false,
+        origin: RewardOrigin::Task,
+        interest_owed: 0,
+    };
+    let mut mint = vec![0; Mint::LEN];
+    Mint {
+        mint_authority: Some(PLATFORM_AUTHORITY).into(),
+        supply: 100 * LAMPORTS_PER_SOL,
+        decimals: 9,
+        is_initialized: true,
+        freeze_authority: None.into(),
+    }
+    .pack_into_slice(&mut mint);
+
+    let pending_reward_address = seeds::find_pending_reward_address_with_program_id(
+        &pool_address,
+        &FARMER,
+        TASK_ID,
+        &program_id,
+    )
+    .0;
+    let day = reward_pool::DailyStats::day_of(0);
+    vec![
+        AccountInput {
+            is_signer: true,
+            ..AccountInput::new(
+                PLATFORM_AUTHORITY,
+                system_program::id(),
+                10 * LAMPORTS_PER_SOL,
+                Vec::new(),
+            )
+        },
+        AccountInput {
+            is_signer: true,
+            ..AccountInput::new(FARMER, system_program::id(), LAMPORTS_PER_SOL, Vec::new())
+        },
+        borsh_account(pool_address, &|data| pool.serialize(data)),
+        AccountInput::new(
+            REWARD_MINT,
+            spl_token::id(),
+            rent.minimum_balance(Mint::LEN),
+            mint,
+        ),
+        token_account(
+            get_associated_token_address(&FARMER, &REWARD_MINT),
+            FARMER,
+            LAMPORTS_PER_SOL,
+        ),
+        token_account(treasury_vault, treasury_vault, 60 * LAMPORTS_PER_SOL),
+        borsh_account(farmer_state_address, &|data| farmer_state.serialize(data)),
+        borsh_account(pending_reward_address, &|data| {
+            pending_reward.serialize(data)
+        }),
+        unused(
+            seeds::find_instruction_counters_address_with_program_id(&pool_address, &program_id).0,
+        ),
+        unused(seeds::find_reward_approval_address_with_program_id(&pool_address, &program_id).0),
+        unused(seeds::find_daily_stats_address_with_program_id(&pool_address, day, &program_id).0),
+        unused(seeds::find_withdraw_burn_address_with_program_id(&pool_address, &program_id).0),
+        unused(seeds::find_withdrawal_fee_address_with_program_id(&pool_address, &program_id).0),
+        unused(seeds::find_fee_vault_address_with_program_id(&pool_address, &program_id).0),
+        unused(
+            seeds::find_withdrawal_history_address_with_program_id(
+                &pool_address,
+                &FARMER,
+                &program_id,
+            )
+            .0,
+        ),
+        AccountInput::program(system_program::id()),
+        AccountInput::program(spl_token::id()),
+        AccountInput::program(spl_associated_token_account::id()),
+    ]
+}
+
+/// Addresses fuzzed accounts pick their keys from: the fixture's, and the
+/// pool's other PDAs, so instructions get past address checks
+pub fn known_addresses() -> &'static [Pubkey] {
+    static ADDRESSES: OnceLock<Vec<Pubkey>> = OnceLock::new();
+    ADDRESSES.get_or_init(|| {
+        let program_id = reward_pool::id();
+        let pool_address = seeds::find_pool_address_with_program_id(&REWARD_MINT, &program_id).0;
+        let pool_addresses = [
+            seeds::find_treasury_governance_address_with_program_id,
+            seeds::find_parameters_address_with_program_id,
+            seeds::find_guardians_address_with_program_id,
+            seeds::find_pool_metadata_address_with_program_id,
+            seeds::find_pool_archive_address_with_program_id,
+            seeds::find_pool_verifier_address_with_program_id,
+            seeds::find_fee_overrides_address_with_program_id,
+            seeds::find_interest_policy_address_with_program_id,
+            seeds::find_reward_governor_address_with_program_id,
+            seeds::find_secondary_reward_address_with_program_id,
+            seeds::find_secondary_vault_address_with_program_id,
+            seeds::find_milestone_bonuses_address_with_program_id,
+            seeds::find_fee_rebate_address_with_program_id,
+            seeds::find_rebate_vault_address_with_program_id,
+        ];
+        let farmer_addresses = [
+            seeds::find_secondary_balance_address_with_program_id,
+            seeds::find_farmer_stats_address_with_program_id,
+            seeds::find_payout_address_address_with_program_id,
+            seeds::find_imported_balance_address_with_program_id,
+        ];
+
+        let mut addresses: Vec<Pubkey> =
+            pool_accounts().iter().map(|account| account.key).collect();
+        addresses.extend(
+            pool_addresses
+                .iter()
+                .map(|find| find(&pool_address, &program_id).0),
+        );
+        addresses.extend(
+            farmer_addresses
+                .iter()
+                .map(|find| find(&pool_address, &FARMER, &program_id).0),
+        );
+        addresses.push(program_id);
+        addresses
+    })
+}
@@ -0,0 +1,66 @@
+// Diagnostic logging
+//
+// With the `debug-logs` feature the program logs every instruction it
+// decodes, the accounts it was given, each sequenced account it saves and
+// the compute units left around the dispatch. Without it these helpers
+// compile to nothing, so release builds pay no compute units for them.
+use solana_program::{account_info::AccountInfo, program_error::ProgramError};
+#[cfg(feature = "debug-logs")]
+use solana_program::{log::sol_log_compute_units, msg};
+
+// The feature costs compute units on every instruction, so a build with
+// it must not be mistaken for a release
+#[cfg(all(feature = "debug-logs", not(debug_assertions)))]
+compile_error!(
+    "`debug-logs` is for debug builds; enable debug assertions to use it with an optimized build"
+);
+
+/// `msg!` when the `debug-logs` feature is on, nothing otherwise. The
+/// arguments are not evaluated without the feature.
+macro_rules! debug_log {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "debug-logs")]
+        solana_program::msg!($($arg)*);
+    };
+}
+pub(crate) use debug_log;
+
+/// Logs the compute units left at `checkpoint`
+#[inline(always)]
+pub(crate) fn log_compute_units(checkpoint: &str) {
+    #[cfg(feature = "debug-logs")]
+    {
+        msg!("Compute units at {}", checkpoint);
+        sol_log_compute_units();
+    }
+    #[cfg(not(feature = "debug-logs"))]
+    let _ = checkpoint;
+}
+
+/// Logs each account's key, flags, owner, lamports and data length
+#[inline(always)]
+pub(crate) fn log_accounts(accounts: &[AccountInfo]) {
+    #[cfg(feature = "debug-logs")]
+    for (index, account) in accounts.iter().enumerate() {
+        msg!(
+            "Account {}: {} signer={} writable={} owner={} lamports={} len={}",
+            index,
+            account.key,
+            account.is_signer,
+            account.is_writable,
+            account.owner,
+            account.lamports(),
+            account.data_len()
+        );
+    }
+    #[cfg(not(feature = "debug-logs"))]
+    let _ = accounts;
+}
+
+/// Logs the error an instruction failed with
+#[inline(always)]
+pub(crate) fn log_failure(error: &ProgramError) {
+    debug_log!("Failed: {}", error);
+    #[cfg(not(feature = "debug-logs"))]
+    let _ = error;
+}
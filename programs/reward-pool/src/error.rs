@@ -0,0 +1,434 @@
+use solana_program::program_error::ProgramError;
+
+// Program errors
+#[derive(thiserror::Error, Debug, Copy, Clone)]
+pub enum RewardPoolError {
+    #[error("Pool already initialized")]
+    PoolAlreadyInitialized,
+    #[error("Pool not initialized")]
+    PoolNotInitialized,
+    #[error("Invalid authority")]
+    InvalidAuthority,
+    #[error("Insufficient amount")]
+    InsufficientAmount,
+    #[error("Pool paused")]
+    PoolPaused,
+    #[error("Invalid nonce")]
+    InvalidNonce,
+    #[error("Invalid platform fee")]
+    InvalidPlatformFee,
+    #[error("Invalid treasury account")]
+    InvalidTreasuryAccount,
+    #[error("Invalid stats account")]
+    InvalidStatsAccount,
+    #[error("Invalid pending reward account")]
+    InvalidPendingRewardAccount,
+    #[error("Invalid instruction counters account")]
+    InvalidCountersAccount,
+    #[error("Reward account not delegated to the custodian")]
+    InvalidDelegatedAccount,
+    #[error("Invalid withdrawal whitelist account")]
+    InvalidWhitelistAccount,
+    #[error("Withdrawal whitelist full")]
+    WhitelistFull,
+    #[error("Destination already whitelisted")]
+    DestinationAlreadyWhitelisted,
+    #[error("Destination not whitelisted")]
+    DestinationNotWhitelisted,
+    #[error("Sponsored withdrawal to a destination the farmer does not own")]
+    DestinationNotOwnedByFarmer,
+    #[error("Invalid treasury governance account")]
+    InvalidGovernanceAccount,
+    #[error("Invalid treasury governance configuration")]
+    InvalidGovernanceConfig,
+    #[error("Treasury governance already configured")]
+    GovernanceAlreadyConfigured,
+    #[error("Invalid treasury vault account")]
+    InvalidVaultAccount,
+    #[error("Invalid treasury proposal account")]
+    InvalidProposalAccount,
+    #[error("Signer is not a treasury approver")]
+    NotAnApprover,
+    #[error("Proposal already approved by this signer")]
+    AlreadyApproved,
+    #[error("Proposal already executed")]
+    ProposalAlreadyExecuted,
+    #[error("Not enough approvals")]
+    InsufficientApprovals,
+    #[error("Proposal execution delay has not elapsed")]
+    ProposalNotExecutable,
+    #[error("Invalid program data account")]
+    InvalidProgramDataAccount,
+    #[error("Invalid upgrade authority account")]
+    InvalidUpgradeAuthority,
+    #[error("Invalid pool parameters account")]
+    InvalidParametersAccount,
+    #[error("Invalid pool parameter values")]
+    InvalidParameterValues,
+    #[error("Invalid guardians account")]
+    InvalidGuardiansAccount,
+    #[error("Invalid guardian set")]
+    InvalidGuardianConfig,
+    #[error("Invalid pending action account")]
+    InvalidPendingActionAccount,
+    #[error("Action ETA is earlier than the minimum delay allows")]
+    ActionEtaTooEarly,
+    #[error("Action ETA has not been reached")]
+    ActionNotReady,
+    #[error("Invalid platform treasury account")]
+    InvalidPlatformTreasury,
+    #[error("Invalid parameter history account")]
+    InvalidParameterHistoryAccount,
+    #[error("Parameter has no change to roll back")]
+    NothingToRollBack,
+    #[error("Parameter rollback window has passed")]
+    RollbackWindowExpired,
+    #[error("Invalid pool metadata account")]
+    InvalidMetadataAccount,
+    #[error("Pool name or URI too long")]
+    MetadataTooLong,
+    #[error("Invalid state export account")]
+    InvalidStateExportAccount,
+    #[error("Invalid imported balance account")]
+    InvalidImportedBalanceAccount,
+    #[error("Balance proof does not match the exported state")]
+    InvalidBalanceProof,
+    #[error("Balance already imported")]
+    BalanceAlreadyImported,
+    #[error("Invalid pool archive account")]
+    InvalidArchiveAccount,
+    #[error("Claim deadline must be in the future")]
+    InvalidClaimDeadline,
+    #[error("Claim period has not ended")]
+    ClaimPeriodNotOver,
+    #[error("Pool is archived")]
+    PoolArchived,
+    #[error("No unclaimed pending rewards")]
+    NoPendingRewards,
+    #[error("Too many pending rewards in one claim")]
+    TooManyPendingRewards,
+    #[error("Invalid payout address account")]
+    InvalidPayoutAddressAccount,
+    #[error("Destination is not owned by the registered payout address")]
+    DestinationNotPayoutAddress,
+    #[error("Invalid pool charity account")]
+    InvalidCharityAccount,
+    #[error("Invalid farmer state account")]
+    InvalidFarmerStateAccount,
+    #[error("Reward account still holds tokens")]
+    RewardAccountNotEmpty,
+    #[error("Withdrawal whitelist or payout address still in use")]
+    FarmerAccountInUse,
+    #[error("Rent recipient is not the recorded payer")]
+    InvalidRentRecipient,
+    #[error("Auto-forward destination is not a token account of the reward mint")]
+    InvalidForwardDestination,
+    #[error("Claim destination is not the farmer's auto-forward destination")]
+    ForwardDestinationMismatch,
+    #[error("Invalid pending transfer policy account")]
+    InvalidPendingTransferPolicyAccount,
+    #[error("Pending reward transfers are disabled for this pool")]
+    PendingTransfersDisabled,
+    #[error("Withdrawal protections forbid transferring this pending reward")]
+    PendingTransferRestricted,
+    #[error("Withdrawal split is empty, too long, or its shares do not add up to 100%")]
+    InvalidWithdrawalSplit,
+    #[error("Invalid withdrawal history account")]
+    InvalidWithdrawalHistoryAccount,
+    #[error("Auto-claim requires a payout address in effect")]
+    AutoClaimRequiresPayoutAddress,
+    #[error("Auto-claim is not enabled for this farmer")]
+    AutoClaimDisabled,
+    #[error("Pending rewards are below the farmer's auto-claim threshold")]
+    AutoClaimThresholdNotReached,
+    #[error("Invalid pool verifier account")]
+    InvalidPoolVerifierAccount,
+    #[error("The pool verifier did not sign")]
+    MissingVerifier,
+    #[error("Invalid pool attestor account")]
+    InvalidPoolAttestorAccount,
+    #[error("The reward pool program cannot be its own attestor")]
+    InvalidAttestorProgram,
+    #[error("Attestation is not the attestor's or does not match the task and farmer")]
+    InvalidAttestation,
+    #[error("Reward exceeds the attested amount ceiling")]
+    AttestedAmountExceeded,
+    #[error("Invalid task budget account")]
+    InvalidTaskBudgetAccount,
+    #[error("Invalid budget escrow account")]
+    InvalidBudgetEscrowAccount,
+    #[error("Task budget must be positive and expire in the future")]
+    InvalidTaskBudget,
+    #[error("A budget is already locked for this task")]
+    TaskBudgetAlreadyLocked,
+    #[error("Reward exceeds the task's locked budget")]
+    TaskBudgetExceeded,
+    #[error("Task budget is still unused and has not expired")]
+    TaskBudgetNotReleasable,
+    #[error("Invalid reward challenge account")]
+    InvalidChallengeAccount,
+    #[error("Pending reward is locked by a challenge")]
+    RewardChallenged,
+    #[error("Challenge window of the pending reward is over")]
+    ChallengeWindowClosed,
+    #[error("Invalid reward approval account")]
+    InvalidRewardApprovalAccount,
+    #[error("Approver must not be the platform authority recording rewards")]
+    ApproverIsRecorder,
+    #[error("Pending reward is awaiting approval")]
+    RewardAwaitingApproval,
+    #[error("Pending reward is not awaiting approval")]
+    RewardNotAwaitingApproval,
+    #[error("Invalid fee overrides account")]
+    InvalidFeeOverridesAccount,
+    #[error("Fee override table is full")]
+    FeeOverridesFull,
+    #[error("Task type has no fee override")]
+    FeeOverrideNotFound,
+    #[error("Invalid platform account")]
+    InvalidPlatformAccount,
+    #[error("Platform id already registered")]
+    PlatformAlreadyRegistered,
+    #[error("Platform id does not match the pool's platform")]
+    PlatformMismatch,
+    #[error("Invalid campaign account")]
+    InvalidCampaignAccount,
+    #[error("Invalid campaign budget, dates or task types")]
+    InvalidCampaign,
+    #[error("Campaign is not running")]
+    CampaignNotActive,
+    #[error("Campaign budget exceeded")]
+    CampaignBudgetExceeded,
+    #[error("Task type not part of the campaign")]
+    TaskTypeNotInCampaign,
+    #[error("Campaign has not ended yet")]
+    CampaignStillRunning,
+    #[error("Campaign budget already returned")]
+    CampaignClosed,
+    #[error("Invalid buyback account")]
+    InvalidBuybackAccount,
+    #[error("Buyback is not enabled for this pool")]
+    BuybackDisabled,
+    #[error("Buyback output below the minimum accepted")]
+    BuybackSlippageExceeded,
+    #[error("Program cannot be used as the buyback DEX")]
+    InvalidDexProgram,
+    #[error("Buyback slice exceeds 10000 basis points")]
+    InvalidBuybackSlice,
+    #[error("Invalid treasury swap policy account")]
+    InvalidTreasurySwapPolicyAccount,
+    #[error("Invalid treasury swap stable mints or epoch limit")]
+    InvalidTreasurySwapPolicy,
+    #[error("Invalid treasury reserve account")]
+    InvalidTreasuryReserveAccount,
+    #[error("Treasury swaps are not enabled for this pool")]
+    TreasurySwapsDisabled,
+    #[error("Mint is not a stable mint of the pool")]
+    MintNotWhitelisted,
+    #[error("Treasury swap exceeds its amount or the epoch limit")]
+    TreasurySwapLimitExceeded,
+    #[error("Treasury swap output below the minimum accepted")]
+    TreasurySwapSlippageExceeded,
+    #[error("Treasury swap amount must be positive")]
+    InvalidTreasurySwap,
+    #[error("Invalid fee discounts account")]
+    InvalidFeeDiscountsAccount,
+    #[error("Invalid stake seed or fee discounts")]
+    InvalidFeeDiscounts,
+    #[error("Staking account is not the farmer's")]
+    InvalidStakeAccount,
+    #[error("Invalid interest policy account")]
+    InvalidInterestPolicyAccount,
+    #[error("Interest rate above the maximum or negative accrual period")]
+    InvalidInterestRate,
+    #[error("Invalid bonus vault account")]
+    InvalidBonusVaultAccount,
+    #[error("Invalid withdraw burn account")]
+    InvalidWithdrawBurnAccount,
+    #[error("Withdraw burn exceeds the maximum")]
+    InvalidWithdrawBurn,
+    #[error("Invalid reward governor account")]
+    InvalidRewardGovernorAccount,
+    #[error("Reward governor floor must be positive and at most its ceiling")]
+    InvalidRewardGovernor,
+    #[error("Invalid secondary reward account")]
+    InvalidSecondaryRewardAccount,
+    #[error("Invalid secondary vault account")]
+    InvalidSecondaryVaultAccount,
+    #[error("Invalid secondary balance account")]
+    InvalidSecondaryBalanceAccount,
+    #[error("Secondary mint must differ from the reward mint")]
+    InvalidSecondaryMint,
+    #[error("Pool already has a secondary mint")]
+    SecondaryMintAlreadySet,
+    #[error("Pool has no secondary mint")]
+    SecondaryRewardsDisabled,
+    #[error("Secondary vault cannot cover the secondary reward")]
+    InsufficientSecondaryFunds,
+    #[error("Secondary rewards cannot await approval")]
+    SecondaryRewardAwaitingApproval,
+    #[error("Invalid milestone bonuses account")]
+    InvalidMilestoneBonusesAccount,
+    #[error("Milestones must have increasing thresholds and non-zero bonuses")]
+    InvalidMilestones,
+    #[error("Invalid farmer stats account")]
+    InvalidFarmerStatsAccount,
+    #[error("Invalid protocol liquidity account")]
+    InvalidProtocolLiquidityAccount,
+    #[error("Invalid protocol liquidity AMM programs, mints or share")]
+    InvalidProtocolLiquidity,
+    #[error("Protocol-owned liquidity is not enabled for this pool")]
+    ProtocolLiquidityDisabled,
+    #[error("LP tokens received below the minimum accepted")]
+    ProtocolLiquiditySlippageExceeded,
+    #[error("Invalid fee rebate account")]
+    InvalidFeeRebateAccount,
+    #[error("Fee rebate exceeds 10000 basis points or its mint is not the secondary mint")]
+    InvalidFeeRebate,
+    #[error("Invalid rebate vault account")]
+    InvalidRebateVaultAccount,
+    #[error("Invalid protocol config account")]
+    InvalidProtocolConfigAccount,
+    #[error("Protocol fee share exceeds the maximum")]
+    InvalidProtocolConfig,
+    #[error("Pool address does not match the creator, mint and seed")]
+    InvalidFactoryPoolAccount,
+    #[error("Invalid factory record account")]
+    InvalidFactoryRecordAccount,
+    #[error("Invalid protocol treasury account")]
+    InvalidProtocolTreasury,
+    #[error("Claim groups do not match the accounts or exceed MAX_CLAIM_POOLS")]
+    InvalidClaimGroups,
+    #[error("Invalid voucher account")]
+    InvalidVoucherAccount,
+    #[error("Voucher already issued")]
+    VoucherAlreadyIssued,
+    #[error("Voucher already redeemed")]
+    VoucherAlreadyRedeemed,
+    #[error("Voucher amount must be positive")]
+    InvalidVoucherAmount,
+    #[error("Invalid voucher escrow account")]
+    InvalidVoucherEscrowAccount,
+    #[error("Invalid payout queue account")]
+    InvalidPayoutQueueAccount,
+    #[error("Recovery window is negative or exceeds MAX_RECOVERY_WINDOW")]
+    InvalidRecoveryWindow,
+    #[error("The pool has no payout queue")]
+    PayoutQueueDisabled,
+    #[error("Payout tickets are only taken while the pool is paused")]
+    PayoutTicketsClosed,
+    #[error("Invalid payout ticket account")]
+    InvalidPayoutTicketAccount,
+    #[error("Payout ticket already taken for the next recovery")]
+    PayoutTicketAlreadyTaken,
+    #[error("The farmer's payout turn has not come yet")]
+    PayoutTurnNotReached,
+    #[error("Invalid withdrawal ramp account")]
+    InvalidWithdrawalRampAccount,
+    #[error("A withdrawal ramp needs a non-zero epoch cap")]
+    InvalidWithdrawalRamp,
+    #[error("Claim exceeds what the withdrawal ramp allows this epoch")]
+    WithdrawalRampLimitExceeded,
+    #[error("Invalid authority recovery account")]
+    InvalidAuthorityRecoveryAccount,
+    #[error("Inactivity timeout is below MIN_INACTIVITY_TIMEOUT")]
+    InvalidInactivityTimeout,
+    #[error("Signer is not the pool's recovery authority")]
+    InvalidRecoveryAuthority,
+    #[error("The platform authority has not been inactive long enough")]
+    AuthorityNotDormant,
+    #[error("Invalid key history account")]
+    InvalidKeyHistoryAccount,
+    #[error("An authority rotation needs the hash of its reason")]
+    MissingRotationReason,
+    #[error("Invalid farmer recovery account")]
+    InvalidFarmerRecoveryAccount,
+    #[error("Recovery guardians must be distinct, exclude the farmer and fit the threshold")]
+    InvalidRecoveryGuardians,
+    #[error("The farmer has no recovery guardians")]
+    FarmerRecoveryDisabled,
+    #[error("Not enough recovery guardians signed")]
+    NotEnoughGuardianApprovals,
+    #[error("Invalid recovery wallet")]
+    InvalidRecoveryWallet,
+    #[error("No farmer recovery in progress")]
+    NoFarmerRecoveryPending,
+    #[error("The farmer can still cancel the recovery")]
+    FarmerRecoveryNotReady,
+    #[error("Invalid withdrawal fee account")]
+    InvalidWithdrawalFeeAccount,
+    #[error("Withdrawal fee exceeds MAX_WITHDRAWAL_FEE_LAMPORTS")]
+    InvalidWithdrawalFee,
+    #[error("Invalid fee vault account")]
+    InvalidFeeVaultAccount,
+    #[error("The fee vault holds no fees")]
+    NoWithdrawalFees,
+    #[error("The withdrawal cosigner must sign")]
+    MissingWithdrawalCosigner,
+    #[error("Invalid withdrawal cosigner")]
+    InvalidWithdrawalCosigner,
+    #[error("The farmer already has a withdrawal cosigner")]
+    WithdrawalCosignerAlreadySet,
+    #[error("The farmer has no withdrawal cosigner")]
+    NoWithdrawalCosigner,
+    #[error("Invalid snapshot buffer account")]
+    InvalidSnapshotBuffer,
+    #[error("The snapshot buffer is too small for the snapshot")]
+    SnapshotBufferTooSmall,
+    #[error("Guild share exceeds BASIS_POINTS")]
+    InvalidGuildShare,
+    #[error("Invalid guild vault")]
+    InvalidGuildVault,
+    #[error("Farmers with a guild split claim with ClaimMany")]
+    GuildSplitRequiresClaimMany,
+    #[error("Invalid guild account")]
+    InvalidGuildAccount,
+    #[error("Guild members must be distinct with shares adding up to BASIS_POINTS")]
+    InvalidGuildMembers,
+    #[error("Not a member of the guild")]
+    NotGuildMember,
+    #[error("The guild vault holds no rewards")]
+    NoGuildRewards,
+    #[error("Invalid token account of a guild member")]
+    InvalidGuildMemberAccount,
+    #[error("Invalid fee ledger account")]
+    InvalidFeeLedgerAccount,
+    #[error("Invalid fee invoice account")]
+    InvalidFeeInvoiceAccount,
+    #[error("A fee invoice was already closed this epoch")]
+    FeeInvoiceNotDue,
+    #[error("Invalid reward rate account")]
+    InvalidRewardRateAccount,
+    #[error("Reward rate window must be positive")]
+    InvalidRateWindow,
+    #[error("Invalid claim shard account")]
+    InvalidClaimShardAccount,
+    #[error("Invalid claim ledger account")]
+    InvalidClaimLedgerAccount,
+    #[error("Invalid reward receipt account")]
+    InvalidRewardReceiptAccount,
+    #[error("Idempotency key already used for a different reward")]
+    IdempotencyKeyReused,
+    #[error("Pool address does not match its seeds")]
+    InvalidPoolAccount,
+    #[error("Treasury account is not the pool's vault")]
+    InvalidProgramTreasuryAccount,
+    #[error("Invalid withdrawal record account")]
+    InvalidWithdrawalRecordAccount,
+    #[error("Withdrawal amount does not match the pending reward")]
+    WithdrawalAmountMismatch,
+    #[error("Account is not the pool's platform fee vault")]
+    InvalidPlatformFeeVault,
+    #[error("Withdrawal record is still within its retention period")]
+    WithdrawalRecordRetained,
+    #[error("Treasury withdrawal exceeds the funds not owed to farmers")]
+    InsufficientTreasuryFunds,
+    #[error("Pool still owes farmers rewards")]
+    RewardsOutstanding,
+}
+
+impl From<RewardPoolError> for ProgramError {
+    fn from(e: RewardPoolError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,709 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+use crate::state::{
+    ClaimTotals, DonationTarget, FeeTotals, GuildMember, KeyRotation, Milestone, Parameter,
+    ParameterValues, ScheduledAction,
+};
+
+// Structured events
+//
+// Events are Borsh-encoded and written to the transaction logs with
+// `sol_log_data`, so they show up as `Program data: <base64>` lines that
+// indexers can decode with the exported schema.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq, Eq)]
+pub enum RewardPoolEvent {
+    PoolInitialized {
+        pool: Pubkey,
+        platform_authority: Pubkey,
+        reward_mint: Pubkey,
+        platform_treasury: Pubkey,
+        platform_fee_percentage: u8,
+    },
+    RewardRecorded {
+        pool: Pubkey,
+        farmer: Pubkey,
+        task_id: String,
+        amount: u64,
+        platform_fee: u64,
+        campaign_id: Option<u64>,
+        platform_id: Option<u64>,
+        /// Task type, whose fee override `platform_fee` reflects if it has one
+        task_type: Option<u32>,
+        /// Share of the platform fee waived for the farmer's staking tier
+        fee_discount_bps: u16,
+        /// Factor the reward governor scaled the reward by, `BASIS_POINTS`
+        /// when it did not
+        governor_factor_bps: u16,
+        /// Secondary mint kicker booked to the farmer, 0 for none
+        secondary_amount: u64,
+        /// Digest of the evidence of the work rewarded, if given
+        proof_hash: Option<[u8; 32]>,
+        /// Pool verifier that co-signed the reward, if the pool has one
+        verifier: Option<Pubkey>,
+        /// Attestation account checked, if the pool has an attestor
+        attestation: Option<Pubkey>,
+        /// Whether the reward was funded from the budget escrow, by the
+        /// task's locked budget or the campaign's
+        from_budget: bool,
+        /// Whether the pending reward awaits approval before it can be
+        /// withdrawn or claimed
+        awaiting_approval: bool,
+        /// Farmer activity after this event, 0 if never
+        last_recorded_at: i64,
+        last_claimed_at: i64,
+    },
+    RewardWithdrawn {
+        pool: Pubkey,
+        farmer: Pubkey,
+        destination: Pubkey,
+        amount: u64,
+        nonce: u64,
+        /// Farmer activity after this event, 0 if never
+        last_recorded_at: i64,
+        last_claimed_at: i64,
+    },
+    PlatformFeeUpdated {
+        pool: Pubkey,
+        old_fee_percentage: u8,
+        new_fee_percentage: u8,
+    },
+    PoolPaused {
+        pool: Pubkey,
+    },
+    PoolResumed {
+        pool: Pubkey,
+    },
+    RewardsSwept {
+        pool: Pubkey,
+        custodian: Pubkey,
+        destination: Pubkey,
+        /// Reward accounts that had a delegated balance
+        account_count: u32,
+        total_amount: u64,
+    },
+    WithdrawalDestinationAdded {
+        pool: Pubkey,
+        farmer: Pubkey,
+        destination: Pubkey,
+        active_at: i64,
+    },
+    WithdrawalDestinationRemoved {
+        pool: Pubkey,
+        farmer: Pubkey,
+        destination: Pubkey,
+    },
+    TreasuryGovernanceConfigured {
+        pool: Pubkey,
+        vault: Pubkey,
+        approvers: Vec<Pubkey>,
+        required_approvals: u8,
+        approval_threshold: u64,
+        execution_delay: i64,
+    },
+    TreasuryWithdrawalProposed {
+        pool: Pubkey,
+        proposal_id: u64,
+        proposer: Pubkey,
+        destination: Pubkey,
+        amount: u64,
+        executable_at: i64,
+    },
+    TreasuryWithdrawalApproved {
+        pool: Pubkey,
+        proposal_id: u64,
+        approver: Pubkey,
+        approval_count: u8,
+    },
+    TreasuryWithdrawalExecuted {
+        pool: Pubkey,
+        proposal_id: u64,
+        destination: Pubkey,
+        amount: u64,
+    },
+    UpgradeAuthorityChanged {
+        pool: Pubkey,
+        /// `None` once the program is immutable
+        new_authority: Option<Pubkey>,
+        approvers: Vec<Pubkey>,
+    },
+    ParametersUpdated {
+        pool: Pubkey,
+        old_values: ParameterValues,
+        new_values: ParameterValues,
+    },
+    GuardiansUpdated {
+        pool: Pubkey,
+        guardians: Vec<Pubkey>,
+    },
+    ActionScheduled {
+        pool: Pubkey,
+        action_id: u64,
+        action: ScheduledAction,
+        eta: i64,
+    },
+    ActionCancelled {
+        pool: Pubkey,
+        action_id: u64,
+    },
+    ActionExecuted {
+        pool: Pubkey,
+        action_id: u64,
+        action: ScheduledAction,
+    },
+    PlatformTreasuryChanged {
+        pool: Pubkey,
+        old_platform_treasury: Pubkey,
+        new_platform_treasury: Pubkey,
+    },
+    PlatformAuthorityChanged {
+        pool: Pubkey,
+        old_platform_authority: Pubkey,
+        new_platform_authority: Pubkey,
+    },
+    ParameterRolledBack {
+        pool: Pubkey,
+        parameter: Parameter,
+        old_values: ParameterValues,
+        new_values: ParameterValues,
+    },
+    PoolMetadataUpdated {
+        pool: Pubkey,
+        name: String,
+        uri: String,
+    },
+    StateExported {
+        pool: Pubkey,
+        merkle_root: [u8; 32],
+        farmer_count: u32,
+        total_amount: u64,
+    },
+    BalanceImported {
+        pool: Pubkey,
+        source_program_id: Pubkey,
+        source_pool: Pubkey,
+        farmer: Pubkey,
+        amount: u64,
+    },
+    PoolArchived {
+        pool: Pubkey,
+        claim_deadline: i64,
+    },
+    PoolClosed {
+        pool: Pubkey,
+        /// Token account the vaults' remaining tokens were swept to
+        destination: Pubkey,
+        /// Tokens swept from the treasury, platform fee and bonus vaults
+        swept_tokens: u64,
+        /// Lamport withdrawal fees swept from the fee vault, on top of the
+        /// rent of the closed accounts
+        swept_fees: u64,
+    },
+    RewardsClaimed {
+        pool: Pubkey,
+        farmer: Pubkey,
+        /// Pending rewards paid out
+        reward_count: u32,
+        total_amount: u64,
+        /// Secondary rewards owed to the farmer, paid out with the claim
+        secondary_amount: u64,
+        /// Farmer activity after this event, 0 if never
+        last_recorded_at: i64,
+        last_claimed_at: i64,
+    },
+    PayoutAddressRegistered {
+        pool: Pubkey,
+        farmer: Pubkey,
+        /// `Pubkey::default()` when unregistering
+        payout_address: Pubkey,
+        /// Equal to the registration time when it cancelled a pending change
+        active_at: i64,
+    },
+    CharityAccountSet {
+        pool: Pubkey,
+        charity: Pubkey,
+    },
+    RewardDonated {
+        pool: Pubkey,
+        farmer: Pubkey,
+        task_id: String,
+        amount: u64,
+        target: DonationTarget,
+    },
+    FarmerAccountsClosed {
+        pool: Pubkey,
+        farmer: Pubkey,
+        /// Receives the rent of the reward and farmer state accounts
+        rent_recipient: Pubkey,
+        /// Accounts that existed and were closed
+        account_count: u32,
+    },
+    AutoForwardSet {
+        pool: Pubkey,
+        farmer: Pubkey,
+        /// `None` when auto-forward was turned off
+        destination: Option<Pubkey>,
+    },
+    PendingTransferPolicySet {
+        pool: Pubkey,
+        transfers_disabled: bool,
+    },
+    PendingRewardTransferred {
+        pool: Pubkey,
+        from: Pubkey,
+        to: Pubkey,
+        task_id: String,
+        amount: u64,
+    },
+    AutoClaimSet {
+        pool: Pubkey,
+        farmer: Pubkey,
+        /// 0 when auto-claim was turned off
+        threshold: u64,
+    },
+    RewardsAutoClaimed {
+        pool: Pubkey,
+        farmer: Pubkey,
+        cranker: Pubkey,
+        /// Token account of the farmer's payout address
+        destination: Pubkey,
+        reward_count: u32,
+        /// Pending rewards claimed, including the tip
+        total_amount: u64,
+        /// Part of `total_amount` paid to the cranker
+        tip: u64,
+    },
+    VerifierSet {
+        pool: Pubkey,
+        /// `None` when the verifier was removed
+        verifier: Option<Pubkey>,
+    },
+    AttestorSet {
+        pool: Pubkey,
+        /// `None` when the attestor was removed
+        attestor_program: Option<Pubkey>,
+        data_offset: u16,
+    },
+    TaskBudgetLocked {
+        pool: Pubkey,
+        task_id: String,
+        amount: u64,
+        expires_at: i64,
+    },
+    TaskBudgetReleased {
+        pool: Pubkey,
+        task_hash: [u8; 32],
+        /// Tokens returned to the treasury vault
+        amount: u64,
+    },
+    RewardChallenged {
+        pool: Pubkey,
+        farmer: Pubkey,
+        task_id: String,
+        challenger: Pubkey,
+        /// Lamports escrowed in the challenge account
+        bond: u64,
+    },
+    ChallengeResolved {
+        pool: Pubkey,
+        farmer: Pubkey,
+        task_id: String,
+        challenger: Pubkey,
+        /// Platform authority or pool verifier that decided
+        resolver: Pubkey,
+        /// Whether the reward was voided and the bond returned; otherwise
+        /// the bond went to the farmer
+        upheld: bool,
+    },
+    RewardApproverSet {
+        pool: Pubkey,
+        /// `None` when maker-checker mode was turned off
+        approver: Option<Pubkey>,
+    },
+    RewardApproved {
+        pool: Pubkey,
+        farmer: Pubkey,
+        task_id: String,
+        amount: u64,
+        approver: Pubkey,
+    },
+    PlatformRegistered {
+        platform_id: u64,
+        operator: Pubkey,
+    },
+    PlatformOperatorSet {
+        platform_id: u64,
+        old_operator: Pubkey,
+        new_operator: Pubkey,
+    },
+    /// Emitted next to `PoolInitialized` for pools created in a platform
+    /// namespace
+    PlatformPoolCreated {
+        platform_id: u64,
+        pool: Pubkey,
+    },
+    CampaignCreated {
+        pool: Pubkey,
+        campaign_id: u64,
+        budget: u64,
+        starts_at: i64,
+        ends_at: i64,
+        /// Task types the campaign rewards, any when empty
+        task_types: Vec<u32>,
+    },
+    CampaignEnded {
+        pool: Pubkey,
+        campaign_id: u64,
+        spent: u64,
+        reward_count: u64,
+    },
+    CampaignFinalized {
+        pool: Pubkey,
+        campaign_id: u64,
+        /// Unspent budget returned to the treasury vault
+        returned: u64,
+    },
+    BuybackConfigured {
+        pool: Pubkey,
+        /// `None` when the crank is disabled
+        dex_program: Option<Pubkey>,
+        governance_mint: Pubkey,
+        slice_bps: u16,
+        min_output_per_million: u64,
+    },
+    BuybackExecuted {
+        pool: Pubkey,
+        /// Reward tokens swapped
+        spent: u64,
+        /// Tokens bought back and burned
+        burned: u64,
+    },
+    TreasurySwapPolicySet {
+        pool: Pubkey,
+        /// `None` when swaps are disabled
+        dex_program: Option<Pubkey>,
+        stable_mints: Vec<Pubkey>,
+        epoch_limit_bps: u16,
+    },
+    TreasurySwapped {
+        pool: Pubkey,
+        output_mint: Pubkey,
+        /// Vault tokens swapped
+        spent: u64,
+        /// Tokens added to the reserve of `output_mint`
+        received: u64,
+    },
+    FeeDiscountsSet {
+        pool: Pubkey,
+        /// `None` when discounts were removed
+        staking_program: Option<Pubkey>,
+        discounts_bps: Vec<u16>,
+    },
+    InterestPolicySet {
+        pool: Pubkey,
+        /// 0 when interest was turned off
+        rate_bps: u16,
+        max_accrual_secs: i64,
+    },
+    InterestPaid {
+        pool: Pubkey,
+        farmer: Pubkey,
+        /// Paid from the bonus vault next to the claimed rewards
+        interest: u64,
+        /// Interest the bonus vault could not cover, still owed in the
+        /// claim's pending rewards
+        shortfall: u64,
+    },
+    WithdrawBurnSet {
+        pool: Pubkey,
+        /// 0 when withdrawals stopped burning
+        withdraw_burn_bps: u16,
+    },
+    WithdrawalBurned {
+        pool: Pubkey,
+        farmer: Pubkey,
+        /// Burned from the withdrawal before the rest was paid out
+        amount: u64,
+        /// Nonce of the withdrawal, 0 for claims
+        nonce: u64,
+    },
+    RewardGovernorSet {
+        pool: Pubkey,
+        /// 0 when the governor was turned off
+        min_runway_days: u32,
+        floor_bps: u16,
+        ceiling_bps: u16,
+    },
+    SecondaryMintSet {
+        pool: Pubkey,
+        secondary_mint: Pubkey,
+    },
+    MilestoneBonusesSet {
+        pool: Pubkey,
+        /// Empty when milestone bonuses were turned off
+        milestones: Vec<Milestone>,
+    },
+    MilestoneReached {
+        pool: Pubkey,
+        farmer: Pubkey,
+        /// Milestones the farmer has reached, this reward's included
+        milestones_reached: u8,
+        /// Bonus of the milestones this reward reached
+        bonus: u64,
+        lifetime_earned: u64,
+        /// Pending reward booking the bonus
+        pending_reward: Pubkey,
+    },
+    ProtocolLiquiditySet {
+        pool: Pubkey,
+        /// Empty when contributions are disabled
+        amm_programs: Vec<Pubkey>,
+        pair_mint: Pubkey,
+        lp_mint: Pubkey,
+        pol_bps: u16,
+        min_lp_per_million: u64,
+    },
+    LiquidityContributed {
+        pool: Pubkey,
+        amm_program: Pubkey,
+        /// Reward tokens swapped or deposited
+        contributed: u64,
+        /// Tokens of the pair mint deposited
+        paired: u64,
+        /// LP tokens added to the LP vault
+        lp_received: u64,
+    },
+    FeeRebateSet {
+        pool: Pubkey,
+        governance_mint: Pubkey,
+        rebate_bps: u16,
+        rate_per_million: u64,
+    },
+    FeeRebated {
+        pool: Pubkey,
+        farmer: Pubkey,
+        platform_fee: u64,
+        /// Governance tokens booked to the farmer's secondary balance
+        rebate: u64,
+    },
+    TaskTypeFeeSet {
+        pool: Pubkey,
+        task_type: u32,
+        /// `None` when the task type went back to the pool's fee
+        fee_percentage: Option<u8>,
+    },
+    ProtocolConfigSet {
+        admin: Pubkey,
+        treasury: Pubkey,
+        creation_fee: u64,
+        protocol_fee_share_bps: u16,
+    },
+    FactoryPoolCreated {
+        pool: Pubkey,
+        creator: Pubkey,
+        reward_mint: Pubkey,
+        seed: u64,
+        /// Lamports paid to the protocol treasury
+        creation_fee: u64,
+        protocol_fee_share_bps: u16,
+    },
+    ProtocolFeePaid {
+        pool: Pubkey,
+        platform_fee: u64,
+        /// Part of the platform fee moved to the protocol treasury
+        protocol_fee: u64,
+    },
+    VoucherIssued {
+        pool: Pubkey,
+        voucher: Pubkey,
+        voucher_id: u64,
+        mint: Pubkey,
+        recipient: Pubkey,
+        amount: u64,
+    },
+    VoucherRedeemed {
+        pool: Pubkey,
+        voucher: Pubkey,
+        holder: Pubkey,
+        /// Token account the voucher's tokens were paid to
+        destination: Pubkey,
+        amount: u64,
+    },
+    PayoutQueueSet {
+        pool: Pubkey,
+        recovery_window: i64,
+    },
+    PayoutTicketTaken {
+        pool: Pubkey,
+        farmer: Pubkey,
+        round: u64,
+        number: u64,
+        /// Unix timestamp the farmer's oldest unclaimed reward was recorded at
+        score: i64,
+    },
+    RecoveryStarted {
+        pool: Pubkey,
+        round: u64,
+        ticket_count: u64,
+        ends_at: i64,
+    },
+    WithdrawalRampStarted {
+        pool: Pubkey,
+        start_epoch: u64,
+        initial_epoch_cap: u64,
+        epoch_cap_step: u64,
+        /// Epochs until claims are fully open again, 0 for no ramp
+        ramp_epochs: u16,
+    },
+    RecoveryAuthoritySet {
+        pool: Pubkey,
+        /// `Pubkey::default()` when the dead-man switch is off
+        recovery_authority: Pubkey,
+        inactivity_timeout: i64,
+    },
+    DormantAuthorityClaimed {
+        pool: Pubkey,
+        previous_authority: Pubkey,
+        new_authority: Pubkey,
+        /// Unix timestamp the dormancy was measured from
+        last_active_at: i64,
+    },
+    KeyRotationRecorded {
+        pool: Pubkey,
+        /// Position of the entry in the pool's key history
+        index: u32,
+        rotation: KeyRotation,
+    },
+    RecoveryGuardiansSet {
+        pool: Pubkey,
+        farmer: Pubkey,
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+    },
+    FarmerRecoveryInitiated {
+        pool: Pubkey,
+        farmer: Pubkey,
+        new_wallet: Pubkey,
+        /// Unix timestamp from which the recovery can be completed
+        completes_at: i64,
+    },
+    FarmerRecoveryCancelled {
+        pool: Pubkey,
+        farmer: Pubkey,
+        new_wallet: Pubkey,
+    },
+    FarmerRecovered {
+        pool: Pubkey,
+        farmer: Pubkey,
+        new_wallet: Pubkey,
+        /// Whether the farmer state moved in this instruction
+        moved_state: bool,
+        pending_count: u8,
+        pending_amount: u64,
+    },
+    WithdrawalFeeSet {
+        pool: Pubkey,
+        /// 0 when withdrawals stopped paying the fee
+        fee_lamports: u64,
+        exempt_small_claims: bool,
+        small_claim_threshold: u64,
+    },
+    WithdrawalFeeCharged {
+        pool: Pubkey,
+        farmer: Pubkey,
+        /// Farmer, sponsor or cranker who paid the fee
+        payer: Pubkey,
+        lamports: u64,
+        /// Nonce of the withdrawal, 0 for claims
+        nonce: u64,
+    },
+    WithdrawalFeesCollected {
+        pool: Pubkey,
+        destination: Pubkey,
+        lamports: u64,
+    },
+    WithdrawalCosignerSet {
+        pool: Pubkey,
+        farmer: Pubkey,
+        /// Cosigner in effect after the change, `Pubkey::default()` for none
+        cosigner: Pubkey,
+        /// Unix timestamp at which a removal takes effect, 0 if none is pending
+        removal_at: i64,
+    },
+    PoolSnapshotExported {
+        pool: Pubkey,
+        buffer: Pubkey,
+        slot: u64,
+        commitment: [u8; 32],
+        account_count: u32,
+    },
+    GuildSplitSet {
+        pool: Pubkey,
+        farmer: Pubkey,
+        /// `None` when the split was removed
+        guild_vault: Option<Pubkey>,
+        guild_share_bps: u16,
+    },
+    GuildSharePaid {
+        pool: Pubkey,
+        farmer: Pubkey,
+        guild_vault: Pubkey,
+        amount: u64,
+    },
+    GuildMembersSet {
+        pool: Pubkey,
+        guild: Pubkey,
+        authority: Pubkey,
+        members: Vec<GuildMember>,
+    },
+    GuildMemberLeft {
+        pool: Pubkey,
+        guild: Pubkey,
+        member: Pubkey,
+    },
+    GuildRewardsDistributed {
+        pool: Pubkey,
+        guild: Pubkey,
+        amount: u64,
+        member_count: u8,
+    },
+    FeeInvoiceClosed {
+        pool: Pubkey,
+        invoice: Pubkey,
+        number: u64,
+        epoch: u64,
+        fees: FeeTotals,
+        seal: [u8; 32],
+    },
+    ClaimShardsConsolidated {
+        pool: Pubkey,
+        shard_count: u8,
+        /// Claimed since the shards' last consolidation
+        consolidated: ClaimTotals,
+        /// Claim ledger totals after the consolidation
+        totals: ClaimTotals,
+    },
+    ProgramTreasuryFunded {
+        pool: Pubkey,
+        funder: Pubkey,
+        amount: u64,
+        /// Program treasury balance after the top-up
+        balance: u64,
+    },
+    WithdrawalRecordsClosed {
+        pool: Pubkey,
+        farmer: Pubkey,
+        /// Records closed, their rent refunded to whoever paid it
+        count: u32,
+        /// Nonces below it can no longer be withdrawn under
+        nonce_floor: u64,
+    },
+}
+
+impl RewardPoolEvent {
+    /// Writes the event to the transaction logs
+    pub fn emit(&self) {
+        let data = borsh::to_vec(self).expect("event serialization is infallible");
+        sol_log_data(&[&data]);
+    }
+}
@@ -0,0 +1,1846 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::state::{
+    DonationTarget, GuildMember, Milestone, Parameter, ParameterValues, ScheduledAction,
+    WithdrawalSplit,
+};
+
+// Instruction encoding
+//
+// Every payload starts with a tag byte selecting the namespace it is encoded
+// in, followed by the Borsh-encoded instruction of that namespace. Payloads
+// produced before tagging was introduced start directly with the V1 variant
+// index; those indices are far below the reserved tag range, so untagged
+// payloads are still decoded as V1.
+
+// Tag bytes are shared with the interface crate's display decoder
+pub use reward_pool_interface::{INSTRUCTION_TAG_V1, INSTRUCTION_TAG_V2};
+
+/// Original instruction set. Frozen: deployed clients depend on this layout,
+/// so variants and fields must never be added, removed or reordered.
+///
+/// V1 payloads are processed as their V2 equivalent and take the accounts
+/// documented there; the account lists below are the original ones.
+pub mod v1 {
+    use super::*;
+
+    #[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq, Eq)]
+    pub enum RewardPoolInstruction {
+        /// Initializes a new reward pool
+        /// Accounts:
+        /// 0. `[signer]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[]` - Reward token mint
+        /// 3. `[writable]` - Platform treasury account
+        /// 4. `[]` - Rent sysvar
+        /// 5. `[]` - System program
+        /// 6. `[]` - Token program
+        /// 7. `[]` - Associated token account program
+        InitializePool { platform_fee_percentage: u8 },
+
+        /// Records a reward in the pool
+        /// Accounts:
+        /// 0. `[signer]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Platform treasury account
+        /// 3. `[writable]` - Farmer's reward account
+        /// 4. `[]` - Token mint
+        /// 5. `[]` - Token program
+        /// 6. `[]` - Associated token account program
+        RecordReward {
+            amount: u64,
+            farmer_pubkey: Pubkey,
+            task_id: String,
+        },
+
+        /// Allows a farmer to withdraw their rewards
+        /// Accounts:
+        /// 0. `[signer]` - Farmer who withdraws
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Farmer's reward account
+        /// 3. `[writable]` - Farmer's destination account
+        /// 4. `[]` - Token mint
+        /// 5. `[]` - Token program
+        /// 6. `[]` - Associated token account program
+        WithdrawReward { amount: u64, nonce: u64 },
+
+        /// Updates platform fees (admin only)
+        /// Accounts:
+        /// 0. `[signer]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        UpdatePlatformFee { new_fee_percentage: u8 },
+
+        /// Pauses the pool (admin only)
+        /// Accounts:
+        /// 0. `[signer]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        PausePool,
+
+        /// Resumes the pool (admin only)
+        /// Accounts:
+        /// 0. `[signer]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        ResumePool,
+    }
+}
+
+/// Current instruction set. New instructions and new parameters go here;
+/// V1 payloads are converted into their V2 equivalent before processing.
+pub mod v2 {
+    use super::*;
+
+    #[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq, Eq)]
+    pub enum RewardPoolInstruction {
+        /// Initializes a new reward pool at the address derived from its
+        /// reward mint, one pool per mint
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority, paying the pool's rent
+        /// 1. `[writable]` - Reward pool account (PDA `["reward_pool", reward_mint]`)
+        /// 2. `[]` - Reward token mint
+        /// 3. `[writable]` - Treasury vault (PDA `["vault", pool]`), created as
+        ///    the pool's platform treasury
+        /// 4. `[]` - Rent sysvar
+        /// 5. `[]` - System program
+        /// 6. `[]` - Token program
+        /// 7. `[]` - Associated token account program
+        /// 8. `[writable]` - Pool instruction counters account
+        /// 9. `[writable]` - Pool metadata account, required with a name or URI
+        InitializePool {
+            platform_fee_percentage: u8,
+            /// Display name, at most `PoolMetadata::MAX_NAME_LEN` bytes
+            name: Option<String>,
+            /// Metadata URI, at most `PoolMetadata::MAX_URI_LEN` bytes
+            uri: Option<String>,
+        },
+
+        /// Records a reward in the pool, booking it as the farmer's pending
+        /// reward for the task: the tokens stay in the treasury vault until
+        /// the farmer withdraws or claims them, after the approver approved
+        /// them when the pool has one. When the pool's reward governor is
+        /// on, the reward is first scaled by its factor, which drops while
+        /// the platform treasury's runway is short. A `secondary_amount` is
+        /// booked to the farmer in the pool's secondary mint, claimed with
+        /// `ClaimMany`. Rewards taking the farmer past milestones of the pool
+        /// book their bonus as a pending reward. Pools created through the
+        /// factory pay the protocol's share of the platform fee from the
+        /// platform treasury, and the rest of the fee moves to the pool's
+        /// platform fee vault unless a task budget paid. Passing the SPL Memo program tags the
+        /// instruction with the task's `clones:task:<hash>` memo. The farmer
+        /// amount is added to the pool's reward rate accumulators. With an
+        /// `idempotency_key`, the reward leaves a receipt, and a retry under
+        /// the same key succeeds without recording it again. The V1 account
+        /// list, accounts 0 to 6 optionally followed by 7 to 9, is rejected.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Platform treasury, the pool's treasury vault
+        ///    (PDA `["vault", pool]`), signed for by the program
+        /// 3. `[]` - Farmer's reward account, unused
+        /// 4. `[]` - Token mint
+        /// 5. `[]` - Token program
+        /// 6. `[]` - Associated token account program, unused
+        /// 7. `[writable]` - Pool stats account for the current day
+        /// 8. `[]` - System program
+        /// 9. `[writable]` - Pool instruction counters account
+        /// 10. `[writable]` - Farmer state account
+        /// 11. `[]` - Pool verifier account
+        /// 12. `[]` - Pool attestor account
+        /// 13. `[]` - Attestation account of the task, when the pool has an
+        ///     attestor (ignored otherwise)
+        /// 14. `[writable]` - Task budget account (PDA `["task_budget", pool, sha256(task_id)]`)
+        /// 15. `[writable]` - Pool budget escrow (PDA `["budget_escrow", pool]`)
+        /// 16. `[]` - Reward approval account (PDA `["reward_approval", pool]`)
+        /// 17. `[writable]` - Pending reward account of the farmer for the
+        ///     task, created by the instruction
+        /// 18. `[writable]` - Platform fee vault (PDA `["platform_fee_vault",
+        ///     pool]`), created by the first reward paying a fee
+        /// 19. `[]` - Fee overrides account (PDA `["fee_overrides", pool]`)
+        /// 20. `[writable]` - Platform account (PDA `["platform", platform_id]`),
+        ///     ignored for pools outside a platform namespace
+        /// 21. `[writable]` - Campaign account (PDA `["campaign", pool,
+        ///     campaign_id]`), ignored without a campaign id
+        /// 22. `[]` - Fee discounts account (PDA `["fee_discounts", pool]`)
+        /// 23. `[]` - Farmer's account of the staking program, ignored when
+        ///     the pool has no fee discounts
+        /// 24. `[writable]` - Reward governor account (PDA `["reward_governor", pool]`)
+        /// 25. `[writable]` - Milestone bonuses account (PDA `["milestone_bonuses", pool]`)
+        /// 26. `[writable]` - Fee rebate account (PDA `["fee_rebate", pool]`)
+        /// 27. `[writable]` - Reward rate account (PDA `["reward_rate", pool]`)
+        /// 28. `[]` - Treasury governance account (PDA `["treasury_governance", pool]`)
+        ///
+        /// Then the feature accounts, optional trailing accounts: the list
+        /// may end after any of them, and a reward needing one it does not
+        /// reach fails with `NotEnoughAccountKeys`. Accounts the reward does
+        /// not need, before one it does, are ignored.
+        /// 29. `[writable]` - Reward receipt account (PDA `["reward_receipt",
+        ///     pool, idempotency_key]`), needed with an idempotency key
+        /// 30. `[]` - SPL Memo program, any other account to skip the memo
+        /// 31. `[writable]` - Farmer stats account (PDA `["farmer_stats", pool,
+        ///     farmer]`), needed when the pool has milestone bonuses
+        /// 32. `[writable]` - Pending reward account of the farmer for
+        ///     `MilestoneBonuses::task_id` of their next milestone, needed
+        ///     and created when the reward reaches it
+        /// 33. `[writable]` - Secondary reward account (PDA `["secondary_reward",
+        ///     pool]`), needed with a `secondary_amount` or fee rebate
+        /// 34. `[writable]` - Secondary vault (PDA `["secondary_vault", pool]`),
+        ///     funded with fee rebates, needed likewise
+        /// 35. `[writable]` - Farmer's secondary balance account (PDA
+        ///     `["secondary_balance", pool, farmer]`), needed likewise
+        /// 36. `[writable]` - Rebate vault (PDA `["rebate_vault", pool]`),
+        ///     needed when the pool has a fee rebate
+        /// 37. `[writable]` - Factory record account (PDA `["factory_record",
+        ///     pool]`), needed for pools created through the factory
+        /// 38. `[]` - Protocol config account (PDA `["protocol_config"]`),
+        ///     needed likewise
+        /// 39. `[writable]` - Protocol treasury's token account of the
+        ///     reward mint, needed likewise
+        ///
+        /// Then, in any order:
+        /// - `[signer]` - Pool verifier, when the pool has one
+        /// - `[signer]` - Treasury approvers, under treasury governance for
+        ///   rewards above the approval threshold
+        RecordReward {
+            amount: u64,
+            farmer_pubkey: Pubkey,
+            task_id: String,
+            /// Marketing campaign the reward is attributed to
+            campaign_id: Option<u64>,
+            /// Tenant platform the reward is attributed to
+            platform_id: Option<u64>,
+            /// Digest of the evidence of the work done, e.g. of the IPFS CID
+            /// of the task output
+            proof_hash: Option<[u8; 32]>,
+            /// Task type, selecting its fee override if it has one
+            task_type: Option<u32>,
+            /// Kicker in the pool's secondary mint, 0 for none
+            secondary_amount: u64,
+            /// Caller-chosen key making retries safe: a reward recorded
+            /// under the same key again is a no-op
+            idempotency_key: Option<[u8; 32]>,
+        },
+
+        /// Allows a farmer to withdraw a pending reward from the treasury
+        /// vault, signed for by the program; `amount` must be the reward's.
+        /// The interest it accrued, and interest still owed on it, is paid
+        /// from the bonus vault to the destination, the first one with a
+        /// split. Farmers with a guild split claim with `ClaimMany` instead. Passing the farmer's
+        /// reward account instead of the vault moves tokens it holds, paid
+        /// out before rewards were kept in the vault, with the V1 accounts
+        /// 0 to 6 only and none of the checks below. When a sponsor pays the
+        /// fees and rent, the destination must belong to the farmer or be
+        /// on their whitelist, so the sponsor can never redirect funds.
+        /// Once the farmer has a payout address in effect, the destination
+        /// must be owned by it. With a `split`, the amount is divided
+        /// between up to `MAX_WITHDRAWAL_SPLITS` destinations, each checked
+        /// like a single one, in one atomic instruction. When the pool burns
+        /// a share of withdrawals, that share is burned first and the rest
+        /// paid out. The farmer may be a PDA signing through its program's
+        /// CPI; one that cannot fund rent withdraws with a sponsor. When the
+        /// pool charges a lamport withdrawal fee, the sponsor, or else the
+        /// farmer, pays it into the fee vault. A farmer with a withdrawal
+        /// cosigner needs its signature too. Each withdrawal persists its
+        /// `WithdrawalRecord` at the PDA of its `nonce`, and one reusing a
+        /// nonce of the farmer fails with `InvalidNonce`. During a recovery window of the payout queue, the
+        /// farmer waits for their turn as with `ClaimMany`, and the amount
+        /// counts against the pool's withdrawal ramp like a claim.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer who withdraws, read-only when sponsored
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Treasury vault (PDA `["vault", pool]`)
+        /// 3. `[writable]` - Farmer's destination account, the first split
+        ///    destination with a split
+        /// 4. `[writable]` - Token mint, burned from with a withdraw burn
+        /// 5. `[]` - Token program
+        /// 6. `[]` - Associated token account program
+        /// 7. `[writable]` - Pool stats account for the current day
+        /// 8. `[]` - System program
+        /// 9. `[writable]` - Pool instruction counters account
+        /// 10. `[]` - Farmer's withdrawal whitelist account
+        /// 11. `[]` - Pool parameters account
+        /// 12. `[]` - Farmer's payout address account
+        /// 13. `[writable]` - Farmer state account
+        /// 14. `[writable]` - Farmer's withdrawal history account
+        /// 15. `[writable]` - Withdraw burn account
+        /// 16. `[writable]` - Withdrawal fee account
+        /// 17. `[writable]` - Fee vault
+        /// 18. `[signer]` - Farmer's withdrawal cosigner, any account when
+        ///     the farmer has none
+        /// 19. `[signer, writable]` - Optional sponsor paying rent and the
+        ///     withdrawal fee instead of the farmer
+        /// 20. `[writable]` - Further split destinations in split order,
+        ///     starting at 19 without a sponsor
+        /// 21. `[]` - Payout queue account, after the sponsor and further
+        ///     split destinations
+        /// 22. `[]` - Farmer's payout ticket account
+        /// 23. `[writable]` - Withdrawal ramp account
+        /// 24. `[writable]` - Interest policy account (PDA `["interest_policy", pool]`)
+        /// 25. `[writable]` - Bonus vault (PDA `["bonus_vault", pool]`)
+        /// 26. `[writable]` - Pending reward account withdrawn
+        /// 27. `[writable]` - Withdrawal record account of the nonce
+        ///     (PDA `["withdrawal_record", pool, farmer, nonce]`), last
+        WithdrawReward {
+            amount: u64,
+            nonce: u64,
+            /// Destinations and shares to divide the amount between
+            split: Option<Vec<WithdrawalSplit>>,
+        },
+
+        /// Updates platform fees (admin only), up to the pool's maximum fee.
+        /// Accounts 2 to 4 are optional, so the V1 account list still works,
+        /// but without the parameters account the fee can only go down.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pool instruction counters account
+        /// 3. `[]` - System program
+        /// 4. `[]` - Pool parameters account
+        UpdatePlatformFee { new_fee_percentage: u8 },
+
+        /// Pauses the pool (admin or guardian)
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority or guardian
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pool instruction counters account, optional
+        ///    like the system program for the V1 account list
+        /// 3. `[]` - System program
+        /// 4. `[]` - Pool guardians account, required when a guardian signs
+        PausePool,
+
+        /// Resumes the pool (admin only). With the payout queue passed, on
+        /// and holding tickets, opens its recovery window.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pool instruction counters account, optional
+        ///    like the system program for the V1 account list
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Payout queue account (optional)
+        ResumePool,
+
+        /// Summarizes a farmer's unclaimed pending rewards without changing
+        /// state. Walks at most `limit` accounts starting at `cursor` and
+        /// returns a `FarmerPendingSummary` as return data, with the
+        /// farmer's last activity times.
+        /// Accounts:
+        /// 0. `[]` - Reward pool account
+        /// 1. `[]` - Farmer state account
+        /// 2. `[]` - Farmer's pending reward accounts
+        GetFarmerPending {
+            farmer: Pubkey,
+            cursor: u32,
+            limit: u8,
+        },
+
+        /// Returns the pool totals and lifetime instruction counts as a
+        /// `PoolStats` without changing state
+        /// Accounts:
+        /// 0. `[]` - Reward pool account
+        /// 1. `[]` - Pool instruction counters account
+        /// 2. `[]` - Optional withdraw burn account, for the burned total
+        GetPoolStats,
+
+        /// Drains reward accounts delegated to a custodian into a single
+        /// destination, moving up to the delegated amount of each
+        /// Accounts:
+        /// 0. `[signer, writable]` - Custodian, delegate of every reward account
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Destination token account
+        /// 3. `[]` - Token program
+        /// 4. `[writable]` - Pool stats account for the current day
+        /// 5. `[]` - System program
+        /// 6. `[writable]` - Pool instruction counters account
+        /// 7. `[writable]` - Delegated reward accounts, as many as fit
+        SweepDelegatedRewards,
+
+        /// Whitelists a withdrawal destination token account for the
+        /// farmer. It becomes usable after the pool's whitelist activation
+        /// delay; from then on, while any entry exists, withdrawals only go
+        /// to whitelisted destinations.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Farmer's withdrawal whitelist account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        /// 5. `[]` - Pool parameters account
+        AddWithdrawalDestination { destination: Pubkey },
+
+        /// Removes a withdrawal destination from the farmer's whitelist,
+        /// effective immediately
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Farmer's withdrawal whitelist account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        RemoveWithdrawalDestination { destination: Pubkey },
+
+        /// Puts withdrawals from the pool's treasury vault under co-signer
+        /// control. Can only be done once.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Treasury governance account
+        /// 3. `[]` - Treasury vault token account
+        /// 4. `[]` - Reward token mint
+        /// 5. `[]` - Token program
+        /// 6. `[]` - System program
+        /// 7. `[writable]` - Pool instruction counters account
+        ConfigureTreasuryGovernance {
+            approvers: Vec<Pubkey>,
+            required_approvals: u8,
+            /// Withdrawals above this amount need approvals and the delay
+            approval_threshold: u64,
+            /// Seconds between proposal and execution above the threshold
+            execution_delay: i64,
+        },
+
+        /// Proposes moving `amount` from the treasury vault to a token
+        /// account. The proposal takes the governance's next proposal id and
+        /// counts as the proposer's approval if they are an approver.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority or approver
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Treasury governance account
+        /// 3. `[writable]` - Treasury proposal account
+        /// 4. `[]` - Destination token account
+        /// 5. `[]` - System program
+        /// 6. `[writable]` - Pool instruction counters account
+        ProposeTreasuryWithdrawal { amount: u64 },
+
+        /// Approves a treasury withdrawal proposal
+        /// Accounts:
+        /// 0. `[signer, writable]` - Approver
+        /// 1. `[]` - Reward pool account
+        /// 2. `[]` - Treasury governance account
+        /// 3. `[writable]` - Treasury proposal account
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        ApproveTreasuryWithdrawal,
+
+        /// Executes a treasury withdrawal proposal. Once the day's
+        /// withdrawals with this one exceed the threshold, it needs the
+        /// required approvals and its execution delay to have passed; anyone
+        /// can execute it then. Tokens owed to farmers' pending rewards
+        /// cannot be withdrawn.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Executor
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Treasury governance account
+        /// 3. `[writable]` - Treasury proposal account
+        /// 4. `[writable]` - Treasury vault token account
+        /// 5. `[writable]` - Destination token account
+        /// 6. `[]` - Token program
+        /// 7. `[]` - System program
+        /// 8. `[writable]` - Pool instruction counters account
+        ExecuteTreasuryWithdrawal,
+
+        /// Hands the program's upgrade authority, held by the pool's upgrade
+        /// authority PDA, to another key such as a multisig or timelock.
+        /// Needs the platform authority and the required number of treasury
+        /// approvers.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[]` - Treasury governance account
+        /// 3. `[writable]` - Program data account
+        /// 4. `[]` - Pool upgrade authority PDA, current upgrade authority
+        /// 5. `[]` - Upgradeable BPF loader program
+        /// 6. `[]` - System program
+        /// 7. `[writable]` - Pool instruction counters account
+        /// 8. `[]` - New upgrade authority
+        /// 9. `[signer]` - Treasury approvers, as many as required
+        SetUpgradeAuthority,
+
+        /// Removes the program's upgrade authority, making the program
+        /// immutable. Same approvals as `SetUpgradeAuthority`.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[]` - Treasury governance account
+        /// 3. `[writable]` - Program data account
+        /// 4. `[]` - Pool upgrade authority PDA, current upgrade authority
+        /// 5. `[]` - Upgradeable BPF loader program
+        /// 6. `[]` - System program
+        /// 7. `[writable]` - Pool instruction counters account
+        /// 8. `[signer]` - Treasury approvers, as many as required
+        RemoveUpgradeAuthority,
+
+        /// Sets the pool's tunable limits. Same approvals as
+        /// `SetUpgradeAuthority`.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[]` - Treasury governance account
+        /// 3. `[writable]` - Pool parameters account
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        /// 6. `[writable]` - Pool parameter history account
+        /// 7. `[signer]` - Treasury approvers, as many as required
+        UpdateParameters { values: ParameterValues },
+
+        /// Replaces the keys allowed to pause the pool, empty to revoke
+        /// them all (admin only)
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pool guardians account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        SetGuardians { guardians: Vec<Pubkey> },
+
+        /// Queues an admin action to run at or after `eta`, at least
+        /// `MIN_ACTION_DELAY` from now (admin only). Actions that need
+        /// treasury approvals when run directly need them here.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pending action account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        /// 5. `[]` - Treasury governance account
+        /// 6. `[signer]` - Treasury approvers, as many as required
+        ScheduleAction {
+            action_id: u64,
+            eta: i64,
+            action: ScheduledAction,
+        },
+
+        /// Drops a queued action (admin only)
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pending action account
+        /// 3. `[writable]` - Payer of the pending action, refunded
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        CancelAction,
+
+        /// Runs a queued action once its ETA is reached. Anyone can execute.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Executor
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pending action account
+        /// 3. `[writable]` - Payer of the pending action, refunded
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        /// 6. Action accounts:
+        ///    - `UpdatePlatformFee`: `[]` pool parameters account
+        ///    - `UpdateParameters`: `[writable]` pool parameters account,
+        ///      `[writable]` pool parameter history account
+        ///    - `SetPlatformTreasury`: `[]` new platform treasury, which must
+        ///      be the pool's treasury vault
+        ///    - `SetPlatformAuthority`, `RotatePlatformAuthority`:
+        ///      `[writable]` key history account, grown at the executor's
+        ///      expense
+        ///    - `SwapTreasury`: `[writable]` treasury swap policy account,
+        ///      `[writable]` platform fee vault, `[writable]` treasury reserve
+        ///      of the output mint, `[]` output mint, `[]` token program,
+        ///      `[]` DEX program, then the accounts of the swap, passed
+        ///      through to the DEX in order
+        ExecuteAction,
+
+        /// Restores the value a parameter had before its last change, within
+        /// `PARAMETER_ROLLBACK_WINDOW` of that change (admin only)
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pool parameters account
+        /// 3. `[writable]` - Pool parameter history account
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        RollbackParameter { parameter: Parameter },
+
+        /// Pauses every listed pool the signer is the platform authority
+        /// of, skipping and logging the others. Not counted, as it spans
+        /// pools.
+        /// Accounts:
+        /// 0. `[signer]` - Platform authority
+        /// 1. `[writable]` - Reward pool accounts
+        PauseAll,
+
+        /// Sets the fee of every listed pool the signer is the platform
+        /// authority of, skipping and logging the others and the pools
+        /// whose maximum fee is lower. Not counted, as it spans pools.
+        /// Accounts:
+        /// 0. `[signer]` - Platform authority
+        /// 1. Per pool, in pairs:
+        ///    - `[writable]` - Reward pool account
+        ///    - `[]` - Pool parameters account
+        SetFeeAll { new_fee_percentage: u8 },
+
+        /// Sets the pool's display name and metadata URI; empty strings
+        /// clear them (admin only)
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pool metadata account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        SetPoolMetadata { name: String, uri: String },
+
+        /// Freezes the pool for migration to a new program deployment and
+        /// commits to the farmer balances it owes. The pool is paused and
+        /// handed over to its state export account for good (admin only)
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - State export account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        ExportState {
+            /// Root of the merkle tree over `(farmer, amount)` balances
+            merkle_root: [u8; 32],
+            farmer_count: u32,
+            total_amount: u64,
+        },
+
+        /// Books a farmer balance exported by a pool of a previous
+        /// deployment, proven against its state export, as the farmer's
+        /// pending reward for `ImportedBalance::TASK_ID`, owed from the
+        /// treasury vault. Each farmer can be imported once per pool (admin
+        /// only)
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[]` - State export account, owned by the previous deployment
+        /// 3. `[writable]` - Imported balance account of the farmer
+        /// 4. `[]` - Platform treasury, the pool's treasury vault
+        /// 5. `[writable]` - Pending reward account of the farmer for
+        ///    `ImportedBalance::TASK_ID`
+        /// 6. `[]` - Reward token mint, unused
+        /// 7. `[]` - Token program, unused
+        /// 8. `[]` - Associated token program, unused
+        /// 9. `[]` - System program
+        /// 10. `[writable]` - Pool instruction counters account
+        ImportState {
+            /// Program id of the previous deployment
+            source_program_id: Pubkey,
+            farmer: Pubkey,
+            amount: u64,
+            /// Sibling hashes from the farmer's leaf up to the root
+            proof: Vec<[u8; 32]>,
+        },
+
+        /// Archives a finished pool: rewards and admin changes stop for
+        /// good, farmers keep withdrawing until `claim_deadline` (admin only)
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pool archive account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        ArchivePool { claim_deadline: i64 },
+
+        /// Closes an archived pool once its claim deadline has passed and
+        /// every reward owed to farmers has been paid out, refunding the
+        /// rent of the pool, archive and counters accounts. The treasury,
+        /// platform fee and bonus vaults are emptied into the destination
+        /// and closed, and the withdrawal fee vault is closed, its fees
+        /// and the rent going to the authority.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority that archived the pool
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pool archive account
+        /// 3. `[writable]` - Pool instruction counters account
+        /// 4. `[writable]` - Treasury vault
+        /// 5. `[writable]` - Platform fee vault
+        /// 6. `[writable]` - Bonus vault
+        /// 7. `[writable]` - Withdrawal fee vault
+        /// 8. `[writable]` - Token account of the reward mint receiving the
+        ///    vaults' tokens
+        /// 9. `[]` - Token program
+        ClosePool,
+
+        /// Pays out up to `MAX_CLAIM_BATCH` unclaimed pending rewards of the
+        /// farmer from the pool's treasury vault in a single transfer, and
+        /// marks them withdrawn. Rewards already withdrawn are skipped, but
+        /// interest still owed on them is paid. The interest the rewards
+        /// accrued, if the pool pays any, comes on top from the bonus vault,
+        /// and the secondary rewards the farmer is owed are
+        /// paid from the secondary vault in the same instruction. A farmer
+        /// with a guild split has its share of the rewards paid to the
+        /// guild vault. Once the farmer has a payout address in effect, the
+        /// rewards and secondary rewards go to token accounts it owns, and
+        /// once they have whitelisted destinations, to accounts on their
+        /// whitelist. A farmer with a withdrawal cosigner needs it to sign.
+        /// The pool's withdrawal burn and lamport fee apply as on
+        /// `WithdrawReward`, the farmer paying the fee. The claim is counted
+        /// in the farmer's claim shard rather than the pool's instruction
+        /// counters.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Treasury vault token account
+        /// 3. `[writable]` - Farmer reward account
+        /// 4. `[]` - Token program
+        /// 5. `[]` - System program
+        /// 6. `[writable]` - Farmer's claim shard account (PDA
+        ///    `["claim_shard", pool, claim_shard_index(farmer)]`)
+        /// 7. `[writable]` - Farmer state account
+        /// 8. `[writable]` - Interest policy account
+        /// 9. `[writable]` - Bonus vault (PDA `["bonus_vault", pool]`)
+        /// 10. `[writable]` - Secondary reward account
+        /// 11. `[writable]` - Secondary vault
+        /// 12. `[writable]` - Farmer's secondary balance account
+        /// 13. `[writable]` - Farmer's token account of the secondary mint,
+        ///     ignored when they are owed none
+        /// 14. `[]` - Payout queue account
+        /// 15. `[]` - Farmer's payout ticket account
+        /// 16. `[writable]` - Withdrawal ramp account
+        /// 17. `[writable]` - Farmer's guild vault, ignored without a guild split
+        /// 18. `[]` - Farmer's payout address account
+        /// 19. `[]` - Farmer's withdrawal whitelist account
+        /// 20. `[signer]` - Farmer's withdrawal cosigner, any account when
+        ///     the farmer has none
+        /// 21. `[writable]` - Withdraw burn account
+        /// 22. `[writable]` - Reward mint, burned from with a withdraw burn
+        /// 23. `[writable]` - Withdrawal fee account
+        /// 24. `[writable]` - Fee vault
+        /// 25. `[writable]` - Farmer's pending reward accounts
+        ClaimMany,
+
+        /// Locks the farmer's withdrawals to token accounts owned by
+        /// `payout_address` once the pool's activation delay has passed.
+        /// `Pubkey::default()` unregisters, after the same delay; the
+        /// address in effect cancels a pending change immediately.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Farmer's payout address account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        /// 5. `[]` - Pool parameters account
+        RegisterPayoutAddress { payout_address: Pubkey },
+
+        /// Sets the token account farmers can donate pending rewards to
+        /// with `DonateReward`
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pool charity account
+        /// 3. `[]` - Charity token account of the reward mint
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        SetCharityAccount,
+
+        /// Forfeits an unclaimed pending reward instead of claiming it: the
+        /// tokens stay in the treasury vault, go to the pool's charity
+        /// account or are burned. The pending reward account is closed and
+        /// its rent returned to the farmer.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pending reward account
+        /// 3. `[writable]` - Treasury vault (PDA `["vault", pool]`)
+        /// 4. `[writable]` - Reward token mint
+        /// 5. `[]` - Token program
+        /// 6. `[]` - System program
+        /// 7. `[writable]` - Pool instruction counters account
+        /// 8. `[]` - Pool charity account (charity donations only)
+        /// 9. `[writable]` - Charity token account (charity donations only)
+        DonateReward { target: DonationTarget },
+
+        /// Closes the farmer's accounts in the pool that exist, for farmers
+        /// leaving the platform: the empty reward token account and the
+        /// farmer state, whose rent goes to the payer recorded in the
+        /// farmer state (the farmer when there is none), and the withdrawal
+        /// whitelist and payout address, whose rent goes to the farmer.
+        /// The whitelist must have no entry and no payout address may be in
+        /// effect or pending, so a stolen key cannot skip their delays.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Farmer's reward account
+        /// 3. `[writable]` - Farmer state account
+        /// 4. `[writable]` - Rent recipient
+        /// 5. `[writable]` - Farmer's withdrawal whitelist account
+        /// 6. `[writable]` - Farmer's payout address account
+        /// 7. `[]` - Token program
+        /// 8. `[]` - System program
+        /// 9. `[writable]` - Pool instruction counters account
+        CloseFarmerAccounts,
+
+        /// Turns auto-forward on or off for the farmer. While it is on,
+        /// `RecordReward` transfers rewards straight to the destination
+        /// token account instead of the farmer's reward account. The
+        /// destination must be allowed by the farmer's withdrawal whitelist
+        /// and payout address when it is set; it is ignored when turning
+        /// auto-forward off.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Farmer state account
+        /// 3. `[]` - Destination token account of the reward mint
+        /// 4. `[]` - Farmer's withdrawal whitelist account
+        /// 5. `[]` - Farmer's payout address account
+        /// 6. `[]` - System program
+        /// 7. `[writable]` - Pool instruction counters account
+        SetAutoForward { enabled: bool },
+
+        /// Allows or forbids `TransferPending` in the pool, for pools with
+        /// compliance requirements on who gets paid
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pool pending transfer policy account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        SetPendingTransferPolicy { transfers_disabled: bool },
+
+        /// Reassigns an unclaimed pending reward to `new_farmer`, e.g. from a
+        /// team account to a member's wallet. The reward moves to the pending
+        /// reward account of `new_farmer` for the same task, paid by the
+        /// current beneficiary. The old account stays behind as a withdrawn
+        /// reward of 0 tokens, so the task cannot be recorded for the
+        /// current beneficiary again. A
+        /// beneficiary with whitelisted destinations cannot transfer, and
+        /// one with a payout address in effect can only transfer to it. A
+        /// beneficiary with a withdrawal cosigner needs it to sign.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Current beneficiary
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Pending reward account
+        /// 3. `[writable]` - Pending reward account of `new_farmer` for the task
+        /// 4. `[]` - Pool pending transfer policy account
+        /// 5. `[]` - Beneficiary's withdrawal whitelist account
+        /// 6. `[]` - Beneficiary's payout address account
+        /// 7. `[]` - System program
+        /// 8. `[writable]` - Pool instruction counters account
+        /// 9. `[]` - Beneficiary's farmer state account
+        /// 10. `[signer]` - Beneficiary's withdrawal cosigner, any account
+        ///     when the beneficiary has none
+        TransferPending { new_farmer: Pubkey },
+
+        /// Returns the farmer's last `WithdrawalHistory::CAPACITY`
+        /// withdrawals as a `WithdrawalHistorySummary` without changing state
+        /// Accounts:
+        /// 0. `[]` - Reward pool account
+        /// 1. `[]` - Farmer's withdrawal history account
+        GetWithdrawalHistory { farmer: Pubkey },
+
+        /// Sets the pending total at which anyone may claim the farmer's
+        /// pending rewards to their payout address with `CrankAutoClaim`,
+        /// 0 to turn auto-claim off. Turning it on requires a payout
+        /// address in effect.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Farmer state account
+        /// 3. `[]` - Farmer's payout address account
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        SetAutoClaim { threshold: u64 },
+
+        /// Permissionless: claims up to `MAX_CLAIM_BATCH` pending rewards of
+        /// a farmer with auto-claim on, passed as remaining accounts, once
+        /// they add up to the farmer's threshold. They are paid from the
+        /// treasury vault to a token account of the farmer's payout address,
+        /// less the pool's withdrawal burn, a tip of `AUTO_CLAIM_TIP_BPS` to
+        /// the cranker and the share of the farmer's guild split, if any. The
+        /// cranker pays the pool's lamport withdrawal fee. Counted in the
+        /// farmer's claim shard like `ClaimMany`.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Cranker
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Treasury vault (PDA `["vault", pool]`)
+        /// 3. `[]` - Farmer
+        /// 4. `[writable]` - Farmer state account
+        /// 5. `[]` - Farmer's withdrawal whitelist account
+        /// 6. `[]` - Farmer's payout address account
+        /// 7. `[writable]` - Destination token account owned by the payout address
+        /// 8. `[writable]` - Cranker's token account of the reward mint
+        /// 9. `[]` - Token program
+        /// 10. `[]` - System program
+        /// 11. `[writable]` - Farmer's claim shard account
+        /// 12. `[writable]` - Interest policy account
+        /// 13. `[writable]` - Bonus vault (PDA `["bonus_vault", pool]`)
+        /// 14. `[]` - Payout queue account
+        /// 15. `[]` - Farmer's payout ticket account
+        /// 16. `[writable]` - Withdrawal ramp account
+        /// 17. `[writable]` - Farmer's guild vault, ignored without a guild split
+        /// 18. `[writable]` - Withdraw burn account
+        /// 19. `[writable]` - Reward mint, burned from with a withdraw burn
+        /// 20. `[writable]` - Withdrawal fee account
+        /// 21. `[writable]` - Fee vault
+        /// 22. `[writable]` - Farmer's pending reward accounts (remaining accounts)
+        CrankAutoClaim,
+
+        /// Sets the verifier whose signature `RecordReward` requires, or
+        /// removes it with `None`. Once a verifier is set, only a change it
+        /// signs is accepted, so the platform alone cannot drop it.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pool verifier account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        /// 5. `[signer]` - Current verifier, when the pool has one
+        SetVerifier { verifier: Option<Pubkey> },
+
+        /// Sets the program whose `TaskAttestation` accounts `RecordReward`
+        /// requires, read at `data_offset` of the account, or removes it
+        /// with `None`
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pool attestor account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        SetAttestor {
+            attestor_program: Option<Pubkey>,
+            data_offset: u16,
+        },
+
+        /// Moves a task's expected reward from the treasury vault into the
+        /// pool's budget escrow before the task is assigned, proving the
+        /// funds exist. `RecordReward` for the task pays from the budget.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Treasury vault (PDA `["vault", pool]`)
+        /// 3. `[writable]` - Pool budget escrow (PDA `["budget_escrow", pool]`)
+        /// 4. `[writable]` - Task budget account (PDA `["task_budget", pool, sha256(task_id)]`)
+        /// 5. `[]` - Reward token mint
+        /// 6. `[]` - Token program
+        /// 7. `[]` - System program
+        /// 8. `[writable]` - Pool instruction counters account
+        LockTaskBudget {
+            task_id: String,
+            amount: u64,
+            /// Unix timestamp after which an unused budget can be released
+            expires_at: i64,
+        },
+
+        /// Permissionless: returns what is left of a task budget to the
+        /// treasury vault once its reward is recorded or it expired, and
+        /// closes the budget account, refunding its rent to its payer
+        /// Accounts:
+        /// 0. `[signer, writable]` - Caller
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Treasury vault (PDA `["vault", pool]`)
+        /// 3. `[writable]` - Pool budget escrow (PDA `["budget_escrow", pool]`)
+        /// 4. `[writable]` - Task budget account
+        /// 5. `[writable]` - Payer recorded in the task budget
+        /// 6. `[]` - Token program
+        /// 7. `[]` - System program
+        /// 8. `[writable]` - Pool instruction counters account
+        ReleaseUnusedBudget,
+
+        /// Challenges a pending reward within `CHALLENGE_WINDOW` of its
+        /// recording, escrowing a `CHALLENGE_BOND` and locking the reward
+        /// until the challenge is resolved
+        /// Accounts:
+        /// 0. `[signer, writable]` - Challenger
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Pending reward account
+        /// 3. `[writable]` - Reward challenge account (PDA `["reward_challenge", pool, pending_reward]`)
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        ChallengeReward,
+
+        /// Decides a challenge. Upholding it voids the pending reward and
+        /// returns the bond; rejecting it unlocks the reward and pays the
+        /// bond to the farmer.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority or pool verifier
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[]` - Pool verifier account
+        /// 3. `[writable]` - Pending reward account
+        /// 4. `[writable]` - Reward challenge account
+        /// 5. `[writable]` - Challenger
+        /// 6. `[writable]` - Farmer of the pending reward
+        /// 7. `[]` - System program
+        /// 8. `[writable]` - Pool instruction counters account
+        ResolveChallenge { upheld: bool },
+
+        /// Turns maker-checker mode on with `approver`, who must not be the
+        /// platform authority, or off with `None`. In that mode
+        /// `RecordReward` books rewards as pending, awaiting approval.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Reward approval account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        SetRewardApprover { approver: Option<Pubkey> },
+
+        /// Makes a pending reward awaiting approval claimable
+        /// Accounts:
+        /// 0. `[signer, writable]` - Approver
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Reward approval account
+        /// 3. `[writable]` - Pending reward account
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        ApproveReward,
+
+        /// Sets the platform fee of a task type, within the pool's maximum
+        /// fee, or removes its override with `None`
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[]` - Pool parameters account
+        /// 3. `[writable]` - Fee overrides account
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        SetTaskTypeFee {
+            task_type: u32,
+            fee_percentage: Option<u8>,
+        },
+
+        /// Claims a platform id, making the signer its operator. Not
+        /// counted, as it involves no pool.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Operator
+        /// 1. `[writable]` - Platform account (PDA `["platform", platform_id]`)
+        /// 2. `[]` - System program
+        RegisterPlatform { platform_id: u64 },
+
+        /// Hands a platform to a new operator. Not counted, as it involves
+        /// no pool.
+        /// Accounts:
+        /// 0. `[signer]` - Current operator
+        /// 1. `[writable]` - Platform account
+        SetPlatformOperator { operator: Pubkey },
+
+        /// Initializes a pool in a platform namespace, at the PDA
+        /// `["reward_pool", platform_id, reward_mint]`, with the platform's
+        /// operator as its platform authority
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform operator
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[]` - Reward token mint
+        /// 3. `[writable]` - Treasury vault (PDA `["vault", pool]`), created as
+        ///    the pool's platform treasury
+        /// 4. `[writable]` - Platform account
+        /// 5. `[]` - System program
+        /// 6. `[writable]` - Pool instruction counters account
+        /// 7. `[]` - Token program
+        InitializePlatformPool {
+            platform_id: u64,
+            platform_fee_percentage: u8,
+        },
+
+        /// Creates a campaign whose rewards draw on `budget` between
+        /// `starts_at` and `ends_at`, restricted to `task_types` unless empty.
+        /// The budget moves from the treasury vault into the budget escrow.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Campaign account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        /// 5. `[writable]` - Treasury vault (PDA `["vault", pool]`)
+        /// 6. `[writable]` - Budget escrow (PDA `["budget_escrow", pool]`)
+        /// 7. `[]` - Reward token mint
+        /// 8. `[]` - Token program
+        CreateCampaign {
+            campaign_id: u64,
+            budget: u64,
+            starts_at: i64,
+            ends_at: i64,
+            /// At most `Campaign::MAX_TASK_TYPES`
+            task_types: Vec<u32>,
+        },
+
+        /// Ends a campaign before its end date; it takes no further rewards
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Campaign account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        EndCampaign,
+
+        /// Returns the unspent budget of a campaign past its end to the
+        /// treasury vault and closes the campaign. Permissionless.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Caller
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Campaign account
+        /// 3. `[writable]` - Treasury vault
+        /// 4. `[writable]` - Budget escrow
+        /// 5. `[]` - Token program
+        /// 6. `[]` - System program
+        /// 7. `[writable]` - Pool instruction counters account
+        FinalizeCampaign,
+
+        /// Configures the pool's buyback-and-burn crank, creating its token
+        /// accounts on first use. `None` disables the crank. Same approvals
+        /// as `UpdateParameters`.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[]` - Treasury governance account
+        /// 3. `[writable]` - Buyback config account (PDA `["buyback", pool]`)
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        /// 6. `[writable]` - Buyback source (PDA `["buyback_source", pool]`)
+        /// 7. `[writable]` - Buyback output (PDA `["buyback_output", pool]`)
+        /// 8. `[]` - Reward token mint
+        /// 9. `[]` - Token mint bought back and burned
+        /// 10. `[]` - Token program
+        /// 11. `[signer]` - Treasury approvers, as many as required
+        ConfigureBuyback {
+            /// The only DEX program runs may swap through
+            dex_program: Option<Pubkey>,
+            /// Fixed by the first configuration
+            governance_mint: Pubkey,
+            /// Share of the fees collected since the last run that is
+            /// swapped, in basis points
+            slice_bps: u16,
+            /// Least output accepted per million tokens swapped
+            min_output_per_million: u64,
+        },
+
+        /// Moves the configured slice of the fees collected since the last
+        /// run from the platform fee vault, at most what it holds, to the
+        /// buyback source, swaps it through the configured DEX and burns the
+        /// output. Permissionless.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Caller
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Buyback config account
+        /// 3. `[writable]` - Platform fee vault (PDA `["platform_fee_vault", pool]`)
+        /// 4. `[writable]` - Buyback source
+        /// 5. `[writable]` - Buyback output
+        /// 6. `[writable]` - Token mint bought back and burned
+        /// 7. `[]` - Token program
+        /// 8. `[]` - System program
+        /// 9. `[writable]` - Pool instruction counters account
+        /// 10. `[]` - DEX program
+        /// 11. Accounts of the swap, passed through to the DEX in order
+        RunBuyback {
+            /// Instruction data of the swap, passed through to the DEX
+            swap_data: Vec<u8>,
+        },
+
+        /// Sets the DEX program and stable mints queued `SwapTreasury`
+        /// actions may use, and the share of the platform fee vault they may
+        /// swap per epoch. `None` disables swaps. Same approvals as
+        /// `UpdateParameters`.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[]` - Treasury governance account
+        /// 3. `[writable]` - Treasury swap policy account (PDA `["treasury_swap_policy", pool]`)
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        /// 6. `[signer]` - Treasury approvers, as many as required
+        SetTreasurySwapPolicy {
+            dex_program: Option<Pubkey>,
+            /// At most `TreasurySwapPolicy::MAX_STABLE_MINTS`
+            stable_mints: Vec<Pubkey>,
+            /// Share of the fee vault swappable per epoch, in basis points
+            epoch_limit_bps: u16,
+        },
+
+        /// Sets the platform fee discounts of farmers by staking tier, read
+        /// from their `[stake_seed, farmer]` account of `staking_program`,
+        /// or removes them with `None`
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Fee discounts account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        SetFeeDiscounts {
+            staking_program: Option<Pubkey>,
+            /// At most 32 bytes
+            stake_seed: Vec<u8>,
+            /// Offset of the tier byte in the staking account
+            tier_offset: u16,
+            /// Share of the platform fee waived per tier, tier 0 first, at
+            /// most `FeeDiscounts::MAX_TIERS`
+            discounts_bps: Vec<u16>,
+        },
+
+        /// Sets the yearly interest unclaimed pending rewards accrue from now
+        /// until they are claimed, paid from the pool's bonus vault, which
+        /// anyone funds with a token transfer. What rewards accrued so far
+        /// stays at the earlier rate. A `rate_bps` of 0 turns it off.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Interest policy account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        /// 5. `[writable]` - Bonus vault (PDA `["bonus_vault", pool]`)
+        /// 6. `[]` - Reward mint
+        /// 7. `[]` - Token program
+        SetInterestPolicy {
+            /// Simple yearly rate, at most `MAX_INTEREST_RATE_BPS`
+            rate_bps: u16,
+            /// Longest time a reward accrues interest for
+            max_accrual_secs: i64,
+        },
+
+        /// Sets the share of every `WithdrawReward` and claim burned instead
+        /// of paid out, up to `MAX_WITHDRAW_BURN_BPS`; 0 stops the burn
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Withdraw burn account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        SetWithdrawBurn { withdraw_burn_bps: u16 },
+
+        /// Configures the reward governor, which scales newly recorded
+        /// rewards down while the platform treasury's runway, its balance
+        /// over the rolling daily rate it paid out, is below
+        /// `min_runway_days`; 0 turns it off
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Reward governor account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        SetRewardGovernor {
+            min_runway_days: u32,
+            /// Smallest factor rewards are scaled by, in basis points
+            floor_bps: u16,
+            /// Factor rewards are scaled by while the runway is long enough,
+            /// at most `BASIS_POINTS`
+            ceiling_bps: u16,
+        },
+
+        /// Sets the pool's secondary mint, e.g. a governance token, and
+        /// creates the vault backing its rewards. The mint cannot change
+        /// once set.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Secondary reward account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        /// 5. `[writable]` - Secondary vault (PDA `["secondary_vault", pool]`)
+        /// 6. `[]` - Secondary mint
+        /// 7. `[]` - Token program
+        SetSecondaryMint,
+
+        /// Sets the bonuses farmers are booked as their lifetime earnings
+        /// cross each threshold, or turns them off with none
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Milestone bonuses account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        SetMilestoneBonuses {
+            /// Increasing thresholds, at most `MilestoneBonuses::MAX_MILESTONES`
+            milestones: Vec<Milestone>,
+        },
+
+        /// Configures the pool's protocol-owned liquidity contributions,
+        /// creating its token accounts on first use. No AMM programs or a
+        /// zero share disables them. Same approvals as `UpdateParameters`.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[]` - Treasury governance account
+        /// 3. `[writable]` - Protocol liquidity account (PDA `["protocol_liquidity", pool]`)
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        /// 6. `[writable]` - POL vault (PDA `["pol_vault", pool]`)
+        /// 7. `[writable]` - POL pair vault (PDA `["pol_pair_vault", pool]`)
+        /// 8. `[writable]` - POL LP vault (PDA `["pol_lp_vault", pool]`)
+        /// 9. `[]` - Reward token mint
+        /// 10. `[]` - Token mint paired with the reward mint, fixed by the
+        ///     first configuration
+        /// 11. `[]` - LP token mint of the AMM pool, fixed by the first
+        ///     configuration
+        /// 12. `[]` - Token program
+        /// 13. `[signer]` - Treasury approvers, as many as required
+        SetProtocolLiquidity {
+            /// The only AMM programs contributions may go through, at most
+            /// `ProtocolLiquidity::MAX_AMM_PROGRAMS`
+            amm_programs: Vec<Pubkey>,
+            /// Share of the fees collected since the last contribution that
+            /// is contributed, in basis points
+            pol_bps: u16,
+            /// Least LP tokens accepted per million reward tokens contributed
+            min_lp_per_million: u64,
+        },
+
+        /// Moves the configured share of the fees collected since the last
+        /// contribution from the platform fee vault, at most what it holds, to
+        /// the POL vault, swaps part of it into the pair mint and deposits
+        /// both sides through a whitelisted AMM, the LP tokens going to the
+        /// LP vault. Permissionless.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Caller
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Protocol liquidity account
+        /// 3. `[writable]` - Platform fee vault
+        /// 4. `[writable]` - POL vault
+        /// 5. `[writable]` - POL pair vault
+        /// 6. `[writable]` - POL LP vault
+        /// 7. `[]` - Token program
+        /// 8. `[]` - System program
+        /// 9. `[writable]` - Pool instruction counters account
+        /// 10. `[]` - AMM program
+        /// 11. Accounts of the swap, `swap_account_count` of them, then
+        ///     accounts of the deposit, passed through to the AMM in order
+        ContributeLiquidity {
+            /// Instruction data of the swap pairing part of the POL vault,
+            /// passed through to the AMM; empty to deposit without swapping
+            swap_data: Vec<u8>,
+            swap_account_count: u8,
+            /// Instruction data of the deposit, passed through to the AMM
+            deposit_data: Vec<u8>,
+        },
+
+        /// Sets the share of platform fees rebated to farmers in the
+        /// governance token, which must be the pool's secondary mint,
+        /// creating the rebate vault on first use. A zero share turns
+        /// rebates off.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Fee rebate account (PDA `["fee_rebate", pool]`)
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        /// 5. `[]` - Secondary reward account (PDA `["secondary_reward", pool]`)
+        /// 6. `[writable]` - Rebate vault (PDA `["rebate_vault", pool]`)
+        /// 7. `[]` - Governance token mint
+        /// 8. `[]` - Token program
+        SetFeeRebate {
+            /// Share of each platform fee rebated, in basis points
+            rebate_bps: u16,
+            /// Governance tokens rebated per million reward tokens of fee
+            /// rebated
+            rate_per_million: u64,
+        },
+
+        /// Sets the protocol-wide configuration of the pool factory. The
+        /// program's upgrade authority creates it, its admin changes it
+        /// afterwards. Not counted, as it involves no pool.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Upgrade authority when creating the
+        ///    configuration, its admin otherwise
+        /// 1. `[writable]` - Protocol config account (PDA `["protocol_config"]`)
+        /// 2. `[]` - Program data account of the program
+        /// 3. `[]` - System program
+        SetProtocolConfig {
+            admin: Pubkey,
+            /// Wallet receiving creation fees, whose token accounts receive
+            /// the protocol's share of platform fees
+            treasury: Pubkey,
+            /// Lamports a pool creator pays
+            creation_fee: u64,
+            /// Share of factory pools' platform fees owed to the protocol,
+            /// at most `MAX_PROTOCOL_FEE_SHARE_BPS`
+            protocol_fee_share_bps: u16,
+        },
+
+        /// Creates a pool for anyone, at the PDA `["factory_pool", creator,
+        /// reward_mint, seed]`, with the creator as its platform authority.
+        /// The creator pays the protocol's creation fee, and the pool's
+        /// rewards route the protocol fee share in effect now to the
+        /// protocol treasury.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Creator
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[]` - Reward token mint
+        /// 3. `[writable]` - Treasury vault (PDA `["vault", pool]`), created as
+        ///    the pool's platform treasury
+        /// 4. `[writable]` - Factory record account (PDA `["factory_record", pool]`)
+        /// 5. `[writable]` - Protocol config account (PDA `["protocol_config"]`)
+        /// 6. `[writable]` - Protocol treasury wallet
+        /// 7. `[]` - System program
+        /// 8. `[writable]` - Pool instruction counters account
+        /// 9. `[]` - Token program
+        CreatePool {
+            /// Distinguishes the creator's pools of the same mint
+            seed: u64,
+            platform_fee_percentage: u8,
+        },
+
+        /// Claims the farmer's pending rewards in up to `MAX_CLAIM_POOLS`
+        /// pools at once, each group settled like a `ClaimMany` of its pool
+        /// and the whole claim failing if any group does. `MAX_CLAIM_BATCH`
+        /// caps the pending rewards of all groups together. Secondary
+        /// rewards are left for `ClaimMany`, as are the claims of farmers
+        /// with a guild split. Not counted, as it spans several pools.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[]` - Token program
+        /// 2. `[]` - System program
+        /// 3. `[signer]` - Farmer's withdrawal cosigner in every pool, any
+        ///    account when the farmer has none
+        /// 4. Then, for each entry of `pending_counts`:
+        ///    - `[writable]` - Reward pool account
+        ///    - `[writable]` - Treasury vault token account
+        ///    - `[writable]` - Farmer's token account of the pool's reward mint
+        ///    - `[writable]` - Farmer state account
+        ///    - `[writable]` - Interest policy account
+        ///    - `[writable]` - Bonus vault (PDA `["bonus_vault", pool]`)
+        ///    - `[]` - Payout queue account
+        ///    - `[]` - Farmer's payout ticket account
+        ///    - `[writable]` - Withdrawal ramp account
+        ///    - `[]` - Farmer's payout address account
+        ///    - `[]` - Farmer's withdrawal whitelist account
+        ///    - `[writable]` - Withdraw burn account
+        ///    - `[writable]` - Reward mint, burned from with a withdraw burn
+        ///    - `[writable]` - Withdrawal fee account
+        ///    - `[writable]` - Fee vault
+        ///    - `[writable]` - That many of the farmer's pending reward accounts
+        ClaimAcrossPools {
+            /// Pending rewards claimed in each pool, in account order
+            pending_counts: Vec<u8>,
+        },
+
+        /// Issues a reward voucher redeemable later, for offline events.
+        /// Moves `amount` from the treasury vault into the pool's voucher
+        /// escrow and mints the voucher's NFT, a mint of supply one with no
+        /// authority left, to the recipient's associated token account.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Treasury vault (PDA `["vault", pool]`)
+        /// 3. `[writable]` - Pool voucher escrow (PDA `["voucher_escrow", pool]`)
+        /// 4. `[writable]` - Voucher account (PDA `["voucher", pool, voucher_id]`)
+        /// 5. `[writable]` - Voucher NFT mint (PDA `["voucher_mint", voucher]`)
+        /// 6. `[]` - Recipient wallet
+        /// 7. `[writable]` - Recipient's associated token account of the voucher mint
+        /// 8. `[]` - Reward token mint
+        /// 9. `[]` - Token program
+        /// 10. `[]` - Associated token program
+        /// 11. `[]` - System program
+        /// 12. `[writable]` - Pool instruction counters account
+        IssueVoucher { voucher_id: u64, amount: u64 },
+
+        /// Redeems a voucher: burns the holder's NFT, closing its token
+        /// account, and pays the voucher's tokens from the voucher escrow.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Voucher holder
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Voucher account
+        /// 3. `[writable]` - Voucher NFT mint
+        /// 4. `[writable]` - Holder's token account of the voucher mint
+        /// 5. `[writable]` - Pool voucher escrow
+        /// 6. `[writable]` - Destination token account of the reward mint
+        /// 7. `[]` - Token program
+        /// 8. `[]` - System program
+        /// 9. `[writable]` - Pool instruction counters account
+        RedeemVoucher,
+
+        /// Sets the recovery window of the pool's payout queue, capped at
+        /// `MAX_RECOVERY_WINDOW`. While it is on, farmers take tickets
+        /// during a pause and claims after `ResumePool` go in ticket order
+        /// for the window. A `recovery_window` of 0 turns it off and ends
+        /// any window in progress.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Payout queue account (PDA `["payout_queue", pool]`)
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        SetPayoutQueue { recovery_window: i64 },
+
+        /// Takes the farmer's ticket for the recovery window after the
+        /// current pause, scored by the oldest claimable reward among the
+        /// pending reward accounts passed. Only while the pool is paused
+        /// and its payout queue is on; one ticket per farmer and round.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Payout queue account
+        /// 3. `[writable]` - Payout ticket (PDA `["payout_ticket", pool, farmer]`)
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        /// 6. `[]` - Farmer's pending reward accounts (remaining accounts)
+        TakePayoutTicket,
+
+        /// Resumes the pool (admin only) with claims from the treasury vault
+        /// capped per epoch: `initial_epoch_cap` in the epoch of the resume,
+        /// `epoch_cap_step` more in each following one, and no cap once
+        /// `ramp_epochs` epochs have passed. A `ramp_epochs` of 0 ends any
+        /// ramp in progress. Opens the payout queue's recovery window like
+        /// `ResumePool`.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Pool instruction counters account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Withdrawal ramp account (PDA `["withdrawal_ramp", pool]`)
+        /// 5. `[writable]` - Payout queue account (optional)
+        ResumeWithRamp {
+            initial_epoch_cap: u64,
+            epoch_cap_step: u64,
+            ramp_epochs: u16,
+        },
+
+        /// Designates the wallet, such as a DAO's governance account, that
+        /// may take over the pool once the platform authority has been
+        /// inactive for `inactivity_timeout` seconds, at least
+        /// `MIN_INACTIVITY_TIMEOUT`. Every counted instruction the authority
+        /// signs restarts the timeout. `Pubkey::default()` turns the switch
+        /// off.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Authority recovery account (PDA `["authority_recovery", pool]`)
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        SetRecoveryAuthority {
+            recovery_authority: Pubkey,
+            inactivity_timeout: i64,
+        },
+
+        /// Makes the recovery authority the pool's platform authority once
+        /// the current one has been inactive for the configured timeout.
+        /// The switch is turned off until the new authority sets it again.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Recovery authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Authority recovery account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        /// 5. `[writable]` - Key history account (PDA `["key_history", pool]`)
+        ClaimDormantAuthority,
+
+        /// Names up to `FarmerRecovery::MAX_GUARDIANS` guardians who can
+        /// together recover the farmer's rewards in the pool, `threshold` of
+        /// them being needed. An empty list with a threshold of 0 turns
+        /// recovery off. Cancels any recovery in progress.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Farmer recovery account (PDA `["farmer_recovery", pool, farmer]`)
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        SetRecoveryGuardians {
+            guardians: Vec<Pubkey>,
+            threshold: u8,
+        },
+
+        /// Starts recovering the farmer's rewards to `new_wallet`, signed by
+        /// the threshold of their guardians. Replaces any recovery in
+        /// progress and restarts `FARMER_RECOVERY_DELAY`.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Guardian
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Farmer recovery account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        /// 5. `[signer]` - Further guardians (remaining accounts)
+        InitiateFarmerRecovery { new_wallet: Pubkey },
+
+        /// Cancels the recovery in progress of the signing farmer's rewards
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Farmer recovery account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        CancelFarmerRecovery,
+
+        /// Moves the farmer's state and up to `MAX_CLAIM_BATCH` pending
+        /// rewards to the recovery's new wallet once `FARMER_RECOVERY_DELAY`
+        /// has passed. Permissionless and repeatable, so rewards recorded
+        /// for the lost wallet later can follow. The farmer state's rent is
+        /// refunded to the payer, while each moved pending reward stays
+        /// behind as a withdrawn reward of 0 tokens, so its task cannot be
+        /// recorded for the lost wallet again.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Payer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[]` - Farmer recovery account
+        /// 3. `[writable]` - Farmer's state account
+        /// 4. `[writable]` - New wallet's state account
+        /// 5. `[]` - System program
+        /// 6. `[writable]` - Pool instruction counters account
+        /// 7. `[writable]` - Pairs of the farmer's pending reward account and
+        ///    the new wallet's account for the same task (remaining accounts)
+        CompleteFarmerRecovery,
+
+        /// Sets the lamports every `WithdrawReward` and claim pays into the
+        /// pool's fee vault, up to `MAX_WITHDRAWAL_FEE_LAMPORTS`; 0 stops the
+        /// fee. With `exempt_small_claims`, withdrawals of less than
+        /// `small_claim_threshold` tokens pay nothing.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Withdrawal fee account
+        /// 3. `[writable]` - Fee vault, created with the withdrawal fee account
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        SetWithdrawalFee {
+            fee_lamports: u64,
+            exempt_small_claims: bool,
+            small_claim_threshold: u64,
+        },
+
+        /// Moves the lamports collected in the pool's fee vault, above its
+        /// rent, to a destination
+        /// Accounts:
+        /// 0. `[signer, writable]` - Platform authority
+        /// 1. `[writable]` - Reward pool account
+        /// 2. `[writable]` - Fee vault
+        /// 3. `[writable]` - Destination of the fees
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        CollectWithdrawalFees,
+
+        /// Sets the second key every `WithdrawReward` of the farmer needs.
+        /// The new cosigner signs, and a farmer with a cosigner can only set
+        /// it again, which cancels a pending removal. `Pubkey::default()`
+        /// removes it: at once when the cosigner signs, otherwise after
+        /// `COSIGNER_REMOVAL_DELAY`.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Farmer state account
+        /// 3. `[signer]` - New cosigner, or the current one to remove it
+        ///    at once; any account for a delayed removal
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        SetWithdrawalCosigner { cosigner: Pubkey },
+
+        /// Copies the pool, its parameters, its instruction counters and
+        /// the daily stats passed into a `PoolSnapshot` with the current
+        /// slot and a hash committing to them. Anyone can export; the
+        /// buffer is created beforehand, owned by the program, zeroed and
+        /// at least `PoolSnapshot::len_with` bytes long, and is written once.
+        /// Accounts:
+        /// 0. `[signer]` - Exporter
+        /// 1. `[]` - Reward pool account
+        /// 2. `[signer, writable]` - Snapshot buffer account
+        /// 3. `[]` - Pool parameters account
+        /// 4. `[]` - Pool instruction counters account
+        /// 5. `[]` - Pool stats accounts of the days to include (remaining accounts)
+        ExportSnapshot,
+
+        /// Pays `guild_share_bps` of every claim of the farmer's pending
+        /// rewards to a guild vault, a token account of the reward mint,
+        /// or stops doing so with 0. The guild vault must pass the farmer's
+        /// whitelist, and once they have a payout address in effect, it must
+        /// own the guild vault. A farmer with a withdrawal cosigner needs it
+        /// to sign.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Farmer state account
+        /// 3. `[]` - Guild vault, ignored when removing the split
+        /// 4. `[]` - System program
+        /// 5. `[writable]` - Pool instruction counters account
+        /// 6. `[]` - Farmer's payout address account
+        /// 7. `[]` - Farmer's withdrawal whitelist account
+        /// 8. `[signer]` - Farmer's withdrawal cosigner, any account when
+        ///    the farmer has none
+        SetGuildSplit { guild_share_bps: u16 },
+
+        /// Sets the members of the signer's guild and their shares, which
+        /// add up to `BASIS_POINTS`, replacing the previous members. The
+        /// first call creates the guild and its vault, a token account of
+        /// the reward mint farmers can point their guild split at.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Guild authority
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Guild account (PDA `["guild", pool, authority]`)
+        /// 3. `[writable]` - Guild vault (PDA `["guild_vault", guild]`)
+        /// 4. `[]` - Reward token mint
+        /// 5. `[]` - SPL Token program
+        /// 6. `[]` - System program
+        /// 7. `[writable]` - Pool instruction counters account
+        SetGuildMembers { members: Vec<GuildMember> },
+
+        /// Removes the signing member from a guild. They receive nothing of
+        /// later distributions; the others split them in proportion to
+        /// their shares.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Member
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Guild account
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        LeaveGuild,
+
+        /// Pays the guild vault's balance out to the guild's members by
+        /// their shares. Permissionless.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Payer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Guild account
+        /// 3. `[writable]` - Guild vault
+        /// 4. `[]` - SPL Token program
+        /// 5. `[]` - System program
+        /// 6. `[writable]` - Pool instruction counters account
+        /// 7. `[writable]` - Token accounts of the reward mint owned by each
+        ///    member, in the guild's member order (remaining accounts)
+        DistributeGuildRewards,
+
+        /// Closes the pool's fee invoice for the period since the previous
+        /// one, at most once per epoch: records the platform fees, lamport
+        /// withdrawal fees, burns, rebates and protocol fees accrued in the
+        /// period and seals them, chained to the previous invoice.
+        /// Permissionless; the payer funds the invoice account.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Payer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Fee ledger account (PDA `["fee_ledger", pool]`)
+        /// 3. `[writable]` - Fee invoice account (PDA `["fee_invoice", pool, number]`)
+        /// 4. `[]` - Pool withdrawal fee account
+        /// 5. `[]` - Pool withdraw burn account
+        /// 6. `[]` - Pool buyback account
+        /// 7. `[]` - Pool fee rebate account
+        /// 8. `[]` - Pool factory record account
+        /// 9. `[]` - System program
+        /// 10. `[writable]` - Pool instruction counters account
+        CloseFeeInvoice,
+
+        /// Returns fee invoice `number` of the pool as a `FeeInvoiceReport`,
+        /// checking its seal and its chaining to the previous invoice,
+        /// without changing state
+        /// Accounts:
+        /// 0. `[]` - Reward pool account
+        /// 1. `[]` - Fee invoice account
+        /// 2. `[]` - Previous fee invoice account, ignored for the first
+        ///    invoice
+        GetFeeInvoice { number: u64 },
+
+        /// Returns the pool's time-weighted average reward rate over the
+        /// last `window` seconds as a `RewardRateReport`, without changing
+        /// state
+        /// Accounts:
+        /// 0. `[]` - Reward pool account
+        /// 1. `[]` - Reward rate account (PDA `["reward_rate", pool]`)
+        GetRewardRate { window: i64 },
+
+        /// Permissionless: adds what the passed claim shards counted since
+        /// their last consolidation to the pool's claim ledger, and their
+        /// claims to the `ClaimMany` and `CrankAutoClaim` slots of the
+        /// pool's instruction counters. Any subset of the shards can be
+        /// passed; shards that were never written are skipped.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Payer of the ledger's rent
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Claim ledger account (PDA `["claim_ledger", pool]`)
+        /// 3. `[]` - System program
+        /// 4. `[writable]` - Pool instruction counters account
+        /// 5. `[writable]` - Claim shard accounts, at most `CLAIM_SHARD_COUNT`
+        ConsolidateClaimShards,
+
+        /// Permissionless: transfers `amount` reward tokens into the pool's
+        /// treasury vault, which pays its rewards.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Funder
+        /// 1. `[]` - Reward pool account
+        /// 2. `[writable]` - Treasury vault (PDA `["vault", pool]`)
+        /// 3. `[writable]` - Funder's token account of the reward mint
+        /// 4. `[]` - Reward mint
+        /// 5. `[]` - Token program
+        /// 6. `[]` - System program, unused
+        /// 7. `[writable]` - Pool instruction counters account
+        FundTreasury { amount: u64 },
+
+        /// Closes withdrawal records of the signing farmer older than
+        /// `WITHDRAWAL_RECORD_RETENTION`, refunding their rent to whoever
+        /// paid it. The nonces stay spent: the farmer's withdrawal history
+        /// raises its `nonce_floor` past the highest nonce closed.
+        /// Accounts:
+        /// 0. `[signer, writable]` - Farmer
+        /// 1. `[]` - Reward pool account
+        /// 2. `[]` - System program
+        /// 3. `[writable]` - Pool instruction counters account
+        /// 4. `[writable]` - Farmer's withdrawal history account
+        /// 5. `[writable]` - Farmer's withdrawal record accounts, each
+        ///    followed by the record's `payer`, receiving its rent
+        CloseWithdrawalRecords,
+    }
+
+    impl RewardPoolInstruction {
+        /// Encodes the instruction with its V2 tag byte
+        pub fn pack(&self) -> Vec<u8> {
+            VersionedInstruction::V2(self.clone()).pack()
+        }
+    }
+
+    impl From<v1::RewardPoolInstruction> for RewardPoolInstruction {
+        fn from(instruction: v1::RewardPoolInstruction) -> Self {
+            match instruction {
+                v1::RewardPoolInstruction::InitializePool {
+                    platform_fee_percentage,
+                } => Self::InitializePool {
+                    platform_fee_percentage,
+                    name: None,
+                    uri: None,
+                },
+                v1::RewardPoolInstruction::RecordReward {
+                    amount,
+                    farmer_pubkey,
+                    task_id,
+                } => Self::RecordReward {
+                    amount,
+                    farmer_pubkey,
+                    task_id,
+                    campaign_id: None,
+                    platform_id: None,
+                    proof_hash: None,
+                    task_type: None,
+                    secondary_amount: 0,
+                    idempotency_key: None,
+                },
+                v1::RewardPoolInstruction::WithdrawReward { amount, nonce } => {
+                    Self::WithdrawReward {
+                        amount,
+                        nonce,
+                        split: None,
+                    }
+                }
+                v1::RewardPoolInstruction::UpdatePlatformFee { new_fee_percentage } => {
+                    Self::UpdatePlatformFee { new_fee_percentage }
+                }
+                v1::RewardPoolInstruction::PausePool => Self::PausePool,
+                v1::RewardPoolInstruction::ResumePool => Self::ResumePool,
+            }
+        }
+    }
+}
+
+// Instruction decoded from any supported namespace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionedInstruction {
+    V1(v1::RewardPoolInstruction),
+    V2(v2::RewardPoolInstruction),
+}
+
+impl VersionedInstruction {
+    /// Decodes tagged payloads as well as legacy untagged V1 payloads
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let (&tag, rest) = input
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        let instruction = match tag {
+            INSTRUCTION_TAG_V1 => v1::RewardPoolInstruction::try_from_slice(rest).map(Self::V1),
+            INSTRUCTION_TAG_V2 => v2::RewardPoolInstruction::try_from_slice(rest).map(Self::V2),
+            _ => v1::RewardPoolInstruction::try_from_slice(input).map(Self::V1),
+        };
+
+        instruction.map_err(|_| ProgramError::InvalidInstructionData)
+    }
+
+    /// Encodes the instruction prefixed with its namespace tag
+    pub fn pack(&self) -> Vec<u8> {
+        let (tag, payload) = match self {
+            Self::V1(instruction) => (INSTRUCTION_TAG_V1, borsh::to_vec(instruction)),
+            Self::V2(instruction) => (INSTRUCTION_TAG_V2, borsh::to_vec(instruction)),
+        };
+
+        let mut data = vec![tag];
+        data.extend(payload.expect("instruction serialization is infallible"));
+        data
+    }
+
+    /// Converts the instruction into the latest namespace for processing
+    pub fn into_latest(self) -> v2::RewardPoolInstruction {
+        match self {
+            Self::V1(instruction) => instruction.into(),
+            Self::V2(instruction) => instruction,
+        }
+    }
+}
@@ -0,0 +1,5 @@
+// Transfer memos
+//
+// Memo formats live in the `reward-pool-interface` crate so accounting
+// tools can match the memos the program writes.
+pub use reward_pool_interface::memo::*;
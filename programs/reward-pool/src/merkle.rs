@@ -0,0 +1,5 @@
+// Farmer balance commitments
+//
+// Hashing lives in the `reward-pool-interface` crate so exporters and
+// importers build exactly the trees the program verifies.
+pub use reward_pool_interface::merkle::*;
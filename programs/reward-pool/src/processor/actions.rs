@@ -0,0 +1,331 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token::state::Account as TokenAccount;
+
+use super::{
+    key_history::record_key_rotation,
+    parameters::store_parameters,
+    pool::apply_platform_fee,
+    treasury::{check_approvals, load_governance, load_pool},
+    treasury_swap::execute_treasury_swap,
+    utils::{close_pda_account, create_pda_account},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_pending_action_address_with_program_id, find_vault_address_with_program_id,
+        PENDING_ACTION_SEED,
+    },
+    state::{PendingAction, ScheduledAction, Sequenced},
+    MAX_PLATFORM_FEE_PERCENTAGE, MIN_ACTION_DELAY,
+};
+
+// Queueing an admin action
+pub(super) fn process_schedule_action(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    action_id: u64,
+    eta: i64,
+    action: ScheduledAction,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let pending_action_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if action.requires_approvals() {
+        let governance_info = next_account_info(account_info_iter)?;
+        let governance = load_governance(program_id, pool_info.key, governance_info)?;
+        check_approvals(&governance, account_info_iter.as_slice())?;
+    }
+
+    // Reject what could never execute now rather than after the delay
+    match &action {
+        ScheduledAction::UpdatePlatformFee { new_fee_percentage }
+            if *new_fee_percentage > MAX_PLATFORM_FEE_PERCENTAGE =>
+        {
+            return Err(RewardPoolError::InvalidPlatformFee.into());
+        }
+        ScheduledAction::UpdateParameters { values } if !values.is_valid() => {
+            return Err(RewardPoolError::InvalidParameterValues.into());
+        }
+        ScheduledAction::SwapTreasury { amount: 0, .. } => {
+            return Err(RewardPoolError::InvalidTreasurySwap.into());
+        }
+        ScheduledAction::RotatePlatformAuthority { reason_hash, .. } if *reason_hash == [0; 32] => {
+            return Err(RewardPoolError::MissingRotationReason.into());
+        }
+        _ => {}
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if eta < now.saturating_add(MIN_ACTION_DELAY) {
+        return Err(RewardPoolError::ActionEtaTooEarly.into());
+    }
+
+    let (expected_pending_action, bump_seed) =
+        find_pending_action_address_with_program_id(pool_info.key, action_id, program_id);
+    if *pending_action_info.key != expected_pending_action || !pending_action_info.data_is_empty() {
+        return Err(RewardPoolError::InvalidPendingActionAccount.into());
+    }
+
+    let mut pending_action = PendingAction {
+        pool: *pool_info.key,
+        action_id,
+        payer: *platform_authority_info.key,
+        action,
+        scheduled_at: now,
+        eta,
+        bump_seed,
+        sequence: 0,
+    };
+
+    // Actions differ in size; the account holds exactly this one
+    create_pda_account(
+        platform_authority_info,
+        pending_action_info,
+        system_program_info,
+        program_id,
+        borsh::object_length(&pending_action)?,
+        &[
+            PENDING_ACTION_SEED,
+            pool_info.key.as_ref(),
+            &action_id.to_le_bytes(),
+            &[bump_seed],
+        ],
+    )?;
+    pending_action.save(pending_action_info)?;
+
+    RewardPoolEvent::ActionScheduled {
+        pool: *pool_info.key,
+        action_id,
+        action: pending_action.action,
+        eta,
+    }
+    .emit();
+
+    msg!("Action {} scheduled for {}", action_id, eta);
+    Ok(())
+}
+
+// Dropping a queued action
+pub(super) fn process_cancel_action(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let pending_action_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pending_action =
+        load_pending_action(program_id, pool_info.key, pending_action_info, payer_info)?;
+    close_pda_account(pending_action_info, payer_info)?;
+
+    RewardPoolEvent::ActionCancelled {
+        pool: *pool_info.key,
+        action_id: pending_action.action_id,
+    }
+    .emit();
+
+    msg!("Action {} cancelled", pending_action.action_id);
+    Ok(())
+}
+
+// Running a queued action
+pub(super) fn process_execute_action(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let executor_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let pending_action_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !executor_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut pool_data = load_pool(program_id, pool_info)?;
+    let pending_action =
+        load_pending_action(program_id, pool_info.key, pending_action_info, payer_info)?;
+
+    if Clock::get()?.unix_timestamp < pending_action.eta {
+        return Err(RewardPoolError::ActionNotReady.into());
+    }
+
+    // Actions lapse once the authority that scheduled them is gone, e.g.
+    // after an authority change or a state export
+    if pool_data.platform_authority != pending_action.payer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    match &pending_action.action {
+        ScheduledAction::UpdatePlatformFee { new_fee_percentage } => {
+            let parameters_info = next_account_info(account_info_iter)?;
+            apply_platform_fee(
+                program_id,
+                pool_info,
+                &mut pool_data,
+                Some(parameters_info),
+                *new_fee_percentage,
+            )?;
+        }
+        ScheduledAction::UpdateParameters { values } => {
+            let parameters_info = next_account_info(account_info_iter)?;
+            let history_info = next_account_info(account_info_iter)?;
+            store_parameters(
+                program_id,
+                executor_info,
+                pool_info,
+                parameters_info,
+                history_info,
+                system_program_info,
+                *values,
+            )?;
+        }
+        ScheduledAction::SetPlatformTreasury { platform_treasury } => {
+            let platform_treasury_info = next_account_info(account_info_iter)?;
+            // Only moves a pool from before treasuries were vaults onto its
+            // vault, which `FundTreasury` creates
+            let (vault, _) = find_vault_address_with_program_id(pool_info.key, program_id);
+            if *platform_treasury != vault {
+                return Err(RewardPoolError::InvalidProgramTreasuryAccount.into());
+            }
+            if platform_treasury_info.key != platform_treasury
+                || *platform_treasury_info.owner != spl_token::id()
+            {
+                return Err(RewardPoolError::InvalidPlatformTreasury.into());
+            }
+            let token_account = TokenAccount::unpack(&platform_treasury_info.data.borrow())?;
+            if token_account.mint != pool_data.reward_mint {
+                return Err(RewardPoolError::InvalidPlatformTreasury.into());
+            }
+
+            let old_platform_treasury = pool_data.platform_treasury;
+            pool_data.platform_treasury = *platform_treasury;
+            pool_data.save(pool_info)?;
+
+            RewardPoolEvent::PlatformTreasuryChanged {
+                pool: *pool_info.key,
+                old_platform_treasury,
+                new_platform_treasury: *platform_treasury,
+            }
+            .emit();
+        }
+        ScheduledAction::SetPlatformAuthority { platform_authority }
+        | ScheduledAction::RotatePlatformAuthority {
+            platform_authority, ..
+        } => {
+            let reason_hash = match &pending_action.action {
+                ScheduledAction::RotatePlatformAuthority { reason_hash, .. } => *reason_hash,
+                _ => [0; 32],
+            };
+            let history_info = next_account_info(account_info_iter)?;
+            let old_platform_authority = pool_data.platform_authority;
+            pool_data.platform_authority = *platform_authority;
+            pool_data.save(pool_info)?;
+
+            record_key_rotation(
+                program_id,
+                executor_info,
+                pool_info.key,
+                history_info,
+                system_program_info,
+                old_platform_authority,
+                *platform_authority,
+                reason_hash,
+            )?;
+
+            RewardPoolEvent::PlatformAuthorityChanged {
+                pool: *pool_info.key,
+                old_platform_authority,
+                new_platform_authority: *platform_authority,
+            }
+            .emit();
+        }
+        ScheduledAction::SwapTreasury {
+            output_mint,
+            amount,
+            min_output,
+            swap_data,
+        } => {
+            execute_treasury_swap(
+                program_id,
+                pool_info,
+                executor_info,
+                system_program_info,
+                account_info_iter.as_slice(),
+                output_mint,
+                *amount,
+                *min_output,
+                swap_data.clone(),
+            )?;
+        }
+    }
+
+    close_pda_account(pending_action_info, payer_info)?;
+
+    RewardPoolEvent::ActionExecuted {
+        pool: *pool_info.key,
+        action_id: pending_action.action_id,
+        action: pending_action.action,
+    }
+    .emit();
+
+    msg!("Action {} executed", pending_action.action_id);
+    Ok(())
+}
+
+fn load_pending_action(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    pending_action_info: &AccountInfo,
+    payer_info: &AccountInfo,
+) -> Result<PendingAction, ProgramError> {
+    if pending_action_info.owner != program_id {
+        return Err(RewardPoolError::InvalidPendingActionAccount.into());
+    }
+    let pending_action = PendingAction::try_from_slice(&pending_action_info.data.borrow())?;
+    if pending_action.pool != *pool || pending_action.payer != *payer_info.key {
+        return Err(RewardPoolError::InvalidPendingActionAccount.into());
+    }
+    Ok(pending_action)
+}
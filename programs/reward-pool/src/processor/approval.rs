@@ -0,0 +1,186 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{pending::load_pending_reward, treasury::load_pool, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{find_reward_approval_address_with_program_id, REWARD_APPROVAL_SEED},
+    state::{RewardApproval, Sequenced},
+};
+
+// Turning maker-checker mode on or off
+pub(super) fn process_set_reward_approver(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    approver: Option<Pubkey>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let reward_approval_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if approver == Some(pool_data.platform_authority) {
+        return Err(RewardPoolError::ApproverIsRecorder.into());
+    }
+
+    let (expected_reward_approval, bump_seed) =
+        find_reward_approval_address_with_program_id(pool_info.key, program_id);
+    if *reward_approval_info.key != expected_reward_approval {
+        return Err(RewardPoolError::InvalidRewardApprovalAccount.into());
+    }
+
+    let mut reward_approval = if reward_approval_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            reward_approval_info,
+            system_program_info,
+            program_id,
+            RewardApproval::LEN,
+            &[REWARD_APPROVAL_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        RewardApproval {
+            pool: *pool_info.key,
+            approver: Pubkey::default(),
+            set_at: 0,
+            approved_count: 0,
+            approved_amount: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_reward_approval(program_id, reward_approval_info)?
+    };
+
+    reward_approval.approver = approver.unwrap_or_default();
+    reward_approval.set_at = Clock::get()?.unix_timestamp;
+    reward_approval.save(reward_approval_info)?;
+
+    RewardPoolEvent::RewardApproverSet {
+        pool: *pool_info.key,
+        approver,
+    }
+    .emit();
+
+    match approver {
+        Some(approver) => msg!("Reward approver set to {}", approver),
+        None => msg!("Reward approver removed"),
+    }
+    Ok(())
+}
+
+// Approving a pending reward
+pub(super) fn process_approve_reward(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let approver_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let reward_approval_info = next_account_info(account_info_iter)?;
+    let pending_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !approver_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    let (expected_reward_approval, _) =
+        find_reward_approval_address_with_program_id(pool_info.key, program_id);
+    if *reward_approval_info.key != expected_reward_approval {
+        return Err(RewardPoolError::InvalidRewardApprovalAccount.into());
+    }
+    let mut reward_approval = load_reward_approval(program_id, reward_approval_info)?;
+    if reward_approval.approver() != Some(*approver_info.key) {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+    // The platform authority may have been handed to the approver since
+    if *approver_info.key == pool_data.platform_authority {
+        return Err(RewardPoolError::ApproverIsRecorder.into());
+    }
+
+    let mut pending = load_pending_reward(program_id, pool_info.key, pending_info)?;
+    if !pending.awaiting_approval {
+        return Err(RewardPoolError::RewardNotAwaitingApproval.into());
+    }
+
+    pending.awaiting_approval = false;
+    pending.serialize(&mut &mut pending_info.data.borrow_mut()[..])?;
+
+    reward_approval.approved_count = reward_approval
+        .approved_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    reward_approval.approved_amount = reward_approval
+        .approved_amount
+        .checked_add(pending.amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    reward_approval.save(reward_approval_info)?;
+
+    RewardPoolEvent::RewardApproved {
+        pool: *pool_info.key,
+        farmer: pending.farmer_pubkey,
+        task_id: pending.task_id,
+        amount: pending.amount,
+        approver: *approver_info.key,
+    }
+    .emit();
+
+    msg!("Reward of {} tokens approved", pending.amount);
+    Ok(())
+}
+
+/// Returns the approver rewards of the pool wait for, `None` when the pool
+/// records rewards directly
+pub(super) fn read_reward_approver(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    reward_approval_info: &AccountInfo,
+) -> Result<Option<Pubkey>, ProgramError> {
+    let (expected_reward_approval, _) =
+        find_reward_approval_address_with_program_id(pool, program_id);
+    if *reward_approval_info.key != expected_reward_approval {
+        return Err(RewardPoolError::InvalidRewardApprovalAccount.into());
+    }
+    if reward_approval_info.data_is_empty() {
+        return Ok(None);
+    }
+
+    Ok(load_reward_approval(program_id, reward_approval_info)?.approver())
+}
+
+fn load_reward_approval(
+    program_id: &Pubkey,
+    reward_approval_info: &AccountInfo,
+) -> Result<RewardApproval, ProgramError> {
+    if reward_approval_info.owner != program_id {
+        return Err(RewardPoolError::InvalidRewardApprovalAccount.into());
+    }
+    RewardApproval::try_from_slice(&reward_approval_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidRewardApprovalAccount.into())
+}
@@ -0,0 +1,147 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    hash::hash,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::{treasury::load_pool, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{find_pool_attestor_address_with_program_id, POOL_ATTESTOR_SEED},
+    state::{PoolAttestor, Sequenced, TaskAttestation},
+};
+
+// Setting or removing the pool's reward attestor
+pub(super) fn process_set_attestor(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    attestor_program: Option<Pubkey>,
+    data_offset: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let pool_attestor_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let (expected_pool_attestor, bump_seed) =
+        find_pool_attestor_address_with_program_id(pool_info.key, program_id);
+    if *pool_attestor_info.key != expected_pool_attestor {
+        return Err(RewardPoolError::InvalidPoolAttestorAccount.into());
+    }
+
+    // Accounts of this program hold data the platform partly controls, such
+    // as pool metadata, so they cannot stand in for attestations
+    if attestor_program == Some(*program_id) {
+        return Err(RewardPoolError::InvalidAttestorProgram.into());
+    }
+
+    let mut pool_attestor = if pool_attestor_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            pool_attestor_info,
+            system_program_info,
+            program_id,
+            PoolAttestor::LEN,
+            &[POOL_ATTESTOR_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        PoolAttestor {
+            pool: *pool_info.key,
+            attestor_program: Pubkey::default(),
+            data_offset: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_pool_attestor(program_id, pool_attestor_info)?
+    };
+
+    pool_attestor.attestor_program = attestor_program.unwrap_or_default();
+    pool_attestor.data_offset = data_offset;
+    pool_attestor.save(pool_attestor_info)?;
+
+    RewardPoolEvent::AttestorSet {
+        pool: *pool_info.key,
+        attestor_program,
+        data_offset,
+    }
+    .emit();
+
+    match attestor_program {
+        Some(attestor_program) => msg!("Attestor set to {}", attestor_program),
+        None => msg!("Attestor removed"),
+    }
+    Ok(())
+}
+
+/// Checks that the attestation in `attestation_info` allows booking
+/// `amount` for the task and farmer, when the pool has an attestor. Returns
+/// the attestation account checked, `None` when the pool has no attestor.
+pub(super) fn check_task_attestation(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    pool_attestor_info: &AccountInfo,
+    attestation_info: &AccountInfo,
+    task_id: &str,
+    farmer: &Pubkey,
+    amount: u64,
+) -> Result<Option<Pubkey>, ProgramError> {
+    let (expected_pool_attestor, _) = find_pool_attestor_address_with_program_id(pool, program_id);
+    if *pool_attestor_info.key != expected_pool_attestor {
+        return Err(RewardPoolError::InvalidPoolAttestorAccount.into());
+    }
+    if pool_attestor_info.data_is_empty() {
+        return Ok(None);
+    }
+
+    let pool_attestor = load_pool_attestor(program_id, pool_attestor_info)?;
+    let Some(attestor_program) = pool_attestor.attestor_program() else {
+        return Ok(None);
+    };
+
+    // Only the attestor program can have written the account
+    if *attestation_info.owner != attestor_program {
+        return Err(RewardPoolError::InvalidAttestation.into());
+    }
+    let data = attestation_info.data.borrow();
+    let attestation = data
+        .get(pool_attestor.data_offset as usize..)
+        .and_then(|mut attestation| TaskAttestation::deserialize(&mut attestation).ok())
+        .ok_or(RewardPoolError::InvalidAttestation)?;
+
+    if attestation.task_hash != hash(task_id.as_bytes()).to_bytes() || attestation.farmer != *farmer
+    {
+        return Err(RewardPoolError::InvalidAttestation.into());
+    }
+    if amount > attestation.amount_ceiling {
+        return Err(RewardPoolError::AttestedAmountExceeded.into());
+    }
+
+    Ok(Some(*attestation_info.key))
+}
+
+fn load_pool_attestor(
+    program_id: &Pubkey,
+    pool_attestor_info: &AccountInfo,
+) -> Result<PoolAttestor, ProgramError> {
+    if pool_attestor_info.owner != program_id {
+        return Err(RewardPoolError::InvalidPoolAttestorAccount.into());
+    }
+    PoolAttestor::try_from_slice(&pool_attestor_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidPoolAttestorAccount.into())
+}
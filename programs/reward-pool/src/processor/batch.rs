@@ -0,0 +1,146 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{parameters::load_parameters, pool::apply_platform_fee, treasury::load_pool};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    state::{RewardPool, Sequenced},
+};
+
+// Pausing several pools
+pub(super) fn process_pause_all(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if pool_infos.is_empty() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    // Every pool the authority administers sees it act, paused or not
+    let now = Clock::get()?.unix_timestamp;
+    let mut paused = 0u32;
+    for pool_info in pool_infos {
+        let Some(mut pool_data) =
+            controlled_pool(program_id, pool_info, platform_authority_info.key)
+        else {
+            continue;
+        };
+        pool_data.last_authority_action_at = now;
+
+        if pool_data.is_paused {
+            msg!("Pool {}: already paused", pool_info.key);
+            pool_data.save(pool_info)?;
+            continue;
+        }
+
+        pool_data.is_paused = true;
+        pool_data.save(pool_info)?;
+
+        RewardPoolEvent::PoolPaused {
+            pool: *pool_info.key,
+        }
+        .emit();
+
+        msg!("Pool {}: paused", pool_info.key);
+        paused += 1;
+    }
+
+    msg!("Paused {} of {} pools", paused, pool_infos.len());
+    Ok(())
+}
+
+// Setting the fee of several pools
+pub(super) fn process_set_fee_all(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_fee_percentage: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if pool_infos.is_empty() || !pool_infos.len().is_multiple_of(2) {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    // Every pool the authority administers sees it act, updated or not
+    let now = Clock::get()?.unix_timestamp;
+    let mut updated = 0u32;
+    for pair in pool_infos.chunks_exact(2) {
+        let (pool_info, parameters_info) = (&pair[0], &pair[1]);
+        let Some(mut pool_data) =
+            controlled_pool(program_id, pool_info, platform_authority_info.key)
+        else {
+            continue;
+        };
+        pool_data.last_authority_action_at = now;
+
+        let parameters = load_parameters(program_id, pool_info.key, parameters_info)?;
+        if new_fee_percentage > parameters.max_platform_fee_percentage {
+            msg!(
+                "Pool {}: skipped, fee above its maximum of {}%",
+                pool_info.key,
+                parameters.max_platform_fee_percentage
+            );
+            pool_data.save(pool_info)?;
+            continue;
+        }
+
+        apply_platform_fee(
+            program_id,
+            pool_info,
+            &mut pool_data,
+            Some(parameters_info),
+            new_fee_percentage,
+        )?;
+        updated += 1;
+    }
+
+    msg!(
+        "Updated the fee of {} of {} pools",
+        updated,
+        pool_infos.len() / 2
+    );
+    Ok(())
+}
+
+// Pool the signer administers, or `None` after logging why it is skipped
+fn controlled_pool(
+    program_id: &Pubkey,
+    pool_info: &AccountInfo,
+    platform_authority: &Pubkey,
+) -> Option<RewardPool> {
+    let Ok(pool_data) = load_pool(program_id, pool_info) else {
+        msg!("Pool {}: skipped, not a reward pool", pool_info.key);
+        return None;
+    };
+
+    if pool_data.platform_authority != *platform_authority {
+        msg!(
+            "Pool {}: skipped, signer is not its authority",
+            pool_info.key
+        );
+        return None;
+    }
+
+    Some(pool_data)
+}
@@ -0,0 +1,325 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    hash::hash,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token::{instruction as token_instruction, state::Account as TokenAccount};
+
+use super::{
+    treasury::load_pool,
+    utils::{close_pda_account, create_pda_account},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_budget_escrow_address_with_program_id, find_task_budget_address_with_program_id,
+        find_vault_address_with_program_id, BUDGET_ESCROW_SEED, TASK_BUDGET_SEED, VAULT_SEED,
+    },
+    state::{Sequenced, TaskBudget},
+};
+
+// Locking a task's reward before the task is assigned
+pub(super) fn process_lock_task_budget(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    task_id: String,
+    amount: u64,
+    expires_at: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let task_budget_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    if amount == 0 || expires_at <= Clock::get()?.unix_timestamp {
+        return Err(RewardPoolError::InvalidTaskBudget.into());
+    }
+
+    let (expected_task_budget, bump_seed) =
+        find_task_budget_address_with_program_id(pool_info.key, &task_id, program_id);
+    if *task_budget_info.key != expected_task_budget {
+        return Err(RewardPoolError::InvalidTaskBudgetAccount.into());
+    }
+    if !task_budget_info.data_is_empty() {
+        return Err(RewardPoolError::TaskBudgetAlreadyLocked.into());
+    }
+
+    lock_in_budget_escrow(
+        program_id,
+        pool_info.key,
+        &pool_data.reward_mint,
+        platform_authority_info,
+        vault_info,
+        escrow_info,
+        reward_mint_info,
+        token_program_info,
+        system_program_info,
+        amount,
+    )?;
+
+    let task_hash = hash(task_id.as_bytes()).to_bytes();
+    create_pda_account(
+        platform_authority_info,
+        task_budget_info,
+        system_program_info,
+        program_id,
+        TaskBudget::LEN,
+        &[
+            TASK_BUDGET_SEED,
+            pool_info.key.as_ref(),
+            &task_hash,
+            &[bump_seed],
+        ],
+    )?;
+    let mut task_budget = TaskBudget {
+        pool: *pool_info.key,
+        task_hash,
+        amount,
+        expires_at,
+        is_recorded: false,
+        payer: *platform_authority_info.key,
+        bump_seed,
+        sequence: 0,
+    };
+    task_budget.save(task_budget_info)?;
+
+    RewardPoolEvent::TaskBudgetLocked {
+        pool: *pool_info.key,
+        task_id,
+        amount,
+        expires_at,
+    }
+    .emit();
+
+    msg!("Locked {} tokens until {}", amount, expires_at);
+    Ok(())
+}
+
+// Returning what is left of a task budget to the vault
+pub(super) fn process_release_unused_budget(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let _caller_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let task_budget_info = next_account_info(account_info_iter)?;
+    let rent_recipient_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    load_pool(program_id, pool_info)?;
+
+    let (expected_vault, _) = find_vault_address_with_program_id(pool_info.key, program_id);
+    if *vault_info.key != expected_vault {
+        return Err(RewardPoolError::InvalidVaultAccount.into());
+    }
+
+    if task_budget_info.owner != program_id {
+        return Err(RewardPoolError::InvalidTaskBudgetAccount.into());
+    }
+    let task_budget = TaskBudget::try_from_slice(&task_budget_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidTaskBudgetAccount)?;
+    let expected_task_budget = Pubkey::create_program_address(
+        &[
+            TASK_BUDGET_SEED,
+            pool_info.key.as_ref(),
+            &task_budget.task_hash,
+            &[task_budget.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidTaskBudgetAccount)?;
+    if task_budget.pool != *pool_info.key || *task_budget_info.key != expected_task_budget {
+        return Err(RewardPoolError::InvalidTaskBudgetAccount.into());
+    }
+
+    if task_budget.payer != *rent_recipient_info.key {
+        return Err(RewardPoolError::InvalidRentRecipient.into());
+    }
+
+    if !task_budget.is_recorded && Clock::get()?.unix_timestamp < task_budget.expires_at {
+        return Err(RewardPoolError::TaskBudgetNotReleasable.into());
+    }
+
+    if task_budget.amount > 0 {
+        transfer_from_budget_escrow(
+            program_id,
+            pool_info.key,
+            escrow_info,
+            vault_info,
+            token_program_info,
+            task_budget.amount,
+        )?;
+    }
+
+    close_pda_account(task_budget_info, rent_recipient_info)?;
+
+    RewardPoolEvent::TaskBudgetReleased {
+        pool: *pool_info.key,
+        task_hash: task_budget.task_hash,
+        amount: task_budget.amount,
+    }
+    .emit();
+
+    msg!("Released {} tokens of unused budget", task_budget.amount);
+    Ok(())
+}
+
+/// Reads the budget locked for a task, `None` when there is none
+pub(super) fn load_task_budget(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    task_id: &str,
+    task_budget_info: &AccountInfo,
+) -> Result<Option<TaskBudget>, ProgramError> {
+    let (expected_task_budget, _) =
+        find_task_budget_address_with_program_id(pool, task_id, program_id);
+    if *task_budget_info.key != expected_task_budget {
+        return Err(RewardPoolError::InvalidTaskBudgetAccount.into());
+    }
+    if task_budget_info.data_is_empty() {
+        return Ok(None);
+    }
+
+    if task_budget_info.owner != program_id {
+        return Err(RewardPoolError::InvalidTaskBudgetAccount.into());
+    }
+    TaskBudget::try_from_slice(&task_budget_info.data.borrow())
+        .map(Some)
+        .map_err(|_| RewardPoolError::InvalidTaskBudgetAccount.into())
+}
+
+/// Moves `amount` from the treasury vault into the pool's budget escrow,
+/// creating the escrow at `payer_info`'s expense on first use
+#[allow(clippy::too_many_arguments)]
+pub(super) fn lock_in_budget_escrow<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    reward_mint: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    vault_info: &AccountInfo<'a>,
+    escrow_info: &AccountInfo<'a>,
+    reward_mint_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    let (expected_vault, vault_bump_seed) = find_vault_address_with_program_id(pool, program_id);
+    if *vault_info.key != expected_vault {
+        return Err(RewardPoolError::InvalidVaultAccount.into());
+    }
+
+    // Like the vault, the escrow is its own token owner
+    let (expected_escrow, escrow_bump_seed) =
+        find_budget_escrow_address_with_program_id(pool, program_id);
+    if *escrow_info.key != expected_escrow {
+        return Err(RewardPoolError::InvalidBudgetEscrowAccount.into());
+    }
+    if escrow_info.data_is_empty() {
+        if reward_mint_info.key != reward_mint || *token_program_info.key != spl_token::id() {
+            return Err(RewardPoolError::InvalidBudgetEscrowAccount.into());
+        }
+        create_pda_account(
+            payer_info,
+            escrow_info,
+            system_program_info,
+            token_program_info.key,
+            TokenAccount::LEN,
+            &[BUDGET_ESCROW_SEED, pool.as_ref(), &[escrow_bump_seed]],
+        )?;
+        invoke(
+            &token_instruction::initialize_account3(
+                token_program_info.key,
+                escrow_info.key,
+                reward_mint_info.key,
+                escrow_info.key,
+            )?,
+            &[
+                escrow_info.clone(),
+                reward_mint_info.clone(),
+                token_program_info.clone(),
+            ],
+        )?;
+    }
+
+    invoke_signed(
+        &token_instruction::transfer(
+            token_program_info.key,
+            vault_info.key,
+            escrow_info.key,
+            vault_info.key,
+            &[],
+            amount,
+        )?,
+        &[
+            vault_info.clone(),
+            escrow_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[VAULT_SEED, pool.as_ref(), &[vault_bump_seed]]],
+    )
+}
+
+/// Pays `amount` out of the pool's budget escrow to `destination_info`
+pub(super) fn transfer_from_budget_escrow<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    escrow_info: &AccountInfo<'a>,
+    destination_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    let (expected_escrow, escrow_bump_seed) =
+        find_budget_escrow_address_with_program_id(pool, program_id);
+    if *escrow_info.key != expected_escrow {
+        return Err(RewardPoolError::InvalidBudgetEscrowAccount.into());
+    }
+
+    invoke_signed(
+        &token_instruction::transfer(
+            token_program_info.key,
+            escrow_info.key,
+            destination_info.key,
+            escrow_info.key,
+            &[],
+            amount,
+        )?,
+        &[
+            escrow_info.clone(),
+            destination_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[BUDGET_ESCROW_SEED, pool.as_ref(), &[escrow_bump_seed]]],
+    )
+}
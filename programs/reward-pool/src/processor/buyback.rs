@@ -0,0 +1,374 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token::instruction as token_instruction;
+
+use super::{
+    program_treasury::transfer_from_platform_fee_vault,
+    treasury::{check_approvals, load_governance, load_pool},
+    utils::{create_pda_account, create_self_owned_token_account, token_balance},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_buyback_address_with_program_id, find_buyback_output_address_with_program_id,
+        find_buyback_source_address_with_program_id, BUYBACK_OUTPUT_SEED, BUYBACK_SEED,
+        BUYBACK_SOURCE_SEED,
+    },
+    state::{BuybackConfig, Sequenced},
+    BASIS_POINTS,
+};
+
+// Configuring the buyback-and-burn crank
+pub(super) fn process_configure_buyback(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    dex_program: Option<Pubkey>,
+    governance_mint: Pubkey,
+    slice_bps: u16,
+    min_output_per_million: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let governance_info = next_account_info(account_info_iter)?;
+    let buyback_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let source_info = next_account_info(account_info_iter)?;
+    let output_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let governance_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let approver_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let governance = load_governance(program_id, pool_info.key, governance_info)?;
+    check_approvals(&governance, approver_infos)?;
+
+    // The DEX is handed a token account this program signs for, so it must
+    // not be a program that would act on that signature itself
+    if let Some(dex_program) = dex_program {
+        if dex_program == *program_id || dex_program == spl_token::id() {
+            return Err(RewardPoolError::InvalidDexProgram.into());
+        }
+    }
+    if slice_bps > BASIS_POINTS {
+        return Err(RewardPoolError::InvalidBuybackSlice.into());
+    }
+
+    if *reward_mint_info.key != pool_data.reward_mint
+        || *governance_mint_info.key != governance_mint
+        || governance_mint == pool_data.reward_mint
+        || *token_program_info.key != spl_token::id()
+    {
+        return Err(RewardPoolError::InvalidBuybackAccount.into());
+    }
+
+    let (expected_buyback, bump_seed) =
+        find_buyback_address_with_program_id(pool_info.key, program_id);
+    if *buyback_info.key != expected_buyback {
+        return Err(RewardPoolError::InvalidBuybackAccount.into());
+    }
+
+    let mut buyback = if buyback_info.data_is_empty() {
+        let (expected_source, source_bump_seed) =
+            find_buyback_source_address_with_program_id(pool_info.key, program_id);
+        let (expected_output, output_bump_seed) =
+            find_buyback_output_address_with_program_id(pool_info.key, program_id);
+        if *source_info.key != expected_source || *output_info.key != expected_output {
+            return Err(RewardPoolError::InvalidBuybackAccount.into());
+        }
+
+        // Like the vault, both token accounts are their own owners
+        create_self_owned_token_account(
+            platform_authority_info,
+            source_info,
+            reward_mint_info,
+            token_program_info,
+            system_program_info,
+            &[
+                BUYBACK_SOURCE_SEED,
+                pool_info.key.as_ref(),
+                &[source_bump_seed],
+            ],
+        )?;
+        create_self_owned_token_account(
+            platform_authority_info,
+            output_info,
+            governance_mint_info,
+            token_program_info,
+            system_program_info,
+            &[
+                BUYBACK_OUTPUT_SEED,
+                pool_info.key.as_ref(),
+                &[output_bump_seed],
+            ],
+        )?;
+
+        create_pda_account(
+            platform_authority_info,
+            buyback_info,
+            system_program_info,
+            program_id,
+            BuybackConfig::LEN,
+            &[BUYBACK_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        BuybackConfig {
+            pool: *pool_info.key,
+            dex_program: Pubkey::default(),
+            governance_mint,
+            slice_bps: 0,
+            min_output_per_million: 0,
+            // Fees collected before the crank existed are not bought back
+            fees_accounted: pool_data.total_platform_fees_collected,
+            total_spent: 0,
+            total_burned: 0,
+            run_count: 0,
+            last_run_at: 0,
+            last_burned: 0,
+            source_bump_seed,
+            output_bump_seed,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        let buyback = load_buyback(program_id, pool_info.key, buyback_info)?;
+        if buyback.governance_mint != governance_mint {
+            return Err(RewardPoolError::InvalidBuybackAccount.into());
+        }
+        buyback
+    };
+
+    buyback.dex_program = dex_program.unwrap_or_default();
+    buyback.slice_bps = slice_bps;
+    buyback.min_output_per_million = min_output_per_million;
+    buyback.save(buyback_info)?;
+
+    RewardPoolEvent::BuybackConfigured {
+        pool: *pool_info.key,
+        dex_program,
+        governance_mint,
+        slice_bps,
+        min_output_per_million,
+    }
+    .emit();
+
+    match dex_program {
+        Some(dex_program) => msg!(
+            "Buyback enabled through {} for {} bps of fees",
+            dex_program,
+            slice_bps
+        ),
+        None => msg!("Buyback disabled"),
+    }
+    Ok(())
+}
+
+// Swapping a slice of the fees and burning the output
+pub(super) fn process_run_buyback(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    swap_data: Vec<u8>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let _caller_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let buyback_info = next_account_info(account_info_iter)?;
+    let fee_vault_info = next_account_info(account_info_iter)?;
+    let source_info = next_account_info(account_info_iter)?;
+    let output_info = next_account_info(account_info_iter)?;
+    let governance_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let _system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let dex_program_info = next_account_info(account_info_iter)?;
+    let swap_infos = account_info_iter.as_slice();
+
+    // Validations
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    let mut buyback = load_buyback(program_id, pool_info.key, buyback_info)?;
+    if !buyback.is_enabled() {
+        return Err(RewardPoolError::BuybackDisabled.into());
+    }
+    if *dex_program_info.key != buyback.dex_program {
+        return Err(RewardPoolError::InvalidDexProgram.into());
+    }
+
+    let source_seeds: &[&[u8]] = &[
+        BUYBACK_SOURCE_SEED,
+        pool_info.key.as_ref(),
+        &[buyback.source_bump_seed],
+    ];
+    let output_seeds: &[&[u8]] = &[
+        BUYBACK_OUTPUT_SEED,
+        pool_info.key.as_ref(),
+        &[buyback.output_bump_seed],
+    ];
+    let expected_source = Pubkey::create_program_address(source_seeds, program_id)
+        .map_err(|_| RewardPoolError::InvalidBuybackAccount)?;
+    let expected_output = Pubkey::create_program_address(output_seeds, program_id)
+        .map_err(|_| RewardPoolError::InvalidBuybackAccount)?;
+    if *source_info.key != expected_source
+        || *output_info.key != expected_output
+        || *governance_mint_info.key != buyback.governance_mint
+        || *token_program_info.key != spl_token::id()
+    {
+        return Err(RewardPoolError::InvalidBuybackAccount.into());
+    }
+
+    // Fund the run with its slice of the fees collected since the last one,
+    // out of the fee vault only: the treasury vault holds what farmers are
+    // owed
+    let new_fees = pool_data
+        .total_platform_fees_collected
+        .saturating_sub(buyback.fees_accounted);
+    let allowance = (new_fees as u128 * buyback.slice_bps as u128 / BASIS_POINTS as u128) as u64;
+    transfer_from_platform_fee_vault(
+        program_id,
+        pool_info.key,
+        fee_vault_info,
+        source_info,
+        token_program_info,
+        allowance,
+    )?;
+
+    let source_before = token_balance(source_info)?;
+    let output_before = token_balance(output_info)?;
+    if source_before == 0 {
+        return Err(RewardPoolError::InsufficientAmount.into());
+    }
+
+    // Only the buyback source is signed for; the DEX sees nothing else of
+    // the pool's
+    let swap = Instruction {
+        program_id: *dex_program_info.key,
+        accounts: swap_infos
+            .iter()
+            .map(|info| AccountMeta {
+                pubkey: *info.key,
+                is_signer: info.is_signer || info.key == source_info.key,
+                is_writable: info.is_writable,
+            })
+            .collect(),
+        data: swap_data,
+    };
+    let mut swap_account_infos = swap_infos.to_vec();
+    swap_account_infos.push(dex_program_info.clone());
+    invoke_signed(&swap, &swap_account_infos, &[source_seeds])?;
+
+    let spent = source_before.saturating_sub(token_balance(source_info)?);
+    let output_after = token_balance(output_info)?;
+    let received = output_after.saturating_sub(output_before);
+    if (received as u128) * 1_000_000 < (spent as u128) * (buyback.min_output_per_million as u128) {
+        return Err(RewardPoolError::BuybackSlippageExceeded.into());
+    }
+
+    // Burn everything the output holds, including leftovers of earlier runs
+    if output_after > 0 {
+        invoke_signed(
+            &token_instruction::burn(
+                token_program_info.key,
+                output_info.key,
+                governance_mint_info.key,
+                output_info.key,
+                &[],
+                output_after,
+            )?,
+            &[
+                output_info.clone(),
+                governance_mint_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[output_seeds],
+        )?;
+    }
+
+    buyback.fees_accounted = pool_data.total_platform_fees_collected;
+    buyback.total_spent = buyback
+        .total_spent
+        .checked_add(spent)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    buyback.total_burned = buyback
+        .total_burned
+        .checked_add(output_after)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    buyback.run_count = buyback
+        .run_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    buyback.last_run_at = Clock::get()?.unix_timestamp;
+    buyback.last_burned = output_after;
+    buyback.save(buyback_info)?;
+
+    RewardPoolEvent::BuybackExecuted {
+        pool: *pool_info.key,
+        spent,
+        burned: output_after,
+    }
+    .emit();
+
+    msg!("Buyback swapped {} and burned {}", spent, output_after);
+    Ok(())
+}
+
+/// Returns the buyback configuration of the pool, `None` when the pool has
+/// none
+pub(super) fn read_buyback(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    buyback_info: &AccountInfo,
+) -> Result<Option<BuybackConfig>, ProgramError> {
+    let (expected_buyback, _) = find_buyback_address_with_program_id(pool, program_id);
+    if *buyback_info.key != expected_buyback {
+        return Err(RewardPoolError::InvalidBuybackAccount.into());
+    }
+    if buyback_info.data_is_empty() {
+        return Ok(None);
+    }
+    load_buyback(program_id, pool, buyback_info).map(Some)
+}
+
+fn load_buyback(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    buyback_info: &AccountInfo,
+) -> Result<BuybackConfig, ProgramError> {
+    if buyback_info.owner != program_id {
+        return Err(RewardPoolError::InvalidBuybackAccount.into());
+    }
+    let buyback = BuybackConfig::try_from_slice(&buyback_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidBuybackAccount)?;
+    let expected_buyback = Pubkey::create_program_address(
+        &[BUYBACK_SEED, pool.as_ref(), &[buyback.bump_seed]],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidBuybackAccount)?;
+    if buyback.pool != *pool || *buyback_info.key != expected_buyback {
+        return Err(RewardPoolError::InvalidBuybackAccount.into());
+    }
+    Ok(buyback)
+}
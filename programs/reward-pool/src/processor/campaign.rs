@@ -0,0 +1,315 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{
+    budget::{lock_in_budget_escrow, transfer_from_budget_escrow},
+    treasury::load_pool,
+    utils::create_pda_account,
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_campaign_address_with_program_id, find_vault_address_with_program_id, CAMPAIGN_SEED,
+    },
+    state::{Campaign, Sequenced},
+};
+
+// Creating a campaign
+pub(super) fn process_create_campaign(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    campaign_id: u64,
+    budget: u64,
+    starts_at: i64,
+    ends_at: i64,
+    task_types: Vec<u32>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let campaign_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if budget == 0
+        || ends_at <= starts_at
+        || ends_at <= now
+        || task_types.len() > Campaign::MAX_TASK_TYPES
+    {
+        return Err(RewardPoolError::InvalidCampaign.into());
+    }
+
+    let (expected_campaign, bump_seed) =
+        find_campaign_address_with_program_id(pool_info.key, campaign_id, program_id);
+    if *campaign_info.key != expected_campaign || !campaign_info.data_is_empty() {
+        return Err(RewardPoolError::InvalidCampaignAccount.into());
+    }
+
+    lock_in_budget_escrow(
+        program_id,
+        pool_info.key,
+        &pool_data.reward_mint,
+        platform_authority_info,
+        vault_info,
+        escrow_info,
+        reward_mint_info,
+        token_program_info,
+        system_program_info,
+        budget,
+    )?;
+
+    create_pda_account(
+        platform_authority_info,
+        campaign_info,
+        system_program_info,
+        program_id,
+        Campaign::LEN,
+        &[
+            CAMPAIGN_SEED,
+            pool_info.key.as_ref(),
+            &campaign_id.to_le_bytes(),
+            &[bump_seed],
+        ],
+    )?;
+    let mut campaign = Campaign {
+        pool: *pool_info.key,
+        campaign_id,
+        budget,
+        spent: 0,
+        starts_at,
+        ends_at,
+        created_at: now,
+        task_type_count: task_types.len() as u8,
+        task_types: [0; Campaign::MAX_TASK_TYPES],
+        reward_count: 0,
+        escrowed: budget,
+        is_ended: false,
+        is_closed: false,
+        bump_seed,
+        sequence: 0,
+    };
+    campaign.task_types[..task_types.len()].copy_from_slice(&task_types);
+    campaign.save(campaign_info)?;
+
+    RewardPoolEvent::CampaignCreated {
+        pool: *pool_info.key,
+        campaign_id,
+        budget,
+        starts_at,
+        ends_at,
+        task_types,
+    }
+    .emit();
+
+    msg!(
+        "Campaign {} created with a budget of {}",
+        campaign_id,
+        budget
+    );
+    Ok(())
+}
+
+// Ending a campaign early
+pub(super) fn process_end_campaign(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let campaign_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let mut campaign = load_campaign(program_id, pool_info.key, campaign_info)?;
+    if campaign.is_ended {
+        return Err(RewardPoolError::CampaignNotActive.into());
+    }
+
+    campaign.is_ended = true;
+    campaign.ends_at = campaign.ends_at.min(Clock::get()?.unix_timestamp);
+    campaign.save(campaign_info)?;
+
+    RewardPoolEvent::CampaignEnded {
+        pool: *pool_info.key,
+        campaign_id: campaign.campaign_id,
+        spent: campaign.spent,
+        reward_count: campaign.reward_count,
+    }
+    .emit();
+
+    msg!(
+        "Campaign {} ended, {} of {} spent",
+        campaign.campaign_id,
+        campaign.spent,
+        campaign.budget
+    );
+    Ok(())
+}
+
+// Returning the unspent budget of an ended campaign
+pub(super) fn process_finalize_campaign(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let _caller_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let campaign_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    load_pool(program_id, pool_info)?;
+
+    let (expected_vault, _) = find_vault_address_with_program_id(pool_info.key, program_id);
+    if *vault_info.key != expected_vault {
+        return Err(RewardPoolError::InvalidVaultAccount.into());
+    }
+
+    let mut campaign = load_campaign(program_id, pool_info.key, campaign_info)?;
+    if campaign.is_closed {
+        return Err(RewardPoolError::CampaignClosed.into());
+    }
+    if !campaign.is_ended && Clock::get()?.unix_timestamp < campaign.ends_at {
+        return Err(RewardPoolError::CampaignStillRunning.into());
+    }
+
+    let returned = campaign.escrowed;
+    if returned > 0 {
+        transfer_from_budget_escrow(
+            program_id,
+            pool_info.key,
+            escrow_info,
+            vault_info,
+            token_program_info,
+            returned,
+        )?;
+    }
+
+    campaign.escrowed = 0;
+    campaign.is_ended = true;
+    campaign.is_closed = true;
+    campaign.save(campaign_info)?;
+
+    RewardPoolEvent::CampaignFinalized {
+        pool: *pool_info.key,
+        campaign_id: campaign.campaign_id,
+        returned,
+    }
+    .emit();
+
+    msg!(
+        "Campaign {} finalized, {} tokens returned",
+        campaign.campaign_id,
+        returned
+    );
+    Ok(())
+}
+
+/// Loads the campaign a reward of `amount` for a task of `task_type` is
+/// attributed to, failing unless the campaign runs at `now` and covers it
+pub(super) fn load_reward_campaign(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    campaign_info: &AccountInfo,
+    campaign_id: u64,
+    task_type: Option<u32>,
+    amount: u64,
+    now: i64,
+) -> Result<Campaign, ProgramError> {
+    let campaign = load_campaign(program_id, pool, campaign_info)?;
+    if campaign.campaign_id != campaign_id {
+        return Err(RewardPoolError::InvalidCampaignAccount.into());
+    }
+
+    if !campaign.is_active(now) {
+        return Err(RewardPoolError::CampaignNotActive.into());
+    }
+    if !campaign.task_types().is_empty()
+        && !task_type.is_some_and(|task_type| campaign.task_types().contains(&task_type))
+    {
+        return Err(RewardPoolError::TaskTypeNotInCampaign.into());
+    }
+    if amount > campaign.remaining() {
+        return Err(RewardPoolError::CampaignBudgetExceeded.into());
+    }
+
+    Ok(campaign)
+}
+
+/// Draws a recorded reward on its campaign's budget. `paid` is the part the
+/// budget escrow pays out for the campaign, 0 when a task budget pays.
+pub(super) fn record_campaign_reward(
+    campaign: &mut Campaign,
+    campaign_info: &AccountInfo,
+    amount: u64,
+    paid: u64,
+) -> ProgramResult {
+    campaign.spent += amount;
+    campaign.escrowed = campaign
+        .escrowed
+        .checked_sub(paid)
+        .ok_or(RewardPoolError::CampaignBudgetExceeded)?;
+    campaign.reward_count = campaign
+        .reward_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    campaign.save(campaign_info)
+}
+
+fn load_campaign(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    campaign_info: &AccountInfo,
+) -> Result<Campaign, ProgramError> {
+    if campaign_info.owner != program_id {
+        return Err(RewardPoolError::InvalidCampaignAccount.into());
+    }
+    let campaign = Campaign::try_from_slice(&campaign_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidCampaignAccount)?;
+    let expected_campaign = Pubkey::create_program_address(
+        &[
+            CAMPAIGN_SEED,
+            pool.as_ref(),
+            &campaign.campaign_id.to_le_bytes(),
+            &[campaign.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidCampaignAccount)?;
+    if campaign.pool != *pool || *campaign_info.key != expected_campaign {
+        return Err(RewardPoolError::InvalidCampaignAccount.into());
+    }
+    Ok(campaign)
+}
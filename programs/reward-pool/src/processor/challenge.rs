@@ -0,0 +1,195 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use solana_system_interface::instruction as system_instruction;
+
+use super::{
+    pending::{load_pending_reward, release_outstanding_rewards},
+    treasury::load_pool,
+    utils::{close_pda_account, create_pda_account},
+    verifier::read_pool_verifier,
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{find_reward_challenge_address_with_program_id, REWARD_CHALLENGE_SEED},
+    state::{RewardChallenge, Sequenced},
+    CHALLENGE_BOND, CHALLENGE_WINDOW,
+};
+
+// Challenging a pending reward
+pub(super) fn process_challenge_reward(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let challenger_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let pending_info = next_account_info(account_info_iter)?;
+    let challenge_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !challenger_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    let mut pending = load_pending_reward(program_id, pool_info.key, pending_info)?;
+    if pending.is_withdrawn {
+        return Err(RewardPoolError::NoPendingRewards.into());
+    }
+    if pending.is_challenged {
+        return Err(RewardPoolError::RewardChallenged.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if now > pending.recorded_at.saturating_add(CHALLENGE_WINDOW) {
+        return Err(RewardPoolError::ChallengeWindowClosed.into());
+    }
+
+    let (expected_challenge, bump_seed) =
+        find_reward_challenge_address_with_program_id(pool_info.key, pending_info.key, program_id);
+    if *challenge_info.key != expected_challenge {
+        return Err(RewardPoolError::InvalidChallengeAccount.into());
+    }
+
+    create_pda_account(
+        challenger_info,
+        challenge_info,
+        system_program_info,
+        program_id,
+        RewardChallenge::LEN,
+        &[
+            REWARD_CHALLENGE_SEED,
+            pool_info.key.as_ref(),
+            pending_info.key.as_ref(),
+            &[bump_seed],
+        ],
+    )?;
+    // The bond sits in the challenge account on top of its rent
+    invoke(
+        &system_instruction::transfer(challenger_info.key, challenge_info.key, CHALLENGE_BOND),
+        &[
+            challenger_info.clone(),
+            challenge_info.clone(),
+            system_program_info.clone(),
+        ],
+    )?;
+
+    let mut challenge = RewardChallenge {
+        pool: *pool_info.key,
+        pending_reward: *pending_info.key,
+        challenger: *challenger_info.key,
+        amount: pending.amount,
+        bond: CHALLENGE_BOND,
+        challenged_at: now,
+        bump_seed,
+        sequence: 0,
+    };
+    challenge.save(challenge_info)?;
+
+    pending.is_challenged = true;
+    pending.serialize(&mut &mut pending_info.data.borrow_mut()[..])?;
+
+    RewardPoolEvent::RewardChallenged {
+        pool: *pool_info.key,
+        farmer: pending.farmer_pubkey,
+        task_id: pending.task_id,
+        challenger: *challenger_info.key,
+        bond: CHALLENGE_BOND,
+    }
+    .emit();
+
+    msg!("Reward of {} tokens challenged", pending.amount);
+    Ok(())
+}
+
+// Deciding a challenge
+pub(super) fn process_resolve_challenge(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    upheld: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let resolver_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let pool_verifier_info = next_account_info(account_info_iter)?;
+    let pending_info = next_account_info(account_info_iter)?;
+    let challenge_info = next_account_info(account_info_iter)?;
+    let challenger_info = next_account_info(account_info_iter)?;
+    let farmer_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !resolver_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    // The verifier, when the pool has one, arbitrates next to the platform
+    let mut pool_data = load_pool(program_id, pool_info)?;
+    let verifier = read_pool_verifier(program_id, pool_info.key, pool_verifier_info)?;
+    if pool_data.platform_authority != *resolver_info.key && verifier != Some(*resolver_info.key) {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let mut pending = load_pending_reward(program_id, pool_info.key, pending_info)?;
+    if pending.farmer_pubkey != *farmer_info.key {
+        return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+    }
+
+    let (expected_challenge, _) =
+        find_reward_challenge_address_with_program_id(pool_info.key, pending_info.key, program_id);
+    if *challenge_info.key != expected_challenge || challenge_info.owner != program_id {
+        return Err(RewardPoolError::InvalidChallengeAccount.into());
+    }
+    let challenge = RewardChallenge::try_from_slice(&challenge_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidChallengeAccount)?;
+    if challenge.challenger != *challenger_info.key {
+        return Err(RewardPoolError::InvalidChallengeAccount.into());
+    }
+
+    if upheld {
+        // Like a donation to the pool, the voided reward stays in the vault
+        close_pda_account(pending_info, farmer_info)?;
+        release_outstanding_rewards(pool_info, &mut pool_data, pending.amount)?;
+    } else {
+        pending.is_challenged = false;
+        pending.serialize(&mut &mut pending_info.data.borrow_mut()[..])?;
+
+        **challenge_info.try_borrow_mut_lamports()? = challenge_info
+            .lamports()
+            .checked_sub(challenge.bond)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        **farmer_info.try_borrow_mut_lamports()? = farmer_info
+            .lamports()
+            .checked_add(challenge.bond)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+    close_pda_account(challenge_info, challenger_info)?;
+
+    RewardPoolEvent::ChallengeResolved {
+        pool: *pool_info.key,
+        farmer: pending.farmer_pubkey,
+        task_id: pending.task_id,
+        challenger: challenge.challenger,
+        resolver: *resolver_info.key,
+        upheld,
+    }
+    .emit();
+
+    msg!("Challenge {}", if upheld { "upheld" } else { "rejected" });
+    Ok(())
+}
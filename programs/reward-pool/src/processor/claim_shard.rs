@@ -0,0 +1,216 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{counters::add_claim_counts, treasury::load_pool, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        claim_shard_index, find_claim_ledger_address_with_program_id,
+        find_claim_shard_address_with_program_id, CLAIM_LEDGER_SEED, CLAIM_SHARD_COUNT,
+        CLAIM_SHARD_SEED,
+    },
+    state::{ClaimLedger, ClaimShard, ClaimTotals, Sequenced},
+};
+
+/// Counts a claim of `farmer` in their claim shard, creating the shard on
+/// its first claim
+#[allow(clippy::too_many_arguments)]
+pub(super) fn record_claim<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    claim_shard_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    claim: ClaimTotals,
+    now: i64,
+) -> ProgramResult {
+    let shard = claim_shard_index(farmer);
+    let (expected_claim_shard, bump_seed) =
+        find_claim_shard_address_with_program_id(pool, shard, program_id);
+    if *claim_shard_info.key != expected_claim_shard {
+        return Err(RewardPoolError::InvalidClaimShardAccount.into());
+    }
+
+    let mut claim_shard = if claim_shard_info.data_is_empty() {
+        create_pda_account(
+            payer_info,
+            claim_shard_info,
+            system_program_info,
+            program_id,
+            ClaimShard::LEN,
+            &[CLAIM_SHARD_SEED, pool.as_ref(), &[shard], &[bump_seed]],
+        )?;
+        ClaimShard::new(*pool, shard, bump_seed)
+    } else {
+        // The shard stands in for the counters account, and fails the same
+        // way when it is not the program's
+        if claim_shard_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        load_claim_shard(program_id, pool, claim_shard_info)?
+    };
+
+    claim_shard.totals.add(&claim);
+    claim_shard.last_claimed_at = now;
+    claim_shard.save(claim_shard_info)
+}
+
+// Consolidating claim shards into the pool's claim ledger
+pub(super) fn process_consolidate_claim_shards(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let ledger_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let counters_info = next_account_info(account_info_iter)?;
+    let claim_shard_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if claim_shard_infos.len() > CLAIM_SHARD_COUNT as usize {
+        return Err(RewardPoolError::InvalidClaimShardAccount.into());
+    }
+
+    load_pool(program_id, pool_info)?;
+
+    let (expected_ledger, ledger_bump_seed) =
+        find_claim_ledger_address_with_program_id(pool_info.key, program_id);
+    if *ledger_info.key != expected_ledger {
+        return Err(RewardPoolError::InvalidClaimLedgerAccount.into());
+    }
+    let mut ledger = if ledger_info.data_is_empty() {
+        create_pda_account(
+            payer_info,
+            ledger_info,
+            system_program_info,
+            program_id,
+            ClaimLedger::LEN,
+            &[
+                CLAIM_LEDGER_SEED,
+                pool_info.key.as_ref(),
+                &[ledger_bump_seed],
+            ],
+        )?;
+        ClaimLedger {
+            pool: *pool_info.key,
+            totals: ClaimTotals::default(),
+            consolidation_count: 0,
+            last_consolidated_at: 0,
+            bump_seed: ledger_bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_claim_ledger(program_id, pool_info.key, ledger_info)?
+    };
+
+    // A shard passed twice has nothing left to add the second time
+    let mut consolidated = ClaimTotals::default();
+    let mut shard_count: u8 = 0;
+    for claim_shard_info in claim_shard_infos {
+        if claim_shard_info.data_is_empty() {
+            continue;
+        }
+        let mut claim_shard = load_claim_shard(program_id, pool_info.key, claim_shard_info)?;
+        consolidated.add(&claim_shard.totals.since(&claim_shard.consolidated));
+        claim_shard.consolidated = claim_shard.totals;
+        claim_shard.save(claim_shard_info)?;
+        shard_count += 1;
+    }
+
+    add_claim_counts(
+        program_id,
+        pool_info,
+        counters_info,
+        payer_info,
+        system_program_info,
+        &consolidated,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    ledger.totals.add(&consolidated);
+    ledger.consolidation_count = ledger
+        .consolidation_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    ledger.last_consolidated_at = now;
+    ledger.save(ledger_info)?;
+
+    RewardPoolEvent::ClaimShardsConsolidated {
+        pool: *pool_info.key,
+        shard_count,
+        consolidated,
+        totals: ledger.totals,
+    }
+    .emit();
+
+    msg!(
+        "Consolidated {} claim shards: {} claims, {} tokens",
+        shard_count,
+        consolidated.claim_count + consolidated.auto_claim_count,
+        consolidated.amount
+    );
+    Ok(())
+}
+
+fn load_claim_shard(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    claim_shard_info: &AccountInfo,
+) -> Result<ClaimShard, ProgramError> {
+    if claim_shard_info.owner != program_id {
+        return Err(RewardPoolError::InvalidClaimShardAccount.into());
+    }
+    let claim_shard = ClaimShard::try_from_slice(&claim_shard_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidClaimShardAccount)?;
+    let expected_claim_shard = Pubkey::create_program_address(
+        &[
+            CLAIM_SHARD_SEED,
+            pool.as_ref(),
+            &[claim_shard.shard],
+            &[claim_shard.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidClaimShardAccount)?;
+    if claim_shard.pool != *pool || *claim_shard_info.key != expected_claim_shard {
+        return Err(RewardPoolError::InvalidClaimShardAccount.into());
+    }
+    Ok(claim_shard)
+}
+
+fn load_claim_ledger(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    ledger_info: &AccountInfo,
+) -> Result<ClaimLedger, ProgramError> {
+    if ledger_info.owner != program_id {
+        return Err(RewardPoolError::InvalidClaimLedgerAccount.into());
+    }
+    let ledger = ClaimLedger::try_from_slice(&ledger_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidClaimLedgerAccount)?;
+    let expected_ledger = Pubkey::create_program_address(
+        &[CLAIM_LEDGER_SEED, pool.as_ref(), &[ledger.bump_seed]],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidClaimLedgerAccount)?;
+    if ledger.pool != *pool || *ledger_info.key != expected_ledger {
+        return Err(RewardPoolError::InvalidClaimLedgerAccount.into());
+    }
+    Ok(ledger)
+}
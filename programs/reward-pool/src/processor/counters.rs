@@ -0,0 +1,280 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult,
+    program_error::ProgramError, pubkey::Pubkey, sysvar::Sysvar,
+};
+
+use super::utils::{create_pda_account, resize_pda_account};
+use crate::{
+    error::RewardPoolError,
+    instruction::v2::RewardPoolInstruction,
+    seeds::{find_instruction_counters_address_with_program_id, INSTRUCTION_COUNTERS_SEED},
+    state::{ClaimTotals, InstructionCounters, RewardPool, Sequenced},
+};
+
+// Counter slots of the claims counted in claim shards
+const CLAIM_MANY_SLOT: usize = 30;
+const CRANK_AUTO_CLAIM_SLOT: usize = 40;
+
+// Counter slot of an executed instruction and where its accounts are. The
+// pool is always account 1 and the payer account 0, unless the instruction
+// takes an optional sponsor that is present. Accounts that may follow in
+// the sponsor's place never sign, so a non-signer there is no sponsor.
+// V1 account lists end before the counters account, which is optional
+// trailing for them: an instruction passed without it is not counted.
+pub(super) struct InstructionCounter {
+    slot: usize,
+    counters_index: usize,
+    system_program_index: usize,
+    sponsor_index: Option<usize>,
+}
+
+impl InstructionCounter {
+    /// Counter of a state-changing instruction, `None` for views and
+    /// snapshots, for batches spanning several pools, for platform
+    /// instructions involving no pool, for `ClosePool`, which closes the
+    /// counters account, and for claims, counted in their farmer's claim
+    /// shard until `ConsolidateClaimShards` adds them to their slots
+    pub(super) fn of(instruction: &RewardPoolInstruction) -> Option<Self> {
+        let (slot, counters_index, system_program_index, sponsor_index) = match instruction {
+            RewardPoolInstruction::InitializePool { .. } => (0, 8, 5, None),
+            RewardPoolInstruction::RecordReward { .. } => (1, 9, 8, None),
+            RewardPoolInstruction::WithdrawReward { .. } => (2, 9, 8, Some(19)),
+            RewardPoolInstruction::UpdatePlatformFee { .. } => (3, 2, 3, None),
+            RewardPoolInstruction::PausePool => (4, 2, 3, None),
+            RewardPoolInstruction::ResumePool => (5, 2, 3, None),
+            RewardPoolInstruction::SweepDelegatedRewards => (8, 6, 5, None),
+            RewardPoolInstruction::AddWithdrawalDestination { .. } => (9, 4, 3, None),
+            RewardPoolInstruction::RemoveWithdrawalDestination { .. } => (10, 4, 3, None),
+            RewardPoolInstruction::ConfigureTreasuryGovernance { .. } => (11, 7, 6, None),
+            RewardPoolInstruction::ProposeTreasuryWithdrawal { .. } => (12, 6, 5, None),
+            RewardPoolInstruction::ApproveTreasuryWithdrawal => (13, 5, 4, None),
+            RewardPoolInstruction::ExecuteTreasuryWithdrawal => (14, 8, 7, None),
+            RewardPoolInstruction::SetUpgradeAuthority => (15, 7, 6, None),
+            RewardPoolInstruction::RemoveUpgradeAuthority => (16, 7, 6, None),
+            RewardPoolInstruction::UpdateParameters { .. } => (17, 5, 4, None),
+            RewardPoolInstruction::SetGuardians { .. } => (18, 4, 3, None),
+            RewardPoolInstruction::ScheduleAction { .. } => (19, 4, 3, None),
+            RewardPoolInstruction::CancelAction => (20, 5, 4, None),
+            RewardPoolInstruction::ExecuteAction => (21, 5, 4, None),
+            RewardPoolInstruction::RollbackParameter { .. } => (22, 5, 4, None),
+            RewardPoolInstruction::SetPoolMetadata { .. } => (25, 4, 3, None),
+            RewardPoolInstruction::ExportState { .. } => (26, 4, 3, None),
+            RewardPoolInstruction::ImportState { .. } => (27, 10, 9, None),
+            RewardPoolInstruction::ArchivePool { .. } => (28, 4, 3, None),
+            RewardPoolInstruction::RegisterPayoutAddress { .. } => (31, 4, 3, None),
+            RewardPoolInstruction::SetCharityAccount => (32, 5, 4, None),
+            RewardPoolInstruction::DonateReward { .. } => (33, 7, 6, None),
+            RewardPoolInstruction::CloseFarmerAccounts => (34, 9, 8, None),
+            RewardPoolInstruction::SetAutoForward { .. } => (35, 7, 6, None),
+            RewardPoolInstruction::SetPendingTransferPolicy { .. } => (36, 4, 3, None),
+            RewardPoolInstruction::TransferPending { .. } => (37, 8, 7, None),
+            RewardPoolInstruction::SetAutoClaim { .. } => (39, 5, 4, None),
+            RewardPoolInstruction::SetVerifier { .. } => (41, 4, 3, None),
+            RewardPoolInstruction::SetAttestor { .. } => (42, 4, 3, None),
+            RewardPoolInstruction::LockTaskBudget { .. } => (43, 8, 7, None),
+            RewardPoolInstruction::ReleaseUnusedBudget => (44, 8, 7, None),
+            RewardPoolInstruction::ChallengeReward => (45, 5, 4, None),
+            RewardPoolInstruction::ResolveChallenge { .. } => (46, 8, 7, None),
+            RewardPoolInstruction::SetRewardApprover { .. } => (47, 4, 3, None),
+            RewardPoolInstruction::ApproveReward => (48, 5, 4, None),
+            RewardPoolInstruction::SetTaskTypeFee { .. } => (49, 5, 4, None),
+            RewardPoolInstruction::InitializePlatformPool { .. } => (52, 6, 5, None),
+            RewardPoolInstruction::CreateCampaign { .. } => (53, 4, 3, None),
+            RewardPoolInstruction::EndCampaign => (54, 4, 3, None),
+            RewardPoolInstruction::FinalizeCampaign => (55, 7, 6, None),
+            RewardPoolInstruction::ConfigureBuyback { .. } => (56, 5, 4, None),
+            RewardPoolInstruction::RunBuyback { .. } => (57, 9, 8, None),
+            RewardPoolInstruction::SetTreasurySwapPolicy { .. } => (58, 5, 4, None),
+            RewardPoolInstruction::SetFeeDiscounts { .. } => (59, 4, 3, None),
+            RewardPoolInstruction::SetInterestPolicy { .. } => (60, 4, 3, None),
+            RewardPoolInstruction::SetWithdrawBurn { .. } => (61, 4, 3, None),
+            RewardPoolInstruction::SetRewardGovernor { .. } => (62, 4, 3, None),
+            RewardPoolInstruction::SetSecondaryMint => (63, 4, 3, None),
+            RewardPoolInstruction::SetMilestoneBonuses { .. } => (64, 4, 3, None),
+            RewardPoolInstruction::SetProtocolLiquidity { .. } => (65, 5, 4, None),
+            RewardPoolInstruction::ContributeLiquidity { .. } => (66, 9, 8, None),
+            RewardPoolInstruction::SetFeeRebate { .. } => (67, 4, 3, None),
+            RewardPoolInstruction::CreatePool { .. } => (68, 8, 7, None),
+            RewardPoolInstruction::IssueVoucher { .. } => (69, 12, 11, None),
+            RewardPoolInstruction::RedeemVoucher => (70, 9, 8, None),
+            RewardPoolInstruction::SetPayoutQueue { .. } => (71, 4, 3, None),
+            RewardPoolInstruction::TakePayoutTicket => (72, 5, 4, None),
+            RewardPoolInstruction::ResumeWithRamp { .. } => (73, 2, 3, None),
+            RewardPoolInstruction::SetRecoveryAuthority { .. } => (74, 4, 3, None),
+            RewardPoolInstruction::ClaimDormantAuthority => (75, 4, 3, None),
+            RewardPoolInstruction::SetRecoveryGuardians { .. } => (76, 4, 3, None),
+            RewardPoolInstruction::InitiateFarmerRecovery { .. } => (77, 4, 3, None),
+            RewardPoolInstruction::CancelFarmerRecovery => (78, 4, 3, None),
+            RewardPoolInstruction::CompleteFarmerRecovery => (79, 6, 5, None),
+            RewardPoolInstruction::SetWithdrawalFee { .. } => (80, 5, 4, None),
+            RewardPoolInstruction::CollectWithdrawalFees => (81, 5, 4, None),
+            RewardPoolInstruction::SetWithdrawalCosigner { .. } => (82, 5, 4, None),
+            RewardPoolInstruction::SetGuildSplit { .. } => (83, 5, 4, None),
+            RewardPoolInstruction::SetGuildMembers { .. } => (84, 7, 6, None),
+            RewardPoolInstruction::LeaveGuild => (85, 4, 3, None),
+            RewardPoolInstruction::DistributeGuildRewards => (86, 6, 5, None),
+            RewardPoolInstruction::CloseFeeInvoice => (87, 10, 9, None),
+            RewardPoolInstruction::ConsolidateClaimShards => (88, 4, 3, None),
+            RewardPoolInstruction::FundTreasury { .. } => (89, 7, 6, None),
+            RewardPoolInstruction::CloseWithdrawalRecords => (90, 3, 2, None),
+            RewardPoolInstruction::GetFarmerPending { .. }
+            | RewardPoolInstruction::GetPoolStats
+            | RewardPoolInstruction::GetWithdrawalHistory { .. }
+            | RewardPoolInstruction::ExportSnapshot
+            | RewardPoolInstruction::GetFeeInvoice { .. }
+            | RewardPoolInstruction::GetRewardRate { .. }
+            | RewardPoolInstruction::PauseAll
+            | RewardPoolInstruction::SetFeeAll { .. }
+            | RewardPoolInstruction::ClaimAcrossPools { .. }
+            | RewardPoolInstruction::RegisterPlatform { .. }
+            | RewardPoolInstruction::SetPlatformOperator { .. }
+            | RewardPoolInstruction::SetProtocolConfig { .. }
+            | RewardPoolInstruction::ClosePool
+            | RewardPoolInstruction::ClaimMany
+            | RewardPoolInstruction::CrankAutoClaim => return None,
+        };
+
+        Some(Self {
+            slot,
+            counters_index,
+            system_program_index,
+            sponsor_index,
+        })
+    }
+
+    /// Increments the instruction's count, creating the pool's counters
+    /// account on its first counted instruction
+    pub(super) fn record<'a>(
+        &self,
+        program_id: &Pubkey,
+        accounts: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        let account = |index: usize| {
+            accounts
+                .get(index)
+                .ok_or(ProgramError::NotEnoughAccountKeys)
+        };
+        let payer_info = self
+            .sponsor_index
+            .and_then(|index| accounts.get(index))
+            .filter(|sponsor| sponsor.is_signer)
+            .map_or_else(|| account(0), Ok)?;
+        let pool_info = account(1)?;
+        let Some(counters_info) = accounts.get(self.counters_index) else {
+            return Ok(());
+        };
+        let mut counters = load_counters(
+            program_id,
+            pool_info,
+            counters_info,
+            payer_info,
+            account(self.system_program_index)?,
+        )?;
+
+        let count = &mut counters.counts[self.slot];
+        *count = count
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        counters.save(counters_info)
+    }
+}
+
+/// Notes on the pool the time its platform authority signed a pool
+/// instruction, keeping the authority's dead-man switch from firing. The
+/// authority's instructions take the pool writable, with or without the
+/// counters account; a pool passed read-only is left as is.
+pub(super) fn note_authority_action(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let (Some(signer_info), Some(pool_info)) = (accounts.first(), accounts.get(1)) else {
+        return Ok(());
+    };
+    if !signer_info.is_signer || !pool_info.is_writable || pool_info.owner != program_id {
+        return Ok(());
+    }
+    let Ok(mut pool_data) = RewardPool::try_from_slice(&pool_info.data.borrow()) else {
+        return Ok(());
+    };
+    if pool_data.platform_authority != *signer_info.key {
+        return Ok(());
+    }
+    pool_data.last_authority_action_at = Clock::get()?.unix_timestamp;
+    pool_data.save(pool_info)
+}
+
+/// Adds the claims `ConsolidateClaimShards` consolidated to the slots of
+/// `ClaimMany` and `CrankAutoClaim`
+pub(super) fn add_claim_counts<'a>(
+    program_id: &Pubkey,
+    pool_info: &AccountInfo<'a>,
+    counters_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    claims: &ClaimTotals,
+) -> ProgramResult {
+    let mut counters = load_counters(
+        program_id,
+        pool_info,
+        counters_info,
+        payer_info,
+        system_program_info,
+    )?;
+    for (slot, count) in [
+        (CLAIM_MANY_SLOT, claims.claim_count),
+        (CRANK_AUTO_CLAIM_SLOT, claims.auto_claim_count),
+    ] {
+        counters.counts[slot] = counters.counts[slot]
+            .checked_add(count)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+    counters.save(counters_info)
+}
+
+// Loads the pool's counters account, creating it or growing it to the
+// current size first
+fn load_counters<'a>(
+    program_id: &Pubkey,
+    pool_info: &AccountInfo<'a>,
+    counters_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+) -> Result<InstructionCounters, ProgramError> {
+    let (expected_counters, bump_seed) =
+        find_instruction_counters_address_with_program_id(pool_info.key, program_id);
+    if *counters_info.key != expected_counters {
+        return Err(RewardPoolError::InvalidCountersAccount.into());
+    }
+
+    if counters_info.data_is_empty() {
+        create_pda_account(
+            payer_info,
+            counters_info,
+            system_program_info,
+            program_id,
+            InstructionCounters::LEN,
+            &[
+                INSTRUCTION_COUNTERS_SEED,
+                pool_info.key.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        Ok(InstructionCounters::new(*pool_info.key, bump_seed))
+    } else {
+        if counters_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let counters = InstructionCounters::unpack(&counters_info.data.borrow())?;
+        // Accounts created before the counters grew gain the new slots
+        if counters_info.data_len() < InstructionCounters::LEN {
+            resize_pda_account(
+                payer_info,
+                counters_info,
+                system_program_info,
+                InstructionCounters::LEN,
+            )?;
+        }
+        Ok(counters)
+    }
+}
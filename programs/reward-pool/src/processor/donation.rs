@@ -0,0 +1,236 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_token::{instruction as token_instruction, state::Account as TokenAccount};
+
+use super::{
+    pending::release_outstanding_rewards,
+    treasury::load_pool,
+    utils::{close_pda_account, create_pda_account},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_pending_reward_address_with_program_id, find_pool_charity_address_with_program_id,
+        find_vault_address_with_program_id, POOL_CHARITY_SEED, VAULT_SEED,
+    },
+    state::{DonationTarget, PendingReward, PoolCharity, Sequenced},
+};
+
+// Setting the pool's charity account
+pub(super) fn process_set_charity_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let pool_charity_info = next_account_info(account_info_iter)?;
+    let charity_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if *charity_info.owner != spl_token::id() {
+        return Err(RewardPoolError::InvalidCharityAccount.into());
+    }
+    let charity_account = TokenAccount::unpack(&charity_info.data.borrow())?;
+    if charity_account.mint != pool_data.reward_mint {
+        return Err(RewardPoolError::InvalidCharityAccount.into());
+    }
+
+    let (expected_pool_charity, bump_seed) =
+        find_pool_charity_address_with_program_id(pool_info.key, program_id);
+    if *pool_charity_info.key != expected_pool_charity {
+        return Err(RewardPoolError::InvalidCharityAccount.into());
+    }
+
+    let mut pool_charity = if pool_charity_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            pool_charity_info,
+            system_program_info,
+            program_id,
+            PoolCharity::LEN,
+            &[POOL_CHARITY_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        PoolCharity {
+            pool: *pool_info.key,
+            charity: *charity_info.key,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        if pool_charity_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let pool_charity = PoolCharity::try_from_slice(&pool_charity_info.data.borrow())?;
+        PoolCharity {
+            charity: *charity_info.key,
+            ..pool_charity
+        }
+    };
+    pool_charity.save(pool_charity_info)?;
+
+    RewardPoolEvent::CharityAccountSet {
+        pool: *pool_info.key,
+        charity: *charity_info.key,
+    }
+    .emit();
+
+    msg!("Charity account set to {}", charity_info.key);
+    Ok(())
+}
+
+// Donating a pending reward
+pub(super) fn process_donate_reward(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    target: DonationTarget,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let pending_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let _system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut pool_data = load_pool(program_id, pool_info)?;
+
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    let (expected_vault, vault_bump_seed) =
+        find_vault_address_with_program_id(pool_info.key, program_id);
+    if *vault_info.key != expected_vault {
+        return Err(RewardPoolError::InvalidVaultAccount.into());
+    }
+
+    if *reward_mint_info.key != pool_data.reward_mint || *token_program_info.key != spl_token::id()
+    {
+        return Err(RewardPoolError::InvalidVaultAccount.into());
+    }
+
+    if pending_info.owner != program_id {
+        return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+    }
+    let pending = PendingReward::try_from_slice(&pending_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidPendingRewardAccount)?;
+    let (expected_address, _) = find_pending_reward_address_with_program_id(
+        pool_info.key,
+        farmer_info.key,
+        &pending.task_id,
+        program_id,
+    );
+    if pending.farmer_pubkey != *farmer_info.key || *pending_info.key != expected_address {
+        return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+    }
+
+    if pending.is_withdrawn {
+        return Err(RewardPoolError::NoPendingRewards.into());
+    }
+    if pending.is_challenged {
+        return Err(RewardPoolError::RewardChallenged.into());
+    }
+    if pending.awaiting_approval {
+        return Err(RewardPoolError::RewardAwaitingApproval.into());
+    }
+
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, pool_info.key.as_ref(), &[vault_bump_seed]];
+    match target {
+        // The reward is already funded from the vault, nothing to move
+        DonationTarget::Pool => {}
+        DonationTarget::Charity => {
+            let pool_charity_info = next_account_info(account_info_iter)?;
+            let charity_info = next_account_info(account_info_iter)?;
+
+            let (expected_pool_charity, _) =
+                find_pool_charity_address_with_program_id(pool_info.key, program_id);
+            if *pool_charity_info.key != expected_pool_charity
+                || pool_charity_info.owner != program_id
+            {
+                return Err(RewardPoolError::InvalidCharityAccount.into());
+            }
+            let pool_charity = PoolCharity::try_from_slice(&pool_charity_info.data.borrow())
+                .map_err(|_| RewardPoolError::InvalidCharityAccount)?;
+            if pool_charity.charity != *charity_info.key {
+                return Err(RewardPoolError::InvalidCharityAccount.into());
+            }
+
+            invoke_signed(
+                &token_instruction::transfer(
+                    token_program_info.key,
+                    vault_info.key,
+                    charity_info.key,
+                    vault_info.key,
+                    &[],
+                    pending.amount,
+                )?,
+                &[
+                    vault_info.clone(),
+                    charity_info.clone(),
+                    token_program_info.clone(),
+                ],
+                &[vault_seeds],
+            )?;
+        }
+        DonationTarget::Burn => {
+            invoke_signed(
+                &token_instruction::burn(
+                    token_program_info.key,
+                    vault_info.key,
+                    reward_mint_info.key,
+                    vault_info.key,
+                    &[],
+                    pending.amount,
+                )?,
+                &[
+                    vault_info.clone(),
+                    reward_mint_info.clone(),
+                    token_program_info.clone(),
+                ],
+                &[vault_seeds],
+            )?;
+        }
+    }
+
+    close_pda_account(pending_info, farmer_info)?;
+    release_outstanding_rewards(pool_info, &mut pool_data, pending.amount)?;
+
+    RewardPoolEvent::RewardDonated {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        task_id: pending.task_id,
+        amount: pending.amount,
+        target,
+    }
+    .emit();
+
+    msg!("Donated {} tokens", pending.amount);
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{key_history::record_key_rotation, treasury::load_pool, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_authority_recovery_address_with_program_id,
+        find_instruction_counters_address_with_program_id, AUTHORITY_RECOVERY_SEED,
+    },
+    state::{AuthorityRecovery, InstructionCounters, Sequenced},
+    MIN_INACTIVITY_TIMEOUT,
+};
+
+// Designating who takes over the pool if its authority goes silent
+pub(super) fn process_set_recovery_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    recovery_authority: Pubkey,
+    inactivity_timeout: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let recovery_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let enabled = recovery_authority != Pubkey::default();
+    if enabled && inactivity_timeout < MIN_INACTIVITY_TIMEOUT {
+        return Err(RewardPoolError::InvalidInactivityTimeout.into());
+    }
+
+    let (expected_recovery, bump_seed) =
+        find_authority_recovery_address_with_program_id(pool_info.key, program_id);
+    if *recovery_info.key != expected_recovery {
+        return Err(RewardPoolError::InvalidAuthorityRecoveryAccount.into());
+    }
+
+    let mut recovery = if recovery_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            recovery_info,
+            system_program_info,
+            program_id,
+            AuthorityRecovery::LEN,
+            &[
+                AUTHORITY_RECOVERY_SEED,
+                pool_info.key.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        AuthorityRecovery {
+            pool: *pool_info.key,
+            recovery_authority: Pubkey::default(),
+            inactivity_timeout: 0,
+            configured_at: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_authority_recovery(program_id, pool_info.key, recovery_info)?
+    };
+
+    let inactivity_timeout = if enabled { inactivity_timeout } else { 0 };
+    recovery.recovery_authority = recovery_authority;
+    recovery.inactivity_timeout = inactivity_timeout;
+    recovery.configured_at = Clock::get()?.unix_timestamp;
+    recovery.save(recovery_info)?;
+
+    RewardPoolEvent::RecoveryAuthoritySet {
+        pool: *pool_info.key,
+        recovery_authority,
+        inactivity_timeout,
+    }
+    .emit();
+
+    msg!(
+        "Recovery authority set to {} after {} seconds of inactivity",
+        recovery_authority,
+        inactivity_timeout
+    );
+    Ok(())
+}
+
+// Taking over a pool whose authority has gone silent
+pub(super) fn process_claim_dormant_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let recovery_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let recovery_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let counters_info = next_account_info(account_info_iter)?;
+    let history_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !recovery_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidRecoveryAuthority.into());
+    }
+
+    let mut pool_data = load_pool(program_id, pool_info)?;
+
+    let (expected_recovery, _) =
+        find_authority_recovery_address_with_program_id(pool_info.key, program_id);
+    if *recovery_info.key != expected_recovery || recovery_info.data_is_empty() {
+        return Err(RewardPoolError::InvalidAuthorityRecoveryAccount.into());
+    }
+    let mut recovery = load_authority_recovery(program_id, pool_info.key, recovery_info)?;
+    if !recovery.is_enabled() || recovery.recovery_authority != *recovery_authority_info.key {
+        return Err(RewardPoolError::InvalidRecoveryAuthority.into());
+    }
+
+    let (expected_counters, _) =
+        find_instruction_counters_address_with_program_id(pool_info.key, program_id);
+    if *counters_info.key != expected_counters {
+        return Err(RewardPoolError::InvalidCountersAccount.into());
+    }
+    // Counters of older pools hold the authority's actions from before the
+    // pool recorded them
+    let counted_action_at = if counters_info.data_is_empty() {
+        0
+    } else {
+        if counters_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        InstructionCounters::unpack(&counters_info.data.borrow())?.last_authority_action_at
+    };
+    let last_authority_action_at = pool_data.last_authority_action_at.max(counted_action_at);
+
+    let now = Clock::get()?.unix_timestamp;
+    if now < recovery.claimable_at(last_authority_action_at) {
+        return Err(RewardPoolError::AuthorityNotDormant.into());
+    }
+
+    // Take over the pool and disarm the switch for the new authority
+    let previous_authority = pool_data.platform_authority;
+    pool_data.platform_authority = *recovery_authority_info.key;
+    pool_data.save(pool_info)?;
+
+    let last_active_at = last_authority_action_at.max(recovery.configured_at);
+    recovery.recovery_authority = Pubkey::default();
+    recovery.inactivity_timeout = 0;
+    recovery.configured_at = now;
+    recovery.save(recovery_info)?;
+
+    // No reason document backs a takeover, so its reason hash stays zeroed
+    record_key_rotation(
+        program_id,
+        recovery_authority_info,
+        pool_info.key,
+        history_info,
+        system_program_info,
+        previous_authority,
+        *recovery_authority_info.key,
+        [0; 32],
+    )?;
+
+    RewardPoolEvent::DormantAuthorityClaimed {
+        pool: *pool_info.key,
+        previous_authority,
+        new_authority: *recovery_authority_info.key,
+        last_active_at,
+    }
+    .emit();
+
+    msg!(
+        "Platform authority {} inactive since {}, replaced by {}",
+        previous_authority,
+        last_active_at,
+        recovery_authority_info.key
+    );
+    Ok(())
+}
+
+fn load_authority_recovery(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    recovery_info: &AccountInfo,
+) -> Result<AuthorityRecovery, ProgramError> {
+    if recovery_info.owner != program_id {
+        return Err(RewardPoolError::InvalidAuthorityRecoveryAccount.into());
+    }
+    let recovery = AuthorityRecovery::try_from_slice(&recovery_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidAuthorityRecoveryAccount)?;
+    let expected_recovery = Pubkey::create_program_address(
+        &[
+            AUTHORITY_RECOVERY_SEED,
+            pool.as_ref(),
+            &[recovery.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidAuthorityRecoveryAccount)?;
+    if recovery.pool != *pool || *recovery_info.key != expected_recovery {
+        return Err(RewardPoolError::InvalidAuthorityRecoveryAccount.into());
+    }
+    Ok(recovery)
+}
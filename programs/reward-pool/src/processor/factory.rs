@@ -0,0 +1,428 @@
+use borsh::BorshDeserialize;
+use solana_loader_v3_interface::get_program_data_address;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use solana_system_interface::instruction as system_instruction;
+use spl_token::state::Account as TokenAccount;
+
+use super::{
+    program_treasury::{create_treasury_vault, transfer_from_platform_treasury},
+    utils::create_pda_account,
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_factory_pool_address_with_program_id, find_factory_record_address_with_program_id,
+        find_protocol_config_address_with_program_id, FACTORY_POOL_SEED, FACTORY_RECORD_SEED,
+        PROTOCOL_CONFIG_SEED,
+    },
+    state::{FactoryPool, PoolAddressKind, ProtocolConfig, RewardPool, Sequenced},
+    MAX_PLATFORM_FEE_PERCENTAGE, MAX_PROTOCOL_FEE_SHARE_BPS,
+};
+
+// Tag of the `ProgramData` variant of the upgradeable loader's state and
+// the offset of its upgrade authority, after the tag, the deployment slot
+// and the authority's option tag
+const PROGRAM_DATA_TAG: u32 = 3;
+const PROGRAM_DATA_AUTHORITY_OFFSET: usize = 4 + 8 + 1;
+
+// Setting the protocol-wide configuration of the pool factory
+pub(super) fn process_set_protocol_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    admin: Pubkey,
+    treasury: Pubkey,
+    creation_fee: u64,
+    protocol_fee_share_bps: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let program_data_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !signer_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if protocol_fee_share_bps > MAX_PROTOCOL_FEE_SHARE_BPS {
+        return Err(RewardPoolError::InvalidProtocolConfig.into());
+    }
+
+    let (expected_config, bump_seed) = find_protocol_config_address_with_program_id(program_id);
+    if *config_info.key != expected_config {
+        return Err(RewardPoolError::InvalidProtocolConfigAccount.into());
+    }
+
+    // The program's upgrade authority creates the configuration, its admin
+    // changes it afterwards
+    let mut config = if config_info.data_is_empty() {
+        if upgrade_authority(program_id, program_data_info)? != Some(*signer_info.key) {
+            return Err(RewardPoolError::InvalidAuthority.into());
+        }
+        create_pda_account(
+            signer_info,
+            config_info,
+            system_program_info,
+            program_id,
+            ProtocolConfig::LEN,
+            &[PROTOCOL_CONFIG_SEED, &[bump_seed]],
+        )?;
+        ProtocolConfig {
+            admin,
+            treasury,
+            creation_fee,
+            protocol_fee_share_bps,
+            pool_count: 0,
+            total_creation_fees: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        let config = load_protocol_config(program_id, config_info)?;
+        if config.admin != *signer_info.key {
+            return Err(RewardPoolError::InvalidAuthority.into());
+        }
+        config
+    };
+
+    config.admin = admin;
+    config.treasury = treasury;
+    config.creation_fee = creation_fee;
+    config.protocol_fee_share_bps = protocol_fee_share_bps;
+    config.save(config_info)?;
+
+    RewardPoolEvent::ProtocolConfigSet {
+        admin,
+        treasury,
+        creation_fee,
+        protocol_fee_share_bps,
+    }
+    .emit();
+
+    msg!(
+        "Protocol config set: creation fee {} lamports, fee share {} bps",
+        creation_fee,
+        protocol_fee_share_bps
+    );
+    Ok(())
+}
+
+// Creating a pool through the factory
+pub(super) fn process_create_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    seed: u64,
+    platform_fee_percentage: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let creator_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let platform_treasury_info = next_account_info(account_info_iter)?;
+    let factory_record_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let protocol_treasury_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !creator_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if platform_fee_percentage > MAX_PLATFORM_FEE_PERCENTAGE {
+        return Err(RewardPoolError::InvalidPlatformFee.into());
+    }
+
+    let mut config = load_protocol_config(program_id, config_info)?;
+    if *protocol_treasury_info.key != config.treasury {
+        return Err(RewardPoolError::InvalidProtocolTreasury.into());
+    }
+
+    let (expected_pool, pool_bump_seed) = find_factory_pool_address_with_program_id(
+        creator_info.key,
+        reward_mint_info.key,
+        seed,
+        program_id,
+    );
+    if *pool_info.key != expected_pool {
+        return Err(RewardPoolError::InvalidFactoryPoolAccount.into());
+    }
+    if !pool_info.data_is_empty() {
+        return Err(RewardPoolError::PoolAlreadyInitialized.into());
+    }
+
+    let (expected_record, bump_seed) =
+        find_factory_record_address_with_program_id(pool_info.key, program_id);
+    if *factory_record_info.key != expected_record {
+        return Err(RewardPoolError::InvalidFactoryRecordAccount.into());
+    }
+
+    if config.creation_fee > 0 {
+        invoke(
+            &system_instruction::transfer(
+                creator_info.key,
+                protocol_treasury_info.key,
+                config.creation_fee,
+            ),
+            &[
+                creator_info.clone(),
+                protocol_treasury_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    }
+
+    create_pda_account(
+        creator_info,
+        pool_info,
+        system_program_info,
+        program_id,
+        RewardPool::LEN,
+        &[
+            FACTORY_POOL_SEED,
+            creator_info.key.as_ref(),
+            reward_mint_info.key.as_ref(),
+            &seed.to_le_bytes(),
+            &[pool_bump_seed],
+        ],
+    )?;
+    create_treasury_vault(
+        program_id,
+        creator_info,
+        pool_info.key,
+        platform_treasury_info,
+        reward_mint_info,
+        token_program_info,
+        system_program_info,
+    )?;
+    let mut pool_data = RewardPool {
+        platform_authority: *creator_info.key,
+        reward_mint: *reward_mint_info.key,
+        platform_treasury: *platform_treasury_info.key,
+        platform_fee_percentage,
+        total_rewards_distributed: 0,
+        total_platform_fees_collected: 0,
+        is_paused: false,
+        bump_seed: pool_bump_seed,
+        address_kind: PoolAddressKind::Factory,
+        address_creator: *creator_info.key,
+        address_seed: seed,
+        outstanding_rewards: 0,
+        last_authority_action_at: 0,
+        sequence: 0,
+    };
+    pool_data.save(pool_info)?;
+
+    create_pda_account(
+        creator_info,
+        factory_record_info,
+        system_program_info,
+        program_id,
+        FactoryPool::LEN,
+        &[FACTORY_RECORD_SEED, pool_info.key.as_ref(), &[bump_seed]],
+    )?;
+    let mut record = FactoryPool {
+        pool: *pool_info.key,
+        creator: *creator_info.key,
+        seed,
+        protocol_fee_share_bps: config.protocol_fee_share_bps,
+        created_at: Clock::get()?.unix_timestamp,
+        total_protocol_fees: 0,
+        pool_bump_seed,
+        bump_seed,
+        sequence: 0,
+    };
+    record.save(factory_record_info)?;
+
+    config.pool_count = config
+        .pool_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    config.total_creation_fees = config
+        .total_creation_fees
+        .checked_add(config.creation_fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    config.save(config_info)?;
+
+    RewardPoolEvent::PoolInitialized {
+        pool: *pool_info.key,
+        platform_authority: pool_data.platform_authority,
+        reward_mint: pool_data.reward_mint,
+        platform_treasury: pool_data.platform_treasury,
+        platform_fee_percentage,
+    }
+    .emit();
+    RewardPoolEvent::FactoryPoolCreated {
+        pool: *pool_info.key,
+        creator: *creator_info.key,
+        reward_mint: *reward_mint_info.key,
+        seed,
+        creation_fee: config.creation_fee,
+        protocol_fee_share_bps: record.protocol_fee_share_bps,
+    }
+    .emit();
+
+    msg!("Pool {} created by {}", pool_info.key, creator_info.key);
+    Ok(())
+}
+
+/// Moves the protocol's share of `platform_fee` from the platform treasury
+/// to `protocol_fee_info`, a token account of the protocol treasury, for
+/// pools created through the factory. Other pools have no factory record
+/// and pay nothing; their config and fee accounts are ignored. Returns the
+/// protocol fee.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn pay_protocol_fee<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    reward_mint: &Pubkey,
+    factory_record_info: &AccountInfo<'a>,
+    config_info: &AccountInfo<'a>,
+    protocol_fee_info: &AccountInfo<'a>,
+    platform_treasury_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    platform_fee: u64,
+) -> Result<u64, ProgramError> {
+    let (expected_record, _) = find_factory_record_address_with_program_id(pool, program_id);
+    if *factory_record_info.key != expected_record {
+        return Err(RewardPoolError::InvalidFactoryRecordAccount.into());
+    }
+    if factory_record_info.data_is_empty() {
+        return Ok(0);
+    }
+    let mut record = load_factory_record(program_id, pool, factory_record_info)?;
+
+    let protocol_fee = record.protocol_fee(platform_fee);
+    if protocol_fee == 0 {
+        return Ok(0);
+    }
+
+    let config = load_protocol_config(program_id, config_info)?;
+    let fee_account = TokenAccount::unpack(&protocol_fee_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidProtocolTreasury)?;
+    if protocol_fee_info.owner != token_program_info.key
+        || fee_account.owner != config.treasury
+        || fee_account.mint != *reward_mint
+    {
+        return Err(RewardPoolError::InvalidProtocolTreasury.into());
+    }
+
+    transfer_from_platform_treasury(
+        program_id,
+        pool,
+        platform_treasury_info,
+        protocol_fee_info,
+        token_program_info,
+        protocol_fee,
+    )?;
+
+    record.total_protocol_fees = record
+        .total_protocol_fees
+        .checked_add(protocol_fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    record.save(factory_record_info)?;
+
+    RewardPoolEvent::ProtocolFeePaid {
+        pool: *pool,
+        platform_fee,
+        protocol_fee,
+    }
+    .emit();
+
+    Ok(protocol_fee)
+}
+
+/// Upgrade authority recorded in the program's `ProgramData` account, `None`
+/// once the program is immutable
+fn upgrade_authority(
+    program_id: &Pubkey,
+    program_data_info: &AccountInfo,
+) -> Result<Option<Pubkey>, ProgramError> {
+    if *program_data_info.key != get_program_data_address(program_id) {
+        return Err(RewardPoolError::InvalidProgramDataAccount.into());
+    }
+    let data = program_data_info.data.borrow();
+    let authority = data
+        .get(..PROGRAM_DATA_AUTHORITY_OFFSET + 32)
+        .filter(|metadata| metadata[..4] == PROGRAM_DATA_TAG.to_le_bytes())
+        .ok_or(RewardPoolError::InvalidProgramDataAccount)?;
+    Ok(
+        (authority[PROGRAM_DATA_AUTHORITY_OFFSET - 1] == 1).then(|| {
+            Pubkey::new_from_array(
+                authority[PROGRAM_DATA_AUTHORITY_OFFSET..]
+                    .try_into()
+                    .expect("32 bytes"),
+            )
+        }),
+    )
+}
+
+fn load_protocol_config(
+    program_id: &Pubkey,
+    config_info: &AccountInfo,
+) -> Result<ProtocolConfig, ProgramError> {
+    if config_info.owner != program_id {
+        return Err(RewardPoolError::InvalidProtocolConfigAccount.into());
+    }
+    let config = ProtocolConfig::try_from_slice(&config_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidProtocolConfigAccount)?;
+    let expected_config =
+        Pubkey::create_program_address(&[PROTOCOL_CONFIG_SEED, &[config.bump_seed]], program_id)
+            .map_err(|_| RewardPoolError::InvalidProtocolConfigAccount)?;
+    if *config_info.key != expected_config {
+        return Err(RewardPoolError::InvalidProtocolConfigAccount.into());
+    }
+    Ok(config)
+}
+
+/// Returns the factory record of the pool, `None` for pools created outside
+/// the factory
+pub(super) fn read_factory_record(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    factory_record_info: &AccountInfo,
+) -> Result<Option<FactoryPool>, ProgramError> {
+    let (expected_record, _) = find_factory_record_address_with_program_id(pool, program_id);
+    if *factory_record_info.key != expected_record {
+        return Err(RewardPoolError::InvalidFactoryRecordAccount.into());
+    }
+    if factory_record_info.data_is_empty() {
+        return Ok(None);
+    }
+    load_factory_record(program_id, pool, factory_record_info).map(Some)
+}
+
+fn load_factory_record(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    factory_record_info: &AccountInfo,
+) -> Result<FactoryPool, ProgramError> {
+    if factory_record_info.owner != program_id {
+        return Err(RewardPoolError::InvalidFactoryRecordAccount.into());
+    }
+    let record = FactoryPool::try_from_slice(&factory_record_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidFactoryRecordAccount)?;
+    let expected_record = Pubkey::create_program_address(
+        &[FACTORY_RECORD_SEED, pool.as_ref(), &[record.bump_seed]],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidFactoryRecordAccount)?;
+    if record.pool != *pool || *factory_record_info.key != expected_record {
+        return Err(RewardPoolError::InvalidFactoryRecordAccount.into());
+    }
+    Ok(record)
+}
@@ -0,0 +1,584 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::{instruction as token_instruction, state::Account as TokenAccount};
+
+use super::{
+    treasury::load_pool,
+    utils::{close_pda_account, create_pda_account},
+    whitelist::{
+        check_payout_address, check_withdrawal_destination, load_payout_address, load_whitelist,
+    },
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_farmer_state_address_with_program_id, find_payout_address_address_with_program_id,
+        find_withdrawal_history_address_with_program_id,
+        find_withdrawal_whitelist_address_with_program_id, FARMER_STATE_SEED,
+        WITHDRAWAL_HISTORY_SEED,
+    },
+    state::{FarmerState, Sequenced, WithdrawalHistory, WithdrawalRecord},
+    COSIGNER_REMOVAL_DELAY,
+};
+
+// Closing a leaving farmer's accounts
+pub(super) fn process_close_farmer_accounts(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let reward_account_info = next_account_info(account_info_iter)?;
+    let farmer_state_info = next_account_info(account_info_iter)?;
+    let rent_recipient_info = next_account_info(account_info_iter)?;
+    let whitelist_info = next_account_info(account_info_iter)?;
+    let payout_address_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+
+    if *token_program_info.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *reward_account_info.key
+        != get_associated_token_address(farmer_info.key, &pool_data.reward_mint)
+    {
+        return Err(RewardPoolError::DestinationNotOwnedByFarmer.into());
+    }
+
+    let (expected_farmer_state, _) =
+        find_farmer_state_address_with_program_id(pool_info.key, farmer_info.key, program_id);
+    if *farmer_state_info.key != expected_farmer_state {
+        return Err(RewardPoolError::InvalidFarmerStateAccount.into());
+    }
+
+    let (expected_whitelist, _) = find_withdrawal_whitelist_address_with_program_id(
+        pool_info.key,
+        farmer_info.key,
+        program_id,
+    );
+    if *whitelist_info.key != expected_whitelist {
+        return Err(RewardPoolError::InvalidWhitelistAccount.into());
+    }
+
+    let (expected_payout_address, _) =
+        find_payout_address_address_with_program_id(pool_info.key, farmer_info.key, program_id);
+    if *payout_address_info.key != expected_payout_address {
+        return Err(RewardPoolError::InvalidPayoutAddressAccount.into());
+    }
+
+    // Rent goes back to whoever funded the farmer's accounts
+    let payer = if farmer_state_info.data_is_empty() {
+        *farmer_info.key
+    } else {
+        load_farmer_state(program_id, farmer_state_info)?.payer
+    };
+    if *rent_recipient_info.key != payer {
+        return Err(RewardPoolError::InvalidRentRecipient.into());
+    }
+
+    // Protections only go away once they no longer restrict anything
+    let now = Clock::get()?.unix_timestamp;
+    if !whitelist_info.data_is_empty()
+        && load_whitelist(program_id, whitelist_info)?.entry_count > 0
+    {
+        return Err(RewardPoolError::FarmerAccountInUse.into());
+    }
+    if !payout_address_info.data_is_empty() {
+        let registration = load_payout_address(program_id, payout_address_info)?;
+        if registration.effective(now).is_some() || registration.pending_active_at > now {
+            return Err(RewardPoolError::FarmerAccountInUse.into());
+        }
+    }
+
+    let mut account_count: u32 = 0;
+
+    if !reward_account_info.data_is_empty() {
+        let reward_account = TokenAccount::unpack(&reward_account_info.data.borrow())?;
+        if reward_account.amount > 0 {
+            return Err(RewardPoolError::RewardAccountNotEmpty.into());
+        }
+
+        invoke(
+            &token_instruction::close_account(
+                token_program_info.key,
+                reward_account_info.key,
+                rent_recipient_info.key,
+                farmer_info.key,
+                &[],
+            )?,
+            &[
+                reward_account_info.clone(),
+                rent_recipient_info.clone(),
+                farmer_info.clone(),
+                token_program_info.clone(),
+            ],
+        )?;
+        account_count += 1;
+    }
+
+    for (account_info, recipient_info) in [
+        (farmer_state_info, rent_recipient_info),
+        (whitelist_info, farmer_info),
+        (payout_address_info, farmer_info),
+    ] {
+        if !account_info.data_is_empty() {
+            close_pda_account(account_info, recipient_info)?;
+            account_count += 1;
+        }
+    }
+
+    RewardPoolEvent::FarmerAccountsClosed {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        rent_recipient: *rent_recipient_info.key,
+        account_count,
+    }
+    .emit();
+
+    msg!("Closed {} farmer accounts", account_count);
+    Ok(())
+}
+
+// Turning auto-forward on or off
+pub(super) fn process_set_auto_forward(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    enabled: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let farmer_state_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let whitelist_info = next_account_info(account_info_iter)?;
+    let payout_address_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+
+    let destination = if enabled {
+        if *destination_info.owner != spl_token::id() {
+            return Err(RewardPoolError::InvalidForwardDestination.into());
+        }
+        let destination_account = TokenAccount::unpack(&destination_info.data.borrow())?;
+        if destination_account.mint != pool_data.reward_mint {
+            return Err(RewardPoolError::InvalidForwardDestination.into());
+        }
+
+        // Forwarded rewards never sit in the reward account, so the
+        // destination has to pass the same checks as a withdrawal
+        check_withdrawal_destination(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            whitelist_info,
+            destination_info.key,
+        )?;
+        check_payout_address(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            payout_address_info,
+            destination_info,
+        )?;
+
+        Some(*destination_info.key)
+    } else {
+        None
+    };
+
+    update_farmer_state(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        farmer_state_info,
+        farmer_info,
+        system_program_info,
+        |farmer_state| {
+            farmer_state.auto_forward = enabled;
+            farmer_state.forward_destination = destination.unwrap_or_default();
+        },
+    )?;
+
+    RewardPoolEvent::AutoForwardSet {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        destination,
+    }
+    .emit();
+
+    match destination {
+        Some(destination) => msg!("Auto-forwarding rewards to {}", destination),
+        None => msg!("Auto-forward disabled"),
+    }
+    Ok(())
+}
+
+// Setting the farmer's auto-claim threshold
+pub(super) fn process_set_auto_claim(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    threshold: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let farmer_state_info = next_account_info(account_info_iter)?;
+    let payout_address_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    load_pool(program_id, pool_info)?;
+
+    // Anyone can crank the claim, so it may only ever pay the payout address
+    if threshold > 0 {
+        let (expected_payout_address, _) =
+            find_payout_address_address_with_program_id(pool_info.key, farmer_info.key, program_id);
+        if *payout_address_info.key != expected_payout_address {
+            return Err(RewardPoolError::InvalidPayoutAddressAccount.into());
+        }
+        if payout_address_info.data_is_empty()
+            || load_payout_address(program_id, payout_address_info)?
+                .effective(Clock::get()?.unix_timestamp)
+                .is_none()
+        {
+            return Err(RewardPoolError::AutoClaimRequiresPayoutAddress.into());
+        }
+    }
+
+    update_farmer_state(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        farmer_state_info,
+        farmer_info,
+        system_program_info,
+        |farmer_state| farmer_state.auto_claim_threshold = threshold,
+    )?;
+
+    RewardPoolEvent::AutoClaimSet {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        threshold,
+    }
+    .emit();
+
+    if threshold > 0 {
+        msg!("Auto-claiming pending rewards from {} tokens", threshold);
+    } else {
+        msg!("Auto-claim disabled");
+    }
+    Ok(())
+}
+
+// Setting or removing the farmer's withdrawal cosigner
+pub(super) fn process_set_withdrawal_cosigner(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    cosigner: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let farmer_state_info = next_account_info(account_info_iter)?;
+    let cosigner_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    load_pool(program_id, pool_info)?;
+
+    let (expected_farmer_state, _) =
+        find_farmer_state_address_with_program_id(pool_info.key, farmer_info.key, program_id);
+    if *farmer_state_info.key != expected_farmer_state {
+        return Err(RewardPoolError::InvalidFarmerStateAccount.into());
+    }
+    let farmer_state = if farmer_state_info.data_is_empty() {
+        None
+    } else {
+        Some(load_farmer_state(program_id, farmer_state_info)?)
+    };
+
+    let now = Clock::get()?.unix_timestamp;
+    let current = farmer_state
+        .as_ref()
+        .and_then(|farmer_state| farmer_state.cosigner(now));
+    let signed_by = |key: &Pubkey| cosigner_info.is_signer && cosigner_info.key == key;
+
+    let (withdrawal_cosigner, cosigner_removal_at) = if cosigner == Pubkey::default() {
+        let current = current.ok_or(RewardPoolError::NoWithdrawalCosigner)?;
+        if signed_by(&current) {
+            (Pubkey::default(), 0)
+        } else {
+            // Without the cosigner's signature the removal waits, so a
+            // stolen farmer key cannot drop it before the farmer notices
+            let removal_at = match farmer_state.map_or(0, |state| state.cosigner_removal_at) {
+                0 => now.saturating_add(COSIGNER_REMOVAL_DELAY),
+                removal_at => removal_at,
+            };
+            (current, removal_at)
+        }
+    } else {
+        // The new cosigner signs, so a mistyped key cannot lock the farmer out
+        if cosigner == *farmer_info.key || !signed_by(&cosigner) {
+            return Err(RewardPoolError::InvalidWithdrawalCosigner.into());
+        }
+        // Setting the current cosigner again cancels a pending removal
+        if current.is_some_and(|current| current != cosigner) {
+            return Err(RewardPoolError::WithdrawalCosignerAlreadySet.into());
+        }
+        (cosigner, 0)
+    };
+
+    update_farmer_state(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        farmer_state_info,
+        farmer_info,
+        system_program_info,
+        |farmer_state| {
+            farmer_state.withdrawal_cosigner = withdrawal_cosigner;
+            farmer_state.cosigner_removal_at = cosigner_removal_at;
+        },
+    )?;
+
+    RewardPoolEvent::WithdrawalCosignerSet {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        cosigner: withdrawal_cosigner,
+        removal_at: cosigner_removal_at,
+    }
+    .emit();
+
+    if cosigner_removal_at > 0 {
+        msg!(
+            "Withdrawal cosigner {} removed at {}",
+            withdrawal_cosigner,
+            cosigner_removal_at
+        );
+    } else {
+        msg!("Withdrawal cosigner set to {}", withdrawal_cosigner);
+    }
+    Ok(())
+}
+
+/// Fails unless `cosigner_info` signed as the farmer's withdrawal cosigner,
+/// when they have one in effect
+pub(super) fn check_withdrawal_cosigner(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    farmer_state_info: &AccountInfo,
+    cosigner_info: &AccountInfo,
+) -> ProgramResult {
+    let (expected_farmer_state, _) =
+        find_farmer_state_address_with_program_id(pool, farmer, program_id);
+    if *farmer_state_info.key != expected_farmer_state {
+        return Err(RewardPoolError::InvalidFarmerStateAccount.into());
+    }
+    if farmer_state_info.data_is_empty() {
+        return Ok(());
+    }
+
+    let farmer_state = load_farmer_state(program_id, farmer_state_info)?;
+    match farmer_state.cosigner(Clock::get()?.unix_timestamp) {
+        Some(cosigner) if !cosigner_info.is_signer || *cosigner_info.key != cosigner => {
+            Err(RewardPoolError::MissingWithdrawalCosigner.into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fails unless `destination_info` may receive a claim of the farmer's
+/// pending rewards: their auto-forward destination while auto-forward is
+/// on, their own account of `reward_mint` or a whitelisted one otherwise,
+/// and in either case an account their whitelist allows and their payout
+/// address owns once one is in effect
+#[allow(clippy::too_many_arguments)]
+pub(super) fn check_claim_destination(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    reward_mint: &Pubkey,
+    farmer_state_info: &AccountInfo,
+    whitelist_info: &AccountInfo,
+    payout_address_info: &AccountInfo,
+    destination_info: &AccountInfo,
+) -> ProgramResult {
+    let (expected_farmer_state, _) =
+        find_farmer_state_address_with_program_id(pool, farmer, program_id);
+    if *farmer_state_info.key != expected_farmer_state {
+        return Err(RewardPoolError::InvalidFarmerStateAccount.into());
+    }
+    let whitelisted = check_withdrawal_destination(
+        program_id,
+        pool,
+        farmer,
+        whitelist_info,
+        destination_info.key,
+    )?;
+    let locked_to_payout_address = check_payout_address(
+        program_id,
+        pool,
+        farmer,
+        payout_address_info,
+        destination_info,
+    )?;
+    if !farmer_state_info.data_is_empty() {
+        let farmer_state = load_farmer_state(program_id, farmer_state_info)?;
+        if farmer_state.auto_forward {
+            if *destination_info.key != farmer_state.forward_destination {
+                return Err(RewardPoolError::ForwardDestinationMismatch.into());
+            }
+            return Ok(());
+        }
+    }
+    if whitelisted || locked_to_payout_address {
+        return Ok(());
+    }
+
+    let destination = TokenAccount::unpack(&destination_info.data.borrow())?;
+    if destination.owner != *farmer || destination.mint != *reward_mint {
+        return Err(RewardPoolError::DestinationNotOwnedByFarmer.into());
+    }
+    Ok(())
+}
+
+/// Applies `update` to a farmer's state account, creating it at the expense
+/// of `payer` on the farmer's first activity in the pool. Returns the
+/// updated state.
+pub(super) fn update_farmer_state<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    farmer_state_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    update: impl FnOnce(&mut FarmerState),
+) -> Result<FarmerState, ProgramError> {
+    let (expected_farmer_state, bump_seed) =
+        find_farmer_state_address_with_program_id(pool, farmer, program_id);
+    if *farmer_state_info.key != expected_farmer_state {
+        return Err(RewardPoolError::InvalidFarmerStateAccount.into());
+    }
+
+    let mut farmer_state = if farmer_state_info.data_is_empty() {
+        create_pda_account(
+            payer_info,
+            farmer_state_info,
+            system_program_info,
+            program_id,
+            FarmerState::LEN,
+            &[
+                FARMER_STATE_SEED,
+                pool.as_ref(),
+                farmer.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        FarmerState::new(*pool, *farmer, *payer_info.key, bump_seed)
+    } else {
+        load_farmer_state(program_id, farmer_state_info)?
+    };
+
+    update(&mut farmer_state);
+    farmer_state.save(farmer_state_info)?;
+    Ok(farmer_state)
+}
+
+/// Reads a farmer's state account; the caller checks its address
+pub(super) fn load_farmer_state(
+    program_id: &Pubkey,
+    farmer_state_info: &AccountInfo,
+) -> Result<FarmerState, ProgramError> {
+    if farmer_state_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    FarmerState::try_from_slice(&farmer_state_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidFarmerStateAccount.into())
+}
+
+/// Adds a withdrawal to the farmer's history, creating the account at the
+/// expense of `payer` on the farmer's first withdrawal
+pub(super) fn record_withdrawal<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    history_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    record: WithdrawalRecord,
+) -> ProgramResult {
+    let (expected_history, bump_seed) =
+        find_withdrawal_history_address_with_program_id(pool, farmer, program_id);
+    if *history_info.key != expected_history {
+        return Err(RewardPoolError::InvalidWithdrawalHistoryAccount.into());
+    }
+
+    let mut history = if history_info.data_is_empty() {
+        create_pda_account(
+            payer_info,
+            history_info,
+            system_program_info,
+            program_id,
+            WithdrawalHistory::LEN,
+            &[
+                WITHDRAWAL_HISTORY_SEED,
+                pool.as_ref(),
+                farmer.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        WithdrawalHistory::new(*pool, *farmer, bump_seed)
+    } else {
+        load_withdrawal_history(program_id, history_info)?
+    };
+
+    history.push(record);
+    history.save(history_info)
+}
+
+/// Reads a farmer's withdrawal history; the caller checks its address
+pub(super) fn load_withdrawal_history(
+    program_id: &Pubkey,
+    history_info: &AccountInfo,
+) -> Result<WithdrawalHistory, ProgramError> {
+    if history_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    WithdrawalHistory::try_from_slice(&history_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidWithdrawalHistoryAccount.into())
+}
@@ -0,0 +1,439 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    hash::hash,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{
+    farmer::load_farmer_state,
+    pending::leave_pending_tombstone,
+    treasury::load_pool,
+    utils::{close_pda_account, create_pda_account},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_farmer_recovery_address_with_program_id, find_farmer_state_address_with_program_id,
+        find_pending_reward_address_with_program_id, FARMER_RECOVERY_SEED, FARMER_STATE_SEED,
+        PENDING_REWARD_SEED,
+    },
+    state::{FarmerRecovery, FarmerState, PendingReward, Sequenced},
+    FARMER_RECOVERY_DELAY, MAX_CLAIM_BATCH,
+};
+
+// Naming the guardians who can recover a farmer's rewards
+pub(super) fn process_set_recovery_guardians(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    guardians: Vec<Pubkey>,
+    threshold: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let recovery_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    load_pool(program_id, pool_info)?;
+
+    let distinct = guardians
+        .iter()
+        .enumerate()
+        .all(|(index, guardian)| !guardians[..index].contains(guardian));
+    let threshold_fits = if guardians.is_empty() {
+        threshold == 0
+    } else {
+        (1..=guardians.len()).contains(&(threshold as usize))
+    };
+    if guardians.len() > FarmerRecovery::MAX_GUARDIANS
+        || !distinct
+        || !threshold_fits
+        || guardians.contains(farmer_info.key)
+    {
+        return Err(RewardPoolError::InvalidRecoveryGuardians.into());
+    }
+
+    let (expected_recovery, bump_seed) =
+        find_farmer_recovery_address_with_program_id(pool_info.key, farmer_info.key, program_id);
+    if *recovery_info.key != expected_recovery {
+        return Err(RewardPoolError::InvalidFarmerRecoveryAccount.into());
+    }
+
+    let mut recovery = if recovery_info.data_is_empty() {
+        create_pda_account(
+            farmer_info,
+            recovery_info,
+            system_program_info,
+            program_id,
+            FarmerRecovery::LEN,
+            &[
+                FARMER_RECOVERY_SEED,
+                pool_info.key.as_ref(),
+                farmer_info.key.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        FarmerRecovery {
+            pool: *pool_info.key,
+            farmer: *farmer_info.key,
+            guardian_count: 0,
+            guardians: [Pubkey::default(); FarmerRecovery::MAX_GUARDIANS],
+            threshold: 0,
+            new_wallet: Pubkey::default(),
+            initiated_at: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_farmer_recovery(program_id, pool_info.key, recovery_info)?
+    };
+
+    // New guardians start from a clean slate
+    recovery.guardian_count = guardians.len() as u8;
+    recovery.guardians = [Pubkey::default(); FarmerRecovery::MAX_GUARDIANS];
+    recovery.guardians[..guardians.len()].copy_from_slice(&guardians);
+    recovery.threshold = threshold;
+    recovery.new_wallet = Pubkey::default();
+    recovery.initiated_at = 0;
+    recovery.save(recovery_info)?;
+
+    RewardPoolEvent::RecoveryGuardiansSet {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        guardians,
+        threshold,
+    }
+    .emit();
+
+    msg!(
+        "Recovery guardians set: {} of {}",
+        threshold,
+        recovery.guardian_count
+    );
+    Ok(())
+}
+
+// Guardians starting the recovery of a farmer's rewards
+pub(super) fn process_initiate_farmer_recovery(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_wallet: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let guardian_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let recovery_info = next_account_info(account_info_iter)?;
+    let _system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let further_guardians = account_info_iter.as_slice();
+
+    // Validations
+    if !guardian_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    load_pool(program_id, pool_info)?;
+
+    if recovery_info.data_is_empty() {
+        return Err(RewardPoolError::FarmerRecoveryDisabled.into());
+    }
+    let mut recovery = load_farmer_recovery(program_id, pool_info.key, recovery_info)?;
+    if recovery.threshold == 0 {
+        return Err(RewardPoolError::FarmerRecoveryDisabled.into());
+    }
+    if new_wallet == Pubkey::default() || new_wallet == recovery.farmer {
+        return Err(RewardPoolError::InvalidRecoveryWallet.into());
+    }
+
+    // Each guardian counts once, whatever the number of times it is passed
+    let mut approvals = 0u8;
+    for signer_info in std::iter::once(guardian_info)
+        .chain(further_guardians)
+        .filter(|info| info.is_signer)
+    {
+        if let Some(index) = recovery.guardian_index(signer_info.key) {
+            approvals |= 1 << index;
+        }
+    }
+    if (approvals.count_ones() as u8) < recovery.threshold {
+        return Err(RewardPoolError::NotEnoughGuardianApprovals.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    recovery.new_wallet = new_wallet;
+    recovery.initiated_at = now;
+    recovery.save(recovery_info)?;
+
+    let completes_at = now.saturating_add(FARMER_RECOVERY_DELAY);
+    RewardPoolEvent::FarmerRecoveryInitiated {
+        pool: *pool_info.key,
+        farmer: recovery.farmer,
+        new_wallet,
+        completes_at,
+    }
+    .emit();
+
+    msg!(
+        "Recovery of {} to {} started, completes at {}",
+        recovery.farmer,
+        new_wallet,
+        completes_at
+    );
+    Ok(())
+}
+
+// Farmer stopping a recovery of their rewards
+pub(super) fn process_cancel_farmer_recovery(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let recovery_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    load_pool(program_id, pool_info)?;
+
+    let (expected_recovery, _) =
+        find_farmer_recovery_address_with_program_id(pool_info.key, farmer_info.key, program_id);
+    if *recovery_info.key != expected_recovery || recovery_info.data_is_empty() {
+        return Err(RewardPoolError::InvalidFarmerRecoveryAccount.into());
+    }
+    let mut recovery = load_farmer_recovery(program_id, pool_info.key, recovery_info)?;
+    if !recovery.is_pending() {
+        return Err(RewardPoolError::NoFarmerRecoveryPending.into());
+    }
+
+    let new_wallet = recovery.new_wallet;
+    recovery.new_wallet = Pubkey::default();
+    recovery.initiated_at = 0;
+    recovery.save(recovery_info)?;
+
+    RewardPoolEvent::FarmerRecoveryCancelled {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        new_wallet,
+    }
+    .emit();
+
+    msg!("Recovery to {} cancelled", new_wallet);
+    Ok(())
+}
+
+// Moving a farmer's rewards to the wallet their guardians recovered them to
+pub(super) fn process_complete_farmer_recovery(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let recovery_info = next_account_info(account_info_iter)?;
+    let farmer_state_info = next_account_info(account_info_iter)?;
+    let new_farmer_state_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let pending_pairs = account_info_iter.as_slice();
+
+    // Validations
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    if recovery_info.data_is_empty() {
+        return Err(RewardPoolError::InvalidFarmerRecoveryAccount.into());
+    }
+    let recovery = load_farmer_recovery(program_id, pool_info.key, recovery_info)?;
+    if !recovery.is_pending() {
+        return Err(RewardPoolError::NoFarmerRecoveryPending.into());
+    }
+    if Clock::get()?.unix_timestamp < recovery.initiated_at.saturating_add(FARMER_RECOVERY_DELAY) {
+        return Err(RewardPoolError::FarmerRecoveryNotReady.into());
+    }
+
+    if !pending_pairs.len().is_multiple_of(2) || pending_pairs.len() > 2 * MAX_CLAIM_BATCH {
+        return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+    }
+
+    let farmer = recovery.farmer;
+    let new_wallet = recovery.new_wallet;
+
+    // The farmer state moves once, keeping the payer its rent returns to
+    let (expected_farmer_state, _) =
+        find_farmer_state_address_with_program_id(pool_info.key, &farmer, program_id);
+    let (expected_new_farmer_state, new_state_bump) =
+        find_farmer_state_address_with_program_id(pool_info.key, &new_wallet, program_id);
+    if *farmer_state_info.key != expected_farmer_state
+        || *new_farmer_state_info.key != expected_new_farmer_state
+    {
+        return Err(RewardPoolError::InvalidFarmerStateAccount.into());
+    }
+
+    // The moved farmer state closes once every new account exists,
+    // refunding the payer the rent it advanced for its copy. Moved pending
+    // rewards stay behind as tombstones, so their tasks cannot be recorded
+    // for the lost wallet again.
+    let moved_state = !farmer_state_info.data_is_empty();
+    if moved_state {
+        if !new_farmer_state_info.data_is_empty() {
+            return Err(RewardPoolError::InvalidFarmerStateAccount.into());
+        }
+        let mut farmer_state = load_farmer_state(program_id, farmer_state_info)?;
+        // The forward destination and cosigner belong to the lost wallet
+        farmer_state.farmer = new_wallet;
+        farmer_state.auto_forward = false;
+        farmer_state.forward_destination = Pubkey::default();
+        farmer_state.withdrawal_cosigner = Pubkey::default();
+        farmer_state.cosigner_removal_at = 0;
+        farmer_state.bump_seed = new_state_bump;
+
+        create_pda_account(
+            payer_info,
+            new_farmer_state_info,
+            system_program_info,
+            program_id,
+            FarmerState::LEN,
+            &[
+                FARMER_STATE_SEED,
+                pool_info.key.as_ref(),
+                new_wallet.as_ref(),
+                &[new_state_bump],
+            ],
+        )?;
+        farmer_state.save(new_farmer_state_info)?;
+    }
+
+    let mut pending_amount = 0u64;
+    for pair in pending_pairs.chunks(2) {
+        let (pending_info, new_pending_info) = (&pair[0], &pair[1]);
+
+        if pending_info.owner != program_id {
+            return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+        }
+        let mut pending = PendingReward::try_from_slice(&pending_info.data.borrow())
+            .map_err(|_| RewardPoolError::InvalidPendingRewardAccount)?;
+        let (expected_pending, _) = find_pending_reward_address_with_program_id(
+            pool_info.key,
+            &farmer,
+            &pending.task_id,
+            program_id,
+        );
+        if pending.farmer_pubkey != farmer || *pending_info.key != expected_pending {
+            return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+        }
+        if pending.is_withdrawn {
+            return Err(RewardPoolError::NoPendingRewards.into());
+        }
+        if pending.is_challenged {
+            return Err(RewardPoolError::RewardChallenged.into());
+        }
+
+        let (expected_new_pending, bump_seed) = find_pending_reward_address_with_program_id(
+            pool_info.key,
+            &new_wallet,
+            &pending.task_id,
+            program_id,
+        );
+        if *new_pending_info.key != expected_new_pending || !new_pending_info.data_is_empty() {
+            return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+        }
+
+        create_pda_account(
+            payer_info,
+            new_pending_info,
+            system_program_info,
+            program_id,
+            pending_info.data_len(),
+            &[
+                PENDING_REWARD_SEED,
+                pool_info.key.as_ref(),
+                new_wallet.as_ref(),
+                hash(pending.task_id.as_bytes()).as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        pending.farmer_pubkey = new_wallet;
+        pending.serialize(&mut &mut new_pending_info.data.borrow_mut()[..])?;
+
+        pending_amount = pending_amount
+            .checked_add(pending.amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        pending.farmer_pubkey = farmer;
+        leave_pending_tombstone(pending_info, &mut pending)?;
+    }
+
+    let pending_count = (pending_pairs.len() / 2) as u8;
+    if !moved_state && pending_count == 0 {
+        return Err(RewardPoolError::NoPendingRewards.into());
+    }
+    if moved_state {
+        close_pda_account(farmer_state_info, payer_info)?;
+    }
+
+    RewardPoolEvent::FarmerRecovered {
+        pool: *pool_info.key,
+        farmer,
+        new_wallet,
+        moved_state,
+        pending_count,
+        pending_amount,
+    }
+    .emit();
+
+    msg!(
+        "Recovered {} pending rewards of {} tokens from {} to {}",
+        pending_count,
+        pending_amount,
+        farmer,
+        new_wallet
+    );
+    Ok(())
+}
+
+fn load_farmer_recovery(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    recovery_info: &AccountInfo,
+) -> Result<FarmerRecovery, ProgramError> {
+    if recovery_info.owner != program_id {
+        return Err(RewardPoolError::InvalidFarmerRecoveryAccount.into());
+    }
+    let recovery = FarmerRecovery::try_from_slice(&recovery_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidFarmerRecoveryAccount)?;
+    let expected_recovery = Pubkey::create_program_address(
+        &[
+            FARMER_RECOVERY_SEED,
+            pool.as_ref(),
+            recovery.farmer.as_ref(),
+            &[recovery.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidFarmerRecoveryAccount)?;
+    if recovery.pool != *pool || *recovery_info.key != expected_recovery {
+        return Err(RewardPoolError::InvalidFarmerRecoveryAccount.into());
+    }
+    Ok(recovery)
+}
@@ -0,0 +1,260 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::{parameters::load_parameters, treasury::load_pool, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_fee_discounts_address_with_program_id, find_fee_overrides_address_with_program_id,
+        FEE_DISCOUNTS_SEED, FEE_OVERRIDES_SEED,
+    },
+    state::{FeeDiscounts, FeeOverrides, Sequenced},
+    BASIS_POINTS,
+};
+
+// Setting or removing the platform fee of a task type
+pub(super) fn process_set_task_type_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    task_type: u32,
+    fee_percentage: Option<u8>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let parameters_info = next_account_info(account_info_iter)?;
+    let fee_overrides_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if let Some(fee_percentage) = fee_percentage {
+        let parameters = load_parameters(program_id, pool_info.key, parameters_info)?;
+        if fee_percentage > parameters.max_platform_fee_percentage {
+            return Err(RewardPoolError::InvalidPlatformFee.into());
+        }
+    }
+
+    let (expected_fee_overrides, bump_seed) =
+        find_fee_overrides_address_with_program_id(pool_info.key, program_id);
+    if *fee_overrides_info.key != expected_fee_overrides {
+        return Err(RewardPoolError::InvalidFeeOverridesAccount.into());
+    }
+
+    let mut fee_overrides = if fee_overrides_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            fee_overrides_info,
+            system_program_info,
+            program_id,
+            FeeOverrides::LEN,
+            &[FEE_OVERRIDES_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        FeeOverrides::new(*pool_info.key, bump_seed)
+    } else {
+        load_fee_overrides(program_id, fee_overrides_info)?
+    };
+
+    match fee_percentage {
+        Some(fee_percentage) => fee_overrides.set(task_type, fee_percentage)?,
+        None => fee_overrides.remove(task_type)?,
+    }
+    fee_overrides.save(fee_overrides_info)?;
+
+    RewardPoolEvent::TaskTypeFeeSet {
+        pool: *pool_info.key,
+        task_type,
+        fee_percentage,
+    }
+    .emit();
+
+    match fee_percentage {
+        Some(fee_percentage) => msg!("Task type {} fee set to {}%", task_type, fee_percentage),
+        None => msg!("Task type {} fee override removed", task_type),
+    }
+    Ok(())
+}
+
+// Setting or removing the fee discounts of staking tiers
+pub(super) fn process_set_fee_discounts(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    staking_program: Option<Pubkey>,
+    stake_seed: Vec<u8>,
+    tier_offset: u16,
+    discounts_bps: Vec<u16>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let fee_discounts_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let mut stake_seed_bytes = [0; 32];
+    if stake_seed.len() > stake_seed_bytes.len()
+        || discounts_bps.len() > FeeDiscounts::MAX_TIERS
+        || discounts_bps
+            .iter()
+            .any(|discount_bps| *discount_bps > BASIS_POINTS)
+    {
+        return Err(RewardPoolError::InvalidFeeDiscounts.into());
+    }
+    stake_seed_bytes[..stake_seed.len()].copy_from_slice(&stake_seed);
+
+    let (expected_fee_discounts, bump_seed) =
+        find_fee_discounts_address_with_program_id(pool_info.key, program_id);
+    if *fee_discounts_info.key != expected_fee_discounts {
+        return Err(RewardPoolError::InvalidFeeDiscountsAccount.into());
+    }
+
+    let mut fee_discounts = if fee_discounts_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            fee_discounts_info,
+            system_program_info,
+            program_id,
+            FeeDiscounts::LEN,
+            &[FEE_DISCOUNTS_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        FeeDiscounts {
+            pool: *pool_info.key,
+            staking_program: Pubkey::default(),
+            stake_seed: [0; 32],
+            stake_seed_len: 0,
+            tier_offset: 0,
+            tier_count: 0,
+            discounts_bps: [0; FeeDiscounts::MAX_TIERS],
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_fee_discounts(program_id, fee_discounts_info)?
+    };
+
+    fee_discounts.staking_program = staking_program.unwrap_or_default();
+    fee_discounts.stake_seed = stake_seed_bytes;
+    fee_discounts.stake_seed_len = stake_seed.len() as u8;
+    fee_discounts.tier_offset = tier_offset;
+    fee_discounts.tier_count = discounts_bps.len() as u8;
+    fee_discounts.discounts_bps = [0; FeeDiscounts::MAX_TIERS];
+    fee_discounts.discounts_bps[..discounts_bps.len()].copy_from_slice(&discounts_bps);
+    fee_discounts.save(fee_discounts_info)?;
+
+    RewardPoolEvent::FeeDiscountsSet {
+        pool: *pool_info.key,
+        staking_program,
+        discounts_bps,
+    }
+    .emit();
+
+    match staking_program {
+        Some(staking_program) => msg!("Fee discounts set for stakers of {}", staking_program),
+        None => msg!("Fee discounts removed"),
+    }
+    Ok(())
+}
+
+/// Returns the share of the platform fee waived for `farmer`'s staking
+/// tier, 0 when the pool has no discounts or the farmer does not stake.
+/// `stake_info` must be the farmer's staking account, so the platform cannot
+/// withhold a discount by passing another one.
+pub(super) fn farmer_fee_discount(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    fee_discounts_info: &AccountInfo,
+    stake_info: &AccountInfo,
+    farmer: &Pubkey,
+) -> Result<u16, ProgramError> {
+    let (expected_fee_discounts, _) = find_fee_discounts_address_with_program_id(pool, program_id);
+    if *fee_discounts_info.key != expected_fee_discounts {
+        return Err(RewardPoolError::InvalidFeeDiscountsAccount.into());
+    }
+    if fee_discounts_info.data_is_empty() {
+        return Ok(0);
+    }
+
+    let fee_discounts = load_fee_discounts(program_id, fee_discounts_info)?;
+    let Some(stake_address) = fee_discounts.stake_address(farmer) else {
+        return Ok(0);
+    };
+    if *stake_info.key != stake_address {
+        return Err(RewardPoolError::InvalidStakeAccount.into());
+    }
+
+    // Farmers who never staked have no account, or one the staking program
+    // does not own yet
+    if Some(*stake_info.owner) != fee_discounts.staking_program() {
+        return Ok(0);
+    }
+    let tier = stake_info
+        .data
+        .borrow()
+        .get(fee_discounts.tier_offset as usize)
+        .copied();
+    Ok(tier.map_or(0, |tier| fee_discounts.discount_bps(tier)))
+}
+
+/// Returns the platform fee of `task_type`, `None` when it uses the pool's fee
+pub(super) fn task_type_fee(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    fee_overrides_info: &AccountInfo,
+    task_type: u32,
+) -> Result<Option<u8>, ProgramError> {
+    let (expected_fee_overrides, _) = find_fee_overrides_address_with_program_id(pool, program_id);
+    if *fee_overrides_info.key != expected_fee_overrides {
+        return Err(RewardPoolError::InvalidFeeOverridesAccount.into());
+    }
+    if fee_overrides_info.data_is_empty() {
+        return Ok(None);
+    }
+
+    Ok(load_fee_overrides(program_id, fee_overrides_info)?.fee_percentage(task_type))
+}
+
+fn load_fee_overrides(
+    program_id: &Pubkey,
+    fee_overrides_info: &AccountInfo,
+) -> Result<FeeOverrides, ProgramError> {
+    if fee_overrides_info.owner != program_id {
+        return Err(RewardPoolError::InvalidFeeOverridesAccount.into());
+    }
+    FeeOverrides::try_from_slice(&fee_overrides_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidFeeOverridesAccount.into())
+}
+
+fn load_fee_discounts(
+    program_id: &Pubkey,
+    fee_discounts_info: &AccountInfo,
+) -> Result<FeeDiscounts, ProgramError> {
+    if fee_discounts_info.owner != program_id {
+        return Err(RewardPoolError::InvalidFeeDiscountsAccount.into());
+    }
+    FeeDiscounts::try_from_slice(&fee_discounts_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidFeeDiscountsAccount.into())
+}
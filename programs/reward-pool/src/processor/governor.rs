@@ -0,0 +1,166 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::{
+    treasury::load_pool,
+    utils::{create_pda_account, token_balance},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{find_reward_governor_address_with_program_id, REWARD_GOVERNOR_SEED},
+    state::{DailyStats, RewardGovernor, Sequenced},
+    BASIS_POINTS,
+};
+
+// Configuring the reward governor
+pub(super) fn process_set_reward_governor(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    min_runway_days: u32,
+    floor_bps: u16,
+    ceiling_bps: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let governor_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if floor_bps == 0 || floor_bps > ceiling_bps || ceiling_bps > BASIS_POINTS {
+        return Err(RewardPoolError::InvalidRewardGovernor.into());
+    }
+
+    let (expected_governor, bump_seed) =
+        find_reward_governor_address_with_program_id(pool_info.key, program_id);
+    if *governor_info.key != expected_governor {
+        return Err(RewardPoolError::InvalidRewardGovernorAccount.into());
+    }
+
+    let mut governor = if governor_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            governor_info,
+            system_program_info,
+            program_id,
+            RewardGovernor::LEN,
+            &[REWARD_GOVERNOR_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        RewardGovernor {
+            pool: *pool_info.key,
+            min_runway_days: 0,
+            floor_bps: 0,
+            ceiling_bps: 0,
+            window_day: 0,
+            daily_distributed: [0; RewardGovernor::WINDOW_DAYS],
+            last_factor_bps: BASIS_POINTS,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_reward_governor(program_id, pool_info.key, governor_info)?
+    };
+
+    governor.min_runway_days = min_runway_days;
+    governor.floor_bps = floor_bps;
+    governor.ceiling_bps = ceiling_bps;
+    governor.save(governor_info)?;
+
+    RewardPoolEvent::RewardGovernorSet {
+        pool: *pool_info.key,
+        min_runway_days,
+        floor_bps,
+        ceiling_bps,
+    }
+    .emit();
+
+    if governor.is_enabled() {
+        msg!(
+            "Rewards scaled between {} and {} bps below {} days of runway",
+            floor_bps,
+            ceiling_bps,
+            min_runway_days
+        );
+    } else {
+        msg!("Reward governor disabled");
+    }
+    Ok(())
+}
+
+/// Returns the reward governor of the pool with its window moved to `now`,
+/// and the factor a reward recorded now is scaled by given the platform
+/// treasury's balance. Without a governor the factor is `BASIS_POINTS`.
+pub(super) fn read_reward_governor(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    governor_info: &AccountInfo,
+    platform_treasury_info: &AccountInfo,
+    now: i64,
+) -> Result<(Option<RewardGovernor>, u16), ProgramError> {
+    let (expected_governor, _) = find_reward_governor_address_with_program_id(pool, program_id);
+    if *governor_info.key != expected_governor {
+        return Err(RewardPoolError::InvalidRewardGovernorAccount.into());
+    }
+    if governor_info.data_is_empty() {
+        return Ok((None, BASIS_POINTS));
+    }
+
+    let mut governor = load_reward_governor(program_id, pool, governor_info)?;
+    governor.roll(DailyStats::day_of(now));
+    let factor_bps = if governor.is_enabled() {
+        governor.factor_bps(token_balance(platform_treasury_info)?)
+    } else {
+        BASIS_POINTS
+    };
+    Ok((Some(governor), factor_bps))
+}
+
+/// Adds what a reward paid out of the platform treasury to the governor's
+/// window, along with the factor the reward was scaled by
+pub(super) fn record_governed_reward(
+    governor: &mut RewardGovernor,
+    governor_info: &AccountInfo,
+    distributed: u64,
+    factor_bps: u16,
+) -> ProgramResult {
+    governor.record(distributed);
+    governor.last_factor_bps = factor_bps;
+    governor.save(governor_info)
+}
+
+fn load_reward_governor(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    governor_info: &AccountInfo,
+) -> Result<RewardGovernor, ProgramError> {
+    if governor_info.owner != program_id {
+        return Err(RewardPoolError::InvalidRewardGovernorAccount.into());
+    }
+    let governor = RewardGovernor::try_from_slice(&governor_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidRewardGovernorAccount)?;
+    let expected_governor = Pubkey::create_program_address(
+        &[REWARD_GOVERNOR_SEED, pool.as_ref(), &[governor.bump_seed]],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidRewardGovernorAccount)?;
+    if governor.pool != *pool || *governor_info.key != expected_governor {
+        return Err(RewardPoolError::InvalidRewardGovernorAccount.into());
+    }
+    Ok(governor)
+}
@@ -0,0 +1,121 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::{treasury::load_pool, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{find_guardians_address_with_program_id, GUARDIANS_SEED},
+    state::{PoolGuardians, Sequenced},
+};
+
+// Setting the pool guardians
+pub(super) fn process_set_guardians(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    guardians: Vec<Pubkey>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let guardians_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let distinct = guardians
+        .iter()
+        .enumerate()
+        .all(|(index, guardian)| !guardians[..index].contains(guardian));
+    if guardians.len() > PoolGuardians::MAX_GUARDIANS || !distinct {
+        return Err(RewardPoolError::InvalidGuardianConfig.into());
+    }
+
+    let (expected_guardians, bump_seed) =
+        find_guardians_address_with_program_id(pool_info.key, program_id);
+    if *guardians_info.key != expected_guardians {
+        return Err(RewardPoolError::InvalidGuardiansAccount.into());
+    }
+
+    let mut guardian_set = if guardians_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            guardians_info,
+            system_program_info,
+            program_id,
+            PoolGuardians::LEN,
+            &[GUARDIANS_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        PoolGuardians {
+            pool: *pool_info.key,
+            guardian_count: 0,
+            guardians: [Pubkey::default(); PoolGuardians::MAX_GUARDIANS],
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_guardians_account(program_id, guardians_info)?
+    };
+
+    guardian_set.guardian_count = guardians.len() as u8;
+    guardian_set.guardians = [Pubkey::default(); PoolGuardians::MAX_GUARDIANS];
+    guardian_set.guardians[..guardians.len()].copy_from_slice(&guardians);
+    guardian_set.save(guardians_info)?;
+
+    RewardPoolEvent::GuardiansUpdated {
+        pool: *pool_info.key,
+        guardians,
+    }
+    .emit();
+
+    msg!("Pool guardians updated: {}", guardian_set.guardian_count);
+    Ok(())
+}
+
+/// Whether `key` is a guardian of the pool. Without the guardians account,
+/// or while it does not exist, the pool has none.
+pub(super) fn is_guardian(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    guardians_info: Option<&AccountInfo>,
+    key: &Pubkey,
+) -> Result<bool, ProgramError> {
+    let Some(guardians_info) = guardians_info else {
+        return Ok(false);
+    };
+
+    let (expected_guardians, _) = find_guardians_address_with_program_id(pool, program_id);
+    if *guardians_info.key != expected_guardians {
+        return Err(RewardPoolError::InvalidGuardiansAccount.into());
+    }
+    if guardians_info.data_is_empty() {
+        return Ok(false);
+    }
+    Ok(load_guardians_account(program_id, guardians_info)?.is_guardian(key))
+}
+
+fn load_guardians_account(
+    program_id: &Pubkey,
+    guardians_info: &AccountInfo,
+) -> Result<PoolGuardians, ProgramError> {
+    if guardians_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(PoolGuardians::try_from_slice(
+        &guardians_info.data.borrow(),
+    )?)
+}
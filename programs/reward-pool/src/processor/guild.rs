@@ -0,0 +1,470 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_token::{instruction as token_instruction, state::Account as TokenAccount};
+
+use super::{
+    farmer::{check_withdrawal_cosigner, load_farmer_state, update_farmer_state},
+    treasury::load_pool,
+    utils::{create_pda_account, create_self_owned_token_account, token_balance},
+    whitelist::{check_payout_address, check_withdrawal_destination},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_farmer_state_address_with_program_id, find_guild_address_with_program_id,
+        find_guild_vault_address_with_program_id, GUILD_SEED, GUILD_VAULT_SEED,
+    },
+    state::{FarmerState, Guild, GuildMember, Sequenced},
+    BASIS_POINTS,
+};
+
+// Setting the farmer's guild split
+pub(super) fn process_set_guild_split(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    guild_share_bps: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let farmer_state_info = next_account_info(account_info_iter)?;
+    let guild_vault_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let payout_address_info = next_account_info(account_info_iter)?;
+    let whitelist_info = next_account_info(account_info_iter)?;
+    let cosigner_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+
+    // The crank pays the guild's share without the farmer, so the split is
+    // where their cosigner guards it
+    check_withdrawal_cosigner(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        farmer_state_info,
+        cosigner_info,
+    )?;
+
+    if guild_share_bps > BASIS_POINTS {
+        return Err(RewardPoolError::InvalidGuildShare.into());
+    }
+
+    let guild_vault = if guild_share_bps > 0 {
+        if *guild_vault_info.owner != spl_token::id() {
+            return Err(RewardPoolError::InvalidGuildVault.into());
+        }
+        let guild_vault = TokenAccount::unpack(&guild_vault_info.data.borrow())?;
+        if guild_vault.mint != pool_data.reward_mint {
+            return Err(RewardPoolError::InvalidGuildVault.into());
+        }
+        // The guild's share is paid out of custody like a claim, so the
+        // guild vault must pass the farmer's whitelist and payout address
+        check_withdrawal_destination(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            whitelist_info,
+            guild_vault_info.key,
+        )?;
+        check_payout_address(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            payout_address_info,
+            guild_vault_info,
+        )?;
+        Some(*guild_vault_info.key)
+    } else {
+        None
+    };
+
+    update_farmer_state(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        farmer_state_info,
+        farmer_info,
+        system_program_info,
+        |farmer_state| {
+            farmer_state.guild_vault = guild_vault.unwrap_or_default();
+            farmer_state.guild_share_bps = guild_share_bps;
+        },
+    )?;
+
+    RewardPoolEvent::GuildSplitSet {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        guild_vault,
+        guild_share_bps,
+    }
+    .emit();
+
+    match guild_vault {
+        Some(guild_vault) => msg!(
+            "Paying {} bps of claims to guild vault {}",
+            guild_share_bps,
+            guild_vault
+        ),
+        None => msg!("Guild split removed"),
+    }
+    Ok(())
+}
+
+// Setting the members of a guild
+pub(super) fn process_set_guild_members(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    members: Vec<GuildMember>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let guild_info = next_account_info(account_info_iter)?;
+    let guild_vault_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+
+    let distinct = members.iter().enumerate().all(|(index, member)| {
+        !members[..index]
+            .iter()
+            .any(|other| other.member == member.member)
+    });
+    let total_shares: u32 = members.iter().map(|member| member.share_bps as u32).sum();
+    if members.is_empty()
+        || members.len() > Guild::MAX_MEMBERS
+        || !distinct
+        || members.iter().any(|member| member.share_bps == 0)
+        || total_shares != BASIS_POINTS as u32
+    {
+        return Err(RewardPoolError::InvalidGuildMembers.into());
+    }
+
+    let (expected_guild, bump_seed) =
+        find_guild_address_with_program_id(pool_info.key, authority_info.key, program_id);
+    if *guild_info.key != expected_guild {
+        return Err(RewardPoolError::InvalidGuildAccount.into());
+    }
+
+    let mut guild = if guild_info.data_is_empty() {
+        let (expected_guild_vault, vault_bump_seed) =
+            find_guild_vault_address_with_program_id(guild_info.key, program_id);
+        if *guild_vault_info.key != expected_guild_vault
+            || *reward_mint_info.key != pool_data.reward_mint
+            || *token_program_info.key != spl_token::id()
+        {
+            return Err(RewardPoolError::InvalidGuildVault.into());
+        }
+
+        // Like the treasury vault, the guild vault is its own owner
+        create_self_owned_token_account(
+            authority_info,
+            guild_vault_info,
+            reward_mint_info,
+            token_program_info,
+            system_program_info,
+            &[
+                GUILD_VAULT_SEED,
+                guild_info.key.as_ref(),
+                &[vault_bump_seed],
+            ],
+        )?;
+        create_pda_account(
+            authority_info,
+            guild_info,
+            system_program_info,
+            program_id,
+            Guild::LEN,
+            &[
+                GUILD_SEED,
+                pool_info.key.as_ref(),
+                authority_info.key.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        Guild {
+            pool: *pool_info.key,
+            authority: *authority_info.key,
+            member_count: 0,
+            members: [GuildMember::default(); Guild::MAX_MEMBERS],
+            total_distributed: 0,
+            distribution_count: 0,
+            vault_bump_seed,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_guild(program_id, pool_info.key, guild_info)?
+    };
+
+    guild.member_count = members.len() as u8;
+    guild.members = [GuildMember::default(); Guild::MAX_MEMBERS];
+    guild.members[..members.len()].copy_from_slice(&members);
+    guild.save(guild_info)?;
+
+    RewardPoolEvent::GuildMembersSet {
+        pool: *pool_info.key,
+        guild: *guild_info.key,
+        authority: *authority_info.key,
+        members,
+    }
+    .emit();
+
+    msg!("Guild members set: {}", guild.member_count);
+    Ok(())
+}
+
+// Leaving a guild
+pub(super) fn process_leave_guild(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let member_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let guild_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !member_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    load_pool(program_id, pool_info)?;
+    let mut guild = load_guild(program_id, pool_info.key, guild_info)?;
+    let index = guild
+        .member_index(member_info.key)
+        .ok_or(RewardPoolError::NotGuildMember)?;
+
+    let count = guild.member_count as usize;
+    guild.members.copy_within(index + 1..count, index);
+    guild.members[count - 1] = GuildMember::default();
+    guild.member_count -= 1;
+    guild.save(guild_info)?;
+
+    RewardPoolEvent::GuildMemberLeft {
+        pool: *pool_info.key,
+        guild: *guild_info.key,
+        member: *member_info.key,
+    }
+    .emit();
+
+    msg!("Left guild {}", guild_info.key);
+    Ok(())
+}
+
+// Paying a guild's vault out to its members
+pub(super) fn process_distribute_guild_rewards(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let guild_info = next_account_info(account_info_iter)?;
+    let guild_vault_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let _system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let member_account_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    let mut guild = load_guild(program_id, pool_info.key, guild_info)?;
+    if guild.member_count == 0 {
+        return Err(RewardPoolError::InvalidGuildMembers.into());
+    }
+
+    let vault_seeds: &[&[u8]] = &[
+        GUILD_VAULT_SEED,
+        guild_info.key.as_ref(),
+        &[guild.vault_bump_seed],
+    ];
+    let expected_guild_vault = Pubkey::create_program_address(vault_seeds, program_id)
+        .map_err(|_| RewardPoolError::InvalidGuildVault)?;
+    if *guild_vault_info.key != expected_guild_vault {
+        return Err(RewardPoolError::InvalidGuildVault.into());
+    }
+    if *token_program_info.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if member_account_infos.len() != guild.members().len() {
+        return Err(RewardPoolError::InvalidGuildMemberAccount.into());
+    }
+    for (member, member_account_info) in guild.members().iter().zip(member_account_infos) {
+        if *member_account_info.owner != spl_token::id() {
+            return Err(RewardPoolError::InvalidGuildMemberAccount.into());
+        }
+        let member_account = TokenAccount::unpack(&member_account_info.data.borrow())?;
+        if member_account.owner != member.member || member_account.mint != pool_data.reward_mint {
+            return Err(RewardPoolError::InvalidGuildMemberAccount.into());
+        }
+    }
+
+    let amount = token_balance(guild_vault_info)?;
+    if amount == 0 {
+        return Err(RewardPoolError::NoGuildRewards.into());
+    }
+
+    for (member_account_info, member_amount) in
+        member_account_infos.iter().zip(guild.amounts(amount))
+    {
+        if member_amount == 0 {
+            continue;
+        }
+        invoke_signed(
+            &token_instruction::transfer(
+                token_program_info.key,
+                guild_vault_info.key,
+                member_account_info.key,
+                guild_vault_info.key,
+                &[],
+                member_amount,
+            )?,
+            &[
+                guild_vault_info.clone(),
+                member_account_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[vault_seeds],
+        )?;
+    }
+
+    guild.total_distributed = guild.total_distributed.saturating_add(amount);
+    guild.distribution_count = guild
+        .distribution_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    guild.save(guild_info)?;
+
+    RewardPoolEvent::GuildRewardsDistributed {
+        pool: *pool_info.key,
+        guild: *guild_info.key,
+        amount,
+        member_count: guild.member_count,
+    }
+    .emit();
+
+    msg!(
+        "Distributed {} tokens to {} guild members",
+        amount,
+        guild.member_count
+    );
+    Ok(())
+}
+
+/// Returns the farmer's state when they have a guild split, `None` when they
+/// have none
+pub(super) fn load_guild_split(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    farmer_state_info: &AccountInfo,
+) -> Result<Option<FarmerState>, ProgramError> {
+    let (expected_farmer_state, _) =
+        find_farmer_state_address_with_program_id(pool, farmer, program_id);
+    if *farmer_state_info.key != expected_farmer_state {
+        return Err(RewardPoolError::InvalidFarmerStateAccount.into());
+    }
+    if farmer_state_info.data_is_empty() {
+        return Ok(None);
+    }
+    let farmer_state = load_farmer_state(program_id, farmer_state_info)?;
+    Ok((farmer_state.guild_share_bps > 0).then_some(farmer_state))
+}
+
+/// Pays the guild's share of a claim of `amount` from the treasury vault to
+/// the farmer's guild vault, and returns it
+pub(super) fn pay_guild_share<'a>(
+    pool: &Pubkey,
+    farmer_state: &FarmerState,
+    guild_vault_info: &AccountInfo<'a>,
+    vault_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    vault_seeds: &[&[u8]],
+    amount: u64,
+) -> Result<u64, ProgramError> {
+    if *guild_vault_info.key != farmer_state.guild_vault {
+        return Err(RewardPoolError::InvalidGuildVault.into());
+    }
+
+    let share = farmer_state.guild_share(amount);
+    if share == 0 {
+        return Ok(0);
+    }
+    invoke_signed(
+        &token_instruction::transfer(
+            token_program_info.key,
+            vault_info.key,
+            guild_vault_info.key,
+            vault_info.key,
+            &[],
+            share,
+        )?,
+        &[
+            vault_info.clone(),
+            guild_vault_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[vault_seeds],
+    )?;
+
+    RewardPoolEvent::GuildSharePaid {
+        pool: *pool,
+        farmer: farmer_state.farmer,
+        guild_vault: *guild_vault_info.key,
+        amount: share,
+    }
+    .emit();
+    Ok(share)
+}
+
+fn load_guild(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    guild_info: &AccountInfo,
+) -> Result<Guild, ProgramError> {
+    if guild_info.owner != program_id {
+        return Err(RewardPoolError::InvalidGuildAccount.into());
+    }
+    let guild = Guild::try_from_slice(&guild_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidGuildAccount)?;
+    let expected_guild = Pubkey::create_program_address(
+        &[
+            GUILD_SEED,
+            pool.as_ref(),
+            guild.authority.as_ref(),
+            &[guild.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidGuildAccount)?;
+    if guild.pool != *pool || *guild_info.key != expected_guild {
+        return Err(RewardPoolError::InvalidGuildAccount.into());
+    }
+    Ok(guild)
+}
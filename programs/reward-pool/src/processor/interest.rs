@@ -0,0 +1,275 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token::instruction as token_instruction;
+
+use super::{
+    treasury::load_pool,
+    utils::{create_pda_account, create_self_owned_token_account, token_balance},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_bonus_vault_address_with_program_id, find_interest_policy_address_with_program_id,
+        BONUS_VAULT_SEED, INTEREST_POLICY_SEED,
+    },
+    state::{InterestPolicy, InterestRatePeriod, PendingReward, Sequenced},
+    MAX_INTEREST_RATE_BPS,
+};
+
+// Setting the interest unclaimed rewards earn
+pub(super) fn process_set_interest_policy(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    rate_bps: u16,
+    max_accrual_secs: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let policy_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let bonus_vault_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if rate_bps > MAX_INTEREST_RATE_BPS || max_accrual_secs < 0 {
+        return Err(RewardPoolError::InvalidInterestRate.into());
+    }
+
+    let (expected_policy, bump_seed) =
+        find_interest_policy_address_with_program_id(pool_info.key, program_id);
+    if *policy_info.key != expected_policy {
+        return Err(RewardPoolError::InvalidInterestPolicyAccount.into());
+    }
+
+    let mut policy = if policy_info.data_is_empty() {
+        let (expected_bonus_vault, bonus_vault_bump_seed) =
+            find_bonus_vault_address_with_program_id(pool_info.key, program_id);
+        if *bonus_vault_info.key != expected_bonus_vault
+            || *reward_mint_info.key != pool_data.reward_mint
+            || *token_program_info.key != spl_token::id()
+        {
+            return Err(RewardPoolError::InvalidBonusVaultAccount.into());
+        }
+
+        // Like the treasury vault, the bonus vault is its own owner
+        create_self_owned_token_account(
+            platform_authority_info,
+            bonus_vault_info,
+            reward_mint_info,
+            token_program_info,
+            system_program_info,
+            &[
+                BONUS_VAULT_SEED,
+                pool_info.key.as_ref(),
+                &[bonus_vault_bump_seed],
+            ],
+        )?;
+
+        create_pda_account(
+            platform_authority_info,
+            policy_info,
+            system_program_info,
+            program_id,
+            InterestPolicy::LEN,
+            &[INTEREST_POLICY_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        InterestPolicy {
+            pool: *pool_info.key,
+            rate_bps: 0,
+            max_accrual_secs: 0,
+            total_paid: 0,
+            total_shortfall: 0,
+            claim_count: 0,
+            rate_period_count: 0,
+            rate_periods: [InterestRatePeriod::default(); InterestPolicy::MAX_RATE_PERIODS],
+            bonus_vault_bump_seed,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_interest_policy(program_id, pool_info.key, policy_info)?
+    };
+
+    // Rewards keep what they accrued at the old rate up to now
+    policy.set_rate(rate_bps, Clock::get()?.unix_timestamp);
+    policy.max_accrual_secs = max_accrual_secs;
+    policy.save(policy_info)?;
+
+    RewardPoolEvent::InterestPolicySet {
+        pool: *pool_info.key,
+        rate_bps,
+        max_accrual_secs,
+    }
+    .emit();
+
+    msg!(
+        "Interest set to {} bps a year for up to {} seconds",
+        rate_bps,
+        max_accrual_secs
+    );
+    Ok(())
+}
+
+/// Returns the interest policy claims of the pool accrue under, `None` when
+/// the pool has none. A policy whose interest is now off is still returned,
+/// for what rewards accrued before and interest still owed.
+pub(super) fn load_claim_interest_policy(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    policy_info: &AccountInfo,
+) -> Result<Option<InterestPolicy>, ProgramError> {
+    let (expected_policy, _) = find_interest_policy_address_with_program_id(pool, program_id);
+    if *policy_info.key != expected_policy {
+        return Err(RewardPoolError::InvalidInterestPolicyAccount.into());
+    }
+    if policy_info.data_is_empty() {
+        return Ok(None);
+    }
+
+    load_interest_policy(program_id, pool, policy_info).map(Some)
+}
+
+/// Pays the interest a claim owes, `accrued` on the rewards it paid out and
+/// `carried` from earlier claims, from the bonus vault to
+/// `destination_info`. The bonus vault pays what it holds; the rest stays
+/// owed in the claim's `pending_infos`, so a claim never fails for lack of
+/// interest.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn pay_claim_interest<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    policy: &mut InterestPolicy,
+    policy_info: &AccountInfo<'a>,
+    bonus_vault_info: &AccountInfo<'a>,
+    destination_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    pending_infos: &[AccountInfo],
+    accrued: u64,
+    carried: u64,
+) -> ProgramResult {
+    let owed = accrued.saturating_add(carried);
+    if owed == 0 {
+        return Ok(());
+    }
+
+    let bonus_vault_seeds: &[&[u8]] = &[
+        BONUS_VAULT_SEED,
+        pool.as_ref(),
+        &[policy.bonus_vault_bump_seed],
+    ];
+    let expected_bonus_vault = Pubkey::create_program_address(bonus_vault_seeds, program_id)
+        .map_err(|_| RewardPoolError::InvalidBonusVaultAccount)?;
+    if *bonus_vault_info.key != expected_bonus_vault || *token_program_info.key != spl_token::id() {
+        return Err(RewardPoolError::InvalidBonusVaultAccount.into());
+    }
+
+    let interest = owed.min(token_balance(bonus_vault_info)?);
+    let shortfall = owed - interest;
+    if interest > 0 {
+        invoke_signed(
+            &token_instruction::transfer(
+                token_program_info.key,
+                bonus_vault_info.key,
+                destination_info.key,
+                bonus_vault_info.key,
+                &[],
+                interest,
+            )?,
+            &[
+                bonus_vault_info.clone(),
+                destination_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[bonus_vault_seeds],
+        )?;
+    }
+
+    settle_pending_interest(pending_infos, interest)?;
+
+    policy.total_paid = policy.total_paid.saturating_add(interest);
+    policy.total_shortfall = policy
+        .total_shortfall
+        .saturating_sub(carried)
+        .saturating_add(shortfall);
+    policy.claim_count = policy
+        .claim_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    policy.save(policy_info)?;
+
+    RewardPoolEvent::InterestPaid {
+        pool: *pool,
+        farmer: *farmer,
+        interest,
+        shortfall,
+    }
+    .emit();
+
+    msg!("Paid {} interest, {} short", interest, shortfall);
+    Ok(())
+}
+
+/// Takes `paid` off the interest owed in `pending_infos`, in order, which
+/// `mark_pending_claimed` has checked and booked the claim's interest in
+fn settle_pending_interest(pending_infos: &[AccountInfo], paid: u64) -> ProgramResult {
+    let mut left = paid;
+    for pending_info in pending_infos {
+        if left == 0 {
+            break;
+        }
+        let mut pending = PendingReward::try_from_slice(&pending_info.data.borrow())
+            .map_err(|_| RewardPoolError::InvalidPendingRewardAccount)?;
+        let settled = pending.interest_owed.min(left);
+        if settled == 0 {
+            continue;
+        }
+        pending.interest_owed -= settled;
+        left -= settled;
+        pending.serialize(&mut &mut pending_info.data.borrow_mut()[..])?;
+    }
+    Ok(())
+}
+
+fn load_interest_policy(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    policy_info: &AccountInfo,
+) -> Result<InterestPolicy, ProgramError> {
+    if policy_info.owner != program_id {
+        return Err(RewardPoolError::InvalidInterestPolicyAccount.into());
+    }
+    let policy = InterestPolicy::try_from_slice(&policy_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidInterestPolicyAccount)?;
+    let expected_policy = Pubkey::create_program_address(
+        &[INTEREST_POLICY_SEED, pool.as_ref(), &[policy.bump_seed]],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidInterestPolicyAccount)?;
+    if policy.pool != *pool || *policy_info.key != expected_policy {
+        return Err(RewardPoolError::InvalidInterestPolicyAccount.into());
+    }
+    Ok(policy)
+}
@@ -0,0 +1,259 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{
+    buyback::read_buyback, factory::read_factory_record, rebate::read_fee_rebate,
+    treasury::load_pool, utils::create_pda_account, withdraw_burn::read_withdraw_burn,
+    withdrawal_fee::read_withdrawal_fee,
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_fee_invoice_address_with_program_id, find_fee_ledger_address_with_program_id,
+        FEE_INVOICE_SEED, FEE_LEDGER_SEED,
+    },
+    state::{FeeInvoice, FeeLedger, FeeTotals, Sequenced},
+    views::FeeInvoiceReport,
+};
+
+// Closing the pool's fee invoice for the current epoch
+pub(super) fn process_close_fee_invoice(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let ledger_info = next_account_info(account_info_iter)?;
+    let invoice_info = next_account_info(account_info_iter)?;
+    let withdrawal_fee_info = next_account_info(account_info_iter)?;
+    let withdraw_burn_info = next_account_info(account_info_iter)?;
+    let buyback_info = next_account_info(account_info_iter)?;
+    let fee_rebate_info = next_account_info(account_info_iter)?;
+    let factory_record_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+
+    let (expected_ledger, ledger_bump_seed) =
+        find_fee_ledger_address_with_program_id(pool_info.key, program_id);
+    if *ledger_info.key != expected_ledger {
+        return Err(RewardPoolError::InvalidFeeLedgerAccount.into());
+    }
+    let clock = Clock::get()?;
+    let mut ledger = if ledger_info.data_is_empty() {
+        create_pda_account(
+            payer_info,
+            ledger_info,
+            system_program_info,
+            program_id,
+            FeeLedger::LEN,
+            &[FEE_LEDGER_SEED, pool_info.key.as_ref(), &[ledger_bump_seed]],
+        )?;
+        FeeLedger {
+            pool: *pool_info.key,
+            invoice_count: 0,
+            last_epoch: 0,
+            last_slot: 0,
+            last_closed_at: 0,
+            closed_totals: FeeTotals::default(),
+            last_seal: [0; 32],
+            bump_seed: ledger_bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_fee_ledger(program_id, pool_info.key, ledger_info)?
+    };
+    if ledger.invoice_count > 0 && clock.epoch <= ledger.last_epoch {
+        return Err(RewardPoolError::FeeInvoiceNotDue.into());
+    }
+
+    let number = ledger.invoice_count;
+    let number_bytes = number.to_le_bytes();
+    let (expected_invoice, invoice_bump_seed) =
+        find_fee_invoice_address_with_program_id(pool_info.key, number, program_id);
+    if *invoice_info.key != expected_invoice || !invoice_info.data_is_empty() {
+        return Err(RewardPoolError::InvalidFeeInvoiceAccount.into());
+    }
+
+    // Every category is a running total of the pool, so the invoice holds
+    // what each grew by since the last close
+    let totals = FeeTotals {
+        platform_fees: pool_data.total_platform_fees_collected,
+        withdrawal_fee_lamports: read_withdrawal_fee(
+            program_id,
+            pool_info.key,
+            withdrawal_fee_info,
+        )?
+        .map_or(0, |withdrawal_fee| withdrawal_fee.total_collected),
+        withdraw_burned: read_withdraw_burn(program_id, pool_info.key, withdraw_burn_info)?
+            .map_or(0, |withdraw_burn| withdraw_burn.total_burned),
+        buyback_burned: read_buyback(program_id, pool_info.key, buyback_info)?
+            .map_or(0, |buyback| buyback.total_burned),
+        fee_rebates: read_fee_rebate(program_id, pool_info.key, fee_rebate_info)?
+            .map_or(0, |fee_rebate| fee_rebate.total_rebated),
+        protocol_fees: read_factory_record(program_id, pool_info.key, factory_record_info)?
+            .map_or(0, |record| record.total_protocol_fees),
+    };
+
+    let mut invoice = FeeInvoice {
+        pool: *pool_info.key,
+        number,
+        epoch: clock.epoch,
+        start_slot: ledger.last_slot,
+        start_at: ledger.last_closed_at,
+        end_slot: clock.slot,
+        end_at: clock.unix_timestamp,
+        fees: totals.since(&ledger.closed_totals),
+        previous_seal: ledger.last_seal,
+        seal: [0; 32],
+        bump_seed: invoice_bump_seed,
+        sequence: 0,
+    };
+    invoice.seal = invoice.compute_seal();
+
+    create_pda_account(
+        payer_info,
+        invoice_info,
+        system_program_info,
+        program_id,
+        FeeInvoice::LEN,
+        &[
+            FEE_INVOICE_SEED,
+            pool_info.key.as_ref(),
+            &number_bytes,
+            &[invoice_bump_seed],
+        ],
+    )?;
+    invoice.save(invoice_info)?;
+
+    ledger.invoice_count = number
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    ledger.last_epoch = clock.epoch;
+    ledger.last_slot = clock.slot;
+    ledger.last_closed_at = clock.unix_timestamp;
+    ledger.closed_totals = totals;
+    ledger.last_seal = invoice.seal;
+    ledger.save(ledger_info)?;
+
+    RewardPoolEvent::FeeInvoiceClosed {
+        pool: *pool_info.key,
+        invoice: *invoice_info.key,
+        number,
+        epoch: clock.epoch,
+        fees: invoice.fees,
+        seal: invoice.seal,
+    }
+    .emit();
+
+    msg!(
+        "Closed fee invoice {} for epoch {} with {} in platform fees",
+        number,
+        clock.epoch,
+        invoice.fees.platform_fees
+    );
+    Ok(())
+}
+
+// Reporting a fee invoice with its seal checked
+pub(super) fn process_get_fee_invoice(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    number: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pool_info = next_account_info(account_info_iter)?;
+    let invoice_info = next_account_info(account_info_iter)?;
+    let previous_invoice_info = next_account_info(account_info_iter)?;
+
+    load_pool(program_id, pool_info)?;
+
+    let invoice = load_fee_invoice(program_id, pool_info.key, number, invoice_info)?;
+
+    // The first invoice chains to a zero seal
+    let chained = match number.checked_sub(1) {
+        Some(previous_number) => {
+            let previous_invoice = load_fee_invoice(
+                program_id,
+                pool_info.key,
+                previous_number,
+                previous_invoice_info,
+            )?;
+            invoice.previous_seal == previous_invoice.seal
+        }
+        None => invoice.previous_seal == [0; 32],
+    };
+
+    let report = FeeInvoiceReport {
+        sealed: invoice.is_sealed(),
+        chained,
+        invoice,
+    };
+
+    set_return_data(&borsh::to_vec(&report)?);
+    Ok(())
+}
+
+fn load_fee_ledger(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    ledger_info: &AccountInfo,
+) -> Result<FeeLedger, ProgramError> {
+    if ledger_info.owner != program_id {
+        return Err(RewardPoolError::InvalidFeeLedgerAccount.into());
+    }
+    let ledger = FeeLedger::try_from_slice(&ledger_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidFeeLedgerAccount)?;
+    let expected_ledger = Pubkey::create_program_address(
+        &[FEE_LEDGER_SEED, pool.as_ref(), &[ledger.bump_seed]],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidFeeLedgerAccount)?;
+    if ledger.pool != *pool || *ledger_info.key != expected_ledger {
+        return Err(RewardPoolError::InvalidFeeLedgerAccount.into());
+    }
+    Ok(ledger)
+}
+
+fn load_fee_invoice(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    number: u64,
+    invoice_info: &AccountInfo,
+) -> Result<FeeInvoice, ProgramError> {
+    if invoice_info.owner != program_id {
+        return Err(RewardPoolError::InvalidFeeInvoiceAccount.into());
+    }
+    let invoice = FeeInvoice::try_from_slice(&invoice_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidFeeInvoiceAccount)?;
+    let expected_invoice = Pubkey::create_program_address(
+        &[
+            FEE_INVOICE_SEED,
+            pool.as_ref(),
+            &number.to_le_bytes(),
+            &[invoice.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidFeeInvoiceAccount)?;
+    if invoice.pool != *pool || invoice.number != number || *invoice_info.key != expected_invoice {
+        return Err(RewardPoolError::InvalidFeeInvoiceAccount.into());
+    }
+    Ok(invoice)
+}
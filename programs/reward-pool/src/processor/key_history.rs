@@ -0,0 +1,91 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult,
+    program_error::ProgramError, pubkey::Pubkey, sysvar::Sysvar,
+};
+
+use super::utils::{create_pda_account, resize_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{find_key_history_address_with_program_id, KEY_HISTORY_SEED},
+    state::{KeyHistory, KeyRotation, Sequenced},
+};
+
+/// Appends a change of the pool's platform authority to its key history,
+/// creating or growing the account at `payer_info`'s expense
+#[allow(clippy::too_many_arguments)]
+pub(super) fn record_key_rotation<'a>(
+    program_id: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    pool: &Pubkey,
+    history_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    old_key: Pubkey,
+    new_key: Pubkey,
+    reason_hash: [u8; 32],
+) -> ProgramResult {
+    let (expected_history, bump_seed) = find_key_history_address_with_program_id(pool, program_id);
+    if *history_info.key != expected_history {
+        return Err(RewardPoolError::InvalidKeyHistoryAccount.into());
+    }
+
+    let mut history = if history_info.data_is_empty() {
+        KeyHistory {
+            pool: *pool,
+            entries: Vec::new(),
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_key_history(program_id, pool, history_info)?
+    };
+
+    let rotation = KeyRotation {
+        old_key,
+        new_key,
+        slot: Clock::get()?.slot,
+        reason_hash,
+    };
+    history.entries.push(rotation);
+
+    // The account holds exactly the entries recorded so far
+    let space = KeyHistory::len_with(history.entries.len());
+    if history_info.data_is_empty() {
+        create_pda_account(
+            payer_info,
+            history_info,
+            system_program_info,
+            program_id,
+            space,
+            &[KEY_HISTORY_SEED, pool.as_ref(), &[bump_seed]],
+        )?;
+    } else {
+        resize_pda_account(payer_info, history_info, system_program_info, space)?;
+    }
+    history.save(history_info)?;
+
+    RewardPoolEvent::KeyRotationRecorded {
+        pool: *pool,
+        index: (history.entries.len() - 1) as u32,
+        rotation,
+    }
+    .emit();
+    Ok(())
+}
+
+fn load_key_history(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    history_info: &AccountInfo,
+) -> Result<KeyHistory, ProgramError> {
+    if history_info.owner != program_id {
+        return Err(RewardPoolError::InvalidKeyHistoryAccount.into());
+    }
+    let history = KeyHistory::try_from_slice(&history_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidKeyHistoryAccount)?;
+    if history.pool != *pool {
+        return Err(RewardPoolError::InvalidKeyHistoryAccount.into());
+    }
+    Ok(history)
+}
@@ -0,0 +1,420 @@
+use super::{
+    program_treasury::transfer_from_platform_fee_vault,
+    treasury::{check_approvals, load_governance, load_pool},
+    utils::{create_pda_account, create_self_owned_token_account, token_balance},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_pol_lp_vault_address_with_program_id, find_pol_pair_vault_address_with_program_id,
+        find_pol_vault_address_with_program_id, find_protocol_liquidity_address_with_program_id,
+        POL_LP_VAULT_SEED, POL_PAIR_VAULT_SEED, POL_VAULT_SEED, PROTOCOL_LIQUIDITY_SEED,
+    },
+    state::{ProtocolLiquidity, Sequenced},
+    BASIS_POINTS,
+};
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+// Configuring protocol-owned liquidity contributions
+pub(super) fn process_set_protocol_liquidity(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amm_programs: Vec<Pubkey>,
+    pol_bps: u16,
+    min_lp_per_million: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let governance_info = next_account_info(account_info_iter)?;
+    let liquidity_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let pol_vault_info = next_account_info(account_info_iter)?;
+    let pair_vault_info = next_account_info(account_info_iter)?;
+    let lp_vault_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let pair_mint_info = next_account_info(account_info_iter)?;
+    let lp_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let approver_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let governance = load_governance(program_id, pool_info.key, governance_info)?;
+    check_approvals(&governance, approver_infos)?;
+
+    // The AMMs are handed the POL vaults' signatures, so none may be a
+    // program that would act on those signatures itself
+    let distinct = amm_programs
+        .iter()
+        .enumerate()
+        .all(|(index, amm_program)| !amm_programs[..index].contains(amm_program));
+    if amm_programs.len() > ProtocolLiquidity::MAX_AMM_PROGRAMS
+        || !distinct
+        || amm_programs
+            .iter()
+            .any(|amm_program| *amm_program == *program_id || *amm_program == spl_token::id())
+        || pol_bps > BASIS_POINTS
+    {
+        return Err(RewardPoolError::InvalidProtocolLiquidity.into());
+    }
+
+    let pair_mint = *pair_mint_info.key;
+    let lp_mint = *lp_mint_info.key;
+    if *reward_mint_info.key != pool_data.reward_mint
+        || pair_mint == pool_data.reward_mint
+        || lp_mint == pool_data.reward_mint
+        || lp_mint == pair_mint
+        || *token_program_info.key != spl_token::id()
+    {
+        return Err(RewardPoolError::InvalidProtocolLiquidity.into());
+    }
+
+    let (expected_liquidity, bump_seed) =
+        find_protocol_liquidity_address_with_program_id(pool_info.key, program_id);
+    if *liquidity_info.key != expected_liquidity {
+        return Err(RewardPoolError::InvalidProtocolLiquidityAccount.into());
+    }
+
+    let mut liquidity = if liquidity_info.data_is_empty() {
+        let (expected_pol_vault, vault_bump_seed) =
+            find_pol_vault_address_with_program_id(pool_info.key, program_id);
+        let (expected_pair_vault, pair_vault_bump_seed) =
+            find_pol_pair_vault_address_with_program_id(pool_info.key, program_id);
+        let (expected_lp_vault, lp_vault_bump_seed) =
+            find_pol_lp_vault_address_with_program_id(pool_info.key, program_id);
+        if *pol_vault_info.key != expected_pol_vault
+            || *pair_vault_info.key != expected_pair_vault
+            || *lp_vault_info.key != expected_lp_vault
+        {
+            return Err(RewardPoolError::InvalidProtocolLiquidityAccount.into());
+        }
+
+        // Like the vault, the three token accounts are their own owners
+        for (vault_info, mint_info, seed, vault_bump_seed) in [
+            (
+                pol_vault_info,
+                reward_mint_info,
+                POL_VAULT_SEED,
+                vault_bump_seed,
+            ),
+            (
+                pair_vault_info,
+                pair_mint_info,
+                POL_PAIR_VAULT_SEED,
+                pair_vault_bump_seed,
+            ),
+            (
+                lp_vault_info,
+                lp_mint_info,
+                POL_LP_VAULT_SEED,
+                lp_vault_bump_seed,
+            ),
+        ] {
+            create_self_owned_token_account(
+                platform_authority_info,
+                vault_info,
+                mint_info,
+                token_program_info,
+                system_program_info,
+                &[seed, pool_info.key.as_ref(), &[vault_bump_seed]],
+            )?;
+        }
+
+        create_pda_account(
+            platform_authority_info,
+            liquidity_info,
+            system_program_info,
+            program_id,
+            ProtocolLiquidity::LEN,
+            &[
+                PROTOCOL_LIQUIDITY_SEED,
+                pool_info.key.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        ProtocolLiquidity {
+            pool: *pool_info.key,
+            amm_program_count: 0,
+            amm_programs: [Pubkey::default(); ProtocolLiquidity::MAX_AMM_PROGRAMS],
+            pair_mint,
+            lp_mint,
+            pol_bps: 0,
+            min_lp_per_million: 0,
+            // Fees collected before contributions existed are not used
+            fees_accounted: pool_data.total_platform_fees_collected,
+            total_contributed: 0,
+            total_lp: 0,
+            contribution_count: 0,
+            last_contributed_at: 0,
+            vault_bump_seed,
+            pair_vault_bump_seed,
+            lp_vault_bump_seed,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        let liquidity = load_protocol_liquidity(program_id, pool_info.key, liquidity_info)?;
+        if liquidity.pair_mint != pair_mint || liquidity.lp_mint != lp_mint {
+            return Err(RewardPoolError::InvalidProtocolLiquidity.into());
+        }
+        liquidity
+    };
+
+    liquidity.amm_program_count = amm_programs.len() as u8;
+    liquidity.amm_programs = [Pubkey::default(); ProtocolLiquidity::MAX_AMM_PROGRAMS];
+    liquidity.amm_programs[..amm_programs.len()].copy_from_slice(&amm_programs);
+    liquidity.pol_bps = pol_bps;
+    liquidity.min_lp_per_million = min_lp_per_million;
+    liquidity.save(liquidity_info)?;
+
+    if liquidity.is_enabled() {
+        msg!(
+            "Protocol-owned liquidity enabled through {} AMMs for {} bps of fees",
+            amm_programs.len(),
+            pol_bps
+        );
+    } else {
+        msg!("Protocol-owned liquidity disabled");
+    }
+
+    RewardPoolEvent::ProtocolLiquiditySet {
+        pool: *pool_info.key,
+        amm_programs,
+        pair_mint,
+        lp_mint,
+        pol_bps,
+        min_lp_per_million,
+    }
+    .emit();
+    Ok(())
+}
+
+// Pairing a slice of the fees and depositing it as protocol-owned liquidity
+pub(super) fn process_contribute_liquidity(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    swap_data: Vec<u8>,
+    swap_account_count: u8,
+    deposit_data: Vec<u8>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let _caller_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let liquidity_info = next_account_info(account_info_iter)?;
+    let fee_vault_info = next_account_info(account_info_iter)?;
+    let pol_vault_info = next_account_info(account_info_iter)?;
+    let pair_vault_info = next_account_info(account_info_iter)?;
+    let lp_vault_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let _system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let amm_program_info = next_account_info(account_info_iter)?;
+    let amm_infos = account_info_iter.as_slice();
+    let (swap_infos, deposit_infos) = amm_infos
+        .split_at_checked(swap_account_count as usize)
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    // Validations
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    let mut liquidity = load_protocol_liquidity(program_id, pool_info.key, liquidity_info)?;
+    if !liquidity.is_enabled() {
+        return Err(RewardPoolError::ProtocolLiquidityDisabled.into());
+    }
+    if !liquidity.amm_programs().contains(amm_program_info.key) {
+        return Err(RewardPoolError::InvalidDexProgram.into());
+    }
+
+    let pol_vault_seeds: &[&[u8]] = &[
+        POL_VAULT_SEED,
+        pool_info.key.as_ref(),
+        &[liquidity.vault_bump_seed],
+    ];
+    let pair_vault_seeds: &[&[u8]] = &[
+        POL_PAIR_VAULT_SEED,
+        pool_info.key.as_ref(),
+        &[liquidity.pair_vault_bump_seed],
+    ];
+    let expected_pol_vault = Pubkey::create_program_address(pol_vault_seeds, program_id)
+        .map_err(|_| RewardPoolError::InvalidProtocolLiquidityAccount)?;
+    let expected_pair_vault = Pubkey::create_program_address(pair_vault_seeds, program_id)
+        .map_err(|_| RewardPoolError::InvalidProtocolLiquidityAccount)?;
+    let expected_lp_vault = Pubkey::create_program_address(
+        &[
+            POL_LP_VAULT_SEED,
+            pool_info.key.as_ref(),
+            &[liquidity.lp_vault_bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidProtocolLiquidityAccount)?;
+    if *pol_vault_info.key != expected_pol_vault
+        || *pair_vault_info.key != expected_pair_vault
+        || *lp_vault_info.key != expected_lp_vault
+        || *token_program_info.key != spl_token::id()
+    {
+        return Err(RewardPoolError::InvalidProtocolLiquidityAccount.into());
+    }
+
+    // Fund the contribution with its slice of the fees collected since the
+    // last one, out of the fee vault like buybacks
+    let new_fees = pool_data
+        .total_platform_fees_collected
+        .saturating_sub(liquidity.fees_accounted);
+    let allowance = (new_fees as u128 * liquidity.pol_bps as u128 / BASIS_POINTS as u128) as u64;
+    transfer_from_platform_fee_vault(
+        program_id,
+        pool_info.key,
+        fee_vault_info,
+        pol_vault_info,
+        token_program_info,
+        allowance,
+    )?;
+
+    let pol_before = token_balance(pol_vault_info)?;
+    let lp_before = token_balance(lp_vault_info)?;
+    if pol_before == 0 {
+        return Err(RewardPoolError::InsufficientAmount.into());
+    }
+
+    // Only the POL and pair vaults are signed for; the AMM sees nothing
+    // else of the pool's
+    let signed_keys = [*pol_vault_info.key, *pair_vault_info.key];
+    let signer_seeds = [pol_vault_seeds, pair_vault_seeds];
+    if !swap_data.is_empty() {
+        invoke_amm(
+            amm_program_info,
+            swap_infos,
+            swap_data,
+            &signed_keys,
+            &signer_seeds,
+        )?;
+    }
+    let pair_before = token_balance(pair_vault_info)?;
+    invoke_amm(
+        amm_program_info,
+        deposit_infos,
+        deposit_data,
+        &signed_keys,
+        &signer_seeds,
+    )?;
+
+    let contributed = pol_before.saturating_sub(token_balance(pol_vault_info)?);
+    let paired = pair_before.saturating_sub(token_balance(pair_vault_info)?);
+    let lp_received = token_balance(lp_vault_info)?.saturating_sub(lp_before);
+    if lp_received == 0
+        || (lp_received as u128) * 1_000_000
+            < (contributed as u128) * (liquidity.min_lp_per_million as u128)
+    {
+        return Err(RewardPoolError::ProtocolLiquiditySlippageExceeded.into());
+    }
+
+    liquidity.fees_accounted = pool_data.total_platform_fees_collected;
+    liquidity.total_contributed = liquidity
+        .total_contributed
+        .checked_add(contributed)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    liquidity.total_lp = liquidity
+        .total_lp
+        .checked_add(lp_received)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    liquidity.contribution_count = liquidity
+        .contribution_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    liquidity.last_contributed_at = Clock::get()?.unix_timestamp;
+    liquidity.save(liquidity_info)?;
+
+    RewardPoolEvent::LiquidityContributed {
+        pool: *pool_info.key,
+        amm_program: *amm_program_info.key,
+        contributed,
+        paired,
+        lp_received,
+    }
+    .emit();
+
+    msg!(
+        "Contributed {} and {} paired for {} LP tokens",
+        contributed,
+        paired,
+        lp_received
+    );
+    Ok(())
+}
+
+/// Passes `data` and `infos` through to the AMM, the program signing for
+/// the accounts in `signed_keys`
+fn invoke_amm<'a>(
+    amm_program_info: &AccountInfo<'a>,
+    infos: &[AccountInfo<'a>],
+    data: Vec<u8>,
+    signed_keys: &[Pubkey],
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let instruction = Instruction {
+        program_id: *amm_program_info.key,
+        accounts: infos
+            .iter()
+            .map(|info| AccountMeta {
+                pubkey: *info.key,
+                is_signer: info.is_signer || signed_keys.contains(info.key),
+                is_writable: info.is_writable,
+            })
+            .collect(),
+        data,
+    };
+    let mut account_infos = infos.to_vec();
+    account_infos.push(amm_program_info.clone());
+    invoke_signed(&instruction, &account_infos, signer_seeds)
+}
+
+fn load_protocol_liquidity(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    liquidity_info: &AccountInfo,
+) -> Result<ProtocolLiquidity, ProgramError> {
+    if liquidity_info.owner != program_id {
+        return Err(RewardPoolError::InvalidProtocolLiquidityAccount.into());
+    }
+    let liquidity = ProtocolLiquidity::try_from_slice(&liquidity_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidProtocolLiquidityAccount)?;
+    let expected_liquidity = Pubkey::create_program_address(
+        &[
+            PROTOCOL_LIQUIDITY_SEED,
+            pool.as_ref(),
+            &[liquidity.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidProtocolLiquidityAccount)?;
+    if liquidity.pool != *pool || *liquidity_info.key != expected_liquidity {
+        return Err(RewardPoolError::InvalidProtocolLiquidityAccount.into());
+    }
+    Ok(liquidity)
+}
@@ -0,0 +1,121 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::{
+    treasury::load_pool,
+    utils::{create_pda_account, resize_pda_account},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{find_pool_metadata_address_with_program_id, POOL_METADATA_SEED},
+    state::{PoolMetadata, Sequenced},
+};
+
+// Setting the pool metadata
+pub(super) fn process_set_pool_metadata(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    name: String,
+    uri: String,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    store_metadata(
+        program_id,
+        platform_authority_info,
+        pool_info,
+        metadata_info,
+        system_program_info,
+        name,
+        uri,
+    )
+}
+
+/// Validates and writes a pool's metadata, creating or resizing the account
+/// at the expense of `payer`
+pub(super) fn store_metadata<'a>(
+    program_id: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    pool_info: &AccountInfo<'a>,
+    metadata_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    name: String,
+    uri: String,
+) -> ProgramResult {
+    if name.len() > PoolMetadata::MAX_NAME_LEN || uri.len() > PoolMetadata::MAX_URI_LEN {
+        return Err(RewardPoolError::MetadataTooLong.into());
+    }
+
+    let (expected_metadata, bump_seed) =
+        find_pool_metadata_address_with_program_id(pool_info.key, program_id);
+    if *metadata_info.key != expected_metadata {
+        return Err(RewardPoolError::InvalidMetadataAccount.into());
+    }
+
+    let mut metadata = if metadata_info.data_is_empty() {
+        PoolMetadata {
+            pool: *pool_info.key,
+            name,
+            uri,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        if metadata_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let metadata = PoolMetadata::try_from_slice(&metadata_info.data.borrow())?;
+        PoolMetadata {
+            name,
+            uri,
+            ..metadata
+        }
+    };
+
+    // The account holds exactly the current name and URI
+    let space = borsh::object_length(&metadata)?;
+    if metadata_info.data_is_empty() {
+        create_pda_account(
+            payer_info,
+            metadata_info,
+            system_program_info,
+            program_id,
+            space,
+            &[POOL_METADATA_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+    } else {
+        resize_pda_account(payer_info, metadata_info, system_program_info, space)?;
+    }
+    metadata.save(metadata_info)?;
+
+    RewardPoolEvent::PoolMetadataUpdated {
+        pool: *pool_info.key,
+        name: metadata.name,
+        uri: metadata.uri,
+    }
+    .emit();
+
+    msg!("Pool metadata updated");
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{
+    pending::create_pending_reward, program_treasury::check_platform_treasury, treasury::load_pool,
+    utils::create_pda_account,
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    merkle::verify_balance_proof,
+    seeds::{
+        find_imported_balance_address_with_program_id, find_state_export_address_with_program_id,
+        IMPORTED_BALANCE_SEED, STATE_EXPORT_SEED,
+    },
+    state::{ImportedBalance, PendingReward, RewardOrigin, Sequenced, StateExport},
+};
+
+// Exporting the pool state
+pub(super) fn process_export_state(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    merkle_root: [u8; 32],
+    farmer_count: u32,
+    total_amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let state_export_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let mut pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let (expected_export, bump_seed) =
+        find_state_export_address_with_program_id(pool_info.key, program_id);
+    if *state_export_info.key != expected_export || !state_export_info.data_is_empty() {
+        return Err(RewardPoolError::InvalidStateExportAccount.into());
+    }
+
+    create_pda_account(
+        platform_authority_info,
+        state_export_info,
+        system_program_info,
+        program_id,
+        StateExport::LEN,
+        &[STATE_EXPORT_SEED, pool_info.key.as_ref(), &[bump_seed]],
+    )?;
+
+    StateExport {
+        pool: *pool_info.key,
+        reward_mint: pool_data.reward_mint,
+        exported_by: pool_data.platform_authority,
+        merkle_root,
+        farmer_count,
+        total_amount,
+        exported_at: Clock::get()?.unix_timestamp,
+        bump_seed,
+        sequence: 0,
+    }
+    .save(state_export_info)?;
+
+    // No key signs for the export account, so the pool can never be resumed
+    // or administered again
+    let old_platform_authority = pool_data.platform_authority;
+    pool_data.is_paused = true;
+    pool_data.platform_authority = expected_export;
+    pool_data.save(pool_info)?;
+
+    RewardPoolEvent::PoolPaused {
+        pool: *pool_info.key,
+    }
+    .emit();
+    RewardPoolEvent::PlatformAuthorityChanged {
+        pool: *pool_info.key,
+        old_platform_authority,
+        new_platform_authority: expected_export,
+    }
+    .emit();
+    RewardPoolEvent::StateExported {
+        pool: *pool_info.key,
+        merkle_root,
+        farmer_count,
+        total_amount,
+    }
+    .emit();
+
+    msg!(
+        "State exported: {} tokens owed to {} farmers",
+        total_amount,
+        farmer_count
+    );
+    Ok(())
+}
+
+// Importing a farmer balance from a previous deployment
+pub(super) fn process_import_state(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    source_program_id: Pubkey,
+    farmer_pubkey: Pubkey,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let state_export_info = next_account_info(account_info_iter)?;
+    let imported_balance_info = next_account_info(account_info_iter)?;
+    let platform_treasury_info = next_account_info(account_info_iter)?;
+    let pending_info = next_account_info(account_info_iter)?;
+    let _reward_mint_info = next_account_info(account_info_iter)?;
+    let _token_program_info = next_account_info(account_info_iter)?;
+    let _ata_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let mut pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    check_platform_treasury(
+        program_id,
+        pool_info.key,
+        &pool_data,
+        platform_treasury_info,
+    )?;
+
+    // The export must have been written by the previous deployment itself,
+    // for a pool of the same reward token
+    if source_program_id == *program_id || *state_export_info.owner != source_program_id {
+        return Err(RewardPoolError::InvalidStateExportAccount.into());
+    }
+    let state_export = StateExport::try_from_slice(&state_export_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidStateExportAccount)?;
+    let (expected_export, _) =
+        find_state_export_address_with_program_id(&state_export.pool, &source_program_id);
+    if *state_export_info.key != expected_export
+        || state_export.reward_mint != pool_data.reward_mint
+    {
+        return Err(RewardPoolError::InvalidStateExportAccount.into());
+    }
+
+    if !verify_balance_proof(&state_export.merkle_root, &farmer_pubkey, amount, &proof) {
+        return Err(RewardPoolError::InvalidBalanceProof.into());
+    }
+
+    let (expected_imported_balance, bump_seed) =
+        find_imported_balance_address_with_program_id(pool_info.key, &farmer_pubkey, program_id);
+    if *imported_balance_info.key != expected_imported_balance {
+        return Err(RewardPoolError::InvalidImportedBalanceAccount.into());
+    }
+    if !imported_balance_info.data_is_empty() {
+        return Err(RewardPoolError::BalanceAlreadyImported.into());
+    }
+
+    create_pda_account(
+        platform_authority_info,
+        imported_balance_info,
+        system_program_info,
+        program_id,
+        ImportedBalance::LEN,
+        &[
+            IMPORTED_BALANCE_SEED,
+            pool_info.key.as_ref(),
+            farmer_pubkey.as_ref(),
+            &[bump_seed],
+        ],
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    ImportedBalance {
+        pool: *pool_info.key,
+        farmer: farmer_pubkey,
+        source_pool: state_export.pool,
+        amount,
+        imported_at: now,
+        bump_seed,
+        sequence: 0,
+    }
+    .save(imported_balance_info)?;
+
+    // The balance is owed from the treasury vault like any reward, booked
+    // as a pending reward the farmer withdraws or claims
+    create_pending_reward(
+        program_id,
+        pool_info.key,
+        pending_info,
+        platform_authority_info,
+        system_program_info,
+        &PendingReward {
+            farmer_pubkey,
+            amount,
+            task_id: ImportedBalance::TASK_ID.to_string(),
+            recorded_at: now,
+            is_withdrawn: false,
+            campaign_id: None,
+            platform_id: None,
+            proof_hash: None,
+            is_challenged: false,
+            awaiting_approval: false,
+            origin: RewardOrigin::Import,
+            interest_owed: 0,
+        },
+    )?;
+    pool_data.outstanding_rewards = pool_data
+        .outstanding_rewards
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    pool_data.save(pool_info)?;
+
+    RewardPoolEvent::BalanceImported {
+        pool: *pool_info.key,
+        source_program_id,
+        source_pool: state_export.pool,
+        farmer: farmer_pubkey,
+        amount,
+    }
+    .emit();
+
+    msg!(
+        "Balance imported: {} tokens for farmer {}",
+        amount,
+        farmer_pubkey
+    );
+    Ok(())
+}
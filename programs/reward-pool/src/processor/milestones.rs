@@ -0,0 +1,283 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::{pending::create_pending_reward, treasury::load_pool, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_farmer_stats_address_with_program_id, find_milestone_bonuses_address_with_program_id,
+        FARMER_STATS_SEED, MILESTONE_BONUSES_SEED,
+    },
+    state::{FarmerStats, Milestone, MilestoneBonuses, PendingReward, RewardOrigin, Sequenced},
+};
+
+// Setting the milestone bonuses of a pool
+pub(super) fn process_set_milestone_bonuses(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    milestones: Vec<Milestone>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let bonuses_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let increasing = milestones
+        .windows(2)
+        .all(|pair| pair[0].threshold < pair[1].threshold);
+    if milestones.len() > MilestoneBonuses::MAX_MILESTONES
+        || !increasing
+        || milestones
+            .iter()
+            .any(|milestone| milestone.threshold == 0 || milestone.bonus == 0)
+    {
+        return Err(RewardPoolError::InvalidMilestones.into());
+    }
+
+    let (expected_bonuses, bump_seed) =
+        find_milestone_bonuses_address_with_program_id(pool_info.key, program_id);
+    if *bonuses_info.key != expected_bonuses {
+        return Err(RewardPoolError::InvalidMilestoneBonusesAccount.into());
+    }
+
+    let mut bonuses = if bonuses_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            bonuses_info,
+            system_program_info,
+            program_id,
+            MilestoneBonuses::LEN,
+            &[MILESTONE_BONUSES_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        MilestoneBonuses {
+            pool: *pool_info.key,
+            milestone_count: 0,
+            milestones: [Milestone::default(); MilestoneBonuses::MAX_MILESTONES],
+            total_bonuses: 0,
+            bonus_count: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_milestone_bonuses(program_id, pool_info.key, bonuses_info)?
+    };
+
+    bonuses.milestone_count = milestones.len() as u8;
+    bonuses.milestones = [Milestone::default(); MilestoneBonuses::MAX_MILESTONES];
+    bonuses.milestones[..milestones.len()].copy_from_slice(&milestones);
+    bonuses.save(bonuses_info)?;
+
+    msg!("Set {} milestone bonuses", milestones.len());
+
+    RewardPoolEvent::MilestoneBonusesSet {
+        pool: *pool_info.key,
+        milestones,
+    }
+    .emit();
+    Ok(())
+}
+
+/// Adds `earned` to the farmer's lifetime earnings when the pool has
+/// milestone bonuses, and books the bonus of every milestone this takes them
+/// past as a single pending reward, paid from the treasury vault when
+/// claimed. Returns the bonus booked, 0 for pools without milestones, which
+/// need neither the farmer's stats nor the bonus's pending reward.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn track_farmer_milestones<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    bonuses_info: &AccountInfo<'a>,
+    farmer_stats_info: Option<&AccountInfo<'a>>,
+    bonus_pending_info: Option<&AccountInfo<'a>>,
+    platform_authority_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    earned: u64,
+    awaiting_approval: bool,
+    now: i64,
+) -> Result<u64, ProgramError> {
+    let (expected_bonuses, _) = find_milestone_bonuses_address_with_program_id(pool, program_id);
+    if *bonuses_info.key != expected_bonuses {
+        return Err(RewardPoolError::InvalidMilestoneBonusesAccount.into());
+    }
+    if bonuses_info.data_is_empty() {
+        return Ok(0);
+    }
+    let mut bonuses = load_milestone_bonuses(program_id, pool, bonuses_info)?;
+    if bonuses.milestone_count == 0 {
+        return Ok(0);
+    }
+
+    let farmer_stats_info = farmer_stats_info.ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let (expected_stats, bump_seed) =
+        find_farmer_stats_address_with_program_id(pool, farmer, program_id);
+    if *farmer_stats_info.key != expected_stats {
+        return Err(RewardPoolError::InvalidFarmerStatsAccount.into());
+    }
+    let mut farmer_stats = if farmer_stats_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            farmer_stats_info,
+            system_program_info,
+            program_id,
+            FarmerStats::LEN,
+            &[
+                FARMER_STATS_SEED,
+                pool.as_ref(),
+                farmer.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        FarmerStats {
+            pool: *pool,
+            farmer: *farmer,
+            lifetime_earned: 0,
+            milestones_reached: 0,
+            total_bonuses: 0,
+            last_milestone_at: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_farmer_stats(program_id, pool, farmer, farmer_stats_info)?
+    };
+
+    farmer_stats.lifetime_earned = farmer_stats.lifetime_earned.saturating_add(earned);
+
+    let reached: Vec<Milestone> = bonuses
+        .milestones()
+        .iter()
+        .skip(farmer_stats.milestones_reached as usize)
+        .take_while(|milestone| farmer_stats.lifetime_earned >= milestone.threshold)
+        .copied()
+        .collect();
+    let mut booked = 0;
+    if !reached.is_empty() {
+        let bonus = reached
+            .iter()
+            .try_fold(0u64, |total, milestone| total.checked_add(milestone.bonus))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        let bonus_pending_info = bonus_pending_info.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        create_pending_reward(
+            program_id,
+            pool,
+            bonus_pending_info,
+            platform_authority_info,
+            system_program_info,
+            &PendingReward {
+                farmer_pubkey: *farmer,
+                amount: bonus,
+                task_id: MilestoneBonuses::task_id(farmer_stats.milestones_reached),
+                recorded_at: now,
+                is_withdrawn: false,
+                campaign_id: None,
+                platform_id: None,
+                proof_hash: None,
+                is_challenged: false,
+                awaiting_approval,
+                origin: RewardOrigin::Milestone,
+                interest_owed: 0,
+            },
+        )?;
+
+        farmer_stats.milestones_reached += reached.len() as u8;
+        farmer_stats.total_bonuses = farmer_stats.total_bonuses.saturating_add(bonus);
+        farmer_stats.last_milestone_at = now;
+        bonuses.total_bonuses = bonuses.total_bonuses.saturating_add(bonus);
+        bonuses.bonus_count = bonuses
+            .bonus_count
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        bonuses.save(bonuses_info)?;
+
+        RewardPoolEvent::MilestoneReached {
+            pool: *pool,
+            farmer: *farmer,
+            milestones_reached: farmer_stats.milestones_reached,
+            bonus,
+            lifetime_earned: farmer_stats.lifetime_earned,
+            pending_reward: *bonus_pending_info.key,
+        }
+        .emit();
+
+        msg!(
+            "Farmer reached milestone {}, booked {} bonus",
+            farmer_stats.milestones_reached,
+            bonus
+        );
+        booked = bonus;
+    }
+
+    farmer_stats.save(farmer_stats_info)?;
+    Ok(booked)
+}
+
+fn load_milestone_bonuses(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    bonuses_info: &AccountInfo,
+) -> Result<MilestoneBonuses, ProgramError> {
+    if bonuses_info.owner != program_id {
+        return Err(RewardPoolError::InvalidMilestoneBonusesAccount.into());
+    }
+    let bonuses = MilestoneBonuses::try_from_slice(&bonuses_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidMilestoneBonusesAccount)?;
+    let expected_bonuses = Pubkey::create_program_address(
+        &[MILESTONE_BONUSES_SEED, pool.as_ref(), &[bonuses.bump_seed]],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidMilestoneBonusesAccount)?;
+    if bonuses.pool != *pool || *bonuses_info.key != expected_bonuses {
+        return Err(RewardPoolError::InvalidMilestoneBonusesAccount.into());
+    }
+    Ok(bonuses)
+}
+
+fn load_farmer_stats(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    farmer_stats_info: &AccountInfo,
+) -> Result<FarmerStats, ProgramError> {
+    if farmer_stats_info.owner != program_id {
+        return Err(RewardPoolError::InvalidFarmerStatsAccount.into());
+    }
+    let farmer_stats = FarmerStats::try_from_slice(&farmer_stats_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidFarmerStatsAccount)?;
+    let expected_stats = Pubkey::create_program_address(
+        &[
+            FARMER_STATS_SEED,
+            pool.as_ref(),
+            farmer.as_ref(),
+            &[farmer_stats.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidFarmerStatsAccount)?;
+    if farmer_stats.pool != *pool
+        || farmer_stats.farmer != *farmer
+        || *farmer_stats_info.key != expected_stats
+    {
+        return Err(RewardPoolError::InvalidFarmerStatsAccount.into());
+    }
+    Ok(farmer_stats)
+}
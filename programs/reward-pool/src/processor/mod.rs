@@ -0,0 +1,765 @@
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+
+use crate::{
+    debug::{self, debug_log},
+    instruction::{v2::RewardPoolInstruction, VersionedInstruction},
+};
+
+mod actions;
+mod approval;
+mod attestation;
+mod batch;
+mod budget;
+mod buyback;
+mod campaign;
+mod challenge;
+mod claim_shard;
+mod counters;
+mod donation;
+mod dormancy;
+mod factory;
+mod farmer;
+mod farmer_recovery;
+mod fees;
+mod governor;
+mod guardians;
+mod guild;
+mod interest;
+mod invoice;
+mod key_history;
+mod liquidity;
+mod metadata;
+mod migration;
+mod milestones;
+mod parameters;
+mod pending;
+mod platform;
+mod pool;
+mod program_treasury;
+mod queue;
+mod ramp;
+mod rebate;
+mod receipt;
+mod reward;
+mod reward_rate;
+mod secondary;
+mod snapshot;
+mod stats;
+mod treasury;
+mod treasury_swap;
+mod upgrade;
+mod utils;
+mod verifier;
+mod view;
+mod voucher;
+mod whitelist;
+mod withdraw_burn;
+mod withdrawal_fee;
+
+use actions::{process_cancel_action, process_execute_action, process_schedule_action};
+use approval::{process_approve_reward, process_set_reward_approver};
+use attestation::process_set_attestor;
+use batch::{process_pause_all, process_set_fee_all};
+use budget::{process_lock_task_budget, process_release_unused_budget};
+use buyback::{process_configure_buyback, process_run_buyback};
+use campaign::{process_create_campaign, process_end_campaign, process_finalize_campaign};
+use challenge::{process_challenge_reward, process_resolve_challenge};
+use claim_shard::process_consolidate_claim_shards;
+use counters::{note_authority_action, InstructionCounter};
+use donation::{process_donate_reward, process_set_charity_account};
+use dormancy::{process_claim_dormant_authority, process_set_recovery_authority};
+use factory::{process_create_pool, process_set_protocol_config};
+use farmer::{
+    process_close_farmer_accounts, process_set_auto_claim, process_set_auto_forward,
+    process_set_withdrawal_cosigner,
+};
+use farmer_recovery::{
+    process_cancel_farmer_recovery, process_complete_farmer_recovery,
+    process_initiate_farmer_recovery, process_set_recovery_guardians,
+};
+use fees::{process_set_fee_discounts, process_set_task_type_fee};
+use governor::process_set_reward_governor;
+use guardians::process_set_guardians;
+use guild::{
+    process_distribute_guild_rewards, process_leave_guild, process_set_guild_members,
+    process_set_guild_split,
+};
+use interest::process_set_interest_policy;
+use invoice::{process_close_fee_invoice, process_get_fee_invoice};
+use liquidity::{process_contribute_liquidity, process_set_protocol_liquidity};
+use metadata::process_set_pool_metadata;
+use migration::{process_export_state, process_import_state};
+use milestones::process_set_milestone_bonuses;
+use parameters::{process_rollback_parameter, process_update_parameters};
+use pending::{process_set_pending_transfer_policy, process_transfer_pending};
+use platform::{
+    process_initialize_platform_pool, process_register_platform, process_set_platform_operator,
+};
+use pool::{
+    process_archive_pool, process_close_pool, process_initialize_pool, process_pause_pool,
+    process_resume_pool, process_update_platform_fee,
+};
+use program_treasury::process_fund_treasury;
+use queue::{process_set_payout_queue, process_take_payout_ticket};
+use ramp::process_resume_with_ramp;
+use rebate::process_set_fee_rebate;
+use receipt::process_close_withdrawal_records;
+use reward::{
+    process_claim_across_pools, process_claim_many, process_crank_auto_claim,
+    process_record_reward, process_sweep_delegated_rewards, process_withdraw_reward,
+};
+use reward_rate::process_get_reward_rate;
+use secondary::process_set_secondary_mint;
+use snapshot::process_export_snapshot;
+use treasury::{
+    process_approve_treasury_withdrawal, process_configure_treasury_governance,
+    process_execute_treasury_withdrawal, process_propose_treasury_withdrawal,
+};
+use treasury_swap::process_set_treasury_swap_policy;
+use upgrade::process_set_upgrade_authority;
+use verifier::process_set_verifier;
+use view::{process_get_farmer_pending, process_get_pool_stats, process_get_withdrawal_history};
+use voucher::{process_issue_voucher, process_redeem_voucher};
+use whitelist::{
+    process_add_withdrawal_destination, process_register_payout_address,
+    process_remove_withdrawal_destination,
+};
+use withdraw_burn::process_set_withdraw_burn;
+use withdrawal_fee::{process_collect_withdrawal_fees, process_set_withdrawal_fee};
+
+// Main instruction processing function
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = VersionedInstruction::unpack(instruction_data)?.into_latest();
+    debug_log!("Decoded {:?}", instruction);
+    debug::log_accounts(accounts);
+    let counter = InstructionCounter::of(&instruction);
+
+    debug::log_compute_units("dispatch");
+    let result = dispatch(program_id, accounts, instruction);
+    debug::log_compute_units("dispatch end");
+    result.inspect_err(debug::log_failure)?;
+
+    // Only successful executions are counted
+    if let Some(counter) = counter {
+        note_authority_action(program_id, accounts)?;
+        counter.record(program_id, accounts)?;
+    }
+    Ok(())
+}
+
+fn dispatch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction: RewardPoolInstruction,
+) -> ProgramResult {
+    match instruction {
+        RewardPoolInstruction::InitializePool {
+            platform_fee_percentage,
+            name,
+            uri,
+        } => {
+            msg!("Instruction: InitializePool");
+            process_initialize_pool(program_id, accounts, platform_fee_percentage, name, uri)
+        }
+        RewardPoolInstruction::RecordReward {
+            amount,
+            farmer_pubkey,
+            task_id,
+            campaign_id,
+            platform_id,
+            proof_hash,
+            task_type,
+            secondary_amount,
+            idempotency_key,
+        } => {
+            msg!("Instruction: RecordReward");
+            process_record_reward(
+                program_id,
+                accounts,
+                amount,
+                farmer_pubkey,
+                task_id,
+                campaign_id,
+                platform_id,
+                proof_hash,
+                task_type,
+                secondary_amount,
+                idempotency_key,
+            )
+        }
+        RewardPoolInstruction::WithdrawReward {
+            amount,
+            nonce,
+            split,
+        } => {
+            msg!("Instruction: WithdrawReward");
+            process_withdraw_reward(program_id, accounts, amount, nonce, split)
+        }
+        RewardPoolInstruction::UpdatePlatformFee { new_fee_percentage } => {
+            msg!("Instruction: UpdatePlatformFee");
+            process_update_platform_fee(program_id, accounts, new_fee_percentage)
+        }
+        RewardPoolInstruction::PausePool => {
+            msg!("Instruction: PausePool");
+            process_pause_pool(program_id, accounts)
+        }
+        RewardPoolInstruction::ResumePool => {
+            msg!("Instruction: ResumePool");
+            process_resume_pool(program_id, accounts)
+        }
+        RewardPoolInstruction::GetFarmerPending {
+            farmer,
+            cursor,
+            limit,
+        } => {
+            msg!("Instruction: GetFarmerPending");
+            process_get_farmer_pending(program_id, accounts, farmer, cursor, limit)
+        }
+        RewardPoolInstruction::GetPoolStats => {
+            msg!("Instruction: GetPoolStats");
+            process_get_pool_stats(program_id, accounts)
+        }
+        RewardPoolInstruction::SweepDelegatedRewards => {
+            msg!("Instruction: SweepDelegatedRewards");
+            process_sweep_delegated_rewards(program_id, accounts)
+        }
+        RewardPoolInstruction::AddWithdrawalDestination { destination } => {
+            msg!("Instruction: AddWithdrawalDestination");
+            process_add_withdrawal_destination(program_id, accounts, destination)
+        }
+        RewardPoolInstruction::RemoveWithdrawalDestination { destination } => {
+            msg!("Instruction: RemoveWithdrawalDestination");
+            process_remove_withdrawal_destination(program_id, accounts, destination)
+        }
+        RewardPoolInstruction::ConfigureTreasuryGovernance {
+            approvers,
+            required_approvals,
+            approval_threshold,
+            execution_delay,
+        } => {
+            msg!("Instruction: ConfigureTreasuryGovernance");
+            process_configure_treasury_governance(
+                program_id,
+                accounts,
+                approvers,
+                required_approvals,
+                approval_threshold,
+                execution_delay,
+            )
+        }
+        RewardPoolInstruction::ProposeTreasuryWithdrawal { amount } => {
+            msg!("Instruction: ProposeTreasuryWithdrawal");
+            process_propose_treasury_withdrawal(program_id, accounts, amount)
+        }
+        RewardPoolInstruction::ApproveTreasuryWithdrawal => {
+            msg!("Instruction: ApproveTreasuryWithdrawal");
+            process_approve_treasury_withdrawal(program_id, accounts)
+        }
+        RewardPoolInstruction::ExecuteTreasuryWithdrawal => {
+            msg!("Instruction: ExecuteTreasuryWithdrawal");
+            process_execute_treasury_withdrawal(program_id, accounts)
+        }
+        RewardPoolInstruction::SetUpgradeAuthority => {
+            msg!("Instruction: SetUpgradeAuthority");
+            process_set_upgrade_authority(program_id, accounts, true)
+        }
+        RewardPoolInstruction::RemoveUpgradeAuthority => {
+            msg!("Instruction: RemoveUpgradeAuthority");
+            process_set_upgrade_authority(program_id, accounts, false)
+        }
+        RewardPoolInstruction::UpdateParameters { values } => {
+            msg!("Instruction: UpdateParameters");
+            process_update_parameters(program_id, accounts, values)
+        }
+        RewardPoolInstruction::SetGuardians { guardians } => {
+            msg!("Instruction: SetGuardians");
+            process_set_guardians(program_id, accounts, guardians)
+        }
+        RewardPoolInstruction::ScheduleAction {
+            action_id,
+            eta,
+            action,
+        } => {
+            msg!("Instruction: ScheduleAction");
+            process_schedule_action(program_id, accounts, action_id, eta, action)
+        }
+        RewardPoolInstruction::CancelAction => {
+            msg!("Instruction: CancelAction");
+            process_cancel_action(program_id, accounts)
+        }
+        RewardPoolInstruction::ExecuteAction => {
+            msg!("Instruction: ExecuteAction");
+            process_execute_action(program_id, accounts)
+        }
+        RewardPoolInstruction::RollbackParameter { parameter } => {
+            msg!("Instruction: RollbackParameter");
+            process_rollback_parameter(program_id, accounts, parameter)
+        }
+        RewardPoolInstruction::PauseAll => {
+            msg!("Instruction: PauseAll");
+            process_pause_all(program_id, accounts)
+        }
+        RewardPoolInstruction::SetFeeAll { new_fee_percentage } => {
+            msg!("Instruction: SetFeeAll");
+            process_set_fee_all(program_id, accounts, new_fee_percentage)
+        }
+        RewardPoolInstruction::SetPoolMetadata { name, uri } => {
+            msg!("Instruction: SetPoolMetadata");
+            process_set_pool_metadata(program_id, accounts, name, uri)
+        }
+        RewardPoolInstruction::ExportState {
+            merkle_root,
+            farmer_count,
+            total_amount,
+        } => {
+            msg!("Instruction: ExportState");
+            process_export_state(
+                program_id,
+                accounts,
+                merkle_root,
+                farmer_count,
+                total_amount,
+            )
+        }
+        RewardPoolInstruction::ImportState {
+            source_program_id,
+            farmer,
+            amount,
+            proof,
+        } => {
+            msg!("Instruction: ImportState");
+            process_import_state(
+                program_id,
+                accounts,
+                source_program_id,
+                farmer,
+                amount,
+                proof,
+            )
+        }
+        RewardPoolInstruction::ArchivePool { claim_deadline } => {
+            msg!("Instruction: ArchivePool");
+            process_archive_pool(program_id, accounts, claim_deadline)
+        }
+        RewardPoolInstruction::ClosePool => {
+            msg!("Instruction: ClosePool");
+            process_close_pool(program_id, accounts)
+        }
+        RewardPoolInstruction::ClaimMany => {
+            msg!("Instruction: ClaimMany");
+            process_claim_many(program_id, accounts)
+        }
+        RewardPoolInstruction::RegisterPayoutAddress { payout_address } => {
+            msg!("Instruction: RegisterPayoutAddress");
+            process_register_payout_address(program_id, accounts, payout_address)
+        }
+        RewardPoolInstruction::SetCharityAccount => {
+            msg!("Instruction: SetCharityAccount");
+            process_set_charity_account(program_id, accounts)
+        }
+        RewardPoolInstruction::DonateReward { target } => {
+            msg!("Instruction: DonateReward");
+            process_donate_reward(program_id, accounts, target)
+        }
+        RewardPoolInstruction::CloseFarmerAccounts => {
+            msg!("Instruction: CloseFarmerAccounts");
+            process_close_farmer_accounts(program_id, accounts)
+        }
+        RewardPoolInstruction::SetAutoForward { enabled } => {
+            msg!("Instruction: SetAutoForward");
+            process_set_auto_forward(program_id, accounts, enabled)
+        }
+        RewardPoolInstruction::SetPendingTransferPolicy { transfers_disabled } => {
+            msg!("Instruction: SetPendingTransferPolicy");
+            process_set_pending_transfer_policy(program_id, accounts, transfers_disabled)
+        }
+        RewardPoolInstruction::TransferPending { new_farmer } => {
+            msg!("Instruction: TransferPending");
+            process_transfer_pending(program_id, accounts, new_farmer)
+        }
+        RewardPoolInstruction::GetWithdrawalHistory { farmer } => {
+            msg!("Instruction: GetWithdrawalHistory");
+            process_get_withdrawal_history(program_id, accounts, farmer)
+        }
+        RewardPoolInstruction::SetAutoClaim { threshold } => {
+            msg!("Instruction: SetAutoClaim");
+            process_set_auto_claim(program_id, accounts, threshold)
+        }
+        RewardPoolInstruction::CrankAutoClaim => {
+            msg!("Instruction: CrankAutoClaim");
+            process_crank_auto_claim(program_id, accounts)
+        }
+        RewardPoolInstruction::SetVerifier { verifier } => {
+            msg!("Instruction: SetVerifier");
+            process_set_verifier(program_id, accounts, verifier)
+        }
+        RewardPoolInstruction::SetAttestor {
+            attestor_program,
+            data_offset,
+        } => {
+            msg!("Instruction: SetAttestor");
+            process_set_attestor(program_id, accounts, attestor_program, data_offset)
+        }
+        RewardPoolInstruction::LockTaskBudget {
+            task_id,
+            amount,
+            expires_at,
+        } => {
+            msg!("Instruction: LockTaskBudget");
+            process_lock_task_budget(program_id, accounts, task_id, amount, expires_at)
+        }
+        RewardPoolInstruction::ReleaseUnusedBudget => {
+            msg!("Instruction: ReleaseUnusedBudget");
+            process_release_unused_budget(program_id, accounts)
+        }
+        RewardPoolInstruction::ChallengeReward => {
+            msg!("Instruction: ChallengeReward");
+            process_challenge_reward(program_id, accounts)
+        }
+        RewardPoolInstruction::ResolveChallenge { upheld } => {
+            msg!("Instruction: ResolveChallenge");
+            process_resolve_challenge(program_id, accounts, upheld)
+        }
+        RewardPoolInstruction::SetRewardApprover { approver } => {
+            msg!("Instruction: SetRewardApprover");
+            process_set_reward_approver(program_id, accounts, approver)
+        }
+        RewardPoolInstruction::ApproveReward => {
+            msg!("Instruction: ApproveReward");
+            process_approve_reward(program_id, accounts)
+        }
+        RewardPoolInstruction::SetTaskTypeFee {
+            task_type,
+            fee_percentage,
+        } => {
+            msg!("Instruction: SetTaskTypeFee");
+            process_set_task_type_fee(program_id, accounts, task_type, fee_percentage)
+        }
+        RewardPoolInstruction::RegisterPlatform { platform_id } => {
+            msg!("Instruction: RegisterPlatform");
+            process_register_platform(program_id, accounts, platform_id)
+        }
+        RewardPoolInstruction::SetPlatformOperator { operator } => {
+            msg!("Instruction: SetPlatformOperator");
+            process_set_platform_operator(program_id, accounts, operator)
+        }
+        RewardPoolInstruction::InitializePlatformPool {
+            platform_id,
+            platform_fee_percentage,
+        } => {
+            msg!("Instruction: InitializePlatformPool");
+            process_initialize_platform_pool(
+                program_id,
+                accounts,
+                platform_id,
+                platform_fee_percentage,
+            )
+        }
+        RewardPoolInstruction::CreateCampaign {
+            campaign_id,
+            budget,
+            starts_at,
+            ends_at,
+            task_types,
+        } => {
+            msg!("Instruction: CreateCampaign");
+            process_create_campaign(
+                program_id,
+                accounts,
+                campaign_id,
+                budget,
+                starts_at,
+                ends_at,
+                task_types,
+            )
+        }
+        RewardPoolInstruction::EndCampaign => {
+            msg!("Instruction: EndCampaign");
+            process_end_campaign(program_id, accounts)
+        }
+        RewardPoolInstruction::FinalizeCampaign => {
+            msg!("Instruction: FinalizeCampaign");
+            process_finalize_campaign(program_id, accounts)
+        }
+        RewardPoolInstruction::ConfigureBuyback {
+            dex_program,
+            governance_mint,
+            slice_bps,
+            min_output_per_million,
+        } => {
+            msg!("Instruction: ConfigureBuyback");
+            process_configure_buyback(
+                program_id,
+                accounts,
+                dex_program,
+                governance_mint,
+                slice_bps,
+                min_output_per_million,
+            )
+        }
+        RewardPoolInstruction::RunBuyback { swap_data } => {
+            msg!("Instruction: RunBuyback");
+            process_run_buyback(program_id, accounts, swap_data)
+        }
+        RewardPoolInstruction::SetTreasurySwapPolicy {
+            dex_program,
+            stable_mints,
+            epoch_limit_bps,
+        } => {
+            msg!("Instruction: SetTreasurySwapPolicy");
+            process_set_treasury_swap_policy(
+                program_id,
+                accounts,
+                dex_program,
+                stable_mints,
+                epoch_limit_bps,
+            )
+        }
+        RewardPoolInstruction::SetFeeDiscounts {
+            staking_program,
+            stake_seed,
+            tier_offset,
+            discounts_bps,
+        } => {
+            msg!("Instruction: SetFeeDiscounts");
+            process_set_fee_discounts(
+                program_id,
+                accounts,
+                staking_program,
+                stake_seed,
+                tier_offset,
+                discounts_bps,
+            )
+        }
+        RewardPoolInstruction::SetInterestPolicy {
+            rate_bps,
+            max_accrual_secs,
+        } => {
+            msg!("Instruction: SetInterestPolicy");
+            process_set_interest_policy(program_id, accounts, rate_bps, max_accrual_secs)
+        }
+        RewardPoolInstruction::SetWithdrawBurn { withdraw_burn_bps } => {
+            msg!("Instruction: SetWithdrawBurn");
+            process_set_withdraw_burn(program_id, accounts, withdraw_burn_bps)
+        }
+        RewardPoolInstruction::SetRewardGovernor {
+            min_runway_days,
+            floor_bps,
+            ceiling_bps,
+        } => {
+            msg!("Instruction: SetRewardGovernor");
+            process_set_reward_governor(
+                program_id,
+                accounts,
+                min_runway_days,
+                floor_bps,
+                ceiling_bps,
+            )
+        }
+        RewardPoolInstruction::SetSecondaryMint => {
+            msg!("Instruction: SetSecondaryMint");
+            process_set_secondary_mint(program_id, accounts)
+        }
+        RewardPoolInstruction::SetMilestoneBonuses { milestones } => {
+            msg!("Instruction: SetMilestoneBonuses");
+            process_set_milestone_bonuses(program_id, accounts, milestones)
+        }
+        RewardPoolInstruction::SetProtocolLiquidity {
+            amm_programs,
+            pol_bps,
+            min_lp_per_million,
+        } => {
+            msg!("Instruction: SetProtocolLiquidity");
+            process_set_protocol_liquidity(
+                program_id,
+                accounts,
+                amm_programs,
+                pol_bps,
+                min_lp_per_million,
+            )
+        }
+        RewardPoolInstruction::ContributeLiquidity {
+            swap_data,
+            swap_account_count,
+            deposit_data,
+        } => {
+            msg!("Instruction: ContributeLiquidity");
+            process_contribute_liquidity(
+                program_id,
+                accounts,
+                swap_data,
+                swap_account_count,
+                deposit_data,
+            )
+        }
+        RewardPoolInstruction::SetFeeRebate {
+            rebate_bps,
+            rate_per_million,
+        } => {
+            msg!("Instruction: SetFeeRebate");
+            process_set_fee_rebate(program_id, accounts, rebate_bps, rate_per_million)
+        }
+        RewardPoolInstruction::SetProtocolConfig {
+            admin,
+            treasury,
+            creation_fee,
+            protocol_fee_share_bps,
+        } => {
+            msg!("Instruction: SetProtocolConfig");
+            process_set_protocol_config(
+                program_id,
+                accounts,
+                admin,
+                treasury,
+                creation_fee,
+                protocol_fee_share_bps,
+            )
+        }
+        RewardPoolInstruction::CreatePool {
+            seed,
+            platform_fee_percentage,
+        } => {
+            msg!("Instruction: CreatePool");
+            process_create_pool(program_id, accounts, seed, platform_fee_percentage)
+        }
+        RewardPoolInstruction::ClaimAcrossPools { pending_counts } => {
+            msg!("Instruction: ClaimAcrossPools");
+            process_claim_across_pools(program_id, accounts, pending_counts)
+        }
+        RewardPoolInstruction::IssueVoucher { voucher_id, amount } => {
+            msg!("Instruction: IssueVoucher");
+            process_issue_voucher(program_id, accounts, voucher_id, amount)
+        }
+        RewardPoolInstruction::RedeemVoucher => {
+            msg!("Instruction: RedeemVoucher");
+            process_redeem_voucher(program_id, accounts)
+        }
+        RewardPoolInstruction::SetPayoutQueue { recovery_window } => {
+            msg!("Instruction: SetPayoutQueue");
+            process_set_payout_queue(program_id, accounts, recovery_window)
+        }
+        RewardPoolInstruction::TakePayoutTicket => {
+            msg!("Instruction: TakePayoutTicket");
+            process_take_payout_ticket(program_id, accounts)
+        }
+        RewardPoolInstruction::ResumeWithRamp {
+            initial_epoch_cap,
+            epoch_cap_step,
+            ramp_epochs,
+        } => {
+            msg!("Instruction: ResumeWithRamp");
+            process_resume_with_ramp(
+                program_id,
+                accounts,
+                initial_epoch_cap,
+                epoch_cap_step,
+                ramp_epochs,
+            )
+        }
+        RewardPoolInstruction::SetRecoveryAuthority {
+            recovery_authority,
+            inactivity_timeout,
+        } => {
+            msg!("Instruction: SetRecoveryAuthority");
+            process_set_recovery_authority(
+                program_id,
+                accounts,
+                recovery_authority,
+                inactivity_timeout,
+            )
+        }
+        RewardPoolInstruction::ClaimDormantAuthority => {
+            msg!("Instruction: ClaimDormantAuthority");
+            process_claim_dormant_authority(program_id, accounts)
+        }
+        RewardPoolInstruction::SetRecoveryGuardians {
+            guardians,
+            threshold,
+        } => {
+            msg!("Instruction: SetRecoveryGuardians");
+            process_set_recovery_guardians(program_id, accounts, guardians, threshold)
+        }
+        RewardPoolInstruction::InitiateFarmerRecovery { new_wallet } => {
+            msg!("Instruction: InitiateFarmerRecovery");
+            process_initiate_farmer_recovery(program_id, accounts, new_wallet)
+        }
+        RewardPoolInstruction::CancelFarmerRecovery => {
+            msg!("Instruction: CancelFarmerRecovery");
+            process_cancel_farmer_recovery(program_id, accounts)
+        }
+        RewardPoolInstruction::CompleteFarmerRecovery => {
+            msg!("Instruction: CompleteFarmerRecovery");
+            process_complete_farmer_recovery(program_id, accounts)
+        }
+        RewardPoolInstruction::SetWithdrawalFee {
+            fee_lamports,
+            exempt_small_claims,
+            small_claim_threshold,
+        } => {
+            msg!("Instruction: SetWithdrawalFee");
+            process_set_withdrawal_fee(
+                program_id,
+                accounts,
+                fee_lamports,
+                exempt_small_claims,
+                small_claim_threshold,
+            )
+        }
+        RewardPoolInstruction::CollectWithdrawalFees => {
+            msg!("Instruction: CollectWithdrawalFees");
+            process_collect_withdrawal_fees(program_id, accounts)
+        }
+        RewardPoolInstruction::SetWithdrawalCosigner { cosigner } => {
+            msg!("Instruction: SetWithdrawalCosigner");
+            process_set_withdrawal_cosigner(program_id, accounts, cosigner)
+        }
+        RewardPoolInstruction::ExportSnapshot => {
+            msg!("Instruction: ExportSnapshot");
+            process_export_snapshot(program_id, accounts)
+        }
+        RewardPoolInstruction::SetGuildSplit { guild_share_bps } => {
+            msg!("Instruction: SetGuildSplit");
+            process_set_guild_split(program_id, accounts, guild_share_bps)
+        }
+        RewardPoolInstruction::SetGuildMembers { members } => {
+            msg!("Instruction: SetGuildMembers");
+            process_set_guild_members(program_id, accounts, members)
+        }
+        RewardPoolInstruction::LeaveGuild => {
+            msg!("Instruction: LeaveGuild");
+            process_leave_guild(program_id, accounts)
+        }
+        RewardPoolInstruction::DistributeGuildRewards => {
+            msg!("Instruction: DistributeGuildRewards");
+            process_distribute_guild_rewards(program_id, accounts)
+        }
+        RewardPoolInstruction::CloseFeeInvoice => {
+            msg!("Instruction: CloseFeeInvoice");
+            process_close_fee_invoice(program_id, accounts)
+        }
+        RewardPoolInstruction::GetFeeInvoice { number } => {
+            msg!("Instruction: GetFeeInvoice");
+            process_get_fee_invoice(program_id, accounts, number)
+        }
+        RewardPoolInstruction::GetRewardRate { window } => {
+            msg!("Instruction: GetRewardRate");
+            process_get_reward_rate(program_id, accounts, window)
+        }
+        RewardPoolInstruction::ConsolidateClaimShards => {
+            msg!("Instruction: ConsolidateClaimShards");
+            process_consolidate_claim_shards(program_id, accounts)
+        }
+        RewardPoolInstruction::FundTreasury { amount } => {
+            msg!("Instruction: FundTreasury");
+            process_fund_treasury(program_id, accounts, amount)
+        }
+        RewardPoolInstruction::CloseWithdrawalRecords => {
+            msg!("Instruction: CloseWithdrawalRecords");
+            process_close_withdrawal_records(program_id, accounts)
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{
+    treasury::{check_approvals, load_governance, load_pool},
+    utils::create_pda_account,
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_parameter_history_address_with_program_id, find_parameters_address_with_program_id,
+        PARAMETERS_SEED, PARAMETER_HISTORY_SEED,
+    },
+    state::{Parameter, ParameterHistory, ParameterValues, PoolParameters, Sequenced},
+    PARAMETER_ROLLBACK_WINDOW,
+};
+
+// Updating pool parameters
+pub(super) fn process_update_parameters(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    values: ParameterValues,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let governance_info = next_account_info(account_info_iter)?;
+    let parameters_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let history_info = next_account_info(account_info_iter)?;
+    let approver_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let governance = load_governance(program_id, pool_info.key, governance_info)?;
+    check_approvals(&governance, approver_infos)?;
+
+    store_parameters(
+        program_id,
+        platform_authority_info,
+        pool_info,
+        parameters_info,
+        history_info,
+        system_program_info,
+        values,
+    )
+}
+
+/// Validates and writes a pool's parameters, recording the values they
+/// replace in the history. Both accounts are created on first use, paid by
+/// `payer`.
+pub(super) fn store_parameters<'a>(
+    program_id: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    pool_info: &AccountInfo<'a>,
+    parameters_info: &AccountInfo<'a>,
+    history_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    values: ParameterValues,
+) -> ProgramResult {
+    if !values.is_valid() {
+        return Err(RewardPoolError::InvalidParameterValues.into());
+    }
+
+    let (expected_parameters, bump_seed) =
+        find_parameters_address_with_program_id(pool_info.key, program_id);
+    if *parameters_info.key != expected_parameters {
+        return Err(RewardPoolError::InvalidParametersAccount.into());
+    }
+
+    let mut parameters = if parameters_info.data_is_empty() {
+        create_pda_account(
+            payer_info,
+            parameters_info,
+            system_program_info,
+            program_id,
+            PoolParameters::LEN,
+            &[PARAMETERS_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        PoolParameters {
+            pool: *pool_info.key,
+            values: ParameterValues::default(),
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_parameters_account(program_id, parameters_info)?
+    };
+
+    let (expected_history, history_bump_seed) =
+        find_parameter_history_address_with_program_id(pool_info.key, program_id);
+    if *history_info.key != expected_history {
+        return Err(RewardPoolError::InvalidParameterHistoryAccount.into());
+    }
+
+    let mut history = if history_info.data_is_empty() {
+        create_pda_account(
+            payer_info,
+            history_info,
+            system_program_info,
+            program_id,
+            ParameterHistory::LEN,
+            &[
+                PARAMETER_HISTORY_SEED,
+                pool_info.key.as_ref(),
+                &[history_bump_seed],
+            ],
+        )?;
+        ParameterHistory {
+            pool: *pool_info.key,
+            previous_values: ParameterValues::default(),
+            changed_at: [0; Parameter::ALL.len()],
+            bump_seed: history_bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_history_account(program_id, history_info)?
+    };
+
+    // Only the parameters that change get a new rollback point
+    let old_values = parameters.values;
+    let now = Clock::get()?.unix_timestamp;
+    for parameter in Parameter::ALL {
+        if values.differs(&old_values, parameter) {
+            history
+                .previous_values
+                .copy_parameter(&old_values, parameter);
+            history.changed_at[parameter as usize] = now;
+        }
+    }
+    history.save(history_info)?;
+
+    parameters.values = values;
+    parameters.save(parameters_info)?;
+
+    RewardPoolEvent::ParametersUpdated {
+        pool: *pool_info.key,
+        old_values,
+        new_values: values,
+    }
+    .emit();
+
+    msg!("Pool parameters updated");
+    Ok(())
+}
+
+// Rolling back a parameter
+pub(super) fn process_rollback_parameter(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    parameter: Parameter,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let parameters_info = next_account_info(account_info_iter)?;
+    let history_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    // A parameter that changed has both accounts
+    let (expected_parameters, _) =
+        find_parameters_address_with_program_id(pool_info.key, program_id);
+    if *parameters_info.key != expected_parameters {
+        return Err(RewardPoolError::InvalidParametersAccount.into());
+    }
+    let (expected_history, _) =
+        find_parameter_history_address_with_program_id(pool_info.key, program_id);
+    if *history_info.key != expected_history {
+        return Err(RewardPoolError::InvalidParameterHistoryAccount.into());
+    }
+    if history_info.data_is_empty() {
+        return Err(RewardPoolError::NothingToRollBack.into());
+    }
+    let mut parameters = load_parameters_account(program_id, parameters_info)?;
+    let mut history = load_history_account(program_id, history_info)?;
+
+    let changed_at = history
+        .changed_at(parameter)
+        .ok_or(RewardPoolError::NothingToRollBack)?;
+    if Clock::get()?.unix_timestamp > changed_at.saturating_add(PARAMETER_ROLLBACK_WINDOW) {
+        return Err(RewardPoolError::RollbackWindowExpired.into());
+    }
+
+    // The restored value is consumed, so a rollback cannot be repeated
+    let old_values = parameters.values;
+    parameters
+        .values
+        .copy_parameter(&history.previous_values, parameter);
+    history.changed_at[parameter as usize] = 0;
+    history.save(history_info)?;
+    parameters.save(parameters_info)?;
+
+    RewardPoolEvent::ParameterRolledBack {
+        pool: *pool_info.key,
+        parameter,
+        old_values,
+        new_values: parameters.values,
+    }
+    .emit();
+
+    msg!("Pool parameter {:?} rolled back", parameter);
+    Ok(())
+}
+
+/// Current parameters of a pool, the defaults while its parameters account
+/// does not exist
+pub(super) fn load_parameters(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    parameters_info: &AccountInfo,
+) -> Result<ParameterValues, ProgramError> {
+    let (expected_parameters, _) = find_parameters_address_with_program_id(pool, program_id);
+    if *parameters_info.key != expected_parameters {
+        return Err(RewardPoolError::InvalidParametersAccount.into());
+    }
+    if parameters_info.data_is_empty() {
+        return Ok(ParameterValues::default());
+    }
+    Ok(load_parameters_account(program_id, parameters_info)?.values)
+}
+
+fn load_parameters_account(
+    program_id: &Pubkey,
+    parameters_info: &AccountInfo,
+) -> Result<PoolParameters, ProgramError> {
+    if parameters_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(PoolParameters::try_from_slice(
+        &parameters_info.data.borrow(),
+    )?)
+}
+
+fn load_history_account(
+    program_id: &Pubkey,
+    history_info: &AccountInfo,
+) -> Result<ParameterHistory, ProgramError> {
+    if history_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(ParameterHistory::try_from_slice(
+        &history_info.data.borrow(),
+    )?)
+}
@@ -0,0 +1,360 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    hash::hash,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{
+    farmer::check_withdrawal_cosigner,
+    treasury::load_pool,
+    utils::create_pda_account,
+    whitelist::{load_payout_address, load_whitelist},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_payout_address_address_with_program_id, find_pending_reward_address_with_program_id,
+        find_pending_transfer_policy_address_with_program_id,
+        find_withdrawal_whitelist_address_with_program_id, PENDING_REWARD_SEED,
+        PENDING_TRANSFER_POLICY_SEED,
+    },
+    state::{PendingReward, PendingTransferPolicy, RewardPool, Sequenced},
+};
+
+// Allowing or forbidding pending reward transfers
+pub(super) fn process_set_pending_transfer_policy(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    transfers_disabled: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let policy_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let (expected_policy, bump_seed) =
+        find_pending_transfer_policy_address_with_program_id(pool_info.key, program_id);
+    if *policy_info.key != expected_policy {
+        return Err(RewardPoolError::InvalidPendingTransferPolicyAccount.into());
+    }
+
+    let mut policy = if policy_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            policy_info,
+            system_program_info,
+            program_id,
+            PendingTransferPolicy::LEN,
+            &[
+                PENDING_TRANSFER_POLICY_SEED,
+                pool_info.key.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        PendingTransferPolicy {
+            pool: *pool_info.key,
+            transfers_disabled,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        PendingTransferPolicy {
+            transfers_disabled,
+            ..load_pending_transfer_policy(program_id, policy_info)?
+        }
+    };
+    policy.save(policy_info)?;
+
+    RewardPoolEvent::PendingTransferPolicySet {
+        pool: *pool_info.key,
+        transfers_disabled,
+    }
+    .emit();
+
+    msg!(
+        "Pending reward transfers {}",
+        if transfers_disabled {
+            "disabled"
+        } else {
+            "enabled"
+        }
+    );
+    Ok(())
+}
+
+// Reassigning a pending reward to another farmer
+pub(super) fn process_transfer_pending(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_farmer: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let pending_info = next_account_info(account_info_iter)?;
+    let new_pending_info = next_account_info(account_info_iter)?;
+    let policy_info = next_account_info(account_info_iter)?;
+    let whitelist_info = next_account_info(account_info_iter)?;
+    let payout_address_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let farmer_state_info = next_account_info(account_info_iter)?;
+    let cosigner_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    let (expected_policy, _) =
+        find_pending_transfer_policy_address_with_program_id(pool_info.key, program_id);
+    if *policy_info.key != expected_policy {
+        return Err(RewardPoolError::InvalidPendingTransferPolicyAccount.into());
+    }
+    if !policy_info.data_is_empty()
+        && load_pending_transfer_policy(program_id, policy_info)?.transfers_disabled
+    {
+        return Err(RewardPoolError::PendingTransfersDisabled.into());
+    }
+
+    if pending_info.owner != program_id {
+        return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+    }
+    let mut pending = PendingReward::try_from_slice(&pending_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidPendingRewardAccount)?;
+    let (expected_address, _) = find_pending_reward_address_with_program_id(
+        pool_info.key,
+        farmer_info.key,
+        &pending.task_id,
+        program_id,
+    );
+    if pending.farmer_pubkey != *farmer_info.key || *pending_info.key != expected_address {
+        return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+    }
+
+    if pending.is_withdrawn {
+        return Err(RewardPoolError::NoPendingRewards.into());
+    }
+    if pending.is_challenged {
+        return Err(RewardPoolError::RewardChallenged.into());
+    }
+    if pending.awaiting_approval {
+        return Err(RewardPoolError::RewardAwaitingApproval.into());
+    }
+
+    // A transfer must not get around the farmer's withdrawal protections:
+    // only the payout address in effect can receive it, whitelisted token
+    // accounts cannot be matched against a wallet at all, and a cosigner
+    // must sign it like a withdrawal
+    check_withdrawal_cosigner(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        farmer_state_info,
+        cosigner_info,
+    )?;
+    let (expected_whitelist, _) = find_withdrawal_whitelist_address_with_program_id(
+        pool_info.key,
+        farmer_info.key,
+        program_id,
+    );
+    if *whitelist_info.key != expected_whitelist {
+        return Err(RewardPoolError::InvalidWhitelistAccount.into());
+    }
+    if !whitelist_info.data_is_empty()
+        && load_whitelist(program_id, whitelist_info)?.entry_count > 0
+    {
+        return Err(RewardPoolError::PendingTransferRestricted.into());
+    }
+
+    let (expected_payout_address, _) =
+        find_payout_address_address_with_program_id(pool_info.key, farmer_info.key, program_id);
+    if *payout_address_info.key != expected_payout_address {
+        return Err(RewardPoolError::InvalidPayoutAddressAccount.into());
+    }
+    if !payout_address_info.data_is_empty() {
+        let registration = load_payout_address(program_id, payout_address_info)?;
+        if registration
+            .effective(Clock::get()?.unix_timestamp)
+            .is_some_and(|payout_address| payout_address != new_farmer)
+        {
+            return Err(RewardPoolError::PendingTransferRestricted.into());
+        }
+    }
+
+    // Pending rewards are addressed by beneficiary, so the reward moves to
+    // the new beneficiary's account for the task, which must not exist yet
+    let (expected_new_address, bump_seed) = find_pending_reward_address_with_program_id(
+        pool_info.key,
+        &new_farmer,
+        &pending.task_id,
+        program_id,
+    );
+    if new_farmer == *farmer_info.key
+        || *new_pending_info.key != expected_new_address
+        || !new_pending_info.data_is_empty()
+    {
+        return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+    }
+
+    create_pda_account(
+        farmer_info,
+        new_pending_info,
+        system_program_info,
+        program_id,
+        pending_info.data_len(),
+        &[
+            PENDING_REWARD_SEED,
+            pool_info.key.as_ref(),
+            new_farmer.as_ref(),
+            hash(pending.task_id.as_bytes()).as_ref(),
+            &[bump_seed],
+        ],
+    )?;
+    pending.farmer_pubkey = new_farmer;
+    pending.serialize(&mut &mut new_pending_info.data.borrow_mut()[..])?;
+
+    let amount = pending.amount;
+    pending.farmer_pubkey = *farmer_info.key;
+    leave_pending_tombstone(pending_info, &mut pending)?;
+
+    RewardPoolEvent::PendingRewardTransferred {
+        pool: *pool_info.key,
+        from: *farmer_info.key,
+        to: new_farmer,
+        task_id: pending.task_id,
+        amount,
+    }
+    .emit();
+
+    msg!(
+        "Pending reward of {} tokens transferred to {}",
+        amount,
+        new_farmer
+    );
+    Ok(())
+}
+
+/// Leaves `pending`, whose reward moved to another beneficiary's account,
+/// at `pending_info` as a tombstone: withdrawn and owing nothing. Closing
+/// the account instead would let the task be recorded for its old
+/// beneficiary again. Only fixed-size fields change, so the data keeps its
+/// length.
+pub(super) fn leave_pending_tombstone(
+    pending_info: &AccountInfo,
+    pending: &mut PendingReward,
+) -> ProgramResult {
+    pending.amount = 0;
+    pending.is_withdrawn = true;
+    pending.interest_owed = 0;
+    pending.serialize(&mut &mut pending_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn load_pending_transfer_policy(
+    program_id: &Pubkey,
+    policy_info: &AccountInfo,
+) -> Result<PendingTransferPolicy, ProgramError> {
+    if policy_info.owner != program_id {
+        return Err(RewardPoolError::InvalidPendingTransferPolicyAccount.into());
+    }
+    PendingTransferPolicy::try_from_slice(&policy_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidPendingTransferPolicyAccount.into())
+}
+
+/// Reads a pending reward of the pool, whichever farmer it belongs to
+pub(super) fn load_pending_reward(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    pending_info: &AccountInfo,
+) -> Result<PendingReward, ProgramError> {
+    if pending_info.owner != program_id {
+        return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+    }
+    let pending = PendingReward::try_from_slice(&pending_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidPendingRewardAccount)?;
+    let (expected_address, _) = find_pending_reward_address_with_program_id(
+        pool,
+        &pending.farmer_pubkey,
+        &pending.task_id,
+        program_id,
+    );
+    if *pending_info.key != expected_address {
+        return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+    }
+    Ok(pending)
+}
+
+/// Creates the pending reward account of `pending`, which must not exist
+/// yet, paid by `payer_info`
+pub(super) fn create_pending_reward<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    pending_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    pending: &PendingReward,
+) -> ProgramResult {
+    let (expected_address, bump_seed) = find_pending_reward_address_with_program_id(
+        pool,
+        &pending.farmer_pubkey,
+        &pending.task_id,
+        program_id,
+    );
+    if *pending_info.key != expected_address || !pending_info.data_is_empty() {
+        return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+    }
+
+    let data = borsh::to_vec(pending)?;
+    create_pda_account(
+        payer_info,
+        pending_info,
+        system_program_info,
+        program_id,
+        data.len(),
+        &[
+            PENDING_REWARD_SEED,
+            pool.as_ref(),
+            pending.farmer_pubkey.as_ref(),
+            hash(pending.task_id.as_bytes()).as_ref(),
+            &[bump_seed],
+        ],
+    )?;
+    pending_info.data.borrow_mut().copy_from_slice(&data);
+    Ok(())
+}
+
+/// Takes `amount` of pending rewards paid out or given up off what the
+/// pool's treasury vault holds for farmers
+pub(super) fn release_outstanding_rewards(
+    pool_info: &AccountInfo,
+    pool_data: &mut RewardPool,
+    amount: u64,
+) -> ProgramResult {
+    pool_data.outstanding_rewards = pool_data.outstanding_rewards.saturating_sub(amount);
+    pool_data.save(pool_info)
+}
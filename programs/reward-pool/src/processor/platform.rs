@@ -0,0 +1,287 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{program_treasury::create_treasury_vault, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_platform_address_with_program_id, find_platform_pool_address_with_program_id,
+        PLATFORM_SEED, POOL_SEED,
+    },
+    state::{Platform, PoolAddressKind, RewardPool, Sequenced},
+    MAX_PLATFORM_FEE_PERCENTAGE,
+};
+
+// Claiming a platform id
+pub(super) fn process_register_platform(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    platform_id: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let operator_info = next_account_info(account_info_iter)?;
+    let platform_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !operator_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_platform, bump_seed) =
+        find_platform_address_with_program_id(platform_id, program_id);
+    if *platform_info.key != expected_platform {
+        return Err(RewardPoolError::InvalidPlatformAccount.into());
+    }
+    if !platform_info.data_is_empty() {
+        return Err(RewardPoolError::PlatformAlreadyRegistered.into());
+    }
+
+    create_pda_account(
+        operator_info,
+        platform_info,
+        system_program_info,
+        program_id,
+        Platform::LEN,
+        &[PLATFORM_SEED, &platform_id.to_le_bytes(), &[bump_seed]],
+    )?;
+    let mut platform = Platform {
+        platform_id,
+        operator: *operator_info.key,
+        registered_at: Clock::get()?.unix_timestamp,
+        pool_count: 0,
+        reward_count: 0,
+        total_rewards_distributed: 0,
+        total_platform_fees_collected: 0,
+        bump_seed,
+        sequence: 0,
+    };
+    platform.save(platform_info)?;
+
+    RewardPoolEvent::PlatformRegistered {
+        platform_id,
+        operator: *operator_info.key,
+    }
+    .emit();
+
+    msg!("Platform {} registered", platform_id);
+    Ok(())
+}
+
+// Handing a platform to a new operator
+pub(super) fn process_set_platform_operator(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    operator: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let operator_info = next_account_info(account_info_iter)?;
+    let platform_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !operator_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let mut platform = load_platform(program_id, platform_info)?;
+    if platform.operator != *operator_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let old_operator = platform.operator;
+    platform.operator = operator;
+    platform.save(platform_info)?;
+
+    RewardPoolEvent::PlatformOperatorSet {
+        platform_id: platform.platform_id,
+        old_operator,
+        new_operator: operator,
+    }
+    .emit();
+
+    msg!(
+        "Platform {} operator set to {}",
+        platform.platform_id,
+        operator
+    );
+    Ok(())
+}
+
+// Initializing a pool in a platform namespace
+pub(super) fn process_initialize_platform_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    platform_id: u64,
+    platform_fee_percentage: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let operator_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let platform_treasury_info = next_account_info(account_info_iter)?;
+    let platform_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !operator_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let mut platform = load_platform(program_id, platform_info)?;
+    if platform.platform_id != platform_id || platform.operator != *operator_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if platform_fee_percentage > MAX_PLATFORM_FEE_PERCENTAGE {
+        return Err(RewardPoolError::InvalidPlatformFee.into());
+    }
+
+    let (expected_pool, bump_seed) =
+        find_platform_pool_address_with_program_id(platform_id, reward_mint_info.key, program_id);
+    if *pool_info.key != expected_pool {
+        return Err(RewardPoolError::InvalidPlatformAccount.into());
+    }
+    if !pool_info.data_is_empty() {
+        return Err(RewardPoolError::PoolAlreadyInitialized.into());
+    }
+
+    create_pda_account(
+        operator_info,
+        pool_info,
+        system_program_info,
+        program_id,
+        RewardPool::LEN,
+        &[
+            POOL_SEED,
+            &platform_id.to_le_bytes(),
+            reward_mint_info.key.as_ref(),
+            &[bump_seed],
+        ],
+    )?;
+    create_treasury_vault(
+        program_id,
+        operator_info,
+        pool_info.key,
+        platform_treasury_info,
+        reward_mint_info,
+        token_program_info,
+        system_program_info,
+    )?;
+    let mut pool_data = RewardPool {
+        platform_authority: *operator_info.key,
+        reward_mint: *reward_mint_info.key,
+        platform_treasury: *platform_treasury_info.key,
+        platform_fee_percentage,
+        total_rewards_distributed: 0,
+        total_platform_fees_collected: 0,
+        is_paused: false,
+        bump_seed,
+        address_kind: PoolAddressKind::Platform,
+        address_creator: Pubkey::default(),
+        address_seed: platform_id,
+        outstanding_rewards: 0,
+        last_authority_action_at: 0,
+        sequence: 0,
+    };
+    pool_data.save(pool_info)?;
+
+    platform.pool_count = platform
+        .pool_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    platform.save(platform_info)?;
+
+    RewardPoolEvent::PoolInitialized {
+        pool: *pool_info.key,
+        platform_authority: pool_data.platform_authority,
+        reward_mint: pool_data.reward_mint,
+        platform_treasury: pool_data.platform_treasury,
+        platform_fee_percentage,
+    }
+    .emit();
+    RewardPoolEvent::PlatformPoolCreated {
+        platform_id,
+        pool: *pool_info.key,
+    }
+    .emit();
+
+    msg!("Pool initialized for platform {}", platform_id);
+    Ok(())
+}
+
+/// Returns the platform of a pool created in a platform namespace, checking
+/// that `platform_info` is that platform's account. `None` for other pools,
+/// whose `platform_info` is ignored.
+pub(super) fn load_pool_platform(
+    program_id: &Pubkey,
+    pool_data: &RewardPool,
+    platform_info: &AccountInfo,
+) -> Result<Option<Platform>, ProgramError> {
+    if !pool_data.is_namespaced() {
+        return Ok(None);
+    }
+
+    let platform = load_platform(program_id, platform_info)?;
+    if platform.platform_id != pool_data.address_seed {
+        return Err(RewardPoolError::InvalidPlatformAccount.into());
+    }
+
+    Ok(Some(platform))
+}
+
+/// Adds a recorded reward to its platform's totals
+pub(super) fn record_platform_reward(
+    platform: &mut Platform,
+    platform_info: &AccountInfo,
+    amount: u64,
+    platform_fee: u64,
+) -> ProgramResult {
+    platform.reward_count = platform
+        .reward_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    platform.total_rewards_distributed = platform
+        .total_rewards_distributed
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    platform.total_platform_fees_collected = platform
+        .total_platform_fees_collected
+        .checked_add(platform_fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    platform.save(platform_info)
+}
+
+fn load_platform(
+    program_id: &Pubkey,
+    platform_info: &AccountInfo,
+) -> Result<Platform, ProgramError> {
+    if platform_info.owner != program_id {
+        return Err(RewardPoolError::InvalidPlatformAccount.into());
+    }
+    let platform = Platform::try_from_slice(&platform_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidPlatformAccount)?;
+    let expected_platform = Pubkey::create_program_address(
+        &[
+            PLATFORM_SEED,
+            &platform.platform_id.to_le_bytes(),
+            &[platform.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidPlatformAccount)?;
+    if *platform_info.key != expected_platform {
+        return Err(RewardPoolError::InvalidPlatformAccount.into());
+    }
+    Ok(platform)
+}
@@ -0,0 +1,500 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+use super::{
+    guardians::is_guardian,
+    metadata::store_metadata,
+    parameters::load_parameters,
+    program_treasury::{check_platform_fee_vault, check_treasury_vault, create_treasury_vault},
+    queue::start_recovery,
+    treasury::load_pool,
+    utils::{close_pda_account, create_pda_account, sweep_self_owned_token_account},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_bonus_vault_address_with_program_id, find_fee_vault_address_with_program_id,
+        find_instruction_counters_address_with_program_id, find_pool_address_with_program_id,
+        find_pool_archive_address_with_program_id, BONUS_VAULT_SEED, PLATFORM_FEE_VAULT_SEED,
+        POOL_ARCHIVE_SEED, POOL_SEED, VAULT_SEED,
+    },
+    state::{PoolAddressKind, PoolArchive, RewardPool, Sequenced},
+    MAX_PLATFORM_FEE_PERCENTAGE,
+};
+
+// Pool initialization
+pub(super) fn process_initialize_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    platform_fee_percentage: u8,
+    name: Option<String>,
+    uri: Option<String>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let platform_treasury_info = next_account_info(account_info_iter)?;
+    let _rent_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let _ata_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if platform_fee_percentage > MAX_PLATFORM_FEE_PERCENTAGE {
+        return Err(RewardPoolError::InvalidPlatformFee.into());
+    }
+
+    // The pool lives at the address derived from its reward mint, so no
+    // other account can pass for it
+    let (expected_pool, pool_bump_seed) =
+        find_pool_address_with_program_id(reward_mint_info.key, program_id);
+    if *pool_info.key != expected_pool {
+        return Err(RewardPoolError::InvalidPoolAccount.into());
+    }
+    if !pool_info.data_is_empty() {
+        return Err(RewardPoolError::PoolAlreadyInitialized.into());
+    }
+
+    create_pda_account(
+        platform_authority_info,
+        pool_info,
+        system_program_info,
+        program_id,
+        RewardPool::LEN,
+        &[POOL_SEED, reward_mint_info.key.as_ref(), &[pool_bump_seed]],
+    )?;
+    // The pool's treasury is its vault, so rewards only leave it through
+    // the program
+    create_treasury_vault(
+        program_id,
+        platform_authority_info,
+        pool_info.key,
+        platform_treasury_info,
+        reward_mint_info,
+        token_program_info,
+        system_program_info,
+    )?;
+
+    // Initialize pool
+    let pool_data = RewardPool {
+        platform_authority: *platform_authority_info.key,
+        reward_mint: *reward_mint_info.key,
+        platform_treasury: *platform_treasury_info.key,
+        platform_fee_percentage,
+        total_rewards_distributed: 0,
+        total_platform_fees_collected: 0,
+        is_paused: false,
+        bump_seed: pool_bump_seed,
+        address_kind: PoolAddressKind::Mint,
+        address_creator: Pubkey::default(),
+        address_seed: 0,
+        outstanding_rewards: 0,
+        last_authority_action_at: 0,
+        sequence: 0,
+    };
+
+    pool_data.serialize(&mut &mut pool_info.data.borrow_mut()[..])?;
+
+    RewardPoolEvent::PoolInitialized {
+        pool: *pool_info.key,
+        platform_authority: pool_data.platform_authority,
+        reward_mint: pool_data.reward_mint,
+        platform_treasury: pool_data.platform_treasury,
+        platform_fee_percentage,
+    }
+    .emit();
+
+    if name.is_some() || uri.is_some() {
+        let metadata_info = next_account_info(account_info_iter)?;
+        store_metadata(
+            program_id,
+            platform_authority_info,
+            pool_info,
+            metadata_info,
+            system_program_info,
+            name.unwrap_or_default(),
+            uri.unwrap_or_default(),
+        )?;
+    }
+
+    msg!("Pool initialized successfully");
+    Ok(())
+}
+
+// Updating platform fees
+pub(super) fn process_update_platform_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_fee_percentage: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    // Optional trailing accounts, which V1 account lists end before
+    let _counters_info = account_info_iter.next();
+    let _system_program_info = account_info_iter.next();
+    let parameters_info = account_info_iter.next();
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    // Load and update pool
+    let mut pool_data = load_pool(program_id, pool_info)?;
+
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    apply_platform_fee(
+        program_id,
+        pool_info,
+        &mut pool_data,
+        parameters_info,
+        new_fee_percentage,
+    )
+}
+
+/// Sets the pool's fee, within the maximum of its parameters. Without the
+/// parameters account, as in V1 account lists, the fee can only go down.
+pub(super) fn apply_platform_fee(
+    program_id: &Pubkey,
+    pool_info: &AccountInfo,
+    pool_data: &mut RewardPool,
+    parameters_info: Option<&AccountInfo>,
+    new_fee_percentage: u8,
+) -> ProgramResult {
+    let max_fee_percentage = match parameters_info {
+        Some(parameters_info) => {
+            load_parameters(program_id, pool_info.key, parameters_info)?.max_platform_fee_percentage
+        }
+        None => pool_data.platform_fee_percentage,
+    };
+    if new_fee_percentage > max_fee_percentage {
+        return Err(RewardPoolError::InvalidPlatformFee.into());
+    }
+
+    let old_fee_percentage = pool_data.platform_fee_percentage;
+    pool_data.platform_fee_percentage = new_fee_percentage;
+    pool_data.save(pool_info)?;
+
+    RewardPoolEvent::PlatformFeeUpdated {
+        pool: *pool_info.key,
+        old_fee_percentage,
+        new_fee_percentage,
+    }
+    .emit();
+
+    msg!("Platform fees updated: {}%", new_fee_percentage);
+    Ok(())
+}
+
+// Pausing the pool
+pub(super) fn process_pause_pool(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    // Optional trailing accounts, which V1 account lists end before
+    let _counters_info = account_info_iter.next();
+    let _system_program_info = account_info_iter.next();
+    let guardians_info = account_info_iter.next();
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    // Load and update pool
+    let mut pool_data = load_pool(program_id, pool_info)?;
+
+    // Guardians can pause, but nothing else
+    if pool_data.platform_authority != *platform_authority_info.key {
+        if !is_guardian(
+            program_id,
+            pool_info.key,
+            guardians_info,
+            platform_authority_info.key,
+        )? {
+            return Err(RewardPoolError::InvalidAuthority.into());
+        }
+
+        // Nobody could resume an archived pool, so it stays claimable until
+        // it is closed
+        let (archive, _) = find_pool_archive_address_with_program_id(pool_info.key, program_id);
+        if pool_data.platform_authority == archive {
+            return Err(RewardPoolError::PoolArchived.into());
+        }
+    }
+
+    pool_data.is_paused = true;
+    pool_data.save(pool_info)?;
+
+    RewardPoolEvent::PoolPaused {
+        pool: *pool_info.key,
+    }
+    .emit();
+
+    msg!("Pool paused");
+    Ok(())
+}
+
+// Resuming the pool
+pub(super) fn process_resume_pool(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    // Optional trailing accounts, which V1 account lists end before
+    let _counters_info = account_info_iter.next();
+    let _system_program_info = account_info_iter.next();
+    let queue_info = account_info_iter.next();
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    // Load and update pool
+    let mut pool_data = load_pool(program_id, pool_info)?;
+
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    pool_data.is_paused = false;
+    pool_data.save(pool_info)?;
+
+    // Claims held back by the pause go out in ticket order first
+    start_recovery(program_id, pool_info.key, queue_info)?;
+
+    RewardPoolEvent::PoolResumed {
+        pool: *pool_info.key,
+    }
+    .emit();
+
+    msg!("Pool resumed");
+    Ok(())
+}
+
+// Archiving the pool
+pub(super) fn process_archive_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    claim_deadline: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let archive_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let mut pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    // Farmers could not claim from a paused pool
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if claim_deadline <= now {
+        return Err(RewardPoolError::InvalidClaimDeadline.into());
+    }
+
+    let (expected_archive, bump_seed) =
+        find_pool_archive_address_with_program_id(pool_info.key, program_id);
+    if *archive_info.key != expected_archive || !archive_info.data_is_empty() {
+        return Err(RewardPoolError::InvalidArchiveAccount.into());
+    }
+
+    create_pda_account(
+        platform_authority_info,
+        archive_info,
+        system_program_info,
+        program_id,
+        PoolArchive::LEN,
+        &[POOL_ARCHIVE_SEED, pool_info.key.as_ref(), &[bump_seed]],
+    )?;
+
+    PoolArchive {
+        pool: *pool_info.key,
+        archived_by: pool_data.platform_authority,
+        archived_at: now,
+        claim_deadline,
+        bump_seed,
+        sequence: 0,
+    }
+    .save(archive_info)?;
+
+    // No key signs for the archive account, so rewards, fee and parameter
+    // changes stop for good while withdrawals, which only need the farmer,
+    // keep working
+    let old_platform_authority = pool_data.platform_authority;
+    pool_data.platform_authority = expected_archive;
+    pool_data.save(pool_info)?;
+
+    RewardPoolEvent::PlatformAuthorityChanged {
+        pool: *pool_info.key,
+        old_platform_authority,
+        new_platform_authority: expected_archive,
+    }
+    .emit();
+    RewardPoolEvent::PoolArchived {
+        pool: *pool_info.key,
+        claim_deadline,
+    }
+    .emit();
+
+    msg!("Pool archived, claimable until {}", claim_deadline);
+    Ok(())
+}
+
+// Closing an archived pool
+pub(super) fn process_close_pool(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let archived_by_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let archive_info = next_account_info(account_info_iter)?;
+    let counters_info = next_account_info(account_info_iter)?;
+    let treasury_vault_info = next_account_info(account_info_iter)?;
+    let platform_fee_vault_info = next_account_info(account_info_iter)?;
+    let bonus_vault_info = next_account_info(account_info_iter)?;
+    let fee_vault_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !archived_by_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+
+    let (expected_archive, _) =
+        find_pool_archive_address_with_program_id(pool_info.key, program_id);
+    if *archive_info.key != expected_archive || archive_info.owner != program_id {
+        return Err(RewardPoolError::InvalidArchiveAccount.into());
+    }
+    let archive = PoolArchive::try_from_slice(&archive_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidArchiveAccount)?;
+
+    if archive.archived_by != *archived_by_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if Clock::get()?.unix_timestamp < archive.claim_deadline {
+        return Err(RewardPoolError::ClaimPeriodNotOver.into());
+    }
+
+    // Farmers can still withdraw after the deadline, and the vaults are
+    // swept below, so every reward owed must be paid out first
+    if pool_data.outstanding_rewards > 0 {
+        return Err(RewardPoolError::RewardsOutstanding.into());
+    }
+
+    let vault_bump_seed = check_treasury_vault(program_id, pool_info.key, treasury_vault_info)?;
+    let platform_fee_vault_bump_seed =
+        check_platform_fee_vault(program_id, pool_info.key, platform_fee_vault_info)?;
+    let (expected_bonus_vault, bonus_vault_bump_seed) =
+        find_bonus_vault_address_with_program_id(pool_info.key, program_id);
+    if *bonus_vault_info.key != expected_bonus_vault {
+        return Err(RewardPoolError::InvalidBonusVaultAccount.into());
+    }
+    let (expected_fee_vault, _) = find_fee_vault_address_with_program_id(pool_info.key, program_id);
+    if *fee_vault_info.key != expected_fee_vault {
+        return Err(RewardPoolError::InvalidFeeVaultAccount.into());
+    }
+    if *token_program_info.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (expected_counters, _) =
+        find_instruction_counters_address_with_program_id(pool_info.key, program_id);
+    if *counters_info.key != expected_counters {
+        return Err(RewardPoolError::InvalidCountersAccount.into());
+    }
+    if !counters_info.data_is_empty() && counters_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Nothing could move the vaults' funds once the pool is gone, so what
+    // is left in them goes to the authority that archived it: the tokens to
+    // `destination_info`, the lamport fees and the rent to the authority.
+    // The token vaults go first, as their transfers are CPIs and the
+    // accounts closed directly below are not passed to them.
+    let mut swept_tokens: u64 = 0;
+    for (vault_info, seed, bump_seed) in [
+        (treasury_vault_info, VAULT_SEED, vault_bump_seed),
+        (
+            platform_fee_vault_info,
+            PLATFORM_FEE_VAULT_SEED,
+            platform_fee_vault_bump_seed,
+        ),
+        (bonus_vault_info, BONUS_VAULT_SEED, bonus_vault_bump_seed),
+    ] {
+        if vault_info.data_is_empty() {
+            continue;
+        }
+        let swept = sweep_self_owned_token_account(
+            vault_info,
+            destination_info,
+            archived_by_info,
+            token_program_info,
+            &[seed, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        swept_tokens = swept_tokens
+            .checked_add(swept)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+    let mut swept_fees: u64 = 0;
+    if fee_vault_info.owner == program_id {
+        swept_fees = fee_vault_info
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(fee_vault_info.data_len()));
+        close_pda_account(fee_vault_info, archived_by_info)?;
+    }
+
+    if !counters_info.data_is_empty() {
+        close_pda_account(counters_info, archived_by_info)?;
+    }
+    close_pda_account(archive_info, archived_by_info)?;
+    close_pda_account(pool_info, archived_by_info)?;
+
+    RewardPoolEvent::PoolClosed {
+        pool: *pool_info.key,
+        destination: *destination_info.key,
+        swept_tokens,
+        swept_fees,
+    }
+    .emit();
+
+    msg!(
+        "Pool closed, {} tokens and {} lamports of fees swept",
+        swept_tokens,
+        swept_fees
+    );
+    Ok(())
+}
@@ -0,0 +1,263 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use spl_token::instruction as token_instruction;
+
+use super::{
+    treasury::load_pool,
+    utils::{create_self_owned_token_account, token_balance},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_platform_fee_vault_address_with_program_id, find_vault_address_with_program_id,
+        PLATFORM_FEE_VAULT_SEED, VAULT_SEED,
+    },
+    state::RewardPool,
+};
+
+// Topping up the pool's treasury
+pub(super) fn process_fund_treasury(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let funder_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let source_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let _system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !funder_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if *reward_mint_info.key != pool_data.reward_mint {
+        return Err(RewardPoolError::InvalidProgramTreasuryAccount.into());
+    }
+
+    // Every pool is created with its vault
+    check_treasury_vault(program_id, pool_info.key, treasury_info)?;
+    if treasury_info.data_is_empty() {
+        return Err(RewardPoolError::InvalidProgramTreasuryAccount.into());
+    }
+
+    if amount > 0 {
+        invoke(
+            &token_instruction::transfer(
+                token_program_info.key,
+                source_info.key,
+                treasury_info.key,
+                funder_info.key,
+                &[],
+                amount,
+            )?,
+            &[
+                source_info.clone(),
+                treasury_info.clone(),
+                funder_info.clone(),
+                token_program_info.clone(),
+            ],
+        )?;
+    }
+
+    let balance = token_balance(treasury_info)?;
+    RewardPoolEvent::ProgramTreasuryFunded {
+        pool: *pool_info.key,
+        funder: *funder_info.key,
+        amount,
+        balance,
+    }
+    .emit();
+
+    msg!("Treasury funded with {} tokens", amount);
+    Ok(())
+}
+
+/// Creates the pool's treasury vault, a token account of the reward mint
+/// that is its own owner, so only the program signs for it
+pub(super) fn create_treasury_vault<'a>(
+    program_id: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    pool: &Pubkey,
+    vault_info: &AccountInfo<'a>,
+    reward_mint_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    let bump_seed = check_treasury_vault(program_id, pool, vault_info)?;
+    if *token_program_info.key != spl_token::id() {
+        return Err(RewardPoolError::InvalidProgramTreasuryAccount.into());
+    }
+    create_self_owned_token_account(
+        payer_info,
+        vault_info,
+        reward_mint_info,
+        token_program_info,
+        system_program_info,
+        &[VAULT_SEED, pool.as_ref(), &[bump_seed]],
+    )
+}
+
+/// Checks that `treasury_info` is the pool's treasury vault and returns its
+/// bump
+pub(super) fn check_treasury_vault(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    treasury_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let (expected_vault, bump_seed) = find_vault_address_with_program_id(pool, program_id);
+    if *treasury_info.key != expected_vault {
+        return Err(RewardPoolError::InvalidProgramTreasuryAccount.into());
+    }
+    Ok(bump_seed)
+}
+
+/// Checks that `platform_treasury_info` is the pool's platform treasury,
+/// which is always its treasury vault
+pub(super) fn check_platform_treasury(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    pool_data: &RewardPool,
+    platform_treasury_info: &AccountInfo,
+) -> ProgramResult {
+    if *platform_treasury_info.key != pool_data.platform_treasury {
+        return Err(RewardPoolError::InvalidPlatformTreasury.into());
+    }
+    check_treasury_vault(program_id, pool, platform_treasury_info)?;
+    Ok(())
+}
+
+/// Moves `amount` out of the pool's platform treasury, its vault, signed
+/// for by the program with the vault's seeds
+pub(super) fn transfer_from_platform_treasury<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    platform_treasury_info: &AccountInfo<'a>,
+    destination_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    let bump_seed = check_treasury_vault(program_id, pool, platform_treasury_info)?;
+    invoke_signed(
+        &token_instruction::transfer(
+            token_program_info.key,
+            platform_treasury_info.key,
+            destination_info.key,
+            platform_treasury_info.key,
+            &[],
+            amount,
+        )?,
+        &[
+            platform_treasury_info.clone(),
+            destination_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[VAULT_SEED, pool.as_ref(), &[bump_seed]]],
+    )
+}
+
+/// Moves `amount` of platform fees out of the pool's treasury vault into its
+/// platform fee vault, creating the fee vault at `payer_info`'s expense the
+/// first time. Buybacks, liquidity contributions and treasury swaps only
+/// spend what the fee vault holds, never rewards owed to farmers.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn deposit_platform_fee<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    platform_treasury_info: &AccountInfo<'a>,
+    fee_vault_info: &AccountInfo<'a>,
+    reward_mint_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    let bump_seed = check_platform_fee_vault(program_id, pool, fee_vault_info)?;
+    if amount == 0 {
+        return Ok(());
+    }
+    if fee_vault_info.data_is_empty() {
+        create_self_owned_token_account(
+            payer_info,
+            fee_vault_info,
+            reward_mint_info,
+            token_program_info,
+            system_program_info,
+            &[PLATFORM_FEE_VAULT_SEED, pool.as_ref(), &[bump_seed]],
+        )?;
+    }
+    transfer_from_platform_treasury(
+        program_id,
+        pool,
+        platform_treasury_info,
+        fee_vault_info,
+        token_program_info,
+        amount,
+    )
+}
+
+/// Checks that `fee_vault_info` is the pool's platform fee vault and returns
+/// its bump
+pub(super) fn check_platform_fee_vault(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    fee_vault_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let (expected_fee_vault, bump_seed) =
+        find_platform_fee_vault_address_with_program_id(pool, program_id);
+    if *fee_vault_info.key != expected_fee_vault {
+        return Err(RewardPoolError::InvalidPlatformFeeVault.into());
+    }
+    Ok(bump_seed)
+}
+
+/// Moves up to `amount` out of the pool's platform fee vault, signed for by
+/// the program with its seeds, and returns what was moved: the fee vault
+/// never pays more than it holds, and nothing before its first fee
+pub(super) fn transfer_from_platform_fee_vault<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    fee_vault_info: &AccountInfo<'a>,
+    destination_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    amount: u64,
+) -> Result<u64, ProgramError> {
+    let bump_seed = check_platform_fee_vault(program_id, pool, fee_vault_info)?;
+    if fee_vault_info.data_is_empty() {
+        return Ok(0);
+    }
+    let amount = amount.min(token_balance(fee_vault_info)?);
+    if amount == 0 {
+        return Ok(0);
+    }
+    invoke_signed(
+        &token_instruction::transfer(
+            token_program_info.key,
+            fee_vault_info.key,
+            destination_info.key,
+            fee_vault_info.key,
+            &[],
+            amount,
+        )?,
+        &[
+            fee_vault_info.clone(),
+            destination_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[PLATFORM_FEE_VAULT_SEED, pool.as_ref(), &[bump_seed]]],
+    )?;
+    Ok(amount)
+}
@@ -0,0 +1,369 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{treasury::load_pool, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_payout_queue_address_with_program_id, find_payout_ticket_address_with_program_id,
+        find_pending_reward_address_with_program_id, PAYOUT_QUEUE_SEED, PAYOUT_TICKET_SEED,
+    },
+    state::{PayoutQueue, PayoutTicket, PendingReward, Sequenced},
+    MAX_CLAIM_BATCH, MAX_RECOVERY_WINDOW,
+};
+
+// Setting the recovery window of the payout queue
+pub(super) fn process_set_payout_queue(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    recovery_window: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let queue_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if !(0..=MAX_RECOVERY_WINDOW).contains(&recovery_window) {
+        return Err(RewardPoolError::InvalidRecoveryWindow.into());
+    }
+
+    let (expected_queue, bump_seed) =
+        find_payout_queue_address_with_program_id(pool_info.key, program_id);
+    if *queue_info.key != expected_queue {
+        return Err(RewardPoolError::InvalidPayoutQueueAccount.into());
+    }
+
+    let mut queue = if queue_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            queue_info,
+            system_program_info,
+            program_id,
+            PayoutQueue::LEN,
+            &[PAYOUT_QUEUE_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        PayoutQueue {
+            pool: *pool_info.key,
+            recovery_window: 0,
+            round: 0,
+            recovery_started_at: 0,
+            recovery_ends_at: 0,
+            oldest_score: 0,
+            newest_score: 0,
+            ticket_count: 0,
+            next_oldest_score: 0,
+            next_newest_score: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_payout_queue(program_id, pool_info.key, queue_info)?
+    };
+
+    // Turning the queue off releases everyone still waiting for their turn
+    if recovery_window == 0 {
+        queue.recovery_ends_at = queue.recovery_ends_at.min(Clock::get()?.unix_timestamp);
+    }
+    queue.recovery_window = recovery_window;
+    queue.save(queue_info)?;
+
+    RewardPoolEvent::PayoutQueueSet {
+        pool: *pool_info.key,
+        recovery_window,
+    }
+    .emit();
+
+    msg!("Payout queue recovery window set to {}", recovery_window);
+    Ok(())
+}
+
+// Taking a place in the payout queue of the next recovery window
+pub(super) fn process_take_payout_ticket(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let queue_info = next_account_info(account_info_iter)?;
+    let ticket_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let pending_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if pending_infos.len() > MAX_CLAIM_BATCH {
+        return Err(RewardPoolError::TooManyPendingRewards.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if !pool_data.is_paused {
+        return Err(RewardPoolError::PayoutTicketsClosed.into());
+    }
+
+    let (expected_queue, _) = find_payout_queue_address_with_program_id(pool_info.key, program_id);
+    if *queue_info.key != expected_queue {
+        return Err(RewardPoolError::InvalidPayoutQueueAccount.into());
+    }
+    if queue_info.data_is_empty() {
+        return Err(RewardPoolError::PayoutQueueDisabled.into());
+    }
+    let mut queue = load_payout_queue(program_id, pool_info.key, queue_info)?;
+    if !queue.is_enabled() {
+        return Err(RewardPoolError::PayoutQueueDisabled.into());
+    }
+
+    let (expected_ticket, bump_seed) =
+        find_payout_ticket_address_with_program_id(pool_info.key, farmer_info.key, program_id);
+    if *ticket_info.key != expected_ticket {
+        return Err(RewardPoolError::InvalidPayoutTicketAccount.into());
+    }
+
+    let round = queue.round + 1;
+    let mut ticket = if ticket_info.data_is_empty() {
+        create_pda_account(
+            farmer_info,
+            ticket_info,
+            system_program_info,
+            program_id,
+            PayoutTicket::LEN,
+            &[
+                PAYOUT_TICKET_SEED,
+                pool_info.key.as_ref(),
+                farmer_info.key.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        PayoutTicket {
+            pool: *pool_info.key,
+            farmer: *farmer_info.key,
+            round: 0,
+            number: 0,
+            score: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        let ticket = load_payout_ticket(program_id, pool_info.key, ticket_info)?;
+        if ticket.round == round {
+            return Err(RewardPoolError::PayoutTicketAlreadyTaken.into());
+        }
+        ticket
+    };
+
+    let score = oldest_claimable_reward(program_id, pool_info.key, farmer_info.key, pending_infos)?;
+
+    if queue.ticket_count == 0 {
+        queue.next_oldest_score = score;
+        queue.next_newest_score = score;
+    } else {
+        queue.next_oldest_score = queue.next_oldest_score.min(score);
+        queue.next_newest_score = queue.next_newest_score.max(score);
+    }
+    ticket.round = round;
+    ticket.number = queue.ticket_count;
+    ticket.score = score;
+    queue.ticket_count = queue
+        .ticket_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    ticket.save(ticket_info)?;
+    queue.save(queue_info)?;
+
+    RewardPoolEvent::PayoutTicketTaken {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        round,
+        number: ticket.number,
+        score,
+    }
+    .emit();
+
+    msg!(
+        "Took payout ticket {} of round {} with score {}",
+        ticket.number,
+        round,
+        score
+    );
+    Ok(())
+}
+
+/// Opens the recovery window of the tickets taken during the pause the pool
+/// resumes from, if `queue_info` is passed and the queue is on and holds any
+pub(super) fn start_recovery(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    queue_info: Option<&AccountInfo>,
+) -> ProgramResult {
+    let Some(queue_info) = queue_info else {
+        return Ok(());
+    };
+
+    let (expected_queue, _) = find_payout_queue_address_with_program_id(pool, program_id);
+    if *queue_info.key != expected_queue {
+        return Err(RewardPoolError::InvalidPayoutQueueAccount.into());
+    }
+    if queue_info.data_is_empty() {
+        return Ok(());
+    }
+    let mut queue = load_payout_queue(program_id, pool, queue_info)?;
+    if !queue.is_enabled() || queue.ticket_count == 0 {
+        return Ok(());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let ticket_count = queue.ticket_count;
+    queue.round += 1;
+    queue.recovery_started_at = now;
+    queue.recovery_ends_at = now.saturating_add(queue.recovery_window);
+    queue.oldest_score = queue.next_oldest_score;
+    queue.newest_score = queue.next_newest_score;
+    queue.ticket_count = 0;
+    queue.next_oldest_score = 0;
+    queue.next_newest_score = 0;
+    queue.save(queue_info)?;
+
+    RewardPoolEvent::RecoveryStarted {
+        pool: *pool,
+        round: queue.round,
+        ticket_count,
+        ends_at: queue.recovery_ends_at,
+    }
+    .emit();
+
+    msg!(
+        "Recovery round {} started for {} tickets",
+        queue.round,
+        ticket_count
+    );
+    Ok(())
+}
+
+/// Fails unless the farmer may claim from the pool at `now`: always outside
+/// a recovery window, and within one from the turn of their ticket for the
+/// round. Farmers without one wait for the window to end.
+pub(super) fn check_payout_turn(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    queue_info: &AccountInfo,
+    ticket_info: &AccountInfo,
+    now: i64,
+) -> ProgramResult {
+    let (expected_queue, _) = find_payout_queue_address_with_program_id(pool, program_id);
+    if *queue_info.key != expected_queue {
+        return Err(RewardPoolError::InvalidPayoutQueueAccount.into());
+    }
+    if queue_info.data_is_empty() {
+        return Ok(());
+    }
+    let queue = load_payout_queue(program_id, pool, queue_info)?;
+    if !queue.is_recovering(now) {
+        return Ok(());
+    }
+
+    let (expected_ticket, _) = find_payout_ticket_address_with_program_id(pool, farmer, program_id);
+    if *ticket_info.key != expected_ticket {
+        return Err(RewardPoolError::InvalidPayoutTicketAccount.into());
+    }
+    if ticket_info.data_is_empty() {
+        return Err(RewardPoolError::PayoutTurnNotReached.into());
+    }
+    let ticket = load_payout_ticket(program_id, pool, ticket_info)?;
+    if ticket.round != queue.round || now < queue.turn_of(ticket.score) {
+        return Err(RewardPoolError::PayoutTurnNotReached.into());
+    }
+    Ok(())
+}
+
+/// Recording time of the farmer's oldest reward among `pending_infos` that
+/// a claim would pay. Fails when none is left to claim.
+fn oldest_claimable_reward(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    pending_infos: &[AccountInfo],
+) -> Result<i64, ProgramError> {
+    let mut oldest: Option<i64> = None;
+    for pending_info in pending_infos {
+        if pending_info.owner != program_id {
+            return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+        }
+
+        let pending = PendingReward::try_from_slice(&pending_info.data.borrow())
+            .map_err(|_| RewardPoolError::InvalidPendingRewardAccount)?;
+        let (expected_address, _) =
+            find_pending_reward_address_with_program_id(pool, farmer, &pending.task_id, program_id);
+        if pending.farmer_pubkey != *farmer || *pending_info.key != expected_address {
+            return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+        }
+
+        if pending.is_withdrawn || pending.is_challenged || pending.awaiting_approval {
+            continue;
+        }
+        oldest = Some(oldest.map_or(pending.recorded_at, |oldest| {
+            oldest.min(pending.recorded_at)
+        }));
+    }
+
+    oldest.ok_or_else(|| RewardPoolError::NoPendingRewards.into())
+}
+
+fn load_payout_queue(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    queue_info: &AccountInfo,
+) -> Result<PayoutQueue, ProgramError> {
+    if queue_info.owner != program_id {
+        return Err(RewardPoolError::InvalidPayoutQueueAccount.into());
+    }
+    let queue = PayoutQueue::try_from_slice(&queue_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidPayoutQueueAccount)?;
+    let expected_queue = Pubkey::create_program_address(
+        &[PAYOUT_QUEUE_SEED, pool.as_ref(), &[queue.bump_seed]],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidPayoutQueueAccount)?;
+    if queue.pool != *pool || *queue_info.key != expected_queue {
+        return Err(RewardPoolError::InvalidPayoutQueueAccount.into());
+    }
+    Ok(queue)
+}
+
+fn load_payout_ticket(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    ticket_info: &AccountInfo,
+) -> Result<PayoutTicket, ProgramError> {
+    if ticket_info.owner != program_id {
+        return Err(RewardPoolError::InvalidPayoutTicketAccount.into());
+    }
+    PayoutTicket::try_from_slice(&ticket_info.data.borrow())
+        .ok()
+        .filter(|ticket| ticket.pool == *pool)
+        .ok_or_else(|| RewardPoolError::InvalidPayoutTicketAccount.into())
+}
@@ -0,0 +1,162 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{queue::start_recovery, treasury::load_pool, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{find_withdrawal_ramp_address_with_program_id, WITHDRAWAL_RAMP_SEED},
+    state::{Sequenced, WithdrawalRamp},
+};
+
+// Resuming the pool with claims ramping up epoch by epoch
+pub(super) fn process_resume_with_ramp(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    initial_epoch_cap: u64,
+    epoch_cap_step: u64,
+    ramp_epochs: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let ramp_info = next_account_info(account_info_iter)?;
+    let queue_info = account_info_iter.next();
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let mut pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if ramp_epochs > 0 && initial_epoch_cap == 0 {
+        return Err(RewardPoolError::InvalidWithdrawalRamp.into());
+    }
+
+    let (expected_ramp, bump_seed) =
+        find_withdrawal_ramp_address_with_program_id(pool_info.key, program_id);
+    if *ramp_info.key != expected_ramp {
+        return Err(RewardPoolError::InvalidWithdrawalRampAccount.into());
+    }
+
+    let mut ramp = if ramp_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            ramp_info,
+            system_program_info,
+            program_id,
+            WithdrawalRamp::LEN,
+            &[WITHDRAWAL_RAMP_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        WithdrawalRamp {
+            pool: *pool_info.key,
+            start_epoch: 0,
+            initial_epoch_cap: 0,
+            epoch_cap_step: 0,
+            ramp_epochs: 0,
+            epoch: 0,
+            epoch_claimed: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_withdrawal_ramp(program_id, pool_info.key, ramp_info)?
+    };
+
+    // The ramp restarts from its first epoch, whatever was claimed before
+    let epoch = Clock::get()?.epoch;
+    ramp.start_epoch = epoch;
+    ramp.initial_epoch_cap = initial_epoch_cap;
+    ramp.epoch_cap_step = epoch_cap_step;
+    ramp.ramp_epochs = ramp_epochs;
+    ramp.epoch = epoch;
+    ramp.epoch_claimed = 0;
+    ramp.save(ramp_info)?;
+
+    pool_data.is_paused = false;
+    pool_data.save(pool_info)?;
+
+    start_recovery(program_id, pool_info.key, queue_info)?;
+
+    RewardPoolEvent::PoolResumed {
+        pool: *pool_info.key,
+    }
+    .emit();
+    RewardPoolEvent::WithdrawalRampStarted {
+        pool: *pool_info.key,
+        start_epoch: epoch,
+        initial_epoch_cap,
+        epoch_cap_step,
+        ramp_epochs,
+    }
+    .emit();
+
+    msg!(
+        "Pool resumed with claims capped at {} per epoch, {} more each epoch for {} epochs",
+        initial_epoch_cap,
+        epoch_cap_step,
+        ramp_epochs
+    );
+    Ok(())
+}
+
+/// Books a claim of `amount` from the treasury vault against the pool's
+/// withdrawal ramp, failing once the current epoch's cap is reached. Pools
+/// that never ramped, or whose ramp is over, are not capped.
+pub(super) fn apply_withdrawal_ramp(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    ramp_info: &AccountInfo,
+    amount: u64,
+) -> ProgramResult {
+    let (expected_ramp, _) = find_withdrawal_ramp_address_with_program_id(pool, program_id);
+    if *ramp_info.key != expected_ramp {
+        return Err(RewardPoolError::InvalidWithdrawalRampAccount.into());
+    }
+    if ramp_info.data_is_empty() {
+        return Ok(());
+    }
+
+    let mut ramp = load_withdrawal_ramp(program_id, pool, ramp_info)?;
+    let epoch = Clock::get()?.epoch;
+    if ramp.epoch_cap(epoch).is_none() {
+        return Ok(());
+    }
+    ramp.record_claim(epoch, amount)?;
+    ramp.save(ramp_info)
+}
+
+fn load_withdrawal_ramp(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    ramp_info: &AccountInfo,
+) -> Result<WithdrawalRamp, ProgramError> {
+    if ramp_info.owner != program_id {
+        return Err(RewardPoolError::InvalidWithdrawalRampAccount.into());
+    }
+    let ramp = WithdrawalRamp::try_from_slice(&ramp_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidWithdrawalRampAccount)?;
+    let expected_ramp = Pubkey::create_program_address(
+        &[WITHDRAWAL_RAMP_SEED, pool.as_ref(), &[ramp.bump_seed]],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidWithdrawalRampAccount)?;
+    if ramp.pool != *pool || *ramp_info.key != expected_ramp {
+        return Err(RewardPoolError::InvalidWithdrawalRampAccount.into());
+    }
+    Ok(ramp)
+}
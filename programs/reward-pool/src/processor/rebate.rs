@@ -0,0 +1,271 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use spl_token::instruction as token_instruction;
+
+use super::{
+    secondary::{check_secondary_vault, load_secondary_reward},
+    treasury::load_pool,
+    utils::{create_pda_account, create_self_owned_token_account, token_balance},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_fee_rebate_address_with_program_id, find_rebate_vault_address_with_program_id,
+        find_secondary_reward_address_with_program_id, FEE_REBATE_SEED, REBATE_VAULT_SEED,
+    },
+    state::{FeeRebate, Sequenced},
+    BASIS_POINTS,
+};
+
+// Setting the fee rebate of a pool
+pub(super) fn process_set_fee_rebate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    rebate_bps: u16,
+    rate_per_million: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let fee_rebate_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let secondary_reward_info = next_account_info(account_info_iter)?;
+    let rebate_vault_info = next_account_info(account_info_iter)?;
+    let governance_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    // Rebates are booked to secondary balances, so the governance token
+    // must be the pool's secondary mint
+    let (expected_secondary_reward, _) =
+        find_secondary_reward_address_with_program_id(pool_info.key, program_id);
+    if *secondary_reward_info.key != expected_secondary_reward {
+        return Err(RewardPoolError::InvalidSecondaryRewardAccount.into());
+    }
+    if secondary_reward_info.data_is_empty() {
+        return Err(RewardPoolError::SecondaryRewardsDisabled.into());
+    }
+    let secondary_reward = load_secondary_reward(program_id, pool_info.key, secondary_reward_info)?;
+    if *governance_mint_info.key != secondary_reward.secondary_mint || rebate_bps > BASIS_POINTS {
+        return Err(RewardPoolError::InvalidFeeRebate.into());
+    }
+
+    let (expected_fee_rebate, bump_seed) =
+        find_fee_rebate_address_with_program_id(pool_info.key, program_id);
+    if *fee_rebate_info.key != expected_fee_rebate {
+        return Err(RewardPoolError::InvalidFeeRebateAccount.into());
+    }
+
+    let mut fee_rebate = if fee_rebate_info.data_is_empty() {
+        let (expected_rebate_vault, vault_bump_seed) =
+            find_rebate_vault_address_with_program_id(pool_info.key, program_id);
+        if *rebate_vault_info.key != expected_rebate_vault
+            || *token_program_info.key != spl_token::id()
+        {
+            return Err(RewardPoolError::InvalidRebateVaultAccount.into());
+        }
+
+        // Like the secondary vault, the rebate vault is its own owner
+        create_self_owned_token_account(
+            platform_authority_info,
+            rebate_vault_info,
+            governance_mint_info,
+            token_program_info,
+            system_program_info,
+            &[
+                REBATE_VAULT_SEED,
+                pool_info.key.as_ref(),
+                &[vault_bump_seed],
+            ],
+        )?;
+        create_pda_account(
+            platform_authority_info,
+            fee_rebate_info,
+            system_program_info,
+            program_id,
+            FeeRebate::LEN,
+            &[FEE_REBATE_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        FeeRebate {
+            pool: *pool_info.key,
+            governance_mint: *governance_mint_info.key,
+            rebate_bps: 0,
+            rate_per_million: 0,
+            total_rebated: 0,
+            rebate_count: 0,
+            vault_bump_seed,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_fee_rebate(program_id, pool_info.key, fee_rebate_info)?
+    };
+
+    fee_rebate.rebate_bps = rebate_bps;
+    fee_rebate.rate_per_million = rate_per_million;
+    fee_rebate.save(fee_rebate_info)?;
+
+    RewardPoolEvent::FeeRebateSet {
+        pool: *pool_info.key,
+        governance_mint: fee_rebate.governance_mint,
+        rebate_bps,
+        rate_per_million,
+    }
+    .emit();
+
+    msg!(
+        "Fee rebate set to {} bps at {} per million",
+        rebate_bps,
+        rate_per_million
+    );
+    Ok(())
+}
+
+/// Moves the rebate on `platform_fee` from the rebate vault to the secondary
+/// vault and returns it, for the caller to book to the farmer's secondary
+/// balance. The rebate is capped at what the rebate vault holds; pools
+/// without a fee rebate rebate nothing and need none of the vaults.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn rebate_platform_fee<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    fee_rebate_info: &AccountInfo<'a>,
+    rebate_vault_info: Option<&AccountInfo<'a>>,
+    secondary_reward_info: Option<&AccountInfo<'a>>,
+    secondary_vault_info: Option<&AccountInfo<'a>>,
+    token_program_info: &AccountInfo<'a>,
+    platform_fee: u64,
+) -> Result<u64, ProgramError> {
+    let (expected_fee_rebate, _) = find_fee_rebate_address_with_program_id(pool, program_id);
+    if *fee_rebate_info.key != expected_fee_rebate {
+        return Err(RewardPoolError::InvalidFeeRebateAccount.into());
+    }
+    if fee_rebate_info.data_is_empty() {
+        return Ok(0);
+    }
+    let mut fee_rebate = load_fee_rebate(program_id, pool, fee_rebate_info)?;
+    let rebate_vault_info = rebate_vault_info.ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    let expected_rebate_vault = Pubkey::create_program_address(
+        &[
+            REBATE_VAULT_SEED,
+            pool.as_ref(),
+            &[fee_rebate.vault_bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidRebateVaultAccount)?;
+    if *rebate_vault_info.key != expected_rebate_vault {
+        return Err(RewardPoolError::InvalidRebateVaultAccount.into());
+    }
+
+    let rebate = fee_rebate
+        .rebate(platform_fee)
+        .min(token_balance(rebate_vault_info)?);
+    if rebate == 0 {
+        return Ok(0);
+    }
+
+    // The secondary mint is set once, so it still matches the rebate vault's
+    let secondary_reward_info = secondary_reward_info.ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let secondary_vault_info = secondary_vault_info.ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let secondary_reward = load_secondary_reward(program_id, pool, secondary_reward_info)?;
+    check_secondary_vault(program_id, pool, &secondary_reward, secondary_vault_info)?;
+    if *token_program_info.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    invoke_signed(
+        &token_instruction::transfer(
+            token_program_info.key,
+            rebate_vault_info.key,
+            secondary_vault_info.key,
+            rebate_vault_info.key,
+            &[],
+            rebate,
+        )?,
+        &[
+            rebate_vault_info.clone(),
+            secondary_vault_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[
+            REBATE_VAULT_SEED,
+            pool.as_ref(),
+            &[fee_rebate.vault_bump_seed],
+        ]],
+    )?;
+
+    fee_rebate.total_rebated = fee_rebate.total_rebated.saturating_add(rebate);
+    fee_rebate.rebate_count = fee_rebate
+        .rebate_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    fee_rebate.save(fee_rebate_info)?;
+
+    RewardPoolEvent::FeeRebated {
+        pool: *pool,
+        farmer: *farmer,
+        platform_fee,
+        rebate,
+    }
+    .emit();
+
+    Ok(rebate)
+}
+
+/// Returns the fee rebate of the pool, `None` when the pool has none
+pub(super) fn read_fee_rebate(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    fee_rebate_info: &AccountInfo,
+) -> Result<Option<FeeRebate>, ProgramError> {
+    let (expected_fee_rebate, _) = find_fee_rebate_address_with_program_id(pool, program_id);
+    if *fee_rebate_info.key != expected_fee_rebate {
+        return Err(RewardPoolError::InvalidFeeRebateAccount.into());
+    }
+    if fee_rebate_info.data_is_empty() {
+        return Ok(None);
+    }
+    load_fee_rebate(program_id, pool, fee_rebate_info).map(Some)
+}
+
+fn load_fee_rebate(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    fee_rebate_info: &AccountInfo,
+) -> Result<FeeRebate, ProgramError> {
+    if fee_rebate_info.owner != program_id {
+        return Err(RewardPoolError::InvalidFeeRebateAccount.into());
+    }
+    let fee_rebate = FeeRebate::try_from_slice(&fee_rebate_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidFeeRebateAccount)?;
+    let expected_fee_rebate = Pubkey::create_program_address(
+        &[FEE_REBATE_SEED, pool.as_ref(), &[fee_rebate.bump_seed]],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidFeeRebateAccount)?;
+    if fee_rebate.pool != *pool || *fee_rebate_info.key != expected_fee_rebate {
+        return Err(RewardPoolError::InvalidFeeRebateAccount.into());
+    }
+    Ok(fee_rebate)
+}
@@ -0,0 +1,258 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{
+    farmer::load_withdrawal_history,
+    treasury::load_pool,
+    utils::{close_pda_account, create_pda_account},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_reward_receipt_address_with_program_id,
+        find_withdrawal_history_address_with_program_id,
+        find_withdrawal_record_address_with_program_id, REWARD_RECEIPT_SEED,
+        WITHDRAWAL_RECORD_SEED,
+    },
+    state::{RewardReceipt, Sequenced, WithdrawalRecord},
+    WITHDRAWAL_RECORD_RETENTION,
+};
+
+/// Returns whether a reward was already recorded under `idempotency_key`.
+/// Fails when the key was used for a reward of another farmer, amount or
+/// task, so a reused key is never taken for a retry.
+pub(super) fn check_reward_receipt(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    receipt_info: &AccountInfo,
+    idempotency_key: &[u8; 32],
+    farmer: &Pubkey,
+    amount: u64,
+    task_hash: &[u8; 32],
+) -> Result<bool, ProgramError> {
+    let (expected_receipt, _) =
+        find_reward_receipt_address_with_program_id(pool, idempotency_key, program_id);
+    if *receipt_info.key != expected_receipt {
+        return Err(RewardPoolError::InvalidRewardReceiptAccount.into());
+    }
+    if receipt_info.data_is_empty() {
+        return Ok(false);
+    }
+
+    if receipt_info.owner != program_id {
+        return Err(RewardPoolError::InvalidRewardReceiptAccount.into());
+    }
+    let receipt = RewardReceipt::try_from_slice(&receipt_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidRewardReceiptAccount)?;
+    if receipt.pool != *pool || receipt.idempotency_key != *idempotency_key {
+        return Err(RewardPoolError::InvalidRewardReceiptAccount.into());
+    }
+    if !receipt.matches(farmer, amount, task_hash) {
+        return Err(RewardPoolError::IdempotencyKeyReused.into());
+    }
+    Ok(true)
+}
+
+/// Writes the receipt of a reward recorded under `idempotency_key`, the
+/// payer funding its rent
+#[allow(clippy::too_many_arguments)]
+pub(super) fn create_reward_receipt<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    receipt_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    idempotency_key: [u8; 32],
+    farmer: Pubkey,
+    amount: u64,
+    task_hash: [u8; 32],
+) -> ProgramResult {
+    let (_, bump_seed) =
+        find_reward_receipt_address_with_program_id(pool, &idempotency_key, program_id);
+    create_pda_account(
+        payer_info,
+        receipt_info,
+        system_program_info,
+        program_id,
+        RewardReceipt::LEN,
+        &[
+            REWARD_RECEIPT_SEED,
+            pool.as_ref(),
+            &idempotency_key,
+            &[bump_seed],
+        ],
+    )?;
+
+    let clock = Clock::get()?;
+    RewardReceipt {
+        pool: *pool,
+        idempotency_key,
+        farmer,
+        amount,
+        task_hash,
+        recorded_at: clock.unix_timestamp,
+        slot: clock.slot,
+        bump_seed,
+        sequence: 0,
+    }
+    .save(receipt_info)
+}
+
+/// Fails with `InvalidNonce` when the farmer already withdrew under `nonce`,
+/// whether its record is still open or was closed
+pub(super) fn check_withdrawal_record(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    history_info: &AccountInfo,
+    record_info: &AccountInfo,
+    nonce: u64,
+) -> ProgramResult {
+    let (expected_record, _) =
+        find_withdrawal_record_address_with_program_id(pool, farmer, nonce, program_id);
+    if *record_info.key != expected_record {
+        return Err(RewardPoolError::InvalidWithdrawalRecordAccount.into());
+    }
+    if !record_info.data_is_empty() {
+        return Err(RewardPoolError::InvalidNonce.into());
+    }
+
+    let (expected_history, _) =
+        find_withdrawal_history_address_with_program_id(pool, farmer, program_id);
+    if *history_info.key != expected_history {
+        return Err(RewardPoolError::InvalidWithdrawalHistoryAccount.into());
+    }
+    if !history_info.data_is_empty()
+        && nonce < load_withdrawal_history(program_id, history_info)?.nonce_floor
+    {
+        return Err(RewardPoolError::InvalidNonce.into());
+    }
+    Ok(())
+}
+
+/// Persists `record` at the PDA of its farmer and nonce, the payer funding
+/// its rent
+pub(super) fn create_withdrawal_record<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    record_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    record: &WithdrawalRecord,
+) -> ProgramResult {
+    let (_, bump_seed) = find_withdrawal_record_address_with_program_id(
+        pool,
+        &record.farmer_pubkey,
+        record.nonce,
+        program_id,
+    );
+    create_pda_account(
+        payer_info,
+        record_info,
+        system_program_info,
+        program_id,
+        WithdrawalRecord::LEN,
+        &[
+            WITHDRAWAL_RECORD_SEED,
+            pool.as_ref(),
+            record.farmer_pubkey.as_ref(),
+            &record.nonce.to_le_bytes(),
+            &[bump_seed],
+        ],
+    )?;
+    record.serialize(&mut &mut record_info.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+// Closing withdrawal records past their retention
+pub(super) fn process_close_withdrawal_records(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let _system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let history_info = next_account_info(account_info_iter)?;
+    let record_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    load_pool(program_id, pool_info)?;
+
+    // Each record is followed by whoever paid its rent
+    if !record_infos.len().is_multiple_of(2) {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    // Every record was written with the history, which keeps its nonce
+    // spent once the record is gone
+    let (expected_history, _) =
+        find_withdrawal_history_address_with_program_id(pool_info.key, farmer_info.key, program_id);
+    if *history_info.key != expected_history {
+        return Err(RewardPoolError::InvalidWithdrawalHistoryAccount.into());
+    }
+    let mut history = load_withdrawal_history(program_id, history_info)?;
+
+    // Records outlive any retry of their withdrawal
+    let now = Clock::get()?.unix_timestamp;
+    for pair in record_infos.chunks_exact(2) {
+        let (record_info, rent_recipient_info) = (&pair[0], &pair[1]);
+        if record_info.owner != program_id {
+            return Err(RewardPoolError::InvalidWithdrawalRecordAccount.into());
+        }
+        let record = WithdrawalRecord::try_from_slice(&record_info.data.borrow())
+            .map_err(|_| RewardPoolError::InvalidWithdrawalRecordAccount)?;
+        let (expected_record, _) = find_withdrawal_record_address_with_program_id(
+            pool_info.key,
+            farmer_info.key,
+            record.nonce,
+            program_id,
+        );
+        if record.farmer_pubkey != *farmer_info.key || *record_info.key != expected_record {
+            return Err(RewardPoolError::InvalidWithdrawalRecordAccount.into());
+        }
+        if now
+            < record
+                .withdrawn_at
+                .saturating_add(WITHDRAWAL_RECORD_RETENTION)
+        {
+            return Err(RewardPoolError::WithdrawalRecordRetained.into());
+        }
+        if *rent_recipient_info.key != record.payer {
+            return Err(RewardPoolError::InvalidRentRecipient.into());
+        }
+        history.nonce_floor = history.nonce_floor.max(record.nonce.saturating_add(1));
+        close_pda_account(record_info, rent_recipient_info)?;
+    }
+    history.save(history_info)?;
+
+    let count = (record_infos.len() / 2) as u32;
+    RewardPoolEvent::WithdrawalRecordsClosed {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        count,
+        nonce_floor: history.nonce_floor,
+    }
+    .emit();
+
+    msg!(
+        "Closed {} withdrawal records of farmer {}, nonces below {} spent",
+        count,
+        farmer_info.key,
+        history.nonce_floor
+    );
+    Ok(())
+}
@@ -0,0 +1,1892 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    hash::hash,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token::{instruction as token_instruction, state::Account as TokenAccount};
+
+use super::{
+    approval::read_reward_approver,
+    attestation::check_task_attestation,
+    budget::{load_task_budget, transfer_from_budget_escrow},
+    campaign::{load_reward_campaign, record_campaign_reward},
+    claim_shard::record_claim,
+    factory::pay_protocol_fee,
+    farmer::{
+        check_claim_destination, check_withdrawal_cosigner, load_farmer_state, record_withdrawal,
+        update_farmer_state,
+    },
+    fees::{farmer_fee_discount, task_type_fee},
+    governor::{read_reward_governor, record_governed_reward},
+    guild::{load_guild_split, pay_guild_share},
+    interest::{load_claim_interest_policy, pay_claim_interest},
+    milestones::track_farmer_milestones,
+    parameters::load_parameters,
+    pending::{create_pending_reward, release_outstanding_rewards},
+    platform::{load_pool_platform, record_platform_reward},
+    program_treasury::{check_platform_treasury, deposit_platform_fee},
+    queue::check_payout_turn,
+    ramp::apply_withdrawal_ramp,
+    rebate::rebate_platform_fee,
+    receipt::{
+        check_reward_receipt, check_withdrawal_record, create_reward_receipt,
+        create_withdrawal_record,
+    },
+    reward_rate::record_reward_rate,
+    secondary::{book_secondary_reward, pay_secondary_rewards},
+    stats::update_daily_stats,
+    treasury::{check_approvals, load_pool, read_governance},
+    verifier::check_reward_verifier,
+    whitelist::{check_payout_address, check_withdrawal_destination, effective_payout_address},
+    withdraw_burn::{burn_withdrawal, read_withdraw_burn},
+    withdrawal_fee::{charge_withdrawal_fee, read_withdrawal_fee},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    memo::task_memo,
+    seeds::{
+        find_farmer_state_address_with_program_id, find_pending_reward_address_with_program_id,
+        find_vault_address_with_program_id, VAULT_SEED,
+    },
+    state::{
+        ClaimTotals, InterestPolicy, PendingReward, PoolAddressKind, RewardOrigin, Sequenced,
+        WithdrawalRecord, WithdrawalSplit,
+    },
+    AUTO_CLAIM_TIP_BPS, BASIS_POINTS, MAX_CLAIM_BATCH, MAX_CLAIM_POOLS, MAX_WITHDRAWAL_SPLITS,
+};
+
+// Fixed accounts of each pool group of `ClaimAcrossPools`, before its
+// pending rewards
+const CLAIM_GROUP_ACCOUNTS: usize = 15;
+
+// Most accounts a V1 `RecordReward` passed: its seven, then optionally the
+// daily stats, system program and instruction counters
+const V1_RECORD_REWARD_ACCOUNTS: usize = 10;
+
+// Recording a reward
+#[allow(clippy::too_many_arguments)]
+pub(super) fn process_record_reward(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    farmer_pubkey: Pubkey,
+    task_id: String,
+    campaign_id: Option<u64>,
+    platform_id: Option<u64>,
+    proof_hash: Option<[u8; 32]>,
+    task_type: Option<u32>,
+    secondary_amount: u64,
+    idempotency_key: Option<[u8; 32]>,
+) -> ProgramResult {
+    // A V1 account list paid the farmer from the authority's own account,
+    // outside the vault and every check on it, so it is no longer accepted
+    if accounts.len() <= V1_RECORD_REWARD_ACCOUNTS {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let platform_treasury_info = next_account_info(account_info_iter)?;
+    let _farmer_reward_account_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let _ata_program_info = next_account_info(account_info_iter)?;
+    let daily_stats_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let farmer_state_info = next_account_info(account_info_iter)?;
+    let pool_verifier_info = next_account_info(account_info_iter)?;
+    let pool_attestor_info = next_account_info(account_info_iter)?;
+    let attestation_info = next_account_info(account_info_iter)?;
+    let task_budget_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let reward_approval_info = next_account_info(account_info_iter)?;
+    let pending_info = next_account_info(account_info_iter)?;
+    let platform_fee_vault_info = next_account_info(account_info_iter)?;
+    let fee_overrides_info = next_account_info(account_info_iter)?;
+    let platform_info = next_account_info(account_info_iter)?;
+    let campaign_info = next_account_info(account_info_iter)?;
+    let fee_discounts_info = next_account_info(account_info_iter)?;
+    let stake_info = next_account_info(account_info_iter)?;
+    let governor_info = next_account_info(account_info_iter)?;
+    let milestone_bonuses_info = next_account_info(account_info_iter)?;
+    let fee_rebate_info = next_account_info(account_info_iter)?;
+    let reward_rate_info = next_account_info(account_info_iter)?;
+    let governance_info = next_account_info(account_info_iter)?;
+    // Feature accounts, optional trailing accounts ending at the first
+    // signer: a reward needing one the list does not reach fails. The pool
+    // verifier and treasury approvers follow, in any order.
+    let remaining_infos = account_info_iter.as_slice();
+    let feature_count = remaining_infos
+        .iter()
+        .take_while(|info| !info.is_signer)
+        .count();
+    let (feature_infos, cosigner_infos) = remaining_infos.split_at(feature_count);
+    let feature_info_iter = &mut feature_infos.iter();
+    let receipt_info = feature_info_iter.next();
+    let memo_program_info = feature_info_iter.next();
+    let farmer_stats_info = feature_info_iter.next();
+    let milestone_pending_info = feature_info_iter.next();
+    let secondary_reward_info = feature_info_iter.next();
+    let secondary_vault_info = feature_info_iter.next();
+    let secondary_balance_info = feature_info_iter.next();
+    let rebate_vault_info = feature_info_iter.next();
+    let factory_record_info = feature_info_iter.next();
+    let protocol_config_info = feature_info_iter.next();
+    let protocol_fee_info = feature_info_iter.next();
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    // Load pool
+    let mut pool_data = load_pool(program_id, pool_info)?;
+
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    check_platform_treasury(
+        program_id,
+        pool_info.key,
+        &pool_data,
+        platform_treasury_info,
+    )?;
+
+    // A retry of a reward that already landed succeeds without paying again
+    let task_hash = hash(task_id.as_bytes()).to_bytes();
+    if let Some(idempotency_key) = &idempotency_key {
+        if check_reward_receipt(
+            program_id,
+            pool_info.key,
+            receipt_info.ok_or(ProgramError::NotEnoughAccountKeys)?,
+            idempotency_key,
+            &farmer_pubkey,
+            amount,
+            &task_hash,
+        )? {
+            msg!("Reward already recorded under its idempotency key");
+            return Ok(());
+        }
+    }
+    let requested_amount = amount;
+
+    // Rewards of a platform's pools are attributed to that platform
+    let mut platform = load_pool_platform(program_id, &pool_data, platform_info)?;
+    let platform_id = match &platform {
+        Some(platform) if platform_id.is_some_and(|id| id != platform.platform_id) => {
+            return Err(RewardPoolError::PlatformMismatch.into());
+        }
+        Some(platform) => Some(platform.platform_id),
+        None => platform_id,
+    };
+
+    let verifier = check_reward_verifier(
+        program_id,
+        pool_info.key,
+        pool_verifier_info,
+        cosigner_infos,
+    )?;
+    let attestation = check_task_attestation(
+        program_id,
+        pool_info.key,
+        pool_attestor_info,
+        attestation_info,
+        &task_id,
+        &farmer_pubkey,
+        amount,
+    )?;
+    let approver = read_reward_approver(program_id, pool_info.key, reward_approval_info)?;
+    // A kicker is claimable as soon as it is booked, so it cannot wait for
+    // the approver like the reward
+    if approver.is_some() && secondary_amount > 0 {
+        return Err(RewardPoolError::SecondaryRewardAwaitingApproval.into());
+    }
+
+    // The governor scales the reward down while the treasury's runway is
+    // short; budgets and campaigns are charged the scaled amount
+    let now = Clock::get()?.unix_timestamp;
+    let (mut governor, governor_factor_bps) = read_reward_governor(
+        program_id,
+        pool_info.key,
+        governor_info,
+        platform_treasury_info,
+        now,
+    )?;
+    let amount = (amount as u128 * governor_factor_bps as u128 / BASIS_POINTS as u128) as u64;
+
+    // Under treasury governance, rewards above the approval threshold need
+    // the approvers' signatures, whether the treasury or a budget it locked
+    // pays them; a platform authority that is an approver counts as one
+    if let Some(governance) = read_governance(program_id, pool_info.key, governance_info)? {
+        if governance.requires_approval(amount) {
+            check_approvals(&governance, accounts)?;
+        }
+    }
+
+    // Calculate platform fees, at the task type's rate if it has one
+    let fee_percentage = match task_type {
+        Some(task_type) => task_type_fee(program_id, pool_info.key, fee_overrides_info, task_type)?,
+        None => None,
+    }
+    .unwrap_or(pool_data.platform_fee_percentage);
+    let fee_discount_bps = farmer_fee_discount(
+        program_id,
+        pool_info.key,
+        fee_discounts_info,
+        stake_info,
+        &farmer_pubkey,
+    )?;
+    let platform_fee = amount
+        .checked_mul(fee_percentage as u64)
+        .and_then(|fee| fee.checked_div(100))
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    // Stakers are waived part of the fee according to their tier
+    let platform_fee = platform_fee
+        - (platform_fee as u128 * fee_discount_bps as u128 / BASIS_POINTS as u128) as u64;
+    let farmer_amount = amount - platform_fee;
+
+    let mut campaign = campaign_id
+        .map(|campaign_id| {
+            load_reward_campaign(
+                program_id,
+                pool_info.key,
+                campaign_info,
+                campaign_id,
+                task_type,
+                amount,
+                now,
+            )
+        })
+        .transpose()?;
+
+    // A budget locked for the task pays the farmer; the platform fee part
+    // stays locked until the budget is released
+    let mut task_budget = load_task_budget(program_id, pool_info.key, &task_id, task_budget_info)?
+        .filter(|task_budget| !task_budget.is_recorded);
+    if let Some(task_budget) = &mut task_budget {
+        if amount > task_budget.amount {
+            return Err(RewardPoolError::TaskBudgetExceeded.into());
+        }
+        task_budget.amount -= farmer_amount;
+        task_budget.is_recorded = true;
+        task_budget.save(task_budget_info)?;
+    }
+
+    // Otherwise the campaign's budget, locked in the same escrow, pays
+    if let Some(campaign) = &mut campaign {
+        let paid = if task_budget.is_some() {
+            0
+        } else {
+            farmer_amount
+        };
+        record_campaign_reward(campaign, campaign_info, amount, paid)?;
+    }
+    let from_budget = task_budget.is_some() || campaign.is_some();
+
+    // Update pool statistics
+    pool_data.total_rewards_distributed += farmer_amount;
+    pool_data.total_platform_fees_collected += platform_fee;
+
+    if let Some(platform) = &mut platform {
+        record_platform_reward(platform, platform_info, farmer_amount, platform_fee)?;
+    }
+
+    // Only what the platform treasury pays counts towards its runway
+    if let Some(governor) = &mut governor {
+        let distributed = if from_budget { 0 } else { farmer_amount };
+        record_governed_reward(governor, governor_info, distributed, governor_factor_bps)?;
+    }
+
+    let farmer_state = update_farmer_state(
+        program_id,
+        pool_info.key,
+        &farmer_pubkey,
+        farmer_state_info,
+        platform_authority_info,
+        system_program_info,
+        |farmer_state| farmer_state.last_recorded_at = now,
+    )?;
+
+    // Rewards stay in the treasury vault, booked as the farmer's pending
+    // reward for the task until they withdraw or claim it, and approved
+    // first when the pool has an approver
+    create_pending_reward(
+        program_id,
+        pool_info.key,
+        pending_info,
+        platform_authority_info,
+        system_program_info,
+        &PendingReward {
+            farmer_pubkey,
+            amount: farmer_amount,
+            task_id: task_id.clone(),
+            recorded_at: now,
+            is_withdrawn: false,
+            campaign_id,
+            platform_id,
+            proof_hash,
+            is_challenged: false,
+            awaiting_approval: approver.is_some(),
+            origin: RewardOrigin::Task,
+            interest_owed: 0,
+        },
+    )?;
+
+    // A budgeted reward moves from the budget escrow into the vault
+    if from_budget {
+        transfer_from_budget_escrow(
+            program_id,
+            pool_info.key,
+            escrow_info,
+            platform_treasury_info,
+            token_program_info,
+            farmer_amount,
+        )?;
+    }
+    if let Some(memo_program_info) = memo_program_info {
+        tag_task_reward(memo_program_info, &task_id)?;
+    }
+
+    // The protocol's share of a factory pool's fee leaves the platform
+    // treasury and the rest moves to the fee vault; fees of budgeted rewards
+    // stay locked in the escrow
+    if !from_budget {
+        let protocol_fee = if pool_data.address_kind == PoolAddressKind::Factory {
+            pay_protocol_fee(
+                program_id,
+                pool_info.key,
+                &pool_data.reward_mint,
+                factory_record_info.ok_or(ProgramError::NotEnoughAccountKeys)?,
+                protocol_config_info.ok_or(ProgramError::NotEnoughAccountKeys)?,
+                protocol_fee_info.ok_or(ProgramError::NotEnoughAccountKeys)?,
+                platform_treasury_info,
+                token_program_info,
+                platform_fee,
+            )?
+        } else {
+            0
+        };
+        deposit_platform_fee(
+            program_id,
+            pool_info.key,
+            platform_treasury_info,
+            platform_fee_vault_info,
+            reward_mint_info,
+            platform_authority_info,
+            token_program_info,
+            system_program_info,
+            platform_fee - protocol_fee,
+        )?;
+    }
+
+    // Like a kicker, a rebate is claimable as soon as it is booked, so none
+    // is paid while rewards wait for the approver
+    let rebate = if approver.is_none() {
+        rebate_platform_fee(
+            program_id,
+            pool_info.key,
+            &farmer_pubkey,
+            fee_rebate_info,
+            rebate_vault_info,
+            secondary_reward_info,
+            secondary_vault_info,
+            token_program_info,
+            platform_fee,
+        )?
+    } else {
+        0
+    };
+    book_secondary_reward(
+        program_id,
+        pool_info.key,
+        &farmer_pubkey,
+        secondary_reward_info,
+        secondary_vault_info,
+        secondary_balance_info,
+        platform_authority_info,
+        system_program_info,
+        secondary_amount
+            .checked_add(rebate)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    )?;
+
+    // Crossing a milestone books its bonus as a pending reward of its own
+    let bonus = track_farmer_milestones(
+        program_id,
+        pool_info.key,
+        &farmer_pubkey,
+        milestone_bonuses_info,
+        farmer_stats_info,
+        milestone_pending_info,
+        platform_authority_info,
+        system_program_info,
+        farmer_amount,
+        approver.is_some(),
+        now,
+    )?;
+
+    // Save pool, which owes the reward and any bonus to the farmer now
+    pool_data.outstanding_rewards = pool_data
+        .outstanding_rewards
+        .checked_add(farmer_amount)
+        .and_then(|outstanding| outstanding.checked_add(bonus))
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    pool_data.save(pool_info)?;
+
+    update_daily_stats(
+        program_id,
+        pool_info.key,
+        daily_stats_info,
+        platform_authority_info,
+        system_program_info,
+        |stats| {
+            stats.total_distributed += farmer_amount;
+            stats.total_platform_fees += platform_fee;
+            stats.reward_count += 1;
+            stats.observe_farmer(&farmer_pubkey);
+        },
+    )?;
+    record_reward_rate(
+        program_id,
+        pool_info.key,
+        reward_rate_info,
+        platform_authority_info,
+        system_program_info,
+        farmer_amount,
+        now,
+    )?;
+    if let Some(idempotency_key) = idempotency_key {
+        create_reward_receipt(
+            program_id,
+            pool_info.key,
+            receipt_info.ok_or(ProgramError::NotEnoughAccountKeys)?,
+            platform_authority_info,
+            system_program_info,
+            idempotency_key,
+            farmer_pubkey,
+            requested_amount,
+            task_hash,
+        )?;
+    }
+
+    RewardPoolEvent::RewardRecorded {
+        pool: *pool_info.key,
+        farmer: farmer_pubkey,
+        task_id,
+        amount: farmer_amount,
+        platform_fee,
+        campaign_id,
+        platform_id,
+        task_type,
+        fee_discount_bps,
+        governor_factor_bps,
+        secondary_amount,
+        proof_hash,
+        verifier,
+        attestation,
+        from_budget,
+        awaiting_approval: approver.is_some(),
+        last_recorded_at: farmer_state.last_recorded_at,
+        last_claimed_at: farmer_state.last_claimed_at,
+    }
+    .emit();
+
+    msg!(
+        "Reward recorded: {} tokens for farmer {}",
+        farmer_amount,
+        farmer_pubkey
+    );
+    Ok(())
+}
+
+/// Tags the instruction with the task's memo when `memo_program_info` is
+/// the SPL Memo program, for accounting tools that only see memos
+fn tag_task_reward(memo_program_info: &AccountInfo, task_id: &str) -> ProgramResult {
+    if *memo_program_info.key != spl_memo::id() {
+        return Ok(());
+    }
+    solana_program::program::invoke(
+        &spl_memo::build_memo(task_memo(task_id).as_bytes(), &[]),
+        std::slice::from_ref(memo_program_info),
+    )
+}
+
+// Withdrawing rewards
+pub(super) fn process_withdraw_reward(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    nonce: u64,
+    split: Option<Vec<WithdrawalSplit>>,
+) -> ProgramResult {
+    // Rewards are withdrawn from the treasury vault, which holds them until
+    // then. Tokens paid into the farmer's reward account before rewards were
+    // kept there move like they did in V1.
+    let (pool_info, source_info) = match accounts {
+        [_, pool_info, source_info, ..] => (pool_info, source_info),
+        _ => return Err(ProgramError::NotEnoughAccountKeys),
+    };
+    let (expected_vault, _) = find_vault_address_with_program_id(pool_info.key, program_id);
+    if *source_info.key == expected_vault {
+        withdraw_from_vault(program_id, accounts, amount, nonce, split)
+    } else {
+        withdraw_from_reward_account(program_id, accounts, amount, split)
+    }
+}
+
+fn withdraw_from_vault(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    nonce: u64,
+    split: Option<Vec<WithdrawalSplit>>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let farmer_destination_account_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let _ata_program_info = next_account_info(account_info_iter)?;
+    let daily_stats_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let whitelist_info = next_account_info(account_info_iter)?;
+    let parameters_info = next_account_info(account_info_iter)?;
+    let payout_address_info = next_account_info(account_info_iter)?;
+    let farmer_state_info = next_account_info(account_info_iter)?;
+    let history_info = next_account_info(account_info_iter)?;
+    let withdraw_burn_info = next_account_info(account_info_iter)?;
+    let withdrawal_fee_info = next_account_info(account_info_iter)?;
+    let fee_vault_info = next_account_info(account_info_iter)?;
+    let cosigner_info = next_account_info(account_info_iter)?;
+
+    // The optional sponsor comes before further split destinations, the
+    // payout queue accounts, the withdrawal ramp, the interest accounts, the
+    // pending reward withdrawn and the record of the nonce last
+    let further_destination_count = split
+        .as_ref()
+        .map_or(0, |split| split.len().saturating_sub(1));
+    let (
+        remaining_infos,
+        payout_queue_info,
+        payout_ticket_info,
+        withdrawal_ramp_info,
+        interest_policy_info,
+        bonus_vault_info,
+        pending_info,
+        withdrawal_record_info,
+    ) = match account_info_iter.as_slice() {
+        [remaining_infos @ .., payout_queue_info, payout_ticket_info, withdrawal_ramp_info, interest_policy_info, bonus_vault_info, pending_info, withdrawal_record_info] => {
+            (
+                remaining_infos,
+                payout_queue_info,
+                payout_ticket_info,
+                withdrawal_ramp_info,
+                interest_policy_info,
+                bonus_vault_info,
+                pending_info,
+                withdrawal_record_info,
+            )
+        }
+        _ => return Err(ProgramError::NotEnoughAccountKeys),
+    };
+    let (sponsor_info, further_destination_infos) =
+        if remaining_infos.len() > further_destination_count {
+            (remaining_infos.first(), &remaining_infos[1..])
+        } else {
+            (None, remaining_infos)
+        };
+    if further_destination_infos.len() < further_destination_count {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if sponsor_info.is_some_and(|sponsor| !sponsor.is_signer) {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Load pool
+    let mut pool_data = load_pool(program_id, pool_info)?;
+
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    check_withdrawal_cosigner(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        farmer_state_info,
+        cosigner_info,
+    )?;
+
+    // Withdrawals leave custody like claims, so they wait for the farmer's
+    // turn in a recovery window too
+    let now = Clock::get()?.unix_timestamp;
+    check_payout_turn(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        payout_queue_info,
+        payout_ticket_info,
+        now,
+    )?;
+
+    check_withdrawal_record(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        history_info,
+        withdrawal_record_info,
+        nonce,
+    )?;
+
+    let parameters = load_parameters(program_id, pool_info.key, parameters_info)?;
+    if amount < parameters.minimum_withdrawal_amount {
+        return Err(RewardPoolError::InsufficientAmount.into());
+    }
+
+    // The guild's share is only paid by `ClaimMany`
+    if load_guild_split(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        farmer_state_info,
+    )?
+    .is_some()
+    {
+        return Err(RewardPoolError::GuildSplitRequiresClaimMany.into());
+    }
+
+    // The withdrawal pays out the pending reward, whole, and the interest
+    // on it like a claim
+    let mut interest_policy =
+        load_claim_interest_policy(program_id, pool_info.key, interest_policy_info)?;
+    let (_, pending_amount, interest, carried_interest) = mark_pending_claimed(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        std::slice::from_ref(pending_info),
+        interest_policy.as_ref(),
+        now,
+    )?;
+    release_outstanding_rewards(pool_info, &mut pool_data, pending_amount)?;
+    if pending_amount != amount {
+        return Err(RewardPoolError::WithdrawalAmountMismatch.into());
+    }
+    apply_withdrawal_ramp(program_id, pool_info.key, withdrawal_ramp_info, amount)?;
+    let (_, vault_bump_seed) = find_vault_address_with_program_id(pool_info.key, program_id);
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, pool_info.key.as_ref(), &[vault_bump_seed]];
+
+    // The burned share comes off the top, the destinations split the rest
+    let mut withdraw_burn = read_withdraw_burn(program_id, pool_info.key, withdraw_burn_info)?;
+    let burned = withdraw_burn
+        .as_ref()
+        .map_or(0, |withdraw_burn| withdraw_burn.burn_amount(amount));
+    let paid = amount - burned;
+
+    let payouts: Vec<(&AccountInfo, u64)> = match &split {
+        None => vec![(farmer_destination_account_info, paid)],
+        Some(split) => {
+            if split.is_empty()
+                || split.len() > MAX_WITHDRAWAL_SPLITS
+                || split.iter().any(|share| share.share_bps == 0)
+                || split
+                    .iter()
+                    .map(|share| share.share_bps as u32)
+                    .sum::<u32>()
+                    != BASIS_POINTS as u32
+            {
+                return Err(RewardPoolError::InvalidWithdrawalSplit.into());
+            }
+
+            let destination_infos: Vec<&AccountInfo> =
+                std::iter::once(farmer_destination_account_info)
+                    .chain(&further_destination_infos[..further_destination_count])
+                    .collect();
+            for (index, (share, destination_info)) in
+                split.iter().zip(&destination_infos).enumerate()
+            {
+                if share.destination != *destination_info.key
+                    || split[..index]
+                        .iter()
+                        .any(|earlier| earlier.destination == share.destination)
+                {
+                    return Err(RewardPoolError::InvalidWithdrawalSplit.into());
+                }
+            }
+
+            destination_infos
+                .into_iter()
+                .zip(WithdrawalSplit::amounts(split, paid))
+                .collect()
+        }
+    };
+
+    if let Some(withdraw_burn) = withdraw_burn.as_mut().filter(|_| burned > 0) {
+        if *reward_mint_info.key != pool_data.reward_mint {
+            return Err(RewardPoolError::InvalidWithdrawBurnAccount.into());
+        }
+        burn_withdrawal(
+            pool_info.key,
+            farmer_info.key,
+            withdraw_burn,
+            withdraw_burn_info,
+            vault_info,
+            reward_mint_info,
+            token_program_info,
+            vault_seeds,
+            burned,
+            nonce,
+        )?;
+    }
+
+    for &(destination_info, destination_amount) in &payouts {
+        let whitelisted = check_withdrawal_destination(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            whitelist_info,
+            destination_info.key,
+        )?;
+
+        let locked_to_payout_address = check_payout_address(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            payout_address_info,
+            destination_info,
+        )?;
+
+        // A sponsor builds the transaction, so it must not be able to pick
+        // where the funds go
+        if sponsor_info.is_some() && !whitelisted && !locked_to_payout_address {
+            let destination = TokenAccount::unpack(&destination_info.data.borrow())?;
+            if destination.owner != *farmer_info.key {
+                return Err(RewardPoolError::DestinationNotOwnedByFarmer.into());
+            }
+        }
+
+        invoke_signed(
+            &token_instruction::transfer(
+                token_program_info.key,
+                vault_info.key,
+                destination_info.key,
+                vault_info.key,
+                &[],
+                destination_amount,
+            )?,
+            &[
+                vault_info.clone(),
+                destination_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[vault_seeds],
+        )?;
+    }
+
+    // Interest is not burned or split, it all goes to the first destination
+    if let Some(interest_policy) = interest_policy.as_mut() {
+        pay_claim_interest(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            interest_policy,
+            interest_policy_info,
+            bonus_vault_info,
+            farmer_destination_account_info,
+            token_program_info,
+            std::slice::from_ref(pending_info),
+            interest,
+            carried_interest,
+        )?;
+    }
+
+    // The flat lamport fee is paid by whoever pays the rent
+    let mut withdrawal_fee = read_withdrawal_fee(program_id, pool_info.key, withdrawal_fee_info)?;
+    if let Some(withdrawal_fee) = withdrawal_fee.as_mut() {
+        let lamports = withdrawal_fee.fee_for(amount);
+        if lamports > 0 {
+            charge_withdrawal_fee(
+                program_id,
+                pool_info.key,
+                withdrawal_fee,
+                withdrawal_fee_info,
+                fee_vault_info,
+                sponsor_info.unwrap_or(farmer_info),
+                farmer_info.key,
+                system_program_info,
+                lamports,
+                nonce,
+            )?;
+        }
+    }
+
+    update_daily_stats(
+        program_id,
+        pool_info.key,
+        daily_stats_info,
+        sponsor_info.unwrap_or(farmer_info),
+        system_program_info,
+        |stats| {
+            stats.total_withdrawn += amount;
+            stats.withdrawal_count += 1;
+        },
+    )?;
+
+    let farmer_state = update_farmer_state(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        farmer_state_info,
+        sponsor_info.unwrap_or(farmer_info),
+        system_program_info,
+        |farmer_state| farmer_state.last_claimed_at = now,
+    )?;
+
+    // The history keeps the farmer's last withdrawals, the record of the
+    // nonce keeps this one for good
+    let record = WithdrawalRecord {
+        farmer_pubkey: *farmer_info.key,
+        amount,
+        nonce,
+        withdrawn_at: now,
+        payer: *sponsor_info.unwrap_or(farmer_info).key,
+    };
+    record_withdrawal(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        history_info,
+        sponsor_info.unwrap_or(farmer_info),
+        system_program_info,
+        record,
+    )?;
+    create_withdrawal_record(
+        program_id,
+        pool_info.key,
+        withdrawal_record_info,
+        sponsor_info.unwrap_or(farmer_info),
+        system_program_info,
+        &record,
+    )?;
+
+    // One event per destination, so indexers see every transfer
+    for (destination_info, destination_amount) in payouts {
+        RewardPoolEvent::RewardWithdrawn {
+            pool: *pool_info.key,
+            farmer: *farmer_info.key,
+            destination: *destination_info.key,
+            amount: destination_amount,
+            nonce,
+            last_recorded_at: farmer_state.last_recorded_at,
+            last_claimed_at: farmer_state.last_claimed_at,
+        }
+        .emit();
+    }
+
+    msg!(
+        "Withdrawal completed: {} tokens for farmer {}",
+        amount,
+        farmer_info.key
+    );
+    Ok(())
+}
+
+fn withdraw_from_reward_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    split: Option<Vec<WithdrawalSplit>>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let farmer_reward_account_info = next_account_info(account_info_iter)?;
+    let farmer_destination_account_info = next_account_info(account_info_iter)?;
+    let _reward_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let _ata_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if split.is_some() {
+        return Err(RewardPoolError::InvalidWithdrawalSplit.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    let token_account = TokenAccount::unpack(&farmer_reward_account_info.data.borrow())?;
+    if token_account.amount < amount {
+        return Err(RewardPoolError::InsufficientAmount.into());
+    }
+
+    // The farmer signs for their own account; the program only relays
+    solana_program::program::invoke(
+        &token_instruction::transfer(
+            token_program_info.key,
+            farmer_reward_account_info.key,
+            farmer_destination_account_info.key,
+            farmer_info.key,
+            &[],
+            amount,
+        )?,
+        &[
+            farmer_reward_account_info.clone(),
+            farmer_destination_account_info.clone(),
+            farmer_info.clone(),
+            token_program_info.clone(),
+        ],
+    )?;
+
+    msg!(
+        "Withdrawal completed: {} tokens from the reward account of farmer {}",
+        amount,
+        farmer_info.key
+    );
+    Ok(())
+}
+
+// Sweeping delegated reward accounts
+pub(super) fn process_sweep_delegated_rewards(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let custodian_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let daily_stats_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let reward_account_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !custodian_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if reward_account_infos.is_empty() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    // Load pool
+    let pool_data = load_pool(program_id, pool_info)?;
+
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    let mut account_count: u32 = 0;
+    let mut total_amount: u64 = 0;
+
+    for reward_account_info in reward_account_infos {
+        let token_account = TokenAccount::unpack(&reward_account_info.data.borrow())?;
+        if token_account.mint != pool_data.reward_mint
+            || token_account.delegate != COption::Some(*custodian_info.key)
+        {
+            return Err(RewardPoolError::InvalidDelegatedAccount.into());
+        }
+
+        let amount = token_account.delegated_amount.min(token_account.amount);
+        if amount == 0 {
+            continue;
+        }
+
+        // The custodian signs as delegate of the reward account
+        let transfer_ix = token_instruction::transfer(
+            token_program_info.key,
+            reward_account_info.key,
+            destination_info.key,
+            custodian_info.key,
+            &[],
+            amount,
+        )?;
+
+        solana_program::program::invoke(
+            &transfer_ix,
+            &[
+                reward_account_info.clone(),
+                destination_info.clone(),
+                custodian_info.clone(),
+                token_program_info.clone(),
+            ],
+        )?;
+
+        account_count += 1;
+        total_amount = total_amount
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+
+    update_daily_stats(
+        program_id,
+        pool_info.key,
+        daily_stats_info,
+        custodian_info,
+        system_program_info,
+        |stats| {
+            stats.total_withdrawn += total_amount;
+            stats.withdrawal_count += account_count;
+        },
+    )?;
+
+    RewardPoolEvent::RewardsSwept {
+        pool: *pool_info.key,
+        custodian: *custodian_info.key,
+        destination: *destination_info.key,
+        account_count,
+        total_amount,
+    }
+    .emit();
+
+    msg!(
+        "Swept {} tokens from {} reward accounts",
+        total_amount,
+        account_count
+    );
+    Ok(())
+}
+
+// Claiming many pending rewards at once
+pub(super) fn process_claim_many(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let farmer_reward_account_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let claim_shard_info = next_account_info(account_info_iter)?;
+    let farmer_state_info = next_account_info(account_info_iter)?;
+    let interest_policy_info = next_account_info(account_info_iter)?;
+    let bonus_vault_info = next_account_info(account_info_iter)?;
+    let secondary_reward_info = next_account_info(account_info_iter)?;
+    let secondary_vault_info = next_account_info(account_info_iter)?;
+    let secondary_balance_info = next_account_info(account_info_iter)?;
+    let secondary_account_info = next_account_info(account_info_iter)?;
+    let payout_queue_info = next_account_info(account_info_iter)?;
+    let payout_ticket_info = next_account_info(account_info_iter)?;
+    let withdrawal_ramp_info = next_account_info(account_info_iter)?;
+    let guild_vault_info = next_account_info(account_info_iter)?;
+    let payout_address_info = next_account_info(account_info_iter)?;
+    let whitelist_info = next_account_info(account_info_iter)?;
+    let cosigner_info = next_account_info(account_info_iter)?;
+    let withdraw_burn_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let withdrawal_fee_info = next_account_info(account_info_iter)?;
+    let fee_vault_info = next_account_info(account_info_iter)?;
+    let pending_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if pending_infos.len() > MAX_CLAIM_BATCH {
+        return Err(RewardPoolError::TooManyPendingRewards.into());
+    }
+
+    // Load pool
+    let mut pool_data = load_pool(program_id, pool_info)?;
+
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    let (expected_vault, vault_bump_seed) =
+        find_vault_address_with_program_id(pool_info.key, program_id);
+    if *vault_info.key != expected_vault {
+        return Err(RewardPoolError::InvalidVaultAccount.into());
+    }
+
+    // Claims only ever go to the farmer's own account, a whitelisted one or
+    // where they forward their rewards, within their payout address
+    check_claim_destination(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        &pool_data.reward_mint,
+        farmer_state_info,
+        whitelist_info,
+        payout_address_info,
+        farmer_reward_account_info,
+    )?;
+    check_withdrawal_cosigner(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        farmer_state_info,
+        cosigner_info,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    check_payout_turn(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        payout_queue_info,
+        payout_ticket_info,
+        now,
+    )?;
+    let mut interest_policy =
+        load_claim_interest_policy(program_id, pool_info.key, interest_policy_info)?;
+    let (reward_count, total_amount, interest, carried_interest) = mark_pending_claimed(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        pending_infos,
+        interest_policy.as_ref(),
+        now,
+    )?;
+    release_outstanding_rewards(pool_info, &mut pool_data, total_amount)?;
+    apply_withdrawal_ramp(
+        program_id,
+        pool_info.key,
+        withdrawal_ramp_info,
+        total_amount,
+    )?;
+
+    // The guild's share is taken from the rewards left after the burn only,
+    // the interest is all the farmer's
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, pool_info.key.as_ref(), &[vault_bump_seed]];
+    let paid = charge_claim(
+        program_id,
+        pool_info.key,
+        &pool_data.reward_mint,
+        farmer_info.key,
+        withdraw_burn_info,
+        reward_mint_info,
+        withdrawal_fee_info,
+        fee_vault_info,
+        vault_info,
+        farmer_info,
+        token_program_info,
+        system_program_info,
+        vault_seeds,
+        total_amount,
+    )?;
+    let guild_share = match load_guild_split(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        farmer_state_info,
+    )? {
+        Some(farmer_state) => pay_guild_share(
+            pool_info.key,
+            &farmer_state,
+            guild_vault_info,
+            vault_info,
+            token_program_info,
+            vault_seeds,
+            paid,
+        )?,
+        None => 0,
+    };
+
+    invoke_signed(
+        &token_instruction::transfer(
+            token_program_info.key,
+            vault_info.key,
+            farmer_reward_account_info.key,
+            vault_info.key,
+            &[],
+            paid - guild_share,
+        )?,
+        &[
+            vault_info.clone(),
+            farmer_reward_account_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[vault_seeds],
+    )?;
+
+    if let Some(interest_policy) = interest_policy.as_mut() {
+        pay_claim_interest(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            interest_policy,
+            interest_policy_info,
+            bonus_vault_info,
+            farmer_reward_account_info,
+            token_program_info,
+            pending_infos,
+            interest,
+            carried_interest,
+        )?;
+    }
+
+    // The secondary mint kicker is delivered with the rewards it came with,
+    // to the payout address too
+    let secondary_recipient = effective_payout_address(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        payout_address_info,
+    )?
+    .unwrap_or(*farmer_info.key);
+    let secondary_amount = pay_secondary_rewards(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        &secondary_recipient,
+        secondary_reward_info,
+        secondary_vault_info,
+        secondary_balance_info,
+        secondary_account_info,
+        whitelist_info,
+        token_program_info,
+        now,
+    )?;
+
+    let farmer_state = update_farmer_state(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        farmer_state_info,
+        farmer_info,
+        system_program_info,
+        |farmer_state| farmer_state.last_claimed_at = now,
+    )?;
+
+    record_claim(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        claim_shard_info,
+        farmer_info,
+        system_program_info,
+        ClaimTotals {
+            claim_count: 1,
+            auto_claim_count: 0,
+            reward_count: reward_count as u64,
+            amount: total_amount,
+        },
+        now,
+    )?;
+
+    RewardPoolEvent::RewardsClaimed {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        reward_count,
+        total_amount,
+        secondary_amount,
+        last_recorded_at: farmer_state.last_recorded_at,
+        last_claimed_at: farmer_state.last_claimed_at,
+    }
+    .emit();
+
+    msg!(
+        "Claimed {} tokens from {} pending rewards",
+        total_amount,
+        reward_count
+    );
+    Ok(())
+}
+
+// Claiming pending rewards of several pools at once
+pub(super) fn process_claim_across_pools(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    pending_counts: Vec<u8>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let cosigner_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if pending_counts.is_empty() || pending_counts.len() > MAX_CLAIM_POOLS {
+        return Err(RewardPoolError::InvalidClaimGroups.into());
+    }
+    let pending_total: usize = pending_counts.iter().map(|&count| count as usize).sum();
+    if pending_total > MAX_CLAIM_BATCH {
+        return Err(RewardPoolError::TooManyPendingRewards.into());
+    }
+    let group_accounts = CLAIM_GROUP_ACCOUNTS * pending_counts.len() + pending_total;
+    if account_info_iter.as_slice().len() != group_accounts {
+        return Err(RewardPoolError::InvalidClaimGroups.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let pool_count = pending_counts.len();
+    let mut claimed_total: u64 = 0;
+    for pending_count in pending_counts {
+        let pool_info = next_account_info(account_info_iter)?;
+        let vault_info = next_account_info(account_info_iter)?;
+        let farmer_reward_account_info = next_account_info(account_info_iter)?;
+        let farmer_state_info = next_account_info(account_info_iter)?;
+        let interest_policy_info = next_account_info(account_info_iter)?;
+        let bonus_vault_info = next_account_info(account_info_iter)?;
+        let payout_queue_info = next_account_info(account_info_iter)?;
+        let payout_ticket_info = next_account_info(account_info_iter)?;
+        let withdrawal_ramp_info = next_account_info(account_info_iter)?;
+        let payout_address_info = next_account_info(account_info_iter)?;
+        let whitelist_info = next_account_info(account_info_iter)?;
+        let withdraw_burn_info = next_account_info(account_info_iter)?;
+        let reward_mint_info = next_account_info(account_info_iter)?;
+        let withdrawal_fee_info = next_account_info(account_info_iter)?;
+        let fee_vault_info = next_account_info(account_info_iter)?;
+        let (pending_infos, rest) = account_info_iter
+            .as_slice()
+            .split_at(pending_count as usize);
+        *account_info_iter = rest.iter();
+
+        // Each group is checked like a `ClaimMany` of its pool, and any
+        // failing group fails the whole claim
+        let mut pool_data = load_pool(program_id, pool_info)?;
+        if pool_data.is_paused {
+            return Err(RewardPoolError::PoolPaused.into());
+        }
+
+        let (expected_vault, vault_bump_seed) =
+            find_vault_address_with_program_id(pool_info.key, program_id);
+        if *vault_info.key != expected_vault {
+            return Err(RewardPoolError::InvalidVaultAccount.into());
+        }
+
+        check_claim_destination(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            &pool_data.reward_mint,
+            farmer_state_info,
+            whitelist_info,
+            payout_address_info,
+            farmer_reward_account_info,
+        )?;
+        check_withdrawal_cosigner(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            farmer_state_info,
+            cosigner_info,
+        )?;
+
+        // Groups have no room for a guild vault
+        if load_guild_split(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            farmer_state_info,
+        )?
+        .is_some()
+        {
+            return Err(RewardPoolError::GuildSplitRequiresClaimMany.into());
+        }
+
+        check_payout_turn(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            payout_queue_info,
+            payout_ticket_info,
+            now,
+        )?;
+        let mut interest_policy =
+            load_claim_interest_policy(program_id, pool_info.key, interest_policy_info)?;
+        let (reward_count, total_amount, interest, carried_interest) = mark_pending_claimed(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            pending_infos,
+            interest_policy.as_ref(),
+            now,
+        )?;
+        release_outstanding_rewards(pool_info, &mut pool_data, total_amount)?;
+        apply_withdrawal_ramp(
+            program_id,
+            pool_info.key,
+            withdrawal_ramp_info,
+            total_amount,
+        )?;
+
+        let vault_seeds: &[&[u8]] = &[VAULT_SEED, pool_info.key.as_ref(), &[vault_bump_seed]];
+        let paid = charge_claim(
+            program_id,
+            pool_info.key,
+            &pool_data.reward_mint,
+            farmer_info.key,
+            withdraw_burn_info,
+            reward_mint_info,
+            withdrawal_fee_info,
+            fee_vault_info,
+            vault_info,
+            farmer_info,
+            token_program_info,
+            system_program_info,
+            vault_seeds,
+            total_amount,
+        )?;
+        invoke_signed(
+            &token_instruction::transfer(
+                token_program_info.key,
+                vault_info.key,
+                farmer_reward_account_info.key,
+                vault_info.key,
+                &[],
+                paid,
+            )?,
+            &[
+                vault_info.clone(),
+                farmer_reward_account_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[vault_seeds],
+        )?;
+
+        if let Some(interest_policy) = interest_policy.as_mut() {
+            pay_claim_interest(
+                program_id,
+                pool_info.key,
+                farmer_info.key,
+                interest_policy,
+                interest_policy_info,
+                bonus_vault_info,
+                farmer_reward_account_info,
+                token_program_info,
+                pending_infos,
+                interest,
+                carried_interest,
+            )?;
+        }
+
+        let farmer_state = update_farmer_state(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            farmer_state_info,
+            farmer_info,
+            system_program_info,
+            |farmer_state| farmer_state.last_claimed_at = now,
+        )?;
+
+        // Secondary rewards wait for the farmer's next `ClaimMany` of the pool
+        RewardPoolEvent::RewardsClaimed {
+            pool: *pool_info.key,
+            farmer: *farmer_info.key,
+            reward_count,
+            total_amount,
+            secondary_amount: 0,
+            last_recorded_at: farmer_state.last_recorded_at,
+            last_claimed_at: farmer_state.last_claimed_at,
+        }
+        .emit();
+
+        claimed_total = claimed_total.saturating_add(total_amount);
+    }
+
+    msg!(
+        "Claimed {} tokens across {} pools",
+        claimed_total,
+        pool_count
+    );
+    Ok(())
+}
+
+// Claiming a farmer's pending rewards to their payout address, on their behalf
+pub(super) fn process_crank_auto_claim(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let cranker_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let farmer_info = next_account_info(account_info_iter)?;
+    let farmer_state_info = next_account_info(account_info_iter)?;
+    let whitelist_info = next_account_info(account_info_iter)?;
+    let payout_address_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let cranker_token_account_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let claim_shard_info = next_account_info(account_info_iter)?;
+    let interest_policy_info = next_account_info(account_info_iter)?;
+    let bonus_vault_info = next_account_info(account_info_iter)?;
+    let payout_queue_info = next_account_info(account_info_iter)?;
+    let payout_ticket_info = next_account_info(account_info_iter)?;
+    let withdrawal_ramp_info = next_account_info(account_info_iter)?;
+    let guild_vault_info = next_account_info(account_info_iter)?;
+    let withdraw_burn_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let withdrawal_fee_info = next_account_info(account_info_iter)?;
+    let fee_vault_info = next_account_info(account_info_iter)?;
+    let pending_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !cranker_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if pending_infos.len() > MAX_CLAIM_BATCH {
+        return Err(RewardPoolError::TooManyPendingRewards.into());
+    }
+
+    let mut pool_data = load_pool(program_id, pool_info)?;
+
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    let (expected_vault, vault_bump_seed) =
+        find_vault_address_with_program_id(pool_info.key, program_id);
+    if *vault_info.key != expected_vault {
+        return Err(RewardPoolError::InvalidVaultAccount.into());
+    }
+
+    let (expected_farmer_state, _) =
+        find_farmer_state_address_with_program_id(pool_info.key, farmer_info.key, program_id);
+    if *farmer_state_info.key != expected_farmer_state || farmer_state_info.data_is_empty() {
+        return Err(RewardPoolError::AutoClaimDisabled.into());
+    }
+    let farmer_state = load_farmer_state(program_id, farmer_state_info)?;
+    let threshold = farmer_state.auto_claim_threshold;
+    if threshold == 0 {
+        return Err(RewardPoolError::AutoClaimDisabled.into());
+    }
+
+    // The cranker picks the destination, so it must belong to the payout
+    // address the farmer has in effect now and pass their whitelist
+    let destination = TokenAccount::unpack(&destination_info.data.borrow())?;
+    if destination.mint != pool_data.reward_mint {
+        return Err(RewardPoolError::DestinationNotPayoutAddress.into());
+    }
+    if !check_payout_address(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        payout_address_info,
+        destination_info,
+    )? {
+        return Err(RewardPoolError::AutoClaimRequiresPayoutAddress.into());
+    }
+    check_withdrawal_destination(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        whitelist_info,
+        destination_info.key,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    check_payout_turn(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        payout_queue_info,
+        payout_ticket_info,
+        now,
+    )?;
+    let mut interest_policy =
+        load_claim_interest_policy(program_id, pool_info.key, interest_policy_info)?;
+    let (reward_count, total_amount, interest, carried_interest) = mark_pending_claimed(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        pending_infos,
+        interest_policy.as_ref(),
+        now,
+    )?;
+    release_outstanding_rewards(pool_info, &mut pool_data, total_amount)?;
+    if total_amount < threshold {
+        return Err(RewardPoolError::AutoClaimThresholdNotReached.into());
+    }
+    apply_withdrawal_ramp(
+        program_id,
+        pool_info.key,
+        withdrawal_ramp_info,
+        total_amount,
+    )?;
+
+    // The cranker pays the lamport fee, as they pay the transaction's
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, pool_info.key.as_ref(), &[vault_bump_seed]];
+    let paid = charge_claim(
+        program_id,
+        pool_info.key,
+        &pool_data.reward_mint,
+        farmer_info.key,
+        withdraw_burn_info,
+        reward_mint_info,
+        withdrawal_fee_info,
+        fee_vault_info,
+        vault_info,
+        cranker_info,
+        token_program_info,
+        system_program_info,
+        vault_seeds,
+        total_amount,
+    )?;
+    let tip = (paid as u128 * AUTO_CLAIM_TIP_BPS as u128 / BASIS_POINTS as u128) as u64;
+    let guild_share = if farmer_state.guild_share_bps > 0 {
+        pay_guild_share(
+            pool_info.key,
+            &farmer_state,
+            guild_vault_info,
+            vault_info,
+            token_program_info,
+            vault_seeds,
+            paid - tip,
+        )?
+    } else {
+        0
+    };
+    for (recipient_info, amount) in [
+        (destination_info, paid - tip - guild_share),
+        (cranker_token_account_info, tip),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        invoke_signed(
+            &token_instruction::transfer(
+                token_program_info.key,
+                vault_info.key,
+                recipient_info.key,
+                vault_info.key,
+                &[],
+                amount,
+            )?,
+            &[
+                vault_info.clone(),
+                recipient_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[vault_seeds],
+        )?;
+    }
+
+    // The tip and the guild's share are taken from the rewards only, the
+    // interest is all the farmer's
+    if let Some(interest_policy) = interest_policy.as_mut() {
+        pay_claim_interest(
+            program_id,
+            pool_info.key,
+            farmer_info.key,
+            interest_policy,
+            interest_policy_info,
+            bonus_vault_info,
+            destination_info,
+            token_program_info,
+            pending_infos,
+            interest,
+            carried_interest,
+        )?;
+    }
+
+    update_farmer_state(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        farmer_state_info,
+        cranker_info,
+        system_program_info,
+        |farmer_state| farmer_state.last_claimed_at = now,
+    )?;
+
+    record_claim(
+        program_id,
+        pool_info.key,
+        farmer_info.key,
+        claim_shard_info,
+        cranker_info,
+        system_program_info,
+        ClaimTotals {
+            claim_count: 0,
+            auto_claim_count: 1,
+            reward_count: reward_count as u64,
+            amount: total_amount,
+        },
+        now,
+    )?;
+
+    RewardPoolEvent::RewardsAutoClaimed {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        cranker: *cranker_info.key,
+        destination: *destination_info.key,
+        reward_count,
+        total_amount,
+        tip,
+    }
+    .emit();
+
+    msg!(
+        "Auto-claimed {} tokens from {} pending rewards, tip {}",
+        total_amount,
+        reward_count,
+        tip
+    );
+    Ok(())
+}
+
+/// Marks the farmer's unclaimed rewards among `pending_infos` withdrawn,
+/// skipping those already withdrawn, and returns their count, total, the
+/// interest they accrued under `interest_policy` by `now` and the interest
+/// still owed on rewards withdrawn before, which each reward now owes until
+/// it is paid. Fails when nothing is left to claim.
+fn mark_pending_claimed(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    pending_infos: &[AccountInfo],
+    interest_policy: Option<&InterestPolicy>,
+    now: i64,
+) -> Result<(u32, u64, u64, u64), ProgramError> {
+    let mut reward_count: u32 = 0;
+    let mut total_amount: u64 = 0;
+    let mut interest: u64 = 0;
+    let mut carried: u64 = 0;
+
+    for (index, pending_info) in pending_infos.iter().enumerate() {
+        if pending_info.owner != program_id {
+            return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+        }
+
+        let mut pending = PendingReward::try_from_slice(&pending_info.data.borrow())
+            .map_err(|_| RewardPoolError::InvalidPendingRewardAccount)?;
+        let (expected_address, _) =
+            find_pending_reward_address_with_program_id(pool, farmer, &pending.task_id, program_id);
+        if pending.farmer_pubkey != *farmer || *pending_info.key != expected_address {
+            return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+        }
+
+        if pending_infos[..index]
+            .iter()
+            .any(|earlier| earlier.key == pending_info.key)
+        {
+            continue;
+        }
+        // Challenged rewards stay locked until the challenge is resolved,
+        // and rewards awaiting approval until they are approved
+        if pending.is_withdrawn {
+            if interest_policy.is_some() {
+                carried = carried.saturating_add(pending.interest_owed);
+            }
+            continue;
+        }
+        if pending.is_challenged || pending.awaiting_approval {
+            continue;
+        }
+
+        pending.is_withdrawn = true;
+        if let Some(interest_policy) = interest_policy {
+            let accrued = interest_policy.accrued(pending.amount, pending.recorded_at, now);
+            pending.interest_owed = pending.interest_owed.saturating_add(accrued);
+            interest = interest.saturating_add(accrued);
+        }
+        pending.serialize(&mut &mut pending_info.data.borrow_mut()[..])?;
+
+        reward_count += 1;
+        total_amount = total_amount
+            .checked_add(pending.amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+
+    if reward_count == 0 && carried == 0 {
+        return Err(RewardPoolError::NoPendingRewards.into());
+    }
+    Ok((reward_count, total_amount, interest, carried))
+}
+
+/// Burns the pool's share of `amount` claimed out of the treasury vault and
+/// charges its flat lamport fee to `fee_payer_info`, as `WithdrawReward`
+/// does, so no way out of custody skips them. Returns what is left of
+/// `amount` to pay out.
+#[allow(clippy::too_many_arguments)]
+fn charge_claim<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    reward_mint: &Pubkey,
+    farmer: &Pubkey,
+    withdraw_burn_info: &AccountInfo<'a>,
+    reward_mint_info: &AccountInfo<'a>,
+    withdrawal_fee_info: &AccountInfo<'a>,
+    fee_vault_info: &AccountInfo<'a>,
+    vault_info: &AccountInfo<'a>,
+    fee_payer_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    vault_seeds: &[&[u8]],
+    amount: u64,
+) -> Result<u64, ProgramError> {
+    // Claims have no nonce, their events carry 0
+    let mut withdraw_burn = read_withdraw_burn(program_id, pool, withdraw_burn_info)?;
+    let burned = withdraw_burn
+        .as_ref()
+        .map_or(0, |withdraw_burn| withdraw_burn.burn_amount(amount));
+    if let Some(withdraw_burn) = withdraw_burn.as_mut().filter(|_| burned > 0) {
+        if *reward_mint_info.key != *reward_mint {
+            return Err(RewardPoolError::InvalidWithdrawBurnAccount.into());
+        }
+        burn_withdrawal(
+            pool,
+            farmer,
+            withdraw_burn,
+            withdraw_burn_info,
+            vault_info,
+            reward_mint_info,
+            token_program_info,
+            vault_seeds,
+            burned,
+            0,
+        )?;
+    }
+
+    let mut withdrawal_fee = read_withdrawal_fee(program_id, pool, withdrawal_fee_info)?;
+    if let Some(withdrawal_fee) = withdrawal_fee.as_mut() {
+        let lamports = withdrawal_fee.fee_for(amount);
+        if lamports > 0 {
+            charge_withdrawal_fee(
+                program_id,
+                pool,
+                withdrawal_fee,
+                withdrawal_fee_info,
+                fee_vault_info,
+                fee_payer_info,
+                farmer,
+                system_program_info,
+                lamports,
+                0,
+            )?;
+        }
+    }
+    Ok(amount - burned)
+}
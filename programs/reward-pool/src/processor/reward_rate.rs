@@ -0,0 +1,133 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{treasury::load_pool, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    seeds::{find_reward_rate_address_with_program_id, REWARD_RATE_SEED},
+    state::{RewardRate, Sequenced},
+    views::RewardRateReport,
+    SECONDS_PER_HOUR,
+};
+
+/// Adds a reward of `amount` recorded at `now` to the pool's reward rate
+/// accumulators, creating the account on the pool's first reward
+pub(super) fn record_reward_rate<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    reward_rate_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    amount: u64,
+    now: i64,
+) -> ProgramResult {
+    let (expected_reward_rate, bump_seed) =
+        find_reward_rate_address_with_program_id(pool, program_id);
+    if *reward_rate_info.key != expected_reward_rate {
+        return Err(RewardPoolError::InvalidRewardRateAccount.into());
+    }
+
+    let mut reward_rate = if reward_rate_info.data_is_empty() {
+        create_pda_account(
+            payer_info,
+            reward_rate_info,
+            system_program_info,
+            program_id,
+            RewardRate::LEN,
+            &[REWARD_RATE_SEED, pool.as_ref(), &[bump_seed]],
+        )?;
+        RewardRate::new(*pool, now, bump_seed)
+    } else {
+        load_reward_rate(program_id, pool, reward_rate_info)?
+    };
+
+    reward_rate.record(amount, now);
+    reward_rate.save(reward_rate_info)
+}
+
+// Reporting the time-weighted average reward rate over a window
+pub(super) fn process_get_reward_rate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    window: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pool_info = next_account_info(account_info_iter)?;
+    let reward_rate_info = next_account_info(account_info_iter)?;
+
+    if window <= 0 {
+        return Err(RewardPoolError::InvalidRateWindow.into());
+    }
+
+    load_pool(program_id, pool_info)?;
+
+    let (expected_reward_rate, _) =
+        find_reward_rate_address_with_program_id(pool_info.key, program_id);
+    if *reward_rate_info.key != expected_reward_rate {
+        return Err(RewardPoolError::InvalidRewardRateAccount.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    // Pools that never recorded a reward have no reward rate account
+    let report = if reward_rate_info.data_is_empty() {
+        RewardRateReport {
+            pool: *pool_info.key,
+            window_start: now,
+            window_end: now,
+            amount: 0,
+            reward_count: 0,
+            rate_per_hour: 0,
+        }
+    } else {
+        let reward_rate = load_reward_rate(program_id, pool_info.key, reward_rate_info)?;
+        let start = reward_rate
+            .window_start(now.saturating_sub(window))
+            .unwrap_or_default();
+        let amount = reward_rate
+            .cumulative_amount
+            .saturating_sub(start.cumulative_amount);
+        let seconds = now.saturating_sub(start.timestamp).max(1);
+        RewardRateReport {
+            pool: *pool_info.key,
+            window_start: start.timestamp,
+            window_end: now,
+            amount,
+            reward_count: reward_rate
+                .cumulative_count
+                .saturating_sub(start.cumulative_count),
+            rate_per_hour: (amount as u128 * SECONDS_PER_HOUR as u128 / seconds as u128) as u64,
+        }
+    };
+
+    set_return_data(&borsh::to_vec(&report)?);
+    Ok(())
+}
+
+fn load_reward_rate(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    reward_rate_info: &AccountInfo,
+) -> Result<RewardRate, ProgramError> {
+    if reward_rate_info.owner != program_id {
+        return Err(RewardPoolError::InvalidRewardRateAccount.into());
+    }
+    let reward_rate = RewardRate::try_from_slice(&reward_rate_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidRewardRateAccount)?;
+    let expected_reward_rate = Pubkey::create_program_address(
+        &[REWARD_RATE_SEED, pool.as_ref(), &[reward_rate.bump_seed]],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidRewardRateAccount)?;
+    if reward_rate.pool != *pool || *reward_rate_info.key != expected_reward_rate {
+        return Err(RewardPoolError::InvalidRewardRateAccount.into());
+    }
+    Ok(reward_rate)
+}
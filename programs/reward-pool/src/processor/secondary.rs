@@ -0,0 +1,374 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_token::{instruction as token_instruction, state::Account as TokenAccount};
+
+use super::{
+    treasury::load_pool,
+    utils::{create_pda_account, create_self_owned_token_account, token_balance},
+    whitelist::check_withdrawal_destination,
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_secondary_balance_address_with_program_id,
+        find_secondary_reward_address_with_program_id,
+        find_secondary_vault_address_with_program_id, SECONDARY_BALANCE_SEED,
+        SECONDARY_REWARD_SEED, SECONDARY_VAULT_SEED,
+    },
+    state::{SecondaryBalance, SecondaryReward, Sequenced},
+};
+
+// Setting the secondary mint of a pool
+pub(super) fn process_set_secondary_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let secondary_reward_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let secondary_vault_info = next_account_info(account_info_iter)?;
+    let secondary_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if *secondary_mint_info.key == pool_data.reward_mint
+        || *secondary_mint_info.owner != spl_token::id()
+    {
+        return Err(RewardPoolError::InvalidSecondaryMint.into());
+    }
+
+    let (expected_secondary_reward, bump_seed) =
+        find_secondary_reward_address_with_program_id(pool_info.key, program_id);
+    if *secondary_reward_info.key != expected_secondary_reward {
+        return Err(RewardPoolError::InvalidSecondaryRewardAccount.into());
+    }
+    // The vault holds the mint's tokens, so the mint is set once
+    if !secondary_reward_info.data_is_empty() {
+        return Err(RewardPoolError::SecondaryMintAlreadySet.into());
+    }
+
+    let (expected_secondary_vault, vault_bump_seed) =
+        find_secondary_vault_address_with_program_id(pool_info.key, program_id);
+    if *secondary_vault_info.key != expected_secondary_vault
+        || *token_program_info.key != spl_token::id()
+    {
+        return Err(RewardPoolError::InvalidSecondaryVaultAccount.into());
+    }
+
+    // Like the treasury vault, the secondary vault is its own owner
+    create_self_owned_token_account(
+        platform_authority_info,
+        secondary_vault_info,
+        secondary_mint_info,
+        token_program_info,
+        system_program_info,
+        &[
+            SECONDARY_VAULT_SEED,
+            pool_info.key.as_ref(),
+            &[vault_bump_seed],
+        ],
+    )?;
+
+    create_pda_account(
+        platform_authority_info,
+        secondary_reward_info,
+        system_program_info,
+        program_id,
+        SecondaryReward::LEN,
+        &[SECONDARY_REWARD_SEED, pool_info.key.as_ref(), &[bump_seed]],
+    )?;
+    let mut secondary_reward = SecondaryReward {
+        pool: *pool_info.key,
+        secondary_mint: *secondary_mint_info.key,
+        total_owed: 0,
+        total_claimed: 0,
+        vault_bump_seed,
+        bump_seed,
+        sequence: 0,
+    };
+    secondary_reward.save(secondary_reward_info)?;
+
+    RewardPoolEvent::SecondaryMintSet {
+        pool: *pool_info.key,
+        secondary_mint: *secondary_mint_info.key,
+    }
+    .emit();
+
+    msg!("Secondary rewards paid in {}", secondary_mint_info.key);
+    Ok(())
+}
+
+/// Books a `secondary_amount` kicker to the farmer's secondary balance,
+/// creating it at `payer_info`'s expense. The secondary vault must cover
+/// everything owed, this kicker included. Without a kicker, none of the
+/// secondary accounts is needed.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn book_secondary_reward<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    secondary_reward_info: Option<&AccountInfo<'a>>,
+    secondary_vault_info: Option<&AccountInfo<'a>>,
+    secondary_balance_info: Option<&AccountInfo<'a>>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    secondary_amount: u64,
+) -> ProgramResult {
+    if secondary_amount == 0 {
+        return Ok(());
+    }
+    let (Some(secondary_reward_info), Some(secondary_vault_info), Some(secondary_balance_info)) = (
+        secondary_reward_info,
+        secondary_vault_info,
+        secondary_balance_info,
+    ) else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let (expected_secondary_reward, _) =
+        find_secondary_reward_address_with_program_id(pool, program_id);
+    if *secondary_reward_info.key != expected_secondary_reward {
+        return Err(RewardPoolError::InvalidSecondaryRewardAccount.into());
+    }
+    if secondary_reward_info.data_is_empty() {
+        return Err(RewardPoolError::SecondaryRewardsDisabled.into());
+    }
+    let mut secondary_reward = load_secondary_reward(program_id, pool, secondary_reward_info)?;
+    check_secondary_vault(program_id, pool, &secondary_reward, secondary_vault_info)?;
+
+    let total_owed = secondary_reward
+        .total_owed
+        .checked_add(secondary_amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    if total_owed > token_balance(secondary_vault_info)? {
+        return Err(RewardPoolError::InsufficientSecondaryFunds.into());
+    }
+    secondary_reward.total_owed = total_owed;
+    secondary_reward.save(secondary_reward_info)?;
+
+    let (expected_balance, bump_seed) =
+        find_secondary_balance_address_with_program_id(pool, farmer, program_id);
+    if *secondary_balance_info.key != expected_balance {
+        return Err(RewardPoolError::InvalidSecondaryBalanceAccount.into());
+    }
+    let mut balance = if secondary_balance_info.data_is_empty() {
+        create_pda_account(
+            payer_info,
+            secondary_balance_info,
+            system_program_info,
+            program_id,
+            SecondaryBalance::LEN,
+            &[
+                SECONDARY_BALANCE_SEED,
+                pool.as_ref(),
+                farmer.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        SecondaryBalance {
+            pool: *pool,
+            farmer: *farmer,
+            owed: 0,
+            total_recorded: 0,
+            total_claimed: 0,
+            last_claimed_at: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_secondary_balance(program_id, pool, farmer, secondary_balance_info)?
+    };
+
+    balance.owed = balance
+        .owed
+        .checked_add(secondary_amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    balance.total_recorded = balance.total_recorded.saturating_add(secondary_amount);
+    balance.save(secondary_balance_info)
+}
+
+/// Pays the farmer everything their secondary balance owes from the
+/// secondary vault to `destination_info`, a token account of the secondary
+/// mint owned by `recipient`, the farmer or their payout address, that
+/// their whitelist allows. Returns the amount paid, 0 when the pool has no
+/// secondary mint or the farmer is owed nothing.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn pay_secondary_rewards<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    recipient: &Pubkey,
+    secondary_reward_info: &AccountInfo<'a>,
+    secondary_vault_info: &AccountInfo<'a>,
+    secondary_balance_info: &AccountInfo<'a>,
+    destination_info: &AccountInfo<'a>,
+    whitelist_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    now: i64,
+) -> Result<u64, ProgramError> {
+    let (expected_secondary_reward, _) =
+        find_secondary_reward_address_with_program_id(pool, program_id);
+    let (expected_balance, _) =
+        find_secondary_balance_address_with_program_id(pool, farmer, program_id);
+    if *secondary_reward_info.key != expected_secondary_reward {
+        return Err(RewardPoolError::InvalidSecondaryRewardAccount.into());
+    }
+    if *secondary_balance_info.key != expected_balance {
+        return Err(RewardPoolError::InvalidSecondaryBalanceAccount.into());
+    }
+    if secondary_reward_info.data_is_empty() || secondary_balance_info.data_is_empty() {
+        return Ok(0);
+    }
+
+    let mut secondary_reward = load_secondary_reward(program_id, pool, secondary_reward_info)?;
+    let mut balance = load_secondary_balance(program_id, pool, farmer, secondary_balance_info)?;
+    let amount = balance.owed;
+    if amount == 0 {
+        return Ok(0);
+    }
+
+    check_secondary_vault(program_id, pool, &secondary_reward, secondary_vault_info)?;
+    if *token_program_info.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let destination = TokenAccount::unpack(&destination_info.data.borrow())?;
+    if destination.owner != *recipient || destination.mint != secondary_reward.secondary_mint {
+        return Err(RewardPoolError::DestinationNotOwnedByFarmer.into());
+    }
+    check_withdrawal_destination(
+        program_id,
+        pool,
+        farmer,
+        whitelist_info,
+        destination_info.key,
+    )?;
+
+    invoke_signed(
+        &token_instruction::transfer(
+            token_program_info.key,
+            secondary_vault_info.key,
+            destination_info.key,
+            secondary_vault_info.key,
+            &[],
+            amount,
+        )?,
+        &[
+            secondary_vault_info.clone(),
+            destination_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[
+            SECONDARY_VAULT_SEED,
+            pool.as_ref(),
+            &[secondary_reward.vault_bump_seed],
+        ]],
+    )?;
+
+    balance.owed = 0;
+    balance.total_claimed = balance.total_claimed.saturating_add(amount);
+    balance.last_claimed_at = now;
+    balance.save(secondary_balance_info)?;
+
+    secondary_reward.total_owed = secondary_reward.total_owed.saturating_sub(amount);
+    secondary_reward.total_claimed = secondary_reward.total_claimed.saturating_add(amount);
+    secondary_reward.save(secondary_reward_info)?;
+
+    Ok(amount)
+}
+
+pub(super) fn check_secondary_vault(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    secondary_reward: &SecondaryReward,
+    secondary_vault_info: &AccountInfo,
+) -> ProgramResult {
+    let expected_secondary_vault = Pubkey::create_program_address(
+        &[
+            SECONDARY_VAULT_SEED,
+            pool.as_ref(),
+            &[secondary_reward.vault_bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidSecondaryVaultAccount)?;
+    if *secondary_vault_info.key != expected_secondary_vault {
+        return Err(RewardPoolError::InvalidSecondaryVaultAccount.into());
+    }
+    Ok(())
+}
+
+pub(super) fn load_secondary_reward(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    secondary_reward_info: &AccountInfo,
+) -> Result<SecondaryReward, ProgramError> {
+    if secondary_reward_info.owner != program_id {
+        return Err(RewardPoolError::InvalidSecondaryRewardAccount.into());
+    }
+    let secondary_reward = SecondaryReward::try_from_slice(&secondary_reward_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidSecondaryRewardAccount)?;
+    let expected_secondary_reward = Pubkey::create_program_address(
+        &[
+            SECONDARY_REWARD_SEED,
+            pool.as_ref(),
+            &[secondary_reward.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidSecondaryRewardAccount)?;
+    if secondary_reward.pool != *pool || *secondary_reward_info.key != expected_secondary_reward {
+        return Err(RewardPoolError::InvalidSecondaryRewardAccount.into());
+    }
+    Ok(secondary_reward)
+}
+
+fn load_secondary_balance(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    secondary_balance_info: &AccountInfo,
+) -> Result<SecondaryBalance, ProgramError> {
+    if secondary_balance_info.owner != program_id {
+        return Err(RewardPoolError::InvalidSecondaryBalanceAccount.into());
+    }
+    let balance = SecondaryBalance::try_from_slice(&secondary_balance_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidSecondaryBalanceAccount)?;
+    let expected_balance = Pubkey::create_program_address(
+        &[
+            SECONDARY_BALANCE_SEED,
+            pool.as_ref(),
+            farmer.as_ref(),
+            &[balance.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidSecondaryBalanceAccount)?;
+    if balance.pool != *pool
+        || balance.farmer != *farmer
+        || *secondary_balance_info.key != expected_balance
+    {
+        return Err(RewardPoolError::InvalidSecondaryBalanceAccount.into());
+    }
+    Ok(balance)
+}
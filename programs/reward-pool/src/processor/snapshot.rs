@@ -0,0 +1,120 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::treasury::load_pool;
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_daily_stats_address_with_program_id,
+        find_instruction_counters_address_with_program_id, find_parameters_address_with_program_id,
+    },
+    state::{DailyStats, PoolSnapshot, SnapshotAccount},
+};
+
+// Exporting a snapshot of a pool's accounts
+pub(super) fn process_export_snapshot(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let exporter_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let buffer_info = next_account_info(account_info_iter)?;
+    let parameters_info = next_account_info(account_info_iter)?;
+    let counters_info = next_account_info(account_info_iter)?;
+    let stats_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !exporter_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    load_pool(program_id, pool_info)?;
+
+    // The buffer signs, so no account of the program's can be overwritten,
+    // and is zeroed, so a snapshot is never overwritten either
+    if !buffer_info.is_signer
+        || buffer_info.owner != program_id
+        || buffer_info.data.borrow().iter().any(|byte| *byte != 0)
+    {
+        return Err(RewardPoolError::InvalidSnapshotBuffer.into());
+    }
+
+    let (expected_parameters, _) =
+        find_parameters_address_with_program_id(pool_info.key, program_id);
+    if *parameters_info.key != expected_parameters {
+        return Err(RewardPoolError::InvalidParametersAccount.into());
+    }
+    let (expected_counters, _) =
+        find_instruction_counters_address_with_program_id(pool_info.key, program_id);
+    if *counters_info.key != expected_counters {
+        return Err(RewardPoolError::InvalidCountersAccount.into());
+    }
+    for info in [parameters_info, counters_info] {
+        if !info.data_is_empty() && info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+    }
+
+    for stats_info in stats_infos {
+        if stats_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let stats = DailyStats::try_from_slice(&stats_info.data.borrow())
+            .map_err(|_| RewardPoolError::InvalidStatsAccount)?;
+        let (expected_stats, _) =
+            find_daily_stats_address_with_program_id(pool_info.key, stats.day, program_id);
+        if *stats_info.key != expected_stats {
+            return Err(RewardPoolError::InvalidStatsAccount.into());
+        }
+    }
+
+    let clock = Clock::get()?;
+    let snapshot_accounts: Vec<SnapshotAccount> = [pool_info, parameters_info, counters_info]
+        .into_iter()
+        .chain(stats_infos)
+        .map(|info| SnapshotAccount {
+            address: *info.key,
+            data: info.data.borrow().to_vec(),
+        })
+        .collect();
+    let snapshot = PoolSnapshot {
+        pool: *pool_info.key,
+        exported_by: *exporter_info.key,
+        slot: clock.slot,
+        exported_at: clock.unix_timestamp,
+        commitment: PoolSnapshot::commit(clock.slot, &snapshot_accounts),
+        accounts: snapshot_accounts,
+    };
+
+    let data = borsh::to_vec(&snapshot)?;
+    if data.len() > buffer_info.data_len() {
+        return Err(RewardPoolError::SnapshotBufferTooSmall.into());
+    }
+    buffer_info.data.borrow_mut()[..data.len()].copy_from_slice(&data);
+
+    RewardPoolEvent::PoolSnapshotExported {
+        pool: *pool_info.key,
+        buffer: *buffer_info.key,
+        slot: snapshot.slot,
+        commitment: snapshot.commitment,
+        account_count: snapshot.accounts.len() as u32,
+    }
+    .emit();
+
+    msg!(
+        "Exported a snapshot of {} accounts at slot {}",
+        snapshot.accounts.len(),
+        snapshot.slot
+    );
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult,
+    program_error::ProgramError, pubkey::Pubkey, sysvar::Sysvar,
+};
+
+use super::utils::create_pda_account;
+use crate::{
+    error::RewardPoolError,
+    seeds::{find_daily_stats_address_with_program_id, DAILY_STATS_SEED},
+    state::{DailyStats, Sequenced},
+};
+
+/// Applies `update` to the pool's stats account for the current day,
+/// creating the account on the first update of the day
+pub(super) fn update_daily_stats<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    stats_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    update: impl FnOnce(&mut DailyStats),
+) -> ProgramResult {
+    let day = DailyStats::day_of(Clock::get()?.unix_timestamp);
+    let (expected_stats, bump_seed) =
+        find_daily_stats_address_with_program_id(pool, day, program_id);
+    if *stats_info.key != expected_stats {
+        return Err(RewardPoolError::InvalidStatsAccount.into());
+    }
+
+    let mut stats = if stats_info.data_is_empty() {
+        create_pda_account(
+            payer_info,
+            stats_info,
+            system_program_info,
+            program_id,
+            DailyStats::LEN,
+            &[
+                DAILY_STATS_SEED,
+                pool.as_ref(),
+                &day.to_le_bytes(),
+                &[bump_seed],
+            ],
+        )?;
+        DailyStats::new(*pool, day, bump_seed)
+    } else {
+        if stats_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        DailyStats::try_from_slice(&stats_info.data.borrow())?
+    };
+
+    update(&mut stats);
+    stats.save(stats_info)
+}
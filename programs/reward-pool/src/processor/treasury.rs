@@ -0,0 +1,472 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token::{instruction as token_instruction, state::Account as TokenAccount};
+
+use super::utils::{create_pda_account, token_balance};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_treasury_governance_address_with_program_id,
+        find_treasury_proposal_address_with_program_id, find_vault_address_with_program_id,
+        TREASURY_GOVERNANCE_SEED, TREASURY_PROPOSAL_SEED, VAULT_SEED,
+    },
+    state::{RewardPool, Sequenced, TreasuryGovernance, TreasuryProposal},
+};
+
+// Configuring treasury governance
+pub(super) fn process_configure_treasury_governance(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    approvers: Vec<Pubkey>,
+    required_approvals: u8,
+    approval_threshold: u64,
+    execution_delay: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let governance_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if *reward_mint_info.key != pool_data.reward_mint || *token_program_info.key != spl_token::id()
+    {
+        return Err(RewardPoolError::InvalidVaultAccount.into());
+    }
+
+    let distinct = approvers
+        .iter()
+        .enumerate()
+        .all(|(index, approver)| !approvers[..index].contains(approver));
+    if approvers.is_empty()
+        || approvers.len() > TreasuryGovernance::MAX_APPROVERS
+        || !distinct
+        || required_approvals == 0
+        || required_approvals as usize > approvers.len()
+        || execution_delay < 0
+    {
+        return Err(RewardPoolError::InvalidGovernanceConfig.into());
+    }
+
+    let (expected_governance, bump_seed) =
+        find_treasury_governance_address_with_program_id(pool_info.key, program_id);
+    if *governance_info.key != expected_governance {
+        return Err(RewardPoolError::InvalidGovernanceAccount.into());
+    }
+    if !governance_info.data_is_empty() {
+        return Err(RewardPoolError::GovernanceAlreadyConfigured.into());
+    }
+
+    let (expected_vault, vault_bump_seed) =
+        find_vault_address_with_program_id(pool_info.key, program_id);
+    if *vault_info.key != expected_vault {
+        return Err(RewardPoolError::InvalidVaultAccount.into());
+    }
+
+    // Every pool is created with its vault
+    if vault_info.data_is_empty() {
+        return Err(RewardPoolError::InvalidVaultAccount.into());
+    }
+
+    create_pda_account(
+        platform_authority_info,
+        governance_info,
+        system_program_info,
+        program_id,
+        TreasuryGovernance::LEN,
+        &[
+            TREASURY_GOVERNANCE_SEED,
+            pool_info.key.as_ref(),
+            &[bump_seed],
+        ],
+    )?;
+
+    let mut governance = TreasuryGovernance {
+        pool: *pool_info.key,
+        approver_count: approvers.len() as u8,
+        approvers: [Pubkey::default(); TreasuryGovernance::MAX_APPROVERS],
+        required_approvals,
+        approval_threshold,
+        execution_delay,
+        proposal_count: 0,
+        vault_bump_seed,
+        bump_seed,
+        epoch_started_at: 0,
+        epoch_withdrawn: 0,
+        sequence: 0,
+    };
+    governance.approvers[..approvers.len()].copy_from_slice(&approvers);
+    governance.save(governance_info)?;
+
+    RewardPoolEvent::TreasuryGovernanceConfigured {
+        pool: *pool_info.key,
+        vault: *vault_info.key,
+        approvers,
+        required_approvals,
+        approval_threshold,
+        execution_delay,
+    }
+    .emit();
+
+    msg!(
+        "Treasury governance configured: {} approvals above {}",
+        required_approvals,
+        approval_threshold
+    );
+    Ok(())
+}
+
+// Proposing a treasury withdrawal
+pub(super) fn process_propose_treasury_withdrawal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let proposer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let governance_info = next_account_info(account_info_iter)?;
+    let proposal_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !proposer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    let mut governance = load_governance(program_id, pool_info.key, governance_info)?;
+    let approver_index = governance.approver_index(proposer_info.key);
+    if pool_data.platform_authority != *proposer_info.key && approver_index.is_none() {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if amount == 0 {
+        return Err(RewardPoolError::InsufficientAmount.into());
+    }
+
+    let destination = TokenAccount::unpack(&destination_info.data.borrow())?;
+    if destination.mint != pool_data.reward_mint {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let proposal_id = governance.proposal_count;
+    let (expected_proposal, bump_seed) =
+        find_treasury_proposal_address_with_program_id(pool_info.key, proposal_id, program_id);
+    if *proposal_info.key != expected_proposal {
+        return Err(RewardPoolError::InvalidProposalAccount.into());
+    }
+
+    create_pda_account(
+        proposer_info,
+        proposal_info,
+        system_program_info,
+        program_id,
+        TreasuryProposal::LEN,
+        &[
+            TREASURY_PROPOSAL_SEED,
+            pool_info.key.as_ref(),
+            &proposal_id.to_le_bytes(),
+            &[bump_seed],
+        ],
+    )?;
+
+    // Whether the proposal needs approvals depends on what the epoch has
+    // withdrawn when it executes, so the delay is always set and only
+    // applies then
+    let created_at = Clock::get()?.unix_timestamp;
+    let executable_at = created_at.saturating_add(governance.execution_delay);
+
+    let mut proposal = TreasuryProposal {
+        pool: *pool_info.key,
+        proposal_id,
+        proposer: *proposer_info.key,
+        destination: *destination_info.key,
+        amount,
+        approvals: approver_index.map_or(0, |index| 1 << index),
+        created_at,
+        executable_at,
+        executed: false,
+        bump_seed,
+        sequence: 0,
+    };
+    proposal.save(proposal_info)?;
+
+    governance.proposal_count += 1;
+    governance.save(governance_info)?;
+
+    RewardPoolEvent::TreasuryWithdrawalProposed {
+        pool: *pool_info.key,
+        proposal_id,
+        proposer: *proposer_info.key,
+        destination: *destination_info.key,
+        amount,
+        executable_at,
+    }
+    .emit();
+
+    msg!(
+        "Treasury withdrawal {} proposed: {} tokens",
+        proposal_id,
+        amount
+    );
+    Ok(())
+}
+
+// Approving a treasury withdrawal
+pub(super) fn process_approve_treasury_withdrawal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let approver_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let governance_info = next_account_info(account_info_iter)?;
+    let proposal_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !approver_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    load_pool(program_id, pool_info)?;
+
+    let governance = load_governance(program_id, pool_info.key, governance_info)?;
+    let mut proposal = load_proposal(program_id, pool_info.key, proposal_info)?;
+
+    let index = governance
+        .approver_index(approver_info.key)
+        .ok_or(RewardPoolError::NotAnApprover)?;
+    if proposal.executed {
+        return Err(RewardPoolError::ProposalAlreadyExecuted.into());
+    }
+    if proposal.approvals & (1 << index) != 0 {
+        return Err(RewardPoolError::AlreadyApproved.into());
+    }
+
+    proposal.approvals |= 1 << index;
+    proposal.save(proposal_info)?;
+
+    RewardPoolEvent::TreasuryWithdrawalApproved {
+        pool: *pool_info.key,
+        proposal_id: proposal.proposal_id,
+        approver: *approver_info.key,
+        approval_count: proposal.approval_count(),
+    }
+    .emit();
+
+    msg!(
+        "Treasury withdrawal {} approved: {}/{}",
+        proposal.proposal_id,
+        proposal.approval_count(),
+        governance.required_approvals
+    );
+    Ok(())
+}
+
+// Executing a treasury withdrawal
+pub(super) fn process_execute_treasury_withdrawal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let executor_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let governance_info = next_account_info(account_info_iter)?;
+    let proposal_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !executor_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+
+    let mut governance = load_governance(program_id, pool_info.key, governance_info)?;
+    let mut proposal = load_proposal(program_id, pool_info.key, proposal_info)?;
+
+    if proposal.executed {
+        return Err(RewardPoolError::ProposalAlreadyExecuted.into());
+    }
+    if *destination_info.key != proposal.destination {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if governance.withdrawal_requires_approval(proposal.amount, now) {
+        if proposal.approval_count() < governance.required_approvals {
+            return Err(RewardPoolError::InsufficientApprovals.into());
+        }
+        if now < proposal.executable_at {
+            return Err(RewardPoolError::ProposalNotExecutable.into());
+        }
+    }
+
+    let vault_seeds: &[&[u8]] = &[
+        VAULT_SEED,
+        pool_info.key.as_ref(),
+        &[governance.vault_bump_seed],
+    ];
+    let expected_vault = Pubkey::create_program_address(vault_seeds, program_id)?;
+    if *vault_info.key != expected_vault {
+        return Err(RewardPoolError::InvalidVaultAccount.into());
+    }
+
+    // Tokens booked to farmers' pending rewards are not the treasury's
+    let available = token_balance(vault_info)?.saturating_sub(pool_data.outstanding_rewards);
+    if proposal.amount > available {
+        return Err(RewardPoolError::InsufficientTreasuryFunds.into());
+    }
+
+    proposal.executed = true;
+    proposal.save(proposal_info)?;
+    governance.record_withdrawal(proposal.amount, now);
+    governance.save(governance_info)?;
+
+    invoke_signed(
+        &token_instruction::transfer(
+            token_program_info.key,
+            vault_info.key,
+            destination_info.key,
+            vault_info.key,
+            &[],
+            proposal.amount,
+        )?,
+        &[
+            vault_info.clone(),
+            destination_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[vault_seeds],
+    )?;
+
+    RewardPoolEvent::TreasuryWithdrawalExecuted {
+        pool: *pool_info.key,
+        proposal_id: proposal.proposal_id,
+        destination: proposal.destination,
+        amount: proposal.amount,
+    }
+    .emit();
+
+    msg!(
+        "Treasury withdrawal {} executed: {} tokens",
+        proposal.proposal_id,
+        proposal.amount
+    );
+    Ok(())
+}
+
+pub(super) fn load_pool(
+    program_id: &Pubkey,
+    pool_info: &AccountInfo,
+) -> Result<RewardPool, ProgramError> {
+    if pool_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let pool = RewardPool::try_from_slice(&pool_info.data.borrow())?;
+    // Only the address derived from the pool's own seeds holds the pool;
+    // any other account owned by the program fails the derivation
+    if pool.address(program_id)? != *pool_info.key {
+        return Err(RewardPoolError::InvalidPoolAccount.into());
+    }
+    Ok(pool)
+}
+
+pub(super) fn load_governance(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    governance_info: &AccountInfo,
+) -> Result<TreasuryGovernance, ProgramError> {
+    read_governance(program_id, pool, governance_info)?
+        .ok_or_else(|| RewardPoolError::InvalidGovernanceAccount.into())
+}
+
+/// Returns the pool's treasury governance, `None` until it is configured
+pub(super) fn read_governance(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    governance_info: &AccountInfo,
+) -> Result<Option<TreasuryGovernance>, ProgramError> {
+    let (expected_governance, _) =
+        find_treasury_governance_address_with_program_id(pool, program_id);
+    if *governance_info.key != expected_governance {
+        return Err(RewardPoolError::InvalidGovernanceAccount.into());
+    }
+    if governance_info.data_is_empty() {
+        return Ok(None);
+    }
+    if governance_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(Some(TreasuryGovernance::try_from_slice(
+        &governance_info.data.borrow(),
+    )?))
+}
+
+fn load_proposal(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    proposal_info: &AccountInfo,
+) -> Result<TreasuryProposal, ProgramError> {
+    if proposal_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let proposal = TreasuryProposal::try_from_slice(&proposal_info.data.borrow())?;
+    if proposal.pool != *pool {
+        return Err(RewardPoolError::InvalidProposalAccount.into());
+    }
+    Ok(proposal)
+}
+
+/// Fails unless `signers` include the governance's required number of
+/// distinct approvers. Returns the approvers that signed.
+pub(super) fn check_approvals(
+    governance: &TreasuryGovernance,
+    signers: &[AccountInfo],
+) -> Result<Vec<Pubkey>, ProgramError> {
+    // Each approver counts once, whatever the number of times it is passed
+    let mut approvals = 0u8;
+    for signer_info in signers.iter().filter(|info| info.is_signer) {
+        if let Some(index) = governance.approver_index(signer_info.key) {
+            approvals |= 1 << index;
+        }
+    }
+    if (approvals.count_ones() as u8) < governance.required_approvals {
+        return Err(RewardPoolError::InsufficientApprovals.into());
+    }
+
+    Ok(governance
+        .approvers()
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| approvals & (1 << index) != 0)
+        .map(|(_, approver)| *approver)
+        .collect())
+}
@@ -0,0 +1,329 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token::{instruction as token_instruction, state::Account as TokenAccount};
+
+use super::{
+    program_treasury::check_platform_fee_vault,
+    treasury::{check_approvals, load_governance, load_pool},
+    utils::{create_pda_account, token_balance},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_treasury_reserve_address_with_program_id,
+        find_treasury_swap_policy_address_with_program_id, PLATFORM_FEE_VAULT_SEED,
+        TREASURY_RESERVE_SEED, TREASURY_SWAP_POLICY_SEED,
+    },
+    state::{Sequenced, TreasurySwapPolicy},
+    BASIS_POINTS,
+};
+
+// Setting what the treasury may be swapped into
+pub(super) fn process_set_treasury_swap_policy(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    dex_program: Option<Pubkey>,
+    stable_mints: Vec<Pubkey>,
+    epoch_limit_bps: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let governance_info = next_account_info(account_info_iter)?;
+    let policy_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let approver_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let governance = load_governance(program_id, pool_info.key, governance_info)?;
+    check_approvals(&governance, approver_infos)?;
+
+    // The DEX is handed the vault's signature, so it must not be a program
+    // that would act on that signature itself
+    if let Some(dex_program) = dex_program {
+        if dex_program == *program_id || dex_program == spl_token::id() {
+            return Err(RewardPoolError::InvalidDexProgram.into());
+        }
+    }
+
+    let distinct = stable_mints
+        .iter()
+        .enumerate()
+        .all(|(index, mint)| !stable_mints[..index].contains(mint));
+    if stable_mints.len() > TreasurySwapPolicy::MAX_STABLE_MINTS
+        || !distinct
+        || stable_mints.contains(&pool_data.reward_mint)
+        || epoch_limit_bps > BASIS_POINTS
+    {
+        return Err(RewardPoolError::InvalidTreasurySwapPolicy.into());
+    }
+
+    let (expected_policy, bump_seed) =
+        find_treasury_swap_policy_address_with_program_id(pool_info.key, program_id);
+    if *policy_info.key != expected_policy {
+        return Err(RewardPoolError::InvalidTreasurySwapPolicyAccount.into());
+    }
+
+    let mut policy = if policy_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            policy_info,
+            system_program_info,
+            program_id,
+            TreasurySwapPolicy::LEN,
+            &[
+                TREASURY_SWAP_POLICY_SEED,
+                pool_info.key.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        TreasurySwapPolicy {
+            pool: *pool_info.key,
+            dex_program: Pubkey::default(),
+            stable_mint_count: 0,
+            stable_mints: [Pubkey::default(); TreasurySwapPolicy::MAX_STABLE_MINTS],
+            epoch_limit_bps: 0,
+            epoch: 0,
+            epoch_budget: 0,
+            epoch_swapped: 0,
+            total_swapped: 0,
+            swap_count: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_treasury_swap_policy(program_id, pool_info.key, policy_info)?
+    };
+
+    policy.dex_program = dex_program.unwrap_or_default();
+    policy.stable_mint_count = stable_mints.len() as u8;
+    policy.stable_mints = [Pubkey::default(); TreasurySwapPolicy::MAX_STABLE_MINTS];
+    policy.stable_mints[..stable_mints.len()].copy_from_slice(&stable_mints);
+    policy.epoch_limit_bps = epoch_limit_bps;
+    policy.save(policy_info)?;
+
+    RewardPoolEvent::TreasurySwapPolicySet {
+        pool: *pool_info.key,
+        dex_program,
+        stable_mints,
+        epoch_limit_bps,
+    }
+    .emit();
+
+    match dex_program {
+        Some(dex_program) => msg!(
+            "Treasury swaps enabled through {} for {} bps per epoch",
+            dex_program,
+            epoch_limit_bps
+        ),
+        None => msg!("Treasury swaps disabled"),
+    }
+    Ok(())
+}
+
+/// Runs a queued `SwapTreasury` action: swaps `amount` of the platform fee
+/// vault into the reserve of `output_mint` through the policy's DEX, the
+/// program signing for the fee vault. `executor_info` pays for the reserve on
+/// first use.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn execute_treasury_swap<'a>(
+    program_id: &Pubkey,
+    pool_info: &AccountInfo<'a>,
+    executor_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
+    output_mint: &Pubkey,
+    amount: u64,
+    min_output: u64,
+    swap_data: Vec<u8>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let policy_info = next_account_info(account_info_iter)?;
+    let fee_vault_info = next_account_info(account_info_iter)?;
+    let reserve_info = next_account_info(account_info_iter)?;
+    let output_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let dex_program_info = next_account_info(account_info_iter)?;
+    let swap_infos = account_info_iter.as_slice();
+
+    let mut policy = load_treasury_swap_policy(program_id, pool_info.key, policy_info)?;
+    if !policy.is_enabled() {
+        return Err(RewardPoolError::TreasurySwapsDisabled.into());
+    }
+    if *dex_program_info.key != policy.dex_program {
+        return Err(RewardPoolError::InvalidDexProgram.into());
+    }
+    if !policy.stable_mints().contains(output_mint) {
+        return Err(RewardPoolError::MintNotWhitelisted.into());
+    }
+
+    // The treasury vault holds what farmers are owed, so only collected
+    // fees are swapped
+    let fee_vault_bump_seed = check_platform_fee_vault(program_id, pool_info.key, fee_vault_info)?;
+
+    let (expected_reserve, reserve_bump_seed) =
+        find_treasury_reserve_address_with_program_id(pool_info.key, output_mint, program_id);
+    if *reserve_info.key != expected_reserve
+        || output_mint_info.key != output_mint
+        || *token_program_info.key != spl_token::id()
+    {
+        return Err(RewardPoolError::InvalidTreasuryReserveAccount.into());
+    }
+    if reserve_info.data_is_empty() {
+        // Like the vault, each reserve is its own token owner
+        create_pda_account(
+            executor_info,
+            reserve_info,
+            system_program_info,
+            token_program_info.key,
+            TokenAccount::LEN,
+            &[
+                TREASURY_RESERVE_SEED,
+                pool_info.key.as_ref(),
+                output_mint.as_ref(),
+                &[reserve_bump_seed],
+            ],
+        )?;
+        invoke(
+            &token_instruction::initialize_account3(
+                token_program_info.key,
+                reserve_info.key,
+                output_mint_info.key,
+                reserve_info.key,
+            )?,
+            &[
+                reserve_info.clone(),
+                output_mint_info.clone(),
+                token_program_info.clone(),
+            ],
+        )?;
+    }
+
+    // A fee vault the first fee has not created yet holds nothing
+    let fee_vault_before = if fee_vault_info.data_is_empty() {
+        0
+    } else {
+        token_balance(fee_vault_info)?
+    };
+    let reserve_before = token_balance(reserve_info)?;
+
+    // The first swap of an epoch sets how much of the fee vault the epoch
+    // allows
+    let epoch = Clock::get()?.epoch;
+    if policy.swap_count == 0 || policy.epoch != epoch {
+        policy.epoch = epoch;
+        policy.epoch_budget = (fee_vault_before as u128 * policy.epoch_limit_bps as u128
+            / BASIS_POINTS as u128) as u64;
+        policy.epoch_swapped = 0;
+    }
+    let epoch_swapped = policy
+        .epoch_swapped
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    if epoch_swapped > policy.epoch_budget {
+        return Err(RewardPoolError::TreasurySwapLimitExceeded.into());
+    }
+
+    let fee_vault_seeds: &[&[u8]] = &[
+        PLATFORM_FEE_VAULT_SEED,
+        pool_info.key.as_ref(),
+        &[fee_vault_bump_seed],
+    ];
+    let swap = Instruction {
+        program_id: *dex_program_info.key,
+        accounts: swap_infos
+            .iter()
+            .map(|info| AccountMeta {
+                pubkey: *info.key,
+                is_signer: info.is_signer || info.key == fee_vault_info.key,
+                is_writable: info.is_writable,
+            })
+            .collect(),
+        data: swap_data,
+    };
+    let mut swap_account_infos = swap_infos.to_vec();
+    swap_account_infos.push(dex_program_info.clone());
+    invoke_signed(&swap, &swap_account_infos, &[fee_vault_seeds])?;
+
+    // The DEX holds the fee vault's signature for the swap, so whatever it
+    // took beyond the queued amount fails the whole action
+    let spent = fee_vault_before.saturating_sub(token_balance(fee_vault_info)?);
+    if spent > amount {
+        return Err(RewardPoolError::TreasurySwapLimitExceeded.into());
+    }
+    let received = token_balance(reserve_info)?.saturating_sub(reserve_before);
+    if received < min_output {
+        return Err(RewardPoolError::TreasurySwapSlippageExceeded.into());
+    }
+
+    policy.epoch_swapped = policy.epoch_swapped.saturating_add(spent);
+    policy.total_swapped = policy.total_swapped.saturating_add(spent);
+    policy.swap_count = policy
+        .swap_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    policy.save(policy_info)?;
+
+    RewardPoolEvent::TreasurySwapped {
+        pool: *pool_info.key,
+        output_mint: *output_mint,
+        spent,
+        received,
+    }
+    .emit();
+
+    msg!(
+        "Treasury swapped {} into {} of {}",
+        spent,
+        received,
+        output_mint
+    );
+    Ok(())
+}
+
+fn load_treasury_swap_policy(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    policy_info: &AccountInfo,
+) -> Result<TreasurySwapPolicy, ProgramError> {
+    if policy_info.owner != program_id {
+        return Err(RewardPoolError::InvalidTreasurySwapPolicyAccount.into());
+    }
+    let policy = TreasurySwapPolicy::try_from_slice(&policy_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidTreasurySwapPolicyAccount)?;
+    let expected_policy = Pubkey::create_program_address(
+        &[
+            TREASURY_SWAP_POLICY_SEED,
+            pool.as_ref(),
+            &[policy.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidTreasurySwapPolicyAccount)?;
+    if policy.pool != *pool || *policy_info.key != expected_policy {
+        return Err(RewardPoolError::InvalidTreasurySwapPolicyAccount.into());
+    }
+    Ok(policy)
+}
@@ -0,0 +1,96 @@
+use solana_loader_v3_interface::{get_program_data_address, instruction::set_upgrade_authority};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::treasury::{check_approvals, load_governance, load_pool};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{find_upgrade_authority_address_with_program_id, UPGRADE_AUTHORITY_SEED},
+};
+
+// Setting or removing the program upgrade authority
+pub(super) fn process_set_upgrade_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    set: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let governance_info = next_account_info(account_info_iter)?;
+    let program_data_info = next_account_info(account_info_iter)?;
+    let upgrade_authority_info = next_account_info(account_info_iter)?;
+    let loader_program_info = next_account_info(account_info_iter)?;
+    let _system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let new_authority_info = if set {
+        Some(next_account_info(account_info_iter)?)
+    } else {
+        None
+    };
+    let approver_infos = account_info_iter.as_slice();
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let governance = load_governance(program_id, pool_info.key, governance_info)?;
+    let approvers = check_approvals(&governance, approver_infos)?;
+
+    if *program_data_info.key != get_program_data_address(program_id) {
+        return Err(RewardPoolError::InvalidProgramDataAccount.into());
+    }
+
+    let (expected_authority, bump_seed) =
+        find_upgrade_authority_address_with_program_id(pool_info.key, program_id);
+    if *upgrade_authority_info.key != expected_authority {
+        return Err(RewardPoolError::InvalidUpgradeAuthority.into());
+    }
+
+    let new_authority = new_authority_info.map(|info| *info.key);
+    let set_authority_ix = set_upgrade_authority(
+        program_id,
+        upgrade_authority_info.key,
+        new_authority.as_ref(),
+    );
+    if *loader_program_info.key != set_authority_ix.program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut cpi_accounts = vec![program_data_info.clone(), upgrade_authority_info.clone()];
+    cpi_accounts.extend(new_authority_info.cloned());
+    cpi_accounts.push(loader_program_info.clone());
+
+    // The loader rejects the change unless the PDA is the current authority
+    invoke_signed(
+        &set_authority_ix,
+        &cpi_accounts,
+        &[&[UPGRADE_AUTHORITY_SEED, pool_info.key.as_ref(), &[bump_seed]]],
+    )?;
+
+    RewardPoolEvent::UpgradeAuthorityChanged {
+        pool: *pool_info.key,
+        new_authority,
+        approvers,
+    }
+    .emit();
+
+    match new_authority {
+        Some(authority) => msg!("Upgrade authority set to {}", authority),
+        None => msg!("Upgrade authority removed"),
+    }
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::instruction as system_instruction;
+use spl_token::{instruction as token_instruction, state::Account as TokenAccount};
+
+/// Creates a program-owned PDA of `space` bytes paid by `payer`. Accounts
+/// that were pre-funded by a third party are topped up, allocated and
+/// assigned instead, since `create_account` would reject them.
+pub(crate) fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    program_id: &Pubkey,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let required_lamports = Rent::get()?.minimum_balance(space);
+
+    if account.lamports() == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                account.key,
+                required_lamports,
+                space as u64,
+                program_id,
+            ),
+            &[payer.clone(), account.clone(), system_program.clone()],
+            &[signer_seeds],
+        );
+    }
+
+    let missing_lamports = required_lamports.saturating_sub(account.lamports());
+    if missing_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, missing_lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, space as u64),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, program_id),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )
+}
+
+/// Closes a program-owned account, moving its lamports to `recipient` and
+/// handing it back to the system program
+pub(crate) fn close_pda_account<'a>(
+    account: &AccountInfo<'a>,
+    recipient: &AccountInfo<'a>,
+) -> ProgramResult {
+    let lamports = account.lamports();
+    **account.try_borrow_mut_lamports()? = 0;
+    **recipient.try_borrow_mut_lamports()? = recipient
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    account.resize(0)?;
+    account.assign(&solana_system_interface::program::id());
+    Ok(())
+}
+
+/// Resizes a program-owned account to `space` bytes, topping its rent up
+/// from `payer` when it grows
+pub(crate) fn resize_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    space: usize,
+) -> ProgramResult {
+    let missing_lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+    if missing_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, missing_lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    account.resize(space)
+}
+
+/// Creates a token account of `mint_info` at a PDA, owned by itself like the
+/// treasury vault so the program signs for it with `signer_seeds`
+pub(crate) fn create_self_owned_token_account<'a>(
+    payer_info: &AccountInfo<'a>,
+    token_account_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    create_pda_account(
+        payer_info,
+        token_account_info,
+        system_program_info,
+        token_program_info.key,
+        TokenAccount::LEN,
+        signer_seeds,
+    )?;
+    invoke(
+        &token_instruction::initialize_account3(
+            token_program_info.key,
+            token_account_info.key,
+            mint_info.key,
+            token_account_info.key,
+        )?,
+        &[
+            token_account_info.clone(),
+            mint_info.clone(),
+            token_program_info.clone(),
+        ],
+    )
+}
+
+/// Reads the balance of a token account
+pub(crate) fn token_balance(token_account_info: &AccountInfo) -> Result<u64, ProgramError> {
+    Ok(TokenAccount::unpack(&token_account_info.data.borrow())?.amount)
+}
+
+/// Moves everything a self-owned token account holds to `destination_info`
+/// and closes it, refunding its rent to `rent_recipient_info`, signed for by
+/// the program with `signer_seeds`. Returns the amount moved.
+pub(crate) fn sweep_self_owned_token_account<'a>(
+    token_account_info: &AccountInfo<'a>,
+    destination_info: &AccountInfo<'a>,
+    rent_recipient_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    signer_seeds: &[&[u8]],
+) -> Result<u64, ProgramError> {
+    let amount = token_balance(token_account_info)?;
+    if amount > 0 {
+        invoke_signed(
+            &token_instruction::transfer(
+                token_program_info.key,
+                token_account_info.key,
+                destination_info.key,
+                token_account_info.key,
+                &[],
+                amount,
+            )?,
+            &[
+                token_account_info.clone(),
+                destination_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+    }
+    invoke_signed(
+        &token_instruction::close_account(
+            token_program_info.key,
+            token_account_info.key,
+            rent_recipient_info.key,
+            token_account_info.key,
+            &[],
+        )?,
+        &[
+            token_account_info.clone(),
+            rent_recipient_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[signer_seeds],
+    )?;
+    Ok(amount)
+}
@@ -0,0 +1,145 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use super::{treasury::load_pool, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{find_pool_verifier_address_with_program_id, POOL_VERIFIER_SEED},
+    state::{PoolVerifier, Sequenced},
+};
+
+// Setting or removing the pool's reward verifier
+pub(super) fn process_set_verifier(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    verifier: Option<Pubkey>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let pool_verifier_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let current_verifier_info = next_account_info(account_info_iter).ok();
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let (expected_pool_verifier, bump_seed) =
+        find_pool_verifier_address_with_program_id(pool_info.key, program_id);
+    if *pool_verifier_info.key != expected_pool_verifier {
+        return Err(RewardPoolError::InvalidPoolVerifierAccount.into());
+    }
+
+    let mut pool_verifier = if pool_verifier_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            pool_verifier_info,
+            system_program_info,
+            program_id,
+            PoolVerifier::LEN,
+            &[POOL_VERIFIER_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        PoolVerifier {
+            pool: *pool_info.key,
+            verifier: Pubkey::default(),
+            set_at: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_pool_verifier(program_id, pool_verifier_info)?
+    };
+
+    // The verifier exists to keep the platform in check, so it has to agree
+    // to being replaced
+    if let Some(current_verifier) = pool_verifier.verifier() {
+        check_verifier_signature(&current_verifier, current_verifier_info)?;
+    }
+
+    pool_verifier.verifier = verifier.unwrap_or_default();
+    pool_verifier.set_at = Clock::get()?.unix_timestamp;
+    pool_verifier.save(pool_verifier_info)?;
+
+    RewardPoolEvent::VerifierSet {
+        pool: *pool_info.key,
+        verifier,
+    }
+    .emit();
+
+    match verifier {
+        Some(verifier) => msg!("Verifier set to {}", verifier),
+        None => msg!("Verifier removed"),
+    }
+    Ok(())
+}
+
+/// Returns the verifier `RecordReward` must be co-signed by, checking its
+/// signature in `verifier_info`. `None` when the pool has no verifier.
+pub(super) fn check_reward_verifier(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    pool_verifier_info: &AccountInfo,
+    cosigner_infos: &[AccountInfo],
+) -> Result<Option<Pubkey>, ProgramError> {
+    let verifier = read_pool_verifier(program_id, pool, pool_verifier_info)?;
+    if let Some(verifier) = &verifier {
+        let verifier_info = cosigner_infos.iter().find(|info| info.key == verifier);
+        check_verifier_signature(verifier, verifier_info)?;
+    }
+    Ok(verifier)
+}
+
+/// Returns the pool's verifier, `None` when the pool has none
+pub(super) fn read_pool_verifier(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    pool_verifier_info: &AccountInfo,
+) -> Result<Option<Pubkey>, ProgramError> {
+    let (expected_pool_verifier, _) = find_pool_verifier_address_with_program_id(pool, program_id);
+    if *pool_verifier_info.key != expected_pool_verifier {
+        return Err(RewardPoolError::InvalidPoolVerifierAccount.into());
+    }
+    if pool_verifier_info.data_is_empty() {
+        return Ok(None);
+    }
+
+    Ok(load_pool_verifier(program_id, pool_verifier_info)?.verifier())
+}
+
+fn check_verifier_signature(
+    verifier: &Pubkey,
+    verifier_info: Option<&AccountInfo>,
+) -> ProgramResult {
+    match verifier_info {
+        Some(verifier_info) if verifier_info.key == verifier && verifier_info.is_signer => Ok(()),
+        _ => Err(RewardPoolError::MissingVerifier.into()),
+    }
+}
+
+fn load_pool_verifier(
+    program_id: &Pubkey,
+    pool_verifier_info: &AccountInfo,
+) -> Result<PoolVerifier, ProgramError> {
+    if pool_verifier_info.owner != program_id {
+        return Err(RewardPoolError::InvalidPoolVerifierAccount.into());
+    }
+    PoolVerifier::try_from_slice(&pool_verifier_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidPoolVerifierAccount.into())
+}
@@ -0,0 +1,191 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::{
+    farmer::{load_farmer_state, load_withdrawal_history},
+    treasury::load_pool,
+    withdraw_burn::read_withdraw_burn,
+};
+use crate::{
+    error::RewardPoolError,
+    seeds::{
+        find_farmer_state_address_with_program_id,
+        find_instruction_counters_address_with_program_id,
+        find_pending_reward_address_with_program_id,
+        find_withdrawal_history_address_with_program_id,
+    },
+    state::{InstructionCounters, PendingReward},
+    views::{FarmerPendingSummary, PoolStats, WithdrawalHistorySummary, MAX_PENDING_PAGE_SIZE},
+};
+
+// Summarizing a farmer's pending rewards
+pub(super) fn process_get_farmer_pending(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    farmer: Pubkey,
+    cursor: u32,
+    limit: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pool_info = next_account_info(account_info_iter)?;
+    let farmer_state_info = next_account_info(account_info_iter)?;
+    let pending_infos = account_info_iter.as_slice();
+
+    load_pool(program_id, pool_info)?;
+
+    let (expected_farmer_state, _) =
+        find_farmer_state_address_with_program_id(pool_info.key, &farmer, program_id);
+    if *farmer_state_info.key != expected_farmer_state {
+        return Err(RewardPoolError::InvalidFarmerStateAccount.into());
+    }
+    // Farmers with no activity yet have no state account
+    let (last_recorded_at, last_claimed_at) = if farmer_state_info.data_is_empty() {
+        (0, 0)
+    } else {
+        let farmer_state = load_farmer_state(program_id, farmer_state_info)?;
+        (farmer_state.last_recorded_at, farmer_state.last_claimed_at)
+    };
+
+    let start = (cursor as usize).min(pending_infos.len());
+    let end = start
+        .saturating_add(limit.min(MAX_PENDING_PAGE_SIZE) as usize)
+        .min(pending_infos.len());
+
+    let mut summary = FarmerPendingSummary {
+        farmer,
+        pending_count: 0,
+        pending_amount: 0,
+        next_cursor: (end < pending_infos.len()).then_some(end as u32),
+        last_recorded_at,
+        last_claimed_at,
+    };
+
+    for pending_info in &pending_infos[start..end] {
+        // Claimed rewards may have been closed
+        if pending_info.data_is_empty() {
+            continue;
+        }
+        if pending_info.owner != program_id {
+            return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+        }
+
+        let pending = PendingReward::try_from_slice(&pending_info.data.borrow())
+            .map_err(|_| RewardPoolError::InvalidPendingRewardAccount)?;
+        let (expected_address, _) = find_pending_reward_address_with_program_id(
+            pool_info.key,
+            &farmer,
+            &pending.task_id,
+            program_id,
+        );
+        if pending.farmer_pubkey != farmer || *pending_info.key != expected_address {
+            return Err(RewardPoolError::InvalidPendingRewardAccount.into());
+        }
+
+        if !pending.is_withdrawn {
+            summary.pending_count += 1;
+            summary.pending_amount = summary.pending_amount.saturating_add(pending.amount);
+        }
+    }
+
+    msg!(
+        "Farmer {} has {} pending rewards worth {} in this page",
+        farmer,
+        summary.pending_count,
+        summary.pending_amount
+    );
+    set_return_data(&borsh::to_vec(&summary)?);
+    Ok(())
+}
+
+// Reporting pool totals and instruction counts
+pub(super) fn process_get_pool_stats(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pool_info = next_account_info(account_info_iter)?;
+    let counters_info = next_account_info(account_info_iter)?;
+    let withdraw_burn_info = account_info_iter.next();
+
+    let pool = load_pool(program_id, pool_info)?;
+
+    let (expected_counters, _) =
+        find_instruction_counters_address_with_program_id(pool_info.key, program_id);
+    if *counters_info.key != expected_counters {
+        return Err(RewardPoolError::InvalidCountersAccount.into());
+    }
+    // Pools nothing was counted for yet have no counters account
+    let instruction_counts = if counters_info.data_is_empty() {
+        [0; InstructionCounters::MAX_INSTRUCTIONS]
+    } else {
+        if counters_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        InstructionCounters::unpack(&counters_info.data.borrow())?.counts
+    };
+
+    let total_withdraw_burned = match withdraw_burn_info {
+        Some(withdraw_burn_info) => {
+            read_withdraw_burn(program_id, pool_info.key, withdraw_burn_info)?
+                .map_or(0, |withdraw_burn| withdraw_burn.total_burned)
+        }
+        None => 0,
+    };
+
+    let stats = PoolStats {
+        pool: *pool_info.key,
+        platform_fee_percentage: pool.platform_fee_percentage,
+        total_rewards_distributed: pool.total_rewards_distributed,
+        total_platform_fees_collected: pool.total_platform_fees_collected,
+        total_withdraw_burned,
+        is_paused: pool.is_paused,
+        instruction_counts,
+    };
+
+    set_return_data(&borsh::to_vec(&stats)?);
+    Ok(())
+}
+
+// Reporting a farmer's recent withdrawals
+pub(super) fn process_get_withdrawal_history(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    farmer: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pool_info = next_account_info(account_info_iter)?;
+    let history_info = next_account_info(account_info_iter)?;
+
+    load_pool(program_id, pool_info)?;
+
+    let (expected_history, _) =
+        find_withdrawal_history_address_with_program_id(pool_info.key, &farmer, program_id);
+    if *history_info.key != expected_history {
+        return Err(RewardPoolError::InvalidWithdrawalHistoryAccount.into());
+    }
+    // Farmers who never withdrew have no history account
+    let summary = if history_info.data_is_empty() {
+        WithdrawalHistorySummary {
+            farmer,
+            total_count: 0,
+            records: Vec::new(),
+        }
+    } else {
+        let history = load_withdrawal_history(program_id, history_info)?;
+        WithdrawalHistorySummary {
+            farmer,
+            total_count: history.total_count,
+            records: history.recent(),
+        }
+    };
+
+    set_return_data(&borsh::to_vec(&summary)?);
+    Ok(())
+}
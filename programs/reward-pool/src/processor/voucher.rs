@@ -0,0 +1,400 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token::{
+    instruction::{self as token_instruction, AuthorityType},
+    state::{Account as TokenAccount, Mint},
+};
+
+use super::{
+    treasury::load_pool,
+    utils::{create_pda_account, create_self_owned_token_account},
+};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_vault_address_with_program_id, find_voucher_address_with_program_id,
+        find_voucher_escrow_address_with_program_id, find_voucher_mint_address_with_program_id,
+        VAULT_SEED, VOUCHER_ESCROW_SEED, VOUCHER_MINT_SEED, VOUCHER_SEED,
+    },
+    state::{Sequenced, Voucher},
+};
+
+// Issuing a reward voucher NFT
+pub(super) fn process_issue_voucher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    voucher_id: u64,
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let voucher_info = next_account_info(account_info_iter)?;
+    let voucher_mint_info = next_account_info(account_info_iter)?;
+    let recipient_info = next_account_info(account_info_iter)?;
+    let recipient_token_account_info = next_account_info(account_info_iter)?;
+    let reward_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let ata_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    if amount == 0 {
+        return Err(RewardPoolError::InvalidVoucherAmount.into());
+    }
+
+    let (expected_vault, vault_bump_seed) =
+        find_vault_address_with_program_id(pool_info.key, program_id);
+    if *vault_info.key != expected_vault {
+        return Err(RewardPoolError::InvalidVaultAccount.into());
+    }
+
+    let (expected_escrow, escrow_bump_seed) =
+        find_voucher_escrow_address_with_program_id(pool_info.key, program_id);
+    if *escrow_info.key != expected_escrow {
+        return Err(RewardPoolError::InvalidVoucherEscrowAccount.into());
+    }
+
+    let (expected_voucher, bump_seed) =
+        find_voucher_address_with_program_id(pool_info.key, voucher_id, program_id);
+    if *voucher_info.key != expected_voucher {
+        return Err(RewardPoolError::InvalidVoucherAccount.into());
+    }
+    if !voucher_info.data_is_empty() {
+        return Err(RewardPoolError::VoucherAlreadyIssued.into());
+    }
+
+    let (expected_mint, mint_bump_seed) =
+        find_voucher_mint_address_with_program_id(voucher_info.key, program_id);
+    if *voucher_mint_info.key != expected_mint {
+        return Err(RewardPoolError::InvalidVoucherAccount.into());
+    }
+
+    if *reward_mint_info.key != pool_data.reward_mint {
+        return Err(RewardPoolError::InvalidVoucherEscrowAccount.into());
+    }
+
+    if *token_program_info.key != spl_token::id()
+        || *ata_program_info.key != spl_associated_token_account::id()
+    {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Encumber the voucher's tokens: the escrow only pays them out on
+    // redemption
+    if escrow_info.data_is_empty() {
+        create_self_owned_token_account(
+            platform_authority_info,
+            escrow_info,
+            reward_mint_info,
+            token_program_info,
+            system_program_info,
+            &[
+                VOUCHER_ESCROW_SEED,
+                pool_info.key.as_ref(),
+                &[escrow_bump_seed],
+            ],
+        )?;
+    }
+    invoke_signed(
+        &token_instruction::transfer(
+            token_program_info.key,
+            vault_info.key,
+            escrow_info.key,
+            vault_info.key,
+            &[],
+            amount,
+        )?,
+        &[
+            vault_info.clone(),
+            escrow_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[VAULT_SEED, pool_info.key.as_ref(), &[vault_bump_seed]]],
+    )?;
+
+    // The NFT mint is its own mint authority until its single token is
+    // minted, then has none, so the supply stays at one
+    let mint_seeds: &[&[u8]] = &[
+        VOUCHER_MINT_SEED,
+        voucher_info.key.as_ref(),
+        &[mint_bump_seed],
+    ];
+    create_pda_account(
+        platform_authority_info,
+        voucher_mint_info,
+        system_program_info,
+        token_program_info.key,
+        Mint::LEN,
+        mint_seeds,
+    )?;
+    invoke(
+        &token_instruction::initialize_mint2(
+            token_program_info.key,
+            voucher_mint_info.key,
+            voucher_mint_info.key,
+            None,
+            0,
+        )?,
+        &[voucher_mint_info.clone(), token_program_info.clone()],
+    )?;
+
+    invoke(
+        &spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            platform_authority_info.key,
+            recipient_info.key,
+            voucher_mint_info.key,
+            token_program_info.key,
+        ),
+        &[
+            platform_authority_info.clone(),
+            recipient_token_account_info.clone(),
+            recipient_info.clone(),
+            voucher_mint_info.clone(),
+            system_program_info.clone(),
+            token_program_info.clone(),
+            ata_program_info.clone(),
+        ],
+    )?;
+
+    invoke_signed(
+        &token_instruction::mint_to(
+            token_program_info.key,
+            voucher_mint_info.key,
+            recipient_token_account_info.key,
+            voucher_mint_info.key,
+            &[],
+            1,
+        )?,
+        &[
+            voucher_mint_info.clone(),
+            recipient_token_account_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[mint_seeds],
+    )?;
+    invoke_signed(
+        &token_instruction::set_authority(
+            token_program_info.key,
+            voucher_mint_info.key,
+            None,
+            AuthorityType::MintTokens,
+            voucher_mint_info.key,
+            &[],
+        )?,
+        &[voucher_mint_info.clone(), token_program_info.clone()],
+        &[mint_seeds],
+    )?;
+
+    create_pda_account(
+        platform_authority_info,
+        voucher_info,
+        system_program_info,
+        program_id,
+        Voucher::LEN,
+        &[
+            VOUCHER_SEED,
+            pool_info.key.as_ref(),
+            &voucher_id.to_le_bytes(),
+            &[bump_seed],
+        ],
+    )?;
+    Voucher {
+        pool: *pool_info.key,
+        voucher_id,
+        mint: *voucher_mint_info.key,
+        amount,
+        issued_at: Clock::get()?.unix_timestamp,
+        redeemed_at: 0,
+        redeemed_by: Pubkey::default(),
+        bump_seed,
+        sequence: 0,
+    }
+    .save(voucher_info)?;
+
+    RewardPoolEvent::VoucherIssued {
+        pool: *pool_info.key,
+        voucher: *voucher_info.key,
+        voucher_id,
+        mint: *voucher_mint_info.key,
+        recipient: *recipient_info.key,
+        amount,
+    }
+    .emit();
+
+    msg!("Issued voucher {} for {} tokens", voucher_id, amount);
+    Ok(())
+}
+
+// Redeeming a reward voucher NFT
+pub(super) fn process_redeem_voucher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let holder_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let voucher_info = next_account_info(account_info_iter)?;
+    let voucher_mint_info = next_account_info(account_info_iter)?;
+    let holder_token_account_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !holder_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.is_paused {
+        return Err(RewardPoolError::PoolPaused.into());
+    }
+
+    if *token_program_info.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if voucher_info.owner != program_id {
+        return Err(RewardPoolError::InvalidVoucherAccount.into());
+    }
+    let mut voucher = Voucher::try_from_slice(&voucher_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidVoucherAccount)?;
+    let expected_voucher = Pubkey::create_program_address(
+        &[
+            VOUCHER_SEED,
+            pool_info.key.as_ref(),
+            &voucher.voucher_id.to_le_bytes(),
+            &[voucher.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidVoucherAccount)?;
+    if voucher.pool != *pool_info.key
+        || *voucher_info.key != expected_voucher
+        || *voucher_mint_info.key != voucher.mint
+    {
+        return Err(RewardPoolError::InvalidVoucherAccount.into());
+    }
+
+    if voucher.is_redeemed() {
+        return Err(RewardPoolError::VoucherAlreadyRedeemed.into());
+    }
+
+    if *holder_token_account_info.owner != spl_token::id() {
+        return Err(RewardPoolError::InvalidVoucherAccount.into());
+    }
+    let holder_token_account = TokenAccount::unpack(&holder_token_account_info.data.borrow())?;
+    if holder_token_account.mint != voucher.mint
+        || holder_token_account.owner != *holder_info.key
+        || holder_token_account.amount != 1
+    {
+        return Err(RewardPoolError::InvalidVoucherAccount.into());
+    }
+
+    let (expected_escrow, escrow_bump_seed) =
+        find_voucher_escrow_address_with_program_id(pool_info.key, program_id);
+    if *escrow_info.key != expected_escrow {
+        return Err(RewardPoolError::InvalidVoucherEscrowAccount.into());
+    }
+
+    // Burning the NFT proves the holder owned it; the emptied token
+    // account's rent goes back to them
+    invoke(
+        &token_instruction::burn(
+            token_program_info.key,
+            holder_token_account_info.key,
+            voucher_mint_info.key,
+            holder_info.key,
+            &[],
+            1,
+        )?,
+        &[
+            holder_token_account_info.clone(),
+            voucher_mint_info.clone(),
+            holder_info.clone(),
+            token_program_info.clone(),
+        ],
+    )?;
+    invoke(
+        &token_instruction::close_account(
+            token_program_info.key,
+            holder_token_account_info.key,
+            holder_info.key,
+            holder_info.key,
+            &[],
+        )?,
+        &[
+            holder_token_account_info.clone(),
+            holder_info.clone(),
+            token_program_info.clone(),
+        ],
+    )?;
+
+    invoke_signed(
+        &token_instruction::transfer(
+            token_program_info.key,
+            escrow_info.key,
+            destination_info.key,
+            escrow_info.key,
+            &[],
+            voucher.amount,
+        )?,
+        &[
+            escrow_info.clone(),
+            destination_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[
+            VOUCHER_ESCROW_SEED,
+            pool_info.key.as_ref(),
+            &[escrow_bump_seed],
+        ]],
+    )?;
+
+    voucher.redeemed_at = Clock::get()?.unix_timestamp;
+    voucher.redeemed_by = *holder_info.key;
+    voucher.save(voucher_info)?;
+
+    RewardPoolEvent::VoucherRedeemed {
+        pool: *pool_info.key,
+        voucher: *voucher_info.key,
+        holder: *holder_info.key,
+        destination: *destination_info.key,
+        amount: voucher.amount,
+    }
+    .emit();
+
+    msg!(
+        "Redeemed voucher {} for {} tokens",
+        voucher.voucher_id,
+        voucher.amount
+    );
+    Ok(())
+}
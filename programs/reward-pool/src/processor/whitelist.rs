@@ -0,0 +1,307 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token::state::Account as TokenAccount;
+
+use super::{parameters::load_parameters, treasury::load_pool, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_payout_address_address_with_program_id,
+        find_withdrawal_whitelist_address_with_program_id, PAYOUT_ADDRESS_SEED,
+        WITHDRAWAL_WHITELIST_SEED,
+    },
+    state::{PayoutAddress, Sequenced, WithdrawalWhitelist},
+};
+
+// Adding a withdrawal destination
+pub(super) fn process_add_withdrawal_destination(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    destination: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let whitelist_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let parameters_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    load_pool(program_id, pool_info)?;
+
+    let parameters = load_parameters(program_id, pool_info.key, parameters_info)?;
+
+    let (expected_whitelist, bump_seed) = find_withdrawal_whitelist_address_with_program_id(
+        pool_info.key,
+        farmer_info.key,
+        program_id,
+    );
+    if *whitelist_info.key != expected_whitelist {
+        return Err(RewardPoolError::InvalidWhitelistAccount.into());
+    }
+
+    let mut whitelist = if whitelist_info.data_is_empty() {
+        create_pda_account(
+            farmer_info,
+            whitelist_info,
+            system_program_info,
+            program_id,
+            WithdrawalWhitelist::LEN,
+            &[
+                WITHDRAWAL_WHITELIST_SEED,
+                pool_info.key.as_ref(),
+                farmer_info.key.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        WithdrawalWhitelist::new(*pool_info.key, *farmer_info.key, bump_seed)
+    } else {
+        load_whitelist(program_id, whitelist_info)?
+    };
+
+    let active_at = Clock::get()?
+        .unix_timestamp
+        .saturating_add(parameters.whitelist_activation_delay);
+    whitelist.add(destination, active_at)?;
+    whitelist.save(whitelist_info)?;
+
+    RewardPoolEvent::WithdrawalDestinationAdded {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        destination,
+        active_at,
+    }
+    .emit();
+
+    msg!(
+        "Withdrawal destination {} whitelisted from {}",
+        destination,
+        active_at
+    );
+    Ok(())
+}
+
+// Removing a withdrawal destination
+pub(super) fn process_remove_withdrawal_destination(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    destination: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let whitelist_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    load_pool(program_id, pool_info)?;
+
+    let (expected_whitelist, _) = find_withdrawal_whitelist_address_with_program_id(
+        pool_info.key,
+        farmer_info.key,
+        program_id,
+    );
+    if *whitelist_info.key != expected_whitelist || whitelist_info.data_is_empty() {
+        return Err(RewardPoolError::InvalidWhitelistAccount.into());
+    }
+
+    let mut whitelist = load_whitelist(program_id, whitelist_info)?;
+    whitelist.remove(&destination)?;
+    whitelist.save(whitelist_info)?;
+
+    RewardPoolEvent::WithdrawalDestinationRemoved {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        destination,
+    }
+    .emit();
+
+    msg!("Withdrawal destination {} removed", destination);
+    Ok(())
+}
+
+// Registering a payout address
+pub(super) fn process_register_payout_address(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    payout_address: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let farmer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let payout_address_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let _counters_info = next_account_info(account_info_iter)?;
+    let parameters_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !farmer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    load_pool(program_id, pool_info)?;
+
+    let parameters = load_parameters(program_id, pool_info.key, parameters_info)?;
+
+    let (expected_payout_address, bump_seed) =
+        find_payout_address_address_with_program_id(pool_info.key, farmer_info.key, program_id);
+    if *payout_address_info.key != expected_payout_address {
+        return Err(RewardPoolError::InvalidPayoutAddressAccount.into());
+    }
+
+    let mut registration = if payout_address_info.data_is_empty() {
+        create_pda_account(
+            farmer_info,
+            payout_address_info,
+            system_program_info,
+            program_id,
+            PayoutAddress::LEN,
+            &[
+                PAYOUT_ADDRESS_SEED,
+                pool_info.key.as_ref(),
+                farmer_info.key.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        PayoutAddress::new(*pool_info.key, *farmer_info.key, bump_seed)
+    } else {
+        load_payout_address(program_id, payout_address_info)?
+    };
+
+    let now = Clock::get()?.unix_timestamp;
+    registration.register(
+        payout_address,
+        now,
+        now.saturating_add(parameters.whitelist_activation_delay),
+    );
+    registration.save(payout_address_info)?;
+
+    let active_at = match registration.pending_active_at {
+        0 => now,
+        active_at => active_at,
+    };
+    RewardPoolEvent::PayoutAddressRegistered {
+        pool: *pool_info.key,
+        farmer: *farmer_info.key,
+        payout_address,
+        active_at,
+    }
+    .emit();
+
+    msg!(
+        "Payout address {} in effect from {}",
+        payout_address,
+        active_at
+    );
+    Ok(())
+}
+
+/// Fails if the farmer has a payout address in effect that does not own
+/// `destination_info`. Returns whether one is in effect.
+pub(super) fn check_payout_address(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    payout_address_info: &AccountInfo,
+    destination_info: &AccountInfo,
+) -> Result<bool, ProgramError> {
+    let Some(payout_address) =
+        effective_payout_address(program_id, pool, farmer, payout_address_info)?
+    else {
+        return Ok(false);
+    };
+
+    let destination = TokenAccount::unpack(&destination_info.data.borrow())?;
+    if destination.owner != payout_address {
+        return Err(RewardPoolError::DestinationNotPayoutAddress.into());
+    }
+    Ok(true)
+}
+
+/// Fails unless the farmer's whitelist, if it has any entry, allows
+/// withdrawing to `destination` now. Returns whether the destination is
+/// explicitly whitelisted.
+pub(super) fn check_withdrawal_destination(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    whitelist_info: &AccountInfo,
+    destination: &Pubkey,
+) -> Result<bool, ProgramError> {
+    let (expected_whitelist, _) =
+        find_withdrawal_whitelist_address_with_program_id(pool, farmer, program_id);
+    if *whitelist_info.key != expected_whitelist {
+        return Err(RewardPoolError::InvalidWhitelistAccount.into());
+    }
+    if whitelist_info.data_is_empty() {
+        return Ok(false);
+    }
+
+    let whitelist = load_whitelist(program_id, whitelist_info)?;
+    if !whitelist.allows(destination, Clock::get()?.unix_timestamp) {
+        return Err(RewardPoolError::DestinationNotWhitelisted.into());
+    }
+    Ok(whitelist.entry_count > 0)
+}
+
+/// Returns the payout address the farmer has in effect now, if any
+pub(super) fn effective_payout_address(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    payout_address_info: &AccountInfo,
+) -> Result<Option<Pubkey>, ProgramError> {
+    let (expected_payout_address, _) =
+        find_payout_address_address_with_program_id(pool, farmer, program_id);
+    if *payout_address_info.key != expected_payout_address {
+        return Err(RewardPoolError::InvalidPayoutAddressAccount.into());
+    }
+    if payout_address_info.data_is_empty() {
+        return Ok(None);
+    }
+
+    let registration = load_payout_address(program_id, payout_address_info)?;
+    Ok(registration.effective(Clock::get()?.unix_timestamp))
+}
+
+pub(super) fn load_whitelist(
+    program_id: &Pubkey,
+    whitelist_info: &AccountInfo,
+) -> Result<WithdrawalWhitelist, ProgramError> {
+    if whitelist_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(WithdrawalWhitelist::try_from_slice(
+        &whitelist_info.data.borrow(),
+    )?)
+}
+
+pub(super) fn load_payout_address(
+    program_id: &Pubkey,
+    payout_address_info: &AccountInfo,
+) -> Result<PayoutAddress, ProgramError> {
+    if payout_address_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(PayoutAddress::try_from_slice(
+        &payout_address_info.data.borrow(),
+    )?)
+}
@@ -0,0 +1,184 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use spl_token::instruction as token_instruction;
+
+use super::{treasury::load_pool, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{find_withdraw_burn_address_with_program_id, WITHDRAW_BURN_SEED},
+    state::{Sequenced, WithdrawBurn},
+    MAX_WITHDRAW_BURN_BPS,
+};
+
+// Setting the share of withdrawals burned
+pub(super) fn process_set_withdraw_burn(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    withdraw_burn_bps: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let withdraw_burn_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if withdraw_burn_bps > MAX_WITHDRAW_BURN_BPS {
+        return Err(RewardPoolError::InvalidWithdrawBurn.into());
+    }
+
+    let (expected_withdraw_burn, bump_seed) =
+        find_withdraw_burn_address_with_program_id(pool_info.key, program_id);
+    if *withdraw_burn_info.key != expected_withdraw_burn {
+        return Err(RewardPoolError::InvalidWithdrawBurnAccount.into());
+    }
+
+    let mut withdraw_burn = if withdraw_burn_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            withdraw_burn_info,
+            system_program_info,
+            program_id,
+            WithdrawBurn::LEN,
+            &[WITHDRAW_BURN_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        WithdrawBurn {
+            pool: *pool_info.key,
+            withdraw_burn_bps: 0,
+            total_burned: 0,
+            burn_count: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_withdraw_burn(program_id, pool_info.key, withdraw_burn_info)?
+    };
+
+    withdraw_burn.withdraw_burn_bps = withdraw_burn_bps;
+    withdraw_burn.save(withdraw_burn_info)?;
+
+    RewardPoolEvent::WithdrawBurnSet {
+        pool: *pool_info.key,
+        withdraw_burn_bps,
+    }
+    .emit();
+
+    msg!("Withdrawals burn {} bps", withdraw_burn_bps);
+    Ok(())
+}
+
+/// Returns the withdraw burn configuration of the pool, `None` when the pool
+/// has none
+pub(super) fn read_withdraw_burn(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    withdraw_burn_info: &AccountInfo,
+) -> Result<Option<WithdrawBurn>, ProgramError> {
+    let (expected_withdraw_burn, _) = find_withdraw_burn_address_with_program_id(pool, program_id);
+    if *withdraw_burn_info.key != expected_withdraw_burn {
+        return Err(RewardPoolError::InvalidWithdrawBurnAccount.into());
+    }
+    if withdraw_burn_info.data_is_empty() {
+        return Ok(None);
+    }
+    load_withdraw_burn(program_id, pool, withdraw_burn_info).map(Some)
+}
+
+/// Burns `amount` of a withdrawal from the treasury vault, which the
+/// program signs for with `vault_seeds`, and adds it to the pool's burned
+/// total
+#[allow(clippy::too_many_arguments)]
+pub(super) fn burn_withdrawal<'a>(
+    pool: &Pubkey,
+    farmer: &Pubkey,
+    withdraw_burn: &mut WithdrawBurn,
+    withdraw_burn_info: &AccountInfo<'a>,
+    vault_info: &AccountInfo<'a>,
+    reward_mint_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    vault_seeds: &[&[u8]],
+    amount: u64,
+    nonce: u64,
+) -> ProgramResult {
+    if *token_program_info.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    invoke_signed(
+        &token_instruction::burn(
+            token_program_info.key,
+            vault_info.key,
+            reward_mint_info.key,
+            vault_info.key,
+            &[],
+            amount,
+        )?,
+        &[
+            vault_info.clone(),
+            reward_mint_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[vault_seeds],
+    )?;
+
+    withdraw_burn.total_burned = withdraw_burn
+        .total_burned
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    withdraw_burn.burn_count = withdraw_burn
+        .burn_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    withdraw_burn.save(withdraw_burn_info)?;
+
+    RewardPoolEvent::WithdrawalBurned {
+        pool: *pool,
+        farmer: *farmer,
+        amount,
+        nonce,
+    }
+    .emit();
+    Ok(())
+}
+
+fn load_withdraw_burn(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    withdraw_burn_info: &AccountInfo,
+) -> Result<WithdrawBurn, ProgramError> {
+    if withdraw_burn_info.owner != program_id {
+        return Err(RewardPoolError::InvalidWithdrawBurnAccount.into());
+    }
+    let withdraw_burn = WithdrawBurn::try_from_slice(&withdraw_burn_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidWithdrawBurnAccount)?;
+    let expected_withdraw_burn = Pubkey::create_program_address(
+        &[
+            WITHDRAW_BURN_SEED,
+            pool.as_ref(),
+            &[withdraw_burn.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidWithdrawBurnAccount)?;
+    if withdraw_burn.pool != *pool || *withdraw_burn_info.key != expected_withdraw_burn {
+        return Err(RewardPoolError::InvalidWithdrawBurnAccount.into());
+    }
+    Ok(withdraw_burn)
+}
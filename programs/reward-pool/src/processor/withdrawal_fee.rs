@@ -0,0 +1,266 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::instruction as system_instruction;
+
+use super::{treasury::load_pool, utils::create_pda_account};
+use crate::{
+    error::RewardPoolError,
+    events::RewardPoolEvent,
+    seeds::{
+        find_fee_vault_address_with_program_id, find_withdrawal_fee_address_with_program_id,
+        FEE_VAULT_SEED, WITHDRAWAL_FEE_SEED,
+    },
+    state::{Sequenced, WithdrawalFee},
+    MAX_WITHDRAWAL_FEE_LAMPORTS,
+};
+
+// Setting the lamport fee of withdrawals
+pub(super) fn process_set_withdrawal_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee_lamports: u64,
+    exempt_small_claims: bool,
+    small_claim_threshold: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let withdrawal_fee_info = next_account_info(account_info_iter)?;
+    let fee_vault_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    if fee_lamports > MAX_WITHDRAWAL_FEE_LAMPORTS {
+        return Err(RewardPoolError::InvalidWithdrawalFee.into());
+    }
+
+    let (expected_withdrawal_fee, bump_seed) =
+        find_withdrawal_fee_address_with_program_id(pool_info.key, program_id);
+    if *withdrawal_fee_info.key != expected_withdrawal_fee {
+        return Err(RewardPoolError::InvalidWithdrawalFeeAccount.into());
+    }
+
+    let (expected_fee_vault, vault_bump_seed) =
+        find_fee_vault_address_with_program_id(pool_info.key, program_id);
+    if *fee_vault_info.key != expected_fee_vault {
+        return Err(RewardPoolError::InvalidFeeVaultAccount.into());
+    }
+
+    let mut withdrawal_fee = if withdrawal_fee_info.data_is_empty() {
+        create_pda_account(
+            platform_authority_info,
+            withdrawal_fee_info,
+            system_program_info,
+            program_id,
+            WithdrawalFee::LEN,
+            &[WITHDRAWAL_FEE_SEED, pool_info.key.as_ref(), &[bump_seed]],
+        )?;
+        WithdrawalFee {
+            pool: *pool_info.key,
+            fee_lamports: 0,
+            exempt_small_claims: false,
+            small_claim_threshold: 0,
+            total_collected: 0,
+            fee_count: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    } else {
+        load_withdrawal_fee(program_id, pool_info.key, withdrawal_fee_info)?
+    };
+
+    // The vault holds no data, so it only needs to exist to take fees
+    // smaller than its rent
+    if fee_vault_info.owner != program_id {
+        create_pda_account(
+            platform_authority_info,
+            fee_vault_info,
+            system_program_info,
+            program_id,
+            0,
+            &[FEE_VAULT_SEED, pool_info.key.as_ref(), &[vault_bump_seed]],
+        )?;
+    }
+
+    withdrawal_fee.fee_lamports = fee_lamports;
+    withdrawal_fee.exempt_small_claims = exempt_small_claims;
+    withdrawal_fee.small_claim_threshold = small_claim_threshold;
+    withdrawal_fee.save(withdrawal_fee_info)?;
+
+    RewardPoolEvent::WithdrawalFeeSet {
+        pool: *pool_info.key,
+        fee_lamports,
+        exempt_small_claims,
+        small_claim_threshold,
+    }
+    .emit();
+
+    msg!("Withdrawals pay {} lamports", fee_lamports);
+    Ok(())
+}
+
+// Collecting the lamport fees of withdrawals
+pub(super) fn process_collect_withdrawal_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let platform_authority_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let fee_vault_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+
+    // Validations
+    if !platform_authority_info.is_signer {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let pool_data = load_pool(program_id, pool_info)?;
+    if pool_data.platform_authority != *platform_authority_info.key {
+        return Err(RewardPoolError::InvalidAuthority.into());
+    }
+
+    let (expected_fee_vault, _) = find_fee_vault_address_with_program_id(pool_info.key, program_id);
+    if *fee_vault_info.key != expected_fee_vault || fee_vault_info.owner != program_id {
+        return Err(RewardPoolError::InvalidFeeVaultAccount.into());
+    }
+
+    // The vault keeps its rent so it can go on taking fees
+    let lamports = fee_vault_info
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(fee_vault_info.data_len()));
+    if lamports == 0 {
+        return Err(RewardPoolError::NoWithdrawalFees.into());
+    }
+
+    **fee_vault_info.try_borrow_mut_lamports()? -= lamports;
+    **destination_info.try_borrow_mut_lamports()? = destination_info
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    RewardPoolEvent::WithdrawalFeesCollected {
+        pool: *pool_info.key,
+        destination: *destination_info.key,
+        lamports,
+    }
+    .emit();
+
+    msg!(
+        "Collected {} lamports of withdrawal fees to {}",
+        lamports,
+        destination_info.key
+    );
+    Ok(())
+}
+
+/// Returns the withdrawal fee configuration of the pool, `None` when the
+/// pool has none
+pub(super) fn read_withdrawal_fee(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    withdrawal_fee_info: &AccountInfo,
+) -> Result<Option<WithdrawalFee>, ProgramError> {
+    let (expected_withdrawal_fee, _) =
+        find_withdrawal_fee_address_with_program_id(pool, program_id);
+    if *withdrawal_fee_info.key != expected_withdrawal_fee {
+        return Err(RewardPoolError::InvalidWithdrawalFeeAccount.into());
+    }
+    if withdrawal_fee_info.data_is_empty() {
+        return Ok(None);
+    }
+    load_withdrawal_fee(program_id, pool, withdrawal_fee_info).map(Some)
+}
+
+/// Transfers `lamports` of a withdrawal's fee from `payer_info` to the
+/// pool's fee vault and adds them to the pool's collected total
+#[allow(clippy::too_many_arguments)]
+pub(super) fn charge_withdrawal_fee<'a>(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    withdrawal_fee: &mut WithdrawalFee,
+    withdrawal_fee_info: &AccountInfo<'a>,
+    fee_vault_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    farmer: &Pubkey,
+    system_program_info: &AccountInfo<'a>,
+    lamports: u64,
+    nonce: u64,
+) -> ProgramResult {
+    let (expected_fee_vault, _) = find_fee_vault_address_with_program_id(pool, program_id);
+    if *fee_vault_info.key != expected_fee_vault {
+        return Err(RewardPoolError::InvalidFeeVaultAccount.into());
+    }
+
+    invoke(
+        &system_instruction::transfer(payer_info.key, fee_vault_info.key, lamports),
+        &[
+            payer_info.clone(),
+            fee_vault_info.clone(),
+            system_program_info.clone(),
+        ],
+    )?;
+
+    withdrawal_fee.total_collected = withdrawal_fee
+        .total_collected
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    withdrawal_fee.fee_count = withdrawal_fee
+        .fee_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    withdrawal_fee.save(withdrawal_fee_info)?;
+
+    RewardPoolEvent::WithdrawalFeeCharged {
+        pool: *pool,
+        farmer: *farmer,
+        payer: *payer_info.key,
+        lamports,
+        nonce,
+    }
+    .emit();
+    Ok(())
+}
+
+fn load_withdrawal_fee(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    withdrawal_fee_info: &AccountInfo,
+) -> Result<WithdrawalFee, ProgramError> {
+    if withdrawal_fee_info.owner != program_id {
+        return Err(RewardPoolError::InvalidWithdrawalFeeAccount.into());
+    }
+    let withdrawal_fee = WithdrawalFee::try_from_slice(&withdrawal_fee_info.data.borrow())
+        .map_err(|_| RewardPoolError::InvalidWithdrawalFeeAccount)?;
+    let expected_withdrawal_fee = Pubkey::create_program_address(
+        &[
+            WITHDRAWAL_FEE_SEED,
+            pool.as_ref(),
+            &[withdrawal_fee.bump_seed],
+        ],
+        program_id,
+    )
+    .map_err(|_| RewardPoolError::InvalidWithdrawalFeeAccount)?;
+    if withdrawal_fee.pool != *pool || *withdrawal_fee_info.key != expected_withdrawal_fee {
+        return Err(RewardPoolError::InvalidWithdrawalFeeAccount.into());
+    }
+    Ok(withdrawal_fee)
+}
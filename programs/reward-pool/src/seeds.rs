@@ -0,0 +1,6 @@
+// PDA seeds
+//
+// Seeds and derivation helpers live in the `reward-pool-interface` crate so
+// indexers and other consumers can derive addresses without depending on
+// the program itself.
+pub use reward_pool_interface::seeds::*;
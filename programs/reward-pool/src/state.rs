@@ -0,0 +1,3262 @@
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, hash::hashv, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    debug::debug_log,
+    error::RewardPoolError,
+    seeds::{FACTORY_POOL_SEED, POOL_SEED},
+    BASIS_POINTS, MAX_PLATFORM_FEE_PERCENTAGE, MINIMUM_WITHDRAWAL_AMOUNT, SECONDS_PER_DAY,
+    SECONDS_PER_HOUR, SECONDS_PER_YEAR, TREASURY_EPOCH, WHITELIST_ACTIVATION_DELAY,
+};
+
+// Accounts carrying a mutation counter
+//
+// Every write of a sequenced account goes through `save`, which increments
+// `sequence`. Consumers of account updates (Geyser plugins, websocket
+// subscribers) order updates by it and drop the stale ones they receive
+// when slots fork.
+pub trait Sequenced: BorshSerialize + fmt::Debug {
+    fn sequence(&self) -> u64;
+
+    fn sequence_mut(&mut self) -> &mut u64;
+
+    /// Increments the sequence number and writes the account back
+    fn save(&mut self, account: &AccountInfo) -> ProgramResult {
+        let sequence = self.sequence_mut();
+        *sequence = sequence
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.serialize(&mut &mut account.data.borrow_mut()[..])?;
+        debug_log!("Saved {}: {:?}", account.key, self);
+        Ok(())
+    }
+}
+
+// Reward pool structure
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct RewardPool {
+    pub platform_authority: Pubkey,
+    pub reward_mint: Pubkey,
+    pub platform_treasury: Pubkey,
+    pub platform_fee_percentage: u8,
+    pub total_rewards_distributed: u64,
+    pub total_platform_fees_collected: u64,
+    pub is_paused: bool,
+    /// Bump of the pool's address
+    pub bump_seed: u8,
+    /// Seeds the pool's address is derived from
+    pub address_kind: PoolAddressKind,
+    /// Creator of a factory pool, the default key for other pools
+    pub address_creator: Pubkey,
+    /// Platform id of a namespaced pool or seed of a factory pool, 0 for
+    /// pools at their mint's address
+    pub address_seed: u64,
+    /// Tokens of pending rewards the treasury vault holds for farmers until
+    /// they are paid out or given up
+    pub outstanding_rewards: u64,
+    /// Unix timestamp of the latest instruction on the pool signed by its
+    /// platform authority, 0 until the first one
+    pub last_authority_action_at: i64,
+    pub sequence: u64,
+}
+
+impl RewardPool {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 32 + 1 + 8 + 8 + 1 + 1 + 1 + 32 + 8 + 8 + 8 + 8;
+
+    /// Address the pool's stored seeds and bump derive
+    pub fn address(&self, program_id: &Pubkey) -> Result<Pubkey, ProgramError> {
+        let bump = [self.bump_seed];
+        let address_seed = self.address_seed.to_le_bytes();
+        let seeds: &[&[u8]] = match self.address_kind {
+            PoolAddressKind::Mint => &[POOL_SEED, self.reward_mint.as_ref(), &bump],
+            PoolAddressKind::Platform => {
+                &[POOL_SEED, &address_seed, self.reward_mint.as_ref(), &bump]
+            }
+            PoolAddressKind::Factory => &[
+                FACTORY_POOL_SEED,
+                self.address_creator.as_ref(),
+                self.reward_mint.as_ref(),
+                &address_seed,
+                &bump,
+            ],
+        };
+        Pubkey::create_program_address(seeds, program_id)
+            .map_err(|_| RewardPoolError::InvalidPoolAccount.into())
+    }
+
+    /// Whether the pool lives in a platform namespace
+    pub fn is_namespaced(&self) -> bool {
+        self.address_kind == PoolAddressKind::Platform
+    }
+}
+
+// Seeds a pool's address is derived from
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolAddressKind {
+    /// `[POOL_SEED, reward_mint]`, from `InitializePool`
+    Mint,
+    /// `[POOL_SEED, platform_id, reward_mint]`, from `InitializePlatformPool`
+    Platform,
+    /// `[FACTORY_POOL_SEED, creator, reward_mint, seed]`, from `CreatePool`
+    Factory,
+}
+
+impl Sequenced for RewardPool {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Per-day pool totals, one account per unix day
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct DailyStats {
+    pub pool: Pubkey,
+    pub day: i64,
+    pub total_distributed: u64,
+    pub total_platform_fees: u64,
+    pub reward_count: u32,
+    pub withdrawal_count: u32,
+    pub total_withdrawn: u64,
+    /// Linear-counting estimate of the distinct farmers rewarded that day
+    pub unique_farmers_estimate: u32,
+    pub farmer_bitmap: [u8; DailyStats::BITMAP_BYTES],
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl DailyStats {
+    /// Size of the distinct farmer sketch
+    pub const BITMAP_BYTES: usize = 64;
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 4 + 4 + 8 + 4 + Self::BITMAP_BYTES + 1 + 8;
+
+    pub fn new(pool: Pubkey, day: i64, bump_seed: u8) -> Self {
+        Self {
+            pool,
+            day,
+            total_distributed: 0,
+            total_platform_fees: 0,
+            reward_count: 0,
+            withdrawal_count: 0,
+            total_withdrawn: 0,
+            unique_farmers_estimate: 0,
+            farmer_bitmap: [0; Self::BITMAP_BYTES],
+            bump_seed,
+            sequence: 0,
+        }
+    }
+
+    /// Unix day containing a timestamp
+    pub fn day_of(unix_timestamp: i64) -> i64 {
+        unix_timestamp.div_euclid(SECONDS_PER_DAY)
+    }
+
+    /// Adds a farmer to the distinct farmer sketch and refreshes the estimate.
+    /// Pubkeys are uniformly distributed, so their leading bytes are used as
+    /// the hash directly.
+    pub fn observe_farmer(&mut self, farmer: &Pubkey) {
+        let bits = Self::BITMAP_BYTES * 8;
+        let key = farmer.to_bytes();
+        let bit = u16::from_le_bytes([key[0], key[1]]) as usize % bits;
+        let mask = 1u8 << (bit % 8);
+        if self.farmer_bitmap[bit / 8] & mask != 0 {
+            return;
+        }
+        self.farmer_bitmap[bit / 8] |= mask;
+
+        let set: u32 = self
+            .farmer_bitmap
+            .iter()
+            .map(|byte| byte.count_ones())
+            .sum();
+        let zeros = (bits as u32 - set).max(1) as f64;
+        self.unique_farmers_estimate = (bits as f64 * (bits as f64 / zeros).ln()).round() as u32;
+    }
+}
+
+impl Sequenced for DailyStats {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Lifetime execution count of every instruction of a pool, indexed by V2
+// instruction variant. Only the program writes it, so the counts can be
+// trusted as usage metrics.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct InstructionCounters {
+    pub pool: Pubkey,
+    pub counts: [u64; InstructionCounters::MAX_INSTRUCTIONS],
+    pub bump_seed: u8,
+    pub sequence: u64,
+    /// Unix timestamp of the latest counted instruction signed by the
+    /// platform authority before the pool's `last_authority_action_at`
+    /// took over, 0 for pools created since
+    pub last_authority_action_at: i64,
+}
+
+impl InstructionCounters {
+    /// Number of instruction slots, leaving room for future instructions
+    /// while `PoolStats` still fits in return data
+    pub const MAX_INSTRUCTIONS: usize = 96;
+    /// Serialized size of the account
+    pub const LEN: usize = Self::len_with(Self::MAX_INSTRUCTIONS) + 8;
+    /// Slots of accounts created before each time the counters grew, which
+    /// also lack `last_authority_action_at`
+    pub const LEGACY_MAX_INSTRUCTIONS: [usize; 3] = [32, 64, 96];
+
+    pub fn new(pool: Pubkey, bump_seed: u8) -> Self {
+        Self {
+            pool,
+            counts: [0; Self::MAX_INSTRUCTIONS],
+            bump_seed,
+            sequence: 0,
+            last_authority_action_at: 0,
+        }
+    }
+
+    /// Serialized size of a legacy account with `slots` counts
+    pub const fn len_with(slots: usize) -> usize {
+        32 + 8 * slots + 1 + 8
+    }
+
+    /// Whether `len` is the size of an account created before the counters
+    /// grew
+    pub fn is_legacy_len(len: usize) -> bool {
+        Self::LEGACY_MAX_INSTRUCTIONS
+            .iter()
+            .any(|slots| len == Self::len_with(*slots))
+    }
+
+    /// Decodes the account, zero-filling the slots and time missing from
+    /// accounts created before the counters grew
+    pub fn unpack(data: &[u8]) -> borsh::io::Result<Self> {
+        if !Self::is_legacy_len(data.len()) {
+            return Self::try_from_slice(data);
+        }
+
+        let slots = (data.len() - Self::len_with(0)) / 8;
+        let reader = &mut &data[..];
+        let pool = Pubkey::deserialize(reader)?;
+        let mut counts = [0; Self::MAX_INSTRUCTIONS];
+        for count in &mut counts[..slots] {
+            *count = u64::deserialize(reader)?;
+        }
+        Ok(Self {
+            pool,
+            counts,
+            bump_seed: u8::deserialize(reader)?,
+            sequence: u64::deserialize(reader)?,
+            last_authority_action_at: 0,
+        })
+    }
+}
+
+impl Sequenced for InstructionCounters {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Destinations a farmer locked withdrawals to. While any entry is
+// registered, `WithdrawReward` only pays out to entries that are active.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct WithdrawalWhitelist {
+    pub pool: Pubkey,
+    pub farmer: Pubkey,
+    pub entry_count: u8,
+    pub entries: [WhitelistEntry; WithdrawalWhitelist::MAX_ENTRIES],
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, Default)]
+pub struct WhitelistEntry {
+    /// Destination token account
+    pub destination: Pubkey,
+    /// Unix timestamp from which withdrawals to it are allowed
+    pub active_at: i64,
+}
+
+impl WithdrawalWhitelist {
+    /// Destinations a farmer can register
+    pub const MAX_ENTRIES: usize = 8;
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 1 + (32 + 8) * Self::MAX_ENTRIES + 1 + 8;
+
+    pub fn new(pool: Pubkey, farmer: Pubkey, bump_seed: u8) -> Self {
+        Self {
+            pool,
+            farmer,
+            entry_count: 0,
+            entries: [WhitelistEntry::default(); Self::MAX_ENTRIES],
+            bump_seed,
+            sequence: 0,
+        }
+    }
+
+    /// Registered entries, active or not
+    pub fn entries(&self) -> &[WhitelistEntry] {
+        &self.entries[..(self.entry_count as usize).min(Self::MAX_ENTRIES)]
+    }
+
+    fn position(&self, destination: &Pubkey) -> Option<usize> {
+        self.entries()
+            .iter()
+            .position(|entry| entry.destination == *destination)
+    }
+
+    /// Whether withdrawals to `destination` are allowed at `now`. An empty
+    /// whitelist allows any destination.
+    pub fn allows(&self, destination: &Pubkey, now: i64) -> bool {
+        self.entry_count == 0
+            || self
+                .position(destination)
+                .is_some_and(|index| self.entries[index].active_at <= now)
+    }
+
+    /// Registers a destination that becomes usable at `active_at`
+    pub fn add(&mut self, destination: Pubkey, active_at: i64) -> Result<(), RewardPoolError> {
+        if self.position(&destination).is_some() {
+            return Err(RewardPoolError::DestinationAlreadyWhitelisted);
+        }
+        let entry = self
+            .entries
+            .get_mut(self.entry_count as usize)
+            .ok_or(RewardPoolError::WhitelistFull)?;
+        *entry = WhitelistEntry {
+            destination,
+            active_at,
+        };
+        self.entry_count += 1;
+        Ok(())
+    }
+
+    /// Unregisters a destination, effective immediately
+    pub fn remove(&mut self, destination: &Pubkey) -> Result<(), RewardPoolError> {
+        let index = self
+            .position(destination)
+            .ok_or(RewardPoolError::DestinationNotWhitelisted)?;
+        let count = self.entry_count as usize;
+        self.entries.copy_within(index + 1..count, index);
+        self.entries[count - 1] = WhitelistEntry::default();
+        self.entry_count -= 1;
+        Ok(())
+    }
+}
+
+impl Sequenced for WithdrawalWhitelist {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Wallet a farmer's withdrawals are locked to. A newly registered address
+// only replaces the current one at `pending_active_at`, and registering the
+// current address again cancels the pending change, so a stolen signing key
+// cannot redirect withdrawals before the farmer notices.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct PayoutAddress {
+    pub pool: Pubkey,
+    pub farmer: Pubkey,
+    /// Owner every withdrawal destination must have, `Pubkey::default()`
+    /// while none is in effect
+    pub payout_address: Pubkey,
+    /// Address replacing `payout_address` at `pending_active_at`
+    pub pending_payout_address: Pubkey,
+    /// 0 when no change is pending
+    pub pending_active_at: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl PayoutAddress {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 32 + 32 + 8 + 1 + 8;
+
+    pub fn new(pool: Pubkey, farmer: Pubkey, bump_seed: u8) -> Self {
+        Self {
+            pool,
+            farmer,
+            payout_address: Pubkey::default(),
+            pending_payout_address: Pubkey::default(),
+            pending_active_at: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    }
+
+    /// Address in effect at `now`, if any
+    pub fn effective(&self, now: i64) -> Option<Pubkey> {
+        let address = if self.pending_active_at != 0 && self.pending_active_at <= now {
+            self.pending_payout_address
+        } else {
+            self.payout_address
+        };
+        (address != Pubkey::default()).then_some(address)
+    }
+
+    /// Schedules `address` (`Pubkey::default()` to unregister) to take
+    /// effect at `active_at`. Registering the address in effect cancels any
+    /// pending change instead.
+    pub fn register(&mut self, address: Pubkey, now: i64, active_at: i64) {
+        self.payout_address = self.effective(now).unwrap_or_default();
+        if address == self.payout_address {
+            self.pending_payout_address = Pubkey::default();
+            self.pending_active_at = 0;
+        } else {
+            self.pending_payout_address = address;
+            self.pending_active_at = active_at;
+        }
+    }
+}
+
+impl Sequenced for PayoutAddress {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Co-signers and limits governing withdrawals from the pool's treasury
+// vault. Configured once; once the withdrawals of a `TREASURY_EPOCH` add up
+// to more than `approval_threshold`, they need `required_approvals`
+// approvals and wait `execution_delay` seconds.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct TreasuryGovernance {
+    pub pool: Pubkey,
+    pub approver_count: u8,
+    pub approvers: [Pubkey; TreasuryGovernance::MAX_APPROVERS],
+    pub required_approvals: u8,
+    pub approval_threshold: u64,
+    pub execution_delay: i64,
+    /// Id of the next proposal
+    pub proposal_count: u64,
+    pub vault_bump_seed: u8,
+    pub bump_seed: u8,
+    /// Start of the epoch of the last executed withdrawal
+    pub epoch_started_at: i64,
+    /// Tokens withdrawn in that epoch
+    pub epoch_withdrawn: u64,
+    pub sequence: u64,
+}
+
+impl TreasuryGovernance {
+    /// Co-signers a pool can register
+    pub const MAX_APPROVERS: usize = 5;
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 1 + 32 * Self::MAX_APPROVERS + 1 + 8 + 8 + 8 + 1 + 1 + 8 + 8 + 8;
+
+    /// Registered co-signers
+    pub fn approvers(&self) -> &[Pubkey] {
+        &self.approvers[..(self.approver_count as usize).min(Self::MAX_APPROVERS)]
+    }
+
+    /// Index of `approver` among the co-signers
+    pub fn approver_index(&self, approver: &Pubkey) -> Option<usize> {
+        self.approvers()
+            .iter()
+            .position(|candidate| candidate == approver)
+    }
+
+    /// Whether recording a reward of `amount` needs approvals
+    pub fn requires_approval(&self, amount: u64) -> bool {
+        amount > self.approval_threshold
+    }
+
+    /// Tokens already withdrawn in the epoch running at `now`
+    pub fn withdrawn_in_epoch(&self, now: i64) -> u64 {
+        if now < self.epoch_started_at.saturating_add(TREASURY_EPOCH) {
+            self.epoch_withdrawn
+        } else {
+            0
+        }
+    }
+
+    /// Whether withdrawing `amount` at `now` needs approvals and the delay,
+    /// counting what the epoch already withdrew so that splitting a
+    /// withdrawal does not avoid them
+    pub fn withdrawal_requires_approval(&self, amount: u64, now: i64) -> bool {
+        self.requires_approval(self.withdrawn_in_epoch(now).saturating_add(amount))
+    }
+
+    /// Counts `amount` withdrawn at `now`, in a new epoch when the last one
+    /// has ended
+    pub fn record_withdrawal(&mut self, amount: u64, now: i64) {
+        let withdrawn = self.withdrawn_in_epoch(now);
+        if withdrawn == 0 {
+            self.epoch_started_at = now;
+        }
+        self.epoch_withdrawn = withdrawn.saturating_add(amount);
+    }
+}
+
+impl Sequenced for TreasuryGovernance {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Pending withdrawal from the treasury vault. Approvals are a bitmap over
+// the governance's approver indices.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct TreasuryProposal {
+    pub pool: Pubkey,
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub approvals: u8,
+    pub created_at: i64,
+    pub executable_at: i64,
+    pub executed: bool,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl TreasuryProposal {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 8 + 32 + 32 + 8 + 1 + 8 + 8 + 1 + 1 + 8;
+
+    /// Number of distinct approvers that approved
+    pub fn approval_count(&self) -> u8 {
+        self.approvals.count_ones() as u8
+    }
+}
+
+impl Sequenced for TreasuryProposal {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Tunable limits of a pool, set through its governance. Until they are
+// set the account does not exist and handlers use the defaults.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct PoolParameters {
+    pub pool: Pubkey,
+    pub values: ParameterValues,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParameterValues {
+    /// Smallest amount `WithdrawReward` accepts
+    pub minimum_withdrawal_amount: u64,
+    /// Seconds before a whitelisted destination becomes usable
+    pub whitelist_activation_delay: i64,
+    /// Highest fee `UpdatePlatformFee` accepts
+    pub max_platform_fee_percentage: u8,
+}
+
+impl PoolParameters {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 8 + 8 + 1 + 1 + 8;
+}
+
+impl Default for ParameterValues {
+    fn default() -> Self {
+        Self {
+            minimum_withdrawal_amount: MINIMUM_WITHDRAWAL_AMOUNT,
+            whitelist_activation_delay: WHITELIST_ACTIVATION_DELAY,
+            max_platform_fee_percentage: MAX_PLATFORM_FEE_PERCENTAGE,
+        }
+    }
+}
+
+impl ParameterValues {
+    /// Whether the values are within the bounds the program can enforce
+    pub fn is_valid(&self) -> bool {
+        self.minimum_withdrawal_amount > 0
+            && self.whitelist_activation_delay >= 0
+            && self.max_platform_fee_percentage <= MAX_PLATFORM_FEE_PERCENTAGE
+    }
+}
+
+impl ParameterValues {
+    /// Whether `parameter` differs between `self` and `other`
+    pub fn differs(&self, other: &Self, parameter: Parameter) -> bool {
+        match parameter {
+            Parameter::MinimumWithdrawalAmount => {
+                self.minimum_withdrawal_amount != other.minimum_withdrawal_amount
+            }
+            Parameter::WhitelistActivationDelay => {
+                self.whitelist_activation_delay != other.whitelist_activation_delay
+            }
+            Parameter::MaxPlatformFeePercentage => {
+                self.max_platform_fee_percentage != other.max_platform_fee_percentage
+            }
+        }
+    }
+
+    /// Copies the value of `parameter` from `other`
+    pub fn copy_parameter(&mut self, other: &Self, parameter: Parameter) {
+        match parameter {
+            Parameter::MinimumWithdrawalAmount => {
+                self.minimum_withdrawal_amount = other.minimum_withdrawal_amount
+            }
+            Parameter::WhitelistActivationDelay => {
+                self.whitelist_activation_delay = other.whitelist_activation_delay
+            }
+            Parameter::MaxPlatformFeePercentage => {
+                self.max_platform_fee_percentage = other.max_platform_fee_percentage
+            }
+        }
+    }
+}
+
+// Individually governable field of `ParameterValues`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parameter {
+    MinimumWithdrawalAmount,
+    WhitelistActivationDelay,
+    MaxPlatformFeePercentage,
+}
+
+impl Parameter {
+    pub const ALL: [Parameter; 3] = [
+        Parameter::MinimumWithdrawalAmount,
+        Parameter::WhitelistActivationDelay,
+        Parameter::MaxPlatformFeePercentage,
+    ];
+}
+
+// Value each parameter had before its last change, and when that change
+// happened (0 once rolled back or if it never changed)
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct ParameterHistory {
+    pub pool: Pubkey,
+    pub previous_values: ParameterValues,
+    pub changed_at: [i64; Parameter::ALL.len()],
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl ParameterHistory {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 17 + 8 * Parameter::ALL.len() + 1 + 8;
+
+    /// Time of the last change of `parameter` that can still be rolled back
+    pub fn changed_at(&self, parameter: Parameter) -> Option<i64> {
+        Some(self.changed_at[parameter as usize]).filter(|&changed_at| changed_at != 0)
+    }
+}
+
+impl Sequenced for ParameterHistory {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+impl Sequenced for PoolParameters {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Human-readable label and off-chain metadata location of a pool, for
+// explorers and UIs. The account is sized to its current contents.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct PoolMetadata {
+    pub pool: Pubkey,
+    pub name: String,
+    pub uri: String,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl PoolMetadata {
+    /// Longest name, in bytes
+    pub const MAX_NAME_LEN: usize = 32;
+    /// Longest URI, in bytes
+    pub const MAX_URI_LEN: usize = 200;
+}
+
+impl Sequenced for PoolMetadata {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Commitment to the farmer balances of a pool frozen for migration to a
+// new program deployment. The pool is handed over to this account, so no
+// key can resume or administer it anymore.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct StateExport {
+    pub pool: Pubkey,
+    pub reward_mint: Pubkey,
+    /// Platform authority at the time of the export
+    pub exported_by: Pubkey,
+    /// Root of the merkle tree over `(farmer, amount)` balances, see
+    /// `crate::merkle`
+    pub merkle_root: [u8; 32],
+    pub farmer_count: u32,
+    pub total_amount: u64,
+    pub exported_at: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl StateExport {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 32 + 32 + 4 + 8 + 8 + 1 + 8;
+}
+
+impl Sequenced for StateExport {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Farmer balance carried over from an exported pool of a previous
+// deployment. Its existence prevents importing the same balance twice.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct ImportedBalance {
+    pub pool: Pubkey,
+    pub farmer: Pubkey,
+    /// Exported pool the balance was proven against
+    pub source_pool: Pubkey,
+    pub amount: u64,
+    pub imported_at: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl ImportedBalance {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 1 + 8;
+    /// Task id of the pending reward booking the balance
+    pub const TASK_ID: &'static str = "import";
+}
+
+impl Sequenced for ImportedBalance {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Archival record of a finished pool. The pool is handed over to this
+// account, so rewards and admin changes stop for good while farmers keep
+// withdrawing until `claim_deadline`, after which `archived_by` can close it.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct PoolArchive {
+    pub pool: Pubkey,
+    /// Platform authority at the time of archival, the only key allowed to
+    /// close the pool
+    pub archived_by: Pubkey,
+    pub archived_at: i64,
+    pub claim_deadline: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl PoolArchive {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 1 + 8;
+}
+
+impl Sequenced for PoolArchive {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Token account of the pool's reward mint that farmers can donate pending
+// rewards to, chosen by the platform authority
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct PoolCharity {
+    pub pool: Pubkey,
+    pub charity: Pubkey,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl PoolCharity {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 1 + 8;
+}
+
+impl Sequenced for PoolCharity {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Whether farmers of the pool may reassign their pending rewards with
+// `TransferPending`. Transfers are allowed until the platform authority
+// creates this account.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct PendingTransferPolicy {
+    pub pool: Pubkey,
+    pub transfers_disabled: bool,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl PendingTransferPolicy {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 1 + 1 + 8;
+}
+
+impl Sequenced for PendingTransferPolicy {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Third party whose signature `RecordReward` requires on top of the
+// platform authority's, attesting each rewarded task. Only the current
+// verifier can be replaced or removed with its own signature.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct PoolVerifier {
+    pub pool: Pubkey,
+    /// `Pubkey::default()` while the pool has no verifier
+    pub verifier: Pubkey,
+    /// When `verifier` was last set or removed
+    pub set_at: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl PoolVerifier {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 8 + 1 + 8;
+
+    /// Verifier currently required, if any
+    pub fn verifier(&self) -> Option<Pubkey> {
+        (self.verifier != Pubkey::default()).then_some(self.verifier)
+    }
+}
+
+impl Sequenced for PoolVerifier {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Maker-checker configuration of a pool. While an approver is set,
+// `RecordReward` books rewards as pending rewards awaiting approval, and
+// only the approver, never the platform authority, can make them claimable.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct RewardApproval {
+    pub pool: Pubkey,
+    /// `Pubkey::default()` while the pool records rewards directly
+    pub approver: Pubkey,
+    /// When `approver` was last set or removed
+    pub set_at: i64,
+    /// Rewards approved since the account was created
+    pub approved_count: u64,
+    /// Tokens of those rewards
+    pub approved_amount: u64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl RewardApproval {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 1 + 8;
+
+    /// Approver currently required, if any
+    pub fn approver(&self) -> Option<Pubkey> {
+        (self.approver != Pubkey::default()).then_some(self.approver)
+    }
+}
+
+impl Sequenced for RewardApproval {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Namespace of a third-party platform sharing the deployment. Its operator
+// creates the platform's pools, whose rewards add up here.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct Platform {
+    pub platform_id: u64,
+    pub operator: Pubkey,
+    pub registered_at: i64,
+    pub pool_count: u32,
+    pub reward_count: u64,
+    pub total_rewards_distributed: u64,
+    pub total_platform_fees_collected: u64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl Platform {
+    /// Serialized size of the account
+    pub const LEN: usize = 8 + 32 + 8 + 4 + 8 + 8 + 8 + 1 + 8;
+}
+
+impl Sequenced for Platform {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Time-boxed campaign with its own budget, locked in the pool's budget
+// escrow. Rewards attributed to the campaign draw on the budget while the
+// campaign runs; what is left returns to the treasury vault afterwards.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct Campaign {
+    pub pool: Pubkey,
+    pub campaign_id: u64,
+    pub budget: u64,
+    pub spent: u64,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub created_at: i64,
+    pub task_type_count: u8,
+    pub task_types: [u32; Campaign::MAX_TASK_TYPES],
+    pub reward_count: u64,
+    /// Tokens still held in the budget escrow for the campaign
+    pub escrowed: u64,
+    pub is_ended: bool,
+    /// Set once the unspent budget went back to the treasury vault
+    pub is_closed: bool,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl Campaign {
+    /// Task types a campaign can be restricted to
+    pub const MAX_TASK_TYPES: usize = 8;
+    /// Serialized size of the account
+    pub const LEN: usize =
+        32 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 4 * Self::MAX_TASK_TYPES + 8 + 8 + 1 + 1 + 1 + 8;
+
+    /// Task types the campaign rewards, any when empty
+    pub fn task_types(&self) -> &[u32] {
+        &self.task_types[..(self.task_type_count as usize).min(Self::MAX_TASK_TYPES)]
+    }
+
+    /// Whether rewards can be recorded against the campaign at `now`
+    pub fn is_active(&self, now: i64) -> bool {
+        !self.is_ended && self.starts_at <= now && now < self.ends_at
+    }
+
+    /// Budget not spent yet
+    pub fn remaining(&self) -> u64 {
+        self.budget.saturating_sub(self.spent)
+    }
+}
+
+impl Sequenced for Campaign {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Buyback-and-burn crank of a pool. Each run moves `slice_bps` of the fees
+// collected since the previous run from the treasury vault to the buyback
+// source, swaps it through `dex_program` and burns what it bought.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct BuybackConfig {
+    pub pool: Pubkey,
+    /// Only DEX program runs may swap through, default when disabled
+    pub dex_program: Pubkey,
+    pub governance_mint: Pubkey,
+    pub slice_bps: u16,
+    pub min_output_per_million: u64,
+    /// Pool's `total_platform_fees_collected` at the last run
+    pub fees_accounted: u64,
+    pub total_spent: u64,
+    pub total_burned: u64,
+    pub run_count: u64,
+    pub last_run_at: i64,
+    /// Tokens burned by the latest run
+    pub last_burned: u64,
+    pub source_bump_seed: u8,
+    pub output_bump_seed: u8,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl BuybackConfig {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 32 + 2 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 1 + 8;
+
+    /// Whether runs are allowed
+    pub fn is_enabled(&self) -> bool {
+        self.dex_program != Pubkey::default()
+    }
+}
+
+impl Sequenced for BuybackConfig {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Limits on converting collected fees into stable mints. Queued
+// `SwapTreasury` actions may swap through `dex_program` into the listed
+// mints only, and at most `epoch_limit_bps` of the platform fee vault
+// balance seen by the first swap of each epoch.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct TreasurySwapPolicy {
+    pub pool: Pubkey,
+    /// Only DEX program swaps may go through, default when disabled
+    pub dex_program: Pubkey,
+    pub stable_mint_count: u8,
+    pub stable_mints: [Pubkey; TreasurySwapPolicy::MAX_STABLE_MINTS],
+    pub epoch_limit_bps: u16,
+    /// Epoch of the latest swap
+    pub epoch: u64,
+    /// Vault tokens `epoch` allows swapping
+    pub epoch_budget: u64,
+    pub epoch_swapped: u64,
+    pub total_swapped: u64,
+    pub swap_count: u64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl TreasurySwapPolicy {
+    /// Stable mints a pool can convert its treasury into
+    pub const MAX_STABLE_MINTS: usize = 4;
+    /// Serialized size of the account
+    pub const LEN: usize =
+        32 + 32 + 1 + 32 * Self::MAX_STABLE_MINTS + 2 + 8 + 8 + 8 + 8 + 8 + 1 + 8;
+
+    /// Mints swaps may output
+    pub fn stable_mints(&self) -> &[Pubkey] {
+        &self.stable_mints[..(self.stable_mint_count as usize).min(Self::MAX_STABLE_MINTS)]
+    }
+
+    /// Whether swaps are allowed
+    pub fn is_enabled(&self) -> bool {
+        self.dex_program != Pubkey::default()
+    }
+}
+
+impl Sequenced for TreasurySwapPolicy {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Platform fee discounts by staking tier. `RecordReward` reads the farmer's
+// tier from the byte at `tier_offset` of their account of
+// `staking_program`, the PDA `[stake_seed, farmer]`, and takes
+// `discounts_bps[tier]` off the platform fee.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct FeeDiscounts {
+    pub pool: Pubkey,
+    /// `Pubkey::default()` while the pool has no discounts
+    pub staking_program: Pubkey,
+    pub stake_seed: [u8; 32],
+    pub stake_seed_len: u8,
+    pub tier_offset: u16,
+    pub tier_count: u8,
+    /// Share of the platform fee waived per tier, in basis points
+    pub discounts_bps: [u16; FeeDiscounts::MAX_TIERS],
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl FeeDiscounts {
+    /// Tiers a pool can grant a discount to
+    pub const MAX_TIERS: usize = 8;
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 32 + 1 + 2 + 1 + 2 * Self::MAX_TIERS + 1 + 8;
+
+    /// Staking program tiers are read from, if the pool has discounts
+    pub fn staking_program(&self) -> Option<Pubkey> {
+        (self.staking_program != Pubkey::default()).then_some(self.staking_program)
+    }
+
+    /// Seed prefix of the staking program's farmer accounts
+    pub fn stake_seed(&self) -> &[u8] {
+        &self.stake_seed[..(self.stake_seed_len as usize).min(self.stake_seed.len())]
+    }
+
+    /// Discounts by tier, tier 0 first
+    pub fn discounts_bps(&self) -> &[u16] {
+        &self.discounts_bps[..(self.tier_count as usize).min(Self::MAX_TIERS)]
+    }
+
+    /// Discount of `tier`, none for tiers without an entry
+    pub fn discount_bps(&self, tier: u8) -> u16 {
+        self.discounts_bps()
+            .get(tier as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Staking account `farmer`'s tier is read from
+    pub fn stake_address(&self, farmer: &Pubkey) -> Option<Pubkey> {
+        self.staking_program().map(|staking_program| {
+            Pubkey::find_program_address(&[self.stake_seed(), farmer.as_ref()], &staking_program).0
+        })
+    }
+}
+
+impl Sequenced for FeeDiscounts {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Interest on unclaimed pending rewards. Withdrawals and claims accrue on
+// each reward the rates in effect since it was recorded, for at most
+// `max_accrual_secs`, paid from the pool's bonus vault and never from the
+// treasury vault. Interest the bonus vault cannot cover stays owed in the
+// pending reward, and adds to `total_shortfall` until it is paid.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct InterestPolicy {
+    pub pool: Pubkey,
+    /// Simple yearly rate in basis points, 0 while interest is off
+    pub rate_bps: u16,
+    /// Longest time a reward accrues interest for
+    pub max_accrual_secs: i64,
+    pub total_paid: u64,
+    /// Interest accrued beyond what the bonus vault held, still owed
+    pub total_shortfall: u64,
+    pub claim_count: u64,
+    pub rate_period_count: u8,
+    /// Rates set so far, oldest first; before the first, nothing accrues
+    pub rate_periods: [InterestRatePeriod; InterestPolicy::MAX_RATE_PERIODS],
+    pub bonus_vault_bump_seed: u8,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl InterestPolicy {
+    /// Rate changes the policy keeps apart; older ones are merged
+    pub const MAX_RATE_PERIODS: usize = 16;
+
+    /// Serialized size of the account
+    pub const LEN: usize =
+        32 + 2 + 8 + 8 + 8 + 8 + 1 + InterestRatePeriod::LEN * Self::MAX_RATE_PERIODS + 1 + 1 + 8;
+
+    pub fn is_enabled(&self) -> bool {
+        self.rate_bps > 0
+    }
+
+    /// Makes `rate_bps` the rate from `now` on, leaving what accrued before
+    /// at the earlier rates. Once the periods are full, the two oldest
+    /// merge at the lower of their rates, so no reward earns more than
+    /// was set.
+    pub fn set_rate(&mut self, rate_bps: u16, now: i64) {
+        self.rate_bps = rate_bps;
+        let count = self.rate_period_count as usize;
+        match self.rate_periods[..count].last_mut() {
+            Some(last) if last.rate_bps == rate_bps => return,
+            Some(last) if last.since >= now => {
+                last.rate_bps = rate_bps;
+                return;
+            }
+            None if rate_bps == 0 => return,
+            _ => {}
+        }
+
+        if count == Self::MAX_RATE_PERIODS {
+            self.rate_periods[0].rate_bps = self.rate_periods[0]
+                .rate_bps
+                .min(self.rate_periods[1].rate_bps);
+            self.rate_periods.copy_within(2.., 1);
+            self.rate_period_count -= 1;
+        }
+        self.rate_periods[self.rate_period_count as usize] = InterestRatePeriod {
+            since: now,
+            rate_bps,
+        };
+        self.rate_period_count += 1;
+    }
+
+    /// Interest a reward of `amount` recorded at `recorded_at` has accrued
+    /// at `now`
+    pub fn accrued(&self, amount: u64, recorded_at: i64, now: i64) -> u64 {
+        let end = now.min(recorded_at.saturating_add(self.max_accrual_secs.max(0)));
+        let periods = &self.rate_periods[..self.rate_period_count as usize];
+        let rate_secs: u128 = periods
+            .iter()
+            .enumerate()
+            .map(|(index, period)| {
+                let until = periods
+                    .get(index + 1)
+                    .map_or(end, |next| next.since.min(end));
+                let elapsed = until.saturating_sub(period.since.max(recorded_at)).max(0);
+                period.rate_bps as u128 * elapsed as u128
+            })
+            .sum();
+        ((amount as u128).saturating_mul(rate_secs)
+            / (BASIS_POINTS as u128 * SECONDS_PER_YEAR as u128)) as u64
+    }
+}
+
+// A rate of an interest policy, in effect from `since` until the next one
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, Default, PartialEq, Eq,
+)]
+pub struct InterestRatePeriod {
+    pub since: i64,
+    /// Simple yearly rate in basis points
+    pub rate_bps: u16,
+}
+
+impl InterestRatePeriod {
+    /// Serialized size
+    pub const LEN: usize = 8 + 2;
+}
+
+impl Sequenced for InterestPolicy {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Share of every withdrawal and claim burned instead of paid out. The pool
+// signs the burn out of its treasury vault before the rest goes to the
+// destinations.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct WithdrawBurn {
+    pub pool: Pubkey,
+    /// 0 while withdrawals burn nothing
+    pub withdraw_burn_bps: u16,
+    pub total_burned: u64,
+    /// Withdrawals that burned tokens
+    pub burn_count: u64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl WithdrawBurn {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 2 + 8 + 8 + 1 + 8;
+
+    /// Part of a withdrawal of `amount` that is burned
+    pub fn burn_amount(&self, amount: u64) -> u64 {
+        (amount as u128 * self.withdraw_burn_bps as u128 / BASIS_POINTS as u128) as u64
+    }
+}
+
+impl Sequenced for WithdrawBurn {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Scales newly recorded rewards down while the platform treasury runs low.
+// `RecordReward` keeps what the treasury paid out on each of the last
+// `WINDOW_DAYS` days; while the treasury's balance would last fewer than
+// `min_runway_days` at their average, rewards are scaled by the ceiling
+// factor times the share of that runway left, no lower than the floor.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct RewardGovernor {
+    pub pool: Pubkey,
+    /// Runway rewards are damped below, 0 while the governor is off
+    pub min_runway_days: u32,
+    /// Smallest factor rewards are scaled by, in basis points
+    pub floor_bps: u16,
+    /// Factor rewards are scaled by while the runway is long enough
+    pub ceiling_bps: u16,
+    /// Latest day of the window
+    pub window_day: i64,
+    /// Paid out of the treasury per day, the day's slot being
+    /// `day % WINDOW_DAYS`
+    pub daily_distributed: [u64; RewardGovernor::WINDOW_DAYS],
+    /// Factor the latest reward was scaled by
+    pub last_factor_bps: u16,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl RewardGovernor {
+    /// Days the distribution rate is averaged over
+    pub const WINDOW_DAYS: usize = 7;
+
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 4 + 2 + 2 + 8 + 8 * Self::WINDOW_DAYS + 2 + 1 + 8;
+
+    pub fn is_enabled(&self) -> bool {
+        self.min_runway_days > 0
+    }
+
+    /// Moves the window forward to `day`, clearing the days it skips
+    pub fn roll(&mut self, day: i64) {
+        if day <= self.window_day {
+            return;
+        }
+        let skipped = (day - self.window_day).min(Self::WINDOW_DAYS as i64);
+        for offset in 0..skipped {
+            let slot = (day - offset).rem_euclid(Self::WINDOW_DAYS as i64) as usize;
+            self.daily_distributed[slot] = 0;
+        }
+        self.window_day = day;
+    }
+
+    /// Adds `amount` paid out of the treasury to the latest day
+    pub fn record(&mut self, amount: u64) {
+        let slot = self.window_day.rem_euclid(Self::WINDOW_DAYS as i64) as usize;
+        self.daily_distributed[slot] = self.daily_distributed[slot].saturating_add(amount);
+    }
+
+    /// Factor, in basis points, rewards are scaled by with `treasury_balance`
+    /// left in the treasury
+    pub fn factor_bps(&self, treasury_balance: u64) -> u16 {
+        if !self.is_enabled() {
+            return BASIS_POINTS;
+        }
+        let window_total: u128 = self.daily_distributed.iter().map(|&v| v as u128).sum();
+        if window_total == 0 {
+            return self.ceiling_bps;
+        }
+        // runway / min_runway = balance * WINDOW_DAYS / (window_total * min_runway_days)
+        let factor =
+            self.ceiling_bps as u128 * treasury_balance as u128 * Self::WINDOW_DAYS as u128
+                / (window_total * self.min_runway_days as u128);
+        factor.clamp(self.floor_bps as u128, self.ceiling_bps as u128) as u16
+    }
+}
+
+impl Sequenced for RewardGovernor {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Secondary mint of a pool, e.g. a governance token, paid as a kicker next
+// to rewards. `RecordReward` books a reward's `secondary_amount` to the
+// farmer's `SecondaryBalance`, backed by the secondary vault, and
+// `ClaimMany` pays what the farmer is owed.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct SecondaryReward {
+    pub pool: Pubkey,
+    pub secondary_mint: Pubkey,
+    /// Booked to farmers and not claimed yet, which the vault must cover
+    pub total_owed: u64,
+    pub total_claimed: u64,
+    pub vault_bump_seed: u8,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl SecondaryReward {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 1 + 1 + 8;
+}
+
+impl Sequenced for SecondaryReward {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Secondary rewards of a farmer in a pool, created by the first reward
+// carrying a kicker
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct SecondaryBalance {
+    pub pool: Pubkey,
+    pub farmer: Pubkey,
+    /// Booked and not claimed yet
+    pub owed: u64,
+    pub total_recorded: u64,
+    pub total_claimed: u64,
+    /// Unix timestamp of the latest claim, 0 if never
+    pub last_claimed_at: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl SecondaryBalance {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 8 + 1 + 8;
+}
+
+impl Sequenced for SecondaryBalance {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Bonuses a farmer earns as their lifetime earnings in the pool cross each
+// threshold. `RecordReward` books the bonus of every milestone a reward
+// crosses as a pending reward of `RewardOrigin::Milestone`, funded from the
+// platform treasury.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct MilestoneBonuses {
+    pub pool: Pubkey,
+    pub milestone_count: u8,
+    /// Increasing thresholds, the first `milestone_count` in use
+    pub milestones: [Milestone; MilestoneBonuses::MAX_MILESTONES],
+    pub total_bonuses: u64,
+    pub bonus_count: u64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, Default, PartialEq, Eq,
+)]
+pub struct Milestone {
+    /// Lifetime earnings, after platform fees, that reach the milestone
+    pub threshold: u64,
+    pub bonus: u64,
+}
+
+impl MilestoneBonuses {
+    /// Milestones a pool can configure
+    pub const MAX_MILESTONES: usize = 8;
+
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 1 + 16 * Self::MAX_MILESTONES + 8 + 8 + 1 + 8;
+
+    /// Milestones in use
+    pub fn milestones(&self) -> &[Milestone] {
+        &self.milestones[..self.milestone_count as usize]
+    }
+
+    /// Task id of the pending reward booking the bonus of the milestones
+    /// reached from `milestone` on
+    pub fn task_id(milestone: u8) -> String {
+        format!("milestone:{milestone}")
+    }
+}
+
+impl Sequenced for MilestoneBonuses {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Lifetime earnings of a farmer in a pool with milestone bonuses, counted
+// from the pool's first milestone configuration. Created by the farmer's
+// first reward after it.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct FarmerStats {
+    pub pool: Pubkey,
+    pub farmer: Pubkey,
+    /// Rewards recorded, after platform fees; bonuses do not count
+    pub lifetime_earned: u64,
+    /// Milestones whose bonus the farmer was booked
+    pub milestones_reached: u8,
+    pub total_bonuses: u64,
+    /// When the latest milestone was reached, 0 if never
+    pub last_milestone_at: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl FarmerStats {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 8 + 1 + 8 + 8 + 1 + 8;
+}
+
+impl Sequenced for FarmerStats {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Protocol-owned liquidity of a pool. Each contribution moves `pol_bps` of
+// the fees collected since the previous one from the treasury vault to the
+// POL vault, swaps part of it into `pair_mint` through a whitelisted AMM and
+// deposits both sides into its pool. The LP tokens stay in the LP vault,
+// which nothing withdraws from.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct ProtocolLiquidity {
+    pub pool: Pubkey,
+    pub amm_program_count: u8,
+    /// The only AMM programs contributions may go through
+    pub amm_programs: [Pubkey; ProtocolLiquidity::MAX_AMM_PROGRAMS],
+    pub pair_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub pol_bps: u16,
+    /// Least LP tokens accepted per million reward tokens contributed
+    pub min_lp_per_million: u64,
+    /// Pool's `total_platform_fees_collected` at the last contribution
+    pub fees_accounted: u64,
+    /// Reward tokens that left the POL vault, swapped or deposited
+    pub total_contributed: u64,
+    pub total_lp: u64,
+    pub contribution_count: u64,
+    pub last_contributed_at: i64,
+    pub vault_bump_seed: u8,
+    pub pair_vault_bump_seed: u8,
+    pub lp_vault_bump_seed: u8,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl ProtocolLiquidity {
+    /// AMM programs a pool can whitelist
+    pub const MAX_AMM_PROGRAMS: usize = 4;
+    /// Serialized size of the account
+    pub const LEN: usize = 32
+        + 1
+        + 32 * Self::MAX_AMM_PROGRAMS
+        + 32
+        + 32
+        + 2
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + 1
+        + 1
+        + 1
+        + 8;
+
+    /// Whitelisted AMM programs
+    pub fn amm_programs(&self) -> &[Pubkey] {
+        &self.amm_programs[..(self.amm_program_count as usize).min(Self::MAX_AMM_PROGRAMS)]
+    }
+
+    /// Whether contributions are allowed
+    pub fn is_enabled(&self) -> bool {
+        self.pol_bps > 0 && self.amm_program_count > 0
+    }
+}
+
+impl Sequenced for ProtocolLiquidity {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Cashback of platform fees in the pool's governance token, its secondary
+// mint. `RecordReward` books `rebate_bps` of each fee it charges, converted
+// at `rate_per_million`, to the farmer's `SecondaryBalance`, moving it from
+// the rebate vault to the secondary vault.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct FeeRebate {
+    pub pool: Pubkey,
+    pub governance_mint: Pubkey,
+    pub rebate_bps: u16,
+    /// Governance tokens rebated per million reward tokens of fee rebated
+    pub rate_per_million: u64,
+    pub total_rebated: u64,
+    pub rebate_count: u64,
+    pub vault_bump_seed: u8,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl FeeRebate {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 2 + 8 + 8 + 8 + 1 + 1 + 8;
+
+    /// Governance tokens rebated on `platform_fee`
+    pub fn rebate(&self, platform_fee: u64) -> u64 {
+        let rebated_fee = platform_fee as u128 * self.rebate_bps as u128 / BASIS_POINTS as u128;
+        (rebated_fee * self.rate_per_million as u128 / 1_000_000).min(u64::MAX as u128) as u64
+    }
+}
+
+impl Sequenced for FeeRebate {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Protocol-wide settings of the pool factory, one per deployment. Anyone can
+// create a pool through `CreatePool` by paying `creation_fee` lamports to
+// `treasury`; rewards of those pools then route `protocol_fee_share_bps` of
+// their platform fees to token accounts `treasury` owns.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct ProtocolConfig {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    /// Lamports paid to the treasury for each pool created
+    pub creation_fee: u64,
+    pub protocol_fee_share_bps: u16,
+    pub pool_count: u64,
+    pub total_creation_fees: u64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl ProtocolConfig {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 8 + 2 + 8 + 8 + 1 + 8;
+}
+
+impl Sequenced for ProtocolConfig {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Record of a pool created through the factory. The protocol fee share is
+// fixed when the pool is created, so later changes to the configuration
+// only apply to new pools.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct FactoryPool {
+    pub pool: Pubkey,
+    pub creator: Pubkey,
+    pub seed: u64,
+    pub protocol_fee_share_bps: u16,
+    pub created_at: i64,
+    pub total_protocol_fees: u64,
+    pub pool_bump_seed: u8,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl FactoryPool {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 8 + 2 + 8 + 8 + 1 + 1 + 8;
+
+    /// Part of `platform_fee` owed to the protocol
+    pub fn protocol_fee(&self, platform_fee: u64) -> u64 {
+        (platform_fee as u128 * self.protocol_fee_share_bps as u128 / BASIS_POINTS as u128) as u64
+    }
+}
+
+impl Sequenced for FactoryPool {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Reward voucher handed out as an NFT, for offline events. Its tokens wait
+// in the pool's voucher escrow until whoever holds the NFT redeems it,
+// burning the NFT; the account stays as the voucher's record.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct Voucher {
+    pub pool: Pubkey,
+    pub voucher_id: u64,
+    /// Mint of the voucher's NFT, with a supply of one until redeemed
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub issued_at: i64,
+    /// Unix timestamp of the redemption, 0 until redeemed
+    pub redeemed_at: i64,
+    /// Holder who redeemed the voucher, the default key until redeemed
+    pub redeemed_by: Pubkey,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl Voucher {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 8 + 32 + 8 + 8 + 8 + 32 + 1 + 8;
+
+    /// Whether the voucher was redeemed
+    pub fn is_redeemed(&self) -> bool {
+        self.redeemed_at != 0
+    }
+}
+
+impl Sequenced for Voucher {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Orders claims after a pause. While the pool is paused, farmers take a
+// ticket scored by their oldest claimable reward; `ResumePool` then opens a
+// recovery window of `recovery_window` seconds in which each ticket's turn
+// comes in score order, oldest first. Farmers without a ticket for the
+// round wait for the window to end.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct PayoutQueue {
+    pub pool: Pubkey,
+    /// Length of the recovery window in seconds, 0 while the queue is off
+    pub recovery_window: i64,
+    /// Recovery windows opened so far; tickets taken during a pause are
+    /// for `round + 1`
+    pub round: u64,
+    pub recovery_started_at: i64,
+    /// End of the current recovery window, in the past once it is over
+    pub recovery_ends_at: i64,
+    /// Lowest and highest ticket scores of the current round
+    pub oldest_score: i64,
+    pub newest_score: i64,
+    /// Tickets taken for the next round
+    pub ticket_count: u64,
+    /// Lowest and highest ticket scores of the next round
+    pub next_oldest_score: i64,
+    pub next_newest_score: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl PayoutQueue {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8;
+
+    pub fn is_enabled(&self) -> bool {
+        self.recovery_window > 0
+    }
+
+    /// Whether claims are ordered at `now`
+    pub fn is_recovering(&self, now: i64) -> bool {
+        now < self.recovery_ends_at
+    }
+
+    /// Unix timestamp from which a ticket of the current round scored
+    /// `score` can claim. Scores spread linearly over the window, so the
+    /// oldest ticket's turn comes when the window opens.
+    pub fn turn_of(&self, score: i64) -> i64 {
+        let spread = self.newest_score as i128 - self.oldest_score as i128;
+        if spread <= 0 {
+            return self.recovery_started_at;
+        }
+        let window = (self.recovery_ends_at - self.recovery_started_at) as i128;
+        let offset = (score as i128 - self.oldest_score as i128).clamp(0, spread);
+        self.recovery_started_at + (window * offset / (spread + 1)) as i64
+    }
+}
+
+impl Sequenced for PayoutQueue {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// A farmer's place in a payout queue, reused for every round they take a
+// ticket for
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct PayoutTicket {
+    pub pool: Pubkey,
+    pub farmer: Pubkey,
+    /// Recovery round the ticket is for
+    pub round: u64,
+    /// Order the ticket was taken in within its round, from 0
+    pub number: u64,
+    /// Unix timestamp the farmer's oldest claimable reward was recorded at
+    pub score: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl PayoutTicket {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 1 + 8;
+}
+
+impl Sequenced for PayoutTicket {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Per-epoch cap on claims from the treasury vault after `ResumeWithRamp`.
+// The cap starts at `initial_epoch_cap` in `start_epoch` and grows by
+// `epoch_cap_step` each epoch; from `start_epoch + ramp_epochs` on, claims
+// are no longer capped.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct WithdrawalRamp {
+    pub pool: Pubkey,
+    pub start_epoch: u64,
+    pub initial_epoch_cap: u64,
+    pub epoch_cap_step: u64,
+    /// Epochs the cap lasts, 0 once the ramp was ended
+    pub ramp_epochs: u16,
+    /// Epoch of the latest capped claim
+    pub epoch: u64,
+    /// Tokens claimed in `epoch`
+    pub epoch_claimed: u64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl WithdrawalRamp {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 2 + 8 + 8 + 1 + 8;
+
+    /// Tokens claims may take from the vault in `epoch`, `None` once the
+    /// ramp is over
+    pub fn epoch_cap(&self, epoch: u64) -> Option<u64> {
+        let elapsed = epoch.saturating_sub(self.start_epoch);
+        (elapsed < self.ramp_epochs as u64).then(|| {
+            self.initial_epoch_cap
+                .saturating_add(self.epoch_cap_step.saturating_mul(elapsed))
+        })
+    }
+
+    /// Books a claim of `amount` in `epoch` against its cap
+    pub fn record_claim(&mut self, epoch: u64, amount: u64) -> Result<(), RewardPoolError> {
+        let Some(cap) = self.epoch_cap(epoch) else {
+            return Ok(());
+        };
+        if self.epoch != epoch {
+            self.epoch = epoch;
+            self.epoch_claimed = 0;
+        }
+        let claimed = self.epoch_claimed.saturating_add(amount);
+        if claimed > cap {
+            return Err(RewardPoolError::WithdrawalRampLimitExceeded);
+        }
+        self.epoch_claimed = claimed;
+        Ok(())
+    }
+}
+
+impl Sequenced for WithdrawalRamp {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Dead-man switch of a pool's platform authority. Once the authority has
+// signed no counted instruction for `inactivity_timeout` seconds, counted
+// from `configured_at` at the latest, `recovery_authority` can take over
+// the pool with `ClaimDormantAuthority`.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct AuthorityRecovery {
+    pub pool: Pubkey,
+    /// `Pubkey::default()` while the switch is off
+    pub recovery_authority: Pubkey,
+    pub inactivity_timeout: i64,
+    pub configured_at: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl AuthorityRecovery {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 1 + 8;
+
+    /// Whether a recovery authority is designated
+    pub fn is_enabled(&self) -> bool {
+        self.recovery_authority != Pubkey::default()
+    }
+
+    /// When the recovery authority may take over, given the time of the
+    /// platform authority's latest action
+    pub fn claimable_at(&self, last_authority_action_at: i64) -> i64 {
+        last_authority_action_at
+            .max(self.configured_at)
+            .saturating_add(self.inactivity_timeout)
+    }
+}
+
+impl Sequenced for AuthorityRecovery {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Every change of a pool's platform authority, oldest first, so auditors
+// can trace each key that ever controlled the pool. Created by the first
+// change and grown by one entry per change, at the expense of whoever
+// carries it out.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct KeyHistory {
+    pub pool: Pubkey,
+    pub entries: Vec<KeyRotation>,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl KeyHistory {
+    /// Serialized size of an account with `entries` entries
+    pub const fn len_with(entries: usize) -> usize {
+        32 + 4 + KeyRotation::LEN * entries + 1 + 8
+    }
+}
+
+impl Sequenced for KeyHistory {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyRotation {
+    pub old_key: Pubkey,
+    pub new_key: Pubkey,
+    /// Slot the change was made in
+    pub slot: u64,
+    /// Hash of the document giving the reason, zeroed when none was given
+    pub reason_hash: [u8; 32],
+}
+
+impl KeyRotation {
+    /// Serialized size of an entry
+    pub const LEN: usize = 32 + 32 + 8 + 32;
+}
+
+// Guardians a farmer chose to recover their rewards in a pool if they lose
+// their wallet. Once `threshold` of them start a recovery to `new_wallet`,
+// the farmer has `FARMER_RECOVERY_DELAY` to cancel it; after that anyone
+// can move the farmer's state and pending rewards to the new wallet.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct FarmerRecovery {
+    pub pool: Pubkey,
+    pub farmer: Pubkey,
+    pub guardian_count: u8,
+    pub guardians: [Pubkey; FarmerRecovery::MAX_GUARDIANS],
+    /// Guardians that must sign to start a recovery, 0 while recovery is off
+    pub threshold: u8,
+    /// Wallet of the recovery in progress, `Pubkey::default()` if none
+    pub new_wallet: Pubkey,
+    pub initiated_at: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl FarmerRecovery {
+    /// Guardians a farmer can name
+    pub const MAX_GUARDIANS: usize = 5;
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 1 + 32 * Self::MAX_GUARDIANS + 1 + 32 + 8 + 1 + 8;
+
+    /// Named guardians
+    pub fn guardians(&self) -> &[Pubkey] {
+        &self.guardians[..(self.guardian_count as usize).min(Self::MAX_GUARDIANS)]
+    }
+
+    /// Index of `guardian` among the named guardians
+    pub fn guardian_index(&self, guardian: &Pubkey) -> Option<usize> {
+        self.guardians()
+            .iter()
+            .position(|candidate| candidate == guardian)
+    }
+
+    /// Whether a recovery was started and not cancelled
+    pub fn is_pending(&self) -> bool {
+        self.new_wallet != Pubkey::default()
+    }
+}
+
+impl Sequenced for FarmerRecovery {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Flat lamport fee every `WithdrawReward` pays into the pool's fee vault,
+// on top of any token fee. The sponsor pays it for sponsored withdrawals.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct WithdrawalFee {
+    pub pool: Pubkey,
+    /// 0 while withdrawals pay no fee
+    pub fee_lamports: u64,
+    /// Whether withdrawals below `small_claim_threshold` are exempt
+    pub exempt_small_claims: bool,
+    pub small_claim_threshold: u64,
+    pub total_collected: u64,
+    /// Withdrawals that paid the fee
+    pub fee_count: u64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl WithdrawalFee {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 8 + 1 + 8 + 8 + 8 + 1 + 8;
+
+    /// Lamports a withdrawal of `amount` pays
+    pub fn fee_for(&self, amount: u64) -> u64 {
+        if self.exempt_small_claims && amount < self.small_claim_threshold {
+            0
+        } else {
+            self.fee_lamports
+        }
+    }
+}
+
+impl Sequenced for WithdrawalFee {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Point-in-time copy of a pool's accounts, written by `ExportSnapshot` into
+// a fresh account of the caller's. Only the program writes it and never
+// again, so the commitment it carries can be checked against an RPC node's
+// copy of the accounts at `slot`.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq, Eq)]
+pub struct PoolSnapshot {
+    pub pool: Pubkey,
+    pub exported_by: Pubkey,
+    /// Slot the accounts were read in
+    pub slot: u64,
+    pub exported_at: i64,
+    /// Hash of `slot` and the accounts, see `PoolSnapshot::commit`
+    pub commitment: [u8; 32],
+    /// The pool, its parameters, its instruction counters and the daily
+    /// stats passed, in that order
+    pub accounts: Vec<SnapshotAccount>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotAccount {
+    pub address: Pubkey,
+    /// Raw account data, empty for accounts not created yet
+    pub data: Vec<u8>,
+}
+
+impl PoolSnapshot {
+    /// Serialized size of a snapshot of accounts holding `data_lens` bytes
+    pub fn len_with(data_lens: impl IntoIterator<Item = usize>) -> usize {
+        32 + 32
+            + 8
+            + 8
+            + 32
+            + 4
+            + data_lens
+                .into_iter()
+                .map(|data_len| 32 + 4 + data_len)
+                .sum::<usize>()
+    }
+
+    /// Serialized size of a snapshot of a pool whose parameters and
+    /// counters exist, with `stats_count` daily stats
+    pub fn full_len(stats_count: usize) -> usize {
+        Self::len_with(
+            [
+                RewardPool::LEN,
+                PoolParameters::LEN,
+                InstructionCounters::LEN,
+            ]
+            .into_iter()
+            .chain(std::iter::repeat_n(DailyStats::LEN, stats_count)),
+        )
+    }
+
+    /// Hash of `slot` followed by every account's address, data length and
+    /// data, in order
+    pub fn commit(slot: u64, accounts: &[SnapshotAccount]) -> [u8; 32] {
+        let slot = slot.to_le_bytes();
+        let lens: Vec<[u8; 4]> = accounts
+            .iter()
+            .map(|account| (account.data.len() as u32).to_le_bytes())
+            .collect();
+        let mut parts: Vec<&[u8]> = vec![&slot];
+        for (account, len) in accounts.iter().zip(&lens) {
+            parts.extend([account.address.as_ref(), len, &account.data]);
+        }
+        hashv(&parts).to_bytes()
+    }
+
+    /// Whether the commitment matches the accounts the snapshot holds
+    pub fn is_consistent(&self) -> bool {
+        self.commitment == Self::commit(self.slot, &self.accounts)
+    }
+}
+
+// Team of farmers sharing a reward-mint vault. Farmers point their guild
+// split at the vault, and `DistributeGuildRewards` pays what it holds out
+// to the members by their shares. Members who leave take their share with
+// them, so the others split in proportion until the authority sets the
+// members again.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct Guild {
+    pub pool: Pubkey,
+    /// Sets the members; fixed, as the guild's address derives from it
+    pub authority: Pubkey,
+    pub member_count: u8,
+    pub members: [GuildMember; Guild::MAX_MEMBERS],
+    pub total_distributed: u64,
+    pub distribution_count: u64,
+    pub vault_bump_seed: u8,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+// Member of a guild and their share of its distributions
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, Default, PartialEq, Eq,
+)]
+pub struct GuildMember {
+    pub member: Pubkey,
+    /// Share in basis points; the shares add up to `BASIS_POINTS` when set
+    pub share_bps: u16,
+}
+
+impl Guild {
+    /// Members a guild can have
+    pub const MAX_MEMBERS: usize = 16;
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 1 + (32 + 2) * Self::MAX_MEMBERS + 8 + 8 + 1 + 1 + 8;
+
+    /// Current members
+    pub fn members(&self) -> &[GuildMember] {
+        &self.members[..(self.member_count as usize).min(Self::MAX_MEMBERS)]
+    }
+
+    /// Index of `member` among the current members
+    pub fn member_index(&self, member: &Pubkey) -> Option<usize> {
+        self.members()
+            .iter()
+            .position(|candidate| candidate.member == *member)
+    }
+
+    /// Amount each member receives out of `amount`, in proportion to the
+    /// shares of the current members. The rounding remainder goes to the
+    /// first member.
+    pub fn amounts(&self, amount: u64) -> Vec<u64> {
+        let total_shares: u128 = self
+            .members()
+            .iter()
+            .map(|member| member.share_bps as u128)
+            .sum();
+        if total_shares == 0 {
+            return vec![0; self.members().len()];
+        }
+        let mut amounts: Vec<u64> = self
+            .members()
+            .iter()
+            .map(|member| (amount as u128 * member.share_bps as u128 / total_shares) as u64)
+            .collect();
+        let remainder = amount - amounts.iter().sum::<u64>();
+        if let Some(first) = amounts.first_mut() {
+            *first += remainder;
+        }
+        amounts
+    }
+}
+
+impl Sequenced for Guild {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Fee totals of a pool by category, in reward tokens unless noted. The fee
+// ledger holds them since the pool started, an invoice for its period.
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, Default, PartialEq, Eq,
+)]
+pub struct FeeTotals {
+    /// Platform fees charged on recorded rewards
+    pub platform_fees: u64,
+    /// Lamports charged on withdrawals
+    pub withdrawal_fee_lamports: u64,
+    /// Burned from withdrawals
+    pub withdraw_burned: u64,
+    /// Governance tokens bought back with platform fees and burned
+    pub buyback_burned: u64,
+    /// Governance tokens rebated to farmers on their platform fees
+    pub fee_rebates: u64,
+    /// Part of the platform fees paid to the protocol
+    pub protocol_fees: u64,
+}
+
+impl FeeTotals {
+    /// Serialized size
+    pub const LEN: usize = 8 * 6;
+
+    /// Totals accrued between `earlier` and these
+    pub fn since(&self, earlier: &FeeTotals) -> FeeTotals {
+        FeeTotals {
+            platform_fees: self.platform_fees.saturating_sub(earlier.platform_fees),
+            withdrawal_fee_lamports: self
+                .withdrawal_fee_lamports
+                .saturating_sub(earlier.withdrawal_fee_lamports),
+            withdraw_burned: self.withdraw_burned.saturating_sub(earlier.withdraw_burned),
+            buyback_burned: self.buyback_burned.saturating_sub(earlier.buyback_burned),
+            fee_rebates: self.fee_rebates.saturating_sub(earlier.fee_rebates),
+            protocol_fees: self.protocol_fees.saturating_sub(earlier.protocol_fees),
+        }
+    }
+}
+
+// Where a pool's fee invoices stand: the fee totals at the last close, so
+// the next invoice only counts what came after, and the last seal, which
+// the next invoice chains to.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct FeeLedger {
+    pub pool: Pubkey,
+    pub invoice_count: u64,
+    /// Epoch of the last invoice
+    pub last_epoch: u64,
+    pub last_slot: u64,
+    pub last_closed_at: i64,
+    /// Fee totals of the pool when the last invoice was closed
+    pub closed_totals: FeeTotals,
+    /// Seal of the last invoice, zero before the first
+    pub last_seal: [u8; 32],
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl FeeLedger {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + FeeTotals::LEN + 32 + 1 + 8;
+}
+
+impl Sequenced for FeeLedger {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Fees a pool charged, burned and distributed over one period, closed by
+// `CloseFeeInvoice` at most once per epoch and never written again. The
+// period runs from the previous close, or the pool's start for the first
+// invoice, to this one. `seal` hashes the invoice with the previous seal,
+// so invoices form a chain an auditor can check end to end.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq, Eq)]
+pub struct FeeInvoice {
+    pub pool: Pubkey,
+    /// Position of the invoice among the pool's, from 0
+    pub number: u64,
+    /// Epoch in which the invoice was closed
+    pub epoch: u64,
+    /// Slot and time of the previous close, 0 for the first invoice
+    pub start_slot: u64,
+    pub start_at: i64,
+    pub end_slot: u64,
+    pub end_at: i64,
+    pub fees: FeeTotals,
+    pub previous_seal: [u8; 32],
+    pub seal: [u8; 32],
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl FeeInvoice {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 8 + 8 + FeeTotals::LEN + 32 + 32 + 1 + 8;
+
+    /// SHA-256 of every field of the invoice up to `previous_seal`
+    pub fn compute_seal(&self) -> [u8; 32] {
+        let fees = borsh::to_vec(&self.fees).expect("fee serialization is infallible");
+        hashv(&[
+            self.pool.as_ref(),
+            &self.number.to_le_bytes(),
+            &self.epoch.to_le_bytes(),
+            &self.start_slot.to_le_bytes(),
+            &self.start_at.to_le_bytes(),
+            &self.end_slot.to_le_bytes(),
+            &self.end_at.to_le_bytes(),
+            &fees,
+            &self.previous_seal,
+        ])
+        .to_bytes()
+    }
+
+    /// Whether the seal matches the invoice's contents
+    pub fn is_sealed(&self) -> bool {
+        self.seal == self.compute_seal()
+    }
+}
+
+impl Sequenced for FeeInvoice {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Accumulators of a pool's recorded rewards for time-weighted average reward
+// rates. The rate between two rewards integrates over time to the amount
+// recorded, so `cumulative_amount` is the running sum of rate x seconds and
+// the average rate over a window is what it grew by divided by the window's
+// length. `RecordReward` checkpoints the accumulators in `observations`, a
+// ring of at most one entry per `OBSERVATION_INTERVAL`, so windows can
+// start in the past.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct RewardRate {
+    pub pool: Pubkey,
+    /// Farmer amounts recorded since tracking started
+    pub cumulative_amount: u64,
+    /// Rewards recorded since tracking started
+    pub cumulative_count: u64,
+    pub started_at: i64,
+    pub last_recorded_at: i64,
+    /// Number of observations written, including overwritten ones
+    pub observation_count: u64,
+    pub observations: [RateObservation; RewardRate::MAX_OBSERVATIONS],
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+// Accumulators of a pool's reward rate before the reward recorded at
+// `timestamp`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, Default)]
+pub struct RateObservation {
+    pub timestamp: i64,
+    pub cumulative_amount: u64,
+    pub cumulative_count: u64,
+}
+
+impl RewardRate {
+    /// Observations kept, a week of hourly checkpoints
+    pub const MAX_OBSERVATIONS: usize = 168;
+    /// Shortest time between two observations, in seconds
+    pub const OBSERVATION_INTERVAL: i64 = SECONDS_PER_HOUR;
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 8 + (8 + 8 + 8) * Self::MAX_OBSERVATIONS + 1 + 8;
+
+    pub fn new(pool: Pubkey, now: i64, bump_seed: u8) -> Self {
+        Self {
+            pool,
+            cumulative_amount: 0,
+            cumulative_count: 0,
+            started_at: now,
+            last_recorded_at: now,
+            observation_count: 0,
+            observations: [RateObservation::default(); Self::MAX_OBSERVATIONS],
+            bump_seed,
+            sequence: 0,
+        }
+    }
+
+    /// Adds a reward of `amount` recorded at `now`, first checkpointing the
+    /// accumulators if the last observation is an interval old
+    pub fn record(&mut self, amount: u64, now: i64) {
+        let due = self.latest_observation().is_none_or(|latest| {
+            now >= latest.timestamp.saturating_add(Self::OBSERVATION_INTERVAL)
+        });
+        if due {
+            let index = (self.observation_count % Self::MAX_OBSERVATIONS as u64) as usize;
+            self.observations[index] = RateObservation {
+                timestamp: now,
+                cumulative_amount: self.cumulative_amount,
+                cumulative_count: self.cumulative_count,
+            };
+            self.observation_count += 1;
+        }
+        self.cumulative_amount = self.cumulative_amount.saturating_add(amount);
+        self.cumulative_count += 1;
+        self.last_recorded_at = now;
+    }
+
+    /// Observations still held, oldest first
+    pub fn observations(&self) -> impl Iterator<Item = &RateObservation> {
+        let held = self.observation_count.min(Self::MAX_OBSERVATIONS as u64) as usize;
+        let oldest = (self.observation_count as usize).saturating_sub(held);
+        (oldest..oldest + held).map(|index| &self.observations[index % Self::MAX_OBSERVATIONS])
+    }
+
+    /// Accumulators a window starting at `start` is measured from. Only the
+    /// hour after an observation can hold rewards before the next one, so
+    /// past that hour they are known at `start` itself; within it the
+    /// window starts at the observation instead. Windows older than every
+    /// observation held start at the oldest.
+    pub fn window_start(&self, start: i64) -> Option<RateObservation> {
+        let mut before = None;
+        for observation in self.observations() {
+            if observation.timestamp > start {
+                return Some(match before {
+                    Some(before) => Self::settled_at(before, start, observation),
+                    None => *observation,
+                });
+            }
+            before = Some(observation);
+        }
+        let current = RateObservation {
+            timestamp: start,
+            cumulative_amount: self.cumulative_amount,
+            cumulative_count: self.cumulative_count,
+        };
+        before.map(|before| Self::settled_at(before, start, &current))
+    }
+
+    /// Accumulators at `start`, between observations `before` and `after`
+    fn settled_at(
+        before: &RateObservation,
+        start: i64,
+        after: &RateObservation,
+    ) -> RateObservation {
+        if start >= before.timestamp.saturating_add(Self::OBSERVATION_INTERVAL) {
+            RateObservation {
+                timestamp: start,
+                ..*after
+            }
+        } else {
+            *before
+        }
+    }
+
+    fn latest_observation(&self) -> Option<&RateObservation> {
+        self.observations().last()
+    }
+}
+
+impl Sequenced for RewardRate {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// What a pool's claims paid out of the treasury vault. Claims count them in
+// their farmer's claim shard, and `ConsolidateClaimShards` adds up the
+// shards in the pool's claim ledger.
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, Default, PartialEq, Eq,
+)]
+pub struct ClaimTotals {
+    /// `ClaimMany` instructions
+    pub claim_count: u64,
+    /// `CrankAutoClaim` instructions
+    pub auto_claim_count: u64,
+    /// Pending rewards claimed
+    pub reward_count: u64,
+    /// Tokens of the pending rewards claimed, guild shares and tips
+    /// included, interest excluded
+    pub amount: u64,
+}
+
+impl ClaimTotals {
+    /// Serialized size
+    pub const LEN: usize = 8 * 4;
+
+    /// Totals claimed between `earlier` and these
+    pub fn since(&self, earlier: &ClaimTotals) -> ClaimTotals {
+        ClaimTotals {
+            claim_count: self.claim_count.saturating_sub(earlier.claim_count),
+            auto_claim_count: self
+                .auto_claim_count
+                .saturating_sub(earlier.auto_claim_count),
+            reward_count: self.reward_count.saturating_sub(earlier.reward_count),
+            amount: self.amount.saturating_sub(earlier.amount),
+        }
+    }
+
+    /// Adds `other` to these totals
+    pub fn add(&mut self, other: &ClaimTotals) {
+        self.claim_count = self.claim_count.saturating_add(other.claim_count);
+        self.auto_claim_count = self.auto_claim_count.saturating_add(other.auto_claim_count);
+        self.reward_count = self.reward_count.saturating_add(other.reward_count);
+        self.amount = self.amount.saturating_add(other.amount);
+    }
+}
+
+// One of `CLAIM_SHARD_COUNT` accounts counting a pool's claims in place of
+// the pool's instruction counters, so claims leave the counters account
+// untouched. `consolidated` holds the totals the pool's claim ledger has
+// already added up.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct ClaimShard {
+    pub pool: Pubkey,
+    /// Index of the shard, see `claim_shard_index`
+    pub shard: u8,
+    pub totals: ClaimTotals,
+    pub consolidated: ClaimTotals,
+    pub last_claimed_at: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl ClaimShard {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 1 + ClaimTotals::LEN * 2 + 8 + 1 + 8;
+
+    pub fn new(pool: Pubkey, shard: u8, bump_seed: u8) -> Self {
+        Self {
+            pool,
+            shard,
+            totals: ClaimTotals::default(),
+            consolidated: ClaimTotals::default(),
+            last_claimed_at: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    }
+}
+
+impl Sequenced for ClaimShard {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// A pool's claim totals across its shards as of the last consolidation
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct ClaimLedger {
+    pub pool: Pubkey,
+    pub totals: ClaimTotals,
+    pub consolidation_count: u64,
+    pub last_consolidated_at: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl ClaimLedger {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + ClaimTotals::LEN + 8 + 8 + 1 + 8;
+}
+
+impl Sequenced for ClaimLedger {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Receipt of a reward recorded with an idempotency key. A `RecordReward`
+// retried under the same key finds it and succeeds without paying again, so
+// a backend can retry after a timeout without knowing whether the first
+// attempt landed.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct RewardReceipt {
+    pub pool: Pubkey,
+    pub idempotency_key: [u8; 32],
+    pub farmer: Pubkey,
+    /// Amount requested, before the governor and fees
+    pub amount: u64,
+    /// SHA-256 of the task id
+    pub task_hash: [u8; 32],
+    pub recorded_at: i64,
+    pub slot: u64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl RewardReceipt {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 32 + 8 + 8 + 1 + 8;
+
+    /// Whether the receipt is of the reward of `farmer`, `amount` and
+    /// `task_hash`
+    pub fn matches(&self, farmer: &Pubkey, amount: u64, task_hash: &[u8; 32]) -> bool {
+        self.farmer == *farmer && self.amount == amount && self.task_hash == *task_hash
+    }
+}
+
+impl Sequenced for RewardReceipt {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Platform fees of task types that differ from the pool's fee. `RecordReward`
+// charges the override of the reward's task type, if any.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct FeeOverrides {
+    pub pool: Pubkey,
+    pub override_count: u8,
+    pub overrides: [FeeOverride; FeeOverrides::MAX_OVERRIDES],
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, Default)]
+pub struct FeeOverride {
+    pub task_type: u32,
+    pub fee_percentage: u8,
+}
+
+impl FeeOverrides {
+    /// Task types a pool can override the fee of
+    pub const MAX_OVERRIDES: usize = 32;
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 1 + (4 + 1) * Self::MAX_OVERRIDES + 1 + 8;
+
+    pub fn new(pool: Pubkey, bump_seed: u8) -> Self {
+        Self {
+            pool,
+            override_count: 0,
+            overrides: [FeeOverride::default(); Self::MAX_OVERRIDES],
+            bump_seed,
+            sequence: 0,
+        }
+    }
+
+    /// Registered overrides
+    pub fn overrides(&self) -> &[FeeOverride] {
+        &self.overrides[..(self.override_count as usize).min(Self::MAX_OVERRIDES)]
+    }
+
+    fn position(&self, task_type: u32) -> Option<usize> {
+        self.overrides()
+            .iter()
+            .position(|fee_override| fee_override.task_type == task_type)
+    }
+
+    /// Fee of `task_type`, `None` when it uses the pool's fee
+    pub fn fee_percentage(&self, task_type: u32) -> Option<u8> {
+        self.position(task_type)
+            .map(|index| self.overrides[index].fee_percentage)
+    }
+
+    /// Sets the fee of `task_type`, replacing its override if it has one
+    pub fn set(&mut self, task_type: u32, fee_percentage: u8) -> Result<(), RewardPoolError> {
+        let index = match self.position(task_type) {
+            Some(index) => index,
+            None if (self.override_count as usize) < Self::MAX_OVERRIDES => {
+                self.override_count += 1;
+                self.override_count as usize - 1
+            }
+            None => return Err(RewardPoolError::FeeOverridesFull),
+        };
+        self.overrides[index] = FeeOverride {
+            task_type,
+            fee_percentage,
+        };
+        Ok(())
+    }
+
+    /// Removes the override of `task_type`, which falls back to the pool's fee
+    pub fn remove(&mut self, task_type: u32) -> Result<(), RewardPoolError> {
+        let index = self
+            .position(task_type)
+            .ok_or(RewardPoolError::FeeOverrideNotFound)?;
+        let count = self.override_count as usize;
+        self.overrides.copy_within(index + 1..count, index);
+        self.overrides[count - 1] = FeeOverride::default();
+        self.override_count -= 1;
+        Ok(())
+    }
+}
+
+impl Sequenced for FeeOverrides {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Program, ours or a partner's, whose `TaskAttestation` accounts
+// `RecordReward` requires before booking a reward
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct PoolAttestor {
+    pub pool: Pubkey,
+    /// `Pubkey::default()` while the pool has no attestor
+    pub attestor_program: Pubkey,
+    /// Offset of the `TaskAttestation` in the attestor's accounts, e.g. 8
+    /// to skip an Anchor discriminator
+    pub data_offset: u16,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl PoolAttestor {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 2 + 1 + 8;
+
+    /// Attestor program currently required, if any
+    pub fn attestor_program(&self) -> Option<Pubkey> {
+        (self.attestor_program != Pubkey::default()).then_some(self.attestor_program)
+    }
+}
+
+impl Sequenced for PoolAttestor {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Reward set aside for a task before work starts, held in the pool's
+// budget escrow token account. `RecordReward` pays the task's reward from
+// it; whatever is left returns to the treasury vault with
+// `ReleaseUnusedBudget` once the reward is recorded or the budget expired.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct TaskBudget {
+    pub pool: Pubkey,
+    /// SHA-256 of the task id
+    pub task_hash: [u8; 32],
+    /// Tokens still locked for the task
+    pub amount: u64,
+    /// Unix timestamp after which an unused budget can be released
+    pub expires_at: i64,
+    /// Whether `RecordReward` already consumed the budget
+    pub is_recorded: bool,
+    /// Receives the account's rent when it is released
+    pub payer: Pubkey,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl TaskBudget {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 1 + 32 + 1 + 8;
+}
+
+impl Sequenced for TaskBudget {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Open challenge of a pending reward, holding the challenger's bond on top
+// of its rent until `ResolveChallenge` closes it
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct RewardChallenge {
+    pub pool: Pubkey,
+    pub pending_reward: Pubkey,
+    pub challenger: Pubkey,
+    /// Tokens of the pending reward locked by the challenge
+    pub amount: u64,
+    /// Lamports forfeited to the farmer if the challenge is rejected
+    pub bond: u64,
+    pub challenged_at: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl RewardChallenge {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 8 + 1 + 8;
+}
+
+impl Sequenced for RewardChallenge {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Attestor's statement that a task was completed, read from an account of
+// the attestor program at the pool's `data_offset`. Not owned by this
+// program; the attestor decides when to write or close it.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct TaskAttestation {
+    /// SHA-256 of the task id
+    pub task_hash: [u8; 32],
+    pub farmer: Pubkey,
+    /// Largest reward, before platform fees, the task may be booked for
+    pub amount_ceiling: u64,
+}
+
+// Share of a split withdrawal paid to one destination token account
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WithdrawalSplit {
+    pub destination: Pubkey,
+    /// Share of the amount in basis points; the shares of a split add up
+    /// to `BASIS_POINTS`
+    pub share_bps: u16,
+}
+
+impl WithdrawalSplit {
+    /// Amount each destination of `splits` receives out of `amount`. The
+    /// rounding remainder goes to the first destination.
+    pub fn amounts(splits: &[WithdrawalSplit], amount: u64) -> Vec<u64> {
+        let mut amounts: Vec<u64> = splits
+            .iter()
+            .map(|split| (amount as u128 * split.share_bps as u128 / BASIS_POINTS as u128) as u64)
+            .collect();
+        let remainder = amount - amounts.iter().sum::<u64>();
+        if let Some(first) = amounts.first_mut() {
+            *first += remainder;
+        }
+        amounts
+    }
+}
+
+// Where `DonateReward` sends a forfeited pending reward
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DonationTarget {
+    /// Leaves the tokens in the treasury vault for future rewards
+    Pool,
+    /// Transfers the tokens to the pool's charity account
+    Charity,
+    /// Burns the tokens
+    Burn,
+}
+
+// Keys allowed to pause the pool besides the platform authority. They can
+// do nothing else; an empty set revokes them all.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct PoolGuardians {
+    pub pool: Pubkey,
+    pub guardian_count: u8,
+    pub guardians: [Pubkey; PoolGuardians::MAX_GUARDIANS],
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl PoolGuardians {
+    /// Guardians a pool can register
+    pub const MAX_GUARDIANS: usize = 3;
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 1 + 32 * Self::MAX_GUARDIANS + 1 + 8;
+
+    /// Registered guardians
+    pub fn guardians(&self) -> &[Pubkey] {
+        &self.guardians[..(self.guardian_count as usize).min(Self::MAX_GUARDIANS)]
+    }
+
+    /// Whether `key` is a registered guardian
+    pub fn is_guardian(&self, key: &Pubkey) -> bool {
+        self.guardians().contains(key)
+    }
+}
+
+impl Sequenced for PoolGuardians {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Sensitive admin operation that only runs through the action queue, or
+// that can be queued instead of run directly
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq, Eq)]
+pub enum ScheduledAction {
+    UpdatePlatformFee {
+        new_fee_percentage: u8,
+    },
+    UpdateParameters {
+        values: ParameterValues,
+    },
+    SetPlatformTreasury {
+        platform_treasury: Pubkey,
+    },
+    SetPlatformAuthority {
+        platform_authority: Pubkey,
+    },
+    /// Swaps `amount` of the platform fee vault into `output_mint`, a stable
+    /// mint of the pool's `TreasurySwapPolicy`, receiving at least
+    /// `min_output`
+    SwapTreasury {
+        output_mint: Pubkey,
+        amount: u64,
+        min_output: u64,
+        /// Instruction data of the swap, passed through to the DEX
+        swap_data: Vec<u8>,
+    },
+    /// `SetPlatformAuthority` with the hash of a document giving the reason,
+    /// kept in the pool's key history
+    RotatePlatformAuthority {
+        platform_authority: Pubkey,
+        reason_hash: [u8; 32],
+    },
+}
+
+impl ScheduledAction {
+    /// Whether queueing the action needs the treasury approvers, as running
+    /// it directly does
+    pub fn requires_approvals(&self) -> bool {
+        matches!(self, Self::UpdateParameters { .. })
+    }
+}
+
+// Admin action queued until `eta`. Closed, refunding `payer`, once it is
+// executed or cancelled.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct PendingAction {
+    pub pool: Pubkey,
+    pub action_id: u64,
+    /// Account that paid the rent, refunded when the action is closed
+    pub payer: Pubkey,
+    pub action: ScheduledAction,
+    pub scheduled_at: i64,
+    pub eta: i64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl Sequenced for PendingAction {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Per-farmer activity within a pool. Created by whoever first records a
+// reward for the farmer or pays a withdrawal of theirs, which `payer`
+// remembers so the rent can be returned to it. With `auto_forward` set,
+// `RecordReward` pays the farmer's rewards straight to
+// `forward_destination` instead of their reward account. With a non-zero
+// `auto_claim_threshold`, anyone can claim the farmer's pending rewards to
+// their payout address once they add up to the threshold. With a
+// `withdrawal_cosigner`, `WithdrawReward` needs its signature as well;
+// removing it without that signature only takes effect at
+// `cosigner_removal_at`. With a non-zero `guild_share_bps`, claims of the
+// farmer's pending rewards pay that share of them to `guild_vault`.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct FarmerState {
+    pub pool: Pubkey,
+    pub farmer: Pubkey,
+    pub payer: Pubkey,
+    /// Last time a reward was recorded for the farmer, 0 if never
+    pub last_recorded_at: i64,
+    /// Last time the farmer withdrew or claimed, 0 if never
+    pub last_claimed_at: i64,
+    pub auto_forward: bool,
+    /// Token account of the reward mint, `Pubkey::default()` unless
+    /// `auto_forward` is set
+    pub forward_destination: Pubkey,
+    /// Pending total that allows `CrankAutoClaim`, 0 when auto-claim is off
+    pub auto_claim_threshold: u64,
+    /// Second key every withdrawal needs, `Pubkey::default()` for none
+    pub withdrawal_cosigner: Pubkey,
+    /// Time from which the cosigner no longer applies, 0 when no removal is
+    /// pending
+    pub cosigner_removal_at: i64,
+    /// Token account of the reward mint the guild's share of claims goes
+    /// to, `Pubkey::default()` without a guild split
+    pub guild_vault: Pubkey,
+    /// Share of claimed rewards the guild gets, 0 without a guild split
+    pub guild_share_bps: u16,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl FarmerState {
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 1 + 32 + 8 + 32 + 8 + 32 + 2 + 1 + 8;
+
+    pub fn new(pool: Pubkey, farmer: Pubkey, payer: Pubkey, bump_seed: u8) -> Self {
+        Self {
+            pool,
+            farmer,
+            payer,
+            last_recorded_at: 0,
+            last_claimed_at: 0,
+            auto_forward: false,
+            forward_destination: Pubkey::default(),
+            auto_claim_threshold: 0,
+            withdrawal_cosigner: Pubkey::default(),
+            cosigner_removal_at: 0,
+            guild_vault: Pubkey::default(),
+            guild_share_bps: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    }
+
+    /// Guild's share of a claim of `amount`
+    pub fn guild_share(&self, amount: u64) -> u64 {
+        (amount as u128 * self.guild_share_bps as u128 / BASIS_POINTS as u128) as u64
+    }
+
+    /// Cosigner whose signature withdrawals need at `now`, if any
+    pub fn cosigner(&self, now: i64) -> Option<Pubkey> {
+        let removed = self.cosigner_removal_at != 0 && self.cosigner_removal_at <= now;
+        (self.withdrawal_cosigner != Pubkey::default() && !removed)
+            .then_some(self.withdrawal_cosigner)
+    }
+}
+
+impl Sequenced for FarmerState {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
+
+// Structure for pending rewards
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct PendingReward {
+    pub farmer_pubkey: Pubkey,
+    pub amount: u64,
+    pub task_id: String,
+    pub recorded_at: i64,
+    pub is_withdrawn: bool,
+    pub campaign_id: Option<u64>,
+    pub platform_id: Option<u64>,
+    pub proof_hash: Option<[u8; 32]>,
+    /// Locked by an open challenge until it is resolved
+    pub is_challenged: bool,
+    /// Booked in maker-checker mode and not claimable until approved
+    pub awaiting_approval: bool,
+    pub origin: RewardOrigin,
+    /// Interest accrued when the reward was paid out that the bonus vault
+    /// could not cover, paid by the farmer's next claim passing the reward
+    pub interest_owed: u64,
+}
+
+// What booked a pending reward
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewardOrigin {
+    /// A reward recorded for a task
+    Task,
+    /// A milestone bonus, `task_id` being `MilestoneBonuses::task_id`
+    Milestone,
+    /// A balance imported from a previous deployment, `task_id` being
+    /// `ImportedBalance::TASK_ID`
+    Import,
+}
+
+// A withdrawal of a farmer, kept in their `WithdrawalHistory` and in its own
+// account at the PDA of the farmer and nonce, whose existence makes
+// `WithdrawReward` reject the nonce with `InvalidNonce`. Once the farmer
+// closes it with `CloseWithdrawalRecords`, the history's `nonce_floor`
+// rejects the nonce instead.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WithdrawalRecord {
+    pub farmer_pubkey: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+    pub withdrawn_at: i64,
+    /// Paid the record's rent, which closing it refunds: the withdrawal
+    /// sponsor, or else the farmer
+    pub payer: Pubkey,
+}
+
+impl WithdrawalRecord {
+    /// Serialized size of a record
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 32;
+}
+
+// Last `CAPACITY` withdrawals of a farmer in a pool, kept as a ring buffer
+// so farmers can prove recent payouts without an indexer. Created by the
+// farmer's first withdrawal, at the expense of whoever pays its rent.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct WithdrawalHistory {
+    pub pool: Pubkey,
+    pub farmer: Pubkey,
+    /// Withdrawals recorded since the account was created; the next one
+    /// goes to `records[total_count % CAPACITY]`
+    pub total_count: u64,
+    pub records: [WithdrawalRecord; WithdrawalHistory::CAPACITY],
+    /// Nonces below it can no longer be withdrawn under: one past the
+    /// highest nonce whose record was closed, 0 until one is
+    pub nonce_floor: u64,
+    pub bump_seed: u8,
+    pub sequence: u64,
+}
+
+impl WithdrawalHistory {
+    /// Withdrawals kept before the oldest is overwritten
+    pub const CAPACITY: usize = 16;
+    /// Serialized size of the account
+    pub const LEN: usize = 32 + 32 + 8 + WithdrawalRecord::LEN * Self::CAPACITY + 8 + 1 + 8;
+
+    pub fn new(pool: Pubkey, farmer: Pubkey, bump_seed: u8) -> Self {
+        Self {
+            pool,
+            farmer,
+            total_count: 0,
+            records: [WithdrawalRecord {
+                farmer_pubkey: Pubkey::default(),
+                amount: 0,
+                nonce: 0,
+                withdrawn_at: 0,
+                payer: Pubkey::default(),
+            }; Self::CAPACITY],
+            nonce_floor: 0,
+            bump_seed,
+            sequence: 0,
+        }
+    }
+
+    /// Records a withdrawal, overwriting the oldest once full
+    pub fn push(&mut self, record: WithdrawalRecord) {
+        self.records[(self.total_count % Self::CAPACITY as u64) as usize] = record;
+        self.total_count += 1;
+    }
+
+    /// Recorded withdrawals still held, newest first
+    pub fn recent(&self) -> Vec<WithdrawalRecord> {
+        let held = self.total_count.min(Self::CAPACITY as u64);
+        (1..=held)
+            .map(|age| {
+                let index = (self.total_count - age) % Self::CAPACITY as u64;
+                self.records[index as usize]
+            })
+            .collect()
+    }
+}
+
+impl Sequenced for WithdrawalHistory {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut u64 {
+        &mut self.sequence
+    }
+}
@@ -0,0 +1,74 @@
+// Program-test support for integrators
+//
+// Enabled by the `test-support` feature, for programs that CPI into the
+// pool and want to test against it: registration of the program in a
+// `solana-program-test` bank, decoding of its accounts and assertions on
+// its errors. Fixture builders that bootstrap whole pools live in
+// `reward-pool-test-utils`, which re-exports all of this.
+use borsh::BorshDeserialize;
+use solana_program::{instruction::InstructionError, pubkey::Pubkey};
+use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
+use solana_transaction_error::TransactionError;
+
+use crate::{error::RewardPoolError, id, process_instruction};
+
+/// Name the program is registered under, matching `reward_pool.so`
+pub const PROGRAM_NAME: &str = "reward_pool";
+
+/// Registers the program, run natively, at its declared id
+pub fn add_program(program_test: &mut ProgramTest) {
+    program_test.add_program(PROGRAM_NAME, id(), processor!(process_instruction));
+}
+
+/// A `ProgramTest` running the program natively at its declared id; add
+/// the programs under test to it before starting it
+pub fn program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::default();
+    add_program(&mut program_test);
+    program_test
+}
+
+/// Error the failed instruction of a transaction returned, if it failed in
+/// an instruction
+pub fn instruction_error(error: BanksClientError) -> Option<InstructionError> {
+    match error.unwrap() {
+        TransactionError::InstructionError(_, error) => Some(error),
+        _ => None,
+    }
+}
+
+/// Asserts that `result` failed with `expected` in one of its instructions
+#[track_caller]
+pub fn assert_instruction_error<T: std::fmt::Debug>(
+    result: Result<T, BanksClientError>,
+    expected: InstructionError,
+) {
+    match result {
+        Ok(value) => panic!("expected {expected:?}, but the transaction succeeded: {value:?}"),
+        Err(error) => assert_eq!(instruction_error(error), Some(expected)),
+    }
+}
+
+/// Asserts that `result` failed with the pool's `expected` error, e.g. after
+/// a CPI into the pool
+#[track_caller]
+pub fn assert_pool_error<T: std::fmt::Debug>(
+    result: Result<T, BanksClientError>,
+    expected: RewardPoolError,
+) {
+    assert_instruction_error(result, InstructionError::Custom(expected as u32));
+}
+
+/// Decodes the pool account at `address`, e.g. a `RewardPool` or a
+/// `PendingReward`, panicking if it is missing, owned by another program
+/// or not a `T`
+pub async fn get_state<T: BorshDeserialize>(banks_client: &mut BanksClient, address: &Pubkey) -> T {
+    let account = banks_client
+        .get_account(*address)
+        .await
+        .expect("account fetched")
+        .unwrap_or_else(|| panic!("no account at {address}"));
+    assert_eq!(account.owner, id(), "{address} is not owned by the pool");
+    T::deserialize(&mut account.data.as_slice())
+        .unwrap_or_else(|error| panic!("{address} does not decode: {error}"))
+}
@@ -0,0 +1,81 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+use crate::state::{FeeInvoice, InstructionCounters, WithdrawalRecord};
+
+// View results
+//
+// View instructions do not change state; they return one of these,
+// Borsh-encoded, through the transaction return data. Clients read them by
+// simulating the instruction.
+
+/// Largest page `GetFarmerPending` walks in one call
+pub const MAX_PENDING_PAGE_SIZE: u8 = 32;
+
+// Unclaimed pending rewards of a farmer, one page at a time
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq, Eq)]
+pub struct FarmerPendingSummary {
+    pub farmer: Pubkey,
+    /// Unclaimed rewards found in this page
+    pub pending_count: u32,
+    /// Total unclaimed amount found in this page
+    pub pending_amount: u64,
+    /// Cursor of the next page, `None` once every account has been walked
+    pub next_cursor: Option<u32>,
+    /// Last time a reward was recorded for the farmer, 0 if never
+    pub last_recorded_at: i64,
+    /// Last time the farmer withdrew or claimed, 0 if never
+    pub last_claimed_at: i64,
+}
+
+// Pool totals and lifetime instruction counts
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq, Eq)]
+pub struct PoolStats {
+    pub pool: Pubkey,
+    pub platform_fee_percentage: u8,
+    pub total_rewards_distributed: u64,
+    pub total_platform_fees_collected: u64,
+    /// Burned from withdrawals, 0 when the withdraw burn account was not
+    /// passed
+    pub total_withdraw_burned: u64,
+    pub is_paused: bool,
+    /// Successful executions of each instruction, indexed by V2 variant
+    pub instruction_counts: [u64; InstructionCounters::MAX_INSTRUCTIONS],
+}
+
+// Recent withdrawals of a farmer
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawalHistorySummary {
+    pub farmer: Pubkey,
+    /// Withdrawals recorded in total, including those no longer held
+    pub total_count: u64,
+    /// At most `WithdrawalHistory::CAPACITY` withdrawals, newest first
+    pub records: Vec<WithdrawalRecord>,
+}
+
+// Fee invoice of a pool and whether it checks out
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq, Eq)]
+pub struct FeeInvoiceReport {
+    pub invoice: FeeInvoice,
+    /// Whether the seal matches the invoice's contents
+    pub sealed: bool,
+    /// Whether the invoice chains to the seal of the previous one, or to
+    /// nothing for the first invoice
+    pub chained: bool,
+}
+
+// Time-weighted average reward rate of a pool over a window ending now
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, Clone, PartialEq, Eq)]
+pub struct RewardRateReport {
+    pub pool: Pubkey,
+    /// Start of the window measured: the requested start rounded back to
+    /// the observation before it, or the oldest observation held
+    pub window_start: i64,
+    pub window_end: i64,
+    /// Farmer amounts recorded in the window
+    pub amount: u64,
+    /// Rewards recorded in the window
+    pub reward_count: u64,
+    /// `amount` per hour of the window
+    pub rate_per_hour: u64,
+}
@@ -0,0 +1,404 @@
+//! Golden-file snapshots of every account, instruction, event and view
+//! layout.
+//!
+//! A canonical instance of each type is built from its Borsh schema, every
+//! value derived from the path of the field holding it, so adding,
+//! removing, retyping, reordering or renaming a field changes the bytes.
+//! The instance is decoded into the real type and serialized again, and
+//! the result must match `tests/golden/<name>.golden` byte for byte. Enums
+//! at the top level (instructions, events) get one instance per variant.
+//!
+//! After an intended layout change, `UPDATE_GOLDEN=1` rewrites the golden
+//! files, to be reviewed and committed with the change.
+
+use std::{collections::BTreeMap, env, fmt::Write, fs, path::PathBuf};
+
+use borsh::{
+    schema::{BorshSchemaContainer, Definition, Fields},
+    BorshDeserialize, BorshSchema, BorshSerialize,
+};
+use reward_pool::{
+    events::RewardPoolEvent,
+    instruction::{v1, v2},
+    views::{FarmerPendingSummary, FeeInvoiceReport, PoolStats, RewardRateReport},
+    AuthorityRecovery, BuybackConfig, Campaign, ClaimLedger, ClaimShard, DailyStats, FactoryPool,
+    FarmerRecovery, FarmerState, FarmerStats, FeeDiscounts, FeeInvoice, FeeLedger, FeeOverrides,
+    FeeRebate, Guild, ImportedBalance, InstructionCounters, InterestPolicy, KeyHistory,
+    KeyRotation, MilestoneBonuses, ParameterHistory, PayoutAddress, PayoutQueue, PayoutTicket,
+    PendingAction, PendingReward, PendingTransferPolicy, Platform, PoolArchive, PoolAttestor,
+    PoolCharity, PoolGuardians, PoolMetadata, PoolParameters, PoolSnapshot, PoolVerifier,
+    ProtocolConfig, ProtocolLiquidity, RewardApproval, RewardChallenge, RewardGovernor, RewardPool,
+    RewardRate, RewardReceipt, SecondaryBalance, SecondaryReward, StateExport, TaskAttestation,
+    TaskBudget, TreasuryGovernance, TreasuryProposal, TreasurySwapPolicy, Voucher, WithdrawBurn,
+    WithdrawalFee, WithdrawalHistory, WithdrawalRamp, WithdrawalRecord,
+    WithdrawalWhitelist,
+};
+use solana_program::hash::hash;
+
+/// Elements given to variable-length sequences
+const SEQUENCE_LENGTH: u64 = 2;
+
+/// Bytes per line of a drift report's hex excerpts
+const EXCERPT: usize = 16;
+
+/// A wire type: its golden file name, schema, and a Borsh round trip
+/// through the real type
+struct Layout {
+    name: &'static str,
+    schema: BorshSchemaContainer,
+    round_trip: fn(&[u8]) -> Result<Vec<u8>, String>,
+}
+
+fn layout<T: BorshSchema + BorshSerialize + BorshDeserialize>(name: &'static str) -> Layout {
+    Layout {
+        name,
+        schema: BorshSchemaContainer::for_type::<T>(),
+        round_trip: |bytes| {
+            let value = T::try_from_slice(bytes).map_err(|error| error.to_string())?;
+            borsh::to_vec(&value).map_err(|error| error.to_string())
+        },
+    }
+}
+
+fn layouts() -> Vec<Layout> {
+    vec![
+        layout::<v1::RewardPoolInstruction>("instruction_v1"),
+        layout::<v2::RewardPoolInstruction>("instruction_v2"),
+        layout::<RewardPoolEvent>("event"),
+        layout::<RewardPool>("reward_pool"),
+        layout::<DailyStats>("daily_stats"),
+        layout::<FarmerState>("farmer_state"),
+        layout::<InstructionCounters>("instruction_counters"),
+        layout::<PendingReward>("pending_reward"),
+        layout::<WithdrawalRecord>("withdrawal_record"),
+        layout::<WithdrawalHistory>("withdrawal_history"),
+        layout::<WithdrawalWhitelist>("withdrawal_whitelist"),
+        layout::<TreasuryGovernance>("treasury_governance"),
+        layout::<TreasuryProposal>("treasury_proposal"),
+        layout::<PoolParameters>("pool_parameters"),
+        layout::<ParameterHistory>("parameter_history"),
+        layout::<PoolGuardians>("pool_guardians"),
+        layout::<PendingAction>("pending_action"),
+        layout::<PoolMetadata>("pool_metadata"),
+        layout::<StateExport>("state_export"),
+        layout::<ImportedBalance>("imported_balance"),
+        layout::<PoolArchive>("pool_archive"),
+        layout::<PayoutAddress>("payout_address"),
+        layout::<PoolCharity>("pool_charity"),
+        layout::<PoolVerifier>("pool_verifier"),
+        layout::<PoolAttestor>("pool_attestor"),
+        layout::<TaskAttestation>("task_attestation"),
+        layout::<TaskBudget>("task_budget"),
+        layout::<RewardChallenge>("reward_challenge"),
+        layout::<RewardApproval>("reward_approval"),
+        layout::<FeeOverrides>("fee_overrides"),
+        layout::<Platform>("platform"),
+        layout::<Campaign>("campaign"),
+        layout::<BuybackConfig>("buyback_config"),
+        layout::<TreasurySwapPolicy>("treasury_swap_policy"),
+        layout::<FeeDiscounts>("fee_discounts"),
+        layout::<InterestPolicy>("interest_policy"),
+        layout::<WithdrawBurn>("withdraw_burn"),
+        layout::<RewardGovernor>("reward_governor"),
+        layout::<SecondaryReward>("secondary_reward"),
+        layout::<SecondaryBalance>("secondary_balance"),
+        layout::<MilestoneBonuses>("milestone_bonuses"),
+        layout::<FarmerStats>("farmer_stats"),
+        layout::<ProtocolLiquidity>("protocol_liquidity"),
+        layout::<FeeRebate>("fee_rebate"),
+        layout::<ProtocolConfig>("protocol_config"),
+        layout::<FactoryPool>("factory_pool"),
+        layout::<Voucher>("voucher"),
+        layout::<PayoutQueue>("payout_queue"),
+        layout::<PayoutTicket>("payout_ticket"),
+        layout::<WithdrawalRamp>("withdrawal_ramp"),
+        layout::<AuthorityRecovery>("authority_recovery"),
+        layout::<KeyHistory>("key_history"),
+        layout::<FarmerRecovery>("farmer_recovery"),
+        layout::<WithdrawalFee>("withdrawal_fee"),
+        layout::<PoolSnapshot>("pool_snapshot"),
+        layout::<Guild>("guild"),
+        layout::<FeeLedger>("fee_ledger"),
+        layout::<FeeInvoice>("fee_invoice"),
+        layout::<RewardRate>("reward_rate"),
+        layout::<ClaimShard>("claim_shard"),
+        layout::<ClaimLedger>("claim_ledger"),
+        layout::<RewardReceipt>("reward_receipt"),
+        layout::<KeyRotation>("key_rotation"),
+        layout::<PendingTransferPolicy>("pending_transfer_policy"),
+        layout::<FarmerPendingSummary>("farmer_pending_summary"),
+        layout::<PoolStats>("pool_stats"),
+        layout::<FeeInvoiceReport>("fee_invoice_report"),
+        layout::<RewardRateReport>("reward_rate_report"),
+    ]
+}
+
+/// Bytes of a canonical instance, with the field path each value starts at
+#[derive(Default)]
+struct Instance {
+    bytes: Vec<u8>,
+    fields: Vec<(usize, String)>,
+}
+
+impl Instance {
+    /// Field path of the value holding byte `offset`
+    fn field_at(&self, offset: usize) -> &str {
+        self.fields
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= offset)
+            .map_or("<end>", |(_, path)| path)
+    }
+}
+
+struct Builder<'a> {
+    schema: &'a BorshSchemaContainer,
+    instance: Instance,
+}
+
+impl Builder<'_> {
+    fn write(&mut self, declaration: &str, path: &str) {
+        let seed = hash(path.as_bytes()).to_bytes();
+        // Strings are the one sequence whose elements must be valid text
+        if declaration == "String" {
+            self.mark(path);
+            self.instance
+                .bytes
+                .extend_from_slice(&(path.len() as u32).to_le_bytes());
+            self.instance.bytes.extend_from_slice(path.as_bytes());
+            return;
+        }
+        let definition = self
+            .schema
+            .get_definition(declaration)
+            .unwrap_or_else(|| panic!("{path}: no definition of {declaration}"));
+        match definition {
+            Definition::Primitive(size) => {
+                self.mark(path);
+                match declaration {
+                    "bool" => self.instance.bytes.push(seed[0] & 1),
+                    _ => self
+                        .instance
+                        .bytes
+                        .extend_from_slice(&seed[..*size as usize]),
+                }
+            }
+            Definition::Sequence {
+                length_width,
+                length_range,
+                elements,
+            } => {
+                let length = if *length_width == 0 {
+                    *length_range.end()
+                } else {
+                    SEQUENCE_LENGTH.clamp(*length_range.start(), *length_range.end())
+                };
+                self.mark(path);
+                self.instance
+                    .bytes
+                    .extend_from_slice(&length.to_le_bytes()[..*length_width as usize]);
+                for index in 0..length {
+                    self.write(elements, &format!("{path}[{index}]"));
+                }
+            }
+            Definition::Tuple { elements } => {
+                for (index, element) in elements.iter().enumerate() {
+                    self.write(element, &format!("{path}.{index}"));
+                }
+            }
+            Definition::Enum {
+                tag_width,
+                variants,
+                ..
+            } => {
+                // `Some` covers more bytes than `None`; other enums take a
+                // variant picked by the path
+                let index = variants
+                    .iter()
+                    .position(|(_, name, _)| name == "Some")
+                    .unwrap_or(seed[0] as usize % variants.len());
+                self.write_variant(*tag_width, &variants[index], path);
+            }
+            Definition::Struct { fields } => match fields {
+                Fields::NamedFields(fields) => {
+                    for (name, field) in fields {
+                        self.write(field, &format!("{path}.{name}"));
+                    }
+                }
+                Fields::UnnamedFields(fields) => {
+                    for (index, field) in fields.iter().enumerate() {
+                        self.write(field, &format!("{path}.{index}"));
+                    }
+                }
+                Fields::Empty => {}
+            },
+        }
+    }
+
+    fn write_variant(&mut self, tag_width: u8, variant: &(i64, String, String), path: &str) {
+        let (discriminant, name, declaration) = variant;
+        let path = format!("{path}::{name}");
+        self.mark(&path);
+        self.instance
+            .bytes
+            .extend_from_slice(&discriminant.to_le_bytes()[..tag_width as usize]);
+        self.write(declaration, &path);
+    }
+
+    fn mark(&mut self, path: &str) {
+        let offset = self.instance.bytes.len();
+        self.instance.fields.push((offset, path.to_string()));
+    }
+}
+
+/// Canonical instances of the schema's type, by label: one per variant of
+/// an enum, one otherwise
+fn instances(schema: &BorshSchemaContainer) -> BTreeMap<String, Instance> {
+    let declaration = schema.declaration();
+    let build = |write: &dyn Fn(&mut Builder)| {
+        let mut builder = Builder {
+            schema,
+            instance: Instance::default(),
+        };
+        write(&mut builder);
+        builder.instance
+    };
+    match schema.get_definition(declaration) {
+        Some(Definition::Enum {
+            tag_width,
+            variants,
+            ..
+        }) => variants
+            .iter()
+            .map(|variant| {
+                let instance =
+                    build(&|builder| builder.write_variant(*tag_width, variant, declaration));
+                (format!("{declaration}::{}", variant.1), instance)
+            })
+            .collect(),
+        _ => BTreeMap::from([(
+            declaration.clone(),
+            build(&|builder| builder.write(declaration, declaration)),
+        )]),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.golden"))
+}
+
+/// Golden file content: a header, then one `<label> <hex>` line per instance
+fn render(name: &str, serialized: &BTreeMap<String, Vec<u8>>) -> String {
+    let mut golden = format!(
+        "# Borsh layout snapshot of {name}. Regenerate with UPDATE_GOLDEN=1 only for an\n\
+         # intended layout change.\n"
+    );
+    for (label, bytes) in serialized {
+        let _ = writeln!(golden, "{label} {}", to_hex(bytes));
+    }
+    golden
+}
+
+fn parse(golden: &str) -> BTreeMap<String, String> {
+    golden
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.is_empty())
+        .filter_map(|line| line.split_once(' '))
+        .map(|(label, hex)| (label.to_string(), hex.to_string()))
+        .collect()
+}
+
+/// Describes how `actual` drifted from the golden hex of an instance
+fn describe_drift(instance: &Instance, actual: &[u8], expected_hex: &str) -> String {
+    let expected: Vec<u8> = (0..expected_hex.len())
+        .step_by(2)
+        .filter_map(|index| u8::from_str_radix(expected_hex.get(index..index + 2)?, 16).ok())
+        .collect();
+    let offset = expected
+        .iter()
+        .zip(actual)
+        .position(|(expected, actual)| expected != actual)
+        .unwrap_or(expected.len().min(actual.len()));
+    let excerpt =
+        |bytes: &[u8]| to_hex(&bytes[offset.min(bytes.len())..(offset + EXCERPT).min(bytes.len())]);
+    format!(
+        "{} bytes, golden {} bytes; first difference at offset {offset} in {}\n      \
+         golden: {}\n      actual: {}",
+        actual.len(),
+        expected.len(),
+        instance.field_at(offset),
+        excerpt(&expected),
+        excerpt(actual),
+    )
+}
+
+#[test]
+fn layouts_match_golden_files() {
+    let update = env::var_os("UPDATE_GOLDEN").is_some();
+    let mut drift = Vec::new();
+
+    for layout in layouts() {
+        let instances = instances(&layout.schema);
+        let serialized: BTreeMap<String, Vec<u8>> = instances
+            .iter()
+            .map(|(label, instance)| {
+                let bytes = (layout.round_trip)(&instance.bytes)
+                    .unwrap_or_else(|error| panic!("{label} does not decode: {error}"));
+                // A mismatch here is a schema that disagrees with the
+                // serialization, not a layout change
+                assert_eq!(
+                    bytes, instance.bytes,
+                    "{label} round trips to other bytes than its schema describes"
+                );
+                (label.clone(), bytes)
+            })
+            .collect();
+
+        let path = golden_path(layout.name);
+        let rendered = render(layout.name, &serialized);
+        if update {
+            fs::create_dir_all(path.parent().expect("golden directory")).expect("create golden");
+            fs::write(&path, rendered).expect("write golden file");
+            continue;
+        }
+
+        let Ok(golden) = fs::read_to_string(&path) else {
+            drift.push(format!("{}: missing golden file", path.display()));
+            continue;
+        };
+        let expected = parse(&golden);
+        for (label, bytes) in &serialized {
+            match expected.get(label) {
+                None => drift.push(format!("{label}: not in {}.golden", layout.name)),
+                Some(hex) if *hex != to_hex(bytes) => drift.push(format!(
+                    "{label}: {}",
+                    describe_drift(&instances[label], bytes, hex)
+                )),
+                Some(_) => {}
+            }
+        }
+        for label in expected.keys() {
+            if !serialized.contains_key(label) {
+                drift.push(format!("{label}: in {}.golden but gone", layout.name));
+            }
+        }
+    }
+
+    assert!(
+        drift.is_empty(),
+        "\n\nLAYOUT DRIFT: {} instance(s) no longer match their golden files.\n\
+         Deployed accounts and clients depend on these layouts. If the change is\n\
+         intended, rerun with UPDATE_GOLDEN=1 and commit the golden files.\n\n  {}\n",
+        drift.len(),
+        drift.join("\n  ")
+    );
+}
@@ -59,9 +59,7 @@ program
 
             // Create test keypairs
             const platformAuthority = Keypair.generate();
-            const poolAccount = Keypair.generate();
             const rewardMint = new PublicKey('EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v'); // USDC
-            const platformTreasury = new PublicKey('11111111111111111111111111111111'); // To be replaced
 
             console.log('🔧 Initializing reward pool...');
 
@@ -69,22 +67,22 @@ program
             const txSignature = await client.initializePool(
                 deployerKeypair,
                 platformAuthority,
-                poolAccount,
                 rewardMint,
-                platformTreasury,
                 10, // 10% platform fee
             );
 
+            const poolAccount = client.findPoolAddress(rewardMint);
+            const platformTreasury = client.findVaultAddress(poolAccount);
             console.log('✅ Pool initialized successfully!');
             console.log(`📝 Transaction: ${txSignature}`);
-            console.log(`🏊 Pool Account: ${poolAccount.publicKey.toString()}`);
+            console.log(`🏊 Pool Account: ${poolAccount.toString()}`);
             console.log(`👑 Platform Authority: ${platformAuthority.publicKey.toString()}`);
 
             // Save deployment information
             const deploymentInfo = {
                 network,
                 programId: programId.toString(),
-                poolAccount: poolAccount.publicKey.toString(),
+                poolAccount: poolAccount.toString(),
                 platformAuthority: platformAuthority.publicKey.toString(),
                 platformTreasury: platformTreasury.toString(),
                 rewardMint: rewardMint.toString(),
@@ -138,7 +136,6 @@ program
             const programId = new PublicKey(deploymentInfo.programId);
             const poolAccount = new PublicKey(deploymentInfo.poolAccount);
             const rewardMint = new PublicKey(deploymentInfo.rewardMint);
-            const platformTreasury = new PublicKey(deploymentInfo.platformTreasury);
 
             // Create client
             const client = new RewardPoolClient(connection, programId);
@@ -169,14 +166,22 @@ program
             const testAmount = new BN(1000000); // 1 USDC (6 decimals)
             const testTaskId = 'test-task-001';
 
+            // Rewards are recorded in v0 transactions against the pool's lookup table
+            const lookupTable = await client.createRecordRewardLookupTable(
+                deployerKeypair,
+                poolAccount,
+                rewardMint,
+            );
+            console.log(`📇 Lookup table: ${lookupTable.toString()}`);
+
             const recordTx = await client.recordReward(
                 deployerKeypair,
                 poolAccount,
-                platformTreasury,
                 rewardMint,
                 testAmount,
                 testFarmer.publicKey,
                 testTaskId,
+                lookupTable,
             );
 
             console.log(`✅ Reward recorded: ${recordTx}`);
@@ -1,4 +1,11 @@
-import { Connection, Keypair, PublicKey } from '@solana/web3.js';
+import {
+    AddressLookupTableAccount,
+    Connection,
+    Keypair,
+    PACKET_DATA_SIZE,
+    PublicKey,
+    Transaction,
+} from '@solana/web3.js';
 import { RewardPoolClient, RewardPoolClientError } from '../../client/src/reward-pool-client';
 import BN from 'bn.js';
 
@@ -34,14 +41,12 @@ describe('RewardPoolClient', () => {
         it('should create a valid initialization instruction', () => {
             const instruction = client.createInitializePoolInstruction(
                 platformAuthority.publicKey,
-                poolAccount.publicKey,
                 rewardMint,
-                platformTreasury,
                 10
             );
 
             expect(instruction.programId).toEqual(programId);
-            expect(instruction.keys).toHaveLength(8);
+            expect(instruction.keys).toHaveLength(9);
             expect(instruction.data).toHaveLength(2);
             expect(instruction.data[0]).toBe(0); // InitializePool instruction
             expect(instruction.data[1]).toBe(10); // platform_fee_percentage
@@ -50,9 +55,7 @@ describe('RewardPoolClient', () => {
         it('should include the correct keys in the instruction', () => {
             const instruction = client.createInitializePoolInstruction(
                 platformAuthority.publicKey,
-                poolAccount.publicKey,
                 rewardMint,
-                platformTreasury,
                 10
             );
 
@@ -60,9 +63,9 @@ describe('RewardPoolClient', () => {
 
             expect(keys[0]?.pubkey).toEqual(platformAuthority.publicKey);
             expect(keys[0]?.isSigner).toBe(true);
-            expect(keys[0]?.isWritable).toBe(false);
+            expect(keys[0]?.isWritable).toBe(true);
 
-            expect(keys[1]?.pubkey).toEqual(poolAccount.publicKey);
+            expect(keys[1]?.pubkey).toEqual(client.findPoolAddress(rewardMint));
             expect(keys[1]?.isSigner).toBe(false);
             expect(keys[1]?.isWritable).toBe(true);
         });
@@ -77,7 +80,6 @@ describe('RewardPoolClient', () => {
             const instruction = client.createRecordRewardInstruction(
                 platformAuthority.publicKey,
                 poolAccount.publicKey,
-                platformTreasury,
                 new PublicKey('44444444444444444444444444444444'), // farmer reward account
                 rewardMint,
                 amount,
@@ -86,7 +88,62 @@ describe('RewardPoolClient', () => {
             );
 
             expect(instruction.programId).toEqual(programId);
-            expect(instruction.keys).toHaveLength(7);
+            expect(instruction.keys).toHaveLength(40);
+            expect(instruction.keys[3]?.isWritable).toBe(false);
+            expect(instruction.keys[7]?.pubkey).toEqual(
+                client.findDailyStatsAddress(poolAccount.publicKey),
+            );
+            expect(instruction.keys[10]?.pubkey).toEqual(
+                client.findFarmerStateAddress(poolAccount.publicKey, farmerPubkey),
+            );
+            expect(instruction.keys[11]?.pubkey).toEqual(
+                client.findPoolVerifierAddress(poolAccount.publicKey),
+            );
+            expect(instruction.keys[12]?.pubkey).toEqual(
+                client.findPoolAttestorAddress(poolAccount.publicKey),
+            );
+            expect(instruction.keys[14]?.pubkey).toEqual(
+                client.findTaskBudgetAddress(poolAccount.publicKey, taskId),
+            );
+            expect(instruction.keys[14]?.isWritable).toBe(true);
+            expect(instruction.keys[17]?.pubkey).toEqual(
+                client.findPendingRewardAddress(poolAccount.publicKey, farmerPubkey, taskId),
+            );
+            expect(instruction.keys[17]?.isWritable).toBe(true);
+            expect(instruction.keys[19]?.pubkey).toEqual(
+                client.findFeeOverridesAddress(poolAccount.publicKey),
+            );
+            expect(instruction.keys[20]?.pubkey).toEqual(poolAccount.publicKey);
+            expect(instruction.keys[22]?.pubkey).toEqual(
+                client.findFeeDiscountsAddress(poolAccount.publicKey),
+            );
+            expect(instruction.keys[24]?.pubkey).toEqual(
+                client.findRewardGovernorAddress(poolAccount.publicKey),
+            );
+            expect(instruction.keys[24]?.isWritable).toBe(true);
+            expect(instruction.keys[27]?.pubkey).toEqual(
+                client.findSecondaryBalanceAddress(poolAccount.publicKey, farmerPubkey),
+            );
+            expect(instruction.keys[29]?.pubkey).toEqual(
+                client.findFarmerStatsAddress(poolAccount.publicKey, farmerPubkey),
+            );
+            expect(instruction.keys[30]?.pubkey).toEqual(
+                client.findPendingRewardAddress(poolAccount.publicKey, farmerPubkey, 'milestone:0'),
+            );
+            expect(instruction.keys[31]?.pubkey).toEqual(
+                client.findFeeRebateAddress(poolAccount.publicKey),
+            );
+            expect(instruction.keys[32]?.pubkey).toEqual(
+                client.findRebateVaultAddress(poolAccount.publicKey),
+            );
+            expect(instruction.keys[33]?.pubkey).toEqual(
+                client.findFactoryRecordAddress(poolAccount.publicKey),
+            );
+            expect(instruction.keys[34]?.pubkey).toEqual(client.findProtocolConfigAddress());
+            expect(instruction.keys[35]?.pubkey).toEqual(poolAccount.publicKey);
+            expect(instruction.keys[39]?.pubkey).toEqual(
+                client.findTreasuryGovernanceAddress(poolAccount.publicKey),
+            );
             expect(instruction.data[0]).toBe(1); // RecordReward instruction
         });
 
@@ -98,7 +155,6 @@ describe('RewardPoolClient', () => {
             const instruction = client.createRecordRewardInstruction(
                 platformAuthority.publicKey,
                 poolAccount.publicKey,
-                platformTreasury,
                 new PublicKey('44444444444444444444444444444444'),
                 rewardMint,
                 amount,
@@ -113,16 +169,67 @@ describe('RewardPoolClient', () => {
         });
     });
 
+    describe('createRecordRewardTransaction', () => {
+        const recentBlockhash = Keypair.generate().publicKey.toBase58();
+
+        const recordInstruction = () =>
+            client.createRecordRewardInstruction(
+                platformAuthority.publicKey,
+                poolAccount.publicKey,
+                new PublicKey('44444444444444444444444444444444'),
+                rewardMint,
+                new BN(1000000),
+                new PublicKey('33333333333333333333333333333333'),
+                'test-task-001',
+            );
+
+        it('should not fit a legacy transaction', () => {
+            const transaction = new Transaction({
+                feePayer: platformAuthority.publicKey,
+                recentBlockhash,
+            }).add(recordInstruction());
+
+            expect(() =>
+                transaction.serialize({ requireAllSignatures: false, verifySignatures: false }),
+            ).toThrow(/Transaction too large/);
+        });
+
+        it('should fit a v0 transaction against the pool lookup table', () => {
+            const lookupTable = new AddressLookupTableAccount({
+                key: Keypair.generate().publicKey,
+                state: {
+                    deactivationSlot: BigInt('18446744073709551615'),
+                    lastExtendedSlot: 0,
+                    lastExtendedSlotStartIndex: 0,
+                    authority: platformAuthority.publicKey,
+                    addresses: client.recordRewardLookupAddresses(poolAccount.publicKey, rewardMint),
+                },
+            });
+
+            const transaction = client.createRecordRewardTransaction(
+                platformAuthority.publicKey,
+                recordInstruction(),
+                lookupTable,
+                recentBlockhash,
+            );
+            transaction.sign([platformAuthority]);
+
+            expect(transaction.message.addressTableLookups).toHaveLength(1);
+            expect(transaction.serialize().length).toBeLessThanOrEqual(PACKET_DATA_SIZE);
+        });
+    });
+
     describe('createWithdrawRewardInstruction', () => {
         it('should create a valid withdrawal instruction', () => {
             const farmer = new PublicKey('55555555555555555555555555555555');
             const amount = new BN(500000); // 0.5 USDC
             const nonce = new BN(12345);
+            const taskId = 'test-task-001';
 
             const instruction = client.createWithdrawRewardInstruction(
                 farmer,
                 poolAccount.publicKey,
-                new PublicKey('66666666666666666666666666666666'), // farmer reward account
+                taskId,
                 new PublicKey('77777777777777777777777777777777'), // farmer destination account
                 rewardMint,
                 amount,
@@ -130,7 +237,27 @@ describe('RewardPoolClient', () => {
             );
 
             expect(instruction.programId).toEqual(programId);
-            expect(instruction.keys).toHaveLength(7);
+            expect(instruction.keys).toHaveLength(24);
+            expect(instruction.keys[0]?.isWritable).toBe(true);
+            expect(instruction.keys[2]?.pubkey).toEqual(
+                client.findVaultAddress(poolAccount.publicKey),
+            );
+            expect(instruction.keys[4]?.isWritable).toBe(true);
+            expect(instruction.keys[14]?.pubkey).toEqual(
+                client.findWithdrawalHistoryAddress(poolAccount.publicKey, farmer),
+            );
+            expect(instruction.keys[15]?.pubkey).toEqual(
+                client.findWithdrawBurnAddress(poolAccount.publicKey),
+            );
+            expect(instruction.keys[17]?.pubkey).toEqual(
+                client.findFeeVaultAddress(poolAccount.publicKey),
+            );
+            expect(instruction.keys[22]?.pubkey).toEqual(
+                client.findPendingRewardAddress(poolAccount.publicKey, farmer, taskId),
+            );
+            expect(instruction.keys[23]?.pubkey).toEqual(
+                client.findWithdrawalRecordAddress(poolAccount.publicKey, farmer, nonce),
+            );
             expect(instruction.data[0]).toBe(2); // WithdrawReward instruction
         });
     });
@@ -146,7 +273,7 @@ describe('RewardPoolClient', () => {
             );
 
             expect(instruction.programId).toEqual(programId);
-            expect(instruction.keys).toHaveLength(2);
+            expect(instruction.keys).toHaveLength(5);
             expect(instruction.data[0]).toBe(3); // UpdatePlatformFee instruction
             expect(instruction.data[1]).toBe(newFeePercentage);
         });
@@ -160,7 +287,7 @@ describe('RewardPoolClient', () => {
             );
 
             expect(instruction.programId).toEqual(programId);
-            expect(instruction.keys).toHaveLength(2);
+            expect(instruction.keys).toHaveLength(4);
             expect(instruction.data[0]).toBe(4); // PausePool instruction
         });
     });
@@ -173,7 +300,7 @@ describe('RewardPoolClient', () => {
             );
 
             expect(instruction.programId).toEqual(programId);
-            expect(instruction.keys).toHaveLength(2);
+            expect(instruction.keys).toHaveLength(4);
             expect(instruction.data[0]).toBe(5); // ResumePool instruction
         });
     });